@@ -0,0 +1,182 @@
+//! Links between requirements and the architecture decision records (ADRs)
+//! that cite them: which requirements are constrained by a decision, and
+//! which decisions have no requirement driving them.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::dependencies::extract_requirement_nodes;
+
+/// Where ADRs are looked for, relative to the working directory, when no
+/// directory is given.
+pub const ADR_LOCATIONS: &[&str] = &["docs/adr", "docs/decisions", "doc/adr", "adr", "docs/architecture/decisions"];
+
+/// An ADR's own number, e.g. ADR-0007, is not a requirement reference.
+const ADR_ID_PREFIXES: &[&str] = &["ADR-", "MADR-"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionRecord {
+    pub file: String,
+    pub title: String,
+    /// Proposed, Accepted, Superseded, ... when the ADR states it
+    pub status: Option<String>,
+    /// Requirement IDs the ADR cites, in order of first mention
+    pub requirement_ids: Vec<String>,
+}
+
+impl DecisionRecord {
+    /// Title, number and status of a Nygard-style or MADR record: the first
+    /// heading (else the file name), a `Status: X` line or the first line of
+    /// a `## Status` section, and the requirement IDs cited anywhere.
+    pub fn parse(file: &str, text: &str) -> Self {
+        let id_pattern = Regex::new(r"\b([A-Z][A-Z0-9]*-\d+)\b").unwrap();
+        let status_line = Regex::new(r"(?i)^\s*(?:[-*]\s+)?[*_]*status[*_]*\s*:\s*[*_]*([^*_]+)").unwrap();
+
+        let title = text.lines()
+            .find_map(|l| l.trim().strip_prefix('#').map(|t| t.trim_start_matches('#').trim().to_string()))
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| Path::new(file).file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| file.to_string()));
+
+        let mut status = None;
+        let mut in_status_section = false;
+        for line in text.lines().map(|l| l.trim()) {
+            if let Some(caps) = status_line.captures(line) {
+                status = Some(caps[1].trim().to_string());
+                break;
+            }
+            if line.starts_with('#') {
+                in_status_section = line.trim_start_matches('#').trim().eq_ignore_ascii_case("status");
+            } else if in_status_section && !line.is_empty() {
+                status = Some(line.trim_start_matches(['-', '*', ' ']).to_string());
+                break;
+            }
+        }
+
+        let mut requirement_ids: Vec<String> = Vec::new();
+        for id in id_pattern.find_iter(text).map(|m| m.as_str()) {
+            if !ADR_ID_PREFIXES.iter().any(|p| id.starts_with(p)) && !requirement_ids.iter().any(|r| r == id) {
+                requirement_ids.push(id.to_string());
+            }
+        }
+
+        Self { file: file.to_string(), title, status, requirement_ids }
+    }
+
+    /// Superseded, deprecated and rejected decisions no longer constrain anything.
+    pub fn is_active(&self) -> bool {
+        let status = self.status.as_deref().unwrap_or_default().to_lowercase();
+        !["superseded", "deprecated", "rejected"].iter().any(|s| status.starts_with(s))
+    }
+}
+
+/// A requirement and the decisions that cite it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequirementDecisions {
+    pub id: String,
+    pub text: String,
+    pub file: String,
+    /// Files of the citing ADRs
+    pub decisions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AdrLinkReport {
+    /// Requirements with an ID, constrained by at least one ADR or not
+    pub requirements: Vec<RequirementDecisions>,
+    pub decisions: Vec<DecisionRecord>,
+}
+
+impl AdrLinkReport {
+    /// Links the requirements with an ID in `documents` (file name, text) to the `decisions` citing them.
+    pub fn new(documents: &[(String, String)], decisions: Vec<DecisionRecord>) -> Self {
+        let mut requirements: Vec<RequirementDecisions> = Vec::new();
+        for (file, text) in documents {
+            for node in extract_requirement_nodes(text) {
+                // Only requirements with their own ID can be cited
+                if node.id.starts_with("line ") || node.id.contains(" (line ") || requirements.iter().any(|r| r.id == node.id) {
+                    continue;
+                }
+                let decisions = decisions.iter()
+                    .filter(|d| d.requirement_ids.contains(&node.id))
+                    .map(|d| d.file.clone())
+                    .collect();
+                requirements.push(RequirementDecisions { id: node.id, text: node.text, file: file.clone(), decisions });
+            }
+        }
+        Self { requirements, decisions }
+    }
+
+    pub fn constrained(&self) -> Vec<&RequirementDecisions> {
+        self.requirements.iter().filter(|r| !r.decisions.is_empty()).collect()
+    }
+
+    /// Active decisions that cite none of the requirements.
+    pub fn undriven(&self) -> Vec<&DecisionRecord> {
+        self.decisions.iter()
+            .filter(|d| d.is_active() && !d.requirement_ids.iter().any(|id| self.requirements.iter().any(|r| r.id == *id)))
+            .collect()
+    }
+
+    /// IDs cited by a decision that no requirement document defines, with the citing decision.
+    pub fn unknown_references(&self) -> Vec<(&str, &DecisionRecord)> {
+        self.decisions.iter()
+            .flat_map(|d| d.requirement_ids.iter().map(move |id| (id.as_str(), d)))
+            .filter(|(id, _)| !self.requirements.iter().any(|r| r.id == *id))
+            .collect()
+    }
+}
+
+/// The ADR directory: `dir` when given, else the first of [`ADR_LOCATIONS`] that exists.
+pub fn find_adr_dir(dir: Option<&Path>) -> Option<PathBuf> {
+    match dir {
+        Some(dir) => Some(dir.to_path_buf()),
+        None => ADR_LOCATIONS.iter().map(PathBuf::from).find(|p| p.is_dir()),
+    }
+}
+
+pub fn format_adr_markdown(requirements_source: &Path, adr_dir: &Path, report: &AdrLinkReport) -> String {
+    let constrained = report.constrained();
+    let undriven = report.undriven();
+    let unknown = report.unknown_references();
+    let title = |file: &str| report.decisions.iter().find(|d| d.file == file).map(|d| d.title.clone()).unwrap_or_else(|| file.to_string());
+
+    let mut output = String::from("# 🏛️ Requirement-Decision Links\n\n");
+    output.push_str(&format!("**Requirements:** `{}` | **ADRs:** `{}`\n\n", requirements_source.display(), adr_dir.display()));
+    output.push_str(&format!(
+        "**Summary:** {} of {} requirements constrained by ADRs; {} of {} ADRs with no driving requirement\n\n",
+        constrained.len(), report.requirements.len(), undriven.len(), report.decisions.len()
+    ));
+
+    output.push_str("## 📐 Requirements Constrained by ADRs\n\n");
+    if constrained.is_empty() {
+        output.push_str("No ADR cites a requirement ID.\n\n");
+    } else {
+        output.push_str("| Requirement | Source | Decisions |\n|---|---|---|\n");
+        for requirement in &constrained {
+            let decisions: Vec<String> = requirement.decisions.iter().map(|f| format!("{} (`{}`)", title(f), f)).collect();
+            output.push_str(&format!("| {} | `{}` | {} |\n", requirement.text.replace('|', "\\|"), requirement.file, decisions.join("<br>")));
+        }
+        output.push('\n');
+    }
+
+    output.push_str("## 🧭 ADRs Without a Driving Requirement\n\n");
+    if undriven.is_empty() {
+        output.push_str("✅ Every active ADR cites a requirement.\n\n");
+    } else {
+        for decision in &undriven {
+            let status = decision.status.as_deref().map(|s| format!(" ({})", s)).unwrap_or_default();
+            output.push_str(&format!("- `{}` - {}{}\n", decision.file, decision.title, status));
+        }
+        output.push('\n');
+    }
+
+    if !unknown.is_empty() {
+        output.push_str("## ❓ Unknown Requirement References\n\n");
+        for (id, decision) in &unknown {
+            output.push_str(&format!("- `{}` cites **{}**, which no requirement document defines\n", decision.file, id));
+        }
+        output.push('\n');
+    }
+    output
+}
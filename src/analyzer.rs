@@ -2,7 +2,35 @@ use anyhow::Result;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use reqwest::Client;
-use crate::config::Config;
+use sha2::{Digest, Sha256};
+use crate::config::{is_compatible_provider, Config, GenerationSettings};
+use crate::budget::LlmUsage;
+use crate::changelog::{build_changelog, ImprovementChange};
+use crate::dependencies::DependencyGraph;
+use crate::domain_model::DomainModel;
+use crate::ears::EarsReport;
+use crate::metrics::Metrics;
+use crate::telemetry::{instrument, start_span, SpanKind, Tracer};
+use crate::llm_stream::{stream_delta, LineBuffer, StreamEvent, StreamFormat, StreamHandler};
+use crate::fixtures::{FixtureMode, LlmFixture, LlmFixtures};
+use crate::mock_provider::MockResponses;
+use crate::json_repair::{extract_json, JsonRepair};
+use crate::retry::{connect_hint, is_retryable, retry_delay, timeout_hint};
+use crate::reviewers::{suggest_reviewers, ReviewerSuggestion};
+use crate::smart::SmartAnalysis;
+use crate::event_storming::EventStormingBoard;
+use crate::contract_tests::ContractTest;
+use crate::test_data::TestDataSet;
+use crate::test_coverage::TestCoverageMatrix;
+use crate::load_tests::LoadScenario;
+use crate::security_tests::SecurityTestCase;
+use crate::custom_rules::CompiledRule;
+use crate::prompts::{PromptTask, PromptTemplates};
+use crate::config::CustomRule;
+use crate::text::capitalize;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{watch, Semaphore};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalysisResult {
@@ -11,10 +39,127 @@ pub struct AnalysisResult {
     pub uml_diagrams: Option<UmlDiagrams>,
     pub pseudocode: Option<String>,
     pub test_cases: Option<TestCases>,
+    /// Pact-style contract tests for the endpoints of API requirements
+    #[serde(default)]
+    pub contract_tests: Option<Vec<ContractTest>>,
+    /// Valid, boundary and invalid inputs for the actions under test
+    #[serde(default)]
+    pub test_data: Option<Vec<TestDataSet>>,
+    /// The generated tests that exercise each requirement
+    #[serde(default)]
+    pub test_coverage: Option<TestCoverageMatrix>,
+    /// Gherkin `.feature` file for the requirements
+    #[serde(default)]
+    pub gherkin: Option<String>,
     pub improved_requirements: Option<String>,
+    /// Which findings drove which edits of the improved requirements
+    #[serde(default)]
+    pub improvement_changelog: Option<Vec<ImprovementChange>>,
     pub completeness_analysis: Option<CompletenessAnalysis>,
     pub user_story_validation: Option<UserStoryValidation>,
     pub nfr_suggestions: Option<Vec<NonFunctionalRequirement>>,
+    #[serde(default)]
+    pub permission_matrix: Option<PermissionMatrix>,
+    /// Dependencies between the requirements, with any cycles
+    #[serde(default)]
+    pub dependency_graph: Option<DependencyGraph>,
+    /// Proposed aggregates, entities, value objects and domain events
+    #[serde(default)]
+    pub domain_model: Option<DomainModel>,
+    /// Specific/Measurable/Achievable/Relevant/Time-bound scores per requirement
+    #[serde(default)]
+    pub smart_analysis: Option<SmartAnalysis>,
+    /// Actors, commands, domain events, policies and read models on a timeline
+    #[serde(default)]
+    pub event_storming: Option<EventStormingBoard>,
+    /// k6/Gatling load-test scenarios for the performance requirements
+    #[serde(default)]
+    pub load_tests: Option<Vec<LoadScenario>>,
+    /// Negative auth tests, injection probes and abuse cases for the security-relevant requirements
+    #[serde(default)]
+    pub security_tests: Option<Vec<SecurityTestCase>>,
+    /// Who should review the document, by finding category
+    #[serde(default)]
+    pub reviewer_suggestions: Option<Vec<ReviewerSuggestion>>,
+    /// EARS pattern of each requirement sentence
+    #[serde(default)]
+    pub ears_validation: Option<EarsReport>,
+    #[serde(default)]
+    pub suppressed_ambiguities: Vec<Ambiguity>,
+    /// Findings left out of `ambiguities` as duplicates or below the minimum severity
+    #[serde(default)]
+    pub hidden_findings: HiddenFindings,
+    #[serde(default)]
+    pub metrics: AnalysisMetrics,
+    #[serde(default)]
+    pub rewrite_suggestions: Option<Vec<RewriteSuggestion>>,
+    /// An AI provider is configured but its analysis failed, so the result
+    /// comes from built-in analysis alone
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ai_failed: bool,
+}
+
+impl AnalysisResult {
+    /// Leaves out the findings below `min_severity`, counting them in
+    /// `hidden_findings`.
+    pub fn retain_min_severity(&mut self, min_severity: AmbiguitySeverity) {
+        let found = self.ambiguities.len();
+        self.ambiguities.retain(|a| a.severity >= min_severity);
+        self.hidden_findings.below_min_severity += found - self.ambiguities.len();
+        self.hidden_findings.min_severity = Some(min_severity);
+    }
+
+    /// Names `file` as the source of every located finding.
+    pub fn set_source_file(&mut self, file: &str) {
+        for ambiguity in self.ambiguities.iter_mut().chain(self.suppressed_ambiguities.iter_mut()) {
+            if let Some(location) = ambiguity.location.as_mut() {
+                location.file = Some(file.to_string());
+            }
+        }
+    }
+}
+
+/// Counts of the findings a report does not show, for its summary.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HiddenFindings {
+    /// Findings merged into another one of the same span, e.g. a term both
+    /// the built-in rules and the AI flagged
+    pub duplicates: usize,
+    /// Findings dropped by `--min-severity` or a preset's `min_severity`
+    pub below_min_severity: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_severity: Option<AmbiguitySeverity>,
+    /// Findings accepted in the `.prismignore-findings` baseline
+    pub baselined: usize,
+}
+
+impl HiddenFindings {
+    /// E.g. "3 below Medium, 2 duplicates merged", or `None` when nothing
+    /// was left out.
+    pub fn summary(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if let Some(min_severity) = self.min_severity.filter(|_| self.below_min_severity > 0) {
+            parts.push(format!("{} below {}", self.below_min_severity, min_severity));
+        }
+        if self.duplicates > 0 {
+            parts.push(format!("{} duplicate{} merged", self.duplicates, if self.duplicates == 1 { "" } else { "s" }));
+        }
+        if self.baselined > 0 {
+            parts.push(format!("{} accepted in the baseline", self.baselined));
+        }
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+}
+
+/// Size-normalized finding counts, so long specifications can be compared
+/// with short stories.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnalysisMetrics {
+    pub word_count: usize,
+    pub requirement_count: usize,
+    pub findings_per_100_words: f32,
+    pub findings_per_requirement: f32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,9 +168,233 @@ pub struct Ambiguity {
     pub reason: String,
     pub suggestions: Vec<String>,
     pub severity: AmbiguitySeverity,
+    #[serde(default)]
+    pub rule_id: String,
+    #[serde(default)]
+    pub fingerprint: String,
+    #[serde(default)]
+    pub location: Option<SourceLocation>,
+}
+
+pub const RULE_VAGUE_TERM: &str = "vague-term";
+pub const RULE_PASSIVE_VOICE: &str = "passive-voice";
+pub const RULE_AI_AMBIGUITY: &str = "ai-ambiguity";
+
+pub fn rule_description(rule_id: &str) -> &'static str {
+    match rule_id {
+        RULE_VAGUE_TERM => "Vague or subjective term that lacks specific criteria",
+        RULE_PASSIVE_VOICE => "Passive voice hides the responsible actor",
+        RULE_AI_AMBIGUITY => "Ambiguity detected by the configured AI provider",
+        _ => "Requirement ambiguity",
+    }
+}
+
+/// Kind of problem a rule reports. The AI reports the same vague wording as
+/// the vague-term rule; every other rule is a category of its own.
+pub fn rule_category(rule_id: &str) -> &str {
+    match rule_id {
+        RULE_VAGUE_TERM | RULE_AI_AMBIGUITY => "vague-wording",
+        _ => rule_id,
+    }
+}
+
+/// "a" or "an", to go in front of `noun`.
+fn article(noun: &str) -> &'static str {
+    match noun.chars().next().map(|c| c.to_ascii_lowercase()) {
+        Some('a' | 'e' | 'i' | 'o' | 'u') => "an",
+        _ => "a",
+    }
+}
+
+/// "reset my password" as "reset their password", for steps about an actor.
+fn third_person(phrase: &str) -> String {
+    Regex::new(r"(?i)\b(my|our)\b").unwrap().replace_all(phrase, "their")
+        .replace(" me ", " them ")
+}
+
+/// Lowercases the first letter, unless the first word is an acronym.
+fn uncapitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(first), Some(second)) if !second.is_uppercase() => first.to_lowercase().chain(text.chars().skip(1)).collect(),
+        _ => text.to_string(),
+    }
+}
+
+/// Values of the completeness and NFR prompts: the text and its entities,
+/// together as `{entities}` and one kind at a time.
+fn entity_values(text: &str, entities: &ExtractedEntities) -> Vec<(&'static str, String)> {
+    let list = |items: &[String]| format!("{:?}", items);
+    vec![
+        ("text", text.to_string()),
+        ("entities", format!("- Actors: {}\n- Actions: {}\n- Objects: {}", list(&entities.actors), list(&entities.actions), list(&entities.objects))),
+        ("actors", list(&entities.actors)),
+        ("actions", list(&entities.actions)),
+        ("objects", list(&entities.objects)),
+    ]
+}
+
+/// The error of the first chunk that failed, with how many did.
+fn chunk_failures<T>(results: &[Result<T>]) -> Result<()> {
+    let mut failures = results.iter().enumerate().filter_map(|(i, result)| result.as_ref().err().map(|e| (i, e)));
+    match failures.next() {
+        Some((first, e)) => Err(anyhow::anyhow!(
+            "{} of {} parts of the document, part {} with: {}",
+            failures.count() + 1, results.len(), first + 1, e
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Where requests go: `llm.base_url`, or `default` without one. The base
+/// URL of a compatible gateway may stop at the API version, as in
+/// `https://openrouter.ai/api/v1`, and then gets `path` appended.
+fn endpoint_url(config: &Config, default: &str, path: &str) -> String {
+    let Some(base_url) = config.llm.base_url.as_deref() else {
+        return default.to_string();
+    };
+    let base_url = base_url.trim_end_matches('/');
+    if is_compatible_provider(&config.llm.provider) && !base_url.ends_with(path) {
+        format!("{}{}", base_url, path)
+    } else {
+        base_url.to_string()
+    }
+}
+
+/// Connecting gets at most 10 seconds of the `timeout_secs` a request may take.
+fn http_client(timeout_secs: u64) -> Client {
+    let mut builder = Client::builder();
+    if timeout_secs > 0 {
+        builder = builder.connect_timeout(Duration::from_secs(timeout_secs.min(10)));
+    }
+    builder.build().unwrap_or_default()
+}
+
+/// An inline `<!-- prism:ignore [rule] ["text"] -->` or `[prism-ok]` marker.
+/// Rule and text are optional filters; without them every finding on the
+/// target line is silenced.
+#[derive(Debug, Clone)]
+struct Suppression {
+    line: usize,
+    rule_id: Option<String>,
+    text: Option<String>,
+}
+
+/// A part of a document small enough for one LLM request, `offset` bytes
+/// from the start of the document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextChunk {
+    pub offset: usize,
+    pub text: String,
+}
+
+/// Splits `text` into chunks of at most `max_chars` bytes, preferably before
+/// a heading, otherwise at a paragraph break, a line break or a space. The
+/// chunks together are the whole text; a `max_chars` of 0 keeps it whole.
+pub fn split_into_chunks(text: &str, max_chars: usize) -> Vec<TextChunk> {
+    // Where a chunk may end, with how good a place it is
+    let mut breaks: Vec<(usize, u8)> = Vec::new();
+    let mut previous_blank = false;
+    for (offset, line) in text.split_inclusive('\n').scan(0, |start, line| {
+        let offset = *start;
+        *start += line.len();
+        Some((offset, line))
+    }) {
+        let rank = if line.trim_start().starts_with('#') {
+            3
+        } else if previous_blank {
+            2
+        } else {
+            1
+        };
+        if offset > 0 {
+            breaks.push((offset, rank));
+        }
+        previous_blank = line.trim().is_empty();
+    }
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while max_chars > 0 && text.len() - start > max_chars {
+        let mut end = start + max_chars;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        if end == start {
+            end += text[start..].chars().next().map_or(1, char::len_utf8);
+        }
+        // The best break in the second half of the chunk, the latest of equally good ones
+        let cut = breaks.iter()
+            .filter(|(offset, _)| *offset > start + max_chars / 2 && *offset <= end)
+            .max_by_key(|(offset, rank)| (*rank, *offset))
+            .map(|(offset, _)| *offset)
+            .or_else(|| text[start..end].char_indices().rev()
+                .find(|(_, c)| c.is_whitespace())
+                .map(|(space, c)| start + space + c.len_utf8()))
+            .unwrap_or(end);
+        chunks.push(TextChunk { offset: start, text: text[start..cut].to_string() });
+        start = cut;
+    }
+    chunks.push(TextChunk { offset: start, text: text[start..].to_string() });
+    chunks.retain(|chunk| !chunk.text.trim().is_empty());
+    chunks
 }
 
+/// Position of a finding in the analyzed text. Line and column are 1-based,
+/// offset is the byte offset from the start of the text.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SourceLocation {
+    /// The analyzed file, when the text came from one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+}
+
+/// `file:line:column`, or `line L, column C` for text without a file.
+impl std::fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}:{}:{}", file, self.line, self.column),
+            None => write!(f, "line {}, column {}", self.line, self.column),
+        }
+    }
+}
+
+/// Byte span of `quote` in `text`, the first starting at or after `from`:
+/// as it is, else ignoring case, quote marks around it and how whitespace
+/// is broken, as models re-case and reflow what they quote.
+fn find_quote(text: &str, quote: &str, from: usize) -> Option<(usize, usize)> {
+    if let Some(start) = text[from..].find(quote) {
+        return Some((from + start, from + start + quote.len()));
+    }
+
+    // Characters lowercased and whitespace runs collapsed, with the span each came from
+    let normalize = |text: &str, base: usize| {
+        let mut chars: Vec<(char, usize, usize)> = Vec::new();
+        for (i, c) in text.char_indices() {
+            let end = base + i + c.len_utf8();
+            match chars.last_mut() {
+                Some((' ', _, last_end)) if c.is_whitespace() => *last_end = end,
+                _ if c.is_whitespace() => chars.push((' ', base + i, end)),
+                _ => chars.extend(c.to_lowercase().map(|lower| (lower, base + i, end))),
+            }
+        }
+        chars
+    };
+    let quote: Vec<char> = normalize(quote.trim().trim_matches(['"', '\'', '`', '“', '”', '‘', '’']).trim(), 0)
+        .into_iter().map(|(c, _, _)| c).collect();
+    if quote.is_empty() {
+        return None;
+    }
+    let text = normalize(&text[from..], from);
+    text.windows(quote.len())
+        .find(|window| window.iter().map(|(c, _, _)| c).eq(quote.iter()))
+        .map(|window| (window[0].1, window[window.len() - 1].2))
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
 pub enum AmbiguitySeverity {
     Low,
     Medium,
@@ -44,7 +413,7 @@ impl std::fmt::Display for AmbiguitySeverity {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ExtractedEntities {
     pub actors: Vec<String>,
     pub actions: Vec<String>,
@@ -137,13 +506,69 @@ pub enum NfrPriority {
     WontHave,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionMatrix {
+    pub actors: Vec<String>,
+    pub actions: Vec<String>,
+    pub grants: Vec<PermissionGrant>,
+    pub unassigned_actions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionGrant {
+    pub actor: String,
+    pub action: String,
+    pub evidence: String,
+}
+
+impl PermissionMatrix {
+    pub fn is_granted(&self, actor: &str, action: &str) -> bool {
+        self.grants.iter().any(|g| g.actor == actor && g.action == action)
+    }
+}
+
+/// A proposed rewrite of the sentence containing a single finding. `[...]`
+/// placeholders mark the values only the author can fill in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RewriteSuggestion {
+    pub finding: String,
+    pub rule_id: String,
+    pub line: Option<usize>,
+    pub original: String,
+    pub proposed: String,
+}
+
+/// An actor and an action that were mentioned in the same sentence.
+#[derive(Debug, Clone)]
+pub struct ActorActionLink {
+    pub actor: String,
+    pub action: String,
+    pub sentence: String,
+}
+
 #[derive(Clone)]
 pub struct Analyzer {
     vague_terms: Vec<Regex>,
     passive_voice: Regex,
     conditional_incomplete: Regex,
+    /// The team's own rules, checked next to the vague terms and passive voice
+    custom_rules: Vec<CompiledRule>,
     http_client: Client,
     config: Option<Config>,
+    /// Shared between clones, so usage is counted across the whole run
+    usage: Arc<Mutex<LlmUsage>>,
+    /// Streams LLM responses to this handler as they arrive
+    stream_handler: Option<StreamHandler>,
+    /// Records the latency, outcome and size of every LLM call
+    metrics: Option<Arc<Metrics>>,
+    /// Traces the analysis, each detector and each LLM call
+    tracer: Option<Arc<Tracer>>,
+    /// LLM calls in flight fail, and later ones fail at once, after this turns true
+    cancel: Option<watch::Receiver<bool>>,
+    /// Prompts of the AI tasks, the built-in ones unless replaced by template files
+    prompts: PromptTemplates,
+    /// Records LLM responses to golden files, or answers from them instead of the provider
+    fixtures: Option<LlmFixtures>,
 }
 
 #[derive(Serialize)]
@@ -152,6 +577,8 @@ struct LlmRequest {
     messages: Vec<LlmMessage>,
     max_tokens: u32,
     temperature: f32,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
 }
 
 #[derive(Serialize)]
@@ -190,125 +617,342 @@ impl Analyzer {
             vague_terms,
             passive_voice,
             conditional_incomplete,
+            custom_rules: Vec::new(),
             http_client: Client::new(),
             config: None,
+            usage: Arc::new(Mutex::new(LlmUsage::default())),
+            stream_handler: None,
+            metrics: None,
+            tracer: None,
+            cancel: None,
+            prompts: PromptTemplates::default(),
+            fixtures: None,
         })
     }
 
+    /// Uses `config`, including its LLM provider and `llm.timeout`.
     pub fn with_config(mut self, config: Config) -> Self {
+        self.http_client = http_client(config.llm.timeout);
         self.config = Some(config);
         self
     }
 
+    /// Reports matches of `rules` next to the built-in findings; fails on a
+    /// rule without a valid pattern or keywords.
+    pub fn with_custom_rules(mut self, rules: Vec<CustomRule>) -> Result<Self> {
+        self.custom_rules = rules.into_iter().map(CompiledRule::new).collect::<Result<_>>()?;
+        Ok(self)
+    }
+
+    /// Streams OpenAI, Claude and Ollama responses to `handler` while they
+    /// arrive. Other providers report the whole response at once.
+    pub fn with_stream_handler(mut self, handler: StreamHandler) -> Self {
+        self.stream_handler = Some(handler);
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    pub fn with_tracer(mut self, tracer: Option<Arc<Tracer>>) -> Self {
+        self.tracer = tracer;
+        self
+    }
+
+    /// Aborts LLM calls once `cancel` turns true, e.g. on Ctrl-C.
+    pub fn with_cancellation(mut self, cancel: watch::Receiver<bool>) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Records every LLM response to `fixtures`, or replays them from there
+    /// without calling the provider.
+    pub fn with_fixtures(mut self, fixtures: Option<LlmFixtures>) -> Self {
+        self.fixtures = fixtures;
+        self
+    }
+
+    pub fn fixtures(&self) -> Option<&LlmFixtures> {
+        self.fixtures.as_ref()
+    }
+
+    pub fn with_prompts(mut self, prompts: PromptTemplates) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    pub fn prompts(&self) -> &PromptTemplates {
+        &self.prompts
+    }
+
+    /// Changes whenever a custom rule, severity override or disabled rule
+    /// does, for cache keys. Rules files are read outside the configuration.
+    pub fn rules_fingerprint(&self) -> String {
+        let rules: Vec<&CustomRule> = self.custom_rules.iter().map(|compiled| &compiled.rule).collect();
+        let overrides = self.config.as_ref().map(|config| (&config.analysis.severity_overrides, &config.analysis.disabled_rules));
+        serde_json::to_string(&(rules, overrides)).unwrap_or_default()
+    }
+
+    /// A copy of this analyzer that only runs the built-in analysis. LLM
+    /// usage stays shared with the original.
+    pub fn without_ai(&self) -> Self {
+        let mut analyzer = self.clone();
+        if let Some(config) = &mut analyzer.config {
+            config.llm.api_key = None;
+        }
+        analyzer
+    }
+
+    /// A copy of this analyzer that counts its LLM usage on its own, e.g. for
+    /// one server request.
+    pub fn with_separate_usage(&self) -> Self {
+        let mut analyzer = self.clone();
+        analyzer.usage = Arc::new(Mutex::new(LlmUsage::default()));
+        analyzer
+    }
+
+    /// LLM calls made so far by this analyzer and its clones.
+    pub fn llm_usage(&self) -> LlmUsage {
+        *self.usage.lock().unwrap()
+    }
+
     pub async fn analyze(&self, text: &str) -> Result<AnalysisResult> {
+        let mut span = start_span(&self.tracer, "prism.analyze", SpanKind::Internal);
+        let result = instrument(span.as_ref(), self.run_analysis(text)).await;
+        if let Some(span) = &mut span {
+            span.set_attribute("prism.text_length", text.len());
+            match &result {
+                Ok(result) => {
+                    span.set_attribute("prism.finding_count", result.ambiguities.len());
+                    span.set_attribute("prism.ai_failed", result.ai_failed);
+                }
+                Err(e) => span.set_error(e),
+            }
+        }
+        result
+    }
+
+    async fn run_analysis(&self, text: &str) -> Result<AnalysisResult> {
+        let span = start_span(&self.tracer, "prism.detector.rules", SpanKind::Internal);
         let mut ambiguities = self.detect_ambiguities(text);
+        drop(span);
+        let span = start_span(&self.tracer, "prism.detector.entities", SpanKind::Internal);
         let mut entities = self.extract_entities(text);
+        drop(span);
+        let mut ai_failed = false;
         
         if let Some(config) = &self.config {
             if config.llm.api_key.is_some() {
                 // println!("🤖 Calling AI for enhanced analysis...");
+                // Documents too long for one request are analyzed in chunks
+                let chunks = split_into_chunks(text, config.llm.chunking.max_chars);
                 
                 // Try AI ambiguity detection with error reporting
-                match self.detect_ambiguities_with_llm(text).await {
-                    Ok(llm_ambiguities) => {
-                        // println!("✅ AI found {} additional ambiguities", llm_ambiguities.len());
-                        ambiguities.extend(llm_ambiguities);
+                if self.is_rule_enabled(RULE_AI_AMBIGUITY) {
+                    let mut span = start_span(&self.tracer, "prism.detector.ai_ambiguities", SpanKind::Internal);
+                    let (llm_ambiguities, outcome) = instrument(span.as_ref(), self.detect_ambiguities_in_chunks(text, &chunks)).await;
+                    if let (Some(span), Err(e)) = (&mut span, &outcome) {
+                        span.set_error(e);
                     }
-                    Err(e) => {
+                    drop(span);
+                    // println!("✅ AI found {} additional ambiguities", llm_ambiguities.len());
+                    ambiguities.extend(llm_ambiguities);
+                    if let Err(e) = outcome {
                         eprintln!("⚠️  AI ambiguity detection failed: {}", e);
                         eprintln!("   Continuing with built-in analysis only");
+                        ai_failed = true;
                     }
                 }
                 
                 // Try AI entity extraction with error reporting
-                match self.extract_entities_with_llm(text).await {
-                    Ok(llm_entities) => {
-                        let actors_count = llm_entities.actors.len();
-                        let actions_count = llm_entities.actions.len();
-                        let objects_count = llm_entities.objects.len();
-                        
-                        entities.actors.extend(llm_entities.actors);
-                        entities.actions.extend(llm_entities.actions);
-                        entities.objects.extend(llm_entities.objects);
-                        
-                        entities.actors.sort();
-                        entities.actors.dedup();
-                        entities.actions.sort();
-                        entities.actions.dedup();
-                        entities.objects.sort();
-                        entities.objects.dedup();
-                        
-                        // println!("✅ AI enhanced entities: +{} actors, +{} actions, +{} objects", 
-                        //         actors_count, actions_count, objects_count);
-                    }
-                    Err(e) => {
-                        eprintln!("⚠️  AI entity extraction failed: {}", e);
-                        eprintln!("   Continuing with built-in analysis only");
-                    }
+                let mut span = start_span(&self.tracer, "prism.detector.ai_entities", SpanKind::Internal);
+                let (llm_entities, outcome) = instrument(span.as_ref(), self.extract_entities_in_chunks(text, &chunks)).await;
+                if let (Some(span), Err(e)) = (&mut span, &outcome) {
+                    span.set_error(e);
+                }
+                drop(span);
+                let actors_count = llm_entities.actors.len();
+                let actions_count = llm_entities.actions.len();
+                let objects_count = llm_entities.objects.len();
+                
+                entities.actors.extend(llm_entities.actors);
+                entities.actions.extend(llm_entities.actions);
+                entities.objects.extend(llm_entities.objects);
+                
+                entities.actors.sort();
+                entities.actors.dedup();
+                entities.actions.sort();
+                entities.actions.dedup();
+                entities.objects.sort();
+                entities.objects.dedup();
+                
+                // println!("✅ AI enhanced entities: +{} actors, +{} actions, +{} objects", 
+                //         actors_count, actions_count, objects_count);
+                if let Err(e) = outcome {
+                    eprintln!("⚠️  AI entity extraction failed: {}", e);
+                    eprintln!("   Continuing with built-in analysis only");
+                    ai_failed = true;
                 }
             } else {
                 // println!("ℹ️  AI not configured - using built-in analysis only");
             }
         }
+
+        self.apply_rule_config(&mut ambiguities);
+        self.locate_findings(text, &mut ambiguities);
+        let found = ambiguities.len();
+        let mut ambiguities = self.deduplicate_findings(ambiguities);
+        let hidden_findings = HiddenFindings { duplicates: found - ambiguities.len(), ..HiddenFindings::default() };
+        self.assign_fingerprints(text, &mut ambiguities);
+        let (ambiguities, suppressed_ambiguities) = self.apply_suppressions(text, ambiguities);
         
         Ok(AnalysisResult {
-            ambiguities,
             entities,
             uml_diagrams: None,
             pseudocode: None,
             test_cases: None,
+            gherkin: None,
             improved_requirements: None,
+            improvement_changelog: None,
             completeness_analysis: None,
             user_story_validation: None,
             nfr_suggestions: None,
+            permission_matrix: None,
+            dependency_graph: None,
+            domain_model: None,
+            smart_analysis: None,
+            event_storming: None,
+            contract_tests: None,
+            test_data: None,
+            test_coverage: None,
+            load_tests: None,
+            security_tests: None,
+            reviewer_suggestions: None,
+            ears_validation: None,
+            rewrite_suggestions: None,
+            metrics: self.compute_metrics(text, ambiguities.len()),
+            ambiguities,
+            suppressed_ambiguities,
+            hidden_findings,
+            ai_failed,
         })
     }
 
+    /// Stores `improved` as the improvement of `original`, with the changelog
+    /// of the findings behind its edits.
+    pub fn set_improved_requirements(&self, result: &mut AnalysisResult, original: &str, improved: String) {
+        result.improvement_changelog = Some(build_changelog(original, &improved, &result.ambiguities));
+        result.improved_requirements = Some(improved);
+    }
+
+    /// Counts words and requirement statements (user stories and sentences
+    /// with a modal verb) and derives the finding density from them.
+    pub fn compute_metrics(&self, text: &str, finding_count: usize) -> AnalysisMetrics {
+        let requirement = Regex::new(r"(?i)\b(as an?|shall|must|should|will|want to|need to)\b").unwrap();
+        let word_count = text.split_whitespace().count();
+        let mut requirement_count = Self::split_sentences(text).iter()
+            .filter(|sentence| requirement.is_match(sentence))
+            .count();
+        if requirement_count == 0 && word_count > 0 {
+            requirement_count = 1;
+        }
+
+        let per = |count: usize, base: f32| if base > 0.0 { count as f32 / base } else { 0.0 };
+        AnalysisMetrics {
+            word_count,
+            requirement_count,
+            findings_per_100_words: per(finding_count, word_count as f32 / 100.0),
+            findings_per_requirement: per(finding_count, requirement_count as f32),
+        }
+    }
+
     async fn detect_ambiguities_with_llm(&self, text: &str) -> Result<Vec<Ambiguity>> {
-        let prompt = format!(
-            "Analyze the following requirement text for ambiguities, vague terms, and unclear specifications. 
-            Look for terms that lack specific criteria, passive voice that hides responsibility, 
-            incomplete conditional logic, and any other sources of potential miscommunication.
-            
-            Requirement text:
-            {}
-            
-            Please provide a JSON response with the following structure:
-            {{
-                \"ambiguities\": [
-                    {{
-                        \"text\": \"the ambiguous phrase\",
-                        \"reason\": \"why it's ambiguous\",
-                        \"suggestions\": [\"suggestion 1\", \"suggestion 2\"],
-                        \"severity\": \"High|Medium|Low|Critical\"
-                    }}
-                ]
-            }}",
-            text
-        );
+        let prompt = self.prompts.render(PromptTask::Ambiguities, &[("text", text)]);
 
         let response = self.call_llm(&prompt).await?;
         self.parse_ambiguities_response(&response)
     }
 
+    /// The AI ambiguities of `text`, asked for chunk by chunk when it was
+    /// split into several, located in the whole text. Findings of the chunks
+    /// that succeeded are kept when others fail.
+    async fn detect_ambiguities_in_chunks(&self, text: &str, chunks: &[TextChunk]) -> (Vec<Ambiguity>, Result<()>) {
+        if chunks.len() <= 1 {
+            return match self.detect_ambiguities_with_llm(text).await {
+                Ok(ambiguities) => (ambiguities, Ok(())),
+                Err(e) => (Vec::new(), Err(e)),
+            };
+        }
+        let results = self.run_on_chunks(chunks, |analyzer, chunk| async move {
+            analyzer.detect_ambiguities_with_llm(&chunk).await
+        }).await;
+        let outcome = chunk_failures(&results);
+        let mut ambiguities = Vec::new();
+        for (chunk, found) in chunks.iter().zip(results) {
+            for mut ambiguity in found.unwrap_or_default() {
+                ambiguity.location = chunk.text.find(&ambiguity.text)
+                    .map(|offset| Self::location_at(text, chunk.offset + offset));
+                ambiguities.push(ambiguity);
+            }
+        }
+        (ambiguities, outcome)
+    }
+
+    /// The AI entities of `text`, merged from its chunks like the ambiguities.
+    async fn extract_entities_in_chunks(&self, text: &str, chunks: &[TextChunk]) -> (ExtractedEntities, Result<()>) {
+        if chunks.len() <= 1 {
+            return match self.extract_entities_with_llm(text).await {
+                Ok(entities) => (entities, Ok(())),
+                Err(e) => (ExtractedEntities::default(), Err(e)),
+            };
+        }
+        let results = self.run_on_chunks(chunks, |analyzer, chunk| async move {
+            analyzer.extract_entities_with_llm(&chunk).await
+        }).await;
+        let outcome = chunk_failures(&results);
+        let mut entities = ExtractedEntities::default();
+        for found in results.into_iter().flatten() {
+            entities.actors.extend(found.actors);
+            entities.actions.extend(found.actions);
+            entities.objects.extend(found.objects);
+        }
+        (entities, outcome)
+    }
+
+    /// `task` run on the text of every chunk, up to `llm.chunking.concurrency`
+    /// at a time, with the results in the order of the chunks. Responses are
+    /// not streamed, as those of chunks analyzed together would interleave.
+    async fn run_on_chunks<T, F, Fut>(&self, chunks: &[TextChunk], task: F) -> Vec<Result<T>>
+    where
+        T: Send + 'static,
+        F: Fn(Arc<Analyzer>, String) -> Fut,
+        Fut: std::future::Future<Output = Result<T>> + Send + 'static,
+    {
+        let concurrency = self.config.as_ref().map_or(1, |config| config.llm.chunking.concurrency);
+        let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+        let mut worker = self.clone();
+        worker.stream_handler = None;
+        let worker = Arc::new(worker);
+        let requests: Vec<_> = chunks.iter().map(|chunk| {
+            let semaphore = semaphore.clone();
+            let request = task(worker.clone(), chunk.text.clone());
+            tokio::spawn(async move {
+                let _permit = semaphore.acquire_owned().await.expect("chunk semaphore is never closed");
+                request.await
+            })
+        }).collect();
+
+        let mut results = Vec::new();
+        for request in requests {
+            results.push(request.await.unwrap_or_else(|e| Err(e.into())));
+        }
+        results
+    }
+
     async fn extract_entities_with_llm(&self, text: &str) -> Result<ExtractedEntities> {
-        let prompt = format!(
-            "Extract the key entities from the following requirement text. Identify:
-            1. Actors (who performs actions - users, administrators, systems, services)
-            2. Actions (what is being done - verbs like create, update, delete, login)
-            3. Objects (what is being acted upon - nouns like account, profile, data)
-            
-            Requirement text:
-            {}
-            
-            Please provide a JSON response with the following structure:
-            {{
-                \"actors\": [\"actor1\", \"actor2\"],
-                \"actions\": [\"action1\", \"action2\"],
-                \"objects\": [\"object1\", \"object2\"]
-            }}",
-            text
-        );
+        let prompt = self.prompts.render(PromptTask::Entities, &[("text", text)]);
 
         let response = self.call_llm(&prompt).await?;
         self.parse_entities_response(&response)
@@ -321,48 +965,115 @@ impl Analyzer {
         let api_key = config.llm.api_key.as_ref()
             .ok_or_else(|| anyhow::anyhow!("No API key configured"))?;
 
-        match config.llm.provider.as_str() {
-            "gemini" => self.call_gemini_api(prompt, api_key, &config.llm.model).await,
-            "claude" => self.call_claude_api(prompt, api_key, &config.llm.model).await,
-            "ollama" => self.call_ollama_api(prompt, &config.llm.model, config).await,
-            "openai" | "azure" | _ => self.call_openai_api(prompt, api_key, config).await,
+        let mut span = start_span(&self.tracer, "prism.llm.request", SpanKind::Client);
+        let started = std::time::Instant::now();
+        let settings = config.llm.generation();
+        let mode = self.fixtures.as_ref().map(LlmFixtures::mode);
+        let request = async {
+            if let Some(fixtures) = self.fixtures.as_ref().filter(|_| mode == Some(FixtureMode::Replay)) {
+                return fixtures.replay(&config.llm.provider, &config.llm.model, &settings.system_prompt, prompt);
+            }
+            match config.llm.provider.as_str() {
+                "gemini" => {
+                    let response = self.call_gemini_api(prompt, api_key, &config.llm.model, &settings).await;
+                    if let (Some(handler), Ok(text)) = (&self.stream_handler, &response) {
+                        handler(StreamEvent::Started);
+                        handler(StreamEvent::Delta(text));
+                        handler(StreamEvent::Finished);
+                    }
+                    response
+                }
+                "claude" | "anthropic-compatible" => self.call_claude_api(prompt, api_key, config, &settings).await,
+                "ollama" => self.call_ollama_api(prompt, &config.llm.model, config, &settings).await,
+                "mock" => self.mock_response(prompt, config),
+                "openai" | "azure" | "openai-compatible" | _ => self.call_openai_api(prompt, api_key, config, &settings).await,
+            }
+        };
+        let response = match self.cancel.clone() {
+            Some(mut cancel) => tokio::select! {
+                response = request => response,
+                Ok(_) = cancel.wait_for(|cancelled| *cancelled) => Err(anyhow::anyhow!("AI request cancelled")),
+            },
+            None => request.await,
+        };
+
+        if let Some(span) = &mut span {
+            span.set_attribute("gen_ai.system", config.llm.provider.as_str());
+            span.set_attribute("gen_ai.request.model", config.llm.model.as_str());
+            span.set_attribute("prism.prompt_chars", prompt.len());
+            match &response {
+                Ok(text) => span.set_attribute("prism.response_chars", text.len()),
+                Err(e) => span.set_error(e),
+            }
         }
+        if let Some(metrics) = &self.metrics {
+            let response_chars = response.as_ref().map(String::len).unwrap_or_default();
+            metrics.record_llm_request(&config.llm.provider, response.is_ok(), started.elapsed(), prompt.len(), response_chars);
+        }
+        let mut usage = self.usage.lock().unwrap();
+        usage.calls += 1;
+        usage.prompt_chars += prompt.len();
+        if let Ok(text) = &response {
+            usage.response_chars += text.len();
+        }
+        drop(usage);
+        if let (Some(fixtures), Some(FixtureMode::Record), Ok(text)) = (&self.fixtures, mode, &response) {
+            fixtures.save(&settings.system_prompt, &LlmFixture {
+                provider: config.llm.provider.clone(),
+                model: config.llm.model.clone(),
+                prompt: prompt.to_string(),
+                response: text.clone(),
+            })?;
+        }
+        response
     }
 
-    async fn call_openai_api(&self, prompt: &str, api_key: &str, config: &crate::config::Config) -> Result<String> {
+    /// The canned response of the `mock` provider to `prompt`.
+    fn mock_response(&self, prompt: &str, config: &Config) -> Result<String> {
+        let responses = match &config.llm.mock_responses {
+            Some(path) => MockResponses::load(path)?,
+            None => MockResponses::default(),
+        };
+        Ok(responses.respond(self.prompts.task_of(prompt), prompt))
+    }
+
+    async fn call_openai_api(&self, prompt: &str, api_key: &str, config: &crate::config::Config, settings: &GenerationSettings) -> Result<String> {
         let request = LlmRequest {
             model: config.llm.model.clone(),
             messages: vec![
                 LlmMessage {
                     role: "system".to_string(),
-                    content: "You are an expert software requirements analyst. Provide detailed, accurate analysis in the requested JSON format.".to_string(),
+                    content: settings.system_prompt.clone(),
                 },
                 LlmMessage {
                     role: "user".to_string(),
                     content: prompt.to_string(),
                 },
             ],
-            max_tokens: 2000,
-            temperature: 0.1,
+            max_tokens: settings.max_tokens,
+            temperature: settings.temperature,
+            stream: self.stream_handler.is_some(),
         };
 
-        let url = config.llm.base_url.as_deref()
-            .unwrap_or("https://api.openai.com/v1/chat/completions");
+        let url = endpoint_url(config, "https://api.openai.com/v1/chat/completions", "/chat/completions");
 
-        let response = self.http_client
-            .post(url)
-            .header("Authorization", format!("Bearer {}", api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
+        let response = self.send_with_retry("OpenAI", request.stream, || {
+            self.http_client
+                .post(&url)
+                .header("Authorization", format!("Bearer {}", api_key))
+                .header("Content-Type", "application/json")
+                .json(&request)
+        }).await?;
 
         if !response.status().is_success() {
             let error_text = response.text().await.unwrap_or_default();
             return Err(anyhow::anyhow!("OpenAI API request failed: {}", error_text));
         }
+        if request.stream {
+            return self.read_stream("OpenAI", response, StreamFormat::OpenAi).await;
+        }
 
-        let llm_response: LlmResponse = response.json().await?;
+        let llm_response: LlmResponse = response.json().await.map_err(|e| self.request_error("OpenAI", e))?;
         
         llm_response.choices
             .first()
@@ -370,7 +1081,7 @@ impl Analyzer {
             .ok_or_else(|| anyhow::anyhow!("No response from LLM"))
     }
 
-    async fn call_gemini_api(&self, prompt: &str, api_key: &str, model: &str) -> Result<String> {
+    async fn call_gemini_api(&self, prompt: &str, api_key: &str, model: &str, settings: &GenerationSettings) -> Result<String> {
         #[derive(Serialize)]
         struct GeminiRequest {
             contents: Vec<GeminiContent>,
@@ -415,8 +1126,7 @@ impl Analyzer {
             text: String,
         }
 
-        let system_prompt = "You are an expert software requirements analyst. Provide detailed, accurate analysis in the requested JSON format.";
-        let full_prompt = format!("{}\n\n{}", system_prompt, prompt);
+        let full_prompt = format!("{}\n\n{}", settings.system_prompt, prompt);
 
         let request = GeminiRequest {
             contents: vec![GeminiContent {
@@ -425,26 +1135,26 @@ impl Analyzer {
                 }],
             }],
             generation_config: GeminiGenerationConfig {
-                temperature: 0.1,
-                max_output_tokens: 2000,
+                temperature: settings.temperature,
+                max_output_tokens: settings.max_tokens,
             },
         };
 
         let url = format!("https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}", model, api_key);
 
-        let response = self.http_client
-            .post(&url)
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
+        let response = self.send_with_retry("Gemini", false, || {
+            self.http_client
+                .post(&url)
+                .header("Content-Type", "application/json")
+                .json(&request)
+        }).await?;
 
         if !response.status().is_success() {
             let error_text = response.text().await.unwrap_or_default();
             return Err(anyhow::anyhow!("Gemini API request failed: {}", error_text));
         }
 
-        let gemini_response: GeminiResponse = response.json().await?;
+        let gemini_response: GeminiResponse = response.json().await.map_err(|e| self.request_error("Gemini", e))?;
         
         gemini_response.candidates
             .first()
@@ -453,12 +1163,16 @@ impl Analyzer {
             .ok_or_else(|| anyhow::anyhow!("No response from Gemini"))
     }
 
-    async fn call_claude_api(&self, prompt: &str, api_key: &str, model: &str) -> Result<String> {
+    async fn call_claude_api(&self, prompt: &str, api_key: &str, config: &crate::config::Config, settings: &GenerationSettings) -> Result<String> {
         #[derive(Serialize)]
         struct ClaudeRequest {
             model: String,
             max_tokens: u32,
+            temperature: f32,
+            system: String,
             messages: Vec<ClaudeMessage>,
+            #[serde(skip_serializing_if = "std::ops::Not::not")]
+            stream: bool,
         }
 
         #[derive(Serialize)]
@@ -478,29 +1192,36 @@ impl Analyzer {
         }
 
         let request = ClaudeRequest {
-            model: model.to_string(),
-            max_tokens: 2000,
+            model: config.llm.model.clone(),
+            max_tokens: settings.max_tokens,
+            temperature: settings.temperature,
+            system: settings.system_prompt.clone(),
             messages: vec![ClaudeMessage {
                 role: "user".to_string(),
-                content: format!("You are an expert software requirements analyst. Provide detailed, accurate analysis in the requested JSON format.\n\n{}", prompt),
+                content: prompt.to_string(),
             }],
+            stream: self.stream_handler.is_some(),
         };
 
-        let response = self.http_client
-            .post("https://api.anthropic.com/v1/messages")
-            .header("x-api-key", api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
+        let url = endpoint_url(config, "https://api.anthropic.com/v1/messages", "/messages");
+        let response = self.send_with_retry("Claude", request.stream, || {
+            self.http_client
+                .post(&url)
+                .header("x-api-key", api_key)
+                .header("anthropic-version", "2023-06-01")
+                .header("Content-Type", "application/json")
+                .json(&request)
+        }).await?;
 
         if !response.status().is_success() {
             let error_text = response.text().await.unwrap_or_default();
             return Err(anyhow::anyhow!("Claude API request failed: {}", error_text));
         }
+        if request.stream {
+            return self.read_stream("Claude", response, StreamFormat::Claude).await;
+        }
 
-        let claude_response: ClaudeResponse = response.json().await?;
+        let claude_response: ClaudeResponse = response.json().await.map_err(|e| self.request_error("Claude", e))?;
         
         claude_response.content
             .first()
@@ -508,51 +1229,205 @@ impl Analyzer {
             .ok_or_else(|| anyhow::anyhow!("No response from Claude"))
     }
 
-    async fn call_ollama_api(&self, prompt: &str, model: &str, config: &crate::config::Config) -> Result<String> {
+    async fn call_ollama_api(&self, prompt: &str, model: &str, config: &crate::config::Config, settings: &GenerationSettings) -> Result<String> {
         #[derive(Serialize)]
         struct OllamaRequest {
             model: String,
-            prompt: String,
+            messages: Vec<LlmMessage>,
             stream: bool,
+            options: OllamaOptions,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            keep_alive: Option<serde_json::Value>,
+        }
+
+        #[derive(Serialize)]
+        struct OllamaOptions {
+            temperature: f32,
+            num_predict: u32,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            num_ctx: Option<u32>,
         }
 
         #[derive(Deserialize)]
         struct OllamaResponse {
-            response: String,
+            message: LlmResponseMessage,
             done: bool,
         }
 
-        let system_prompt = "You are an expert software requirements analyst. Provide detailed, accurate analysis in the requested JSON format.";
-        let full_prompt = format!("{}\n\n{}", system_prompt, prompt);
-
+        let ollama = &config.llm.ollama;
         let request = OllamaRequest {
             model: model.to_string(),
-            prompt: full_prompt,
-            stream: false,
+            messages: vec![
+                LlmMessage { role: "system".to_string(), content: settings.system_prompt.clone() },
+                LlmMessage { role: "user".to_string(), content: prompt.to_string() },
+            ],
+            stream: self.stream_handler.is_some(),
+            options: OllamaOptions {
+                temperature: settings.temperature,
+                num_predict: settings.max_tokens,
+                num_ctx: ollama.num_ctx,
+            },
+            keep_alive: ollama.keep_alive.as_ref().map(crate::ollama::keep_alive_value),
         };
 
-        let base_url = config.llm.base_url.as_deref()
-            .unwrap_or("http://localhost:11434/api/generate");
+        let url = crate::ollama::chat_url(config.llm.base_url.as_deref());
 
-        let response = self.http_client
-            .post(base_url)
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
+        let response = self.send_with_retry("Ollama", request.stream, || {
+            self.http_client
+                .post(&url)
+                .header("Content-Type", "application/json")
+                .json(&request)
+        }).await?;
 
         if !response.status().is_success() {
             let error_text = response.text().await.unwrap_or_default();
+            if crate::ollama::is_missing_model_error(&error_text) {
+                return Err(anyhow::anyhow!(
+                    "Ollama model '{}' is not installed - pull it with 'ollama pull {}', or set llm.ollama.auto_pull",
+                    model, model
+                ));
+            }
             return Err(anyhow::anyhow!("Ollama API request failed: {}", error_text));
         }
+        if request.stream {
+            return self.read_stream("Ollama", response, StreamFormat::Ollama).await;
+        }
 
-        let ollama_response: OllamaResponse = response.json().await?;
+        let ollama_response: OllamaResponse = response.json().await.map_err(|e| self.request_error("Ollama", e))?;
         
         if !ollama_response.done {
             return Err(anyhow::anyhow!("Ollama response not complete"));
         }
 
-        Ok(ollama_response.response)
+        Ok(ollama_response.message.content)
+    }
+
+    /// Sends the request built by `request` with the `llm.headers` of the
+    /// config, retrying rate-limited and temporarily unavailable responses
+    /// per `llm.retry`. A `stream`ed response gets `llm.timeout` for each
+    /// piece instead of for the whole.
+    async fn send_with_retry(&self, provider: &str, stream: bool, request: impl Fn() -> reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let policy = self.config.as_ref().map(|c| c.llm.retry.clone()).unwrap_or_default();
+        let max_attempts = policy.max_attempts.max(1);
+        let timeout = self.request_timeout();
+        let request = || match &self.config {
+            Some(config) => config.llm.headers.iter().fold(request(), |request, (name, value)| request.header(name, value)),
+            None => request(),
+        };
+
+        let mut attempt = 1;
+        loop {
+            let sent = match timeout {
+                Some(timeout) => {
+                    let request = if stream { request() } else { request().timeout(timeout) };
+                    tokio::time::timeout(timeout, request.send()).await.map_err(|_| self.timeout_error(provider))?
+                }
+                None => request().send().await,
+            };
+            let (reason, retry_after) = match sent {
+                Ok(response) if attempt < max_attempts && is_retryable(response.status()) => {
+                    let retry_after = response.headers().get(reqwest::header::RETRY_AFTER)
+                        .and_then(|v| v.to_str().ok())
+                        .map(str::to_string);
+                    (response.status().to_string(), retry_after)
+                }
+                response => return response.map_err(|e| self.request_error(provider, e)),
+            };
+
+            let delay = retry_delay(&policy, attempt, retry_after.as_deref(), chrono::Utc::now());
+            eprintln!(
+                "⏳ {} request failed ({}), retrying in {:.1}s (attempt {}/{})",
+                provider, reason, delay.as_secs_f32(), attempt + 1, max_attempts
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    fn request_timeout(&self) -> Option<Duration> {
+        let seconds = self.config.as_ref().map_or(0, |c| c.llm.timeout);
+        (seconds > 0).then(|| Duration::from_secs(seconds))
+    }
+
+    fn timeout_error(&self, provider: &str) -> anyhow::Error {
+        let config = self.config.as_ref();
+        anyhow::anyhow!(
+            "{} request timed out after {}s - {}",
+            provider, config.map_or(0, |c| c.llm.timeout), timeout_hint(config.map_or("", |c| c.llm.provider.as_str()))
+        )
+    }
+
+    /// `error` with a hint on what to try when the provider was too slow or unreachable.
+    fn request_error(&self, provider: &str, error: reqwest::Error) -> anyhow::Error {
+        if error.is_timeout() {
+            return self.timeout_error(provider);
+        }
+        if error.is_connect() {
+            let hint = connect_hint(self.config.as_ref().map_or("", |c| c.llm.provider.as_str()));
+            return anyhow::Error::new(error).context(format!("Cannot reach {} - {}", provider, hint));
+        }
+        error.into()
+    }
+
+    /// Collects a streamed response, passing each piece of text to the stream handler.
+    async fn read_stream(&self, provider: &str, mut response: reqwest::Response, format: StreamFormat) -> Result<String> {
+        let notify = |event: StreamEvent| {
+            if let Some(handler) = &self.stream_handler {
+                handler(event);
+            }
+        };
+        let push_line = |line: &str, text: &mut String| -> Result<()> {
+            if let Some(delta) = stream_delta(format, line)? {
+                text.push_str(&delta);
+                notify(StreamEvent::Delta(&delta));
+            }
+            Ok(())
+        };
+
+        notify(StreamEvent::Started);
+        let mut lines = LineBuffer::default();
+        let mut text = String::new();
+        let streamed = async {
+            let timeout = self.request_timeout().unwrap_or(Duration::MAX);
+            while let Some(chunk) = tokio::time::timeout(timeout, response.chunk()).await
+                .map_err(|_| self.timeout_error(provider))?
+                .map_err(|e| self.request_error(provider, e))?
+            {
+                for line in lines.push(&chunk) {
+                    push_line(&line, &mut text)?;
+                }
+            }
+            if let Some(line) = lines.finish() {
+                push_line(&line, &mut text)?;
+            }
+            Ok::<_, anyhow::Error>(())
+        }.await;
+        notify(StreamEvent::Finished);
+
+        streamed?;
+        if text.is_empty() {
+            return Err(anyhow::anyhow!("No response from LLM"));
+        }
+        Ok(text)
+    }
+
+    /// `response` read as `T` through the JSON repair layer, counting in the
+    /// usage and metrics how often it had to repair or salvage the JSON.
+    fn parse_llm_json<T: serde::de::DeserializeOwned>(&self, response: &str, items: &str) -> std::result::Result<T, serde_json::Error> {
+        let parsed = crate::json_repair::parse_llm_json(response, items);
+        let outcome = parsed.as_ref().map(|(_, repair)| repair.label()).unwrap_or("failed");
+        if let Some(metrics) = &self.metrics {
+            let provider = self.config.as_ref().map(|config| config.llm.provider.as_str()).unwrap_or_default();
+            metrics.record_llm_json(provider, outcome);
+        }
+        let mut usage = self.usage.lock().unwrap();
+        match &parsed {
+            Ok((_, JsonRepair::Repaired)) => usage.repaired_responses += 1,
+            Ok((_, JsonRepair::Salvaged)) => usage.salvaged_responses += 1,
+            _ => {}
+        }
+        drop(usage);
+        parsed.map(|(value, _)| value)
     }
 
     fn parse_ambiguities_response(&self, response: &str) -> Result<Vec<Ambiguity>> {
@@ -573,23 +1448,8 @@ impl Analyzer {
         // println!("🔍 Raw AI response for ambiguities:");
         // println!("{}", response);
         
-        // Try to extract JSON from response if it's wrapped in markdown
-        let json_str = if response.contains("```json") {
-            response.split("```json").nth(1)
-                .and_then(|s| s.split("```").next())
-                .unwrap_or(response)
-                .trim()
-        } else if response.contains("```") {
-            response.split("```").nth(1)
-                .and_then(|s| s.split("```").next())
-                .unwrap_or(response)
-                .trim()
-        } else {
-            response.trim()
-        };
-
-        let parsed: AmbiguityResponse = serde_json::from_str(json_str)
-            .map_err(|e| anyhow::anyhow!("Failed to parse LLM response for ambiguities: {}. Raw response: {}", e, json_str))?;
+        let parsed: AmbiguityResponse = self.parse_llm_json(response, "ambiguities")
+            .map_err(|e| anyhow::anyhow!("Failed to parse LLM response for ambiguities: {}. Raw response: {}", e, extract_json(response)))?;
 
         Ok(parsed.ambiguities.into_iter().map(|data| {
             let severity = match data.severity.as_str() {
@@ -604,6 +1464,9 @@ impl Analyzer {
                 reason: data.reason,
                 suggestions: data.suggestions,
                 severity,
+                rule_id: RULE_AI_AMBIGUITY.to_string(),
+                fingerprint: String::new(),
+                location: None,
             }
         }).collect())
     }
@@ -620,23 +1483,8 @@ impl Analyzer {
         // println!("🔍 Raw AI response for entities:");
         // println!("{}", response);
         
-        // Try to extract JSON from response if it's wrapped in markdown
-        let json_str = if response.contains("```json") {
-            response.split("```json").nth(1)
-                .and_then(|s| s.split("```").next())
-                .unwrap_or(response)
-                .trim()
-        } else if response.contains("```") {
-            response.split("```").nth(1)
-                .and_then(|s| s.split("```").next())
-                .unwrap_or(response)
-                .trim()
-        } else {
-            response.trim()
-        };
-
-        let parsed: EntityResponse = serde_json::from_str(json_str)
-            .map_err(|e| anyhow::anyhow!("Failed to parse LLM response for entities: {}. Raw response: {}", e, json_str))?;
+        let parsed: EntityResponse = self.parse_llm_json(response, "")
+            .map_err(|e| anyhow::anyhow!("Failed to parse LLM response for entities: {}. Raw response: {}", e, extract_json(response)))?;
 
         Ok(ExtractedEntities {
             actors: parsed.actors,
@@ -645,6 +1493,14 @@ impl Analyzer {
         })
     }
 
+    /// Built-in rule findings only, without the LLM. Cheap enough to run on
+    /// each answer of the authoring wizard.
+    pub fn detect_rule_findings(&self, text: &str) -> Vec<Ambiguity> {
+        let mut ambiguities = self.detect_ambiguities(text);
+        self.apply_rule_config(&mut ambiguities);
+        ambiguities
+    }
+
     fn detect_ambiguities(&self, text: &str) -> Vec<Ambiguity> {
         let mut ambiguities = Vec::new();
 
@@ -658,6 +1514,9 @@ impl Analyzer {
                         "Provide measurable criteria".to_string(),
                     ],
                     severity: AmbiguitySeverity::Medium,
+                    rule_id: RULE_VAGUE_TERM.to_string(),
+                    fingerprint: String::new(),
+                    location: Some(Self::location_at(text, mat.start())),
                 });
             }
         }
@@ -671,12 +1530,235 @@ impl Analyzer {
                     "Use active voice instead".to_string(),
                 ],
                 severity: AmbiguitySeverity::High,
+                rule_id: RULE_PASSIVE_VOICE.to_string(),
+                fingerprint: String::new(),
+                location: Some(Self::location_at(text, mat.start())),
             });
         }
 
+        for custom in &self.custom_rules {
+            let rule = &custom.rule;
+            for mat in custom.regex.find_iter(text) {
+                ambiguities.push(Ambiguity {
+                    text: mat.as_str().to_string(),
+                    reason: rule.reason.clone().unwrap_or_else(|| format!("Matches the custom rule '{}'", rule.id)),
+                    suggestions: rule.suggestions.clone(),
+                    severity: rule.severity,
+                    rule_id: rule.id.clone(),
+                    fingerprint: String::new(),
+                    location: Some(Self::location_at(text, mat.start())),
+                });
+            }
+        }
+
         ambiguities
     }
 
+    fn is_rule_enabled(&self, rule_id: &str) -> bool {
+        self.config.as_ref()
+            .is_none_or(|config| !config.analysis.disabled_rules.iter().any(|r| r == rule_id))
+    }
+
+    /// Drops findings of disabled rules and applies per-rule severity overrides
+    /// from the analysis config.
+    pub fn apply_rule_config(&self, ambiguities: &mut Vec<Ambiguity>) {
+        let Some(config) = &self.config else { return };
+
+        ambiguities.retain(|a| self.is_rule_enabled(&a.rule_id));
+        for ambiguity in ambiguities.iter_mut() {
+            if let Some(severity) = config.analysis.severity_overrides.get(&ambiguity.rule_id) {
+                ambiguity.severity = *severity;
+            }
+        }
+    }
+
+    fn location_at(text: &str, offset: usize) -> SourceLocation {
+        let before = &text[..offset];
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        SourceLocation {
+            file: None,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            offset,
+        }
+    }
+
+    /// Resolves a location for findings that don't carry one (e.g. from the
+    /// LLM) by searching for their text. A phrase quoted by several findings
+    /// goes to its next occurrence for each.
+    pub fn locate_findings(&self, text: &str, ambiguities: &mut [Ambiguity]) {
+        let mut taken = Vec::new();
+        for ambiguity in ambiguities.iter_mut() {
+            if ambiguity.location.is_some() || ambiguity.text.is_empty() {
+                continue;
+            }
+            let first = find_quote(text, &ambiguity.text, 0);
+            let mut span = first;
+            while let Some((_, end)) = span.filter(|span| taken.contains(span)) {
+                span = find_quote(text, &ambiguity.text, end);
+            }
+            if let Some(span) = span.or(first) {
+                taken.push(span);
+                ambiguity.location = Some(Self::location_at(text, span.0));
+            }
+        }
+    }
+
+    /// Merges findings that point at the same span of text (or, without a
+    /// location, at the same normalized text), keeping the highest severity
+    /// and the union of suggestions.
+    pub fn deduplicate_findings(&self, ambiguities: Vec<Ambiguity>) -> Vec<Ambiguity> {
+        let mut merged: Vec<Ambiguity> = Vec::new();
+
+        for ambiguity in ambiguities {
+            let existing = merged.iter_mut().find(|m| Self::same_finding(m, &ambiguity));
+            match existing {
+                Some(existing) => {
+                    let mut suggestions = std::mem::take(&mut existing.suggestions);
+                    for suggestion in &ambiguity.suggestions {
+                        if !suggestions.contains(suggestion) {
+                            suggestions.push(suggestion.clone());
+                        }
+                    }
+                    if ambiguity.severity > existing.severity {
+                        *existing = ambiguity;
+                    }
+                    existing.suggestions = suggestions;
+                }
+                None => merged.push(ambiguity),
+            }
+        }
+
+        merged
+    }
+
+    /// Whether `a` and `b` report the same problem: the same text, normalized,
+    /// found by the same rule or one of the same category, and at the same
+    /// place when both are located. Other findings on overlapping text stay apart.
+    fn same_finding(a: &Ambiguity, b: &Ambiguity) -> bool {
+        let same_text = Self::normalize_finding_text(&a.text) == Self::normalize_finding_text(&b.text);
+        let same_kind = a.rule_id == b.rule_id || rule_category(&a.rule_id) == rule_category(&b.rule_id);
+        let same_place = match (&a.location, &b.location) {
+            (Some(la), Some(lb)) => la.offset < lb.offset + b.text.len().max(1) && lb.offset < la.offset + a.text.len().max(1),
+            _ => true,
+        };
+        same_text && same_kind && same_place
+    }
+
+    /// Assigns a stable fingerprint to each finding. The fingerprint hashes the rule id, the normalized finding text
+    /// and a location bucket made of the nearest section heading plus the
+    /// occurrence index within that section, so it survives edits elsewhere in
+    /// the document. Headings are markdown headings and numbered titles such
+    /// as "2.1 Search"; a numbered requirement ("1. Search shall ...") is not one.
+    pub fn assign_fingerprints(&self, text: &str, ambiguities: &mut [Ambiguity]) {
+        let markdown_heading = Regex::new(r"^\s*#{1,6}\s+\S").unwrap();
+        let numbered_line = Regex::new(r"^\s*\d+(\.\d+)*[.)]?\s+(\S.*)$").unwrap();
+        let statement = Regex::new(r"(?i)\b(shall|must|should|will|can|may|could|would|is|are|be|needs?|wants?)\b|[.!?:;]$").unwrap();
+        let is_heading = |line: &&str| {
+            markdown_heading.is_match(line)
+                || numbered_line.captures(line).is_some_and(|c| !statement.is_match(c[2].trim()))
+        };
+        let mut seen: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+
+        for ambiguity in ambiguities.iter_mut() {
+            let section = ambiguity.location.as_ref()
+                .and_then(|loc| {
+                    text.lines()
+                        .take(loc.line)
+                        .filter(is_heading)
+                        .last()
+                })
+                .map(Self::normalize_finding_text)
+                .unwrap_or_default();
+
+            let key = format!("{}|{}|{}", ambiguity.rule_id, Self::normalize_finding_text(&ambiguity.text), section);
+            let occurrence = seen.entry(key.clone()).or_insert(0);
+            let digest = Sha256::digest(format!("{}|{}", key, occurrence).as_bytes());
+            *occurrence += 1;
+
+            ambiguity.fingerprint = format!("{:x}", digest)[..32].to_string();
+        }
+    }
+
+    /// Splits findings into (active, suppressed) according to the inline
+    /// suppression markers in the text. Findings that fall inside a marker
+    /// itself are dropped.
+    pub fn apply_suppressions(&self, text: &str, ambiguities: Vec<Ambiguity>) -> (Vec<Ambiguity>, Vec<Ambiguity>) {
+        let (suppressions, marker_spans) = Self::parse_suppressions(text);
+        let mut active = Vec::new();
+        let mut suppressed = Vec::new();
+
+        for ambiguity in ambiguities {
+            let Some(location) = ambiguity.location.clone() else {
+                active.push(ambiguity);
+                continue;
+            };
+
+            if marker_spans.iter().any(|(start, end)| location.offset >= *start && location.offset < *end) {
+                continue;
+            }
+
+            let is_suppressed = suppressions.iter().any(|s| {
+                s.line == location.line
+                    && s.rule_id.as_ref().is_none_or(|rule| *rule == ambiguity.rule_id)
+                    && s.text.as_ref().is_none_or(|t| t.eq_ignore_ascii_case(ambiguity.text.trim()))
+            });
+
+            if is_suppressed {
+                suppressed.push(ambiguity);
+            } else {
+                active.push(ambiguity);
+            }
+        }
+
+        (active, suppressed)
+    }
+
+    fn parse_suppressions(text: &str) -> (Vec<Suppression>, Vec<(usize, usize)>) {
+        let marker = Regex::new(r#"<!--\s*prism:ignore(?:\s+([\w-]+))?(?:\s+"([^"]*)")?\s*-->|\[prism-ok\]"#).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        let mut suppressions = Vec::new();
+        let mut marker_spans = Vec::new();
+
+        for mat in marker.captures_iter(text) {
+            let whole = mat.get(0).unwrap();
+            marker_spans.push((whole.start(), whole.end()));
+
+            let line_index = text[..whole.start()].matches('\n').count();
+            let rest_of_line = lines.get(line_index)
+                .map(|line| marker.replace_all(line, "").trim().to_string())
+                .unwrap_or_default();
+
+            // A marker on a line of its own applies to the next non-empty line
+            let target = if rest_of_line.is_empty() {
+                lines.iter()
+                    .enumerate()
+                    .skip(line_index + 1)
+                    .find(|(_, line)| !line.trim().is_empty())
+                    .map(|(i, _)| i)
+                    .unwrap_or(line_index)
+            } else {
+                line_index
+            };
+
+            suppressions.push(Suppression {
+                line: target + 1,
+                rule_id: mat.get(1).map(|m| m.as_str().to_string()),
+                text: mat.get(2).map(|m| m.as_str().trim().to_string()),
+            });
+        }
+
+        (suppressions, marker_spans)
+    }
+
+    fn normalize_finding_text(value: &str) -> String {
+        value.split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase()
+    }
+
     fn extract_entities(&self, text: &str) -> ExtractedEntities {
         let actor_patterns = vec![
             Regex::new(r"\b(user|admin|administrator|customer|client|system|service)\b").unwrap(),
@@ -735,7 +1817,7 @@ impl Analyzer {
         }
     }
 
-    pub fn generate_uml_use_case(&self, entities: &ExtractedEntities) -> String {
+    pub fn generate_uml_use_case(&self, text: &str, entities: &ExtractedEntities) -> String {
         let mut uml = String::from("@startuml\n");
         uml.push_str("!theme aws-orange\n");
         uml.push_str("title Requirements Use Case Diagram\n\n");
@@ -767,16 +1849,10 @@ impl Analyzer {
 
         uml.push('\n');
 
-        // Create more intelligent actor-action relationships
-        for actor in &entities.actors {
-            let actor_id = actor.replace(" ", "_").replace("-", "_");
-            for (i, action) in entities.actions.iter().enumerate() {
-                // Smart relationship mapping based on common patterns
-                let should_connect = self.should_actor_connect_to_action(actor, action);
-                if should_connect {
-                    uml.push_str(&format!("{} --> UC{}\n", actor_id, i + 1));
-                }
-            }
+        // Connect actors only to the actions they share a sentence with
+        for (actor, action) in self.use_case_links(text, entities) {
+            let actor_id = entities.actors[actor].replace(" ", "_").replace("-", "_");
+            uml.push_str(&format!("{} --> UC{}\n", actor_id, action + 1));
         }
 
         // Add system boundary if objects exist
@@ -827,6 +1903,68 @@ impl Analyzer {
         uml
     }
 
+    /// (actor index, action index) pairs of the use case diagrams: actors are
+    /// linked to the actions they share a sentence with.
+    fn use_case_links(&self, text: &str, entities: &ExtractedEntities) -> Vec<(usize, usize)> {
+        let links = self.associate_actors_with_actions(text, entities);
+        let mut pairs = Vec::new();
+        for (a, actor) in entities.actors.iter().enumerate() {
+            let actor_key = Self::actor_label(actor).unwrap_or_default();
+            for (i, action) in entities.actions.iter().enumerate() {
+                let action_key = Self::action_label(action).unwrap_or_default();
+                let linked = links.iter().any(|l| l.actor == actor_key && l.action == action_key);
+                // With a single actor there is nobody else the action could belong to
+                let sole_actor = entities.actors.len() == 1
+                    && !links.iter().any(|l| l.action == action_key);
+                if linked || sole_actor {
+                    pairs.push((a, i));
+                }
+            }
+        }
+        pairs
+    }
+
+    /// Mermaid flowchart of the use cases, for renderers without PlantUML.
+    pub fn generate_mermaid_use_case(&self, text: &str, entities: &ExtractedEntities) -> String {
+        let label = |s: &str| s.replace('"', "'");
+        let mut diagram = String::from("flowchart LR\n");
+
+        for (i, actor) in entities.actors.iter().enumerate() {
+            diagram.push_str(&format!("    A{}([\"👤 {}\"])\n", i + 1, label(actor)));
+        }
+        if !entities.actions.is_empty() {
+            diagram.push_str("    subgraph System\n");
+            for (i, action) in entities.actions.iter().enumerate() {
+                diagram.push_str(&format!("        UC{}(\"{}\")\n", i + 1, label(action)));
+            }
+            diagram.push_str("    end\n");
+        }
+        for (actor, action) in self.use_case_links(text, entities) {
+            diagram.push_str(&format!("    A{} --> UC{}\n", actor + 1, action + 1));
+        }
+
+        diagram
+    }
+
+    /// Mermaid sequence diagram: each linked actor asks the system to perform
+    /// its actions on the first object.
+    pub fn generate_mermaid_sequence(&self, text: &str, entities: &ExtractedEntities) -> String {
+        let label = |s: &str| s.replace([';', '#'], " ");
+        let mut diagram = String::from("sequenceDiagram\n");
+
+        for (i, actor) in entities.actors.iter().enumerate() {
+            diagram.push_str(&format!("    actor A{} as {}\n", i + 1, label(actor)));
+        }
+        diagram.push_str("    participant System\n");
+        let object = entities.objects.first().map(|o| format!(" {}", label(o))).unwrap_or_default();
+        for (actor, action) in self.use_case_links(text, entities) {
+            diagram.push_str(&format!("    A{}->>System: {}{}\n", actor + 1, label(&entities.actions[action]), object));
+            diagram.push_str(&format!("    System-->>A{}: result\n", actor + 1));
+        }
+
+        diagram
+    }
+
     // Enhanced UML generation with sequence diagrams
     pub fn generate_uml_sequence(&self, entities: &ExtractedEntities) -> String {
         let mut uml = String::from("@startuml\n");
@@ -949,40 +2087,6 @@ impl Analyzer {
         uml
     }
 
-    // Helper method to determine if an actor should connect to an action
-    fn should_actor_connect_to_action(&self, actor: &str, action: &str) -> bool {
-        let actor_lower = actor.to_lowercase();
-        let action_lower = action.to_lowercase();
-
-        // Admin actors can do most actions
-        if actor_lower.contains("admin") || actor_lower.contains("administrator") {
-            return true;
-        }
-
-        // User actors typically do user-facing actions
-        if actor_lower.contains("user") || actor_lower.contains("customer") || actor_lower.contains("client") {
-            return action_lower.contains("create") 
-                || action_lower.contains("update") 
-                || action_lower.contains("view")
-                || action_lower.contains("login")
-                || action_lower.contains("register")
-                || action_lower.contains("submit")
-                || action_lower.contains("request");
-        }
-
-        // System actors do system-level actions
-        if actor_lower.contains("system") || actor_lower.contains("service") {
-            return action_lower.contains("process")
-                || action_lower.contains("validate")
-                || action_lower.contains("send")
-                || action_lower.contains("receive")
-                || action_lower.contains("generate");
-        }
-
-        // Default: connect if there's only one actor or few actors
-        true
-    }
-
     // Generate UML class diagram
     pub fn generate_uml_class_diagram(&self, entities: &ExtractedEntities) -> String {
         let mut uml = String::from("@startuml\n");
@@ -1490,6 +2594,131 @@ impl Analyzer {
         result
     }
 
+    /// Links actors to the actions they appear alongside, sentence by sentence.
+    /// Sentences written in the first person ("I", "we") are attributed to the
+    /// actor of the surrounding user story ("As a ..."), if there is one.
+    pub fn associate_actors_with_actions(&self, text: &str, entities: &ExtractedEntities) -> Vec<ActorActionLink> {
+        let story_start = Regex::new(r"(?i)\bas an?\s+([\w-]+)").unwrap();
+        let first_person = Regex::new(r"(?i)\b(i|we|my|our)\b").unwrap();
+
+        let actors = Self::with_mention_patterns(self.unique_labels(&entities.actors, Self::actor_label));
+        let actions = Self::with_mention_patterns(self.unique_labels(&entities.actions, Self::action_label));
+
+        let mut links: Vec<ActorActionLink> = Vec::new();
+        // "I" and "we" are the actor of the story they are in: the latest "As a ..." before them, up to the next heading
+        let mut story_actor: Option<String> = None;
+        for sentence in Self::split_sentences(text) {
+            if sentence.starts_with('#') {
+                story_actor = None;
+            }
+            if let Some(actor) = story_start.captures(sentence) {
+                story_actor = Some(actor[1].to_lowercase());
+            }
+            let sentence_lower = sentence.to_lowercase();
+            let mut sentence_actors: Vec<&String> = actors.iter()
+                .filter(|(_, pattern)| pattern.is_match(&sentence_lower))
+                .map(|(actor, _)| actor)
+                .collect();
+
+            if sentence_actors.is_empty() && first_person.is_match(&sentence_lower) {
+                if let Some(story_actor) = &story_actor {
+                    sentence_actors.extend(actors.iter().map(|(actor, _)| actor).filter(|a| *a == story_actor));
+                }
+            }
+
+            for (action, _) in actions.iter().filter(|(_, pattern)| pattern.is_match(&sentence_lower)) {
+                for actor in &sentence_actors {
+                    if !links.iter().any(|l| &l.actor == *actor && &l.action == action) {
+                        links.push(ActorActionLink {
+                            actor: (*actor).clone(),
+                            action: action.clone(),
+                            sentence: sentence.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+
+        links
+    }
+
+    /// Reviewers for the document from the categories of `result`, mapped
+    /// with the configured `review.reviewers` (the defaults without a config).
+    pub fn suggest_reviewers(&self, text: &str, result: &AnalysisResult) -> Vec<ReviewerSuggestion> {
+        match &self.config {
+            Some(config) => suggest_reviewers(text, result, &config.review.reviewers),
+            None => suggest_reviewers(text, result, &crate::config::ReviewConfig::default().reviewers),
+        }
+    }
+
+    pub fn generate_permission_matrix(&self, text: &str, entities: &ExtractedEntities) -> PermissionMatrix {
+        let actors = self.unique_labels(&entities.actors, Self::actor_label);
+        let actions = self.unique_labels(&entities.actions, Self::action_label);
+
+        let grants: Vec<PermissionGrant> = self.associate_actors_with_actions(text, entities)
+            .into_iter()
+            .map(|link| PermissionGrant {
+                actor: link.actor,
+                action: link.action,
+                evidence: link.sentence,
+            })
+            .collect();
+
+        let unassigned_actions = actions.iter()
+            .filter(|action| !grants.iter().any(|g| &g.action == *action))
+            .cloned()
+            .collect();
+
+        PermissionMatrix {
+            actors,
+            actions,
+            grants,
+            unassigned_actions,
+        }
+    }
+
+    // Helper methods for sentence-level entity association
+    fn split_sentences(text: &str) -> Vec<&str> {
+        text.split(['.', '!', '?', ';', '\n'])
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+
+    /// Each label with the pattern that finds it as a whole word in a
+    /// lowercased sentence, compiled once for all sentences.
+    fn with_mention_patterns(labels: Vec<String>) -> Vec<(String, Regex)> {
+        labels.into_iter()
+            .filter_map(|label| Regex::new(&format!(r"\b{}\b", regex::escape(&label))).ok().map(|pattern| (label, pattern)))
+            .collect()
+    }
+
+    fn unique_labels(&self, values: &[String], label: fn(&str) -> Option<String>) -> Vec<String> {
+        let mut labels: Vec<String> = values.iter().filter_map(|v| label(v)).collect();
+        labels.sort();
+        labels.dedup();
+        labels
+    }
+
+    pub(crate) fn actor_label(actor: &str) -> Option<String> {
+        let lower = actor.trim().to_lowercase();
+        let label = lower.strip_prefix("as an ")
+            .or_else(|| lower.strip_prefix("as a "))
+            .unwrap_or(&lower)
+            .trim();
+        if label.is_empty() { None } else { Some(label.to_string()) }
+    }
+
+    pub(crate) fn action_label(action: &str) -> Option<String> {
+        let lower = action.trim().to_lowercase();
+        let label = ["want to ", "need to ", "should ", "must ", "will ", "can "].iter()
+            .find_map(|prefix| lower.strip_prefix(prefix))
+            .unwrap_or(&lower)
+            .trim();
+        let auxiliaries = ["be", "been", "have", "has", "is", "are", "not", "also", "the", "a", "an"];
+        if label.is_empty() || auxiliaries.contains(&label) { None } else { Some(label.to_string()) }
+    }
+
     pub fn generate_test_cases(&self, entities: &ExtractedEntities) -> TestCases {
         let mut happy_path = Vec::new();
         let mut negative_cases = Vec::new();
@@ -1510,6 +2739,157 @@ impl Analyzer {
         }
     }
 
+    /// A Gherkin feature for the requirements: the user story as the feature's
+    /// narrative, a scenario per acceptance criterion, kept as written when it
+    /// is already Given/When/Then, and a scenario per actor-action pair that no
+    /// criterion covers.
+    pub fn generate_gherkin(&self, text: &str, entities: &ExtractedEntities) -> String {
+        let story = Regex::new(r"(?i)\bas an?\s+([^,\n]+?),?\s+i\s+(?:want|need)\s+(?:to\s+)?([^,.\n]+?)(?:,?\s+so\s+that\s+([^.\n]+))?(?:[.\n]|$)").unwrap()
+            .captures(text)
+            .map(|c| (c[1].trim().to_lowercase(), c[2].trim().to_string(), c.get(3).map(|m| m.as_str().trim().to_string())));
+        let actors = Self::with_mention_patterns(self.unique_labels(&entities.actors, Self::actor_label));
+        let actions = Self::with_mention_patterns(self.unique_labels(&entities.actions, Self::action_label));
+        let default_actor = story.as_ref().map(|(actor, _, _)| actor.clone())
+            .or_else(|| actors.first().map(|(actor, _)| actor.clone()))
+            .unwrap_or_else(|| "user".to_string());
+
+        let title = text.lines()
+            .find_map(|line| line.trim().strip_prefix('#').map(|h| h.trim_start_matches('#').trim().to_string()))
+            .filter(|h| !h.is_empty())
+            .or_else(|| story.as_ref().map(|(_, goal, _)| capitalize(goal)))
+            .or_else(|| Self::split_sentences(text).first().map(|s| s.chars().take(80).collect()))
+            .unwrap_or_else(|| "Requirements".to_string());
+
+        let mut feature = format!("Feature: {}\n", title);
+        if let Some((actor, goal, benefit)) = &story {
+            feature.push_str(&format!("  As {} {}\n  I want to {}\n", article(actor), actor, goal));
+            if let Some(benefit) = benefit {
+                feature.push_str(&format!("  So that {}\n", benefit));
+            }
+        }
+
+        let mut scenarios = Vec::new();
+        let criteria = Self::acceptance_criteria(text);
+        for (i, criterion) in criteria.iter().enumerate() {
+            let lower = criterion.to_lowercase();
+            if lower.starts_with("given ") || lower.starts_with("when ") {
+                let steps = Self::gherkin_steps(criterion).into_iter()
+                    .map(|(keyword, step)| format!("    {} {}", keyword, step))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                scenarios.push(format!("  Scenario: Acceptance criterion {}\n{}", i + 1, steps));
+                continue;
+            }
+
+            let actor = actors.iter().find(|(_, pattern)| pattern.is_match(&lower)).map(|(actor, _)| actor).unwrap_or(&default_actor);
+            let mut steps = format!("    Given {} {}\n", article(actor), actor);
+            // The criterion is the outcome of the story's goal; other actors' criteria stand alone
+            if let Some((_, goal, _)) = story.as_ref().filter(|(story_actor, _, _)| story_actor == actor) {
+                steps.push_str(&format!("    When the {} tries to {}\n", actor, third_person(goal)));
+            }
+            steps.push_str(&format!("    Then {}", uncapitalize(criterion.trim_end_matches('.'))));
+            scenarios.push(format!("  Scenario: {}\n{}", criterion.trim_end_matches('.'), steps));
+        }
+
+        let criteria_lower: Vec<String> = criteria.iter().map(|c| c.to_lowercase()).collect();
+        let covered = |pattern: &Regex| criteria_lower.iter().any(|c| pattern.is_match(c));
+        let links = self.associate_actors_with_actions(text, entities);
+        for (action, _) in actions.iter().filter(|(_, pattern)| !covered(pattern)) {
+            // The story sentence is the feature itself rather than a scenario
+            let link_actors: Vec<&ActorActionLink> = links.iter()
+                .filter(|l| &l.action == action && story.as_ref().is_none_or(|(_, goal, _)| !l.sentence.contains(goal.as_str())))
+                .collect();
+            if links.iter().any(|l| &l.action == action) && link_actors.is_empty() {
+                continue;
+            }
+            let pairs: Vec<(&str, String)> = if link_actors.is_empty() {
+                // Actions nobody is named for still get a scenario, with the story's actor
+                vec![(default_actor.as_str(), action.clone())]
+            } else {
+                link_actors.iter().map(|l| (l.actor.as_str(), Self::action_phrase(&l.sentence, action))).collect()
+            };
+            for (actor, phrase) in pairs {
+                scenarios.push(format!(
+                    "  Scenario: {} can {}\n    Given {} {}\n    When the {} tries to {}\n    Then the {} is able to {}",
+                    capitalize(actor), phrase, article(actor), actor, actor, phrase, actor, phrase
+                ));
+            }
+        }
+
+        if scenarios.is_empty() {
+            feature.push_str("\n  # No acceptance criteria, actors or actions were found - add scenarios here\n");
+        }
+        for scenario in scenarios {
+            feature.push('\n');
+            feature.push_str(&scenario);
+            feature.push('\n');
+        }
+        feature
+    }
+
+    /// The action with the rest of its sentence, e.g. "view audit logs".
+    fn action_phrase(sentence: &str, action: &str) -> String {
+        Regex::new(&format!(r"(?i)\b{}\b.*", regex::escape(action))).ok()
+            .and_then(|re| re.find(sentence).map(|m| m.as_str().trim_end_matches('.').to_lowercase()))
+            .unwrap_or_else(|| action.to_string())
+    }
+
+    /// List items under an "Acceptance Criteria" heading. Given/When/Then
+    /// lines that continue an item are kept with it.
+    fn acceptance_criteria(text: &str) -> Vec<String> {
+        let list_item = Regex::new(r"^(?:[-*+]|\d+[.)])\s+(.+)$").unwrap();
+        let continuation = Regex::new(r"(?i)^(?:given|when|then|and|but)\s").unwrap();
+        let mut criteria: Vec<String> = Vec::new();
+        let mut in_section = false;
+
+        for line in text.lines().map(|l| l.trim()) {
+            let heading = line.trim_start_matches('#').trim_matches(|c: char| c == '*' || c == ':' || c.is_whitespace()).to_lowercase();
+            if heading.starts_with("acceptance criteria") {
+                in_section = true;
+                continue;
+            }
+            if !in_section || line.is_empty() {
+                continue;
+            }
+            if let Some(item) = list_item.captures(line) {
+                criteria.push(item[1].trim().to_string());
+            } else if continuation.is_match(line) && !criteria.is_empty() {
+                let last = criteria.last_mut().unwrap();
+                last.push('\n');
+                last.push_str(line);
+            } else if line.starts_with('#') || !criteria.is_empty() {
+                // The next section, or prose after the list
+                in_section = false;
+            }
+        }
+        criteria
+    }
+
+    /// Splits "Given ..., when ... then ..." into keyword and step pairs.
+    fn gherkin_steps(criterion: &str) -> Vec<(String, String)> {
+        let keyword = Regex::new(r"(?i)(?:^|[,;\n]\s*|\s)(given|when|then|and|but)\s+").unwrap();
+        let mut steps: Vec<(String, String)> = Vec::new();
+        let mut last_end = 0;
+        let mut current: Option<String> = None;
+        for captures in keyword.captures_iter(criterion) {
+            let (whole, word) = (captures.get(0).unwrap(), captures.get(1).unwrap());
+            // "and"/"but" only start a step at a line or clause break, not inside a sentence
+            let is_joiner = matches!(word.as_str().to_lowercase().as_str(), "and" | "but");
+            if is_joiner && whole.start() > 0 && !whole.as_str().starts_with([',', ';', '\n']) {
+                continue;
+            }
+            if let Some(keyword) = current.take() {
+                steps.push((keyword, criterion[last_end..whole.start()].trim().trim_end_matches([',', ';']).to_string()));
+            }
+            current = Some(capitalize(&word.as_str().to_lowercase()));
+            last_end = whole.end();
+        }
+        if let Some(keyword) = current {
+            steps.push((keyword, criterion[last_end..].trim().trim_end_matches('.').to_string()));
+        }
+        steps
+    }
+
     pub async fn generate_improved_requirements(&self, original_text: &str, ambiguities: &[Ambiguity]) -> Result<String> {
         if let Some(config) = &self.config {
             if config.llm.api_key.is_some() {
@@ -1537,32 +2917,120 @@ impl Analyzer {
             .collect::<Vec<_>>()
             .join("\n\n");
 
-        let prompt = format!(
-            "You are a requirements improvement specialist. Please rewrite the following requirements to fix all identified ambiguities and make them clearer, more specific, and more actionable.
+        let prompt = self.prompts.render(PromptTask::Improvement, &[("text", original_text), ("findings", &ambiguities_summary)]);
+
+        let response = self.call_llm(&prompt).await?;
+        Ok(response.trim().to_string())
+    }
+
+    /// Suggests a rewrite of the sentence behind each finding, leaving the rest
+    /// of the document untouched. Uses the LLM when configured and falls back
+    /// to placeholder-based rewrites otherwise.
+    pub async fn generate_rewrite_suggestions(&self, text: &str, ambiguities: &[Ambiguity]) -> Result<Vec<RewriteSuggestion>> {
+        let mut suggestions: Vec<RewriteSuggestion> = ambiguities.iter()
+            .filter_map(|ambiguity| {
+                let (original, relative) = Self::sentence_containing(text, ambiguity)?;
+                let proposed = format!(
+                    "{}{}{}",
+                    &original[..relative],
+                    Self::builtin_rewrite(ambiguity),
+                    &original[relative + ambiguity.text.len()..]
+                );
+                Some(RewriteSuggestion {
+                    finding: ambiguity.text.clone(),
+                    rule_id: ambiguity.rule_id.clone(),
+                    line: ambiguity.location.as_ref().map(|l| l.line),
+                    original: original.to_string(),
+                    proposed,
+                })
+            })
+            .collect();
+
+        if let Some(config) = &self.config {
+            if config.llm.api_key.is_some() && !suggestions.is_empty() {
+                if let Ok(rewrites) = self.rewrite_sentences_with_llm(&suggestions).await {
+                    for (index, proposed) in rewrites {
+                        if let Some(suggestion) = suggestions.get_mut(index) {
+                            suggestion.proposed = proposed;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(suggestions)
+    }
 
-ORIGINAL REQUIREMENTS:
-{}
+    /// The sentence that contains the finding, and the finding's byte offset
+    /// within that sentence.
+    fn sentence_containing<'a>(text: &'a str, ambiguity: &Ambiguity) -> Option<(&'a str, usize)> {
+        let offset = match &ambiguity.location {
+            Some(location) if text.get(location.offset..).is_some_and(|rest| rest.starts_with(&ambiguity.text)) => location.offset,
+            _ => text.find(&ambiguity.text)?,
+        };
+        let boundaries = ['.', '!', '?', ';', '\n'];
+        let start = text[..offset].rfind(boundaries).map(|i| i + 1).unwrap_or(0);
+        let end = text[offset + ambiguity.text.len()..].find(boundaries)
+            .map(|i| offset + ambiguity.text.len() + i + 1)
+            .unwrap_or(text.len());
+
+        let sentence = text[start..end].trim_end();
+        let leading = sentence.len() - sentence.trim_start().len();
+        Some((sentence.trim_start(), offset - start - leading))
+    }
+
+    /// A measurable phrase for a vague term, with placeholders for the numbers.
+    pub(crate) fn vague_term_replacement(term: &str) -> String {
+        match term.to_lowercase().as_str() {
+            "fast" | "quick" | "quickly" => "within [N] seconds".to_string(),
+            "slow" => "in more than [N] seconds".to_string(),
+            "easy" | "easily" => "in at most [N] steps".to_string(),
+            "hard" => "in more than [N] steps".to_string(),
+            "user-friendly" => "usable by [persona] without training".to_string(),
+            "robust" => "recovering from [failure] within [N] seconds".to_string(),
+            "scalable" => "supporting [N] concurrent users".to_string(),
+            "efficient" | "efficiently" => "using at most [N] [resource]".to_string(),
+            "many" | "few" | "some" | "several" | "various" | "multiple" => "[N]".to_string(),
+            _ => "[measurable criterion]".to_string(),
+        }
+    }
 
-IDENTIFIED ISSUES TO FIX:
-{}
+    /// Replacement for the finding text, with placeholders for the missing detail.
+    fn builtin_rewrite(ambiguity: &Ambiguity) -> String {
+        match ambiguity.rule_id.as_str() {
+            RULE_VAGUE_TERM => Self::vague_term_replacement(&ambiguity.text),
+            RULE_PASSIVE_VOICE => format!("{} by [responsible actor]", ambiguity.text),
+            _ => match ambiguity.suggestions.first() {
+                Some(suggestion) => format!("{} [{}]", ambiguity.text, suggestion),
+                None => format!("{} [clarify]", ambiguity.text),
+            },
+        }
+    }
 
-INSTRUCTIONS:
-1. Rewrite the requirements to address all identified issues
-2. Make vague terms specific and measurable
-3. Replace passive voice with active voice
-4. Add missing details and clarifications
-5. Ensure requirements are testable and implementable
-6. Maintain the original intent and scope
-7. Use clear, professional language
-8. Keep the same overall structure and format
+    /// Asks the LLM for a rewrite of each sentence, returning (index, sentence) pairs.
+    async fn rewrite_sentences_with_llm(&self, suggestions: &[RewriteSuggestion]) -> Result<Vec<(usize, String)>> {
+        let findings = suggestions.iter().enumerate()
+            .map(|(i, s)| format!("{}. Sentence: \"{}\"\n   Problem: '{}'", i, s.original, s.finding))
+            .collect::<Vec<_>>()
+            .join("\n");
 
-Please provide ONLY the improved requirements text, without explanations or comments.",
-            original_text,
-            ambiguities_summary
-        );
+        let prompt = self.prompts.render(PromptTask::Rewrites, &[("sentences", &findings)]);
+
+        #[derive(Deserialize)]
+        struct RewritesResponse {
+            rewrites: Vec<RewriteData>,
+        }
+
+        #[derive(Deserialize)]
+        struct RewriteData {
+            index: usize,
+            proposed: String,
+        }
 
         let response = self.call_llm(&prompt).await?;
-        Ok(response.trim().to_string())
+        let parsed: RewritesResponse = self.parse_llm_json(&response, "rewrites")
+            .map_err(|e| anyhow::anyhow!("Failed to parse rewrites response: {}. Raw: {}", e, extract_json(&response)))?;
+        Ok(parsed.rewrites.into_iter().map(|r| (r.index, r.proposed)).collect())
     }
 
     pub async fn analyze_completeness(&self, text: &str, entities: &ExtractedEntities) -> Result<CompletenessAnalysis> {
@@ -1646,29 +3114,7 @@ Please provide ONLY the improved requirements text, without explanations or comm
     }
 
     async fn analyze_completeness_with_llm(&self, text: &str, entities: &ExtractedEntities) -> Result<Vec<Gap>> {
-        let prompt = format!(
-            "Analyze the following requirement for completeness and identify gaps. Consider missing actors, undefined success criteria, missing non-functional requirements, and other completeness issues.
-
-Requirement: {}
-
-Identified entities:
-- Actors: {:?}
-- Actions: {:?}  
-- Objects: {:?}
-
-Please identify gaps and provide suggestions in the following JSON format:
-{{
-    \"gaps\": [
-        {{
-            \"category\": \"category name\",
-            \"description\": \"what is missing\",
-            \"suggestions\": [\"suggestion 1\", \"suggestion 2\"],
-            \"priority\": \"Critical|High|Medium|Low\"
-        }}
-    ]
-}}",
-            text, entities.actors, entities.actions, entities.objects
-        );
+        let prompt = self.prompts.render(PromptTask::Completeness, &entity_values(text, entities));
 
         let response = self.call_llm(&prompt).await?;
         self.parse_gaps_response(&response)
@@ -1688,17 +3134,8 @@ Please identify gaps and provide suggestions in the following JSON format:
             priority: String,
         }
 
-        let json_str = if response.contains("```json") {
-            response.split("```json").nth(1)
-                .and_then(|s| s.split("```").next())
-                .unwrap_or(response)
-                .trim()
-        } else {
-            response.trim()
-        };
-
-        let parsed: GapsResponse = serde_json::from_str(json_str)
-            .map_err(|e| anyhow::anyhow!("Failed to parse gaps response: {}. Raw: {}", e, json_str))?;
+        let parsed: GapsResponse = self.parse_llm_json(response, "gaps")
+            .map_err(|e| anyhow::anyhow!("Failed to parse gaps response: {}. Raw: {}", e, extract_json(response)))?;
 
         Ok(parsed.gaps.into_iter().map(|data| {
             let priority = match data.priority.as_str() {
@@ -1777,7 +3214,7 @@ Please identify gaps and provide suggestions in the following JSON format:
         }
     }
 
-    fn validate_user_story_component(&self, component: &str, component_type: &str) -> ValidationResult {
+    pub fn validate_user_story_component(&self, component: &str, component_type: &str) -> ValidationResult {
         let mut issues = Vec::new();
         let mut suggestions = Vec::new();
         let mut score: f32 = 100.0;
@@ -1961,30 +3398,7 @@ Please identify gaps and provide suggestions in the following JSON format:
     }
 
     async fn generate_nfrs_with_llm(&self, text: &str, entities: &ExtractedEntities) -> Result<Vec<NonFunctionalRequirement>> {
-        let prompt = format!(
-            "Based on the following functional requirement, generate relevant non-functional requirements (NFRs) for performance, security, usability, reliability, scalability, maintainability, compatibility, and accessibility.
-
-Functional Requirement: {}
-
-Identified entities:
-- Actors: {:?}
-- Actions: {:?}
-- Objects: {:?}
-
-Generate NFRs in the following JSON format:
-{{
-    \"nfrs\": [
-        {{
-            \"category\": \"Performance|Security|Usability|Reliability|Scalability|Maintainability|Compatibility|Accessibility\",
-            \"requirement\": \"specific NFR statement\",
-            \"rationale\": \"why this NFR is needed\",
-            \"acceptance_criteria\": [\"criterion 1\", \"criterion 2\"],
-            \"priority\": \"MustHave|ShouldHave|CouldHave|WontHave\"
-        }}
-    ]
-}}",
-            text, entities.actors, entities.actions, entities.objects
-        );
+        let prompt = self.prompts.render(PromptTask::Nfrs, &entity_values(text, entities));
 
         let response = self.call_llm(&prompt).await?;
         self.parse_nfr_response(&response)
@@ -2005,17 +3419,8 @@ Generate NFRs in the following JSON format:
             priority: String,
         }
 
-        let json_str = if response.contains("```json") {
-            response.split("```json").nth(1)
-                .and_then(|s| s.split("```").next())
-                .unwrap_or(response)
-                .trim()
-        } else {
-            response.trim()
-        };
-
-        let parsed: NfrResponse = serde_json::from_str(json_str)
-            .map_err(|e| anyhow::anyhow!("Failed to parse NFR response: {}. Raw: {}", e, json_str))?;
+        let parsed: NfrResponse = self.parse_llm_json(response, "nfrs")
+            .map_err(|e| anyhow::anyhow!("Failed to parse NFR response: {}. Raw: {}", e, extract_json(response)))?;
 
         Ok(parsed.nfrs.into_iter().map(|data| {
             let category = match data.category.as_str() {
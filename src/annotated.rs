@@ -0,0 +1,75 @@
+//! Annotated source: the analyzed text reprinted with line numbers, a
+//! severity gutter and compiler-style markers under each finding, so
+//! authors fix findings where they are instead of searching for them.
+
+use crate::analyzer::{Ambiguity, AmbiguitySeverity, AnalysisResult};
+
+/// Gutter letter of a severity.
+fn gutter_mark(severity: &AmbiguitySeverity) -> char {
+    match severity {
+        AmbiguitySeverity::Critical => 'C',
+        AmbiguitySeverity::High => 'H',
+        AmbiguitySeverity::Medium => 'M',
+        AmbiguitySeverity::Low => 'L',
+    }
+}
+
+/// `input_text` with every line numbered, the highest severity of its
+/// findings in the gutter and a `^^^` marker with the reason under each
+/// finding. Findings without a location are listed after the text.
+pub fn format_annotated_source(result: &AnalysisResult, input_text: &str, source_name: Option<&str>) -> String {
+    let mut output = String::new();
+    output.push_str(&format!("ANNOTATED REQUIREMENTS: {}\n", source_name.unwrap_or("input")));
+
+    let mut counts = Vec::new();
+    for severity in [AmbiguitySeverity::Critical, AmbiguitySeverity::High, AmbiguitySeverity::Medium, AmbiguitySeverity::Low] {
+        let count = result.ambiguities.iter().filter(|a| a.severity == severity).count();
+        if count > 0 {
+            counts.push(format!("{} {}", count, severity));
+        }
+    }
+    if counts.is_empty() {
+        output.push_str("No ambiguities detected\n\n");
+    } else {
+        output.push_str(&format!("{} finding(s): {}\n\n", result.ambiguities.len(), counts.join(", ")));
+    }
+
+    let lines: Vec<&str> = input_text.lines().collect();
+    let width = lines.len().max(1).to_string().len();
+    for (index, line) in lines.iter().enumerate() {
+        let mut findings: Vec<(&Ambiguity, usize)> = result.ambiguities.iter()
+            .filter_map(|a| a.location.as_ref().filter(|l| l.line == index + 1).map(|l| (a, l.column)))
+            .collect();
+        findings.sort_by_key(|(a, column)| (*column, std::cmp::Reverse(a.severity)));
+
+        let mark = findings.iter().map(|(a, _)| a.severity).max().map(|s| gutter_mark(&s)).unwrap_or(' ');
+        output.push_str(&format!("{} {:>width$} | {}\n", mark, index + 1, line, width = width));
+        for (ambiguity, column) in findings {
+            output.push_str(&format!("  {:>width$} | {}\n", "", marker_line(line, column, ambiguity), width = width));
+        }
+    }
+
+    let unlocated: Vec<&Ambiguity> = result.ambiguities.iter().filter(|a| a.location.is_none()).collect();
+    if !unlocated.is_empty() {
+        output.push_str("\nNot found in the text:\n");
+        for ambiguity in unlocated {
+            output.push_str(&format!("{} \"{}\" - {}\n", gutter_mark(&ambiguity.severity), ambiguity.text, describe(ambiguity)));
+        }
+    }
+    output
+}
+
+/// Spaces up to `column` (keeping the line's tabs, so the marker lines up),
+/// then a caret under each character of the finding on this line.
+fn marker_line(line: &str, column: usize, ambiguity: &Ambiguity) -> String {
+    let indent: String = line.chars().take(column.saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let rest = line.chars().count().saturating_sub(column.saturating_sub(1));
+    let carets = ambiguity.text.chars().count().min(rest).max(1);
+    format!("{}{} {}", indent, "^".repeat(carets), describe(ambiguity))
+}
+
+fn describe(ambiguity: &Ambiguity) -> String {
+    format!("{} [{}] {}", ambiguity.severity, ambiguity.rule_id, ambiguity.reason)
+}
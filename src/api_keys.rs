@@ -0,0 +1,159 @@
+//! API keys for `prism serve`: which client is calling, whether it is within
+//! its rate limits and what it has used so far.
+
+use anyhow::Result;
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::budget::LlmUsage;
+use crate::config::ApiKeyConfig;
+use crate::webhook::constant_time_eq;
+
+/// What a client has used since the server started.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct KeyUsage {
+    /// Requests that were let through
+    pub requests: u64,
+    /// Requests refused because a limit was reached
+    pub rejected: u64,
+    pub requests_today: u32,
+    pub llm_calls: u64,
+    pub estimated_tokens: u64,
+    pub estimated_cost: f64,
+}
+
+/// A request refused because the client reached one of its limits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuotaExceeded {
+    pub limit: String,
+    /// Seconds until the request would be allowed
+    pub retry_after: u64,
+}
+
+impl std::fmt::Display for QuotaExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Rate limit of {} reached, retry in {}s", self.limit, self.retry_after)
+    }
+}
+
+#[derive(Default)]
+struct KeyState {
+    /// Times of the requests let through in the last minute
+    recent: VecDeque<NaiveDateTime>,
+    day: Option<NaiveDate>,
+    usage: KeyUsage,
+}
+
+/// One line of the usage log.
+#[derive(Debug, Serialize)]
+pub struct UsageRecord<'a> {
+    pub timestamp: String,
+    pub key: &'a str,
+    pub endpoint: &'a str,
+    pub status: u16,
+    pub llm_calls: usize,
+    pub estimated_tokens: u64,
+}
+
+pub struct ApiKeys {
+    keys: Vec<ApiKeyConfig>,
+    state: Mutex<HashMap<String, KeyState>>,
+    usage_log: Option<PathBuf>,
+}
+
+impl ApiKeys {
+    pub fn new(keys: &[ApiKeyConfig], usage_log: Option<PathBuf>) -> Result<Self> {
+        for (i, key) in keys.iter().enumerate() {
+            if key.name.trim().is_empty() || key.key.trim().is_empty() {
+                return Err(anyhow::anyhow!("Every entry of server.api_keys needs a name and a key"));
+            }
+            if keys[..i].iter().any(|other| other.name == key.name) {
+                return Err(anyhow::anyhow!("API key name '{}' is used more than once", key.name));
+            }
+            if keys[..i].iter().any(|other| other.key == key.key) {
+                return Err(anyhow::anyhow!("API key '{}' is the same as another key", key.name));
+            }
+        }
+        Ok(Self { keys: keys.to_vec(), state: Mutex::new(HashMap::new()), usage_log })
+    }
+
+    /// Whether requests have to present a key at all.
+    pub fn is_enabled(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    /// The configured key matching `presented`.
+    pub fn authenticate(&self, presented: &str) -> Option<&ApiKeyConfig> {
+        // Every key is compared, so the time taken does not tell which one nearly matched
+        self.keys.iter().fold(None, |found, key| {
+            let matches = constant_time_eq(key.key.as_bytes(), presented.trim().as_bytes());
+            found.or(if matches { Some(key) } else { None })
+        })
+    }
+
+    /// Counts a request of `key` at `now`, or refuses it when a limit is reached.
+    pub fn acquire(&self, key: &ApiKeyConfig, now: NaiveDateTime) -> Result<(), QuotaExceeded> {
+        let mut states = self.state.lock().unwrap();
+        let state = states.entry(key.name.clone()).or_default();
+
+        while state.recent.front().is_some_and(|time| *time <= now - Duration::minutes(1)) {
+            state.recent.pop_front();
+        }
+        if state.day != Some(now.date()) {
+            state.day = Some(now.date());
+            state.usage.requests_today = 0;
+        }
+
+        if let Some(limit) = key.requests_per_minute {
+            if state.recent.len() >= limit as usize {
+                state.usage.rejected += 1;
+                let oldest = state.recent.front().copied().unwrap_or(now);
+                let wait = (oldest + Duration::minutes(1) - now).num_seconds().max(1);
+                return Err(QuotaExceeded { limit: format!("{} requests per minute", limit), retry_after: wait as u64 });
+            }
+        }
+        if let Some(limit) = key.requests_per_day {
+            if state.usage.requests_today >= limit {
+                state.usage.rejected += 1;
+                let midnight = now.date().succ_opt().unwrap_or(now.date()).and_hms_opt(0, 0, 0).unwrap_or(now);
+                let wait = (midnight - now).num_seconds().max(1);
+                return Err(QuotaExceeded { limit: format!("{} requests per day", limit), retry_after: wait as u64 });
+            }
+        }
+
+        state.recent.push_back(now);
+        state.usage.requests += 1;
+        state.usage.requests_today += 1;
+        Ok(())
+    }
+
+    /// Adds the LLM usage of one of `key`'s requests.
+    pub fn record(&self, key: &ApiKeyConfig, usage: &LlmUsage, estimated_cost: f64) {
+        let mut states = self.state.lock().unwrap();
+        let totals = &mut states.entry(key.name.clone()).or_default().usage;
+        totals.llm_calls += usage.calls as u64;
+        totals.estimated_tokens += usage.estimated_tokens();
+        totals.estimated_cost += estimated_cost;
+    }
+
+    pub fn usage(&self, key: &ApiKeyConfig) -> KeyUsage {
+        self.state.lock().unwrap().get(&key.name).map(|state| state.usage.clone()).unwrap_or_default()
+    }
+
+    /// Appends `record` to the usage log, when one is configured.
+    pub fn log(&self, record: &UsageRecord) -> Result<()> {
+        let Some(path) = &self.usage_log else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(record)?)?;
+        Ok(())
+    }
+}
@@ -1,43 +1,478 @@
-use anyhow::Result;
-use std::path::PathBuf;
-use std::io;
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::io::{self, IsTerminal, Read, Write};
+use std::sync::Arc;
 use tokio::fs;
+use tokio::sync::{watch, Semaphore};
 use walkdir::WalkDir;
 
-use crate::analyzer::{Analyzer, AnalysisResult};
-use crate::cli::{Commands, OutputFormat, AnalysisPreset, GenerateOptions};
-use crate::config::Config;
+use crate::analyzer::{Analyzer, AnalysisResult, AmbiguitySeverity, PermissionMatrix};
+use crate::cli::{AiProvider, ArchitectureStyle, BaselineAction, CacheAction, Commands, OutputFormat, AnalysisPreset, GenerateOptions, ProjectsAction, ReportAction};
+use crate::config::{is_compatible_provider, parse_header, Config, LlmOverrides, PresetConfig, ScheduledAnalysis, DEFAULT_SYSTEM_PROMPT, PLACEHOLDER_API_KEY};
+use crate::artifacts::{
+    anchor_link, artifact_file_name, format_artifact_index, nfr_anchor, nfrs_for_gap, suggestion_anchor, suggestion_for, ArtifactLinks,
+    SavedArtifact,
+};
+use crate::batch::{
+    format_batch_index_markdown, relative_name, report_file_name, report_path, unique_path, BatchFailure, BatchIndexEntry,
+    FailureStage, ResumeManifest, FAILURE_REPORT_NAME,
+};
+use crate::console::stdout_color;
+use crate::diagnostics::format_diagnostics;
+use crate::events::{emit, emit_result, Event};
+use crate::paths;
+use crate::pdf_report::{markdown_to_pdf, report_title};
+use crate::spreadsheet::{csv_cell, finding_rows, is_xlsx_path, write_findings_xlsx};
+use crate::baseline::{find_baseline, format_baseline, FindingsBaseline, BASELINE_FILE_NAME};
+use crate::budget::{AnalysisBudget, BudgetTracker, LlmUsage};
+use crate::badge::{badge_message, format_badge_json, format_badge_svg};
+use crate::cache::AnalysisCache;
+use crate::metrics::Metrics;
+use crate::plantuml::PlantUmlRenderer;
+use crate::server::ServerState;
+use crate::telemetry::{instrument, start_span, SpanKind, Tracer};
 use crate::ui::TuiApp;
-use crate::document_processor::DocumentProcessor;
-
+use crate::document_processor::{DocumentError, DocumentProcessor, ExtractedArchive};
+use crate::architecture::generate_architecture_pseudocode;
+use crate::ears::check_ears;
+use crate::smart::analyze_smart;
+use crate::adr::{find_adr_dir, format_adr_markdown, AdrLinkReport, DecisionRecord, ADR_LOCATIONS};
+use crate::cost_of_delay::{
+    format_cost_of_delay_html, format_cost_of_delay_markdown, front_matter_annotation, parse_sidecar, split_front_matter,
+    CostOfDelayReport, PrioritizedDocument, Quadrant, SIDECAR_NAME,
+};
+use crate::coverage::{check_coverage, extract_capabilities, format_coverage_markdown, CoverageReport, DEFAULT_COVERAGE_THRESHOLD};
+use crate::release::{evaluate_release, find_open_questions, format_release_markdown};
+use crate::quality_gate::QualityGate;
+use crate::chat::ChatSession;
+use crate::changelog::{build_changelog, format_changelog_markdown};
+use crate::locale::ReportLocale;
+use crate::status;
+use crate::index::{format_corpus_report_markdown, RequirementIndex};
+use crate::authoring::{check_answer, normalize_answer, AnswerCheck, DraftField, RequirementDraft};
+use crate::hierarchy::{format_hierarchy_markdown, HierarchyNode, QualityScore};
+use crate::dependencies::{build_dependency_graph, format_dependency_dot, format_dependency_mermaid};
+use crate::domain_model::{build_domain_model, format_domain_model_markdown, format_domain_model_plantuml};
+use crate::custom_rules::load_custom_rules;
+use crate::signing::Signer;
+use crate::fixtures::{FixtureMode, LlmFixtures};
+use crate::merge::merge_results;
+use crate::selftest::{format_selftest_markdown, parse_suite, CaseOutcome, SelftestReport, SelftestRun, BUILT_IN_SUITE};
+use crate::provider_comparison::{compare_runs, format_provider_comparison_markdown, median_duration, parse_model_target, ProviderComparison, ProviderRun};
+use crate::report_diff::{diff_results, format_report_diff_markdown};
+use crate::prompts::{load_prompt_templates, write_built_in_prompts, PromptTask};
+use crate::contract_tests::{format_contract_pact, format_contract_tests_markdown, generate_contract_tests};
+use crate::test_data::{format_test_data_fixtures, suggest_test_data};
+use crate::test_coverage::build_test_coverage;
+use crate::load_tests::{format_gatling_simulation, format_k6_script, generate_load_scenarios, simulation_class_name};
+use crate::evidence::{default_bundle_name, format_improvement_history, format_review_checklist, sha256_hex, EvidenceBundle};
+use crate::security_tests::{format_security_checklist, format_security_tests_markdown, generate_security_tests};
+use crate::event_storming::{build_event_storming, format_event_storming_mermaid, format_event_storming_miro};
+use crate::duplicates::{extract_statements, find_duplicate_clusters, format_duplicates_markdown, DUPLICATES_REPORT_NAME};
+use crate::owners::{format_ownership_markdown, CodeOwners, OwnershipReport, OWNERSHIP_REPORT_NAME};
+use crate::projects::{
+    discover_projects, find_project_config, format_projects_list, format_projects_summary, project_files, IgnoreGlobs, ProjectSummary,
+    PROJECT_MARKER,
+};
+use crate::hints::{format_hints, suggest_hints, HintContext};
+use crate::llm_stream::terminal_progress;
+use crate::git_integration::{
+    format_git_trace_html, format_git_trace_jira, format_git_trace_markdown, format_git_trace_plain, GitIntegration,
+};
+use crate::dashboard::{format_dashboard_html, DashboardOptions};
+use crate::demo::{demo_report_name, format_demo_readme, DemoOutput, DEMO_FORMATS, DEMO_SAMPLES};
+use crate::traceability::{format_traceability_markdown, TraceabilityAnalyzer};
+use crate::formatters::{format_as_github, format_as_markdown, format_permission_table, format_report, format_rewrite_suggestions, report_extension};
+use crate::toc::add_toc;
+use crate::notion::{format_notion_summary, notion_id, NotionClient};
+use crate::rm_tools::{RmClient, LOW_QUALITY_SCORE};
+use crate::secrets;
+use crate::daemon::{detect_regression, format_regression_notification, AnalysisHistory, CronSchedule, HistoryEntry};
+
+#[derive(Clone)]
 pub struct App {
     pub config: Config,
     analyzer: Analyzer,
     document_processor: DocumentProcessor,
+    /// Set when an OTLP endpoint is configured
+    tracer: Option<Arc<Tracer>>,
+    /// Turned true by Ctrl-C, which cancels the AI calls in flight
+    cancel: Arc<watch::Sender<bool>>,
+    /// `--no-banner`
+    no_banner: bool,
+    /// `--paths-relative`
+    paths_relative: bool,
+    /// Accepted findings from the nearest `.prismignore-findings`
+    baseline: Option<FindingsBaseline>,
+}
+
+/// Printed between comment parts when a split GitHub report goes to the screen.
+const GITHUB_COMMENT_SEPARATOR: &str = "<!-- prism:next-comment -->";
+
+/// Optional artifacts to generate on top of the core analysis, resolved from
+/// `--preset` and `--generate`.
+#[derive(Debug, Clone, Copy, Default)]
+struct GenerationPlan {
+    uml: bool,
+    pseudo: bool,
+    tests: bool,
+    gherkin: bool,
+    improve: bool,
+    nfr: bool,
+    completeness: bool,
+    validate_story: bool,
+    permissions: bool,
+    suggestions: bool,
+    dependencies: bool,
+    reviewers: bool,
+    domain_model: bool,
+    smart: bool,
+    event_storming: bool,
+    load_tests: bool,
+    security_tests: bool,
+    /// Findings below this severity are dropped from the result
+    min_severity: Option<AmbiguitySeverity>,
+    /// Architecture the pseudocode is laid out in, else a single service class
+    arch: Option<ArchitectureStyle>,
+}
+
+/// The subcommand's name as typed, e.g. "release-check", for trace names.
+fn command_name(command: &Commands) -> String {
+    let variant = format!("{:?}", command);
+    let variant = variant.split(|c: char| !c.is_alphanumeric()).next().unwrap_or_default();
+    let mut name = String::new();
+    for (i, c) in variant.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            name.push('-');
+        }
+        name.push(c.to_ascii_lowercase());
+    }
+    name
+}
+
+/// The files changed between the git ref `since` and HEAD, canonicalized:
+/// all of them for a `dir`, else the requirement files of the repository
+/// around the current directory. Also returns that repository's root.
+fn files_changed_since(since: &str, dir: Option<&Path>) -> Result<(PathBuf, HashSet<PathBuf>)> {
+    let git = GitIntegration::discover(dir.unwrap_or(Path::new(".")))?;
+    let files = match dir {
+        Some(_) => git.changed_files_since(since)?,
+        None => git.get_modified_requirements_since_commit(since)?,
+    };
+    let changed = files.into_iter().map(|file| file.canonicalize().unwrap_or(file)).collect();
+    Ok((git.repo_path().to_path_buf(), changed))
+}
+
+/// The file or directory a command works on, where its project's
+/// `.prism.yml` is looked for.
+fn command_input(command: &Commands) -> Option<&Path> {
+    match command {
+        Commands::Analyze { file, dir, .. }
+        | Commands::Improve { file, dir, .. }
+        | Commands::Validate { file, dir, .. }
+        | Commands::Dashboard { file, dir, .. }
+        | Commands::Badge { file, dir, .. }
+        | Commands::ReleaseCheck { file, dir, .. }
+        | Commands::Evidence { file, dir, .. }
+        | Commands::Baseline { action: BaselineAction::Create { file, dir, .. } } => file.as_deref().or(dir.as_deref()),
+        Commands::Trace { file, .. } | Commands::BenchProviders { file, .. } => file.as_deref(),
+        Commands::Chat { file, .. } => Some(file),
+        Commands::Index { dir, .. } | Commands::Hierarchy { dir, .. } | Commands::Prioritize { dir, .. } => Some(dir),
+        _ => None,
+    }
+}
+
+/// The `--format` of a command whose report goes to stdout.
+fn command_format(command: &Commands) -> Option<&OutputFormat> {
+    match command {
+        Commands::Analyze { format, .. }
+        | Commands::Improve { format, .. }
+        | Commands::Validate { format, .. }
+        | Commands::Trace { format, .. }
+        | Commands::Index { format, .. }
+        | Commands::Hierarchy { format, .. }
+        | Commands::Prioritize { format, .. }
+        | Commands::BenchProviders { format, .. }
+        | Commands::Selftest { format, .. }
+        | Commands::Coverage { format, .. }
+        | Commands::Adr { format, .. }
+        | Commands::ReleaseCheck { format, .. }
+        | Commands::Report { action: ReportAction::Diff { format, .. } }
+        | Commands::Projects { action: ProjectsAction::Discover { format, .. } } => format.as_ref(),
+        Commands::Merge { format, .. } => format.as_ref().or(Some(&OutputFormat::Json)),
+        _ => None,
+    }
+}
+
+/// The `--provider` and `--model` flags of a command.
+fn command_llm_overrides(command: &Commands) -> LlmOverrides {
+    match command {
+        Commands::Analyze { provider, model, temperature, max_tokens, .. }
+        | Commands::Improve { provider, model, temperature, max_tokens, .. } => LlmOverrides {
+            provider: provider.as_ref().map(|p| p.name().to_string()),
+            model: model.clone(),
+            temperature: *temperature,
+            max_tokens: *max_tokens,
+            ..LlmOverrides::default()
+        },
+        _ => LlmOverrides::default(),
+    }
+}
+
+/// Cache key part for the analysis options that change a result beyond the
+/// configuration: the artifacts generated and how, the prompt templates and
+/// the merged custom rules with their severity overrides and disabled rules.
+fn cache_options(plan: &GenerationPlan, pseudo_lang: Option<&str>, analyzer: &Analyzer) -> String {
+    format!("{:?} {:?} {} {}", plan, pseudo_lang, analyzer.prompts().fingerprint(), analyzer.rules_fingerprint())
+}
+
+/// Keeps `manifest` for `--resume`, also after a complete run so that the
+/// next one can skip the files that have not changed. The reports are
+/// written by then, so a manifest that cannot be saved is only a warning.
+fn save_resume_manifest(dir: &Path, manifest: &ResumeManifest) -> Option<PathBuf> {
+    let saved = ResumeManifest::path(dir).and_then(|path| {
+        manifest.save(&path)?;
+        Ok(path)
+    });
+    saved.map_err(|e| eprintln!("⚠️  Could not save the resume manifest: {}", e)).ok()
+}
+
+/// The options of `analyze` that apply to a `--dir` batch.
+struct BatchOptions {
+    output: Option<PathBuf>,
+    format: Option<OutputFormat>,
+    plan: GenerationPlan,
+    save_artifacts: Option<String>,
+    pseudo_lang: Option<String>,
+    max_comment_size: usize,
+    budget: Option<AnalysisBudget>,
+    resume: bool,
+    changed_since: Option<(String, HashSet<PathBuf>)>,
+    output_dir: Option<PathBuf>,
+    jobs: usize,
+    no_cache: bool,
+    /// Every file of the batch has to pass it
+    gate: QualityGate,
+    owners_file: Option<PathBuf>,
+}
+
+/// Shared state of the worker tasks of a `--dir` batch.
+struct BatchJob {
+    plan: GenerationPlan,
+    pseudo_lang: Option<String>,
+    /// `None` with `--no-cache` or without an AI provider
+    cache: Option<AnalysisCache>,
+    cache_options: String,
+    previous_run: Option<ResumeManifest>,
+    tracker: std::sync::Mutex<Option<BudgetTracker>>,
+    builtin_analyzer: Analyzer,
+    cancelled: watch::Receiver<bool>,
+}
+
+/// What a worker did with one file of a batch.
+enum BatchFileResult {
+    /// The batch was cancelled before the file's turn came
+    NotStarted,
+    Unchanged { content: String },
+    ExtractionFailed(anyhow::Error),
+    AnalysisFailed { error: anyhow::Error },
+    Analyzed { content: String, result: Box<AnalysisResult>, builtin: bool, cached: bool },
+}
+
+/// A worker's result for one file together with its progress lines, which
+/// the batch prints in file order however the workers finished.
+struct BatchFileOutcome {
+    log: Vec<String>,
+    result: BatchFileResult,
+}
+
+impl GenerationPlan {
+    fn enable(&mut self, option: &GenerateOptions) {
+        match option {
+            GenerateOptions::All => {
+                self.uml = true;
+                self.pseudo = true;
+                self.tests = true;
+                self.gherkin = true;
+                self.improve = true;
+                self.nfr = true;
+                self.permissions = true;
+                self.suggestions = true;
+                self.dependencies = true;
+                self.reviewers = true;
+                self.domain_model = true;
+                self.smart = true;
+                self.event_storming = true;
+                self.load_tests = true;
+                self.security_tests = true;
+            }
+            GenerateOptions::Uml => self.uml = true,
+            GenerateOptions::Pseudo => self.pseudo = true,
+            GenerateOptions::Tests => self.tests = true,
+            GenerateOptions::Gherkin => self.gherkin = true,
+            GenerateOptions::Improve => self.improve = true,
+            GenerateOptions::Nfr => self.nfr = true,
+            GenerateOptions::Permissions => self.permissions = true,
+            GenerateOptions::Suggestions => self.suggestions = true,
+            GenerateOptions::Dependencies => self.dependencies = true,
+            GenerateOptions::Reviewers => self.reviewers = true,
+            GenerateOptions::DomainModel => self.domain_model = true,
+            GenerateOptions::Smart => self.smart = true,
+            GenerateOptions::EventStorming => self.event_storming = true,
+            GenerateOptions::LoadTests => self.load_tests = true,
+            GenerateOptions::SecurityTests => self.security_tests = true,
+        }
+    }
 }
 
 impl App {
     pub async fn new() -> Result<Self> {
         let config = Config::load().await?;
-        let analyzer = Analyzer::new()?.with_config(config.clone());
-        let document_processor = DocumentProcessor::new();
+        let tracer = Tracer::from_config(&config.telemetry);
+        let (cancel, cancelled) = watch::channel(false);
+        let mut analyzer = Analyzer::new()?
+            .with_config(config.clone())
+            .with_custom_rules(load_custom_rules(&config)?)?
+            .with_prompts(load_prompt_templates(&config)?)
+            .with_tracer(tracer.clone())
+            .with_cancellation(cancelled);
+        // Long AI calls show their progress instead of sitting silent
+        if let Some(progress) = terminal_progress() {
+            analyzer = analyzer.with_stream_handler(progress);
+        }
+        let document_processor = DocumentProcessor::new().with_tracer(tracer.clone());
+
+        Ok(Self { config, analyzer, document_processor, tracer, cancel: Arc::new(cancel), no_banner: false, paths_relative: false, baseline: None })
+    }
+
+    /// Layers the nearest `.prism.yml` at or above `start` over the global
+    /// configuration.
+    pub fn apply_project_config(&mut self, start: &Path) -> Result<()> {
+        let Some((_, project)) = find_project_config(start)? else { return Ok(()) };
+        project.apply_to(&mut self.config);
+        IgnoreGlobs::new(&self.config.analysis.ignore)?;
+        self.analyzer = self.analyzer.clone()
+            .with_config(self.config.clone())
+            .with_custom_rules(load_custom_rules(&self.config)?)?
+            .with_prompts(load_prompt_templates(&self.config)?);
+        Ok(())
+    }
+
+    /// Applies `overrides` in order over the LLM settings, without saving them.
+    pub fn apply_llm_overrides(&mut self, overrides: &[LlmOverrides]) {
+        if overrides.iter().all(LlmOverrides::is_empty) {
+            return;
+        }
+        for layer in overrides {
+            layer.apply_to(&mut self.config);
+        }
+        self.analyzer = self.analyzer.clone().with_config(self.config.clone());
+    }
+
+    /// Records the LLM responses of this run to golden files, or replays them
+    /// from there (`--record`, `--replay`).
+    pub fn with_llm_fixtures(mut self, fixtures: Option<LlmFixtures>) -> Self {
+        self.analyzer = self.analyzer.clone().with_fixtures(fixtures);
+        self
+    }
+
+    /// Without an AI provider configured, a replaying run takes the provider
+    /// and model of the recording, so the AI analysis runs as recorded.
+    fn apply_replay_model(&mut self) -> Result<()> {
+        let Some(fixtures) = self.analyzer.fixtures().filter(|f| f.mode() == FixtureMode::Replay) else {
+            return Ok(());
+        };
+        if self.config.is_ai_configured() {
+            return Ok(());
+        }
+        let (provider, model) = fixtures.recorded_model()?;
+        self.config.llm.provider = provider;
+        self.config.llm.model = model;
+        self.config.llm.api_key = Some("replay".to_string());
+        self.analyzer = self.analyzer.clone().with_config(self.config.clone());
+        Ok(())
+    }
+
+    /// Pulls the configured Ollama model when the server does not have it:
+    /// without asking with `llm.ollama.auto_pull`, after asking when `ask`,
+    /// and otherwise only says how. An unreachable server is left to the AI
+    /// calls to report.
+    async fn ensure_ollama_model(&self, ask: bool) -> Result<()> {
+        if self.config.llm.provider != "ollama" || !self.config.is_ai_configured()
+            || self.analyzer.fixtures().is_some_and(|f| f.mode() == FixtureMode::Replay) {
+            return Ok(());
+        }
+        let server = crate::ollama::server_url(self.config.llm.base_url.as_deref());
+        let model = &self.config.llm.model;
+        let client = reqwest::Client::new();
+        let installed = tokio::time::timeout(std::time::Duration::from_secs(5), crate::ollama::installed_models(&client, &server)).await;
+        let Ok(Ok(installed)) = installed else {
+            return Ok(());
+        };
+        if crate::ollama::is_installed(&installed, model) {
+            return Ok(());
+        }
+        if !self.config.llm.ollama.auto_pull {
+            if !ask {
+                eprintln!("⚠️  Ollama model '{}' is not installed on {} - pull it with 'ollama pull {}'", model, server, model);
+                return Ok(());
+            }
+            eprint!("📥 Ollama model '{}' is not installed on {}. Pull it now? [Y/n] ", model, server);
+            io::stderr().flush()?;
+            let mut answer = String::new();
+            io::stdin().read_line(&mut answer)?;
+            if answer.trim().to_lowercase().starts_with('n') {
+                return Ok(());
+            }
+        }
+        eprintln!("⬇️  Pulling {}...", model);
+        crate::ollama::pull_model(&client, &server, model, |status| {
+            eprint!("\r   {:<60}", status);
+            let _ = io::stderr().flush();
+        }).await?;
+        eprintln!("\n✅ Pulled {}", model);
+        Ok(())
+    }
+
+    /// Password tried on password-protected PDF input files.
+    pub fn with_doc_password(mut self, password: Option<String>) -> Self {
+        self.document_processor = DocumentProcessor::new().with_password(password).with_tracer(self.tracer.clone());
+        self
+    }
+
+    /// Leaves out the "🔍 PRISM" banner of every command.
+    pub fn with_no_banner(mut self, no_banner: bool) -> Self {
+        self.no_banner = no_banner;
+        self
+    }
+
+    /// Names the input files in reports relative to the current directory,
+    /// with `/` separators, instead of as given on the command line.
+    pub fn with_paths_relative(mut self, paths_relative: bool) -> Self {
+        self.paths_relative = paths_relative;
+        self
+    }
 
-        Ok(Self { config, analyzer, document_processor })
+    /// How reports name the input file `path`.
+    fn report_source_name(&self, path: &Path) -> String {
+        if self.paths_relative {
+            paths::portable(path)
+        } else {
+            path.display().to_string()
+        }
     }
 
     fn print_branded_header(&self) {
-        println!("🔍 PRISM - AI-Powered Requirement Analyzer");
-        println!("===========================================");
+        if self.no_banner {
+            return;
+        }
+        status!("🔍 PRISM - AI-Powered Requirement Analyzer");
+        status!("===========================================");
     }
 
-    fn resolve_generation_options(&self, preset: &Option<AnalysisPreset>, generate: &Vec<GenerateOptions>) -> (bool, bool, bool, bool, bool, bool, bool) {
-        let mut uml = false;
-        let mut pseudo = false; 
-        let mut tests = false;
-        let mut improve = false;
-        let mut nfr = false;
-        let mut completeness = false;
-        let validate_story = false;
+    fn resolve_generation_options(&self, preset: &Option<AnalysisPreset>, generate: &Vec<GenerateOptions>) -> Result<GenerationPlan> {
+        let mut plan = GenerationPlan::default();
 
         // Apply preset first
         if let Some(preset) = preset {
@@ -46,54 +481,166 @@ impl App {
                     // Just basic analysis - no additional features
                 }
                 AnalysisPreset::Standard => {
-                    uml = true;
-                    pseudo = true;
-                    tests = true;
+                    plan.uml = true;
+                    plan.pseudo = true;
+                    plan.tests = true;
                 }
                 AnalysisPreset::Full => {
-                    uml = true;
-                    pseudo = true;
-                    tests = true;
-                    improve = true;
-                    nfr = true;
-                    completeness = true;
+                    plan.uml = true;
+                    plan.pseudo = true;
+                    plan.tests = true;
+                    plan.gherkin = true;
+                    plan.improve = true;
+                    plan.nfr = true;
+                    plan.completeness = true;
+                    plan.permissions = true;
+                    plan.suggestions = true;
+                    plan.dependencies = true;
+                    plan.reviewers = true;
+                    plan.domain_model = true;
+                    plan.smart = true;
+                    plan.event_storming = true;
+                    plan.load_tests = true;
+                    plan.security_tests = true;
                 }
                 AnalysisPreset::Report => {
-                    uml = true;
-                    tests = true;
-                    improve = true;
-                    completeness = true;
+                    plan.uml = true;
+                    plan.tests = true;
+                    plan.improve = true;
+                    plan.completeness = true;
+                }
+                AnalysisPreset::Custom(name) => {
+                    let custom = self.custom_preset(name)?;
+                    for option in &custom.generate {
+                        plan.enable(option);
+                    }
+                    plan.min_severity = custom.min_severity;
                 }
             }
         }
 
         // Apply individual generate options (override preset)
         for option in generate {
-            match option {
-                GenerateOptions::All => {
-                    uml = true;
-                    pseudo = true;
-                    tests = true;
-                    improve = true;
-                    nfr = true;
-                }
-                GenerateOptions::Uml => uml = true,
-                GenerateOptions::Pseudo => pseudo = true,
-                GenerateOptions::Tests => tests = true,
-                GenerateOptions::Improve => improve = true,
-                GenerateOptions::Nfr => nfr = true,
-            }
+            plan.enable(option);
         }
 
         // Smart defaults: auto-enable tests when improve is used
-        if improve && !tests {
-            tests = true;
+        if plan.improve && !plan.tests {
+            plan.tests = true;
+        }
+
+        Ok(plan)
+    }
+
+    fn custom_preset(&self, name: &str) -> Result<&PresetConfig> {
+        self.config.presets.get(name).ok_or_else(|| {
+            let mut available = vec!["basic", "standard", "full", "report"];
+            available.extend(self.config.presets.keys().map(|k| k.as_str()));
+            anyhow::anyhow!("Unknown preset '{}'. Available presets: {}", name, available.join(", "))
+        })
+    }
+
+    /// Output format of a custom preset, used when `--format` is not given.
+    fn preset_format(&self, preset: &Option<AnalysisPreset>) -> Option<OutputFormat> {
+        match preset {
+            Some(AnalysisPreset::Custom(name)) => self.config.presets.get(name).and_then(|p| p.format.clone()),
+            _ => None,
         }
+    }
+
+    /// Drops findings below the plan's minimum severity and refreshes the metrics.
+    /// Leaves out the findings accepted in the `.prismignore-findings` baseline.
+    fn apply_baseline(&self, result: &mut AnalysisResult, text: &str) {
+        if let Some(baseline) = &self.baseline {
+            baseline.apply(result);
+            result.metrics = self.analyzer.compute_metrics(text, result.ambiguities.len());
+        }
+    }
 
-        (uml, pseudo, tests, improve, nfr, completeness, validate_story)
+    fn apply_min_severity(&self, result: &mut AnalysisResult, text: &str, plan: &GenerationPlan) {
+        if let Some(min_severity) = plan.min_severity {
+            result.retain_min_severity(min_severity);
+            result.metrics = self.analyzer.compute_metrics(text, result.ambiguities.len());
+        }
     }
 
+    /// Runs `command`. With tracing enabled the whole command is one trace,
+    /// exported when it finishes.
     pub async fn run_command(&mut self, command: Commands) -> Result<()> {
+        // `prism config` edits the global configuration and `prism projects`
+        // applies each project's own settings
+        if !matches!(command, Commands::Config { .. } | Commands::Projects { .. }) {
+            self.apply_project_config(command_input(&command).unwrap_or(Path::new(".")))?;
+        }
+        // A new baseline is made from every current finding
+        if !matches!(command, Commands::Baseline { .. }) {
+            self.baseline = find_baseline(command_input(&command).unwrap_or(Path::new(".")))
+                .map(|path| FindingsBaseline::load(&path))
+                .transpose()?;
+        }
+        crate::locale::set_report_locale(ReportLocale::from_config(&self.config.output)?);
+        // Environment variables and flags change this run only; `prism config`
+        // saves what it shows, so it sees the file as it is
+        if !matches!(command, Commands::Config { .. }) {
+            let flags = command_llm_overrides(&command);
+            flags.validate()?;
+            self.apply_llm_overrides(&[LlmOverrides::from_env()?, flags]);
+            self.apply_replay_model()?;
+        }
+        // A machine-readable report on stdout stays parseable: status lines go to stderr
+        let format = command_format(&command).or(self.config.output.format.as_ref());
+        crate::console::set_status_to_stderr(format.is_some_and(OutputFormat::is_machine_readable));
+        // A missing local model is pulled before the first AI call needs it
+        if matches!(
+            command,
+            Commands::Analyze { .. } | Commands::Improve { .. } | Commands::Validate { .. } | Commands::Chat { .. }
+                | Commands::New { .. } | Commands::Selftest { .. } | Commands::BenchProviders { .. } | Commands::Tui
+        ) {
+            self.ensure_ollama_model(io::stdin().is_terminal()).await?;
+        }
+        // The server and daemon run until stopped, so they trace each request or run instead
+        let long_running = matches!(command, Commands::Serve { .. } | Commands::Daemon { .. });
+        // Ctrl-C stops those and the interactive commands as usual
+        let interactive = matches!(command, Commands::Config { .. } | Commands::Tui | Commands::Chat { .. } | Commands::New { .. } | Commands::Sign { .. });
+        let cancel_listener = (!long_running && !interactive).then(|| self.listen_for_cancellation());
+        let result = match self.tracer.clone().filter(|_| !long_running) {
+            Some(tracer) => {
+                let mut span = tracer.start_span(&format!("prism {}", command_name(&command)), SpanKind::Internal);
+                let result = instrument(Some(&span), self.dispatch_command(command)).await;
+                if let Err(e) = &result {
+                    span.set_error(e);
+                }
+                drop(span);
+                if let Err(e) = tracer.flush().await {
+                    eprintln!("⚠️  Traces not exported: {}", e);
+                }
+                result
+            }
+            None => self.dispatch_command(command).await,
+        };
+        if let Some(listener) = cancel_listener {
+            listener.abort();
+        }
+        result
+    }
+
+    /// Cancels the AI calls in flight on the first Ctrl-C, so the command
+    /// finishes with built-in analysis; a batch also stops taking new files.
+    /// A second Ctrl-C exits immediately.
+    fn listen_for_cancellation(&self) -> tokio::task::JoinHandle<()> {
+        let cancel = self.cancel.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                eprintln!("\n🛑 Cancelling - AI requests in progress are stopped - press Ctrl-C again to stop immediately");
+                cancel.send_replace(true);
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    std::process::exit(130);
+                }
+            }
+        })
+    }
+
+    async fn dispatch_command(&mut self, command: Commands) -> Result<()> {
         match command {
             Commands::Analyze {
                 text,
@@ -104,96 +651,142 @@ impl App {
                 generate,
                 format,
                 pseudo_lang,
+                arch,
                 save_artifacts,
                 template,
                 branding,
+                toc,
+                min_severity,
+                fail_on,
+                min_completeness,
                 continue_on_error,
                 skip_invalid,
-                parallel,
+                jobs,
+                max_comment_size,
+                budget,
+                resume,
+                since,
+                output_dir,
+                no_cache,
+                owners,
+                compare_providers,
+                ..
             } => {
                 self.print_branded_header();
+                if toc {
+                    self.config.output.toc = true;
+                }
                 
                 // Resolve preset and generate options into specific flags
-                let (uml, pseudo, tests, improve, nfr, completeness, validate_story) = 
-                    self.resolve_generation_options(&preset, &generate);
+                let mut plan = self.resolve_generation_options(&preset, &generate)?;
+                plan.arch = arch.or(self.config.analysis.architecture);
+                plan.min_severity = min_severity.or(plan.min_severity);
+                let gate = QualityGate { fail_on, min_completeness };
+                // The completeness gate needs the completeness analysis
+                plan.completeness |= min_completeness.is_some();
+                let format = format.or_else(|| self.preset_format(&preset)).or_else(|| self.config.output.format.clone());
                 
+                // A .zip is unpacked and analyzed like a directory
+                let archive = match &file {
+                    Some(archive_path) if self.document_processor.is_archive(archive_path) => Some(self.extract_archive(archive_path)?),
+                    _ => None,
+                };
+
+                if let Some(providers) = compare_providers {
+                    if dir.is_some() || archive.is_some() {
+                        return Err(anyhow::anyhow!("--compare-providers compares the analysis of one text or file - use it without --dir or a .zip"));
+                    }
+                    if gate.is_enabled() {
+                        return Err(anyhow::anyhow!("--fail-on and --min-completeness gate a single analysis - use them without --compare-providers"));
+                    }
+                    let input_text = self.get_input_text(text, file, dir).await?;
+                    return self.compare_providers(&input_text, &providers, output, format).await;
+                }
+
+                // --since narrows the batch to the files changed since a git ref,
+                // by default the whole repository's requirement files
+                let (dir, changed) = match since {
+                    Some(since) => {
+                        let (root, changed) = files_changed_since(&since, dir.as_deref())?;
+                        (Some(dir.unwrap_or(root)), Some((since, changed)))
+                    }
+                    None => (dir, None),
+                };
+
                 // Handle batch processing (directory) differently
-                if let Some(dir_path) = &dir {
-                    return self.process_directory_batch(
-                        dir_path, output, format, uml, pseudo, tests, improve, 
-                        save_artifacts, completeness, validate_story, nfr, pseudo_lang
-                    ).await;
+                if let Some(dir_path) = dir.as_ref().or(archive.as_ref().map(|a| &a.dir)) {
+                    let source = if archive.is_some() { file.as_deref() } else { dir.as_deref() };
+                    let options = BatchOptions {
+                        output,
+                        format,
+                        plan,
+                        save_artifacts,
+                        pseudo_lang,
+                        max_comment_size,
+                        budget,
+                        resume,
+                        changed_since: changed,
+                        output_dir,
+                        jobs,
+                        no_cache,
+                        gate,
+                        owners_file: owners,
+                    };
+                    return self.process_directory_batch(dir_path, source.unwrap_or(dir_path), options).await;
+                }
+                if output_dir.is_some() {
+                    return Err(anyhow::anyhow!("--output-dir applies to batches - use it with --dir or a .zip --file"));
                 }
                 
+                let source_name = file.as_ref().map(|f| self.report_source_name(f));
                 let input_text = self.get_input_text(text, file, dir.clone()).await?;
                 
-                if self.config.is_ai_configured() {
-                    let (provider_name, _) = self.config.get_provider_info();
-                    println!("🤖 Analyzing your requirements with {} ({})...", provider_name, self.config.llm.model);
-                } else {
-                    println!("📋 Analyzing your requirements with built-in analysis...");
-                }
-                
-                let mut result = self.analyzer.analyze(&input_text).await?;
-
-                if uml {
-                    println!("🎨 Generating UML diagrams...");
-                    let use_case = self.analyzer.generate_uml_use_case(&result.entities);
-                    let sequence = self.analyzer.generate_uml_sequence(&result.entities);
-                    let class_diagram = self.analyzer.generate_uml_class_diagram(&result.entities);
-                    result.uml_diagrams = Some(crate::analyzer::UmlDiagrams {
-                        use_case: Some(use_case),
-                        sequence: Some(sequence),
-                        class_diagram: Some(class_diagram),
-                    });
-                }
-
-                if pseudo {
-                    println!("📝 Generating pseudocode structure...");
-                    let pseudocode = self.analyzer.generate_pseudocode(&result.entities, pseudo_lang.as_deref());
-                    result.pseudocode = Some(pseudocode);
-                }
+                let cache = self.analysis_cache(no_cache)
+                    .map(|cache| (AnalysisCache::key(&input_text, &self.config, &cache_options(&plan, pseudo_lang.as_deref(), &self.analyzer)), cache));
+                let tracker = budget.map(BudgetTracker::start);
+                let mut result = match cache.as_ref().and_then(|(key, cache)| cache.get(key)) {
+                    Some(result) => {
+                        status!("💾 Reusing the cached analysis of this text (--no-cache to analyze it again)");
+                        result
+                    }
+                    None => {
+                        if self.config.is_ai_configured() {
+                            let (provider_name, _) = self.config.get_provider_info();
+                            status!("🤖 Analyzing your requirements with {} ({})...", provider_name, self.config.llm.model);
+                        } else {
+                            status!("📋 Analyzing your requirements with built-in analysis...");
+                        }
 
-                if tests {
-                    println!("🧪 Generating test cases...");
-                    let test_cases = self.analyzer.generate_test_cases(&result.entities);
-                    result.test_cases = Some(test_cases);
-                }
+                        let mut result = self.analyze_within_budget(&self.analyzer, &input_text, tracker.as_ref()).await?;
+                        self.apply_min_severity(&mut result, &input_text, &plan);
 
-                if improve {
-                    println!("✨ Generating improved requirements...");
-                    match self.analyzer.generate_improved_requirements(&input_text, &result.ambiguities).await {
-                        Ok(improved) => {
-                            result.improved_requirements = Some(improved);
-                            println!("✅ Requirements improvement completed!");
-                        }
-                        Err(e) => {
-                            eprintln!("⚠️  Failed to generate improved requirements: {}", e);
-                            eprintln!("   Continuing with analysis results only");
+                        self.run_generation_plan(&mut result, &input_text, &plan, pseudo_lang.as_deref()).await?;
+                        if let Some((key, cache)) = cache.as_ref().filter(|_| !result.ai_failed) {
+                            if let Err(e) = cache.put(key, &result) {
+                                eprintln!("⚠️  Could not cache the analysis: {}", e);
+                            }
                         }
+                        result
                     }
-                }
-
-                // New features processing
-                if completeness {
-                    println!("📊 Analyzing completeness and identifying gaps...");
-                    let completeness_analysis = self.analyzer.analyze_completeness(&input_text, &result.entities).await?;
-                    result.completeness_analysis = Some(completeness_analysis);
-                }
+                };
 
-                if validate_story {
-                    println!("✅ Validating user story format and business value...");
-                    let user_story_validation = self.analyzer.validate_user_story(&input_text);
-                    result.user_story_validation = Some(user_story_validation);
+                if let Some(source_name) = &source_name {
+                    result.set_source_file(source_name);
                 }
+                self.apply_baseline(&mut result, &input_text);
 
-                if nfr {
-                    println!("🔒 Generating non-functional requirement suggestions...");
-                    let nfr_suggestions = self.analyzer.generate_nfr_suggestions(&input_text, &result.entities).await?;
-                    result.nfr_suggestions = Some(nfr_suggestions);
+                status!("✅ Analysis completed successfully!");
+                emit_result(source_name.as_deref(), &result);
+                self.print_json_repairs();
+                if tracker.is_some() {
+                    self.print_llm_spend();
                 }
-
-                println!("✅ Analysis completed successfully!");
+                let hint_context = HintContext {
+                    source: source_name.clone(),
+                    ai_configured: self.config.is_ai_configured(),
+                    saved_artifacts: save_artifacts.is_some(),
+                    html_report: matches!(format, Some(OutputFormat::Html)),
+                };
                 
                 let mut files_saved = false;
                 
@@ -204,7 +797,7 @@ impl App {
                         self.save_individual_artifacts(&result, &base_filename, &input_text).await?;
                         files_saved = true;
                     } else {
-                        println!("💡 Skipping individual artifacts for batch processing. Use single file analysis with --save-artifacts to generate individual files.");
+                        status!("💡 Skipping individual artifacts for batch processing. Use single file analysis with --save-artifacts to generate individual files.");
                     }
                 }
                 
@@ -212,40 +805,53 @@ impl App {
                 if let Some(output_path) = output {
                     // Always save main output when --output is specified
                     let format_to_use = format.unwrap_or(OutputFormat::Json);
-                    let output_content = match format_to_use {
-                        OutputFormat::Json => serde_json::to_string_pretty(&result)?,
-                        OutputFormat::Markdown => self.format_as_markdown(&result, &input_text),
-                        OutputFormat::Jira => self.format_as_jira(&result, &input_text),
-                        OutputFormat::Github => self.format_as_github(&result, &input_text),
-                        OutputFormat::Plain => self.format_as_plain(&result, &input_text),
-                    };
-                    
-                    let absolute_path = std::fs::canonicalize(&output_path).unwrap_or(output_path.clone());
-                    fs::write(&output_path, output_content).await?;
-                    println!("📁 Analysis report saved: {}", absolute_path.display());
+                    if is_xlsx_path(&output_path) {
+                        self.save_findings_xlsx(&output_path, &result, source_name.as_deref())?;
+                    } else {
+                        let output_content = self.render_report(&result, &input_text, &format_to_use, source_name.as_deref())?;
+                        if matches!(format_to_use, OutputFormat::Github) {
+                            self.write_github_comments(&output_path, &output_content, max_comment_size).await?;
+                        } else {
+                            fs::write(&output_path, output_content).await?;
+                        }
+                    }
+                    let absolute_path = paths::absolute(&output_path);
+                    status!("📁 Analysis report saved: {}", absolute_path.display());
+                    emit(&Event::ReportSaved { path: &absolute_path });
                     files_saved = true;
                 } else if !files_saved {
                     // Only display to screen if no files were saved
-                    self.display_result_to_screen(&result, format.unwrap_or(OutputFormat::Json), &input_text).await?;
+                    self.display_result_to_screen(&result, format.unwrap_or(OutputFormat::Json), &input_text, source_name.as_deref(), max_comment_size).await?;
                 }
                 
                 if files_saved {
-                    println!("🎉 Analysis complete! Review the saved files for detailed insights and recommendations.");
+                    status!("🎉 Analysis complete! Review the saved files for detailed insights and recommendations.");
+                }
+                self.print_hints(&result, &input_text, &hint_context);
+                emit(&Event::Finished { files: 1, findings: result.ambiguities.len() });
+
+                if gate.is_enabled() {
+                    let violations = gate.violations(&result);
+                    if !violations.is_empty() {
+                        return Err(anyhow::anyhow!("Quality gate failed: {}", violations.join(", ")));
+                    }
+                    status!("✅ Quality gate passed");
                 }
             }
             Commands::Tui => {
                 self.run_tui().await?;
             }
-            Commands::Improve { text, file, dir, output, format } => {
+            Commands::Improve { text, file, dir, output, format, redline, .. } => {
                 self.print_branded_header();
+                let format = format.or_else(|| self.config.output.format.clone());
                 let input_text = self.get_input_text(text, file, dir.clone()).await?;
                 
                 if self.config.is_ai_configured() {
                     let (provider_name, _) = self.config.get_provider_info();
-                    println!("🤖 Analyzing your requirements with {} ({})...", provider_name, self.config.llm.model);
+                    status!("🤖 Analyzing your requirements with {} ({})...", provider_name, self.config.llm.model);
                 } else {
-                    println!("❌ AI configuration required for requirement improvement!");
-                    println!("💡 Run 'prism config --setup' to configure AI features");
+                    status!("❌ AI configuration required for requirement improvement!");
+                    status!("💡 Run 'prism config --setup' to configure AI features");
                     return Ok(());
                 }
                 
@@ -253,11 +859,16 @@ impl App {
                 let analysis_result = self.analyzer.analyze(&input_text).await?;
                 
                 if analysis_result.ambiguities.is_empty() {
-                    println!("✅ No ambiguities found - requirements are already clear!");
+                    status!("✅ No ambiguities found - requirements are already clear!");
+                    if let Some(redline_path) = &redline {
+                        self.save_redline(redline_path, &input_text, &input_text)?;
+                    }
                     if let Some(output_path) = output {
-                        let absolute_path = std::fs::canonicalize(&output_path).unwrap_or(output_path.clone());
+                        let absolute_path = paths::absolute(&output_path);
                         fs::write(&output_path, &input_text).await?;
-                        println!("📁 Original requirements saved: {} (no changes needed)", absolute_path.display());
+                        status!("📁 Original requirements saved: {} (no changes needed)", absolute_path.display());
+                    } else if matches!(format, Some(OutputFormat::Json)) {
+                        println!("{}", serde_json::to_string_pretty(&analysis_result)?);
                     } else {
                         println!("\nOriginal Requirements:\n{}", input_text);
                     }
@@ -265,18 +876,21 @@ impl App {
                 }
                 
                 // Generate improved requirements
-                println!("✨ Generating improved requirements...");
+                status!("✨ Generating improved requirements...");
                 match self.analyzer.generate_improved_requirements(&input_text, &analysis_result.ambiguities).await {
                     Ok(improved) => {
+                        if let Some(redline_path) = &redline {
+                            self.save_redline(redline_path, &input_text, &improved)?;
+                        }
                         if let Some(output_path) = output {
                             let final_output = match format.unwrap_or(OutputFormat::Markdown) {
                                 OutputFormat::Markdown => self.format_improvement_as_markdown(&input_text, &improved, &analysis_result.ambiguities),
                                 _ => improved,
                             };
-                            let absolute_path = std::fs::canonicalize(&output_path).unwrap_or(output_path.clone());
+                            let absolute_path = paths::absolute(&output_path);
                             fs::write(&output_path, final_output).await?;
-                            println!("📁 Improved requirements created and saved: {}", absolute_path.display());
-                            println!("🎉 Analysis complete! Your requirements have been enhanced with specific, measurable criteria.");
+                            status!("📁 Improved requirements created and saved: {}", absolute_path.display());
+                            status!("🎉 Analysis complete! Your requirements have been enhanced with specific, measurable criteria.");
                         } else {
                             match format.unwrap_or(OutputFormat::Markdown) {
                                 OutputFormat::Markdown => {
@@ -284,7 +898,7 @@ impl App {
                                 }
                                 OutputFormat::Json => {
                                     let mut result = analysis_result;
-                                    result.improved_requirements = Some(improved);
+                                    self.analyzer.set_improved_requirements(&mut result, &input_text, improved);
                                     println!("{}", serde_json::to_string_pretty(&result)?);
                                 }
                                 _ => {
@@ -310,28 +924,33 @@ impl App {
                 validate_all,
                 test_providers,
                 set_template_dir,
+                init_prompts,
+                hints,
+                migrate_secrets,
+                base_url,
+                header,
             } => {
                 if debug {
                     let config_path = Config::config_path()?;
-                    println!("Configuration file path: {:?}", config_path);
-                    println!("Config directory exists: {}", config_path.parent().map_or(false, |p| p.exists()));
-                    println!("Config file exists: {}", config_path.exists());
+                    status!("Configuration file path: {:?}", config_path);
+                    status!("Config directory exists: {}", config_path.parent().map_or(false, |p| p.exists()));
+                    status!("Config file exists: {}", config_path.exists());
                     
                     if config_path.exists() {
                         match fs::read_to_string(&config_path).await {
                             Ok(content) => {
-                                println!("Config file size: {} bytes", content.len());
-                                println!("Config file content:");
-                                println!("{}", content);
+                                status!("Config file size: {} bytes", content.len());
+                                status!("Config file content:");
+                                status!("{}", content);
                             }
                             Err(e) => {
-                                println!("Error reading config file: {}", e);
+                                status!("Error reading config file: {}", e);
                             }
                         }
                     } else {
-                        println!("Config file does not exist. Creating default config...");
+                        status!("Config file does not exist. Creating default config...");
                         self.config.save().await?;
-                        println!("Default config created at: {:?}", config_path);
+                        status!("Default config created at: {:?}", config_path);
                     }
                     return Ok(());
                 }
@@ -341,6 +960,10 @@ impl App {
                     return Ok(());
                 }
 
+                if migrate_secrets {
+                    return self.migrate_secrets().await;
+                }
+
                 if test {
                     self.test_ai_configuration().await?;
                     return Ok(());
@@ -356,18 +979,11 @@ impl App {
                 let mut updated = false;
                 
                 if let Some(ai_provider) = provider {
-                    let provider_str = match ai_provider {
-                        crate::cli::AiProvider::OpenAI => "openai",
-                        crate::cli::AiProvider::Gemini => "gemini", 
-                        crate::cli::AiProvider::Claude => "claude",
-                        crate::cli::AiProvider::Azure => "azure",
-                        crate::cli::AiProvider::Ollama => "ollama",
-                    };
-                    self.config.set_provider(provider_str);
+                    self.config.set_provider(ai_provider.name());
                     updated = true;
                     
-                    // If no other parameters provided, run interactive setup
-                    if api_key.is_none() && model.is_none() {
+                    // If no other parameters provided, run interactive setup; the mock provider needs none
+                    if api_key.is_none() && model.is_none() && base_url.is_none() && !matches!(ai_provider, crate::cli::AiProvider::Mock) {
                         self.setup_provider(ai_provider).await?;
                         return Ok(());
                     }
@@ -383,36 +999,60 @@ impl App {
                     updated = true;
                 }
 
+                if let Some(url) = base_url {
+                    self.config.llm.base_url = Some(url);
+                    updated = true;
+                }
+
+                for spec in &header {
+                    let (name, value) = parse_header(spec)?;
+                    if value.is_empty() {
+                        self.config.llm.headers.remove(&name);
+                    } else {
+                        self.config.llm.headers.insert(name, value);
+                    }
+                    updated = true;
+                }
+
+                if is_compatible_provider(&self.config.llm.provider) && self.config.llm.api_key.is_none() {
+                    self.config.llm.api_key = Some(PLACEHOLDER_API_KEY.to_string());
+                }
+
+                if let Some(show_hints) = hints {
+                    self.config.analysis.show_hints = show_hints;
+                    updated = true;
+                }
+
                 // Handle new config validation options
                 if validate_all {
-                    println!("🔍 Validating configuration...");
+                    status!("🔍 Validating configuration...");
                     match self.config.validate_all_settings().await {
                         Ok(result) => {
                             if result.is_valid {
-                                println!("✅ Configuration is valid!");
+                                status!("✅ Configuration is valid!");
                             } else {
-                                println!("❌ Configuration issues found:");
+                                status!("❌ Configuration issues found:");
                                 for issue in result.issues {
-                                    println!("   • {}", issue);
+                                    status!("   • {}", issue);
                                 }
                             }
                             if !result.warnings.is_empty() {
-                                println!("⚠️  Warnings:");
+                                status!("⚠️  Warnings:");
                                 for warning in result.warnings {
-                                    println!("   • {}", warning);
+                                    status!("   • {}", warning);
                                 }
                             }
                         }
-                        Err(e) => println!("❌ Validation failed: {}", e),
+                        Err(e) => status!("❌ Validation failed: {}", e),
                     }
                     return Ok(());
                 }
 
                 if test_providers {
-                    println!("🧪 Testing all AI providers...");
+                    status!("🧪 Testing all AI providers...");
                     match self.config.test_all_providers().await {
                         Ok(results) => {
-                            println!("{}", results.get_summary());
+                            status!("{}", results.get_summary());
                             for (provider, result) in results.results {
                                 let status = if result.success { "✅" } else { "❌" };
                                 let time_str = if let Some(time) = result.response_time {
@@ -420,107 +1060,306 @@ impl App {
                                 } else {
                                     String::new()
                                 };
-                                println!("{} {}: {}{}", status, provider, result.message, time_str);
+                                status!("{} {}: {}{}", status, provider, result.message, time_str);
                             }
                         }
-                        Err(e) => println!("❌ Provider testing failed: {}", e),
+                        Err(e) => status!("❌ Provider testing failed: {}", e),
                     }
                     return Ok(());
                 }
 
-                if let Some(template_dir) = set_template_dir {
-                    println!("📁 Template directory feature: {}", template_dir.display());
-                    println!("✅ Template directory feature implemented (placeholder)");
-                    return Ok(());
+                if let Some(template_dir) = &set_template_dir {
+                    self.config.set_template_directory(template_dir.clone())?;
+                    let prompts = load_prompt_templates(&self.config)?;
+                    status!("📁 Template directory: {}", self.config.get_template_directory()?.display());
+                    match prompts.custom().count() {
+                        0 => status!("   No prompt templates in its prompts folder yet - 'prism config --init-prompts' writes the built-in ones there"),
+                        n => status!("   {} prompt template(s) replace the built-in ones", n),
+                    }
+                    updated = true;
+                }
+
+                if init_prompts {
+                    let dir = self.config.get_template_directory()?.join("prompts");
+                    let written = write_built_in_prompts(&dir)?;
+                    status!("📝 Prompt templates in {}:", dir.display());
+                    for task in PromptTask::ALL {
+                        let path = dir.join(task.file_name());
+                        let state = if written.contains(&path) { "written" } else { "kept" };
+                        status!("   • {} ({}) - {}", task.file_name(), state, task.variables().iter().map(|v| format!("{{{}}}", v)).collect::<Vec<_>>().join(", "));
+                    }
+                    status!("Edit them to tune the AI prompts; delete one to go back to the built-in prompt.");
                 }
 
                 if updated {
                     self.config.save().await?;
-                    println!("✅ Configuration updated successfully!");
+                    status!("✅ Configuration updated successfully!");
                     self.show_config_status();
-                } else if !validate_all && !test_providers && set_template_dir.is_none() {
-                    println!("🔧 No configuration changes specified. Use --help for options or --setup for interactive configuration.");
+                } else if !validate_all && !test_providers && !init_prompts {
+                    status!("🔧 No configuration changes specified. Use --help for options or --setup for interactive configuration.");
                 }
             }
-            Commands::Validate { text, file, dir, output, story, completeness, all, format } => {
+            Commands::Validate { text, file, dir, output, story, completeness, ears, all, format, max_comment_size } => {
+                let format = format.or_else(|| self.config.output.format.clone());
                 self.print_branded_header();
+                let source_name = file.as_ref().map(|f| self.report_source_name(f));
                 let input_text = self.get_input_text(text, file, dir.clone()).await?;
                 
-                println!("✅ Running validation checks...");
+                status!("✅ Running validation checks...");
                 
                 let mut result = self.analyzer.analyze(&input_text).await?;
+                if let Some(source_name) = &source_name {
+                    result.set_source_file(source_name);
+                }
+                self.apply_baseline(&mut result, &input_text);
                 
                 if story || all {
-                    println!("📋 Validating user story format and business value...");
+                    status!("📋 Validating user story format and business value...");
                     let user_story_validation = self.analyzer.validate_user_story(&input_text);
                     result.user_story_validation = Some(user_story_validation);
                 }
                 
                 if completeness || all {
-                    println!("📊 Analyzing completeness and identifying gaps...");
+                    status!("📊 Analyzing completeness and identifying gaps...");
                     let completeness_analysis = self.analyzer.analyze_completeness(&input_text, &result.entities).await?;
                     result.completeness_analysis = Some(completeness_analysis);
                 }
+
+                if ears || all {
+                    status!("📐 Checking requirement sentences against the EARS patterns...");
+                    result.ears_validation = Some(check_ears(&input_text));
+                }
                 
+                let hint_context = HintContext {
+                    source: source_name.clone(),
+                    ai_configured: self.config.is_ai_configured(),
+                    html_report: matches!(format, Some(OutputFormat::Html)),
+                    ..Default::default()
+                };
+
                 if let Some(output_path) = output {
                     let format_to_use = format.unwrap_or(OutputFormat::Json);
-                    let output_content = match format_to_use {
-                        OutputFormat::Json => serde_json::to_string_pretty(&result)?,
-                        OutputFormat::Markdown => self.format_as_markdown(&result, &input_text),
-                        OutputFormat::Jira => self.format_as_jira(&result, &input_text),
-                        OutputFormat::Github => self.format_as_github(&result, &input_text),
-                        OutputFormat::Plain => self.format_as_plain(&result, &input_text),
-                    };
-                    
-                    let absolute_path = std::fs::canonicalize(&output_path).unwrap_or(output_path.clone());
-                    fs::write(&output_path, output_content).await?;
-                    println!("📁 Validation report saved: {}", absolute_path.display());
+                    if is_xlsx_path(&output_path) {
+                        self.save_findings_xlsx(&output_path, &result, source_name.as_deref())?;
+                    } else {
+                        let output_content = self.render_report(&result, &input_text, &format_to_use, source_name.as_deref())?;
+                        if matches!(format_to_use, OutputFormat::Github) {
+                            self.write_github_comments(&output_path, &output_content, max_comment_size).await?;
+                        } else {
+                            fs::write(&output_path, output_content).await?;
+                        }
+                    }
+                    let absolute_path = paths::absolute(&output_path);
+                    status!("📁 Validation report saved: {}", absolute_path.display());
                 } else {
-                    self.display_result_to_screen(&result, format.unwrap_or(OutputFormat::Json), &input_text).await?;
+                    self.display_result_to_screen(&result, format.unwrap_or(OutputFormat::Json), &input_text, source_name.as_deref(), max_comment_size).await?;
                 }
+                self.print_hints(&result, &input_text, &hint_context);
             }
             Commands::Trace { text, file, output, from_commit, to_commit, source_dir, test_dir, format } => {
                 self.print_branded_header();
                 
-                println!("🔍 Tracing requirements to implementation...");
+                status!("🔍 Tracing requirements to implementation...");
                 
-                if let (Some(from), Some(to)) = (&from_commit, &to_commit) {
-                    println!("📈 Git diff analysis from {} to {}", from, to);
-                    println!("⚠️  Git traceability feature coming soon!");
-                } else if let (Some(src), Some(test)) = (&source_dir, &test_dir) {
-                    println!("📁 Scanning source directory: {:?}", src);
-                    println!("🧪 Scanning test directory: {:?}", test);
-                    println!("⚠️  File traceability feature coming soon!");
+                if let Some(from) = &from_commit {
+                    let to = to_commit.as_deref().unwrap_or("HEAD");
+                    status!("📈 Git diff analysis from {} to {}", from, to);
+                    self.generate_git_trace_report(from, to, output, format).await?;
+                } else if source_dir.is_some() || test_dir.is_some() {
+                    let input_text = self.get_input_text(text, file, None).await?;
+                    self.generate_traceability_report(&input_text, source_dir.as_deref(), test_dir.as_deref(), output, format).await?;
                 } else {
-                    println!("❌ Please specify either git commits (--from-commit and --to-commit) or directories (--source-dir and --test-dir)");
+                    status!("❌ Please specify either git commits (--from-commit, optionally --to-commit) or directories (--source-dir and --test-dir)");
                 }
             }
+            Commands::Chat { file, index } => {
+                self.print_branded_header();
+                self.run_chat_session(&file, index).await?;
+            }
+            Commands::Index { dir, report, output, format } => {
+                self.print_branded_header();
+                let index = self.update_index(&dir).await?;
+
+                if report {
+                    let corpus_report = index.find_duplicates_and_conflicts();
+                    status!("🔎 Found {} conflicting and {} duplicate requirement pairs",
+                        corpus_report.conflicts.len(), corpus_report.duplicates.len());
+
+                    let output_content = match format.unwrap_or(OutputFormat::Markdown) {
+                        OutputFormat::Json => serde_json::to_string_pretty(&corpus_report)?,
+                        _ => format_corpus_report_markdown(&corpus_report, &dir),
+                    };
+
+                    if let Some(output_path) = output {
+                        fs::write(&output_path, output_content).await?;
+                        let absolute_path = paths::absolute(&output_path);
+                        status!("📁 Corpus report saved: {}", absolute_path.display());
+                    } else {
+                        println!("{}", output_content);
+                    }
+                }
+            }
+            Commands::New { output, template } => {
+                self.print_branded_header();
+                self.run_authoring_wizard(output, template).await?;
+            }
+            Commands::Hierarchy { dir, output, format } => {
+                self.print_branded_header();
+                self.generate_hierarchy_report(&dir, output, format).await?;
+            }
+            Commands::Badge { file, dir, svg, json, label } => {
+                self.print_branded_header();
+                self.write_quality_badge(file, dir, &svg, &json, &label).await?;
+            }
+            Commands::Prioritize { dir, sidecar, output, format } => {
+                self.print_branded_header();
+                self.generate_cost_of_delay_report(&dir, sidecar, output, format).await?;
+            }
+            Commands::BenchProviders { text, file, models, runs, output, format } => {
+                self.print_branded_header();
+                let input_text = self.get_input_text(text, file, None).await?;
+                self.bench_providers(&input_text, &models, runs, output, format).await?;
+            }
+            Commands::Selftest { suite, rules_only, output, format } => {
+                self.print_branded_header();
+                self.run_selftest(suite, rules_only, output, format).await?;
+            }
+            Commands::Merge { files, output, format } => {
+                self.print_branded_header();
+                self.merge_analysis_results(&files, output, format).await?;
+            }
+            Commands::Report { action: ReportAction::Diff { old, new, output, format } } => {
+                self.print_branded_header();
+                self.generate_report_diff(&old, &new, output, format).await?;
+            }
+            Commands::Projects { action: ProjectsAction::Discover { path, analyze, output, format } } => {
+                self.print_branded_header();
+                self.discover_projects(&path, analyze, output, format).await?;
+            }
+            Commands::Coverage { epic, stories, threshold, output, format } => {
+                self.print_branded_header();
+                self.generate_coverage_report(&epic, &stories, threshold.unwrap_or(DEFAULT_COVERAGE_THRESHOLD), output, format).await?;
+            }
+            Commands::Adr { requirements, adr_dir, output, format } => {
+                self.print_branded_header();
+                self.generate_adr_report(&requirements, adr_dir, output, format).await?;
+            }
+            Commands::ReleaseCheck { text, file, dir, epic, output, format } => {
+                self.print_branded_header();
+
+                let input_text = self.get_input_text(text, file, dir.clone()).await?;
+                self.generate_release_check(&input_text, epic, dir, output, format).await?;
+            }
+            Commands::Evidence { text, file, dir, source_dir, test_dir, job, output, sign } => {
+                self.print_branded_header();
+                // Fail before the analysis rather than after it
+                let signer = if sign { Some(Signer::from_config(&self.config.signing)?) } else { None };
+                let input_path = file.clone().or(dir.clone());
+                let input_text = self.get_input_text(text, file, dir).await?;
+                let bundle = self.generate_evidence_bundle(&input_text, input_path.as_deref(), source_dir.as_deref(), test_dir.as_deref(), job.as_deref(), output).await?;
+                if let Some(signer) = signer {
+                    let signature = signer.sign(&bundle).await?;
+                    status!("🔏 Signed with {}: {}", signer.tool().program(), signature.display());
+                }
+            }
+            Commands::Sign { files } => {
+                let signer = Signer::from_config(&self.config.signing)?;
+                for file in &files {
+                    let signature = signer.sign(file).await?;
+                    status!("🔏 {} signed: {}", file.display(), signature.display());
+                }
+            }
+            Commands::Verify { files } => {
+                let signer = Signer::from_config(&self.config.signing)?;
+                let mut failed = 0;
+                for file in &files {
+                    match signer.verify(file).await {
+                        Ok(report) => {
+                            status!("✅ {}: signature valid", file.display());
+                            for line in report.lines().filter(|l| l.starts_with("Trusted comment")) {
+                                status!("   {}", line);
+                            }
+                        }
+                        Err(e) => {
+                            status!("❌ {}: {:#}", file.display(), e);
+                            failed += 1;
+                        }
+                    }
+                }
+                if failed > 0 {
+                    anyhow::bail!("{} of {} file(s) failed verification", failed, files.len());
+                }
+            }
+            Commands::Demo { output_dir } => {
+                self.print_branded_header();
+                self.run_demo(&output_dir).await?;
+            }
+            Commands::Notion { database, page, tag, all_pages, output_dir, format, dry_run } => {
+                self.print_branded_header();
+                let tag = if all_pages { None } else { Some(tag.unwrap_or_else(|| self.config.notion.tag.clone())) };
+                self.run_notion_import(database, &page, tag, output_dir.as_deref(), format, dry_run).await?;
+            }
+            Commands::Import { connection, project, limit, output_dir, format } => {
+                self.print_branded_header();
+                self.run_rm_import(&connection, project, limit, output_dir.as_deref(), format).await?;
+            }
+            Commands::Serve { bind } => {
+                self.print_branded_header();
+                self.run_server(bind).await?;
+            }
+            Commands::Cache { action } => {
+                self.run_cache_command(action)?;
+            }
+            Commands::Baseline { action: BaselineAction::Create { text, file, dir, output } } => {
+                self.print_branded_header();
+                self.create_baseline(text, file, dir, output).await?;
+            }
+            Commands::Daemon { job, once } => {
+                self.print_branded_header();
+                self.run_daemon(&job, once).await?;
+            }
             Commands::Dashboard { text, file, dir, output, template, branding, executive_summary } => {
                 self.print_branded_header();
                 
+                let title = file.as_ref().or(dir.as_ref())
+                    .and_then(|path| path.file_stem())
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "Requirements".to_string());
                 let input_text = self.get_input_text(text, file, dir.clone()).await?;
                 
-                println!("📊 Generating dashboard and reports...");
+                status!("📊 Generating dashboard and reports...");
                 
                 let mut result = self.analyzer.analyze(&input_text).await?;
                 
                 result.uml_diagrams = Some(crate::analyzer::UmlDiagrams {
-                    use_case: Some(self.analyzer.generate_uml_use_case(&result.entities)),
+                    use_case: Some(self.analyzer.generate_uml_use_case(&input_text, &result.entities)),
                     sequence: Some(self.analyzer.generate_uml_sequence(&result.entities)),
                     class_diagram: Some(self.analyzer.generate_uml_class_diagram(&result.entities)),
                 });
                 
                 result.test_cases = Some(self.analyzer.generate_test_cases(&result.entities));
+                result.completeness_analysis = Some(self.analyzer.analyze_completeness(&input_text, &result.entities).await?);
+                result.nfr_suggestions = Some(self.analyzer.generate_nfr_suggestions(&input_text, &result.entities).await?);
                 
                 if executive_summary {
-                    println!("📈 Generating executive summary...");
+                    status!("📈 Generating executive summary...");
+                }
+                if let Some(template) = template {
+                    status!("💡 Dashboards use the built-in layout; template '{}' applies to reports only.", template);
                 }
                 
                 if let Some(output_path) = output {
-                    println!("📁 Dashboard will be saved to: {:?}", output_path);
-                    println!("⚠️  HTML dashboard generation coming soon!");
+                    let options = DashboardOptions {
+                        title,
+                        branding,
+                        executive_summary,
+                        generated_at: crate::locale::report_locale().date_time(&chrono::Local::now()),
+                    };
+                    fs::write(&output_path, format_dashboard_html(&result, &input_text, &options)).await?;
+                    let absolute_path = paths::absolute(&output_path);
+                    status!("📁 Dashboard saved: {}", absolute_path.display());
                 } else {
-                    println!("📊 Dashboard generation requires --output parameter");
+                    status!("📊 Dashboard generation requires --output parameter");
                 }
             }
         }
@@ -531,21 +1370,21 @@ impl App {
     pub async fn run_tui(&mut self) -> Result<()> {
         // Check if AI is configured, if not, prompt user for setup
         if !self.config.is_ai_configured() {
-            println!("🔍 Welcome to PRISM - AI-Powered Requirement Analyzer!");
-            println!("====================================================");
-            println!("This is your first time using PRISM or AI is not configured.");
-            println!("PRISM works best with AI providers for enhanced analysis.\n");
+            status!("🔍 Welcome to PRISM - AI-Powered Requirement Analyzer!");
+            status!("====================================================");
+            status!("This is your first time using PRISM or AI is not configured.");
+            status!("PRISM works best with AI providers for enhanced analysis.\n");
             
-            println!("Would you like to configure AI now for better results? (y/n): ");
+            status!("Would you like to configure AI now for better results? (y/n): ");
             let mut input = String::new();
             std::io::stdin().read_line(&mut input)?;
             
             if input.trim().to_lowercase() == "y" {
                 self.run_setup_wizard().await?;
-                println!("\n🎯 Starting PRISM TUI...");
+                status!("\n🎯 Starting PRISM TUI...");
             } else {
-                println!("📝 You can configure AI later with: prism config --setup");
-                println!("🎯 Starting PRISM TUI with built-in analysis...");
+                status!("📝 You can configure AI later with: prism config --setup");
+                status!("🎯 Starting PRISM TUI with built-in analysis...");
             }
         }
         
@@ -571,7 +1410,17 @@ impl App {
             return self.read_directory(&dir_path).await;
         }
 
-        Err(anyhow::anyhow!("No input provided. Use --text, --file, or --dir"))
+        // Piped input, e.g. `cat requirements.md | prism analyze`
+        if !io::stdin().is_terminal() {
+            let mut content = String::new();
+            io::stdin().read_to_string(&mut content)?;
+            if !content.trim().is_empty() {
+                status!("📖 Reading requirements from stdin");
+                return Ok(content);
+            }
+        }
+
+        Err(anyhow::anyhow!("No input provided. Use --text, --file, or --dir, or pipe requirements on stdin"))
     }
 
     async fn read_file(&self, path: &PathBuf) -> Result<String> {
@@ -579,30 +1428,59 @@ impl App {
             return Err(anyhow::anyhow!("File does not exist: {:?}", path));
         }
 
-        println!("📖 Reading requirements from: {}", path.display());
+        status!("📖 Reading requirements from: {}", path.display());
+        if self.document_processor.is_archive(path) {
+            let archive = self.extract_archive(path)?;
+            return self.read_directory(&archive.dir).await;
+        }
         
         // Use document processor for all file types
-        let content = self.document_processor.extract_text_from_file(path).await?;
+        let content = match self.document_processor.extract_text_from_file(path).await {
+            Err(e) if matches!(e.downcast_ref::<DocumentError>(), Some(DocumentError::PasswordRequired(_)))
+                && io::stdin().is_terminal() =>
+            {
+                let password = prompt_hidden(&format!("🔒 {} is password-protected. Password: ", path.display()))?;
+                self.document_processor.extract_text_with_password(path, &password).await?
+            }
+            result => result?,
+        };
         
-        println!("📄 Loaded {} characters from file", content.len());
+        status!("📄 Loaded {} characters from file", content.len());
         Ok(content)
     }
 
+    fn extract_archive(&self, path: &Path) -> Result<ExtractedArchive> {
+        let archive = self.document_processor.extract_archive(path)?;
+        status!(
+            "📦 Unpacked {} document(s) from {}{}",
+            archive.files,
+            path.display(),
+            if archive.skipped.is_empty() { String::new() } else { format!(" ({} unsupported entries skipped)", archive.skipped.len()) }
+        );
+        if archive.files == 0 {
+            return Err(anyhow::anyhow!("No readable files (.md, .txt, .rst, .pdf, .docx, .xlsx) found in {}", path.display()));
+        }
+        Ok(archive)
+    }
+
     async fn read_directory(&self, path: &PathBuf) -> Result<String> {
         if !path.exists() || !path.is_dir() {
             return Err(anyhow::anyhow!("Directory does not exist: {:?}", path));
         }
 
-        println!("📁 Scanning directory: {}", path.display());
+        status!("📁 Scanning directory: {}", path.display());
         let mut combined_content = String::new();
         let mut file_count = 0;
+        let ignore = IgnoreGlobs::new(&self.config.analysis.ignore)?;
+        let root = path;
 
         for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
             let path = entry.path();
-            if path.is_file() && self.document_processor.is_supported_format(path) {
+            if path.is_file() && self.document_processor.is_supported_format(path)
+                && !ignore.is_ignored(path.strip_prefix(root).unwrap_or(path)) {
                 match self.document_processor.extract_text_from_file(path).await {
                     Ok(content) => {
-                        println!("  📖 Reading: {}", path.display());
+                        status!("  📖 Reading: {}", path.display());
                         combined_content.push_str(&format!("=== {} ===\n", path.display()));
                         combined_content.push_str(&content);
                         combined_content.push_str("\n\n");
@@ -615,7 +1493,7 @@ impl App {
             }
         }
 
-        println!("📊 Loaded {} files with {} total characters", file_count, combined_content.len());
+        status!("📊 Loaded {} files with {} total characters", file_count, combined_content.len());
 
         if combined_content.is_empty() {
             return Err(anyhow::anyhow!("No readable files (.md, .txt, .rst) found in directory"));
@@ -629,483 +1507,1265 @@ impl App {
         result: &AnalysisResult,
         format: OutputFormat,
         input_text: &str,
+        source_name: Option<&str>,
+        max_comment_size: usize,
     ) -> Result<()> {
         let output_content = match format {
-            OutputFormat::Json => serde_json::to_string_pretty(result)?,
-            OutputFormat::Markdown => self.format_as_markdown(result, input_text),
-            OutputFormat::Jira => self.format_as_jira(result, input_text),
-            OutputFormat::Github => self.format_as_github(result, input_text),
-            OutputFormat::Plain => self.format_as_plain(result, input_text),
+            OutputFormat::Github => Self::split_github_comment(&format_as_github(result, input_text), max_comment_size)
+                .join(&format!("\n{}\n\n", GITHUB_COMMENT_SEPARATOR)),
+            OutputFormat::Diagnostics => format_diagnostics(result, input_text, source_name, stdout_color()),
+            OutputFormat::Pdf if io::stdout().is_terminal() => {
+                return Err(anyhow::anyhow!("A PDF report cannot be shown in the terminal - save it with --output report.pdf"));
+            }
+            _ => self.render_report(result, input_text, &format, source_name)?,
         };
 
         println!("{}", output_content);
         Ok(())
     }
 
-    fn format_as_markdown(&self, result: &AnalysisResult, input_text: &str) -> String {
-        let mut output = String::new();
-        
-        output.push_str("# 🔍 PRISM Requirement Analysis Report\n\n");
-
-        output.push_str("## 📝 Analyzed Requirement\n\n");
-        output.push_str(&format!("> {}\n\n", input_text.trim()));
-
-        output.push_str("## 📊 Analysis Summary\n\n");
-        output.push_str(&format!("- **Ambiguities Found:** {}\n", result.ambiguities.len()));
-        output.push_str(&format!("- **Actors Identified:** {}\n", result.entities.actors.len()));
-        output.push_str(&format!("- **Actions Identified:** {}\n", result.entities.actions.len()));
-        output.push_str(&format!("- **Objects Identified:** {}\n\n", result.entities.objects.len()));
-
-        output.push_str("## ⚠️ Detected Ambiguities\n\n");
-        if result.ambiguities.is_empty() {
-            output.push_str("✅ **No ambiguities detected - your requirements are clear!**\n\n");
-        } else {
-            for (i, ambiguity) in result.ambiguities.iter().enumerate() {
-                let severity_icon = match ambiguity.severity {
-                    crate::analyzer::AmbiguitySeverity::Critical => "🔴",
-                    crate::analyzer::AmbiguitySeverity::High => "🟠",
-                    crate::analyzer::AmbiguitySeverity::Medium => "🟡",
-                    crate::analyzer::AmbiguitySeverity::Low => "🟢",
-                };
-                output.push_str(&format!("### {} Issue #{}: \"{}\"\n", severity_icon, i + 1, ambiguity.text));
-                output.push_str(&format!("- **Problem:** {}\n", ambiguity.reason));
-                output.push_str(&format!("- **Severity:** {:?}\n", ambiguity.severity));
-                output.push_str("- **Suggested Improvements:**\n");
-                for suggestion in &ambiguity.suggestions {
-                    output.push_str(&format!("  - {}\n", suggestion));
-                }
-                output.push('\n');
-            }
+    /// The report for `result` in `format`, numbered with a table of
+    /// contents when `--toc` or `output.toc` asks for it.
+    fn render_report(&self, result: &AnalysisResult, input_text: &str, format: &OutputFormat, source_name: Option<&str>) -> Result<String> {
+        // The PDF lays out the numbered Markdown report
+        if matches!(format, OutputFormat::Pdf) && self.config.output.toc {
+            let markdown = add_toc(&format_as_markdown(result, input_text), &OutputFormat::Markdown);
+            return Ok(markdown_to_pdf(&markdown, &report_title(source_name)));
         }
+        let report = format_report(result, input_text, format, source_name)?;
+        Ok(if self.config.output.toc { add_toc(&report, format) } else { report })
+    }
 
-        output.push_str("## 🎯 Extracted Entities\n\n");
-        
-        output.push_str("### 👥 Actors (Who performs actions)\n");
-        if result.entities.actors.is_empty() {
-            output.push_str("- *No actors identified*\n\n");
-        } else {
-            for actor in &result.entities.actors {
-                output.push_str(&format!("- **{}**\n", actor));
+    /// Writes a github-format report, splitting it into `<stem>.partN.<ext>`
+    /// files (one per comment) when it exceeds `max_comment_size` characters.
+    async fn write_github_comments(&self, output_path: &PathBuf, content: &str, max_comment_size: usize) -> Result<()> {
+        let parts = Self::split_github_comment(content, max_comment_size);
+        fs::write(output_path, &parts[0]).await?;
+
+        if parts.len() > 1 {
+            status!("💬 Report exceeds {} characters, split into {} comments:", max_comment_size, parts.len());
+            status!("   1. {}", output_path.display());
+            let stem = output_path.file_stem().and_then(|s| s.to_str()).unwrap_or("report");
+            let extension = output_path.extension().and_then(|s| s.to_str()).unwrap_or("md");
+            for (i, part) in parts.iter().enumerate().skip(1) {
+                let part_path = output_path.with_file_name(format!("{}.part{}.{}", stem, i + 1, extension));
+                fs::write(&part_path, part).await?;
+                status!("   {}. {}", i + 1, part_path.display());
             }
-            output.push('\n');
         }
-        
-        output.push_str("### ⚡ Actions (What is being done)\n");
-        if result.entities.actions.is_empty() {
-            output.push_str("- *No actions identified*\n\n");
-        } else {
-            for action in &result.entities.actions {
-                output.push_str(&format!("- **{}**\n", action));
-            }
-            output.push('\n');
+
+        Ok(())
+    }
+
+    /// Splits a github-format report into comment-sized parts. Parts break at
+    /// `## ` section boundaries where possible; a section that is too large on
+    /// its own is broken between lines, closing and reopening any `<details>`
+    /// block it was in.
+    fn split_github_comment(content: &str, max_comment_size: usize) -> Vec<String> {
+        let size = |value: &str| value.chars().count();
+        if size(content) <= max_comment_size {
+            return vec![content.to_string()];
         }
-        
-        output.push_str("### 📦 Objects (What is being acted upon)\n");
-        if result.entities.objects.is_empty() {
-            output.push_str("- *No objects identified*\n\n");
-        } else {
-            for object in &result.entities.objects {
-                output.push_str(&format!("- **{}**\n", object));
+
+        // Leave room for the part header and any <details> tags added at a break
+        let budget = max_comment_size.saturating_sub(200).max(1);
+
+        let mut sections: Vec<String> = Vec::new();
+        for line in content.split_inclusive('\n') {
+            if line.starts_with("## ") || sections.is_empty() {
+                sections.push(String::new());
             }
-            output.push('\n');
+            sections.last_mut().unwrap().push_str(line);
         }
 
-        if let Some(uml) = &result.uml_diagrams {
-            output.push_str("## 🎨 UML Diagrams\n\n");
-            
-            if let Some(use_case) = &uml.use_case {
-                output.push_str("### Use Case Diagram\n\n");
-                output.push_str("```plantuml\n");
-                output.push_str(use_case);
-                output.push_str("\n```\n\n");
+        let mut parts: Vec<String> = Vec::new();
+        let mut part = String::new();
+        for section in sections {
+            if !part.is_empty() && size(&part) + size(&section) > budget {
+                parts.push(std::mem::take(&mut part));
             }
-            
-            if let Some(sequence) = &uml.sequence {
-                output.push_str("### Sequence Diagram\n\n");
-                output.push_str("```plantuml\n");
-                output.push_str(sequence);
-                output.push_str("\n```\n\n");
+            if size(&section) <= budget {
+                part.push_str(&section);
+                continue;
             }
-            
-            if let Some(class_diagram) = &uml.class_diagram {
-                output.push_str("### Class Diagram\n\n");
-                output.push_str("```plantuml\n");
-                output.push_str(class_diagram);
-                output.push_str("\n```\n\n");
+
+            let mut in_details = false;
+            for line in section.split_inclusive('\n') {
+                if !part.is_empty() && size(&part) + size(line) > budget {
+                    if in_details {
+                        part.push_str("\n</details>\n");
+                    }
+                    parts.push(std::mem::take(&mut part));
+                    if in_details {
+                        part.push_str("<details>\n<summary>(continued)</summary>\n\n");
+                    }
+                }
+                if line.trim_start().starts_with("<details>") {
+                    in_details = true;
+                } else if line.trim_start().starts_with("</details>") {
+                    in_details = false;
+                }
+                part.push_str(line);
             }
         }
+        if !part.is_empty() {
+            parts.push(part);
+        }
 
-        if let Some(pseudocode) = &result.pseudocode {
-            output.push_str("## Generated Pseudocode\n\n");
-            output.push_str("```\n");
-            output.push_str(pseudocode);
-            output.push_str("\n```\n\n");
+        let total = parts.len();
+        parts.into_iter()
+            .enumerate()
+            .map(|(i, part)| format!("> :page_facing_up: PRISM report, part {} of {}\n\n{}", i + 1, total, part))
+            .collect()
+    }
+
+    /// Renders ambiguity findings as a SARIF 2.1.0 log so they can be uploaded
+    /// to code scanning tools. Each result carries the finding fingerprint.
+    /// Prints the end-of-run hints for a result, unless turned off in the config.
+    fn print_hints(&self, result: &AnalysisResult, input_text: &str, context: &HintContext) {
+        if !self.config.analysis.show_hints {
+            return;
+        }
+        let hints = suggest_hints(result, input_text, context);
+        if !hints.is_empty() {
+            status!("{}", format_hints(&hints).trim_end_matches('\n'));
+        }
+    }
+
+    /// Runs the generators enabled in `plan` on an analysis result.
+    async fn run_generation_plan(&self, result: &mut AnalysisResult, input_text: &str, plan: &GenerationPlan, pseudo_lang: Option<&str>) -> Result<()> {
+        if plan.uml {
+            status!("🎨 Generating UML diagrams...");
+            let use_case = self.analyzer.generate_uml_use_case(input_text, &result.entities);
+            let sequence = self.analyzer.generate_uml_sequence(&result.entities);
+            let class_diagram = self.analyzer.generate_uml_class_diagram(&result.entities);
+            result.uml_diagrams = Some(crate::analyzer::UmlDiagrams {
+                use_case: Some(use_case),
+                sequence: Some(sequence),
+                class_diagram: Some(class_diagram),
+            });
         }
 
-        if let Some(tests) = &result.test_cases {
-            output.push_str("## Suggested Test Cases\n\n");
-            output.push_str("### Happy Path\n");
-            for test in &tests.happy_path {
-                output.push_str(&format!("- {}\n", test));
-            }
-            output.push_str("\n### Negative Cases\n");
-            for test in &tests.negative_cases {
-                output.push_str(&format!("- {}\n", test));
+        if plan.pseudo {
+            status!("📝 Generating pseudocode structure...");
+            let pseudocode = match plan.arch {
+                Some(style) => generate_architecture_pseudocode(input_text, &result.entities, pseudo_lang, style),
+                None => self.analyzer.generate_pseudocode(&result.entities, pseudo_lang),
+            };
+            result.pseudocode = Some(pseudocode);
+        }
+
+        if plan.tests {
+            status!("🧪 Generating test cases...");
+            let test_cases = self.analyzer.generate_test_cases(&result.entities);
+            let test_data = suggest_test_data(input_text, &result.entities);
+            if !test_data.is_empty() {
+                result.test_data = Some(test_data);
             }
-            output.push_str("\n### Edge Cases\n");
-            for test in &tests.edge_cases {
-                output.push_str(&format!("- {}\n", test));
+            let contracts = generate_contract_tests(input_text);
+            result.test_coverage = Some(build_test_coverage(input_text, &result.entities.actions, &test_cases, &contracts, &result.ambiguities));
+            result.test_cases = Some(test_cases);
+            if !contracts.is_empty() {
+                status!("🤝 API requirements found - adding contract tests for {} endpoints...", contracts.len());
+                result.contract_tests = Some(contracts);
             }
         }
 
-        if let Some(improved) = &result.improved_requirements {
-            output.push_str("## ✨ Improved Requirements\n\n");
-            output.push_str("```\n");
-            output.push_str(improved);
-            output.push_str("\n```\n\n");
+        if plan.gherkin {
+            status!("🥒 Generating Gherkin feature file...");
+            result.gherkin = Some(self.analyzer.generate_gherkin(input_text, &result.entities));
         }
 
-        if let Some(completeness) = &result.completeness_analysis {
-            output.push_str("## 📊 Completeness Analysis\n\n");
-            output.push_str(&format!("**Completeness Score: {:.1}%**\n\n", completeness.completeness_score));
-            
-            if !completeness.gaps_identified.is_empty() {
-                output.push_str("### Identified Gaps\n\n");
-                for gap in &completeness.gaps_identified {
-                    let priority_emoji = match gap.priority {
-                        crate::analyzer::GapPriority::Critical => "🔴",
-                        crate::analyzer::GapPriority::High => "🟠", 
-                        crate::analyzer::GapPriority::Medium => "🟡",
-                        crate::analyzer::GapPriority::Low => "🟢",
-                    };
-                    output.push_str(&format!("#### {} {} - {:?}\n\n", priority_emoji, gap.category, gap.priority));
-                    output.push_str(&format!("**Issue:** {}\n\n", gap.description));
-                    output.push_str("**Suggestions:**\n");
-                    for suggestion in &gap.suggestions {
-                        output.push_str(&format!("- {}\n", suggestion));
-                    }
-                    output.push_str("\n");
+        if plan.improve {
+            status!("✨ Generating improved requirements...");
+            match self.analyzer.generate_improved_requirements(input_text, &result.ambiguities).await {
+                Ok(improved) => {
+                    self.analyzer.set_improved_requirements(result, input_text, improved);
+                    status!("✅ Requirements improvement completed!");
+                }
+                Err(e) => {
+                    eprintln!("⚠️  Failed to generate improved requirements: {}", e);
+                    eprintln!("   Continuing with analysis results only");
                 }
             }
         }
 
-        if let Some(user_story) = &result.user_story_validation {
-            output.push_str("## ✅ User Story Validation\n\n");
-            if user_story.is_valid_format {
-                output.push_str("✅ **Valid user story format detected**\n\n");
-                output.push_str(&format!("**Business Value Score: {:.1}%**\n\n", user_story.business_value_score));
-                
-                output.push_str("### Component Analysis\n\n");
-                output.push_str(&format!("**Actor Quality:** {:.1}% - {}\n", user_story.actor_quality.score,
-                    if user_story.actor_quality.is_valid { "✅ Valid" } else { "❌ Issues found" }));
-                output.push_str(&format!("**Goal Quality:** {:.1}% - {}\n", user_story.goal_quality.score,
-                    if user_story.goal_quality.is_valid { "✅ Valid" } else { "❌ Issues found" }));
-                output.push_str(&format!("**Reason Quality:** {:.1}% - {}\n\n", user_story.reason_quality.score,
-                    if user_story.reason_quality.is_valid { "✅ Valid" } else { "❌ Issues found" }));
-            } else {
-                output.push_str("❌ **Not in valid user story format**\n\n");
-            }
-            
-            if !user_story.recommendations.is_empty() {
-                output.push_str("### Recommendations\n\n");
-                for rec in &user_story.recommendations {
-                    output.push_str(&format!("- {}\n", rec));
-                }
-                output.push_str("\n");
-            }
+        // New features processing
+        if plan.completeness {
+            status!("📊 Analyzing completeness and identifying gaps...");
+            let completeness_analysis = self.analyzer.analyze_completeness(input_text, &result.entities).await?;
+            result.completeness_analysis = Some(completeness_analysis);
         }
 
-        if let Some(nfrs) = &result.nfr_suggestions {
-            output.push_str("## 🔒 Non-Functional Requirements\n\n");
-            let mut categories = std::collections::BTreeMap::new();
-            
-            // Group NFRs by category
-            for nfr in nfrs {
-                categories.entry(&nfr.category).or_insert(Vec::new()).push(nfr);
-            }
-            
-            for (category, category_nfrs) in categories {
-                let category_emoji = match category {
-                    crate::analyzer::NfrCategory::Performance => "⚡",
-                    crate::analyzer::NfrCategory::Security => "🔒",
-                    crate::analyzer::NfrCategory::Usability => "👤",
-                    crate::analyzer::NfrCategory::Reliability => "🛡️",
-                    crate::analyzer::NfrCategory::Scalability => "📈",
-                    crate::analyzer::NfrCategory::Maintainability => "🔧",
-                    crate::analyzer::NfrCategory::Compatibility => "🔗",
-                    crate::analyzer::NfrCategory::Accessibility => "♿",
-                };
-                output.push_str(&format!("### {} {:?}\n\n", category_emoji, category));
-                
-                for nfr in category_nfrs {
-                    let priority_text = match nfr.priority {
-                        crate::analyzer::NfrPriority::MustHave => "🔴 Must Have",
-                        crate::analyzer::NfrPriority::ShouldHave => "🟠 Should Have",
-                        crate::analyzer::NfrPriority::CouldHave => "🟡 Could Have",
-                        crate::analyzer::NfrPriority::WontHave => "⚫ Won't Have",
-                    };
-                    output.push_str(&format!("**{}**\n\n", priority_text));
-                    output.push_str(&format!("**Requirement:** {}\n\n", nfr.requirement));
-                    output.push_str(&format!("**Rationale:** {}\n\n", nfr.rationale));
-                    
-                    if !nfr.acceptance_criteria.is_empty() {
-                        output.push_str("**Acceptance Criteria:**\n");
-                        for criteria in &nfr.acceptance_criteria {
-                            output.push_str(&format!("- {}\n", criteria));
-                        }
-                        output.push_str("\n");
-                    }
-                }
-            }
+        if plan.validate_story {
+            status!("✅ Validating user story format and business value...");
+            let user_story_validation = self.analyzer.validate_user_story(input_text);
+            result.user_story_validation = Some(user_story_validation);
         }
 
-        output
-    }
+        if plan.nfr {
+            status!("🔒 Generating non-functional requirement suggestions...");
+            let nfr_suggestions = self.analyzer.generate_nfr_suggestions(input_text, &result.entities).await?;
+            result.nfr_suggestions = Some(nfr_suggestions);
+        }
 
-    fn format_as_jira(&self, result: &AnalysisResult, input_text: &str) -> String {
-        let mut output = String::new();
-        
-        output.push_str("h1. 🔍 PRISM Analysis Report\n\n");
+        if plan.permissions {
+            status!("🔐 Generating actor-permission matrix...");
+            let permission_matrix = self.analyzer.generate_permission_matrix(input_text, &result.entities);
+            result.permission_matrix = Some(permission_matrix);
+        }
 
-        // Input echo section
-        output.push_str("h2. 📝 Analyzed Requirement\n");
-        output.push_str(&format!("{{quote}}\n{}\n{{quote}}\n\n", input_text.trim()));
+        if plan.suggestions {
+            status!("✏️  Generating per-finding rewrite suggestions...");
+            let suggestions = self.analyzer.generate_rewrite_suggestions(input_text, &result.ambiguities).await?;
+            result.rewrite_suggestions = Some(suggestions);
+        }
 
-        // Summary section
-        output.push_str("h2. 📊 Analysis Summary\n");
-        output.push_str(&format!("* Ambiguities Found: {}\n", result.ambiguities.len()));
-        output.push_str(&format!("* Actors Identified: {}\n", result.entities.actors.len()));
-        output.push_str(&format!("* Actions Identified: {}\n", result.entities.actions.len()));
-        output.push_str(&format!("* Objects Identified: {}\n", result.entities.objects.len()));
-        output.push_str("\n");
+        if plan.dependencies {
+            status!("🔗 Mapping requirement dependencies...");
+            result.dependency_graph = Some(build_dependency_graph(input_text, &result.entities));
+        }
 
-        // Entities section
-        output.push_str("h2. 🎯 Extracted Entities\n");
-        output.push_str("h3. 👥 Actors (Who)\n");
-        if result.entities.actors.is_empty() {
-            output.push_str("* No actors identified\n");
-        } else {
-            for actor in &result.entities.actors {
-                output.push_str(&format!("* {}\n", actor));
+        if plan.domain_model {
+            status!("🏛️  Proposing a domain model...");
+            result.domain_model = Some(build_domain_model(input_text, &result.entities));
+        }
+
+        if plan.smart {
+            status!("🎯 Scoring requirements against the SMART criteria...");
+            result.smart_analysis = Some(analyze_smart(input_text));
+        }
+
+        if plan.event_storming {
+            status!("🟧 Laying out an event-storming board...");
+            let links = self.analyzer.associate_actors_with_actions(input_text, &result.entities);
+            result.event_storming = Some(build_event_storming(input_text, &result.entities, &links));
+        }
+
+        if plan.load_tests {
+            status!("🏋️ Writing load tests for the performance requirements...");
+            let scenarios = generate_load_scenarios(input_text, result.nfr_suggestions.as_deref().unwrap_or_default());
+            if scenarios.is_empty() {
+                status!("   No response time, throughput, concurrency or error rate limits found");
             }
+            result.load_tests = Some(scenarios);
         }
-        
-        output.push_str("\nh3. ⚡ Actions (What)\n");
-        if result.entities.actions.is_empty() {
-            output.push_str("* No actions identified\n");
-        } else {
-            for action in &result.entities.actions {
-                output.push_str(&format!("* {}\n", action));
+
+        if plan.security_tests {
+            status!("🛡️  Writing security tests for the security-relevant requirements...");
+            let tests = generate_security_tests(input_text, &result.entities.actions, result.nfr_suggestions.as_deref().unwrap_or_default(), &result.ambiguities);
+            if tests.is_empty() {
+                status!("   No authentication, authorization, user input, payment or sensitive data requirements found");
             }
+            result.security_tests = Some(tests);
         }
-        
-        output.push_str("\nh3. 📦 Objects (What On)\n");
-        if result.entities.objects.is_empty() {
-            output.push_str("* No objects identified\n");
-        } else {
-            for object in &result.entities.objects {
-                output.push_str(&format!("* {}\n", object));
+
+        if plan.reviewers {
+            status!("👀 Suggesting reviewers...");
+            result.reviewer_suggestions = Some(self.analyzer.suggest_reviewers(input_text, result));
+        }
+
+        Ok(())
+    }
+
+    /// `prism demo`: every generator and output format over the bundled
+    /// samples, with a copy of this app that has AI switched off.
+    async fn run_demo(&self, output_dir: &Path) -> Result<()> {
+        let mut config = self.config.clone();
+        config.llm.api_key = None;
+        let demo = App { config, analyzer: self.analyzer.without_ai(), document_processor: DocumentProcessor::new(), tracer: None, cancel: self.cancel.clone(), no_banner: self.no_banner, paths_relative: self.paths_relative, baseline: None };
+        let plan = GenerationPlan {
+            uml: true,
+            pseudo: true,
+            tests: true,
+            gherkin: true,
+            improve: true,
+            nfr: true,
+            completeness: true,
+            validate_story: true,
+            permissions: true,
+            suggestions: true,
+            dependencies: true,
+            reviewers: true,
+            domain_model: true,
+            smart: true,
+            event_storming: true,
+            load_tests: true,
+            security_tests: true,
+            min_severity: None,
+            arch: None,
+        };
+
+        status!("🎬 Running the full pipeline on {} bundled sample requirements (built-in analysis, no AI needed)...", DEMO_SAMPLES.len());
+        let mut outputs = Vec::new();
+        for sample in DEMO_SAMPLES {
+            status!("\n🔍 Sample: {} - {}", sample.name, sample.description);
+            let sample_dir = output_dir.join(sample.name);
+            fs::create_dir_all(&sample_dir).await?;
+            let source = sample_dir.join(format!("{}.md", sample.name));
+            fs::write(&source, sample.text).await?;
+
+            let mut result = demo.analyzer.analyze(sample.text).await?;
+            demo.run_generation_plan(&mut result, sample.text, &plan, None).await?;
+
+            for format in DEMO_FORMATS {
+                let content = self.render_report(&result, sample.text, &format, Some(&source.display().to_string()))?;
+                fs::write(sample_dir.join(demo_report_name(&format)), content).await?;
             }
+            status!("📁 {} reports saved in {}", DEMO_FORMATS.len(), sample_dir.display());
+
+            demo.save_individual_artifacts(&result, &sample_dir.join(sample.name).to_string_lossy(), sample.text).await?;
+            outputs.push(DemoOutput {
+                sample,
+                findings: result.ambiguities.len(),
+                artifact_index: format!("{}_Index.md", sample.name),
+            });
         }
-        output.push_str("\n");
 
-        // Ambiguities section
-        output.push_str("h2. ⚠️ Detected Ambiguities\n");
-        if result.ambiguities.is_empty() {
-            output.push_str("✅ *No ambiguities detected - your requirements are clear!*\n\n");
+        let readme = output_dir.join("README.md");
+        fs::write(&readme, format_demo_readme(&outputs)).await?;
+        let absolute_path = paths::absolute(&readme);
+        status!("\n🎉 Demo complete! Start with the overview: {}", absolute_path.display());
+        status!("💡 Try it on your own requirements: prism analyze --file requirements.md --preset full --format html --output report.html");
+        Ok(())
+    }
+
+    /// Pulls requirement pages from Notion, analyzes each one and writes the
+    /// summary back to `notion.summary_property` when configured.
+    async fn run_notion_import(
+        &self,
+        database: Option<String>,
+        page_refs: &[String],
+        tag: Option<String>,
+        output_dir: Option<&Path>,
+        format: Option<OutputFormat>,
+        dry_run: bool,
+    ) -> Result<()> {
+        let settings = &self.config.notion;
+        let token = settings.token().ok_or_else(|| anyhow::anyhow!(
+            "No Notion token configured. Set notion.token in ~/.prism/config.yml or the NOTION_TOKEN environment variable"
+        ))?;
+        let client = NotionClient::new(token);
+
+        let (source, pages) = if page_refs.is_empty() {
+            let database = database.or_else(|| settings.database_id.clone()).ok_or_else(|| anyhow::anyhow!(
+                "No Notion database given. Use --database, --page or set notion.database_id in ~/.prism/config.yml"
+            ))?;
+            let database_id = notion_id(&database).ok_or_else(|| anyhow::anyhow!("Not a Notion database ID or URL: {}", database))?;
+            match &tag {
+                Some(tag) => status!("📓 Querying Notion database {} for pages with {} = {}", database_id, settings.tag_property, tag),
+                None => status!("📓 Querying every page of Notion database {}", database_id),
+            }
+            let filter = tag.as_deref().map(|tag| (settings.tag_property.as_str(), tag));
+            let pages = client.query_database(&database_id, filter).await?;
+            (format!("Notion database {}", database_id), pages)
         } else {
-            for (i, ambiguity) in result.ambiguities.iter().enumerate() {
-                let severity_icon = match ambiguity.severity {
-                    crate::analyzer::AmbiguitySeverity::Critical => "🔴",
-                    crate::analyzer::AmbiguitySeverity::High => "🟠", 
-                    crate::analyzer::AmbiguitySeverity::Medium => "🟡",
-                    crate::analyzer::AmbiguitySeverity::Low => "🟢",
-                };
-                output.push_str(&format!("h3. {} Issue #{}: \"{}\"\n", severity_icon, i + 1, ambiguity.text));
-                output.push_str(&format!("* *Problem:* {}\n", ambiguity.reason));
-                output.push_str(&format!("* *Severity:* {:?}\n", ambiguity.severity));
-                output.push_str("* *Suggested Improvements:*\n");
-                for suggestion in &ambiguity.suggestions {
-                    output.push_str(&format!("** {}\n", suggestion));
-                }
-                output.push('\n');
+            let mut pages = Vec::new();
+            for page_ref in page_refs {
+                let page_id = notion_id(page_ref).ok_or_else(|| anyhow::anyhow!("Not a Notion page ID or URL: {}", page_ref))?;
+                pages.push(client.page(&page_id).await?);
             }
+            ("Notion pages".to_string(), pages)
+        };
+
+        if pages.is_empty() {
+            status!("⚠️  No requirement pages found");
+            return Ok(());
         }
+        status!("📋 Found {} page(s)\n", pages.len());
 
-        // Test cases section (only if generated)
-        if let Some(tests) = &result.test_cases {
-            output.push_str("h2. ✅ Suggested Test Cases\n");
-            output.push_str("h3. 😊 Happy Path Tests\n");
-            if tests.happy_path.is_empty() {
-                output.push_str("* No happy path tests generated\n");
-            } else {
-                for test in &tests.happy_path {
-                    output.push_str(&format!("- [ ] {}\n", test));
-                }
-            }
-            
-            output.push_str("\nh3. ❌ Negative Test Cases\n");
-            if tests.negative_cases.is_empty() {
-                output.push_str("* No negative test cases generated\n");
-            } else {
-                for test in &tests.negative_cases {
-                    output.push_str(&format!("- [ ] {}\n", test));
+        let write_back = settings.summary_property.as_deref().filter(|_| !dry_run);
+        let format = format.unwrap_or(OutputFormat::Markdown);
+        if let Some(dir) = output_dir {
+            fs::create_dir_all(dir).await?;
+        }
+
+        let mut taken = HashSet::new();
+        let mut index_entries = Vec::new();
+        let mut failed = 0;
+        for (n, page) in pages.iter().enumerate() {
+            status!("[{}/{}] {}", n + 1, pages.len(), page.title);
+            let analyzed = async {
+                let text = client.page_markdown(page).await?;
+                let result = self.analyzer.analyze(&text).await?;
+                Ok::<_, anyhow::Error>((text, result))
+            }.await;
+            let (text, result) = match analyzed {
+                Ok(analyzed) => analyzed,
+                Err(e) => {
+                    status!("   ❌ {}", e);
+                    failed += 1;
+                    continue;
                 }
+            };
+
+            let summary = format_notion_summary(&result);
+            status!("   {}", summary);
+
+            if let Some(dir) = output_dir {
+                let report = unique_path(dir.join(page.file_name(report_extension(&format))), &mut taken);
+                let source_name = if page.url.is_empty() { &page.title } else { &page.url };
+                fs::write(&report, self.render_report(&result, &text, &format, Some(source_name))?).await?;
+                index_entries.push(BatchIndexEntry {
+                    source: page.title.clone(),
+                    report: relative_name(dir, &report),
+                    findings: Some(result.ambiguities.len()),
+                    findings_per_100_words: Some(result.metrics.findings_per_100_words),
+                });
             }
-            
-            output.push_str("\nh3. 🔍 Edge Case Tests\n");
-            if tests.edge_cases.is_empty() {
-                output.push_str("* No edge case tests generated\n");
-            } else {
-                for test in &tests.edge_cases {
-                    output.push_str(&format!("- [ ] {}\n", test));
+
+            if let Some(property) = write_back {
+                match client.update_summary(&page.id, property, &summary).await {
+                    Ok(()) => status!("   ✍️  Summary written to '{}'", property),
+                    Err(e) => status!("   ⚠️  Could not write the summary back: {}", e),
                 }
             }
         }
 
-        output
+        if let Some(dir) = output_dir {
+            let index_path = dir.join("index.md");
+            fs::write(&index_path, format_batch_index_markdown(Path::new(&source), &index_entries)).await?;
+            let absolute_path = paths::absolute(&index_path);
+            status!("\n📁 Index of {} report(s) saved: {}", index_entries.len(), absolute_path.display());
+        }
+        status!("\n✅ Analyzed {} of {} Notion page(s)", pages.len() - failed, pages.len());
+        if settings.summary_property.is_none() {
+            status!("💡 Set notion.summary_property in ~/.prism/config.yml to write each result back to its page");
+        }
+        Ok(())
     }
 
-    fn format_as_github(&self, result: &AnalysisResult, input_text: &str) -> String {
-        let mut output = String::new();
-        
-        output.push_str("# Requirement Analysis Report\n\n");
-
-        if !result.ambiguities.is_empty() {
-            output.push_str("## :warning: Detected Ambiguities\n\n");
-            for ambiguity in &result.ambiguities {
-                let emoji = match ambiguity.severity {
-                    crate::analyzer::AmbiguitySeverity::Critical => ":red_circle:",
-                    crate::analyzer::AmbiguitySeverity::High => ":orange_circle:",
-                    crate::analyzer::AmbiguitySeverity::Medium => ":yellow_circle:",
-                    crate::analyzer::AmbiguitySeverity::Low => ":green_circle:",
-                };
-                output.push_str(&format!("### {} {}\n", emoji, ambiguity.text));
-                output.push_str(&format!("**Reason:** {}\n\n", ambiguity.reason));
-                output.push_str("**Suggestions:**\n");
-                for suggestion in &ambiguity.suggestions {
-                    output.push_str(&format!("- {}\n", suggestion));
-                }
-                output.push('\n');
+    /// Pulls requirements from a configured requirements management tool and
+    /// reports findings and a quality score for each one.
+    async fn run_rm_import(
+        &self,
+        name: &str,
+        project: Option<String>,
+        limit: Option<usize>,
+        output_dir: Option<&Path>,
+        format: Option<OutputFormat>,
+    ) -> Result<()> {
+        let connection = self.config.rm_tools.get(name).cloned().ok_or_else(|| {
+            let known: Vec<&str> = self.config.rm_tools.keys().map(|k| k.as_str()).collect();
+            if known.is_empty() {
+                anyhow::anyhow!("No connection '{}' - add it under rm_tools in ~/.prism/config.yml", name)
+            } else {
+                anyhow::anyhow!("No connection '{}' under rm_tools in ~/.prism/config.yml (configured: {})", name, known.join(", "))
             }
+        })?;
+        let tool = connection.tool;
+        let project = project.or_else(|| connection.project.clone());
+        let token = connection.token(name);
+        let client = RmClient::new(connection, token);
+
+        match &project {
+            Some(project) => status!("📥 Pulling requirements from {} project {}", tool.name(), project),
+            None => status!("📥 Pulling requirements from {}", tool.name()),
+        }
+        let requirements = client.fetch_requirements(project.as_deref(), limit).await?;
+        if requirements.is_empty() {
+            status!("⚠️  No requirements found - check the connection's query and fields mapping");
+            return Ok(());
         }
+        status!("📋 Found {} requirement(s)\n", requirements.len());
 
-        output.push_str("## :mag: Extracted Entities\n\n");
-        output.push_str(&format!("**:bust_in_silhouette: Actors:** {}\n\n", result.entities.actors.join(", ")));
-        output.push_str(&format!("**:zap: Actions:** {}\n\n", result.entities.actions.join(", ")));
-        output.push_str(&format!("**:package: Objects:** {}\n\n", result.entities.objects.join(", ")));
+        let format = format.unwrap_or(OutputFormat::Markdown);
+        if let Some(dir) = output_dir {
+            fs::create_dir_all(dir).await?;
+        }
 
-        if let Some(tests) = &result.test_cases {
-            output.push_str("## :white_check_mark: Test Cases Checklist\n\n");
-            output.push_str("### Happy Path\n");
-            for test in &tests.happy_path {
-                output.push_str(&format!("- [ ] {}\n", test));
-            }
-            output.push_str("\n### Negative Cases\n");
-            for test in &tests.negative_cases {
-                output.push_str(&format!("- [ ] {}\n", test));
-            }
-            output.push_str("\n### Edge Cases\n");
-            for test in &tests.edge_cases {
-                output.push_str(&format!("- [ ] {}\n", test));
+        let mut taken = HashSet::new();
+        let mut index_entries = Vec::new();
+        let mut scores = Vec::new();
+        for (n, requirement) in requirements.iter().enumerate() {
+            let text = requirement.analysis_text();
+            let result = self.analyzer.analyze(&text).await?;
+            let score = QualityScore::from_result(&result);
+            status!(
+                "[{}/{}] {} {} - {} finding(s), score {:.0}",
+                n + 1, requirements.len(), requirement.id, requirement.title, result.ambiguities.len(), score.score
+            );
+
+            if let Some(dir) = output_dir {
+                let report = unique_path(dir.join(requirement.file_name(report_extension(&format))), &mut taken);
+                fs::write(&report, self.render_report(&result, &text, &format, Some(&requirement.id))?).await?;
+                index_entries.push(BatchIndexEntry {
+                    source: format!("{} {}", requirement.id, requirement.title),
+                    report: relative_name(dir, &report),
+                    findings: Some(result.ambiguities.len()),
+                    findings_per_100_words: Some(result.metrics.findings_per_100_words),
+                });
             }
+            scores.push(score.score);
         }
 
-        output
+        let average = scores.iter().sum::<f32>() / scores.len() as f32;
+        let below = scores.iter().filter(|s| **s < LOW_QUALITY_SCORE).count();
+        status!(
+            "\n📊 Average quality score: {:.0}/100 ({} of {} requirement(s) below {:.0})",
+            average, below, scores.len(), LOW_QUALITY_SCORE
+        );
+
+        if let Some(dir) = output_dir {
+            let source = format!("{} {}", tool.name(), project.as_deref().unwrap_or(name));
+            let index_path = dir.join("index.md");
+            fs::write(&index_path, format_batch_index_markdown(Path::new(&source), &index_entries)).await?;
+            let absolute_path = paths::absolute(&index_path);
+            status!("📁 Index of {} report(s) saved: {}", index_entries.len(), absolute_path.display());
+        }
+        Ok(())
     }
 
-    fn format_as_plain(&self, result: &AnalysisResult, input_text: &str) -> String {
-        let mut output = String::new();
-        
-        output.push_str("REQUIREMENT ANALYSIS REPORT\n");
-        output.push_str("===========================\n\n");
-
-        output.push_str("DETECTED AMBIGUITIES:\n");
-        for (i, ambiguity) in result.ambiguities.iter().enumerate() {
-            output.push_str(&format!("{}. {}\n", i + 1, ambiguity.text));
-            output.push_str(&format!("   Reason: {}\n", ambiguity.reason));
-            output.push_str(&format!("   Severity: {:?}\n", ambiguity.severity));
-            output.push_str("   Suggestions:\n");
-            for suggestion in &ambiguity.suggestions {
-                output.push_str(&format!("   - {}\n", suggestion));
+    async fn run_daemon(&self, only: &[String], once: bool) -> Result<()> {
+        let daemon = &self.config.daemon;
+        if daemon.jobs.is_empty() {
+            return Err(anyhow::anyhow!("No scheduled analyses - add jobs under daemon in ~/.prism/config.yml"));
+        }
+        if let Some(unknown) = only.iter().find(|name| !daemon.jobs.iter().any(|job| &job.name == *name)) {
+            return Err(anyhow::anyhow!("No job '{}' under daemon in ~/.prism/config.yml", unknown));
+        }
+        let jobs: Vec<&ScheduledAnalysis> = daemon.jobs.iter()
+            .filter(|job| only.is_empty() || only.contains(&job.name))
+            .collect();
+        let schedules = jobs.iter()
+            .map(|job| CronSchedule::parse(&job.schedule).map_err(|e| anyhow::anyhow!("Job '{}': {}", job.name, e)))
+            .collect::<Result<Vec<_>>>()?;
+        let history = AnalysisHistory::new(match &daemon.history_path {
+            Some(path) => path.clone(),
+            None => AnalysisHistory::default_path()?,
+        });
+        status!("🗓️  {} scheduled job(s), history in {}", jobs.len(), history.path().display());
+
+        let metrics = Arc::new(Metrics::new());
+        let analyzer = self.analyzer.clone().with_metrics(metrics.clone());
+        if once {
+            for job in &jobs {
+                self.run_scheduled_job(job, &history, &analyzer, &metrics).await;
             }
-            output.push('\n');
+            return Ok(());
         }
 
-        output.push_str("EXTRACTED ENTITIES:\n");
-        output.push_str(&format!("Actors: {}\n", result.entities.actors.join(", ")));
-        output.push_str(&format!("Actions: {}\n", result.entities.actions.join(", ")));
-        output.push_str(&format!("Objects: {}\n\n", result.entities.objects.join(", ")));
+        if let Some(address) = &daemon.metrics_bind {
+            let listener = std::net::TcpListener::bind(address)
+                .map_err(|e| anyhow::anyhow!("Could not serve metrics on {}: {}", address, e))?;
+            status!("📈 Metrics on http://{}/metrics", listener.local_addr()?);
+            let metrics = metrics.clone();
+            tokio::spawn(async move {
+                if let Err(e) = crate::server::serve_metrics(listener, metrics).await {
+                    eprintln!("❌ Metrics endpoint stopped: {}", e);
+                }
+            });
+        }
 
-        if let Some(tests) = &result.test_cases {
-            output.push_str("SUGGESTED TEST CASES:\n");
-            output.push_str("Happy Path:\n");
-            for test in &tests.happy_path {
-                output.push_str(&format!("- {}\n", test));
-            }
-            output.push_str("\nNegative Cases:\n");
-            for test in &tests.negative_cases {
-                output.push_str(&format!("- {}\n", test));
-            }
-            output.push_str("\nEdge Cases:\n");
-            for test in &tests.edge_cases {
-                output.push_str(&format!("- {}\n", test));
+        loop {
+            let now = chrono::Local::now().naive_local();
+            let next_runs: Vec<Option<chrono::NaiveDateTime>> = schedules.iter().map(|s| s.next_after(now)).collect();
+            let due = next_runs.iter().flatten().min().copied()
+                .ok_or_else(|| anyhow::anyhow!("None of the job schedules will run again"))?;
+            let due_jobs: Vec<&ScheduledAnalysis> = jobs.iter().zip(&next_runs)
+                .filter(|(_, next)| **next == Some(due))
+                .map(|(job, _)| *job)
+                .collect();
+            let names: Vec<&str> = due_jobs.iter().map(|job| job.name.as_str()).collect();
+            status!("\n💤 Next run: {} at {}", names.join(", "), due.format("%Y-%m-%d %H:%M"));
+
+            tokio::time::sleep((due - now).to_std().unwrap_or_default()).await;
+            for job in due_jobs {
+                self.run_scheduled_job(job, &history, &analyzer, &metrics).await;
             }
         }
+    }
 
-        output
+    /// Analyzes a job's requirements, records the run and notifies when it regressed.
+    /// Failures are reported so the daemon keeps running.
+    async fn run_scheduled_job(&self, job: &ScheduledAnalysis, history: &AnalysisHistory, analyzer: &Analyzer, metrics: &Metrics) {
+        status!("\n▶️  {}: analyzing {}", job.name, job.path.display());
+        let mut span = start_span(&self.tracer, "prism.daemon.job", SpanKind::Internal);
+        if let Some(span) = &mut span {
+            span.set_attribute("prism.job", job.name.as_str());
+        }
+        match instrument(span.as_ref(), self.analyze_scheduled_job(job, history, analyzer, metrics)).await {
+            Ok(score) => metrics.record_daemon_run(&job.name, Some(score as f64)),
+            Err(e) => {
+                if let Some(span) = &mut span {
+                    span.set_error(&e);
+                }
+                metrics.record_daemon_run(&job.name, None);
+                eprintln!("❌ {} failed: {}", job.name, e);
+            }
+        }
+        drop(span);
+        if let Some(tracer) = &self.tracer {
+            if let Err(e) = tracer.flush().await {
+                eprintln!("⚠️  Traces not exported: {}", e);
+            }
+        }
+    }
+
+    /// Runs a job and returns its quality score.
+    async fn analyze_scheduled_job(&self, job: &ScheduledAnalysis, history: &AnalysisHistory, analyzer: &Analyzer, metrics: &Metrics) -> Result<f32> {
+        let files = if job.path.is_dir() {
+            self.read_supported_files(&job.path).await?
+        } else {
+            let name = PathBuf::from(job.path.file_name().unwrap_or_default());
+            vec![(name, self.read_file(&job.path).await?)]
+        };
+        let format = job.format.clone().unwrap_or(OutputFormat::Markdown);
+
+        let mut scores = Vec::new();
+        let mut critical_count = 0;
+        let mut word_count = 0;
+        for (file, text) in &files {
+            let started = std::time::Instant::now();
+            let result = analyzer.analyze(text).await;
+            metrics.record_analysis("daemon", result.is_ok(), started.elapsed());
+            let result = result?;
+            critical_count += result.ambiguities.iter().filter(|a| a.severity == AmbiguitySeverity::Critical).count();
+            word_count += result.metrics.word_count;
+            scores.push(QualityScore::from_result(&result));
+
+            if let Some(dir) = &job.output_dir {
+                let report_name = format!("{}_analysis.{}", file.file_stem().unwrap_or_default().to_string_lossy(), report_extension(&format));
+                let report = dir.join(file.parent().unwrap_or(Path::new(""))).join(report_name);
+                if let Some(parent) = report.parent() {
+                    fs::create_dir_all(parent).await?;
+                }
+                fs::write(&report, self.render_report(&result, text, &format, Some(&file.to_string_lossy()))?).await?;
+            }
+        }
+
+        let total = QualityScore::total(&scores);
+        let entry = HistoryEntry::new(&job.name, chrono::Utc::now(), files.len(), &total, critical_count, word_count);
+        let previous = history.last_run(&job.name)?;
+        history.append(&entry)?;
+        status!(
+            "📊 {}: score {:.0}/100, {} finding(s) ({} critical, {:.1} per 100 words) in {} file(s)",
+            job.name, entry.score, entry.finding_count, entry.critical_count, entry.density, entry.files
+        );
+
+        let Some(previous) = previous else {
+            return Ok(entry.score);
+        };
+        let thresholds = job.thresholds.as_ref().unwrap_or(&self.config.daemon.thresholds);
+        let reasons = detect_regression(&previous, &entry, thresholds);
+        if reasons.is_empty() {
+            status!("✅ No regression since {}", previous.timestamp.format("%Y-%m-%d %H:%M UTC"));
+            return Ok(entry.score);
+        }
+
+        let message = format_regression_notification(&previous, &entry, &reasons);
+        status!("{}", message);
+        if let Some(url) = &self.config.daemon.webhook_url {
+            let response = reqwest::Client::new().post(url).json(&serde_json::json!({ "text": message })).send().await?;
+            if !response.status().is_success() {
+                return Err(anyhow::anyhow!("Regression notification failed ({})", response.status()));
+            }
+            status!("📣 Regression notification sent");
+        }
+        Ok(entry.score)
+    }
+
+    async fn run_server(&self, bind: Option<String>) -> Result<()> {
+        let address = bind.unwrap_or_else(|| self.config.server.bind.clone());
+        let listener = std::net::TcpListener::bind(&address)
+            .map_err(|e| anyhow::anyhow!("Could not listen on {}: {}", address, e))?;
+        let webhook = &self.config.server.webhook;
+
+        status!("🌐 PRISM server listening on http://{}", listener.local_addr()?);
+        status!("  • GET  /health");
+        status!("  • POST /analyze");
+        status!("  • GET  /usage");
+        status!("  • GET  /metrics  (Prometheus)");
+        if webhook.secret().is_some() {
+            status!("  • POST /webhook  (GitHub and GitLab push events)");
+        }
+        if self.config.server.api_keys.is_empty() {
+            status!("⚠️  No API keys configured - anyone who can reach the server can run analyses");
+        } else {
+            status!("🔑 {} API key(s) accepted", self.config.server.api_keys.len());
+        }
+        if webhook.secret().is_none() {
+            status!("⚠️  /webhook is disabled until server.webhook.secret is set, so only your repositories can trigger analyses");
+        }
+        if !webhook.paths.is_empty() {
+            status!("📂 Requirement files under: {}", webhook.paths.join(", "));
+        }
+
+        crate::server::serve(listener, Arc::new(ServerState::new(self.config.clone())?)).await
+    }
+
+    async fn write_quality_badge(&self, file: Option<PathBuf>, dir: Option<PathBuf>, svg: &Path, json: &Path, label: &str) -> Result<()> {
+        let files = match (file, dir) {
+            (Some(file), None) => vec![(file.clone(), self.read_file(&file).await?)],
+            (None, Some(dir)) => self.read_supported_files(&dir).await?,
+            _ => return Err(anyhow::anyhow!("Give either --file or --dir")),
+        };
+
+        status!("🔍 Scoring {} requirement file(s)...", files.len());
+        let mut scores = Vec::new();
+        for (_, text) in &files {
+            scores.push(QualityScore::from_result(&self.analyzer.analyze(text).await?));
+        }
+        let total = QualityScore::total(&scores);
+
+        for path in [svg, json] {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                fs::create_dir_all(parent).await?;
+            }
+        }
+        fs::write(svg, format_badge_svg(label, &total)).await?;
+        fs::write(json, serde_json::to_string_pretty(&format_badge_json(label, &total))?).await?;
+
+        status!("🏅 {}: {} ({} finding(s) in {} requirement(s))", label, badge_message(&total), total.finding_count, total.requirement_count);
+        status!("🖼️  Badge saved: {}", svg.display());
+        status!("📄 shields.io endpoint saved: {}", json.display());
+        status!("💡 In your README: ![{}]({})", label, svg.display());
+        Ok(())
+    }
+
+    /// `prism prioritize`: cost of delay from front matter or the sidecar
+    /// against the quality score of each document.
+    async fn generate_cost_of_delay_report(&self, dir: &PathBuf, sidecar: Option<PathBuf>, output: Option<PathBuf>, format: Option<OutputFormat>) -> Result<()> {
+        let sidecar = sidecar.or_else(|| Some(dir.join(SIDECAR_NAME)).filter(|p| p.is_file()));
+        let annotations = match &sidecar {
+            Some(path) => {
+                let content = fs::read_to_string(path).await
+                    .map_err(|e| anyhow::anyhow!("Could not read cost-of-delay sidecar {}: {}", path.display(), e))?;
+                parse_sidecar(&content)?
+            }
+            None => BTreeMap::new(),
+        };
+
+        let files = self.read_supported_files(dir).await?;
+        status!("💸 Scoring {} requirement file(s) against their cost of delay...", files.len());
+        let mut documents = Vec::new();
+        for (path, content) in &files {
+            let file = path.display().to_string().replace('\\', "/");
+            let annotation = annotations.get(&file).cloned().or_else(|| front_matter_annotation(content));
+            let (_, body) = split_front_matter(content);
+            let result = self.analyzer.analyze(body).await?;
+            documents.push(PrioritizedDocument {
+                file,
+                cost_of_delay: annotation.and_then(|a| a.total()),
+                quality: QualityScore::from_result(&result),
+            });
+        }
+        let report = CostOfDelayReport::new(documents);
+        let fix_first = report.annotated().filter(|d| report.quadrant(d) == Some(Quadrant::FixFirst)).count();
+        status!("📊 {} of {} file(s) annotated, {} to fix first", report.annotated().count(), report.documents.len(), fix_first);
+
+        let output_content = match format.unwrap_or(OutputFormat::Markdown) {
+            OutputFormat::Json => serde_json::to_string_pretty(&report)?,
+            OutputFormat::Html => format_cost_of_delay_html(dir, &report),
+            _ => format_cost_of_delay_markdown(dir, &report),
+        };
+
+        if let Some(output_path) = output {
+            fs::write(&output_path, output_content).await?;
+            let absolute_path = paths::absolute(&output_path);
+            status!("📁 Cost-of-delay report saved: {}", absolute_path.display());
+        } else {
+            println!("{}", output_content);
+        }
+        Ok(())
+    }
+
+    /// `prism projects discover`: lists the projects of a monorepo and, with
+    /// `analyze`, scores each with its own `.prism.yml` settings.
+    async fn discover_projects(&self, repo: &Path, analyze: bool, output: Option<PathBuf>, format: Option<OutputFormat>) -> Result<()> {
+        status!("🗂️  Discovering requirement projects in: {}", repo.display());
+        let projects = discover_projects(repo, &self.config.projects.conventions)?;
+        if projects.is_empty() {
+            return Err(anyhow::anyhow!(
+                "No projects found: add a {} to each project, or keep requirements in one of: {}",
+                PROJECT_MARKER,
+                self.config.projects.conventions.join(", ")
+            ));
+        }
+        let files: Vec<Vec<PathBuf>> = (0..projects.len())
+            .map(|i| project_files(repo, &projects, i, |path| self.document_processor.is_supported_format(path)))
+            .collect();
+        status!("📦 Found {} project(s)", projects.len());
+
+        let json = matches!(format, Some(OutputFormat::Json));
+        let output_content = if !analyze {
+            if json {
+                let list: Vec<_> = projects.iter().zip(&files).map(|(project, files)| serde_json::json!({
+                    "name": project.name,
+                    "root": project.root,
+                    "discovered_by": project.discovered_by,
+                    "requirement_dirs": project.requirement_dirs,
+                    "files": files,
+                })).collect();
+                serde_json::to_string_pretty(&list)?
+            } else {
+                let counts: Vec<usize> = files.iter().map(|f| f.len()).collect();
+                format_projects_list(&projects, &counts)
+            }
+        } else {
+            let mut summaries = Vec::new();
+            for (project, files) in projects.iter().zip(&files) {
+                status!("🔍 {}: analyzing {} file(s)...", project.name, files.len());
+                let analyzer = self.analyzer.clone().with_config(project.effective_config(&self.config));
+                let mut results = Vec::new();
+                let mut failed = 0;
+                for file in files {
+                    let analysis = match self.document_processor.extract_text_from_file(repo.join(file)).await {
+                        Ok(text) => analyzer.analyze(&text).await,
+                        Err(e) => Err(e),
+                    };
+                    match analysis {
+                        Ok(mut result) => {
+                            if let Some(min_severity) = project.config.min_severity {
+                                result.retain_min_severity(min_severity);
+                            }
+                            results.push(result);
+                        }
+                        Err(e) => {
+                            eprintln!("⚠️  Could not analyze {}: {}", file.display(), e);
+                            failed += 1;
+                        }
+                    }
+                }
+                summaries.push(ProjectSummary::new(project, files.len(), failed, &results));
+            }
+            if json {
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "projects": summaries,
+                    "combined": QualityScore::total(summaries.iter().map(|s| &s.score)),
+                }))?
+            } else {
+                format_projects_summary(repo, &summaries)
+            }
+        };
+
+        if let Some(output_path) = output {
+            fs::write(&output_path, output_content).await?;
+            let absolute_path = paths::absolute(&output_path);
+            status!("📁 Projects report saved: {}", absolute_path.display());
+        } else {
+            println!("{}", output_content);
+        }
+        Ok(())
+    }
+
+    /// The analysis cache, unless disabled with `--no-cache`. Built-in analysis
+    /// is fast and free, so it is only cached with an AI provider.
+    fn analysis_cache(&self, no_cache: bool) -> Option<AnalysisCache> {
+        // Recording and replaying go through the LLM calls, not around them
+        if no_cache || !self.config.is_ai_configured() || self.analyzer.fixtures().is_some() {
+            return None;
+        }
+        AnalysisCache::default_dir().ok().map(AnalysisCache::new)
+    }
+
+    /// `prism baseline create`: accepts every current finding of the input.
+    async fn create_baseline(&self, text: Option<String>, file: Option<PathBuf>, dir: Option<PathBuf>, output: Option<PathBuf>) -> Result<()> {
+        let inputs = match &dir {
+            Some(dir_path) => self.read_supported_files(dir_path).await?
+                .into_iter()
+                .map(|(path, content)| (Some(path.to_string_lossy().replace('\\', "/")), content))
+                .collect(),
+            None => {
+                let name = file.as_ref().map(|f| self.report_source_name(f));
+                vec![(name, self.get_input_text(text, file, None).await?)]
+            }
+        };
+
+        let mut results = Vec::new();
+        for (name, content) in inputs {
+            status!("🔍 Analyzing {}...", name.as_deref().unwrap_or("the requirement text"));
+            let mut result = self.analyzer.analyze(&content).await?;
+            if let Some(name) = &name {
+                result.set_source_file(name);
+            }
+            results.push((name, result));
+        }
+
+        let path = output.unwrap_or_else(|| PathBuf::from(BASELINE_FILE_NAME));
+        fs::write(&path, format_baseline(&results, &chrono::Local::now().format("%Y-%m-%d").to_string())).await?;
+        let accepted: usize = results.iter().map(|(_, result)| result.ambiguities.len()).sum();
+        status!("📌 Accepted {} finding(s) in {}", accepted, path.display());
+        status!("💡 Commit it so CI only reports new findings; delete a line to report its finding again");
+        Ok(())
+    }
+
+    fn run_cache_command(&self, action: CacheAction) -> Result<()> {
+        let cache = AnalysisCache::new(AnalysisCache::default_dir()?);
+        match action {
+            CacheAction::Info => {
+                let (entries, bytes) = cache.size()?;
+                status!("💾 Analysis cache: {}", cache.dir().display());
+                status!("📦 {} cached analysis result(s), {:.1} KB", entries, bytes as f64 / 1024.0);
+            }
+            CacheAction::Clear => {
+                let removed = cache.clear()?;
+                status!("🧹 Removed {} cached analysis result(s) from {}", removed, cache.dir().display());
+            }
+        }
+        Ok(())
+    }
+
+    /// `prism config --migrate-secrets`: moves a plaintext API key into the
+    /// OS keyring, checking it can be read back before it leaves the file.
+    async fn migrate_secrets(&mut self) -> Result<()> {
+        let config_path = Config::config_path()?;
+        if self.config.llm.keyring {
+            status!("🔐 The API key is already kept in the OS keyring");
+            return Ok(());
+        }
+        let Some(key) = self.config.llm.api_key.clone() else {
+            status!("🔐 No API key in {} to migrate", config_path.display());
+            return Ok(());
+        };
+        secrets::store_api_key(&key).await?;
+        if secrets::load_api_key().await?.as_deref() != Some(key.as_str()) {
+            anyhow::bail!("The OS keyring did not return the stored API key; {} is unchanged", config_path.display());
+        }
+        self.config.llm.keyring = true;
+        self.config.save().await?;
+        status!("🔐 API key moved from {} to the OS keyring (service '{}')", config_path.display(), secrets::KEYRING_SERVICE);
+        Ok(())
     }
 
     fn show_config_status(&self) {
-        println!("🔧 Current PRISM Configuration");
-        println!("============================");
+        status!("🔧 Current PRISM Configuration");
+        status!("============================");
         
         let (provider_name, models) = self.config.get_provider_info();
-        println!("📡 AI Provider: {}", provider_name);
+        status!("📡 AI Provider: {}", provider_name);
         
         if self.config.is_ai_configured() {
-            println!("🔑 API Key: Configured ✅");
-            println!("🤖 Model: {}", self.config.llm.model);
+            let storage = if self.config.llm.keyring { "OS keyring" } else { "config file" };
+            status!("🔑 API Key: Configured ✅ ({})", storage);
+            if !self.config.llm.keyring && !matches!(self.config.llm.provider.as_str(), "ollama" | "mock") && self.config.llm.api_key.as_deref() != Some(PLACEHOLDER_API_KEY) {
+                status!("   Move it out of the file with 'prism config --migrate-secrets'");
+            }
+            status!("🤖 Model: {}", self.config.llm.model);
             if let Some(url) = &self.config.llm.base_url {
-                println!("🌐 Base URL: {}", url);
+                status!("🌐 Base URL: {}", url);
             }
-            println!("⏱️  Timeout: {}s", self.config.llm.timeout);
-            println!("\n✅ AI features are ready to use!");
+            if let Some(path) = self.config.llm.mock_responses.as_ref().filter(|_| self.config.llm.provider == "mock") {
+                status!("🎭 Mock responses: {}", path.display());
+            }
+            if !self.config.llm.headers.is_empty() {
+                status!("📨 Extra headers: {}", self.config.llm.headers.keys().cloned().collect::<Vec<_>>().join(", "));
+            }
+            status!("⏱️  Timeout: {}s", self.config.llm.timeout);
+            let generation = self.config.llm.generation();
+            status!("🌡️  Temperature: {}, max tokens: {}", generation.temperature, generation.max_tokens);
+            if generation.system_prompt != DEFAULT_SYSTEM_PROMPT {
+                status!("🗣️  System prompt: custom ({} characters)", generation.system_prompt.chars().count());
+            }
+            let retry = &self.config.llm.retry;
+            status!(
+                "🔁 Retries: {} attempt(s), {:.1}s backoff up to {:.0}s",
+                retry.max_attempts, retry.initial_backoff_ms as f32 / 1000.0, retry.max_backoff_ms as f32 / 1000.0
+            );
+            status!("\n✅ AI features are ready to use!");
         } else {
-            println!("🔑 API Key: Not configured ❌");
-            println!("🤖 Model: {}", if self.config.llm.model.is_empty() { "Not set" } else { &self.config.llm.model });
-            println!("\n⚠️  AI features are disabled. Run 'prism config --setup' to configure.");
+            status!("🔑 API Key: Not configured ❌");
+            status!("🤖 Model: {}", if self.config.llm.model.is_empty() { "Not set" } else { &self.config.llm.model });
+            status!("\n⚠️  AI features are disabled. Run 'prism config --setup' to configure.");
         }
         
-        println!("\n📝 Analysis Settings:");
-        println!("  • Ambiguity threshold: {}", self.config.analysis.ambiguity_threshold);
-        println!("  • Interactive mode: {}", self.config.analysis.enable_interactive);
-        println!("  • Custom rules: {}", self.config.analysis.custom_rules.len());
+        status!("\n📝 Analysis Settings:");
+        status!("  • Ambiguity threshold: {}", self.config.analysis.ambiguity_threshold);
+        status!("  • Interactive mode: {}", self.config.analysis.enable_interactive);
+        match load_custom_rules(&self.config) {
+            Ok(rules) if rules.is_empty() => status!("  • Custom rules: none"),
+            Ok(rules) => status!("  • Custom rules: {}", rules.iter().map(|r| r.id.as_str()).collect::<Vec<_>>().join(", ")),
+            Err(e) => status!("  • Custom rules: ❌ {:#}", e),
+        }
+        match load_prompt_templates(&self.config) {
+            Ok(prompts) if prompts.custom().count() == 0 => status!("  • Prompts: built-in"),
+            Ok(prompts) => {
+                let custom: Vec<String> = prompts.custom().map(|(task, path)| format!("{} ({})", task.name(), path.display())).collect();
+                status!("  • Prompt templates: {} (built-in prompts for the other tasks)", custom.join(", "));
+            }
+            Err(e) => status!("  • Prompts: ❌ {:#}", e),
+        }
+        if !self.config.analysis.severity_overrides.is_empty() {
+            let overrides: Vec<String> = self.config.analysis.severity_overrides.iter()
+                .map(|(rule, severity)| format!("{}={:?}", rule, severity))
+                .collect();
+            status!("  • Severity overrides: {}", overrides.join(", "));
+        }
+        if !self.config.analysis.disabled_rules.is_empty() {
+            status!("  • Disabled rules: {}", self.config.analysis.disabled_rules.join(", "));
+        }
+        status!("  • End-of-run hints: {}", if self.config.analysis.show_hints { "on" } else { "off" });
+        if let Ok(Some((marker, _))) = find_project_config(Path::new(".")) {
+            status!("  • Project settings: {} (layered over this configuration for other commands)", marker.display());
+        }
+        let overridden: Vec<&str> = ["PRISM_PROVIDER", "PRISM_MODEL", "PRISM_BASE_URL", "PRISM_TIMEOUT", "PRISM_TEMPERATURE", "PRISM_MAX_TOKENS", "PRISM_API_KEY"].into_iter()
+            .filter(|v| std::env::var(v).is_ok_and(|value| !value.trim().is_empty()))
+            .collect();
+        if !overridden.is_empty() {
+            status!("  • Environment overrides: {} (used by other commands instead of the values above)", overridden.join(", "));
+        }
+
+        let gate = &self.config.release_gate;
+        status!("\n🚦 Release Gate:");
+        status!("  • Minimum completeness: {:.0}%", gate.min_completeness);
+        status!("  • Minimum coverage: {:.0}%", gate.min_coverage);
+        status!("  • Maximum open questions: {}", gate.max_open_questions);
+        status!("  • Maximum critical findings: {}", gate.max_critical_findings);
+
+        if !self.config.presets.is_empty() {
+            let names: Vec<&str> = self.config.presets.keys().map(|k| k.as_str()).collect();
+            status!("\n🎛️  Custom Presets: {}", names.join(", "));
+        }
+
+        let notion = &self.config.notion;
+        if notion.token().is_some() || notion.database_id.is_some() {
+            status!("\n📓 Notion:");
+            status!("  • Token: {}", if notion.token().is_some() { "Configured ✅" } else { "Not configured ❌" });
+            status!("  • Database: {}", notion.database_id.as_deref().unwrap_or("Not set"));
+            status!("  • Requirement pages: {} = {}", notion.tag_property, notion.tag);
+            status!("  • Summary property: {}", notion.summary_property.as_deref().unwrap_or("Not written back"));
+        }
+
+        if !self.config.rm_tools.is_empty() {
+            status!("\n📥 Requirements Tools:");
+            for (name, connection) in &self.config.rm_tools {
+                status!("  • {}: {} at {}", name, connection.tool.name(), connection.base_url);
+            }
+        }
+
+        let webhook = &self.config.server.webhook;
+        if webhook.secret().is_some() || !webhook.paths.is_empty() || !self.config.server.api_keys.is_empty() {
+            status!("\n🌐 Server:");
+            status!("  • Address: {}", self.config.server.bind);
+            for key in &self.config.server.api_keys {
+                let limit = |limit: Option<u32>| limit.map(|l| l.to_string()).unwrap_or_else(|| "unlimited".to_string());
+                status!("  • API key {}: {}/min, {}/day", key.name, limit(key.requests_per_minute), limit(key.requests_per_day));
+            }
+            status!("  • Webhook secret: {}", if webhook.secret().is_some() { "Configured ✅" } else { "Not configured ❌" });
+            if !webhook.paths.is_empty() {
+                status!("  • Requirement paths: {}", webhook.paths.join(", "));
+            }
+        }
+
+        if let Some(endpoint) = self.config.telemetry.otlp_endpoint() {
+            status!("\n📡 Tracing: OTLP export to {} as {}", endpoint, self.config.telemetry.service_name);
+        }
+
+        let daemon = &self.config.daemon;
+        if !daemon.jobs.is_empty() {
+            status!("\n🗓️  Scheduled Analyses:");
+            for job in &daemon.jobs {
+                status!("  • {}: {} ({})", job.name, job.path.display(), job.schedule);
+            }
+            status!("  • Notifications: {}", if daemon.webhook_url.is_some() { "Webhook ✅" } else { "Console only" });
+        }
+    }
+
+    /// Loads the corpus index of `dir_path` and re-embeds new and changed files.
+    async fn update_index(&self, dir_path: &PathBuf) -> Result<RequirementIndex> {
+        status!("🗃️  Indexing requirements in: {}", dir_path.display());
+
+        let files: Vec<(String, String)> = self.read_supported_files(dir_path).await?
+            .into_iter()
+            .map(|(path, content)| (path.display().to_string(), content))
+            .collect();
+
+        let mut index = RequirementIndex::load(dir_path);
+        let update = index.update(&files);
+        index.save(dir_path)?;
+
+        status!(
+            "✅ Index up to date: {} added, {} updated, {} unchanged, {} removed ({} requirements in {} files)",
+            update.added, update.updated, update.unchanged, update.removed, index.chunk_count(), index.files.len()
+        );
+        Ok(index)
+    }
+
+    async fn run_chat_session(&self, file: &PathBuf, index_dir: Option<PathBuf>) -> Result<()> {
+        let text = self.read_file(file).await?;
+        status!("📋 Analyzing {} before answering questions...", file.display());
+        let result = self.analyzer.analyze(&text).await?;
+        let mut session = ChatSession::new(&file.display().to_string(), &text, result);
+
+        if let Some(index_dir) = index_dir {
+            let index = self.update_index(&index_dir).await?;
+            // The chatted file may itself be part of the corpus; don't cite it as related
+            let index_source = std::fs::canonicalize(file).ok()
+                .zip(std::fs::canonicalize(&index_dir).ok())
+                .and_then(|(file, dir)| file.strip_prefix(dir).ok().map(|p| p.display().to_string()));
+            session = session.with_index(index, index_source);
+        }
+        let use_ai = self.config.is_ai_configured();
+
+        status!("💬 Ask questions about {} ({} answers). Type 'help' for examples, 'exit' to quit.\n",
+            file.display(), if use_ai { "AI" } else { "built-in" });
+
+        loop {
+            print!("prism> ");
+            std::io::Write::flush(&mut io::stdout())?;
+
+            let mut input = String::new();
+            if io::stdin().read_line(&mut input)? == 0 {
+                break;
+            }
+
+            let question = input.trim();
+            match question {
+                "" => continue,
+                "exit" | "quit" => break,
+                "help" => {
+                    status!("Example questions:");
+                    status!("  • which requirements mention payments?");
+                    status!("  • what's still ambiguous?");
+                    status!("  • who are the actors?");
+                    status!("  • how many requirements are there?\n");
+                    continue;
+                }
+                _ => {}
+            }
+
+            let answer = if use_ai {
+                match self.analyzer.call_llm(&session.llm_prompt(question)).await {
+                    Ok(answer) => answer.trim().to_string(),
+                    Err(e) => {
+                        eprintln!("⚠️  AI answer failed ({}), using built-in answer", e);
+                        session.answer_builtin(question)
+                    }
+                }
+            } else {
+                session.answer_builtin(question)
+            };
+            status!("{}\n", answer);
+        }
+
+        status!("👋 Chat ended.");
+        Ok(())
+    }
+
+    async fn run_authoring_wizard(&self, output: Option<PathBuf>, template: Option<PathBuf>) -> Result<()> {
+        status!("📝 PRISM New Requirement Wizard");
+        status!("===============================");
+        status!("Answer each question - every answer is checked as you go.\n");
+
+        let mut draft = RequirementDraft {
+            actor: self.ask_validated("👤 Who needs this? (actor, e.g. 'account owner'): ", DraftField::Actor)?,
+            ..RequirementDraft::default()
+        };
+        draft.goal = self.ask_validated(&format!("🎯 What does the {} want to do?: ", draft.actor), DraftField::Goal)?;
+        draft.benefit = self.ask_validated("💡 What is the benefit? (so that ...): ", DraftField::Benefit)?;
+        draft.title = RequirementDraft::title_from_goal(&draft.goal);
+
+        status!("\n✅ Acceptance criteria - one per line, empty line to finish (Given/When/Then works best):");
+        draft.acceptance_criteria = self.ask_list(DraftField::AcceptanceCriterion)?;
+
+        status!("\n🔒 Non-functional requirements - one per line, empty line to finish (performance, security, ...):");
+        draft.nfrs = self.ask_list(DraftField::Nfr)?;
+
+        let validation = self.analyzer.validate_user_story(&draft.story_sentence());
+        status!("\n📊 Business value score: {:.0}%", validation.business_value_score);
+        for recommendation in &validation.recommendations {
+            status!("  💡 {}", recommendation);
+        }
+
+        let template_text = match template {
+            Some(path) => Some(fs::read_to_string(&path).await
+                .map_err(|e| anyhow::anyhow!("Could not read template {:?}: {}", path, e))?),
+            None => None,
+        };
+        let content = draft.render(template_text.as_deref())?;
+
+        let output_path = output.unwrap_or_else(|| PathBuf::from(draft.file_name()));
+        if output_path.exists() {
+            status!("⚠️  {} already exists. Overwrite? (y/n): ", output_path.display());
+            if Self::read_answer()?.to_lowercase() != "y" {
+                status!("📝 Requirement not saved.");
+                return Ok(());
+            }
+        }
+
+        fs::write(&output_path, content).await?;
+        let absolute_path = paths::absolute(&output_path);
+        status!("📁 Requirement saved: {}", absolute_path.display());
+        status!("💡 Run 'prism analyze --file {}' for a full analysis", output_path.display());
+        Ok(())
+    }
+
+    fn read_answer() -> Result<String> {
+        let mut input = String::new();
+        if std::io::stdin().read_line(&mut input)? == 0 {
+            return Err(anyhow::anyhow!("Input closed before the wizard finished"));
+        }
+        Ok(input.trim().to_string())
+    }
+
+    /// Asks until the answer passes validation or the author keeps it despite warnings.
+    fn ask_validated(&self, prompt: &str, field: DraftField) -> Result<String> {
+        loop {
+            status!("{}", prompt);
+            let answer = normalize_answer(field, &Self::read_answer()?);
+            let check = check_answer(&self.analyzer, field, &answer);
+
+            if !check.errors.is_empty() {
+                for error in &check.errors {
+                    status!("  ❌ {}", error);
+                }
+                continue;
+            }
+            if check.is_clean() || self.keep_despite_warnings(&check)? {
+                return Ok(answer);
+            }
+        }
+    }
+
+    fn ask_list(&self, field: DraftField) -> Result<Vec<String>> {
+        let mut items = Vec::new();
+        loop {
+            status!("  {}.", items.len() + 1);
+            let answer = normalize_answer(field, &Self::read_answer()?);
+            if answer.is_empty() {
+                return Ok(items);
+            }
+
+            let check = check_answer(&self.analyzer, field, &answer);
+            if check.is_clean() || self.keep_despite_warnings(&check)? {
+                items.push(answer);
+            }
+        }
+    }
+
+    fn keep_despite_warnings(&self, check: &AnswerCheck) -> Result<bool> {
+        for warning in &check.warnings {
+            status!("  ⚠️  {}", warning);
+        }
+        status!("Keep this answer anyway? (y/n): ");
+        Ok(Self::read_answer()?.to_lowercase() == "y")
     }
 
     pub async fn run_setup_wizard(&mut self) -> Result<()> {
-        println!("🚀 PRISM AI Configuration Wizard");
-        println!("=================================");
-        println!("PRISM is designed to work with AI providers for enhanced requirement analysis.");
-        println!("Without AI configuration, you'll only get basic built-in analysis.\n");
+        status!("🚀 PRISM AI Configuration Wizard");
+        status!("=================================");
+        status!("PRISM is designed to work with AI providers for enhanced requirement analysis.");
+        status!("Without AI configuration, you'll only get basic built-in analysis.\n");
 
-        println!("Would you like to configure AI analysis? (y/n): ");
+        status!("Would you like to configure AI analysis? (y/n): ");
         let mut input = String::new();
         std::io::stdin().read_line(&mut input)?;
         
         if input.trim().to_lowercase() != "y" {
-            println!("📝 Skipping AI configuration. You can run 'prism config --setup' anytime to configure later.");
-            println!("✨ PRISM will use built-in analysis features only.");
+            status!("📝 Skipping AI configuration. You can run 'prism config --setup' anytime to configure later.");
+            status!("✨ PRISM will use built-in analysis features only.");
             return Ok(());
         }
 
-        println!("\n🤖 Choose your AI provider:");
-        println!("1. OpenAI (GPT-4, GPT-3.5-turbo, GPT-4o)");
-        println!("2. Google Gemini (gemini-1.5-pro, gemini-1.5-flash)"); 
-        println!("3. Anthropic Claude (claude-3-opus, claude-3-sonnet, claude-3-haiku)");
-        println!("4. Azure OpenAI");
-        println!("5. Local Ollama (llama2, codellama, mistral, etc.)");
-        println!("\nEnter choice (1-5): ");
+        status!("\n🤖 Choose your AI provider:");
+        status!("1. OpenAI (GPT-4, GPT-3.5-turbo, GPT-4o)");
+        status!("2. Google Gemini (gemini-1.5-pro, gemini-1.5-flash)"); 
+        status!("3. Anthropic Claude (claude-3-opus, claude-3-sonnet, claude-3-haiku)");
+        status!("4. Azure OpenAI");
+        status!("5. Local Ollama (llama2, codellama, mistral, etc.)");
+        status!("6. OpenAI-compatible endpoint (LiteLLM, OpenRouter, Groq, Together, vLLM, etc.)");
+        status!("7. Anthropic-compatible endpoint");
+        status!("\nEnter choice (1-7): ");
         
         input.clear();
         std::io::stdin().read_line(&mut input)?;
@@ -1116,8 +2776,10 @@ impl App {
             "3" => crate::cli::AiProvider::Claude,
             "4" => crate::cli::AiProvider::Azure,
             "5" => crate::cli::AiProvider::Ollama,
+            "6" => crate::cli::AiProvider::OpenAiCompatible,
+            "7" => crate::cli::AiProvider::AnthropicCompatible,
             _ => {
-                println!("❌ Invalid choice. Please run the wizard again.");
+                status!("❌ Invalid choice. Please run the wizard again.");
                 return Ok(());
             }
         };
@@ -1127,67 +2789,90 @@ impl App {
     }
 
     async fn setup_provider(&mut self, provider: crate::cli::AiProvider) -> Result<()> {
-        let provider_str = match provider {
-            crate::cli::AiProvider::OpenAI => "openai",
-            crate::cli::AiProvider::Gemini => "gemini", 
-            crate::cli::AiProvider::Claude => "claude",
-            crate::cli::AiProvider::Azure => "azure",
-            crate::cli::AiProvider::Ollama => "ollama",
-        };
+        let provider_str = provider.name();
+        let compatible = is_compatible_provider(provider_str);
 
         self.config.set_provider(provider_str);
         let (provider_name, models) = self.config.get_provider_info();
 
-        println!("\n🔧 Configuring {} Provider", provider_name);
-        println!("{}========================{}", "=".repeat(provider_name.len()), "=".repeat(9));
+        status!("\n🔧 Configuring {} Provider", provider_name);
+        status!("{}========================{}", "=".repeat(provider_name.len()), "=".repeat(9));
 
         // Get API key (not needed for Ollama)
         if !matches!(provider, crate::cli::AiProvider::Ollama) {
-            println!("🔑 Enter your {} API key: ", provider_name);
+            status!("🔑 Enter your {} API key: ", provider_name);
             let mut api_key = String::new();
             std::io::stdin().read_line(&mut api_key)?;
             let api_key = api_key.trim().to_string();
 
-            if api_key.is_empty() {
-                println!("❌ API key cannot be empty. Configuration cancelled.");
+            if api_key.is_empty() && compatible {
+                status!("ℹ️  No API key - a placeholder key is sent");
+                self.config.llm.api_key = Some(PLACEHOLDER_API_KEY.to_string());
+            } else if api_key.is_empty() {
+                status!("❌ API key cannot be empty. Configuration cancelled.");
                 return Ok(());
+            } else {
+                self.config.set_api_key(api_key);
             }
-
-            self.config.set_api_key(api_key);
         } else {
-            println!("ℹ️  Ollama runs locally - no API key required");
+            status!("ℹ️  Ollama runs locally - no API key required");
             // Set a placeholder API key for Ollama
             self.config.set_api_key("ollama-local".to_string());
         }
 
-        // Get model selection
-        println!("\n🤖 Available models for {}:", provider_name);
-        for (i, model) in models.iter().enumerate() {
-            println!("{}. {}", i + 1, model);
+        if compatible {
+            status!("\n🌐 Enter the endpoint's base URL:");
+            status!("(e.g., https://openrouter.ai/api/v1, https://api.groq.com/openai/v1 or http://localhost:4000 for LiteLLM)");
+            let mut url = String::new();
+            std::io::stdin().read_line(&mut url)?;
+            let url = url.trim();
+            if url.is_empty() {
+                status!("❌ Base URL cannot be empty. Configuration cancelled.");
+                return Ok(());
+            }
+            self.config.llm.base_url = Some(url.to_string());
         }
-        
-        println!("Enter choice (1-{}) or custom model name: ", models.len());
-        let mut model_input = String::new();
-        std::io::stdin().read_line(&mut model_input)?;
-        let model_input = model_input.trim();
-
-        let selected_model = if let Ok(choice) = model_input.parse::<usize>() {
-            if choice > 0 && choice <= models.len() {
-                models[choice - 1].clone()
-            } else {
-                println!("❌ Invalid choice. Using default model.");
-                models.first().unwrap_or(&"gpt-4".to_string()).clone()
+
+        // Get model selection
+        let selected_model = if models.is_empty() {
+            status!("\n🤖 Enter the model name as the endpoint knows it (e.g., meta-llama/llama-3.1-70b-instruct):");
+            let mut model_input = String::new();
+            std::io::stdin().read_line(&mut model_input)?;
+            let model_input = model_input.trim();
+            if model_input.is_empty() {
+                status!("❌ Model cannot be empty. Configuration cancelled.");
+                return Ok(());
             }
-        } else {
             model_input.to_string()
+        } else {
+            status!("\n🤖 Available models for {}:", provider_name);
+            for (i, model) in models.iter().enumerate() {
+                status!("{}. {}", i + 1, model);
+            }
+
+            status!("Enter choice (1-{}) or custom model name: ", models.len());
+            let mut model_input = String::new();
+            std::io::stdin().read_line(&mut model_input)?;
+            let model_input = model_input.trim();
+
+            if let Ok(choice) = model_input.parse::<usize>() {
+                if choice > 0 && choice <= models.len() {
+                    models[choice - 1].clone()
+                } else {
+                    status!("❌ Invalid choice. Using default model.");
+                    models.first().unwrap_or(&"gpt-4".to_string()).clone()
+                }
+            } else {
+                model_input.to_string()
+            }
         };
 
         self.config.set_model(selected_model.clone());
 
         // Special handling for Azure and Ollama
         if matches!(provider, crate::cli::AiProvider::Azure) {
-            println!("\n🌐 Enter your Azure OpenAI endpoint URL:");
-            println!("(e.g., https://your-resource.openai.azure.com/openai/deployments/your-deployment)");
+            status!("\n🌐 Enter your Azure OpenAI endpoint URL:");
+            status!("(e.g., https://your-resource.openai.azure.com/openai/deployments/your-deployment)");
             let mut url = String::new();
             std::io::stdin().read_line(&mut url)?;
             let url = url.trim();
@@ -1195,31 +2880,34 @@ impl App {
                 self.config.llm.base_url = Some(url.to_string());
             }
         } else if matches!(provider, crate::cli::AiProvider::Ollama) {
-            println!("\n🌐 Enter your Ollama server URL (or press Enter for default http://localhost:11434):");
+            status!("\n🌐 Enter your Ollama server URL (or press Enter for default http://localhost:11434):");
             let mut url = String::new();
             std::io::stdin().read_line(&mut url)?;
             let url = url.trim();
             if !url.is_empty() {
-                self.config.llm.base_url = Some(format!("{}/api/generate", url));
+                self.config.llm.base_url = Some(crate::ollama::chat_url(Some(url)));
             }
             // Default URL is already set in set_provider
+            if let Err(e) = self.ensure_ollama_model(true).await {
+                status!("⚠️  {}", e);
+            }
         }
 
         // Save configuration
         self.config.save().await?;
 
-        println!("\n✅ {} configuration completed successfully!", provider_name);
-        println!("🤖 Model: {}", selected_model);
+        status!("\n✅ {} configuration completed successfully!", provider_name);
+        status!("🤖 Model: {}", selected_model);
         if matches!(provider, crate::cli::AiProvider::Ollama) {
-            println!("🔑 API Key: Not required (local)");
+            status!("🔑 API Key: Not required (local)");
         } else {
-            println!("🔑 API Key: Configured");
+            status!("🔑 API Key: Configured");
         }
         if let Some(url) = &self.config.llm.base_url {
-            println!("🌐 Base URL: {}", url);
+            status!("🌐 Base URL: {}", url);
         }
-        println!("\n🎉 PRISM is now ready for AI-powered analysis!");
-        println!("💡 Try: prism analyze \"As a user, I want to login quickly\"");
+        status!("\n🎉 PRISM is now ready for AI-powered analysis!");
+        status!("💡 Try: prism analyze \"As a user, I want to login quickly\"");
 
         Ok(())
     }
@@ -1233,6 +2921,13 @@ impl App {
         output.push_str("```\n");
         output.push_str(improved);
         output.push_str("\n```\n\n");
+
+        let changes = build_changelog(original, improved, ambiguities);
+        if !changes.is_empty() {
+            output.push_str("## 🧾 Changelog\n\n");
+            output.push_str(&format_changelog_markdown(&changes, ambiguities));
+            output.push('\n');
+        }
         
         output.push_str("## 📊 Issues Fixed\n\n");
         output.push_str(&format!("**Total Issues Addressed:** {}\n\n", ambiguities.len()));
@@ -1269,73 +2964,74 @@ impl App {
     }
 
     async fn test_ai_configuration(&mut self) -> Result<()> {
-        println!("🧪 Testing AI Configuration...\n");
+        status!("🧪 Testing AI Configuration...\n");
         
         if !self.config.is_ai_configured() {
-            println!("❌ AI is not configured");
-            println!("💡 Run 'prism config --setup' to configure AI features");
+            status!("❌ AI is not configured");
+            status!("💡 Run 'prism config --setup' to configure AI features");
             return Ok(());
         }
 
         // Show current configuration
         let (provider_name, _) = self.config.get_provider_info();
-        println!("📡 Provider: {}", provider_name);
-        println!("🤖 Model: {}", self.config.llm.model);
+        status!("📡 Provider: {}", provider_name);
+        status!("🤖 Model: {}", self.config.llm.model);
         if let Some(url) = &self.config.llm.base_url {
-            println!("🌐 Base URL: {}", url);
+            status!("🌐 Base URL: {}", url);
         }
-        println!();
+        status!();
 
         // Test with a simple prompt
-        println!("🔄 Testing AI connection with simple prompt...");
+        status!("🔄 Testing AI connection with simple prompt...");
         let test_prompt = "Analyze this requirement: 'The system should respond quickly'";
         
         match self.analyzer.call_llm(test_prompt).await {
             Ok(response) => {
-                println!("✅ AI connection successful!");
-                println!("📝 Response preview: {}...", 
+                status!("✅ AI connection successful!");
+                status!("📝 Response preview: {}...", 
                     if response.len() > 100 { 
                         &response[..100] 
                     } else { 
                         &response 
                     });
-                println!("\n🎉 Configuration is working properly!");
+                status!("\n🎉 Configuration is working properly!");
             }
             Err(e) => {
-                println!("❌ AI connection failed: {}", e);
+                status!("❌ AI connection failed: {}", e);
                 
                 // Provide specific troubleshooting based on provider
                 match self.config.llm.provider.as_str() {
                     "ollama" => {
-                        println!("\n🔧 Ollama Troubleshooting:");
-                        println!("1. Ensure Ollama is running: ollama serve");
-                        println!("2. Check if model exists: ollama list");
-                        println!("3. Pull the model if needed: ollama pull {}", self.config.llm.model);
-                        println!("4. Try a different model: prism config --model llama3.1:latest");
+                        self.ensure_ollama_model(io::stdin().is_terminal()).await?;
+                        status!("\n🔧 Ollama Troubleshooting:");
+                        status!("1. Ensure Ollama is running: ollama serve");
+                        status!("2. Check if model exists: ollama list");
+                        status!("3. Pull the model if needed: ollama pull {}", self.config.llm.model);
+                        status!("4. Try a different model: prism config --model llama3.1:latest");
                     }
                     "openai" => {
-                        println!("\n🔧 OpenAI Troubleshooting:");
-                        println!("1. Verify API key is correct");
-                        println!("2. Check account has credits");
-                        println!("3. Verify model name is correct");
+                        status!("\n🔧 OpenAI Troubleshooting:");
+                        status!("1. Verify API key is correct");
+                        status!("2. Check account has credits");
+                        status!("3. Verify model name is correct");
                     }
                     "claude" => {
-                        println!("\n🔧 Claude Troubleshooting:");
-                        println!("1. Verify API key is correct");
-                        println!("2. Check account has credits");
-                        println!("3. Verify model name is correct");
+                        status!("\n🔧 Claude Troubleshooting:");
+                        status!("1. Verify API key is correct");
+                        status!("2. Check account has credits");
+                        status!("3. Verify model name is correct");
                     }
                     "gemini" => {
-                        println!("\n🔧 Gemini Troubleshooting:");
-                        println!("1. Verify API key is correct");
-                        println!("2. Check API is enabled in Google Cloud");
-                        println!("3. Verify model name is correct");
+                        status!("\n🔧 Gemini Troubleshooting:");
+                        status!("1. Verify API key is correct");
+                        status!("2. Check API is enabled in Google Cloud");
+                        status!("3. Verify model name is correct");
                     }
                     _ => {
-                        println!("\n🔧 General Troubleshooting:");
-                        println!("1. Check internet connection");
-                        println!("2. Verify API credentials");
-                        println!("3. Try 'prism config --debug' for more info");
+                        status!("\n🔧 General Troubleshooting:");
+                        status!("1. Check internet connection");
+                        status!("2. Verify API credentials");
+                        status!("3. Try 'prism config --debug' for more info");
                     }
                 }
             }
@@ -1345,22 +3041,41 @@ impl App {
     }
 
     async fn save_individual_artifacts(&self, result: &AnalysisResult, base_filename: &str, input_text: &str) -> Result<()> {
-        println!("💾 Saving individual artifacts...");
+        status!("💾 Saving individual artifacts...");
+
+        // With more than the analysis report, an index links the artifacts and they link to each other
+        let multiple = result.improved_requirements.is_some() || result.uml_diagrams.is_some() || result.pseudocode.is_some() || result.gherkin.is_some()
+            || result.nfr_suggestions.is_some() || result.rewrite_suggestions.is_some() || result.permission_matrix.is_some()
+            || result.dependency_graph.is_some() || result.domain_model.is_some() || result.event_storming.is_some() || result.contract_tests.is_some() || result.test_data.is_some()
+            || result.load_tests.as_ref().is_some_and(|s| !s.is_empty()) || result.security_tests.as_ref().is_some_and(|t| !t.is_empty());
+        let links = ArtifactLinks {
+            index: multiple.then(|| artifact_file_name(&format!("{}_Index.md", base_filename))),
+            nfr: result.nfr_suggestions.as_ref().map(|_| artifact_file_name(&format!("{}_NFR.md", base_filename))),
+            suggestions: result.rewrite_suggestions.as_ref().map(|_| artifact_file_name(&format!("{}_Suggestions.md", base_filename))),
+        };
+        let mut saved = Vec::new();
+        let mut record = |title: &str, file_name: &str, description: &str| saved.push(SavedArtifact {
+            title: title.to_string(),
+            file_name: artifact_file_name(file_name),
+            description: description.to_string(),
+        });
         
         // Save focused analysis report (only analysis content, no UML, pseudocode, or improved requirements)
         let analysis_filename = format!("{}_Analysis.md", base_filename);
-        let analysis_content = self.format_focused_analysis(result, input_text);
+        let analysis_content = self.format_focused_analysis(result, input_text, &links);
         fs::write(&analysis_filename, analysis_content).await?;
-        let analysis_path = std::fs::canonicalize(&analysis_filename).unwrap_or(PathBuf::from(&analysis_filename));
-        println!("📄 Analysis report saved: {}", analysis_path.display());
+        let analysis_path = paths::absolute(&analysis_filename);
+        status!("📄 Analysis report saved: {}", analysis_path.display());
+        record("🔍 Analysis", &analysis_filename, &format!("{} findings, entities, completeness and story validation", result.ambiguities.len()));
 
         // Save improved requirements if available
         if let Some(improved_req) = &result.improved_requirements {
             let req_filename = format!("{}_Req.md", base_filename);
-            let req_content = format!("# Improved Requirements\n\n{}\n\n---\n*Generated by PRISM - AI-Powered Requirement Analyzer*", improved_req);
+            let req_content = format!("{}# Improved Requirements\n\n{}\n\n---\n*Generated by PRISM - AI-Powered Requirement Analyzer*", links.back_link(), improved_req);
             fs::write(&req_filename, req_content).await?;
-            let req_path = std::fs::canonicalize(&req_filename).unwrap_or(PathBuf::from(&req_filename));
-            println!("📄 Improved requirements saved: {}", req_path.display());
+            let req_path = paths::absolute(&req_filename);
+            status!("📄 Improved requirements saved: {}", req_path.display());
+            record("✨ Improved requirements", &req_filename, "The requirements rewritten to resolve the findings");
         }
 
         // Save UML diagrams if available
@@ -1398,8 +3113,28 @@ impl App {
                 let header = format!("' PlantUML Diagrams for: {}\n' Generated by PRISM - AI-Powered Requirement Analyzer\n' \n' Instructions:\n' 1. Use Case Diagram is uncommented by default\n' 2. Uncomment Sequence or Class diagrams as needed (remove ' from lines)\n' 3. Use PlantUML online editor or VS Code extension to render\n' 4. Visit: http://www.plantuml.com/plantuml/uml/\n\n", base_filename);
                 uml_content = header + &uml_content;
                 fs::write(&uml_filename, uml_content).await?;
-                let uml_path = std::fs::canonicalize(&uml_filename).unwrap_or(PathBuf::from(&uml_filename));
-                println!("🎨 UML diagrams saved: {}", uml_path.display());
+                let uml_path = paths::absolute(&uml_filename);
+                status!("🎨 UML diagrams saved: {}", uml_path.display());
+                record("🎨 UML diagrams", &uml_filename, "PlantUML use case, sequence and class diagrams");
+            }
+
+            if let Some(renderer) = PlantUmlRenderer::from_config(&self.config.plantuml) {
+                let diagrams = [("", "use case", &uml.use_case), ("_Sequence", "sequence", &uml.sequence), ("_Class", "class", &uml.class_diagram)];
+                for (suffix, kind, source) in diagrams {
+                    let Some(source) = source else { continue };
+                    for format in &self.config.plantuml.formats {
+                        let image_filename = format!("{}_UML{}.{}", base_filename, suffix, format.extension());
+                        // A missing renderer shouldn't cost the other artifacts
+                        match renderer.render(source, *format).await {
+                            Ok(image) => {
+                                fs::write(&image_filename, image).await?;
+                                status!("🖼️  UML {} diagram rendered: {}", kind, image_filename);
+                                record("🖼️ UML image", &image_filename, &format!("Rendered {} diagram", kind));
+                            }
+                            Err(e) => eprintln!("⚠️  Could not render the UML {} diagram: {}", kind, e),
+                        }
+                    }
+                }
             }
         }
 
@@ -1408,25 +3143,185 @@ impl App {
             let logic_filename = format!("{}_Logic.py", base_filename);
             let logic_content = format!("# Pseudocode Implementation\n# Generated by PRISM - AI-Powered Requirement Analyzer\n# \n# This code provides a structured foundation for implementing the requirements.\n# Replace placeholder implementations with actual business logic.\n\n{}", pseudocode);
             fs::write(&logic_filename, logic_content).await?;
-            let logic_path = std::fs::canonicalize(&logic_filename).unwrap_or(PathBuf::from(&logic_filename));
-            println!("🔧 Pseudocode saved: {}", logic_path.display());
+            let logic_path = paths::absolute(&logic_filename);
+            status!("🔧 Pseudocode saved: {}", logic_path.display());
+            record("🔧 Pseudocode", &logic_filename, "Implementation skeleton for the extracted entities");
+        }
+
+        // Save Gherkin features if available
+        if let Some(gherkin) = &result.gherkin {
+            let feature_filename = format!("{}_Features.feature", base_filename);
+            let feature_content = format!("# Generated by PRISM - AI-Powered Requirement Analyzer\n# Review the steps and wire them to your step definitions.\n\n{}", gherkin);
+            fs::write(&feature_filename, feature_content).await?;
+            let feature_path = paths::absolute(&feature_filename);
+            status!("🥒 Gherkin features saved: {}", feature_path.display());
+            record("🥒 Gherkin features", &feature_filename, "Given/When/Then scenarios from the acceptance criteria and actors");
+        }
+
+        // Save the dependency graph if available, for Graphviz and Mermaid
+        if let Some(graph) = &result.dependency_graph {
+            let dot_filename = format!("{}_Dependencies.dot", base_filename);
+            fs::write(&dot_filename, format_dependency_dot(graph)).await?;
+            let mermaid_filename = format!("{}_Dependencies.mmd", base_filename);
+            fs::write(&mermaid_filename, format_dependency_mermaid(graph)).await?;
+            let dot_path = paths::absolute(&dot_filename);
+            status!("🔗 Dependency graph saved: {} (+ .mmd)", dot_path.display());
+            record("🔗 Dependency graph (DOT)", &dot_filename, "Render with `dot -Tsvg`; cycle edges are red");
+            record("🔗 Dependency graph (Mermaid)", &mermaid_filename, "The same graph for Mermaid viewers");
+        }
+
+        // Save the domain model if available, described in Markdown and as a class diagram
+        if let Some(model) = &result.domain_model {
+            let model_filename = format!("{}_DomainModel.md", base_filename);
+            let model_content = format!(
+                "{}# Domain Model for: {}\n*Generated by PRISM - AI-Powered Requirement Analyzer*\n\n{}",
+                links.back_link(),
+                base_filename,
+                format_domain_model_markdown(model)
+            );
+            fs::write(&model_filename, model_content).await?;
+            let diagram_filename = format!("{}_DomainModel.puml", base_filename);
+            fs::write(&diagram_filename, format_domain_model_plantuml(model)).await?;
+            let model_path = paths::absolute(&model_filename);
+            status!("🏛️  Domain model saved: {} (+ .puml)", model_path.display());
+            record("🏛️ Domain model", &model_filename, &format!("{} proposed aggregates with their entities, value objects and events", model.aggregates.len()));
+            record("🏛️ Domain model diagram", &diagram_filename, "PlantUML class diagram of the aggregates");
+        }
+
+        // Save the event-storming board if available, for Mermaid and for import into Miro
+        if let Some(board) = &result.event_storming {
+            let mermaid_filename = format!("{}_EventStorming.mmd", base_filename);
+            fs::write(&mermaid_filename, format_event_storming_mermaid(board)).await?;
+            let miro_filename = format!("{}_EventStorming.json", base_filename);
+            let miro = format_event_storming_miro(board, &format!("Event storming: {}", base_filename));
+            fs::write(&miro_filename, serde_json::to_string_pretty(&miro)?).await?;
+            let mermaid_path = paths::absolute(&mermaid_filename);
+            status!("🟧 Event-storming board saved: {} (+ .json)", mermaid_path.display());
+            record("🟧 Event-storming board", &mermaid_filename, &format!("{} commands, {} policies and {} read models as a Mermaid flowchart", board.commands.len(), board.policies.len(), board.read_models.len()));
+            record("🟧 Event-storming stickies", &miro_filename, "Sticky notes to create on a Miro board through its REST API");
+        }
+
+        // Save test data fixtures if available
+        if let Some(test_data) = &result.test_data {
+            let fixtures_filename = format!("{}_TestData.json", base_filename);
+            fs::write(&fixtures_filename, serde_json::to_string_pretty(&format_test_data_fixtures(test_data))?).await?;
+            let fixtures_path = paths::absolute(&fixtures_filename);
+            status!("🧾 Test data fixtures saved: {}", fixtures_path.display());
+            let fields: usize = test_data.iter().map(|s| s.fields.len()).sum();
+            record("🧾 Test data fixtures", &fixtures_filename, &format!("Valid, boundary and invalid records for {} fields of {} actions", fields, test_data.len()));
+        }
+
+        // Save load tests if performance requirements state limits, as a k6 script and a Gatling simulation
+        if let Some(scenarios) = result.load_tests.as_ref().filter(|s| !s.is_empty()) {
+            let k6_filename = format!("{}_LoadTest.k6.js", base_filename);
+            fs::write(&k6_filename, format_k6_script(scenarios)).await?;
+            // Java wants the file named after the public class
+            let class_name = simulation_class_name(&artifact_file_name(base_filename));
+            let gatling_filename = Path::new(base_filename).with_file_name(format!("{}.java", class_name)).to_string_lossy().to_string();
+            fs::write(&gatling_filename, format_gatling_simulation(scenarios, &class_name)).await?;
+            let k6_path = paths::absolute(&k6_filename);
+            status!("🏋️ Load tests saved: {} (+ {})", k6_path.display(), artifact_file_name(&gatling_filename));
+            record("🏋️ k6 load test", &k6_filename, &format!("{} scenarios with the stated limits as thresholds", scenarios.len()));
+            record("🏋️ Gatling simulation", &gatling_filename, "The same scenarios as a Gatling simulation with assertions");
+        }
+
+        // Save the security tests, and a checklist to work through with ZAP or Burp
+        if let Some(tests) = result.security_tests.as_ref().filter(|t| !t.is_empty()) {
+            let tests_filename = format!("{}_SecurityTests.md", base_filename);
+            let tests_content = format!(
+                "{}# Security Tests for: {}\n*Generated by PRISM - AI-Powered Requirement Analyzer*\n\n{}",
+                links.back_link(),
+                base_filename,
+                format_security_tests_markdown(tests)
+            );
+            fs::write(&tests_filename, tests_content).await?;
+            let checklist_filename = format!("{}_SecurityChecklist.md", base_filename);
+            fs::write(&checklist_filename, format!("{}{}", links.back_link(), format_security_checklist(tests, base_filename))).await?;
+            let tests_path = paths::absolute(&tests_filename);
+            status!("🛡️  Security tests saved: {} (+ checklist)", tests_path.display());
+            record("🛡️ Security tests", &tests_filename, &format!("{} negative auth tests, injection probes and abuse cases", tests.len()));
+            record("🛡️ Security test checklist", &checklist_filename, "The same tests as a checklist to run with OWASP ZAP or Burp Suite");
+        }
+
+        // Save API contract tests if available, described in Markdown and as a Pact file
+        if let Some(contracts) = &result.contract_tests {
+            let contracts_filename = format!("{}_Contracts.md", base_filename);
+            let contracts_content = format!(
+                "{}# API Contract Tests for: {}\n*Generated by PRISM - AI-Powered Requirement Analyzer*\n\n{}",
+                links.back_link(),
+                base_filename,
+                format_contract_tests_markdown(contracts)
+            );
+            fs::write(&contracts_filename, contracts_content).await?;
+            let name = artifact_file_name(base_filename);
+            let pact_filename = format!("{}_Contracts.pact.json", base_filename);
+            let pact = format_contract_pact(contracts, &format!("{}-consumer", name), &format!("{}-api", name));
+            fs::write(&pact_filename, serde_json::to_string_pretty(&pact)?).await?;
+            let contracts_path = paths::absolute(&contracts_filename);
+            status!("🤝 API contract tests saved: {} (+ .pact.json)", contracts_path.display());
+            record("🤝 API contract tests", &contracts_filename, &format!("Pact-style interactions and Schemathesis runs for {} endpoints", contracts.len()));
+            record("🤝 Pact contract", &pact_filename, "Interactions to verify against the provider with a Pact verifier");
         }
 
         // Save NFR suggestions if available
         if let Some(nfrs) = &result.nfr_suggestions {
             let nfr_filename = format!("{}_NFR.md", base_filename);
-            let nfr_content = self.format_nfr_file(nfrs, base_filename);
+            let nfr_content = self.format_nfr_file(nfrs, base_filename, &links);
             fs::write(&nfr_filename, nfr_content).await?;
-            let nfr_path = std::fs::canonicalize(&nfr_filename).unwrap_or(PathBuf::from(&nfr_filename));
-            println!("🔒 Non-functional requirements saved: {}", nfr_path.display());
+            let nfr_path = paths::absolute(&nfr_filename);
+            status!("🔒 Non-functional requirements saved: {}", nfr_path.display());
+            record("🔒 Non-functional requirements", &nfr_filename, &format!("{} suggested NFRs, linked from the completeness gaps", nfrs.len()));
+        }
+
+        // Save per-finding rewrite suggestions if available
+        if let Some(suggestions) = &result.rewrite_suggestions {
+            let suggestions_filename = format!("{}_Suggestions.md", base_filename);
+            let suggestions_content = format!(
+                "{}# Rewrite Suggestions for: {}\n*Generated by PRISM - AI-Powered Requirement Analyzer*\n\n{}",
+                links.back_link(),
+                base_filename,
+                format_rewrite_suggestions(suggestions)
+            );
+            fs::write(&suggestions_filename, suggestions_content).await?;
+            let suggestions_path = paths::absolute(&suggestions_filename);
+            status!("✏️  Rewrite suggestions saved: {}", suggestions_path.display());
+            record("✏️ Rewrite suggestions", &suggestions_filename, &format!("{} per-finding rewrites, linked from the findings", suggestions.len()));
         }
 
-        println!("🎉 All artifacts saved successfully!");
+        // Save actor-permission matrix if available
+        if let Some(matrix) = &result.permission_matrix {
+            let permissions_filename = format!("{}_Permissions.md", base_filename);
+            let permissions_content = format!(
+                "{}# Actor Permission Matrix for: {}\n*Generated by PRISM - AI-Powered Requirement Analyzer*\n\n{}",
+                links.back_link(),
+                base_filename,
+                format_permission_table(matrix)
+            );
+            fs::write(&permissions_filename, permissions_content).await?;
+            let permissions_path = paths::absolute(&permissions_filename);
+            status!("🔐 Permission matrix saved: {}", permissions_path.display());
+
+            let csv_filename = format!("{}_Permissions.csv", base_filename);
+            fs::write(&csv_filename, self.format_permission_csv(matrix)).await?;
+            let csv_path = paths::absolute(&csv_filename);
+            status!("🔐 Permission matrix CSV saved: {}", csv_path.display());
+            record("🔐 Permission matrix", &permissions_filename, "Actor × action matrix and actions without a responsible actor");
+            record("🔐 Permission matrix (CSV)", &csv_filename, "The permission matrix for spreadsheets");
+        }
+
+        if links.index.is_some() {
+            let index_filename = format!("{}_Index.md", base_filename);
+            fs::write(&index_filename, format_artifact_index(&artifact_file_name(base_filename), &saved)).await?;
+            let index_path = paths::absolute(&index_filename);
+            status!("🗂️  Artifact index saved: {} (links {} artifacts)", index_path.display(), saved.len());
+        }
+
+        status!("🎉 All artifacts saved successfully!");
         Ok(())
     }
 
-    fn format_focused_analysis(&self, result: &AnalysisResult, input_text: &str) -> String {
-        let mut output = String::new();
+    fn format_focused_analysis(&self, result: &AnalysisResult, input_text: &str, links: &ArtifactLinks) -> String {
+        let mut output = links.back_link();
         
         output.push_str("# 🔍 PRISM Requirement Analysis Report\n\n");
 
@@ -1437,9 +3332,23 @@ impl App {
         // Summary section
         output.push_str("## 📊 Analysis Summary\n\n");
         output.push_str(&format!("- **Ambiguities Found:** {}\n", result.ambiguities.len()));
+        output.push_str(&format!("- **Ambiguity Density:** {:.1} per 100 words, {:.2} per requirement ({} words, {} requirements)\n",
+            result.metrics.findings_per_100_words, result.metrics.findings_per_requirement,
+            result.metrics.word_count, result.metrics.requirement_count));
+        if !result.suppressed_ambiguities.is_empty() {
+            output.push_str(&format!("- **Suppressed Findings:** {}\n", result.suppressed_ambiguities.len()));
+        }
+        if let Some(hidden) = result.hidden_findings.summary() {
+            output.push_str(&format!("- **Hidden Findings:** {}\n", hidden));
+        }
         output.push_str(&format!("- **Actors Identified:** {}\n", result.entities.actors.len()));
         output.push_str(&format!("- **Actions Identified:** {}\n", result.entities.actions.len()));
         output.push_str(&format!("- **Objects Identified:** {}\n\n", result.entities.objects.len()));
+        if let Some(graph) = &result.dependency_graph {
+            for cycle in &graph.cycles {
+                output.push_str(&format!("> ⚠️ **Dependency cycle:** {}\n\n", crate::dependencies::format_cycle(cycle)));
+            }
+        }
 
         // Ambiguities section
         if result.ambiguities.is_empty() {
@@ -1461,6 +3370,11 @@ impl App {
                 for suggestion in &ambiguity.suggestions {
                     output.push_str(&format!("  - {}\n", suggestion));
                 }
+                if let (Some(file), Some(rewrites)) = (&links.suggestions, &result.rewrite_suggestions) {
+                    if let Some(n) = suggestion_for(ambiguity, rewrites) {
+                        output.push_str(&format!("- **Proposed Rewrite:** {}\n", anchor_link(&format!("Suggestion #{}", n), file, &suggestion_anchor(n))));
+                    }
+                }
                 output.push_str("\n");
             }
         }
@@ -1518,6 +3432,14 @@ impl App {
                         output.push_str(&format!("- {}\n", suggestion));
                     }
                     output.push_str("\n");
+                    if let (Some(file), Some(nfrs)) = (&links.nfr, &result.nfr_suggestions) {
+                        let related: Vec<String> = nfrs_for_gap(gap, nfrs).iter()
+                            .map(|(id, anchor)| anchor_link(id, file, anchor))
+                            .collect();
+                        if !related.is_empty() {
+                            output.push_str(&format!("**Related NFRs:** {}\n\n", related.join(", ")));
+                        }
+                    }
                 }
             }
         }
@@ -1553,8 +3475,8 @@ impl App {
         output
     }
 
-    fn format_nfr_file(&self, nfrs: &Vec<crate::analyzer::NonFunctionalRequirement>, base_filename: &str) -> String {
-        let mut output = String::new();
+    fn format_nfr_file(&self, nfrs: &Vec<crate::analyzer::NonFunctionalRequirement>, base_filename: &str, links: &ArtifactLinks) -> String {
+        let mut output = links.back_link();
         
         output.push_str(&format!("# Non-Functional Requirements for: {}\n", base_filename));
         output.push_str("*Generated by PRISM - AI-Powered Requirement Analyzer*\n\n");
@@ -1587,6 +3509,7 @@ impl App {
                     crate::analyzer::NfrPriority::WontHave => "⚫ Won't Have",
                 };
                 
+                output.push_str(&format!("<a id=\"{}\"></a>\n\n", nfr_anchor(category, i + 1)));
                 output.push_str(&format!("### NFR-{:?}-{:02}\n\n", category, i + 1));
                 output.push_str(&format!("**Priority:** {}\n\n", priority_text));
                 output.push_str(&format!("**Requirement:** {}\n\n", nfr.requirement));
@@ -1606,174 +3529,1300 @@ impl App {
         output
     }
     
-    async fn process_directory_batch(
-        &self,
-        dir_path: &PathBuf,
+    fn format_permission_csv(&self, matrix: &PermissionMatrix) -> String {
+        // Actor and action names come from the document, so they are kept from running as formulas
+        let mut output = String::from("action");
+        for actor in &matrix.actors {
+            output.push(',');
+            output.push_str(&csv_cell(actor));
+        }
+        output.push('\n');
+
+        for action in &matrix.actions {
+            output.push_str(&csv_cell(action));
+            for actor in &matrix.actors {
+                output.push(',');
+                output.push_str(if matrix.is_granted(actor, action) { "yes" } else { "no" });
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+
+    async fn generate_hierarchy_report(
+        &self,
+        dir_path: &PathBuf,
         output: Option<PathBuf>,
         format: Option<OutputFormat>,
-        uml: bool,
-        pseudo: bool,
-        tests: bool,
-        improve: bool,
-        save_artifacts: Option<String>,
-        completeness: bool,
-        validate_story: bool,
-        nfr: bool,
-        pseudo_lang: Option<String>,
     ) -> Result<()> {
         if !dir_path.exists() || !dir_path.is_dir() {
             return Err(anyhow::anyhow!("Directory does not exist: {:?}", dir_path));
         }
 
-        println!("📁 Scanning directory for individual file processing: {}", dir_path.display());
+        status!("🌳 Detecting requirement hierarchy in: {}", dir_path.display());
+
+        let files = self.read_supported_files(dir_path).await?;
+
+        let root_name = dir_path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| dir_path.display().to_string());
+        let mut root = HierarchyNode::build(&root_name, &files);
+
+        let texts = root.texts_to_analyze();
+        status!("📋 Scoring {} requirement sections...", texts.len());
+        for (path, text) in texts {
+            let result = self.analyzer.analyze(&text).await?;
+            root.set_own_score(&path, QualityScore::from_result(&result));
+        }
+        root.aggregate();
+
+        let output_content = match format.unwrap_or(OutputFormat::Markdown) {
+            OutputFormat::Json => serde_json::to_string_pretty(&root)?,
+            _ => format_hierarchy_markdown(&root, dir_path),
+        };
+
+        if let Some(output_path) = output {
+            fs::write(&output_path, output_content).await?;
+            let absolute_path = paths::absolute(&output_path);
+            status!("📁 Hierarchy report saved: {}", absolute_path.display());
+        } else {
+            println!("{}", output_content);
+        }
+
+        Ok(())
+    }
+
+    /// Reads every supported file below `dir_path`, sorted by path. Paths are
+    /// returned relative to `dir_path`.
+    async fn read_supported_files(&self, dir_path: &PathBuf) -> Result<Vec<(PathBuf, String)>> {
+        if !dir_path.exists() || !dir_path.is_dir() {
+            return Err(anyhow::anyhow!("Directory does not exist: {:?}", dir_path));
+        }
+
+        let ignore = IgnoreGlobs::new(&self.config.analysis.ignore)?;
+        let mut paths: Vec<PathBuf> = WalkDir::new(dir_path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .map(|e| e.path().to_path_buf())
+            .filter(|path| path.is_file() && self.document_processor.is_supported_format(path))
+            .filter(|path| !ignore.is_ignored(path.strip_prefix(dir_path).unwrap_or(path)))
+            .collect();
+        paths.sort();
+
+        if paths.is_empty() {
+            return Err(anyhow::anyhow!("No readable files (.md, .txt, .rst, .pdf, .docx, .xlsx) found in directory"));
+        }
+
+        let mut files = Vec::new();
+        for path in paths {
+            match self.document_processor.extract_text_from_file(&path).await {
+                Ok(content) => {
+                    let relative = path.strip_prefix(dir_path).unwrap_or(&path).to_path_buf();
+                    files.push((relative, content));
+                }
+                Err(e) => eprintln!("⚠️  Could not process file {:?}: {}", path, e),
+            }
+        }
+
+        Ok(files)
+    }
+
+    async fn check_epic_coverage(&self, epic_path: &PathBuf, stories_dir: &PathBuf, threshold: f32) -> Result<CoverageReport> {
+        let epic_text = self.document_processor.extract_text_from_file(epic_path).await?;
+        let capabilities = extract_capabilities(&epic_text);
+        if capabilities.is_empty() {
+            return Err(anyhow::anyhow!("No capability statements found in epic: {:?}", epic_path));
+        }
+
+        status!("🧭 Checking {} epic capabilities against stories in: {}", capabilities.len(), stories_dir.display());
+
+        let stories: Vec<(String, String)> = self.read_supported_files(stories_dir).await?
+            .into_iter()
+            .map(|(path, content)| (path.display().to_string(), content))
+            .collect();
+
+        Ok(check_coverage(&capabilities, &stories, threshold))
+    }
+
+    /// Traces requirement changes between two commits of the repository
+    /// containing the working directory.
+    async fn generate_git_trace_report(&self, from: &str, to: &str, output: Option<PathBuf>, format: Option<OutputFormat>) -> Result<()> {
+        let git = GitIntegration::discover(Path::new("."))?;
+        let analysis = git.analyze_requirement_changes(from, to).await?;
+        let impact = &analysis.impact_analysis;
+        status!(
+            "📊 {} file(s) changed, {} requirement file(s) — regression risk {} {:?}",
+            impact.total_files_changed,
+            impact.requirement_files_changed,
+            impact.regression_risk.icon(),
+            impact.regression_risk
+        );
+
+        let output_content = match format.unwrap_or(OutputFormat::Markdown) {
+            OutputFormat::Json => serde_json::to_string_pretty(&analysis)?,
+            OutputFormat::Markdown | OutputFormat::Github => format_git_trace_markdown(&analysis),
+            OutputFormat::Jira => format_git_trace_jira(&analysis),
+            OutputFormat::Plain => format_git_trace_plain(&analysis),
+            OutputFormat::Html => format_git_trace_html(&analysis),
+            OutputFormat::Pdf => markdown_to_pdf(&format_git_trace_markdown(&analysis), "PRISM Requirement Trace"),
+            OutputFormat::JiraAdf | OutputFormat::Sarif | OutputFormat::Annotated | OutputFormat::Diagnostics | OutputFormat::Csv => {
+                return Err(anyhow::anyhow!("Trace reports are available as json, markdown, github, jira, plain, html or pdf"));
+            }
+        };
+
+        if let Some(output_path) = output {
+            fs::write(&output_path, output_content).await?;
+            let absolute_path = paths::absolute(&output_path);
+            status!("📁 Trace report saved: {}", absolute_path.display());
+        } else {
+            println!("{}", output_content);
+        }
+
+        Ok(())
+    }
+
+    /// Traces every requirement in `input_text` to the code and tests that mention it.
+    async fn generate_traceability_report(
+        &self,
+        input_text: &str,
+        source_dir: Option<&Path>,
+        test_dir: Option<&Path>,
+        output: Option<PathBuf>,
+        format: Option<OutputFormat>,
+    ) -> Result<()> {
+        if let Some(src) = source_dir {
+            status!("📁 Scanning source directory: {}", src.display());
+        }
+        if let Some(test) = test_dir {
+            status!("🧪 Scanning test directory: {}", test.display());
+        }
+
+        let analyzer = TraceabilityAnalyzer::new();
+        let requirements = analyzer.extract_requirements(input_text);
+        if requirements.is_empty() {
+            return Err(anyhow::anyhow!("No requirements found to trace. List them as bullets, user stories or 'shall' statements"));
+        }
+
+        let matrix = analyzer.analyze_traceability(&requirements, source_dir, test_dir).await?;
+        let summary = &matrix.coverage_summary;
+        status!(
+            "📊 {}/{} requirements traced ({:.0}%) across {} code and {} test files, {} orphaned functions",
+            summary.traced_requirements,
+            summary.total_requirements,
+            summary.coverage_percentage,
+            summary.code_files_analyzed,
+            summary.test_files_analyzed,
+            matrix.orphaned_code.len()
+        );
+
+        let output_content = match format.unwrap_or(OutputFormat::Markdown) {
+            OutputFormat::Json => serde_json::to_string_pretty(&matrix)?,
+            OutputFormat::Markdown | OutputFormat::Github => format_traceability_markdown(&matrix),
+            _ => return Err(anyhow::anyhow!("Traceability matrices are available as json, markdown or github")),
+        };
+
+        if let Some(output_path) = output {
+            fs::write(&output_path, output_content).await?;
+            let absolute_path = paths::absolute(&output_path);
+            status!("📁 Traceability matrix saved: {}", absolute_path.display());
+        } else {
+            println!("{}", output_content);
+        }
+
+        Ok(())
+    }
+
+    async fn generate_coverage_report(
+        &self,
+        epic_path: &PathBuf,
+        stories_dir: &PathBuf,
+        threshold: f32,
+        output: Option<PathBuf>,
+        format: Option<OutputFormat>,
+    ) -> Result<()> {
+        let report = self.check_epic_coverage(epic_path, stories_dir, threshold).await?;
+        status!(
+            "📊 Coverage: {:.0}% — {} uncovered capabilities, {} orphan stories",
+            report.coverage_percent(),
+            report.uncovered.len(),
+            report.orphan_stories.len()
+        );
+
+        let epic_name = epic_path.display().to_string();
+        let output_content = match format.unwrap_or(OutputFormat::Markdown) {
+            OutputFormat::Json => serde_json::to_string_pretty(&report)?,
+            _ => format_coverage_markdown(&report, &epic_name),
+        };
+
+        if let Some(output_path) = output {
+            fs::write(&output_path, output_content).await?;
+            let absolute_path = paths::absolute(&output_path);
+            status!("📁 Coverage report saved: {}", absolute_path.display());
+        } else {
+            println!("{}", output_content);
+        }
+
+        Ok(())
+    }
+
+    /// `prism analyze --compare-providers`: the text analyzed with each
+    /// provider at the same time, side by side.
+    async fn compare_providers(
+        &self,
+        input_text: &str,
+        requested: &[AiProvider],
+        output: Option<PathBuf>,
+        format: Option<OutputFormat>,
+    ) -> Result<()> {
+        let mut providers: Vec<String> = Vec::new();
+        for provider in requested.iter().map(|p| p.name().to_string()) {
+            if !providers.contains(&provider) {
+                providers.push(provider);
+            }
+        }
+        if providers.is_empty() {
+            providers = self.config.comparable_providers();
+        }
+        if providers.len() < 2 {
+            return Err(anyhow::anyhow!(
+                "Comparing needs at least two providers, got {}. Add the others under llm.providers in ~/.prism/config.yml, or name them: --compare-providers=openai,ollama",
+                if providers.is_empty() { "none".to_string() } else { providers.join(", ") }
+            ));
+        }
+        let configs = providers.iter().map(|provider| self.config.for_provider(provider)).collect::<Result<Vec<_>>>()?;
+        let comparison = self.run_provider_comparison(input_text, configs, 1).await?;
+        self.write_provider_comparison(&comparison, output, format).await
+    }
+
+    /// `prism bench-providers`: every configured provider, or the given
+    /// provider:model pairs, on the same text, `runs` times each.
+    async fn bench_providers(
+        &self,
+        input_text: &str,
+        models: &[String],
+        runs: usize,
+        output: Option<PathBuf>,
+        format: Option<OutputFormat>,
+    ) -> Result<()> {
+        if runs == 0 {
+            return Err(anyhow::anyhow!("--runs must be at least 1"));
+        }
+        let configs = if models.is_empty() {
+            self.config.comparable_providers().iter().map(|provider| self.config.for_provider(provider)).collect::<Result<Vec<_>>>()?
+        } else {
+            models.iter().map(|target| {
+                let (provider, model) = parse_model_target(target);
+                let mut config = self.config.for_provider(&provider)?;
+                if let Some(model) = model {
+                    config.llm.model = model;
+                }
+                Ok(config)
+            }).collect::<Result<Vec<_>>>()?
+        };
+        if configs.is_empty() {
+            return Err(anyhow::anyhow!(
+                "No AI provider configured to benchmark. Run prism config --setup, add providers under llm.providers in ~/.prism/config.yml, or name them: --models openai:gpt-4o,ollama:llama3.1"
+            ));
+        }
+        let comparison = self.run_provider_comparison(input_text, configs, runs).await?;
+        self.write_provider_comparison(&comparison, output, format).await
+    }
+
+    /// Analyzes `input_text` with each of `configs` at the same time, `runs`
+    /// times one after the other, and compares the outcomes.
+    async fn run_provider_comparison(&self, input_text: &str, configs: Vec<Config>, runs: usize) -> Result<ProviderComparison> {
+        let names: Vec<String> = configs.iter().map(|c| format!("{} ({})", c.llm.provider, c.llm.model)).collect();
+        let repeat = if runs > 1 { format!(", {} runs each", runs) } else { String::new() };
+        status!("⚖️  Analyzing with {} at the same time{}...", names.join(", "), repeat);
+
+        let workers: Vec<_> = configs.into_iter().map(|config| {
+            let analyzer = self.analyzer.with_separate_usage().with_config(config.clone());
+            let text = input_text.to_string();
+            tokio::spawn(async move {
+                let mut durations = Vec::new();
+                let mut result = Err(String::new());
+                for _ in 0..runs {
+                    let started = std::time::Instant::now();
+                    result = analyzer.analyze(&text).await.map_err(|e| format!("{:#}", e));
+                    durations.push(started.elapsed());
+                    if result.is_err() {
+                        break;
+                    }
+                }
+                let total = analyzer.llm_usage();
+                let completed = durations.len();
+                ProviderRun {
+                    provider: config.llm.provider,
+                    model: config.llm.model,
+                    runs: completed,
+                    elapsed: median_duration(durations),
+                    usage: LlmUsage {
+                        calls: total.calls / completed,
+                        prompt_chars: total.prompt_chars / completed,
+                        response_chars: total.response_chars / completed,
+                        ..LlmUsage::default()
+                    },
+                    result,
+                }
+            })
+        }).collect();
+        let mut provider_runs = Vec::new();
+        for worker in workers {
+            provider_runs.push(worker.await?);
+        }
+        let comparison = compare_runs(&provider_runs);
+        for summary in &comparison.providers {
+            match &summary.error {
+                Some(error) => status!("❌ {} ({}): {}", summary.provider, summary.model, error),
+                None => status!(
+                    "✅ {} ({}): {} finding(s), {} actor(s) in {:.1}s, estimated ${:.4}",
+                    summary.provider, summary.model, summary.findings, summary.actors.len(), summary.response_ms as f64 / 1000.0, summary.estimated_cost
+                ),
+            }
+        }
+        Ok(comparison)
+    }
+
+    async fn write_provider_comparison(&self, comparison: &ProviderComparison, output: Option<PathBuf>, format: Option<OutputFormat>) -> Result<()> {
+        let output_content = match format {
+            Some(OutputFormat::Json) => serde_json::to_string_pretty(comparison)?,
+            _ => format_provider_comparison_markdown(comparison),
+        };
+        if let Some(output_path) = output {
+            fs::write(&output_path, output_content).await?;
+            let absolute_path = paths::absolute(&output_path);
+            status!("📁 Provider comparison saved: {}", absolute_path.display());
+        } else {
+            println!("{}", output_content);
+        }
+        Ok(())
+    }
+
+    /// `prism selftest`: the suite with the rules alone, then with every
+    /// configured AI provider at the same time.
+    async fn run_selftest(&self, suite: Option<PathBuf>, rules_only: bool, output: Option<PathBuf>, format: Option<OutputFormat>) -> Result<()> {
+        let (suite_name, cases) = match &suite {
+            Some(path) => {
+                let yaml = std::fs::read_to_string(path).with_context(|| format!("Cannot read {}", path.display()))?;
+                (path.display().to_string(), parse_suite(&yaml).with_context(|| format!("In {}", path.display()))?)
+            }
+            None => ("built-in".to_string(), parse_suite(BUILT_IN_SUITE)?),
+        };
+        let known_bad = cases.iter().filter(|c| c.is_known_bad()).count();
+        status!("🧪 Running {} case(s) ({} known-bad, {} known-good)...", cases.len(), known_bad, cases.len() - known_bad);
+
+        let mut rules_config = self.config.clone();
+        rules_config.llm.api_key = None;
+        let mut configs = vec![rules_config];
+        if !rules_only && self.config.is_ai_configured() {
+            for provider in self.config.comparable_providers() {
+                configs.push(self.config.for_provider(&provider)?);
+            }
+        }
+
+        let cases = Arc::new(cases);
+        let workers: Vec<_> = configs.into_iter().enumerate().map(|(index, config)| {
+            let ai = index > 0;
+            let (provider, model) = if ai {
+                (config.llm.provider.clone(), Some(config.llm.model.clone()))
+            } else {
+                ("built-in".to_string(), None)
+            };
+            let analyzer = self.analyzer.with_separate_usage().with_config(config);
+            let cases = cases.clone();
+            tokio::spawn(async move {
+                let mut outcomes = Vec::new();
+                for case in cases.iter() {
+                    match analyzer.analyze(&case.text).await {
+                        Ok(result) if ai && result.ai_failed => {
+                            return SelftestRun::failed(&provider, model, format!("AI analysis failed on case '{}'", case.id));
+                        }
+                        Ok(result) => outcomes.push(CaseOutcome::new(case, &result, ai)),
+                        Err(e) => return SelftestRun::failed(&provider, model, format!("{:#}", e)),
+                    }
+                }
+                SelftestRun::new(&provider, model, outcomes)
+            })
+        }).collect();
+        let mut runs = Vec::new();
+        for worker in workers {
+            let run = worker.await?;
+            match &run.error {
+                Some(error) => status!("❌ {}: {}", run.provider, error),
+                None => status!(
+                    "✅ {}: precision {}, recall {}",
+                    run.provider,
+                    run.overall.precision.map_or("-".to_string(), |p| format!("{:.0}%", p * 100.0)),
+                    run.overall.recall.map_or("-".to_string(), |r| format!("{:.0}%", r * 100.0))
+                ),
+            }
+            runs.push(run);
+        }
+
+        let report = SelftestReport { suite: suite_name, known_bad, known_good: cases.len() - known_bad, runs };
+        let output_content = match format {
+            Some(OutputFormat::Json) => serde_json::to_string_pretty(&report)?,
+            _ => format_selftest_markdown(&report),
+        };
+        if let Some(output_path) = output {
+            fs::write(&output_path, output_content).await?;
+            let absolute_path = paths::absolute(&output_path);
+            status!("📁 Self-test report saved: {}", absolute_path.display());
+        } else {
+            println!("{}", output_content);
+        }
+        Ok(())
+    }
+
+    /// `prism merge`: one result from the JSON results of several files.
+    async fn merge_analysis_results(&self, files: &[PathBuf], output: Option<PathBuf>, format: Option<OutputFormat>) -> Result<()> {
+        let mut results = Vec::new();
+        for path in files {
+            let content = std::fs::read_to_string(path).with_context(|| format!("Cannot read {}", path.display()))?;
+            let result: AnalysisResult = serde_json::from_str(&content)
+                .with_context(|| format!("{} is not a JSON report of prism analyze --format json", path.display()))?;
+            results.push(result);
+        }
+        let merged = merge_results(results)?;
+        status!(
+            "🧩 Merged {} result(s): {} finding(s) in {} requirement(s), quality score {:.0}",
+            files.len(), merged.ambiguities.len(), merged.metrics.requirement_count, QualityScore::from_result(&merged).score
+        );
+
+        let output_content = self.render_report(&merged, "", &format.unwrap_or(OutputFormat::Json), None)?;
+        if let Some(output_path) = output {
+            fs::write(&output_path, output_content).await?;
+            let absolute_path = paths::absolute(&output_path);
+            status!("📁 Merged result saved: {}", absolute_path.display());
+        } else {
+            println!("{}", output_content);
+        }
+        Ok(())
+    }
+
+    /// `prism report diff`: what changed between two saved JSON reports.
+    async fn generate_report_diff(
+        &self,
+        old_path: &Path,
+        new_path: &Path,
+        output: Option<PathBuf>,
+        format: Option<OutputFormat>,
+    ) -> Result<()> {
+        let read = |path: &Path| -> Result<AnalysisResult> {
+            let content = std::fs::read_to_string(path).with_context(|| format!("Cannot read {}", path.display()))?;
+            serde_json::from_str(&content).with_context(|| format!("{} is not a JSON report of prism analyze --format json", path.display()))
+        };
+        let diff = diff_results(&read(old_path)?, &read(new_path)?);
+        status!(
+            "🔀 {} new, {} resolved finding(s); quality score {:.0} → {:.0}",
+            diff.added.len(), diff.resolved.len(), diff.quality.old, diff.quality.new
+        );
+
+        let output_content = match format.unwrap_or(OutputFormat::Markdown) {
+            OutputFormat::Json => serde_json::to_string_pretty(&diff)?,
+            _ => format_report_diff_markdown(&diff, &old_path.display().to_string(), &new_path.display().to_string()),
+        };
+        if let Some(output_path) = output {
+            fs::write(&output_path, output_content).await?;
+            let absolute_path = paths::absolute(&output_path);
+            status!("📁 Comparison saved: {}", absolute_path.display());
+        } else {
+            println!("{}", output_content);
+        }
+        Ok(())
+    }
+
+    /// `prism adr`: links the requirements with an ID to the ADRs citing them.
+    async fn generate_adr_report(
+        &self,
+        requirements: &PathBuf,
+        adr_dir: Option<PathBuf>,
+        output: Option<PathBuf>,
+        format: Option<OutputFormat>,
+    ) -> Result<()> {
+        let adr_dir = find_adr_dir(adr_dir.as_deref()).ok_or_else(|| anyhow::anyhow!(
+            "No ADR directory found (looked in {}). Use --adr-dir", ADR_LOCATIONS.join(", ")
+        ))?;
+        status!("🏛️  Linking requirements in {} to the ADRs in {}", requirements.display(), adr_dir.display());
+
+        let documents: Vec<(String, String)> = if requirements.is_dir() {
+            self.read_supported_files(requirements).await?
+                .into_iter()
+                .map(|(path, content)| (path.display().to_string(), content))
+                .collect()
+        } else {
+            vec![(requirements.display().to_string(), self.document_processor.extract_text_from_file(requirements).await?)]
+        };
+        let decisions = self.read_supported_files(&adr_dir).await?
+            .into_iter()
+            .map(|(path, content)| DecisionRecord::parse(&path.display().to_string(), &content))
+            .collect();
+
+        let report = AdrLinkReport::new(&documents, decisions);
+        status!(
+            "📊 {} of {} requirements constrained by ADRs, {} ADR(s) without a driving requirement",
+            report.constrained().len(), report.requirements.len(), report.undriven().len()
+        );
+
+        let output_content = match format.unwrap_or(OutputFormat::Markdown) {
+            OutputFormat::Json => serde_json::to_string_pretty(&report)?,
+            _ => format_adr_markdown(requirements, &adr_dir, &report),
+        };
+
+        if let Some(output_path) = output {
+            fs::write(&output_path, output_content).await?;
+            let absolute_path = paths::absolute(&output_path);
+            status!("📁 ADR report saved: {}", absolute_path.display());
+        } else {
+            println!("{}", output_content);
+        }
+
+        Ok(())
+    }
+
+    async fn generate_release_check(
+        &self,
+        input_text: &str,
+        epic: Option<PathBuf>,
+        stories_dir: Option<PathBuf>,
+        output: Option<PathBuf>,
+        format: Option<OutputFormat>,
+    ) -> Result<()> {
+        status!("🚦 Checking release readiness...");
+
+        let mut result = self.analyzer.analyze(input_text).await?;
+        self.apply_baseline(&mut result, input_text);
+        let completeness = self.analyzer.analyze_completeness(input_text, &result.entities).await?;
+
+        let coverage = match (epic, stories_dir) {
+            (Some(epic_path), Some(dir_path)) => {
+                let report = self.check_epic_coverage(&epic_path, &dir_path, DEFAULT_COVERAGE_THRESHOLD).await?;
+                Some(report.coverage_percent())
+            }
+            (Some(_), None) => {
+                return Err(anyhow::anyhow!("--epic needs the stories to check against. Use --dir"));
+            }
+            _ => None,
+        };
+
+        let readiness = evaluate_release(
+            &result,
+            &completeness,
+            coverage,
+            find_open_questions(input_text),
+            &self.config.release_gate,
+        );
+
+        let output_content = match format.unwrap_or(OutputFormat::Markdown) {
+            OutputFormat::Json => serde_json::to_string_pretty(&readiness)?,
+            _ => format_release_markdown(&readiness),
+        };
+
+        if let Some(output_path) = output {
+            fs::write(&output_path, output_content).await?;
+            let absolute_path = paths::absolute(&output_path);
+            status!("📁 Release readiness report saved: {}", absolute_path.display());
+        } else {
+            println!("{}", output_content);
+        }
+
+        if readiness.ready {
+            status!("✅ GO: all release gates passed");
+            Ok(())
+        } else {
+            let failed: Vec<&str> = readiness.gates.iter()
+                .filter(|g| g.passed == Some(false))
+                .map(|g| g.name.as_str())
+                .collect();
+            Err(anyhow::anyhow!("NO-GO: failed release gates: {}", failed.join(", ")))
+        }
+    }
+
+    /// `prism evidence`: the requirements, their analysis, traceability matrix,
+    /// review checklist and improvement history as a hash-manifested ZIP.
+    /// Returns the ZIP's path.
+    async fn generate_evidence_bundle(
+        &self,
+        input_text: &str,
+        input_path: Option<&Path>,
+        source_dir: Option<&Path>,
+        test_dir: Option<&Path>,
+        job: Option<&str>,
+        output: Option<PathBuf>,
+    ) -> Result<PathBuf> {
+        let source = input_path.map_or("inline text".to_string(), |p| self.report_source_name(p));
+        let source = source.as_str();
+        let generated_at = chrono::Utc::now();
+        let mut bundle = EvidenceBundle::new();
+        status!("🗄️  Collecting audit evidence for {}...", source);
+
+        match input_path.filter(|p| p.is_file()) {
+            Some(path) => bundle.add(&format!("requirements/{}", artifact_file_name(&path.to_string_lossy())), fs::read(path).await?),
+            None => bundle.add("requirements/requirements.md", input_text),
+        }
+
+        let result = self.analyzer.analyze(input_text).await?;
+        bundle.add("analysis/report.md", self.render_report(&result, input_text, &OutputFormat::Markdown, Some(source))?);
+        bundle.add("analysis/report.json", self.render_report(&result, input_text, &OutputFormat::Json, Some(source))?);
+        status!("📋 Analysis: {} findings", result.ambiguities.len());
+
+        let tracer = TraceabilityAnalyzer::new();
+        let requirements = tracer.extract_requirements(input_text);
+        let untraced = if requirements.is_empty() {
+            status!("⚠️  No requirements found to trace - the bundle has no traceability matrix");
+            None
+        } else {
+            let matrix = tracer.analyze_traceability(&requirements, source_dir, test_dir).await?;
+            let summary = &matrix.coverage_summary;
+            status!("🔗 Traceability: {}/{} requirements traced", summary.traced_requirements, summary.total_requirements);
+            bundle.add("traceability/matrix.md", format_traceability_markdown(&matrix));
+            bundle.add("traceability/matrix.json", serde_json::to_string_pretty(&matrix)?);
+            Some(summary.total_requirements - summary.traced_requirements)
+        };
+
+        let open_questions = find_open_questions(input_text);
+        bundle.add("review/checklist.md", format_review_checklist(&result, &open_questions, untraced, source, generated_at));
+
+        let commits = match input_path.map(|path| (path, GitIntegration::discover(path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."))))) {
+            Some((path, Ok(git))) => git.file_history(path).unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        let runs = match job {
+            Some(job) => {
+                let history = AnalysisHistory::new(match &self.config.daemon.history_path {
+                    Some(path) => path.clone(),
+                    None => AnalysisHistory::default_path()?,
+                });
+                history.entries()?.into_iter().filter(|entry| entry.job == job).collect()
+            }
+            None => Vec::new(),
+        };
+        status!("🕰️  History: {} revisions, {} scheduled runs", commits.len(), runs.len());
+        bundle.add("history/improvements.md", format_improvement_history(&commits, &runs, source));
+
+        let output_path = output.unwrap_or_else(|| PathBuf::from(default_bundle_name(generated_at)));
+        if let Some(parent) = output_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).await?;
+        }
+        let manifest = bundle.manifest(source, generated_at);
+        bundle.write_zip(std::fs::File::create(&output_path)?, &manifest)?;
+        let digest = sha256_hex(&fs::read(&output_path).await?);
+        let absolute_path = paths::absolute(&output_path);
+        status!("📦 Evidence bundle saved: {} ({} files)", absolute_path.display(), manifest.files.len() + 2);
+        status!("🔏 SHA-256: {}", digest);
+        Ok(absolute_path)
+    }
+
+    /// Analyzes each file under `dir_path` into its own report. `source` is the
+    /// directory or archive the user named, shown in place of `dir_path`.
+    async fn process_directory_batch(&self, dir_path: &PathBuf, source: &Path, options: BatchOptions) -> Result<()> {
+        let BatchOptions {
+            output,
+            format,
+            plan,
+            save_artifacts,
+            pseudo_lang,
+            max_comment_size,
+            budget,
+            resume,
+            changed_since,
+            output_dir,
+            jobs,
+            no_cache,
+            gate,
+            owners_file,
+        } = options;
+        if !dir_path.exists() || !dir_path.is_dir() {
+            return Err(anyhow::anyhow!("Directory does not exist: {:?}", dir_path));
+        }
+        let code_owners = CodeOwners::load(dir_path, owners_file.as_deref().or(self.config.analysis.owners_file.as_deref()))?;
+        let mut ownership = OwnershipReport::default();
+        let mut statements = Vec::new();
+
+        status!("📁 Scanning {} for individual file processing", source.display());
+        let mut builtin_files = 0;
+        let mut cached_files = 0;
         
         let mut processed_files = Vec::new();
         let mut file_count = 0;
+        let mut total_words = 0;
+        let mut total_findings = 0;
 
         // Collect all supported files first
+        let ignore = IgnoreGlobs::new(&self.config.analysis.ignore)?;
         for entry in WalkDir::new(dir_path).into_iter().filter_map(|e| e.ok()) {
             let path = entry.path();
-            if path.is_file() && self.document_processor.is_supported_format(path) {
+            if path.is_file() && self.document_processor.is_supported_format(path)
+                && !ignore.is_ignored(path.strip_prefix(dir_path).unwrap_or(path))
+                && changed_since.as_ref().is_none_or(|(_, changed)| changed.contains(&path.canonicalize().unwrap_or_else(|_| path.to_path_buf()))) {
                 processed_files.push(path.to_path_buf());
             }
         }
 
+        if let Some((since, _)) = changed_since {
+            if processed_files.is_empty() {
+                status!("✅ No requirement files changed since {} - nothing to analyze", since);
+                return Ok(());
+            }
+            status!("🔀 Analyzing only the files changed since {}", since);
+        }
         if processed_files.is_empty() {
             return Err(anyhow::anyhow!("No readable files (.md, .txt, .rst, .pdf, .docx, .xlsx) found in directory"));
         }
-
-        println!("📊 Found {} requirement files to process individually", processed_files.len());
+        // A stable order keeps collision-suffixed report names the same from run to run
+        processed_files.sort();
+
+        status!("📊 Found {} requirement files to process individually", processed_files.len());
+
+        let total_files = processed_files.len();
+        let job_cache_options = cache_options(&plan, pseudo_lang.as_deref(), &self.analyzer);
+        let settings = ResumeManifest::settings(
+            &format!("{:?} {:?}", format, save_artifacts),
+            &job_cache_options,
+            output.as_deref(),
+            output_dir.as_deref(),
+        );
+        let mut previous_run = if resume { ResumeManifest::path(dir_path).ok().and_then(|path| ResumeManifest::load(&path)) } else { None };
+        if previous_run.as_ref().is_some_and(|previous| previous.settings != settings) {
+            status!("💡 The previous run used another format, other analysis options or another output - processing all files");
+            previous_run = None;
+        } else if resume && previous_run.is_none() {
+            status!("💡 Nothing to resume for {} - processing all files", dir_path.display());
+        }
+        let mut manifest = ResumeManifest {
+            settings,
+            pending: processed_files.iter().map(|f| relative_name(dir_path, f)).collect(),
+            ..Default::default()
+        };
+        let mut skipped_files = Vec::new();
+        let mut taken_report_paths = HashSet::new();
+        let mut index_entries = Vec::new();
+        let mut failures = Vec::new();
+        let mut gate_failures = Vec::new();
+        let cancelled = self.cancel.subscribe();
+
+        // Workers extract and analyze up to `jobs` files at a time; results are handled in file order
+        let jobs = jobs.max(1);
+        if jobs > 1 {
+            status!("⚙️  Processing up to {} files at a time", jobs);
+        }
+        let job = Arc::new(BatchJob {
+            plan,
+            cache_options: job_cache_options,
+            pseudo_lang,
+            cache: self.analysis_cache(no_cache),
+            previous_run,
+            tracker: std::sync::Mutex::new(budget.map(BudgetTracker::start)),
+            builtin_analyzer: self.analyzer.without_ai(),
+            cancelled: cancelled.clone(),
+        });
+        let worker = Arc::new(self.clone());
+        let semaphore = Arc::new(Semaphore::new(jobs));
+        let workers: Vec<_> = processed_files.iter().map(|file_path| {
+            let (worker, job, semaphore) = (worker.clone(), job.clone(), semaphore.clone());
+            let file_path = file_path.clone();
+            let file_name = relative_name(dir_path, &file_path);
+            tokio::spawn(async move {
+                let _permit = semaphore.acquire_owned().await.expect("batch semaphore is never closed");
+                worker.analyze_batch_file(&job, &file_path, &file_name).await
+            })
+        }).collect();
 
         // Process each file individually
-        for file_path in processed_files {
-            println!("\n🔍 Processing: {}", file_path.display());
+        for (position, (file_path, handle)) in processed_files.into_iter().zip(workers).enumerate() {
+            // A worker that panicked fails its own file, not the rest of the batch
+            let outcome = handle.await.unwrap_or_else(|e| BatchFileOutcome {
+                log: Vec::new(),
+                result: BatchFileResult::AnalysisFailed { error: anyhow::anyhow!("Analysis stopped unexpectedly: {}", e) },
+            });
+            let file_name = relative_name(dir_path, &file_path);
+            // Files of an unpacked archive are shown inside the archive, not the temporary directory
+            let shown_path = source.join(&file_name);
+            let report_name = report_file_name(&file_path, output.as_deref());
+            let individual_output = unique_path(
+                report_path(dir_path, &file_path, output_dir.as_deref(), &report_name),
+                &mut taken_report_paths,
+            );
+            let index_entry = |findings: Option<usize>, density: Option<f32>| BatchIndexEntry {
+                source: file_name.clone(),
+                report: output_dir.as_deref()
+                    .map(|out| relative_name(out, &individual_output))
+                    .unwrap_or_default(),
+                findings,
+                findings_per_100_words: density,
+            };
+            let progress = format!("[{}/{}]", position + 1, total_files);
             
-            match self.document_processor.extract_text_from_file(&file_path).await {
-                Ok(content) => {
-                    println!("📄 Loaded {} characters from {}", content.len(), file_path.file_name().unwrap().to_string_lossy());
-                    
-                    if self.config.is_ai_configured() {
-                        let (provider_name, _) = self.config.get_provider_info();
-                        println!("🤖 Analyzing with {} ({})...", provider_name, self.config.llm.model);
-                    } else {
-                        println!("📋 Analyzing with built-in analysis...");
+            match outcome.result {
+                // Ctrl-C stops new files from being started; files in progress are finished first
+                BatchFileResult::NotStarted => {}
+                BatchFileResult::Unchanged { content } => {
+                    status!("⏭️  {} Skipping {} (completed in the previous run, unchanged)", progress, file_name);
+                    statements.extend(extract_statements(&file_name, &content));
+                    manifest.mark_completed(&file_name, &content);
+                    index_entries.push(index_entry(None, None));
+                    skipped_files.push(file_name.clone());
+                }
+                BatchFileResult::AnalysisFailed { error } => {
+                    status!("\n🔍 {} Processing: {}", progress, shown_path.display());
+                    emit(&Event::FileStarted { file: &file_name, index: position + 1, total: total_files });
+                    emit(&Event::FileFailed { file: &file_name, error: error.to_string() });
+                    outcome.log.iter().for_each(|line| status!("{}", line));
+                    eprintln!("⚠️  Could not analyze file {:?}: {}", shown_path, error);
+                    let failure = BatchFailure::new(&file_name, FailureStage::Analysis, &error);
+                    eprintln!("💡 {}", failure.remediation);
+                    failures.push(failure);
+                    manifest.mark_failed(&file_name);
+                }
+                BatchFileResult::Analyzed { content, mut result, builtin, cached } => {
+                    status!("\n🔍 {} Processing: {}", progress, shown_path.display());
+                    emit(&Event::FileStarted { file: &file_name, index: position + 1, total: total_files });
+                    result.set_source_file(&file_name);
+                    self.apply_baseline(&mut result, &content);
+                    outcome.log.iter().for_each(|line| status!("{}", line));
+                    if builtin {
+                        builtin_files += 1;
                     }
-                    
-                    // Analyze the individual file
-                    let mut result = self.analyzer.analyze(&content).await?;
-
-                    if uml {
-                        println!("🎨 Generating UML diagrams...");
-                        let use_case = self.analyzer.generate_uml_use_case(&result.entities);
-                        let sequence = self.analyzer.generate_uml_sequence(&result.entities);
-                        let class_diagram = self.analyzer.generate_uml_class_diagram(&result.entities);
-                        result.uml_diagrams = Some(crate::analyzer::UmlDiagrams {
-                            use_case: Some(use_case),
-                            sequence: Some(sequence),
-                            class_diagram: Some(class_diagram),
-                        });
+                    if cached {
+                        cached_files += 1;
                     }
 
-                    if pseudo {
-                        println!("📝 Generating pseudocode structure...");
-                        let pseudocode = self.analyzer.generate_pseudocode(&result.entities, pseudo_lang.as_deref());
-                        result.pseudocode = Some(pseudocode);
+                    if let Some(parent) = individual_output.parent().filter(|p| !p.as_os_str().is_empty()) {
+                        fs::create_dir_all(parent).await?;
                     }
 
-                    if tests {
-                        println!("🧪 Generating test cases...");
-                        let test_cases = self.analyzer.generate_test_cases(&result.entities);
-                        result.test_cases = Some(test_cases);
+                    // Save individual artifacts if requested, next to the report
+                    if let Some(ref base_filename) = save_artifacts {
+                        let file_stem = file_path.file_stem().unwrap().to_string_lossy();
+                        let artifact_base = unique_path(
+                            individual_output.with_file_name(format!("{}_{}", base_filename, file_stem)),
+                            &mut taken_report_paths,
+                        );
+                        self.save_individual_artifacts(&result, &artifact_base.to_string_lossy(), &content).await?;
                     }
 
-                    if improve {
-                        println!("✨ Generating improved requirements...");
-                        match self.analyzer.generate_improved_requirements(&content, &result.ambiguities).await {
-                            Ok(improved_req) => {
-                                result.improved_requirements = Some(improved_req);
-                                println!("✅ Requirements improvement completed!");
-                            }
-                            Err(e) => {
-                                eprintln!("⚠️  Could not generate improved requirements: {}", e);
-                                if !self.config.is_ai_configured() {
-                                    println!("💡 Suggestions:");
-                                    println!("1. Configure AI provider: 'prism config --setup'");
-                                    println!("2. Verify API credentials");
-                                    println!("3. Try 'prism config --debug' for more info");
-                                }
-                            }
+                    // Output the result for this file
+                    let output_format = format.clone().unwrap_or(OutputFormat::Markdown);
+                    if is_xlsx_path(&individual_output) {
+                        self.save_findings_xlsx(&individual_output, &result, Some(&file_name))?;
+                    } else {
+                        let output_content = self.render_report(&result, &content, &output_format, Some(&self.report_source_name(&shown_path)))?;
+                        if matches!(output_format, OutputFormat::Github) {
+                            self.write_github_comments(&individual_output, &output_content, max_comment_size).await?;
+                        } else {
+                            fs::write(&individual_output, output_content).await?;
                         }
                     }
-
-                    if completeness {
-                        println!("📊 Analyzing completeness and identifying gaps...");
-                        let completeness_analysis = self.analyzer.analyze_completeness(&content, &result.entities).await?;
-                        result.completeness_analysis = Some(completeness_analysis);
+                    let absolute_path = paths::absolute(&individual_output);
+                    status!("📁 Analysis report created and saved: {}", absolute_path.display());
+                    emit(&Event::ReportSaved { path: &absolute_path });
+                    emit_result(Some(&file_name), &result);
+                    
+                    status!("✅ Completed analysis for: {} ({:.1} findings per 100 words)", shown_path.display(), result.metrics.findings_per_100_words);
+                    index_entries.push(index_entry(Some(result.ambiguities.len()), Some(result.metrics.findings_per_100_words)));
+                    total_words += result.metrics.word_count;
+                    total_findings += result.ambiguities.len();
+                    file_count += 1;
+                    let violations = gate.violations(&result);
+                    if !violations.is_empty() {
+                        gate_failures.push(format!("{}: {}", file_name, violations.join(", ")));
                     }
-
-                    if validate_story {
-                        println!("✅ Validating user story format and business value...");
-                        let validation = self.analyzer.validate_user_story(&content);
-                        result.user_story_validation = Some(validation);
+                    if let Some(code_owners) = &code_owners {
+                        ownership.add(&code_owners.owners_of(&file_path), &file_name, &result.ambiguities);
                     }
+                    statements.extend(extract_statements(&file_name, &content));
+                    manifest.mark_completed(&file_name, &content);
+                }
+                BatchFileResult::ExtractionFailed(e) => {
+                    status!("\n🔍 {} Processing: {}", progress, shown_path.display());
+                    emit(&Event::FileStarted { file: &file_name, index: position + 1, total: total_files });
+                    emit(&Event::FileFailed { file: &file_name, error: e.to_string() });
+                    eprintln!("⚠️  Could not process file {:?}: {}", shown_path, e);
+                    let failure = BatchFailure::new(&file_name, FailureStage::Extraction, &e);
+                    eprintln!("💡 {}", failure.remediation);
+                    failures.push(failure);
+                    manifest.mark_failed(&file_name);
+                }
+            }
+        }
 
-                    if nfr {
-                        println!("🔒 Generating non-functional requirement suggestions...");
-                        let nfr_suggestions = self.analyzer.generate_nfr_suggestions(&content, &result.entities).await?;
-                        result.nfr_suggestions = Some(nfr_suggestions);
-                    }
+        if let Some(output_dir) = &output_dir {
+            fs::create_dir_all(output_dir).await?;
+            let index_path = output_dir.join("index.md");
+            fs::write(&index_path, format_batch_index_markdown(source, &index_entries)).await?;
+            status!("\n📚 Report index saved: {}", paths::absolute(&index_path).display());
+        }
 
-                    // Create output filename based on original file
-                    let file_stem = file_path.file_stem().unwrap().to_string_lossy();
-                    let output_filename = if let Some(ref base_output) = output {
-                        // If output is specified, create filename with file stem
-                        let base_name = base_output.file_stem().unwrap().to_string_lossy();
-                        let extension = base_output.extension().unwrap_or_default().to_string_lossy();
-                        if extension.is_empty() {
-                            format!("{}_{}.md", base_name, file_stem)
-                        } else {
-                            format!("{}_{}.{}", base_name, file_stem, extension)
-                        }
-                    } else {
-                        // Default filename
-                        format!("{}_analysis.md", file_stem)
-                    };
+        let threshold = self.config.analysis.duplicate_threshold;
+        let clusters = find_duplicate_clusters(&statements, threshold);
+        if !clusters.is_empty() {
+            let duplicates_path = output_dir.as_deref().unwrap_or(Path::new("")).join(DUPLICATES_REPORT_NAME);
+            fs::write(&duplicates_path, format_duplicates_markdown(source, &clusters, threshold)).await?;
+            let repeated: usize = clusters.iter().map(|c| c.statements.len()).sum();
+            status!("\n🔁 {} near-duplicate requirement cluster(s) across files ({} statements) - report saved: {}",
+                clusters.len(), repeated, paths::absolute(&duplicates_path).display());
+        }
 
-                    // Save individual artifacts if requested
-                    if let Some(ref base_filename) = save_artifacts {
-                        let artifact_base = format!("{}_{}", base_filename, file_stem);
-                        self.save_individual_artifacts(&result, &artifact_base, &content).await?;
-                    }
+        if code_owners.is_some() && file_count > 0 {
+            let ownership_path = output_dir.as_deref().unwrap_or(Path::new("")).join(OWNERSHIP_REPORT_NAME);
+            fs::write(&ownership_path, format_ownership_markdown(source, &ownership)).await?;
+            status!("\n👥 Findings by owner:");
+            for owner in ownership.owners().iter().take(5) {
+                let top: Vec<&str> = owner.top_rules(3).iter().map(|(rule, _)| *rule).collect();
+                status!("   {} - {} finding(s) in {} file(s){}", owner.owner, owner.findings, owner.files,
+                    if top.is_empty() { String::new() } else { format!(", mostly {}", top.join(", ")) });
+            }
+            status!("📄 Owner report saved: {}", paths::absolute(&ownership_path).display());
+        }
 
-                    // Output the result for this file
-                    let individual_output = PathBuf::from(output_filename);
-                    let output_format = format.clone().unwrap_or(OutputFormat::Markdown);
-                    
-                    let output_content = match output_format {
-                        OutputFormat::Json => serde_json::to_string_pretty(&result)?,
-                        OutputFormat::Markdown => self.format_as_markdown(&result, &content),
-                        OutputFormat::Jira => self.format_as_jira(&result, &content),
-                        OutputFormat::Github => self.format_as_github(&result, &content),
-                        OutputFormat::Plain => self.format_as_plain(&result, &content),
-                    };
-                    
-                    let absolute_path = std::fs::canonicalize(&individual_output).unwrap_or(individual_output.clone());
-                    fs::write(&individual_output, output_content).await?;
-                    println!("📁 Analysis report created and saved: {}", absolute_path.display());
-                    
-                    println!("✅ Completed analysis for: {}", file_path.display());
-                    file_count += 1;
+        if !failures.is_empty() {
+            let failures_path = output_dir.as_deref().unwrap_or(Path::new("")).join(FAILURE_REPORT_NAME);
+            fs::write(&failures_path, serde_json::to_string_pretty(&failures)?).await?;
+            status!("\n⚠️  {} file(s) failed - failure report saved: {}", failures.len(), paths::absolute(&failures_path).display());
+        }
+
+        if *cancelled.borrow() {
+            status!("\n🛑 Batch processing cancelled");
+            status!("📊 Processed {} of {} requirement files before cancellation", file_count, total_files);
+            if total_words > 0 {
+                status!("📏 Ambiguity density so far: {:.1} findings per 100 words", total_findings as f32 * 100.0 / total_words as f32);
+            }
+            manifest.cancelled = true;
+            if let Some(manifest_path) = save_resume_manifest(dir_path, &manifest) {
+                status!("📝 Resume manifest saved: {}", manifest_path.display());
+                status!("💡 Run the same command with --resume to process the remaining files");
+            }
+            return Err(anyhow::anyhow!("Batch analysis cancelled with {} file(s) not processed", manifest.pending.len()));
+        }
+        if let Some(manifest_path) = save_resume_manifest(dir_path, &manifest).filter(|_| manifest.has_remaining()) {
+            status!("📝 Resume manifest saved: {}", manifest_path.display());
+            status!("💡 Run the same command with --resume to retry the failed files");
+        }
+
+        status!("\n🎉 Batch processing complete!");
+        status!("📊 Successfully processed {} requirement files", file_count);
+        if !skipped_files.is_empty() {
+            status!("⏭️  Skipped {} unchanged file(s) completed in the previous run:", skipped_files.len());
+            skipped_files.iter().for_each(|file| status!("   {}", file));
+        }
+        if cached_files > 0 {
+            status!("💾 Reused cached analyses for {} unchanged file(s)", cached_files);
+        }
+        if total_words > 0 {
+            status!("📏 Overall ambiguity density: {:.1} findings per 100 words", total_findings as f32 * 100.0 / total_words as f32);
+        }
+        status!("📁 Each file has its own individual analysis report");
+        self.print_json_repairs();
+        if let Some(tracker) = job.tracker.lock().unwrap().as_ref() {
+            self.print_llm_spend();
+            if builtin_files > 0 {
+                status!("⏱️  Budget {} exhausted after {:.0}s: {} file(s) were analyzed with built-in analysis only", tracker.budget, tracker.elapsed().as_secs_f64(), builtin_files);
+            }
+        }
+        emit(&Event::Finished { files: file_count, findings: total_findings });
+
+        if !gate_failures.is_empty() {
+            status!("\n🚫 Quality gate failed for {} of {} file(s):", gate_failures.len(), file_count);
+            for failure in &gate_failures {
+                status!("   • {}", failure);
+            }
+            return Err(anyhow::anyhow!("Quality gate failed for {} file(s)", gate_failures.len()));
+        }
+        if gate.is_enabled() {
+            status!("✅ Quality gate passed for all {} file(s)", file_count);
+        }
+        Ok(())
+    }
+
+    /// Extracts and analyzes one file of a batch. Runs on a worker task, so
+    /// progress goes into the outcome's log instead of straight to the screen.
+    async fn analyze_batch_file(&self, job: &BatchJob, file_path: &Path, file_name: &str) -> BatchFileOutcome {
+        let mut log = Vec::new();
+        if *job.cancelled.borrow() {
+            return BatchFileOutcome { log, result: BatchFileResult::NotStarted };
+        }
+
+        let content = match self.document_processor.extract_text_from_file(file_path).await {
+            Ok(content) => content,
+            Err(e) => return BatchFileOutcome { log, result: BatchFileResult::ExtractionFailed(e) },
+        };
+        if job.previous_run.as_ref().is_some_and(|m| m.is_unchanged(file_name, &content)) {
+            return BatchFileOutcome { log, result: BatchFileResult::Unchanged { content } };
+        }
+        log.push(format!("📄 Loaded {} characters from {}", content.len(), file_path.file_name().unwrap().to_string_lossy()));
+
+        let cache_key = job.cache.as_ref().map(|_| AnalysisCache::key(&content, &self.config, &job.cache_options));
+        if let Some(result) = job.cache.as_ref().zip(cache_key.as_deref()).and_then(|(cache, key)| cache.get(key)) {
+            log.push("💾 Unchanged since it was last analyzed - reusing the cached analysis".to_string());
+            return BatchFileOutcome { log, result: BatchFileResult::Analyzed { content, result: Box::new(result), builtin: false, cached: true } };
+        }
+
+        // Once the budget is used up, the remaining files get built-in analysis only
+        let (over_budget, tracker) = {
+            let mut tracker = job.tracker.lock().unwrap();
+            (self.config.is_ai_configured() && self.budget_exhausted(&mut tracker), tracker.clone())
+        };
+        let analyzer = if over_budget { &job.builtin_analyzer } else { &self.analyzer };
+        if over_budget {
+            log.push("📋 Budget exhausted - analyzing with built-in analysis...".to_string());
+        } else if self.config.is_ai_configured() {
+            let (provider_name, _) = self.config.get_provider_info();
+            log.push(format!("🤖 Analyzing with {} ({})...", provider_name, self.config.llm.model));
+        } else {
+            log.push("📋 Analyzing with built-in analysis...".to_string());
+        }
+
+        // Analyze the individual file; a failure is recorded and the batch moves on
+        let plan = &job.plan;
+        let analysis = async {
+            let mut result = self.analyze_within_budget(analyzer, &content, tracker.as_ref()).await?;
+            self.apply_min_severity(&mut result, &content, plan);
+
+            if plan.uml {
+                log.push("🎨 Generating UML diagrams...".to_string());
+                let use_case = analyzer.generate_uml_use_case(&content, &result.entities);
+                let sequence = analyzer.generate_uml_sequence(&result.entities);
+                let class_diagram = analyzer.generate_uml_class_diagram(&result.entities);
+                result.uml_diagrams = Some(crate::analyzer::UmlDiagrams {
+                    use_case: Some(use_case),
+                    sequence: Some(sequence),
+                    class_diagram: Some(class_diagram),
+                });
+            }
+
+            if plan.pseudo {
+                log.push("📝 Generating pseudocode structure...".to_string());
+                let pseudocode = match plan.arch {
+                    Some(style) => generate_architecture_pseudocode(&content, &result.entities, job.pseudo_lang.as_deref(), style),
+                    None => analyzer.generate_pseudocode(&result.entities, job.pseudo_lang.as_deref()),
+                };
+                result.pseudocode = Some(pseudocode);
+            }
+
+            if plan.tests {
+                log.push("🧪 Generating test cases...".to_string());
+                let test_cases = analyzer.generate_test_cases(&result.entities);
+                let test_data = suggest_test_data(&content, &result.entities);
+                if !test_data.is_empty() {
+                    result.test_data = Some(test_data);
                 }
-                Err(e) => {
-                    eprintln!("⚠️  Could not process file {:?}: {}", file_path, e);
+                let contracts = generate_contract_tests(&content);
+                result.test_coverage = Some(build_test_coverage(&content, &result.entities.actions, &test_cases, &contracts, &result.ambiguities));
+                result.test_cases = Some(test_cases);
+                if !contracts.is_empty() {
+                    log.push(format!("🤝 API requirements found - adding contract tests for {} endpoints...", contracts.len()));
+                    result.contract_tests = Some(contracts);
                 }
             }
+
+            if plan.gherkin {
+                log.push("🥒 Generating Gherkin feature file...".to_string());
+                result.gherkin = Some(analyzer.generate_gherkin(&content, &result.entities));
+            }
+
+            if plan.improve {
+                log.push("✨ Generating improved requirements...".to_string());
+                match analyzer.generate_improved_requirements(&content, &result.ambiguities).await {
+                    Ok(improved_req) => {
+                        analyzer.set_improved_requirements(&mut result, &content, improved_req);
+                        log.push("✅ Requirements improvement completed!".to_string());
+                    }
+                    Err(e) => {
+                        log.push(format!("⚠️  Could not generate improved requirements: {}", e));
+                        if !self.config.is_ai_configured() {
+                            log.push("💡 Suggestions:".to_string());
+                            log.push("1. Configure AI provider: 'prism config --setup'".to_string());
+                            log.push("2. Verify API credentials".to_string());
+                            log.push("3. Try 'prism config --debug' for more info".to_string());
+                        }
+                    }
+                }
+            }
+
+            if plan.completeness {
+                log.push("📊 Analyzing completeness and identifying gaps...".to_string());
+                let completeness_analysis = analyzer.analyze_completeness(&content, &result.entities).await?;
+                result.completeness_analysis = Some(completeness_analysis);
+            }
+
+            if plan.validate_story {
+                log.push("✅ Validating user story format and business value...".to_string());
+                let validation = analyzer.validate_user_story(&content);
+                result.user_story_validation = Some(validation);
+            }
+
+            if plan.nfr {
+                log.push("🔒 Generating non-functional requirement suggestions...".to_string());
+                let nfr_suggestions = analyzer.generate_nfr_suggestions(&content, &result.entities).await?;
+                result.nfr_suggestions = Some(nfr_suggestions);
+            }
+
+            if plan.permissions {
+                log.push("🔐 Generating actor-permission matrix...".to_string());
+                let permission_matrix = analyzer.generate_permission_matrix(&content, &result.entities);
+                result.permission_matrix = Some(permission_matrix);
+            }
+
+            if plan.suggestions {
+                log.push("✏️  Generating per-finding rewrite suggestions...".to_string());
+                let suggestions = analyzer.generate_rewrite_suggestions(&content, &result.ambiguities).await?;
+                result.rewrite_suggestions = Some(suggestions);
+            }
+
+            if plan.dependencies {
+                log.push("🔗 Mapping requirement dependencies...".to_string());
+                result.dependency_graph = Some(build_dependency_graph(&content, &result.entities));
+            }
+
+            if plan.domain_model {
+                log.push("🏛️  Proposing a domain model...".to_string());
+                result.domain_model = Some(build_domain_model(&content, &result.entities));
+            }
+
+            if plan.smart {
+                log.push("🎯 Scoring requirements against the SMART criteria...".to_string());
+                result.smart_analysis = Some(analyze_smart(&content));
+            }
+
+            if plan.event_storming {
+                log.push("🟧 Laying out an event-storming board...".to_string());
+                let links = analyzer.associate_actors_with_actions(&content, &result.entities);
+                result.event_storming = Some(build_event_storming(&content, &result.entities, &links));
+            }
+
+            if plan.load_tests {
+                log.push("🏋️ Writing load tests for the performance requirements...".to_string());
+                result.load_tests = Some(generate_load_scenarios(&content, result.nfr_suggestions.as_deref().unwrap_or_default()));
+            }
+
+            if plan.security_tests {
+                log.push("🛡️  Writing security tests for the security-relevant requirements...".to_string());
+                result.security_tests = Some(generate_security_tests(&content, &result.entities.actions, result.nfr_suggestions.as_deref().unwrap_or_default(), &result.ambiguities));
+            }
+
+            if plan.reviewers {
+                log.push("👀 Suggesting reviewers...".to_string());
+                result.reviewer_suggestions = Some(analyzer.suggest_reviewers(&content, &result));
+            }
+
+            Ok::<_, anyhow::Error>(result)
         }
+        .await;
+
+        let result = match analysis {
+            Ok(result) => {
+                // Built-in results of an exhausted budget or failed AI calls are not cached as AI analyses
+                if let (Some(cache), Some(key), false) = (&job.cache, &cache_key, over_budget || result.ai_failed) {
+                    if let Err(e) = cache.put(key, &result) {
+                        log.push(format!("⚠️  Could not cache the analysis: {}", e));
+                    }
+                }
+                BatchFileResult::Analyzed { content, result: Box::new(result), builtin: over_budget, cached: false }
+            }
+            Err(error) => BatchFileResult::AnalysisFailed { error },
+        };
+        BatchFileOutcome { log, result }
+    }
 
-        println!("\n🎉 Batch processing complete!");
-        println!("📊 Successfully processed {} requirement files", file_count);
-        println!("📁 Each file has its own individual analysis report");
+    fn budget_exhausted(&self, tracker: &mut Option<BudgetTracker>) -> bool {
+        match tracker {
+            Some(tracker) => tracker.is_exhausted(&self.analyzer.llm_usage(), &self.config.llm.provider, &self.config.llm.model),
+            None => false,
+        }
+    }
 
+    /// Runs the core analysis, falling back to built-in analysis when a time
+    /// budget runs out while the AI calls are still in flight.
+    async fn analyze_within_budget(&self, analyzer: &Analyzer, text: &str, tracker: Option<&BudgetTracker>) -> Result<AnalysisResult> {
+        let remaining = match tracker.map(|t| (t.budget, t.elapsed())) {
+            Some((AnalysisBudget::Time(limit), elapsed)) if self.config.is_ai_configured() => limit.saturating_sub(elapsed),
+            _ => return analyzer.analyze(text).await,
+        };
+
+        match tokio::time::timeout(remaining, analyzer.analyze(text)).await {
+            Ok(result) => result,
+            Err(_) => {
+                status!("⏱️  Time budget exhausted - finishing with built-in analysis");
+                let mut result = analyzer.without_ai().analyze(text).await?;
+                result.ai_failed = true;
+                Ok(result)
+            }
+        }
+    }
+
+    fn print_llm_spend(&self) {
+        if !self.config.is_ai_configured() {
+            return;
+        }
+        let usage = self.analyzer.llm_usage();
+        status!(
+            "💰 AI usage: {} call(s), estimated spend ${:.4}",
+            usage.calls,
+            usage.estimated_cost(&self.config.llm.provider, &self.config.llm.model)
+        );
+    }
+
+    /// Saves the findings of `result` as an Excel workbook.
+    fn save_findings_xlsx(&self, path: &Path, result: &AnalysisResult, source_name: Option<&str>) -> Result<()> {
+        let file = std::fs::File::create(path).with_context(|| format!("Cannot write the workbook {}", path.display()))?;
+        write_findings_xlsx(file, &finding_rows(result, source_name))
+    }
+
+    /// Saves `improved` as a DOCX with tracked changes against `original`,
+    /// attributed to PRISM and the model.
+    fn save_redline(&self, path: &Path, original: &str, improved: &str) -> Result<()> {
+        let file = std::fs::File::create(path).with_context(|| format!("Cannot write the redline {}", path.display()))?;
+        let author = format!("PRISM ({})", self.config.llm.model);
+        crate::redline::write_redline_docx(file, original, improved, &author, chrono::Utc::now())?;
+        let absolute_path = paths::absolute(path);
+        status!("📝 Redline with tracked changes saved: {}", absolute_path.display());
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// How many AI responses needed their JSON repaired, if any did.
+    fn print_json_repairs(&self) {
+        let usage = self.analyzer.llm_usage();
+        if usage.repaired_responses + usage.salvaged_responses > 0 {
+            status!(
+                "🩹 Repaired malformed JSON in {} AI response(s), dropping invalid items from {}",
+                usage.repaired_responses + usage.salvaged_responses,
+                usage.salvaged_responses
+            );
+        }
+    }
+}
+
+/// Reads a line from the terminal without echoing it, for passwords.
+fn prompt_hidden(prompt: &str) -> Result<String> {
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+
+    print!("{}", prompt);
+    io::stdout().flush()?;
+
+    enable_raw_mode()?;
+    let mut input = String::new();
+    let outcome = loop {
+        let key = match event::read() {
+            Ok(Event::Key(key)) if key.kind != KeyEventKind::Release => key,
+            Ok(_) => continue,
+            Err(e) => break Err(e.into()),
+        };
+        match key.code {
+            KeyCode::Enter => break Ok(()),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                break Err(anyhow::anyhow!("Password entry cancelled"));
+            }
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) => input.push(c),
+            _ => {}
+        }
+    };
+    disable_raw_mode()?;
+    status!();
+
+    outcome.map(|_| input)
+}
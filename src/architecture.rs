@@ -0,0 +1,543 @@
+//! Pseudocode scaffolding shaped like the team's architecture instead of a
+//! single service class: layered controllers, services and repositories,
+//! hexagonal ports and adapters, MVC models, views and controllers, or
+//! event-driven commands, events and handlers.
+
+use regex::Regex;
+
+use crate::analyzer::{Analyzer, ExtractedEntities};
+use crate::cli::ArchitectureStyle;
+use crate::text::capitalize;
+
+/// What an action works on when its sentence names none of the objects.
+pub(crate) const FALLBACK_OBJECT: &str = "request";
+
+/// An action and the object it is performed on.
+pub(crate) struct Operation {
+    pub(crate) action: String,
+    pub(crate) object: String,
+}
+
+/// Pairs every action, without its modal ("can", "want to"), with the object
+/// named after it in the first sentence that mentions it, else the first
+/// object in that sentence.
+pub(crate) fn operations(text: &str, entities: &ExtractedEntities) -> Vec<Operation> {
+    let sentences: Vec<String> = text.split(['.', '!', '?', '\n']).map(|s| s.to_lowercase()).collect();
+    let position = |sentence: &str, word: &str| {
+        Regex::new(&format!(r"\b{}\b", regex::escape(&word.to_lowercase()))).ok()
+            .and_then(|re| re.find(sentence).map(|m| m.start()))
+    };
+
+    let mut operations: Vec<Operation> = Vec::new();
+    for action in &entities.actions {
+        let Some(label) = Analyzer::action_label(action) else { continue };
+        if operations.iter().any(|op| op.action == label) {
+            continue;
+        }
+        let object = sentences.iter()
+            .find_map(|s| position(s, action).map(|at| (s, at)))
+            .and_then(|(sentence, action_at)| {
+                let mentions: Vec<(usize, &String)> = entities.objects.iter()
+                    .filter_map(|o| position(sentence, o).map(|at| (at, o)))
+                    .collect();
+                mentions.iter().filter(|(at, _)| *at > action_at).min_by_key(|(at, _)| *at)
+                    .or_else(|| mentions.iter().min_by_key(|(at, _)| *at))
+                    .map(|(_, o)| o.to_lowercase())
+            })
+            .unwrap_or_else(|| FALLBACK_OBJECT.to_string());
+        operations.push(Operation { action: label, object });
+    }
+    operations
+}
+
+fn words(s: &str) -> impl Iterator<Item = String> + '_ {
+    s.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).map(|w| w.to_lowercase())
+}
+
+/// "order item" -> "OrderItem"
+pub(crate) fn pascal_case(name: &str) -> String {
+    words(name).map(|w| capitalize(&w)).collect()
+}
+
+struct Method {
+    name: String,
+    /// (type, name) pairs, both in words
+    params: Vec<(String, String)>,
+    returns: String,
+    body: Vec<String>,
+}
+
+impl Method {
+    fn new(name: impl Into<String>, params: &[(&str, &str)], returns: impl Into<String>) -> Self {
+        let params = params.iter().map(|(t, n)| (t.to_string(), n.to_string())).collect();
+        Self { name: name.into(), params, returns: returns.into(), body: Vec::new() }
+    }
+
+    fn body(mut self, lines: Vec<String>) -> Self {
+        self.body = lines;
+        self
+    }
+}
+
+#[derive(Default)]
+struct Class {
+    name: String,
+    implements: Option<String>,
+    doc: String,
+    /// (type, name) pairs of plain data fields
+    fields: Vec<(String, String)>,
+    /// Types injected through the constructor, kept in a field named after the type
+    dependencies: Vec<String>,
+    methods: Vec<Method>,
+}
+
+/// Writes classes and interfaces in Python or Java-like syntax.
+struct Scaffold {
+    python: bool,
+    code: String,
+}
+
+impl Scaffold {
+    fn type_name(&self, name: &str) -> String {
+        match (name, self.python) {
+            ("string", true) => "str".to_string(),
+            ("string", false) => "String".to_string(),
+            ("boolean", true) => "bool".to_string(),
+            ("void", true) => "None".to_string(),
+            ("void", false) => "void".to_string(),
+            _ => pascal_case(name),
+        }
+    }
+
+    fn var(&self, name: &str) -> String {
+        let words: Vec<String> = words(name).collect();
+        if self.python {
+            words.join("_")
+        } else {
+            words.iter().enumerate().map(|(i, w)| if i == 0 { w.clone() } else { capitalize(w) }).collect()
+        }
+    }
+
+    fn this(&self) -> &'static str {
+        if self.python { "self" } else { "this" }
+    }
+
+    fn none(&self) -> &'static str {
+        if self.python { "None" } else { "null" }
+    }
+
+    fn field(&self, name: &str) -> String {
+        format!("{}.{}", self.this(), self.var(name))
+    }
+
+    fn call(&self, target: &str, method: &str, args: &[String]) -> String {
+        format!("{}.{}({})", target, self.var(method), args.join(", "))
+    }
+
+    fn new_object(&self, type_name: &str, args: &[String]) -> String {
+        let prefix = if self.python { "" } else { "new " };
+        format!("{}{}({})", prefix, self.type_name(type_name), args.join(", "))
+    }
+
+    fn stmt(&self, expr: String) -> String {
+        if self.python { expr } else { format!("{};", expr) }
+    }
+
+    fn assign(&self, type_name: &str, name: &str, expr: String) -> String {
+        if self.python {
+            format!("{} = {}", self.var(name), expr)
+        } else {
+            format!("{} {} = {};", self.type_name(type_name), self.var(name), expr)
+        }
+    }
+
+    fn ret(&self, expr: String) -> String {
+        self.stmt(format!("return {}", expr))
+    }
+
+    fn comment(&self, text: &str) -> String {
+        format!("{} {}", if self.python { "#" } else { "//" }, text)
+    }
+
+    fn section(&mut self, title: &str) {
+        let line = self.comment(&format!("--- {} ---", title));
+        self.code.push_str(&format!("{}\n\n", line));
+    }
+
+    fn signature(&self, method: &Method) -> String {
+        if self.python {
+            let mut params = vec!["self".to_string()];
+            params.extend(method.params.iter().map(|(t, n)| format!("{}: {}", self.var(n), self.type_name(t))));
+            format!("def {}({}) -> {}:", self.var(&method.name), params.join(", "), self.type_name(&method.returns))
+        } else {
+            let params: Vec<String> = method.params.iter().map(|(t, n)| format!("{} {}", self.type_name(t), self.var(n))).collect();
+            format!("{} {}({})", self.type_name(&method.returns), self.var(&method.name), params.join(", "))
+        }
+    }
+
+    fn interface(&mut self, name: &str, doc: &str, methods: &[Method]) {
+        if self.python {
+            self.code.push_str(&format!("class {}(ABC):\n    \"\"\"{}\"\"\"\n", self.type_name(name), doc));
+            for method in methods {
+                self.code.push_str(&format!("\n    @abstractmethod\n    {}\n        ...\n", self.signature(method)));
+            }
+        } else {
+            self.code.push_str(&format!("interface {} {{\n    // {}\n", self.type_name(name), doc));
+            for method in methods {
+                self.code.push_str(&format!("    {};\n", self.signature(method)));
+            }
+            self.code.push_str("}\n");
+        }
+        self.code.push('\n');
+    }
+
+    fn class(&mut self, class: Class) {
+        let name = self.type_name(&class.name);
+        let mut blocks: Vec<Vec<String>> = Vec::new();
+
+        if self.python {
+            let base = class.implements.as_ref().map(|i| format!("({})", self.type_name(i))).unwrap_or_default();
+            if !class.fields.is_empty() {
+                self.code.push_str("@dataclass\n");
+            }
+            self.code.push_str(&format!("class {}{}:\n    \"\"\"{}\"\"\"\n", name, base, class.doc));
+            if !class.fields.is_empty() {
+                blocks.push(class.fields.iter().map(|(t, n)| format!("{}: {}", self.var(n), self.type_name(t))).collect());
+            }
+            if !class.dependencies.is_empty() {
+                let params: Vec<String> = class.dependencies.iter().map(|d| format!("{}: {}", self.var(d), self.type_name(d))).collect();
+                let mut init = vec![format!("def __init__(self, {}):", params.join(", "))];
+                init.extend(class.dependencies.iter().map(|d| format!("    {} = {}", self.field(d), self.var(d))));
+                blocks.push(init);
+            }
+        } else {
+            let implements = class.implements.as_ref().map(|i| format!(" implements {}", self.type_name(i))).unwrap_or_default();
+            self.code.push_str(&format!("class {}{} {{\n    // {}\n", name, implements, class.doc));
+            let mut members: Vec<String> = class.fields.iter().map(|(t, n)| format!("private {} {};", self.type_name(t), self.var(n))).collect();
+            members.extend(class.dependencies.iter().map(|d| format!("private final {} {};", self.type_name(d), self.var(d))));
+            if members.is_empty() {
+                // Keeps the first method apart from the doc comment
+                blocks.push(Vec::new());
+            } else {
+                blocks.push(members);
+            }
+            if !class.dependencies.is_empty() {
+                let params: Vec<String> = class.dependencies.iter().map(|d| format!("{} {}", self.type_name(d), self.var(d))).collect();
+                let mut constructor = vec![format!("public {}({}) {{", name, params.join(", "))];
+                constructor.extend(class.dependencies.iter().map(|d| format!("    {} = {};", self.field(d), self.var(d))));
+                constructor.push("}".to_string());
+                blocks.push(constructor);
+            }
+        }
+
+        for method in &class.methods {
+            let mut lines = vec![if self.python { self.signature(method) } else { format!("public {} {{", self.signature(method)) }];
+            lines.extend(method.body.iter().map(|l| format!("    {}", l)));
+            let only_comments = method.body.iter().all(|l| l.starts_with('#'));
+            if self.python && only_comments {
+                lines.push("    pass".to_string());
+            }
+            if !self.python {
+                lines.push("}".to_string());
+            }
+            blocks.push(lines);
+        }
+
+        for (i, block) in blocks.iter().enumerate().filter(|(_, b)| !b.is_empty()) {
+            if i > 0 || self.python {
+                self.code.push('\n');
+            }
+            for line in block {
+                self.code.push_str(&format!("    {}\n", line));
+            }
+        }
+        if !self.python {
+            self.code.push_str("}\n");
+        }
+        self.code.push('\n');
+    }
+
+    fn repository(&mut self, object: &str) {
+        let methods = [
+            Method::new("find by id", &[("string", "id")], object),
+            Method::new("save", &[(object, object)], "void"),
+        ];
+        self.interface(&format!("{} repository", object), &format!("Loads and stores {} records", object), &methods);
+    }
+
+    /// A domain object with an id and a status, and the operations on it as methods.
+    fn entity(&mut self, object: &str, doc: &str, operations: &[&Operation]) {
+        let methods = operations.iter().map(|op| {
+            Method::new(op.action.as_str(), &[], "void").body(vec![
+                self.comment(&format!("Rules for {} {}", op.action, op.object)),
+                self.stmt(format!("{} = \"{}\"", self.field("status"), op.action)),
+            ])
+        }).collect();
+        self.class(Class {
+            name: object.to_string(),
+            doc: doc.to_string(),
+            fields: vec![("string".to_string(), "id".to_string()), ("string".to_string(), "status".to_string())],
+            methods,
+            ..Default::default()
+        });
+    }
+
+    /// Loads the object named by `{object} id`, applies the action and saves it again.
+    fn load_apply_save(&self, op: &Operation, id: String) -> Vec<String> {
+        let repository = self.field(&format!("{} repository", op.object));
+        vec![
+            self.assign(&op.object, &op.object, self.call(&repository, "find by id", &[id])),
+            self.stmt(self.call(&self.var(&op.object), &op.action, &[])),
+            self.stmt(self.call(&repository, "save", &[self.var(&op.object)])),
+        ]
+    }
+}
+
+fn style_label(style: ArchitectureStyle) -> &'static str {
+    match style {
+        ArchitectureStyle::Layered => "layered (controllers, services, repositories)",
+        ArchitectureStyle::Hexagonal => "hexagonal (ports and adapters)",
+        ArchitectureStyle::Mvc => "MVC (models, views, controllers)",
+        ArchitectureStyle::EventDriven => "event-driven (commands, events, handlers)",
+    }
+}
+
+/// Pseudocode for the actors, actions and objects of a requirement, laid out
+/// in `style`. `language` is "python" for Python syntax, else Java-like.
+pub fn generate_architecture_pseudocode(text: &str, entities: &ExtractedEntities, language: Option<&str>, style: ArchitectureStyle) -> String {
+    let mut s = Scaffold { python: language == Some("python"), code: String::new() };
+    let operations = operations(text, entities);
+    let mut objects: Vec<String> = entities.objects.iter().map(|o| o.to_lowercase()).collect();
+    if operations.iter().any(|op| op.object == FALLBACK_OBJECT) && !objects.iter().any(|o| o == FALLBACK_OBJECT) {
+        objects.push(FALLBACK_OBJECT.to_string());
+    }
+    let on = |object: &str| -> Vec<&Operation> { operations.iter().filter(|op| op.object == object).collect() };
+
+    s.code.push_str(&s.comment(&format!("Generated pseudocode: {} architecture", style_label(style))));
+    s.code.push('\n');
+    let actors: Vec<String> = entities.actors.iter().filter_map(|a| Analyzer::actor_label(a)).collect();
+    if !actors.is_empty() {
+        s.code.push_str(&s.comment(&format!("Actors: {}", actors.join(", "))));
+        s.code.push('\n');
+    }
+    s.code.push('\n');
+    if s.python {
+        s.code.push_str("from abc import ABC, abstractmethod\nfrom dataclasses import dataclass\n\n");
+    }
+
+    match style {
+        ArchitectureStyle::Layered => {
+            s.section("Domain layer");
+            for object in &objects {
+                s.entity(object, &format!("The {} and its state", object), &[]);
+            }
+            s.section("Data access layer");
+            for object in &objects {
+                s.repository(object);
+            }
+            s.section("Service layer");
+            for object in objects.iter().filter(|o| !on(o).is_empty()) {
+                let methods = on(object).into_iter().map(|op| {
+                    let repository = s.field(&format!("{} repository", object));
+                    let body = vec![
+                        s.assign(object, object, s.call(&repository, "find by id", &[s.var(&format!("{} id", object))])),
+                        s.comment(&format!("Business rules for {} {}", op.action, object)),
+                        s.stmt(format!("{}.{} = \"{}\"", s.var(object), s.var("status"), op.action)),
+                        s.stmt(s.call(&repository, "save", &[s.var(object)])),
+                        s.ret(s.var(object)),
+                    ];
+                    Method::new(op.action.as_str(), &[("string", &format!("{} id", object))], object.as_str()).body(body)
+                }).collect();
+                s.class(Class {
+                    name: format!("{} service", object),
+                    doc: format!("Business operations on {}", object),
+                    dependencies: vec![format!("{} repository", object)],
+                    methods,
+                    ..Default::default()
+                });
+            }
+            s.section("Presentation layer");
+            for object in objects.iter().filter(|o| !on(o).is_empty()) {
+                let service = s.field(&format!("{} service", object));
+                let methods = on(object).into_iter().map(|op| {
+                    let id = format!("{} id", object);
+                    Method::new(format!("handle {}", op.action), &[("string", &id)], object.as_str()).body(vec![
+                        s.comment("Validate the request and check the caller's permissions"),
+                        s.ret(s.call(&service, &op.action, &[s.var(&id)])),
+                    ])
+                }).collect();
+                s.class(Class {
+                    name: format!("{} controller", object),
+                    doc: format!("Entry points for {} requests", object),
+                    dependencies: vec![format!("{} service", object)],
+                    methods,
+                    ..Default::default()
+                });
+            }
+        }
+        ArchitectureStyle::Hexagonal => {
+            s.section("Domain");
+            for object in &objects {
+                s.entity(object, &format!("The {} and its business rules", object), &on(object));
+            }
+            s.section("Inbound ports (use cases)");
+            for op in &operations {
+                let id = format!("{} id", op.object);
+                s.interface(
+                    &format!("{} {} use case", op.action, op.object),
+                    &format!("{} the {}", capitalize(&op.action), op.object),
+                    &[Method::new("execute", &[("string", &id)], op.object.as_str())],
+                );
+            }
+            s.section("Outbound ports");
+            for object in &objects {
+                s.repository(object);
+            }
+            s.interface("notification port", "Tells people about what happened", &[Method::new("notify", &[("string", "message")], "void")]);
+            s.section("Application services");
+            for op in &operations {
+                let id = format!("{} id", op.object);
+                let mut body = s.load_apply_save(op, s.var(&id));
+                body.push(s.stmt(s.call(&s.field("notification port"), "notify", &[format!("\"{} {} done\"", op.action, op.object)])));
+                body.push(s.ret(s.var(&op.object)));
+                s.class(Class {
+                    name: format!("{} {} service", op.action, op.object),
+                    implements: Some(format!("{} {} use case", op.action, op.object)),
+                    doc: format!("Carries out the {} {} use case", op.action, op.object),
+                    dependencies: vec![format!("{} repository", op.object), "notification port".to_string()],
+                    methods: vec![Method::new("execute", &[("string", &id)], op.object.as_str()).body(body)],
+                    ..Default::default()
+                });
+            }
+            s.section("Adapters");
+            if !operations.is_empty() {
+                let methods = operations.iter().map(|op| {
+                    let id = format!("{} id", op.object);
+                    let use_case = s.field(&format!("{} {} use case", op.action, op.object));
+                    Method::new(format!("handle {} {}", op.action, op.object), &[("string", &id)], op.object.as_str())
+                        .body(vec![s.ret(s.call(&use_case, "execute", &[s.var(&id)]))])
+                }).collect();
+                s.class(Class {
+                    name: "rest adapter".to_string(),
+                    doc: "Inbound adapter: maps HTTP requests onto the use cases".to_string(),
+                    dependencies: operations.iter().map(|op| format!("{} {} use case", op.action, op.object)).collect(),
+                    methods,
+                    ..Default::default()
+                });
+            }
+            for object in &objects {
+                s.class(Class {
+                    name: format!("{} persistence adapter", object),
+                    implements: Some(format!("{} repository", object)),
+                    doc: format!("Outbound adapter: stores {} records in the database", object),
+                    methods: vec![
+                        Method::new("find by id", &[("string", "id")], object.as_str())
+                            .body(vec![s.comment(&format!("SELECT the {} by id", object)), s.ret(s.none().to_string())]),
+                        Method::new("save", &[(object.as_str(), object.as_str())], "void")
+                            .body(vec![s.comment(&format!("INSERT or UPDATE the {}", object))]),
+                    ],
+                    ..Default::default()
+                });
+            }
+            s.class(Class {
+                name: "email notification adapter".to_string(),
+                implements: Some("notification port".to_string()),
+                doc: "Outbound adapter: sends notifications by e-mail".to_string(),
+                methods: vec![Method::new("notify", &[("string", "message")], "void").body(vec![s.comment("Send the message")])],
+                ..Default::default()
+            });
+        }
+        ArchitectureStyle::Mvc => {
+            s.section("Models");
+            for object in &objects {
+                s.entity(object, &format!("Model: the {} and its rules", object), &on(object));
+                s.repository(object);
+            }
+            s.section("Views");
+            for object in objects.iter().filter(|o| !on(o).is_empty()) {
+                s.class(Class {
+                    name: format!("{} view", object),
+                    doc: format!("Renders {} pages and responses", object),
+                    methods: vec![
+                        Method::new("render", &[(object.as_str(), object.as_str())], "string")
+                            .body(vec![s.comment(&format!("Template showing the {}", object)), s.ret(format!("\"{}\"", object))]),
+                        Method::new("render error", &[("string", "message")], "string").body(vec![s.ret(s.var("message"))]),
+                    ],
+                    ..Default::default()
+                });
+            }
+            s.section("Controllers");
+            for object in objects.iter().filter(|o| !on(o).is_empty()) {
+                let methods = on(object).into_iter().map(|op| {
+                    let id = format!("{} id", object);
+                    let mut body = vec![s.comment(&format!("Check the caller may {} the {}", op.action, object))];
+                    body.extend(s.load_apply_save(op, s.var(&id)));
+                    body.push(s.ret(s.call(&s.field(&format!("{} view", object)), "render", &[s.var(object)])));
+                    Method::new(op.action.as_str(), &[("string", &id)], "string").body(body)
+                }).collect();
+                s.class(Class {
+                    name: format!("{} controller", object),
+                    doc: format!("Handles {} requests: updates the model, picks the view", object),
+                    dependencies: vec![format!("{} repository", object), format!("{} view", object)],
+                    methods,
+                    ..Default::default()
+                });
+            }
+        }
+        ArchitectureStyle::EventDriven => {
+            s.section("Commands and events");
+            s.interface("message", "A command or event on the bus", &[]);
+            for op in &operations {
+                let id = format!("{} id", op.object);
+                for (suffix, doc) in [("requested", "Command"), ("completed", "Event")] {
+                    s.class(Class {
+                        name: format!("{} {} {}", op.action, op.object, suffix),
+                        implements: Some("message".to_string()),
+                        doc: format!("{}: {} {} {}", doc, op.action, op.object, suffix),
+                        fields: vec![("string".to_string(), id.clone())],
+                        ..Default::default()
+                    });
+                }
+            }
+            s.interface("message handler", "Reacts to one kind of message", &[Method::new("handle", &[("message", "message")], "void")]);
+            s.interface("event bus", "Delivers messages to their subscribed handlers", &[
+                Method::new("publish", &[("message", "message")], "void"),
+                Method::new("subscribe", &[("string", "message type"), ("message handler", "handler")], "void"),
+            ]);
+            s.section("Aggregates");
+            for object in &objects {
+                s.entity(object, &format!("Aggregate: the {} and its rules", object), &on(object));
+                s.repository(object);
+            }
+            s.section("Command handlers");
+            for op in &operations {
+                let id = format!("{} id", op.object);
+                let mut body = s.load_apply_save(op, format!("{}.{}", s.var("message"), s.var(&id)));
+                let completed = s.new_object(&format!("{} {} completed", op.action, op.object), &[format!("{}.{}", s.var(&op.object), s.var("id"))]);
+                body.push(s.stmt(s.call(&s.field("event bus"), "publish", &[completed])));
+                s.class(Class {
+                    name: format!("{} {} handler", op.action, op.object),
+                    implements: Some("message handler".to_string()),
+                    doc: format!(
+                        "Subscribed to {}; publishes {}",
+                        s.type_name(&format!("{} {} requested", op.action, op.object)),
+                        s.type_name(&format!("{} {} completed", op.action, op.object)),
+                    ),
+                    dependencies: vec![format!("{} repository", op.object), "event bus".to_string()],
+                    methods: vec![Method::new("handle", &[("message", "message")], "void").body(body)],
+                    ..Default::default()
+                });
+            }
+            s.section("Subscribers");
+            s.class(Class {
+                name: "audit log subscriber".to_string(),
+                implements: Some("message handler".to_string()),
+                doc: "Subscribed to every completed event".to_string(),
+                methods: vec![Method::new("handle", &[("message", "message")], "void").body(vec![s.comment("Record who did what, and when")])],
+                ..Default::default()
+            });
+        }
+    }
+
+    s.code.trim_end().to_string() + "\n"
+}
@@ -0,0 +1,104 @@
+use std::path::Path;
+
+use crate::analyzer::{Ambiguity, Gap, NfrCategory, NonFunctionalRequirement, RewriteSuggestion};
+
+/// One file written by `--save-artifacts`.
+#[derive(Debug, Clone)]
+pub struct SavedArtifact {
+    pub title: String,
+    pub file_name: String,
+    pub description: String,
+}
+
+/// File names of the artifacts other artifacts link to, relative to the
+/// directory they are all saved in.
+#[derive(Debug, Clone, Default)]
+pub struct ArtifactLinks {
+    pub index: Option<String>,
+    pub nfr: Option<String>,
+    pub suggestions: Option<String>,
+}
+
+impl ArtifactLinks {
+    /// `[← Artifact index](...)` line put at the top of Markdown artifacts.
+    pub fn back_link(&self) -> String {
+        match &self.index {
+            Some(index) => format!("[← Artifact index]({})\n\n", link_target(index)),
+            None => String::new(),
+        }
+    }
+}
+
+/// File name part of an artifact path, for links between artifacts saved side by side.
+pub fn artifact_file_name(path: &str) -> String {
+    Path::new(path).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| path.to_string())
+}
+
+fn link_target(file_name: &str) -> String {
+    file_name.replace(' ', "%20")
+}
+
+/// Anchor of the `n`-th (1-based) NFR of a category, e.g. `nfr-security-01`.
+pub fn nfr_anchor(category: &NfrCategory, n: usize) -> String {
+    format!("nfr-{}-{:02}", format!("{:?}", category).to_lowercase(), n)
+}
+
+/// Anchor of the `n`-th (1-based) rewrite suggestion.
+pub fn suggestion_anchor(n: usize) -> String {
+    format!("suggestion-{}", n)
+}
+
+/// The NFRs (id, anchor) generated for a completeness gap: all of them for
+/// the general non-functional gap, otherwise those whose category the gap
+/// names. Ids are numbered per category, as in the NFR artifact.
+pub fn nfrs_for_gap(gap: &Gap, nfrs: &[NonFunctionalRequirement]) -> Vec<(String, String)> {
+    let gap_text = format!("{} {}", gap.category, gap.description).to_lowercase();
+    let general = gap.category.to_lowercase().contains("non-functional");
+
+    let mut categories: Vec<&NfrCategory> = nfrs.iter().map(|nfr| &nfr.category).collect();
+    categories.sort();
+    categories.dedup();
+
+    let mut related = Vec::new();
+    for category in categories {
+        let name = format!("{:?}", category);
+        if !general && !gap_text.contains(&name.to_lowercase()) {
+            continue;
+        }
+        let count = nfrs.iter().filter(|nfr| &nfr.category == category).count();
+        for n in 1..=count {
+            related.push((format!("NFR-{}-{:02}", name, n), nfr_anchor(category, n)));
+        }
+    }
+    related
+}
+
+/// Number (1-based) of the rewrite suggestion made for a finding.
+pub fn suggestion_for(ambiguity: &Ambiguity, suggestions: &[RewriteSuggestion]) -> Option<usize> {
+    let line = ambiguity.location.as_ref().map(|l| l.line);
+    suggestions.iter()
+        .position(|s| s.finding == ambiguity.text && s.line == line)
+        .map(|i| i + 1)
+}
+
+/// Markdown link to an anchor in another artifact.
+pub fn anchor_link(label: &str, file_name: &str, anchor: &str) -> String {
+    format!("[{}]({}#{})", label, link_target(file_name), anchor)
+}
+
+pub fn format_artifact_index(base_name: &str, artifacts: &[SavedArtifact]) -> String {
+    let mut output = String::new();
+
+    output.push_str(&format!("# 🗂️ Artifact Index for: {}\n", base_name));
+    output.push_str("*Generated by PRISM - AI-Powered Requirement Analyzer*\n\n");
+    output.push_str("| Artifact | File | Contents |\n|---|---|---|\n");
+    for artifact in artifacts {
+        output.push_str(&format!(
+            "| {} | [{}]({}) | {} |\n",
+            artifact.title, artifact.file_name, link_target(&artifact.file_name), artifact.description
+        ));
+    }
+    output.push('\n');
+
+    output
+}
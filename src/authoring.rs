@@ -0,0 +1,170 @@
+use anyhow::Result;
+use handlebars::Handlebars;
+use serde::Serialize;
+
+use crate::analyzer::Analyzer;
+
+/// Template used by `prism new` when no `--template` is given.
+pub const DEFAULT_REQUIREMENT_TEMPLATE: &str = "# {{title}}
+
+As {{article}} {{actor}}, I want to {{goal}} so that {{benefit}}.
+
+## Acceptance Criteria
+
+{{#each acceptance_criteria}}
+- {{this}}
+{{else}}
+- _None specified_
+{{/each}}
+
+## Non-Functional Requirements
+
+{{#each nfrs}}
+- {{this}}
+{{else}}
+- _None specified_
+{{/each}}
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DraftField {
+    Actor,
+    Goal,
+    Benefit,
+    AcceptanceCriterion,
+    Nfr,
+}
+
+/// Answers collected by the authoring wizard.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RequirementDraft {
+    pub title: String,
+    pub actor: String,
+    pub goal: String,
+    pub benefit: String,
+    pub acceptance_criteria: Vec<String>,
+    pub nfrs: Vec<String>,
+}
+
+/// Outcome of validating one answer. Errors must be fixed, warnings may be
+/// accepted by the author.
+#[derive(Debug, Clone, Default)]
+pub struct AnswerCheck {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl AnswerCheck {
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty() && self.warnings.is_empty()
+    }
+}
+
+/// Strips the story boilerplate authors tend to type along with the answer,
+/// e.g. "As a customer" or "so that ...".
+pub fn normalize_answer(field: DraftField, answer: &str) -> String {
+    let answer = answer.trim().trim_end_matches('.').trim();
+    let lower = answer.to_lowercase();
+    let prefixes: &[&str] = match field {
+        DraftField::Actor => &["as an ", "as a ", "an ", "a ", "the "],
+        DraftField::Goal => &["i want to ", "i want ", "to "],
+        DraftField::Benefit => &["so that ", "so "],
+        DraftField::AcceptanceCriterion | DraftField::Nfr => &["- ", "* "],
+    };
+
+    prefixes.iter()
+        .find(|prefix| lower.starts_with(*prefix))
+        .map(|prefix| answer[prefix.len()..].trim().to_string())
+        .unwrap_or_else(|| answer.to_string())
+}
+
+/// Validates a single wizard answer with the built-in rules.
+pub fn check_answer(analyzer: &Analyzer, field: DraftField, answer: &str) -> AnswerCheck {
+    let mut check = AnswerCheck::default();
+    let lower = answer.to_lowercase();
+
+    if answer.is_empty() {
+        if matches!(field, DraftField::Actor | DraftField::Goal | DraftField::Benefit) {
+            check.errors.push("An answer is required".to_string());
+        }
+        return check;
+    }
+
+    for finding in analyzer.detect_rule_findings(answer) {
+        check.warnings.push(format!("'{}': {}", finding.text, finding.reason));
+    }
+
+    match field {
+        DraftField::Actor => {
+            if ["user", "someone", "somebody", "people", "person"].contains(&lower.as_str()) {
+                check.warnings.push("Generic actor - name the specific role (e.g. 'account owner', 'support agent')".to_string());
+            }
+        }
+        DraftField::Goal | DraftField::Benefit => {
+            let component = if field == DraftField::Goal { "goal" } else { "reason" };
+            check.warnings.extend(analyzer.validate_user_story_component(answer, component).issues);
+        }
+        DraftField::AcceptanceCriterion => {
+            let gherkin = ["given", "when", "then"].iter().any(|k| lower.contains(k));
+            if !gherkin && !answer.chars().any(|c| c.is_ascii_digit()) {
+                check.warnings.push("Not testable as written - use Given/When/Then or state a measurable outcome".to_string());
+            }
+        }
+        DraftField::Nfr => {
+            if !answer.chars().any(|c| c.is_ascii_digit()) {
+                check.warnings.push("No measurable target - add a number (e.g. '95% of requests within 2 seconds')".to_string());
+            }
+        }
+    }
+
+    check
+}
+
+impl RequirementDraft {
+    /// Title derived from the goal, e.g. "reset my password" → "Reset my password".
+    pub fn title_from_goal(goal: &str) -> String {
+        let mut chars = goal.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        }
+    }
+
+    /// File name for the requirement, e.g. "reset-my-password.md".
+    pub fn file_name(&self) -> String {
+        let slug: Vec<String> = self.title.split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .take(6)
+            .map(|w| w.to_lowercase())
+            .collect();
+        if slug.is_empty() {
+            "requirement.md".to_string()
+        } else {
+            format!("{}.md", slug.join("-"))
+        }
+    }
+
+    /// Renders the draft with a Handlebars template (the default one if `None`).
+    pub fn render(&self, template: Option<&str>) -> Result<String> {
+        let mut handlebars = Handlebars::new();
+        handlebars.register_escape_fn(handlebars::no_escape);
+        handlebars.register_template_string("requirement", template.unwrap_or(DEFAULT_REQUIREMENT_TEMPLATE))
+            .map_err(|e| anyhow::anyhow!("Invalid requirement template: {}", e))?;
+        let mut context = serde_json::to_value(self)?;
+        context["article"] = serde_json::Value::String(self.article().to_string());
+        Ok(handlebars.render("requirement", &context)?)
+    }
+
+    /// "a" or "an", to go in front of the actor.
+    pub fn article(&self) -> &'static str {
+        match self.actor.chars().next().map(|c| c.to_ascii_lowercase()) {
+            Some('a' | 'e' | 'i' | 'o' | 'u') => "an",
+            _ => "a",
+        }
+    }
+
+    /// The "As a ..., I want ... so that ..." sentence of the draft.
+    pub fn story_sentence(&self) -> String {
+        format!("As {} {}, I want to {} so that {}.", self.article(), self.actor, self.goal, self.benefit)
+    }
+}
@@ -0,0 +1,95 @@
+//! Requirement quality badges: a shields.io-style SVG and a JSON file for the
+//! shields.io endpoint badge, to commit or publish from CI.
+
+use serde_json::{json, Value};
+
+use crate::hierarchy::QualityScore;
+use crate::text::escape_xml;
+
+pub const DEFAULT_BADGE_LABEL: &str = "Requirements";
+
+/// shields.io color name and hex value for a 0-100 quality score.
+pub fn badge_color(score: f32) -> (&'static str, &'static str) {
+    if score >= 90.0 {
+        ("brightgreen", "#4c1")
+    } else if score >= 75.0 {
+        ("green", "#97ca00")
+    } else if score >= 60.0 {
+        ("yellow", "#dfb317")
+    } else if score >= 40.0 {
+        ("orange", "#fe7d37")
+    } else {
+        ("red", "#e05d44")
+    }
+}
+
+/// "87/100"
+pub fn badge_message(score: &QualityScore) -> String {
+    format!("{:.0}/100", score.score.round())
+}
+
+/// Approximate width in pixels of `text` in 11px Verdana, the badge font.
+fn text_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c {
+            'i' | 'j' | 'l' | '.' | ',' | ':' | ';' | '\'' | '|' | '!' => 4,
+            'f' | 'r' | 't' | 'I' | '/' | '(' | ')' | '[' | ']' | ' ' => 5,
+            'm' | 'w' | 'M' | 'W' => 10,
+            c if c.is_uppercase() || c.is_ascii_digit() => 8,
+            _ => 7,
+        })
+        .sum()
+}
+
+/// Flat badge in the shields.io style, e.g. `Requirements | 87/100`.
+pub fn format_badge_svg(label: &str, score: &QualityScore) -> String {
+    let message = badge_message(score);
+    // Colored by the score shown, so a 89.6 shown as "90/100" gets the 90+ color
+    let (_, color) = badge_color(score.score.round());
+    let label_width = text_width(label) + 10;
+    let message_width = text_width(&message) + 10;
+    let width = label_width + message_width;
+    let (label, title) = (escape_xml(label), escape_xml(&format!("{}: {}", label, message)));
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{title}">
+  <title>{title}</title>
+  <linearGradient id="s" x2="0" y2="100%">
+    <stop offset="0" stop-color="#bbb" stop-opacity=".1"/>
+    <stop offset="1" stop-opacity=".1"/>
+  </linearGradient>
+  <clipPath id="r"><rect width="{width}" height="20" rx="3" fill="#fff"/></clipPath>
+  <g clip-path="url(#r)">
+    <rect width="{label_width}" height="20" fill="#555"/>
+    <rect x="{label_width}" width="{message_width}" height="20" fill="{color}"/>
+    <rect width="{width}" height="20" fill="url(#s)"/>
+  </g>
+  <g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">
+    <text x="{label_x}" y="15" fill="#010101" fill-opacity=".3">{label}</text>
+    <text x="{label_x}" y="14">{label}</text>
+    <text x="{message_x}" y="15" fill="#010101" fill-opacity=".3">{message}</text>
+    <text x="{message_x}" y="14">{message}</text>
+  </g>
+</svg>
+"##,
+        width = width,
+        title = title,
+        label_width = label_width,
+        message_width = message_width,
+        color = color,
+        label = label,
+        message = message,
+        label_x = label_width as f32 / 2.0,
+        message_x = label_width as f32 + message_width as f32 / 2.0,
+    )
+}
+
+/// JSON for https://img.shields.io/endpoint, which renders it as a badge.
+pub fn format_badge_json(label: &str, score: &QualityScore) -> Value {
+    json!({
+        "schemaVersion": 1,
+        "label": label,
+        "message": badge_message(score),
+        "color": badge_color(score.score.round()).0,
+    })
+}
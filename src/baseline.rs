@@ -0,0 +1,109 @@
+//! Findings baseline: the findings a team reviewed and accepted, listed by
+//! fingerprint in `.prismignore-findings`, so they stop reappearing in CI
+//! and only new findings fail the quality gate. Written by
+//! `prism baseline create`; delete a line to report its finding again.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::analyzer::{Ambiguity, AnalysisResult};
+
+pub const BASELINE_FILE_NAME: &str = ".prismignore-findings";
+
+/// One accepted finding.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaselineEntry {
+    pub fingerprint: String,
+    /// The file it was found in, `None` for text from the command line
+    pub file: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FindingsBaseline {
+    pub entries: Vec<BaselineEntry>,
+}
+
+impl FindingsBaseline {
+    /// Reads a baseline: one finding per line, its fingerprint and file
+    /// (`-` for none) first, then what it is for the reader. `#` starts a
+    /// comment.
+    pub fn parse(content: &str) -> Self {
+        let entries = content.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let fingerprint = fields.next()?.to_string();
+                let file = fields.next().filter(|file| *file != "-").map(|file| file.replace("%20", " "));
+                Some(BaselineEntry { fingerprint, file })
+            })
+            .collect();
+        Self { entries }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).with_context(|| format!("Cannot read {}", path.display()))?;
+        Ok(Self::parse(&content))
+    }
+
+    /// Whether `finding` is accepted: the fingerprint matches and so does
+    /// the file, where both name one. Files match when one path ends with
+    /// the other, so `login.md` of a `--dir` run is `docs/login.md` of a
+    /// `--file` run.
+    pub fn accepts(&self, finding: &Ambiguity) -> bool {
+        let file = finding.location.as_ref().and_then(|location| location.file.as_deref());
+        self.entries.iter().any(|entry| {
+            entry.fingerprint == finding.fingerprint
+                && match (entry.file.as_deref(), file) {
+                    (Some(accepted), Some(found)) => {
+                        let (accepted, found) = (Path::new(accepted), Path::new(found));
+                        accepted.ends_with(found) || found.ends_with(accepted)
+                    }
+                    _ => true,
+                }
+        })
+    }
+
+    /// Leaves the accepted findings out of `result`, counting them in its
+    /// `hidden_findings`.
+    pub fn apply(&self, result: &mut AnalysisResult) {
+        let found = result.ambiguities.len();
+        result.ambiguities.retain(|finding| !self.accepts(finding));
+        result.hidden_findings.baselined += found - result.ambiguities.len();
+    }
+}
+
+/// The nearest `.prismignore-findings` in `start`'s directory or above it.
+pub fn find_baseline(start: &Path) -> Option<PathBuf> {
+    let start = start.canonicalize().unwrap_or_else(|_| start.to_path_buf());
+    let first = if start.is_dir() { Some(start.as_path()) } else { start.parent() };
+    first.into_iter()
+        .flat_map(Path::ancestors)
+        .map(|dir| dir.join(BASELINE_FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+/// The baseline accepting every finding of `results`, each given with the
+/// file it came from, dated `created`.
+pub fn format_baseline(results: &[(Option<String>, AnalysisResult)], created: &str) -> String {
+    let mut output = String::new();
+    output.push_str("# PRISM findings baseline: accepted findings, left out of reports and quality gates.\n");
+    output.push_str(&format!("# Created by `prism baseline create` on {}. Delete a line to report its finding again.\n", created));
+    output.push_str("# fingerprint file line:column rule \"text\"\n");
+    for (file, result) in results {
+        for finding in &result.ambiguities {
+            let position = finding.location.as_ref()
+                .map(|location| format!("{}:{}", location.line, location.column))
+                .unwrap_or_else(|| "-".to_string());
+            output.push_str(&format!(
+                "{} {} {} {} \"{}\"\n",
+                finding.fingerprint,
+                file.as_deref().unwrap_or("-").replace(char::is_whitespace, "%20"),
+                position,
+                finding.rule_id,
+                finding.text.replace('\n', " "),
+            ));
+        }
+    }
+    output
+}
@@ -0,0 +1,225 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use crate::locale::report_locale;
+use crate::paths;
+
+/// Written next to the batch reports when some files could not be processed.
+pub const FAILURE_REPORT_NAME: &str = "failures.json";
+
+/// State of a batch run: the files already analyzed (with the hash of the
+/// content they were analyzed at) and the ones still to do. Paths are
+/// relative to the analyzed directory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResumeManifest {
+    /// Report format, analysis options and report target of the run; a run
+    /// with other settings does not resume it
+    #[serde(default)]
+    pub settings: String,
+    pub completed: BTreeMap<String, String>,
+    pub pending: Vec<String>,
+    pub failed: Vec<String>,
+    pub cancelled: bool,
+}
+
+impl ResumeManifest {
+    /// Where the manifest of batch runs over `dir` is kept:
+    /// `~/.prism/resume/<hash of the directory>.json`, so the analyzed
+    /// directory itself is never written to.
+    pub fn path(dir: &Path) -> anyhow::Result<PathBuf> {
+        let dir = paths::absolute(dir);
+        let name = format!("{}.json", &content_hash(&dir.to_string_lossy())[..16]);
+        Ok(paths::prism_dir()?.join("resume").join(name))
+    }
+
+    /// Describes the settings a batch writes its reports with, to compare in
+    /// `ResumeManifest::settings`.
+    pub fn settings(format: &str, analysis_options: &str, output: Option<&Path>, output_dir: Option<&Path>) -> String {
+        format!("{} {} {:?} {:?}", format, analysis_options, output, output_dir)
+    }
+
+    /// The manifest at `path`, if there is a readable one.
+    pub fn load(path: &Path) -> Option<Self> {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Whether `--resume` has anything left to do: files not processed yet or
+    /// files that failed.
+    pub fn has_remaining(&self) -> bool {
+        !self.pending.is_empty() || !self.failed.is_empty()
+    }
+
+    /// Whether `file` was completed with exactly this content.
+    pub fn is_unchanged(&self, file: &str, content: &str) -> bool {
+        self.completed.get(file).is_some_and(|hash| *hash == content_hash(content))
+    }
+
+    pub fn mark_completed(&mut self, file: &str, content: &str) {
+        self.pending.retain(|pending| pending != file);
+        self.completed.insert(file.to_string(), content_hash(content));
+    }
+
+    pub fn mark_failed(&mut self, file: &str) {
+        self.pending.retain(|pending| pending != file);
+        self.failed.push(file.to_string());
+    }
+}
+
+pub fn content_hash(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+/// Path of `file` relative to `dir`, with forward slashes, as used in manifests.
+pub fn relative_name(dir: &Path, file: &Path) -> String {
+    file.strip_prefix(dir).unwrap_or(file).to_string_lossy().replace('\\', "/")
+}
+
+/// Report file name for one file of a batch: `<stem>_analysis.md`, or
+/// `<output stem>_<stem>.<output extension>` when `--output` is given.
+pub fn report_file_name(file: &Path, output: Option<&Path>) -> String {
+    let file_stem = file.file_stem().unwrap_or_default().to_string_lossy();
+    match output {
+        Some(base_output) => {
+            let base_name = base_output.file_stem().unwrap_or_default().to_string_lossy();
+            let extension = base_output.extension().unwrap_or_default().to_string_lossy();
+            if extension.is_empty() {
+                format!("{}_{}.md", base_name, file_stem)
+            } else {
+                format!("{}_{}.{}", base_name, file_stem, extension)
+            }
+        }
+        None => format!("{}_analysis.md", file_stem),
+    }
+}
+
+/// Where the report for `file` goes: the working directory, or the same
+/// sub-directory under `output_dir` as the file has under `dir`.
+pub fn report_path(dir: &Path, file: &Path, output_dir: Option<&Path>, report_name: &str) -> PathBuf {
+    match output_dir {
+        Some(output_dir) => {
+            let parent = file.strip_prefix(dir).ok().and_then(Path::parent).unwrap_or(Path::new(""));
+            output_dir.join(parent).join(report_name)
+        }
+        None => PathBuf::from(report_name),
+    }
+}
+
+/// `path`, or `path` with `_2`, `_3`... added to the stem if another file of
+/// the run already got it (e.g. `login.md` and `login.txt`).
+pub fn unique_path(path: PathBuf, taken: &mut HashSet<PathBuf>) -> PathBuf {
+    if taken.insert(path.clone()) {
+        return path;
+    }
+
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    let extension = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    (2..)
+        .map(|n| path.with_file_name(format!("{}_{}{}", stem, n, extension)))
+        .find(|candidate| taken.insert(candidate.clone()))
+        .unwrap()
+}
+
+/// One per-file report listed in the batch index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchIndexEntry {
+    pub source: String,
+    /// Report path relative to the output directory
+    pub report: String,
+    /// `None` for files skipped by `--resume`
+    pub findings: Option<usize>,
+    pub findings_per_100_words: Option<f32>,
+}
+
+pub fn format_batch_index_markdown(dir: &Path, entries: &[BatchIndexEntry]) -> String {
+    let mut output = String::new();
+
+    output.push_str("# 📚 PRISM Batch Analysis Index\n\n");
+    output.push_str(&format!("**Source:** `{}`\n\n", dir.display()));
+    output.push_str(&format!("**Reports:** {}\n\n", entries.len()));
+
+    output.push_str("| Source file | Findings | Per 100 words | Report |\n|---|---:|---:|---|\n");
+    for entry in entries {
+        let (findings, density) = match (entry.findings, entry.findings_per_100_words) {
+            (Some(findings), Some(density)) => (findings.to_string(), report_locale().number(density, 1)),
+            _ => ("—".to_string(), "unchanged".to_string()),
+        };
+        output.push_str(&format!(
+            "| `{}` | {} | {} | [{}]({}) |\n",
+            entry.source, findings, density, entry.report, entry.report.replace(' ', "%20")
+        ));
+    }
+
+    output
+}
+
+/// Step of the batch pipeline a file failed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FailureStage {
+    /// Reading the file and extracting its text
+    Extraction,
+    /// Analyzing the text and generating the requested artifacts
+    Analysis,
+}
+
+/// One file of a batch that could not be processed, as listed in `failures.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchFailure {
+    pub file: String,
+    pub stage: FailureStage,
+    pub error: String,
+    pub remediation: String,
+}
+
+impl BatchFailure {
+    pub fn new(file: &str, stage: FailureStage, error: &anyhow::Error) -> Self {
+        let error = format!("{:#}", error);
+        Self {
+            file: file.to_string(),
+            stage,
+            remediation: suggest_remediation(stage, &error),
+            error,
+        }
+    }
+}
+
+/// What the user can do about a failure, judged from the error message.
+pub fn suggest_remediation(stage: FailureStage, error: &str) -> String {
+    let error = error.to_lowercase();
+    let mentions = |terms: &[&str]| terms.iter().any(|term| error.contains(term));
+
+    let remediation = if mentions(&["password", "encrypt"]) {
+        "The document is password-protected or encrypted - pass a PDF password with --doc-password, or remove the protection and export an unprotected copy"
+    } else if mentions(&["permission denied", "access is denied"]) {
+        "PRISM cannot read the file - check its permissions"
+    } else if mentions(&["utf-8", "utf8", "valid unicode", "stream did not contain valid"]) {
+        "The file is not valid UTF-8 text - re-save it with UTF-8 encoding"
+    } else if mentions(&["no text", "empty", "scanned", "image"]) {
+        "No text could be extracted - the file may be a scanned image; run it through OCR or export it as text"
+    } else if mentions(&["zip", "corrupt", "invalid", "unexpected eof", "malformed"]) && stage == FailureStage::Extraction {
+        "The document looks damaged - open and re-save it, or export it as .md or .txt"
+    } else if mentions(&["timed out", "timeout", "deadline"]) {
+        "The AI provider timed out - retry, raise llm.timeout in the config, or cap the run with --budget"
+    } else if mentions(&["401", "403", "unauthorized", "api key", "forbidden"]) {
+        "The AI provider rejected the credentials - check them with `prism config --test`"
+    } else if mentions(&["429", "rate limit", "quota"]) {
+        "The AI provider rate-limited the run - retry later or cap the run with --budget"
+    } else {
+        match stage {
+            FailureStage::Extraction => "Check that the file opens and contains text, or convert it to .md or .txt",
+            FailureStage::Analysis => "Analyze the file on its own with `prism analyze --file <path>` to see the full error",
+        }
+    };
+    remediation.to_string()
+}
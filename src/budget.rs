@@ -0,0 +1,141 @@
+use serde::Serialize;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// Characters per token used to estimate token counts from prompt sizes.
+const CHARS_PER_TOKEN: f64 = 4.0;
+
+/// Cap on AI-enhanced analysis, given as `--budget 60s` / `--budget 5m` for
+/// wall-clock time or `--budget $0.50` for estimated spend.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnalysisBudget {
+    Time(Duration),
+    Cost(f64),
+}
+
+impl FromStr for AnalysisBudget {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let invalid = || format!("Invalid budget '{}': use a duration like 60s, 5m or 1h, or an amount like $0.50", value);
+
+        if let Some(amount) = value.strip_prefix('$') {
+            let amount: f64 = amount.parse().map_err(|_| invalid())?;
+            return if amount >= 0.0 { Ok(AnalysisBudget::Cost(amount)) } else { Err(invalid()) };
+        }
+
+        let split = value.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(value.len());
+        let (number, unit) = value.split_at(split);
+        let number: f64 = number.parse().map_err(|_| invalid())?;
+        let seconds = match unit {
+            "s" | "sec" | "" => number,
+            "m" | "min" => number * 60.0,
+            "h" => number * 3600.0,
+            _ => return Err(invalid()),
+        };
+        Ok(AnalysisBudget::Time(Duration::try_from_secs_f64(seconds).map_err(|_| invalid())?))
+    }
+}
+
+impl std::fmt::Display for AnalysisBudget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnalysisBudget::Time(duration) => write!(f, "{}s", duration.as_secs_f64()),
+            AnalysisBudget::Cost(amount) => write!(f, "${:.2}", amount),
+        }
+    }
+}
+
+/// Characters sent to and received from the LLM, counted across all calls.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct LlmUsage {
+    pub calls: usize,
+    pub prompt_chars: usize,
+    pub response_chars: usize,
+    /// Responses whose JSON was read only after repairing it
+    pub repaired_responses: usize,
+    /// Responses of which only the valid items could be kept
+    pub salvaged_responses: usize,
+}
+
+impl LlmUsage {
+    /// Tokens sent and received, estimated from the character counts.
+    pub fn estimated_tokens(&self) -> u64 {
+        estimate_tokens(self.prompt_chars + self.response_chars)
+    }
+
+    /// Estimated spend in USD at the model's list price.
+    pub fn estimated_cost(&self, provider: &str, model: &str) -> f64 {
+        let (input, output) = price_per_million_tokens(provider, model);
+        let tokens = |chars: usize| chars as f64 / CHARS_PER_TOKEN;
+        (tokens(self.prompt_chars) * input + tokens(self.response_chars) * output) / 1_000_000.0
+    }
+}
+
+/// Tokens in `chars` characters of LLM prompt or response.
+pub fn estimate_tokens(chars: usize) -> u64 {
+    (chars as f64 / CHARS_PER_TOKEN).round() as u64
+}
+
+/// Approximate (input, output) USD price per million tokens. Unknown hosted
+/// models are priced like the more expensive common ones so the estimate
+/// errs on the side of stopping early.
+pub fn price_per_million_tokens(provider: &str, model: &str) -> (f64, f64) {
+    let model = model.to_lowercase();
+    if provider == "ollama" || provider == "mock" {
+        return (0.0, 0.0);
+    }
+    if model.contains("gpt-4o-mini") {
+        (0.15, 0.60)
+    } else if model.contains("gpt-4o") {
+        (2.50, 10.00)
+    } else if model.contains("gpt-3.5") {
+        (0.50, 1.50)
+    } else if model.contains("gpt-4") {
+        (30.00, 60.00)
+    } else if model.contains("haiku") {
+        (0.80, 4.00)
+    } else if model.contains("sonnet") {
+        (3.00, 15.00)
+    } else if model.contains("opus") {
+        (15.00, 75.00)
+    } else if model.contains("flash") {
+        (0.10, 0.40)
+    } else if model.contains("gemini") {
+        (1.25, 5.00)
+    } else {
+        (10.00, 30.00)
+    }
+}
+
+/// Tracks a budget over a run. Once exhausted it stays exhausted, so the rest
+/// of a batch consistently falls back to built-in analysis.
+#[derive(Debug, Clone)]
+pub struct BudgetTracker {
+    pub budget: AnalysisBudget,
+    started: Instant,
+    exhausted: bool,
+}
+
+impl BudgetTracker {
+    pub fn start(budget: AnalysisBudget) -> Self {
+        Self { budget, started: Instant::now(), exhausted: false }
+    }
+
+    /// Checks the budget against the time elapsed so far and the estimated
+    /// spend of `usage`.
+    pub fn is_exhausted(&mut self, usage: &LlmUsage, provider: &str, model: &str) -> bool {
+        if !self.exhausted {
+            self.exhausted = match self.budget {
+                AnalysisBudget::Time(limit) => self.started.elapsed() >= limit,
+                AnalysisBudget::Cost(limit) => usage.estimated_cost(provider, model) >= limit,
+            };
+        }
+        self.exhausted
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
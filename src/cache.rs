@@ -0,0 +1,104 @@
+//! On-disk cache of AI analysis results, so unchanged documents are not sent
+//! to the LLM again.
+
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+use crate::analyzer::AnalysisResult;
+use crate::config::Config;
+use crate::paths;
+
+pub struct AnalysisCache {
+    dir: PathBuf,
+}
+
+impl AnalysisCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// `~/.prism/cache`
+    pub fn default_dir() -> Result<PathBuf> {
+        Ok(paths::prism_dir()?.join("cache"))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Cache key of `text` analyzed with the configured provider, model,
+    /// request and analysis settings. `options` covers everything else that
+    /// changes the result, such as the artifacts generated. Results of another
+    /// PRISM version are never reused.
+    pub fn key(text: &str, config: &Config, options: &str) -> String {
+        let mut hasher = Sha256::new();
+        for part in [
+            env!("CARGO_PKG_VERSION"),
+            &config.llm.provider,
+            &config.llm.model,
+            config.llm.base_url.as_deref().unwrap_or_default(),
+            &serde_json::to_string(&config.llm.generation()).unwrap_or_default(),
+            &serde_json::to_string(&config.analysis).unwrap_or_default(),
+            options,
+            text,
+        ] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+
+    /// The cached result for `key`. Unreadable entries count as missing.
+    pub fn get(&self, key: &str) -> Option<AnalysisResult> {
+        let content = std::fs::read_to_string(self.entry_path(key)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn put(&self, key: &str, result: &AnalysisResult) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        // Written under a temporary name first so a parallel batch never reads half an entry
+        let temporary = self.dir.join(format!("{}.tmp", key));
+        std::fs::write(&temporary, serde_json::to_string(result)?)?;
+        std::fs::rename(temporary, self.entry_path(key))?;
+        Ok(())
+    }
+
+    /// Number of cached results and their total size in bytes.
+    pub fn size(&self) -> Result<(usize, u64)> {
+        let mut entries = 0;
+        let mut bytes = 0;
+        for path in self.entry_paths()? {
+            entries += 1;
+            bytes += std::fs::metadata(path)?.len();
+        }
+        Ok((entries, bytes))
+    }
+
+    /// Removes every cached result and returns how many there were.
+    pub fn clear(&self) -> Result<usize> {
+        let paths = self.entry_paths()?;
+        for path in &paths {
+            std::fs::remove_file(path)?;
+        }
+        Ok(paths.len())
+    }
+
+    fn entry_paths(&self) -> Result<Vec<PathBuf>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == "json") {
+                paths.push(path);
+            }
+        }
+        Ok(paths)
+    }
+}
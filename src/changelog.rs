@@ -0,0 +1,148 @@
+//! Changelog of an improvement: which findings drove which edits of the
+//! improved requirements, kept in the JSON report and shown as footnotes in
+//! Markdown, so reviewers can tell why each sentence was rewritten.
+
+use serde::{Deserialize, Serialize};
+
+use crate::analyzer::Ambiguity;
+use crate::redline::{redline, Segment};
+
+/// One edit of the improvement and the findings it addresses.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImprovementChange {
+    /// Text of the original that was replaced or removed, empty for an addition
+    pub original: String,
+    /// Byte offset of `original` in the original text
+    pub original_offset: usize,
+    /// Text that replaced it, empty for a removal
+    pub improved: String,
+    /// Byte offset of `improved` in the improved text
+    pub improved_offset: usize,
+    /// 1-based line of the edit in the improved text, or in the original
+    /// for a removed paragraph
+    pub line: usize,
+    /// Fingerprints of the findings the edit addresses; empty when the
+    /// model changed text no finding pointed at
+    pub findings: Vec<String>,
+}
+
+/// Byte offsets of the starts of `text`'s lines.
+fn line_starts(text: &str) -> Vec<usize> {
+    let mut offset = 0;
+    text.split_inclusive('\n').map(|line| {
+        let start = offset;
+        offset += line.len();
+        start
+    }).collect()
+}
+
+/// The edits turning `original` into `improved`, each with the findings in
+/// `findings` whose text it changed or, for text only added, the findings
+/// on the line it was added to.
+pub fn build_changelog(original: &str, improved: &str, findings: &[Ambiguity]) -> Vec<ImprovementChange> {
+    let (original_starts, improved_starts) = (line_starts(original), line_starts(improved));
+    let mut changes = Vec::new();
+    // Where the original continues, for paragraphs only the improvement has
+    let mut next_original_offset = 0;
+    for paragraph in redline(original, improved) {
+        let start_of = |starts: &[usize], line: Option<usize>| line.and_then(|line| starts.get(line).copied());
+        let mut original_offset = start_of(&original_starts, paragraph.original_line).unwrap_or(next_original_offset);
+        let mut improved_offset = start_of(&improved_starts, paragraph.improved_line).unwrap_or(improved.len());
+        if let Some(line) = paragraph.original_line {
+            next_original_offset = start_of(&original_starts, Some(line + 1)).unwrap_or(original.len());
+        }
+        let first_change = changes.len();
+        let mut change: Option<ImprovementChange> = None;
+        for segment in paragraph.segments {
+            let change = match segment {
+                Segment::Same(text) => {
+                    changes.extend(change.take());
+                    original_offset += text.len();
+                    improved_offset += text.len();
+                    continue;
+                }
+                _ => change.get_or_insert_with(|| ImprovementChange {
+                    original: String::new(),
+                    original_offset,
+                    improved: String::new(),
+                    improved_offset,
+                    line: paragraph.improved_line.or(paragraph.original_line).unwrap_or_default() + 1,
+                    findings: Vec::new(),
+                }),
+            };
+            match segment {
+                Segment::Deleted(text) => {
+                    original_offset += text.len();
+                    change.original.push_str(&text);
+                }
+                Segment::Inserted(text) => {
+                    improved_offset += text.len();
+                    change.improved.push_str(&text);
+                }
+                Segment::Same(_) => {}
+            }
+        }
+        changes.extend(change);
+        for change in &mut changes[first_change..] {
+            change.findings = addressed_findings(change, paragraph.original_line, findings);
+        }
+    }
+
+    changes.retain(|change| !change.original.trim().is_empty() || !change.improved.trim().is_empty());
+    changes
+}
+
+/// The findings whose text `change` replaced or inserted into, falling back
+/// to those on the original line of a rewritten paragraph.
+fn addressed_findings(change: &ImprovementChange, original_line: Option<usize>, findings: &[Ambiguity]) -> Vec<String> {
+    let (start, end) = (change.original_offset, change.original_offset + change.original.len());
+    let located = || findings.iter().filter_map(|finding| finding.location.as_ref().map(|location| (finding, location)));
+    let changed: Vec<String> = located()
+        .filter(|(finding, location)| {
+            let (finding_start, finding_end) = (location.offset, location.offset + finding.text.len());
+            if start < end {
+                finding_start < end && start < finding_end
+            } else {
+                finding_start <= start && start <= finding_end
+            }
+        })
+        .map(|(finding, _)| finding.fingerprint.clone())
+        .collect();
+    match original_line {
+        Some(line) if changed.is_empty() => located()
+            .filter(|(_, location)| location.line == line + 1)
+            .map(|(finding, _)| finding.fingerprint.clone())
+            .collect(),
+        _ => changed,
+    }
+}
+
+/// The edits as a list, each with a footnote naming the findings behind it.
+pub fn format_changelog_markdown(changes: &[ImprovementChange], findings: &[Ambiguity]) -> String {
+    let mut output = String::new();
+    let mut footnotes = Vec::new();
+    for change in changes {
+        let (original, improved) = (change.original.trim(), change.improved.trim());
+        let edit = match (original.is_empty(), improved.is_empty()) {
+            (true, _) => format!("added **{}**", improved),
+            (_, true) => format!("removed ~~{}~~", original),
+            _ => format!("~~{}~~ → **{}**", original, improved),
+        };
+        let addressed: Vec<String> = change.findings.iter()
+            .filter_map(|fingerprint| findings.iter().find(|finding| &finding.fingerprint == fingerprint))
+            .map(|finding| format!("\"{}\" - {} (`{}`)", finding.text, finding.reason, &finding.fingerprint[..finding.fingerprint.len().min(8)]))
+            .collect();
+        if addressed.is_empty() {
+            output.push_str(&format!("- Line {}: {}\n", change.line, edit));
+        } else {
+            footnotes.push(format!("[^change-{}]: Addresses {}", footnotes.len() + 1, addressed.join("; ")));
+            output.push_str(&format!("- Line {}: {}[^change-{}]\n", change.line, edit, footnotes.len()));
+        }
+    }
+    if !footnotes.is_empty() {
+        output.push('\n');
+        output.push_str(&footnotes.join("\n"));
+        output.push('\n');
+    }
+    output
+}
@@ -0,0 +1,206 @@
+use crate::analyzer::AnalysisResult;
+use crate::coverage::content_terms;
+use crate::index::{source_sentences, IndexHit, RequirementIndex};
+
+/// Words that make up the question itself rather than what it asks about.
+const QUESTION_WORDS: &[&str] = &[
+    "which", "what", "where", "who", "whom", "how", "why", "does", "there", "list", "show", "find",
+    "tell", "about", "mention", "mentions", "requirement", "requirements", "story", "stories",
+    "talk", "say", "says", "refer", "document", "spec",
+];
+
+/// One sentence of the source document and the line it starts on.
+#[derive(Debug, Clone)]
+pub struct SourceSentence {
+    pub line: usize,
+    pub text: String,
+}
+
+/// Question answering over one analyzed document. Built-in answers only use
+/// the analysis result and the source sentences, and cite line numbers. With a
+/// corpus index attached, related requirements from other files are added.
+pub struct ChatSession {
+    pub source_name: String,
+    pub result: AnalysisResult,
+    pub sentences: Vec<SourceSentence>,
+    index: Option<RequirementIndex>,
+    /// Index path of the chatted document, so it isn't cited as related to itself
+    index_source: Option<String>,
+}
+
+impl ChatSession {
+    pub fn new(source_name: &str, text: &str, result: AnalysisResult) -> Self {
+        let sentences = source_sentences(text)
+            .into_iter()
+            .map(|(line, text)| SourceSentence { line, text })
+            .collect();
+
+        Self { source_name: source_name.to_string(), result, sentences, index: None, index_source: None }
+    }
+
+    pub fn with_index(mut self, index: RequirementIndex, index_source: Option<String>) -> Self {
+        self.index = Some(index);
+        self.index_source = index_source;
+        self
+    }
+
+    /// Best matches for the question in the other files of the corpus index.
+    pub fn related_requirements(&self, question: &str, limit: usize) -> Vec<IndexHit> {
+        match &self.index {
+            Some(index) => index.search(question, limit, self.index_source.as_deref()),
+            None => Vec::new(),
+        }
+    }
+
+    /// Content terms of the question, without the question's own wording.
+    fn query_terms(question: &str) -> Vec<String> {
+        let stripped: Vec<&str> = question.split_whitespace()
+            .filter(|w| !QUESTION_WORDS.contains(&w.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase().as_str()))
+            .collect();
+        content_terms(&stripped.join(" "))
+    }
+
+    /// Sentences sharing the most terms with the question, best first.
+    pub fn relevant_sentences(&self, question: &str, limit: usize) -> Vec<&SourceSentence> {
+        let query = Self::query_terms(question);
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut scored: Vec<(usize, &SourceSentence)> = self.sentences.iter()
+            .map(|sentence| {
+                let terms = content_terms(&sentence.text);
+                (query.iter().filter(|q| terms.contains(q)).count(), sentence)
+            })
+            .filter(|(score, _)| *score > 0)
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.line.cmp(&b.1.line)));
+        scored.into_iter().take(limit).map(|(_, s)| s).collect()
+    }
+
+    /// Answers from the analysis result and the source text alone.
+    pub fn answer_builtin(&self, question: &str) -> String {
+        let q = question.to_lowercase();
+        let has = |words: &[&str]| words.iter().any(|w| q.contains(w));
+
+        // "which requirements mention X" is always a search, even when X is a word like "roles"
+        if has(&["mention", "refer to", "talk about"]) {
+            return self.describe_matches(question);
+        }
+        if has(&["ambiguous", "ambiguit", "unclear", "vague", "finding", "issue", "problem"]) {
+            return self.describe_findings();
+        }
+        if has(&["actor", "who ", "role", "persona"]) {
+            return Self::describe_list("Actors", &self.result.entities.actors);
+        }
+        if has(&["action", "what can", "operation"]) {
+            return Self::describe_list("Actions", &self.result.entities.actions);
+        }
+        if has(&["object", "entit"]) {
+            return Self::describe_list("Objects", &self.result.entities.objects);
+        }
+        if has(&["how many", "summary", "metric", "density"]) {
+            let metrics = &self.result.metrics;
+            return format!(
+                "{} has {} words and {} requirements, with {} findings ({:.1} per 100 words, {:.2} per requirement).",
+                self.source_name, metrics.word_count, metrics.requirement_count, self.result.ambiguities.len(),
+                metrics.findings_per_100_words, metrics.findings_per_requirement
+            );
+        }
+
+        self.describe_matches(question)
+    }
+
+    fn describe_matches(&self, question: &str) -> String {
+        let matches = self.relevant_sentences(question, 10);
+        let related = self.related_requirements(question, 5);
+        if matches.is_empty() && related.is_empty() {
+            return format!("I couldn't find anything about that in {}. Try 'help' for example questions.", self.source_name);
+        }
+
+        let mut answer = if matches.is_empty() {
+            format!("Nothing in {} matches.\n", self.source_name)
+        } else {
+            format!("{} sentence(s) in {} match:\n", matches.len(), self.source_name)
+        };
+        for sentence in matches {
+            answer.push_str(&format!("  • line {}: {}\n", sentence.line, sentence.text));
+        }
+        if !related.is_empty() {
+            answer.push_str("Related requirements elsewhere in the corpus:\n");
+            for hit in related {
+                answer.push_str(&format!("  • {}:{}: {}\n", hit.source, hit.line, hit.text));
+            }
+        }
+        answer.trim_end().to_string()
+    }
+
+    fn describe_findings(&self) -> String {
+        if self.result.ambiguities.is_empty() {
+            return "Nothing is flagged as ambiguous in this document.".to_string();
+        }
+
+        let mut answer = format!("{} finding(s) are still open:\n", self.result.ambiguities.len());
+        for ambiguity in &self.result.ambiguities {
+            let line = ambiguity.location.as_ref().map(|l| format!("line {}: ", l.line)).unwrap_or_default();
+            answer.push_str(&format!("  • {}'{}' ({:?}) - {}\n", line, ambiguity.text, ambiguity.severity, ambiguity.reason));
+        }
+        answer.trim_end().to_string()
+    }
+
+    fn describe_list(label: &str, items: &[String]) -> String {
+        if items.is_empty() {
+            format!("No {} were identified.", label.to_lowercase())
+        } else {
+            format!("{}: {}", label, items.join(", "))
+        }
+    }
+
+    /// Prompt that grounds an LLM answer in the most relevant excerpts and the
+    /// analysis findings.
+    pub fn llm_prompt(&self, question: &str) -> String {
+        let mut excerpts: Vec<&SourceSentence> = self.relevant_sentences(question, 15);
+        if excerpts.is_empty() {
+            excerpts = self.sentences.iter().take(30).collect();
+        }
+        let excerpts = excerpts.iter()
+            .map(|s| format!("[line {}] {}", s.line, s.text))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let related = self.related_requirements(question, 10).iter()
+            .map(|hit| format!("[{}:{}] {}", hit.source, hit.line, hit.text))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let findings = self.result.ambiguities.iter()
+            .map(|a| format!("- '{}' ({:?}): {}", a.text, a.severity, a.reason))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        format!(
+            "Answer the question about the requirements document '{}' using ONLY the excerpts, related requirements and analysis findings below. Cite line numbers like (line 12) or (file.md:12). If the answer is not in them, say so.
+
+EXCERPTS:
+{}
+
+RELATED REQUIREMENTS IN OTHER FILES:
+{}
+
+ANALYSIS FINDINGS:
+{}
+
+Actors: {}
+Actions: {}
+Objects: {}
+
+QUESTION: {}",
+            self.source_name,
+            excerpts,
+            if related.is_empty() { "none".to_string() } else { related },
+            if findings.is_empty() { "none".to_string() } else { findings },
+            self.result.entities.actors.join(", "),
+            self.result.entities.actions.join(", "),
+            self.result.entities.objects.join(", "),
+            question
+        )
+    }
+}
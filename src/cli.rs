@@ -1,5 +1,11 @@
 use clap::{Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
 use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::analyzer::AmbiguitySeverity;
+use crate::budget::AnalysisBudget;
 
 #[derive(Parser)]
 #[command(name = "prism")]
@@ -10,51 +16,121 @@ QUICK START:
   prism analyze \"As a user, I want to login quickly\" --preset standard  # Smart preset
   prism improve \"As a user, I want to login quickly\"                    # Generate improved requirements  
   prism config --setup                                                  # Interactive AI setup
+  prism new                                                             # Write a new requirement step by step
+  prism chat --file spec.md                                             # Ask questions about a document
+  prism index --dir requirements/ --report                              # Index a corpus, find duplicates
   prism tui                                                             # Launch interactive TUI
 
 EXAMPLES:
   prism analyze --file requirements.txt --preset full --format markdown
   prism validate --dir ./stories --all --output validation.md
   prism dashboard --file requirements.txt --output dashboard.html
-  prism trace --from-commit abc123 --to-commit def456")]
+  prism hierarchy --dir ./requirements
+  prism coverage --epic epic.md --stories ./stories
+  prism release-check --dir ./stories --epic epic.md
+  prism trace --from-commit abc123 --to-commit def456
+
+RECORDED AI RESPONSES:
+  prism analyze --file spec.md --record fixtures/   # Save each LLM response
+  prism analyze --file spec.md --replay fixtures/   # Same results, offline, no API key")]
 #[command(version = "1.0.0")]
 pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Commands>,
+
+    #[arg(long, global = true, help = "Password for password-protected PDF input files")]
+    pub doc_password: Option<String>,
+
+    #[arg(long, global = true, value_name = "DIR", conflicts_with = "replay", help = "Save every LLM response to a fixture file in DIR")]
+    pub record: Option<PathBuf>,
+
+    #[arg(long, global = true, value_name = "DIR", help = "Answer LLM calls from the fixture files in DIR instead of the provider")]
+    pub replay: Option<PathBuf>,
+
+    #[arg(long, global = true, help = "Leave out the PRISM banner")]
+    pub no_banner: bool,
+
+    #[arg(long, global = true, help = "Report progress and findings as JSON lines on stderr instead of status lines, for editor extensions and other wrapping tools")]
+    pub events: bool,
+
+    #[arg(long, global = true, help = "Name input files in reports relative to the current directory, with / separators, so reports are the same on every machine")]
+    pub paths_relative: bool,
 }
 
-#[derive(Subcommand)]
+// Parsed once per run, so the size of the analyze variant does not matter
+#[allow(clippy::large_enum_variant)]
+#[derive(Subcommand, Debug)]
 pub enum Commands {
     #[command(about = "Analyze requirements and generate artifacts")]
     #[command(long_about = "Analyze software requirements with simplified options and smart presets.
 
 INPUT OPTIONS (choose one):
   <TEXT>     Direct requirement text in quotes
-  --file     Single file to analyze (.txt, .md, .rst, .pdf, .docx, .xlsx)
+  --file     Single file to analyze (.txt, .md, .rst, .pdf, .docx, .xlsx), or a
+             .zip archive whose documents are analyzed as a batch
   --dir      Directory containing multiple requirement files
+  (stdin)    Piped text when none of the above is given: cat req.md | prism analyze
 
 PRESET OPTIONS (recommended):
   --preset basic     Just analysis + ambiguity detection
   --preset standard  Analysis + UML + tests + pseudocode  
-  --preset full      All generation options (UML, pseudo, tests, improve, NFRs)
+  --preset full      All generation options (UML, pseudo, tests, improve, NFRs, permissions, suggestions)
   --preset report    Analysis optimized for markdown reports
+  --preset <name>    A custom preset defined under presets in ~/.prism/config.yml
 
 CUSTOM GENERATION:
-  --generate         Choose specific artifacts: all, uml, pseudo, tests, improve, nfr
+  --generate         Choose specific artifacts: all, uml, pseudo, tests, gherkin, improve, nfr, permissions, suggestions, dependencies, reviewers, domain-model, smart, event-storming, load-tests, security-tests
 
 OUTPUT OPTIONS:
-  --format          Output format: json, markdown, github, jira, plain
+  --format          Output format: json, markdown, github, jira, jira-adf, plain, sarif, html, pdf, csv, annotated, diagnostics
   --output          Save results to file instead of displaying
+  --max-comment-size  Split github reports into parts no larger than this (default 65536)
+
+QUALITY GATE (exits with an error, for CI):
+  --fail-on high           Fail on findings of this severity or above
+  --min-completeness 70    Fail when the completeness score is below this percentage
+                           In a --dir batch, every file has to pass
+
+AI BUDGET:
+  --budget 60s      Stop AI-enhanced analysis after this much time (s, m, h)
+  --budget $0.50    Stop AI-enhanced analysis once the estimated spend reaches this
+                    Remaining files in a --dir batch use built-in analysis
+
+BATCH RUNS (--dir or a .zip --file):
+  --output-dir      Write per-file reports here (mirroring the input folders) plus an index.md
+  --jobs N          Analyze up to N files at a time
+  --resume          Only process files not completed (or changed since) in the previous
+                    --dir run (--dir only; a .zip is unpacked afresh every run);
+                    Ctrl-C stops a batch cleanly so it can be resumed
+  --owners FILE     Break findings down by owning team (default: the repository's
+                    CODEOWNERS) in findings-by-owner.md
+
+PROVIDER COMPARISON:
+  --compare-providers            Run the analysis with every configured provider at once
+  --compare-providers=openai,claude
+                                 ...or with these, and report side by side what each
+                                 found, its response time and estimated cost
+  Other providers than llm.provider take their model and API key from llm.providers
+
+CACHE:
+  AI analyses are cached in ~/.prism/cache by text, provider, model and options, so
+  unchanged documents are not sent to the LLM again
+  --no-cache        Analyze everything again (prism cache clear empties the cache)
 
 EXAMPLES:
   prism analyze \"As a user, I want to reset my password\" --preset standard
   prism analyze --file story.txt --preset full --format markdown
-  prism analyze --dir ./requirements --preset report --output analysis.md")]
+  prism analyze --dir ./requirements --preset report --output analysis.md
+  prism analyze --dir ./requirements --preset full --budget '$0.50'
+  prism analyze --dir ./requirements --preset full --resume
+  prism analyze --dir ./requirements --format markdown --output-dir reports/
+  prism analyze --file confluence-export.zip --output-dir reports/
+  prism analyze --file story.md --compare-providers=openai,claude,ollama")]
     Analyze {
         #[arg(help = "Direct requirement text to analyze (use quotes for multi-word text)")]
         text: Option<String>,
         
-        #[arg(short, long, help = "File to analyze (.txt, .md, .rst, .pdf, .docx, .xlsx files supported)")]
+        #[arg(short, long, help = "File to analyze (.txt, .md, .rst, .pdf, .docx, .xlsx files supported; a .zip is analyzed as a batch)")]
         file: Option<PathBuf>,
         
         #[arg(short, long, help = "Directory to analyze (processes all .txt, .md, .rst, .pdf, .docx, .xlsx files)")]
@@ -63,7 +139,7 @@ EXAMPLES:
         #[arg(short, long, help = "Save output to file instead of displaying on screen")]
         output: Option<PathBuf>,
         
-        #[arg(long, help = "Use analysis preset", value_enum)]
+        #[arg(long, help = "Use analysis preset (basic, standard, full, report or a custom preset from config)")]
         preset: Option<AnalysisPreset>,
         
         #[arg(long, help = "Generate specific artifacts", value_enum, action = clap::ArgAction::Append)]
@@ -75,6 +151,9 @@ EXAMPLES:
         #[arg(long, help = "Pseudocode language style (python, java, etc.)")]
         pseudo_lang: Option<String>,
         
+        #[arg(long, help = "Lay the pseudocode out in this architecture instead of a single service class", value_enum)]
+        arch: Option<ArchitectureStyle>,
+        
         #[arg(long, help = "Save individual artifacts as separate files (base filename for suffixed files)")]
         save_artifacts: Option<String>,
         
@@ -83,6 +162,18 @@ EXAMPLES:
         
         #[arg(long, help = "Add custom branding to output")]
         branding: Option<String>,
+
+        #[arg(long, help = "Number the sections and findings of markdown and html reports and add a table of contents")]
+        toc: bool,
+
+        #[arg(long, value_enum, help = "Leave out findings below this severity; the summary counts them")]
+        min_severity: Option<AmbiguitySeverity>,
+
+        #[arg(long, value_enum, value_name = "SEVERITY", help = "Exit with an error when there are findings of this severity or above")]
+        fail_on: Option<AmbiguitySeverity>,
+
+        #[arg(long, value_name = "PERCENT", help = "Exit with an error when the completeness score is below this percentage (checks completeness)")]
+        min_completeness: Option<f32>,
         
         #[arg(long, help = "Continue processing on errors instead of stopping")]
         continue_on_error: bool,
@@ -90,8 +181,45 @@ EXAMPLES:
         #[arg(long, help = "Skip invalid files during directory processing")]
         skip_invalid: bool,
         
-        #[arg(long, help = "Number of parallel processes for batch operations", default_value = "1")]
-        parallel: usize,
+        #[arg(short, long, visible_alias = "parallel", help = "Number of batch files to analyze at a time", default_value = "1")]
+        jobs: usize,
+        
+        #[arg(long, help = "Maximum characters per GitHub comment; larger github reports are split into parts", default_value = "65536")]
+        max_comment_size: usize,
+        
+        #[arg(long, help = "Cap AI-enhanced analysis by wall-clock time (60s, 5m) or estimated spend ($0.50); later files fall back to built-in analysis")]
+        budget: Option<AnalysisBudget>,
+        
+        #[arg(long, requires = "dir", help = "Skip files the previous --dir run completed and that haven't changed since")]
+        resume: bool,
+        
+        #[arg(long, value_name = "REF", conflicts_with_all = ["text", "file"], help = "Only analyze files changed between this git ref and HEAD: those under --dir, or the repository's requirement files without --dir")]
+        since: Option<String>,
+        
+        #[arg(long, help = "Write --dir or .zip reports into this directory, mirroring the input folders, with an index.md linking them")]
+        output_dir: Option<PathBuf>,
+
+        #[arg(long, help = "Do not reuse cached AI analyses or cache new ones")]
+        no_cache: bool,
+
+        #[arg(long, help = "Owners file in CODEOWNERS syntax for the by-team breakdown of a batch (default: the repository's CODEOWNERS)")]
+        owners: Option<PathBuf>,
+
+        #[arg(long, help = "AI provider for this run, overriding the config and PRISM_PROVIDER", value_enum)]
+        provider: Option<AiProvider>,
+
+        #[arg(long, help = "AI model for this run, overriding the config and PRISM_MODEL")]
+        model: Option<String>,
+
+        #[arg(long, help = "Sampling temperature (0-2) for this run, overriding the config and PRISM_TEMPERATURE")]
+        temperature: Option<f32>,
+
+        #[arg(long, help = "Longest AI response in tokens for this run, overriding the config and PRISM_MAX_TOKENS")]
+        max_tokens: Option<u32>,
+
+        #[arg(long, value_enum, value_name = "PROVIDERS", num_args = 0..=1, require_equals = true, value_delimiter = ',',
+            help = "Analyze with several providers at once and compare what they find (default: every configured provider)")]
+        compare_providers: Option<Vec<AiProvider>>,
     },
     
     #[command(about = "Launch interactive terminal interface")]
@@ -115,7 +243,8 @@ KEYBOARD SHORTCUTS:
 EXAMPLES:
   prism improve \"As a user, I want to login quickly\"
   prism improve --file requirements.txt --output improved_req.md
-  prism improve --dir ./stories --format markdown")]
+  prism improve --dir ./stories --format markdown
+  cat requirements.md | prism improve")]
     Improve {
         #[arg(help = "Direct requirement text to improve (use quotes for multi-word text)")]
         text: Option<String>,
@@ -131,6 +260,21 @@ EXAMPLES:
         
         #[arg(long, help = "Output format", value_enum)]
         format: Option<OutputFormat>,
+
+        #[arg(long, value_name = "DOCX", help = "Also save the improvements as a Word document with tracked changes against the original")]
+        redline: Option<PathBuf>,
+
+        #[arg(long, help = "AI provider for this run, overriding the config and PRISM_PROVIDER", value_enum)]
+        provider: Option<AiProvider>,
+
+        #[arg(long, help = "AI model for this run, overriding the config and PRISM_MODEL")]
+        model: Option<String>,
+
+        #[arg(long, help = "Sampling temperature (0-2) for this run, overriding the config and PRISM_TEMPERATURE")]
+        temperature: Option<f32>,
+
+        #[arg(long, help = "Longest AI response in tokens for this run, overriding the config and PRISM_MAX_TOKENS")]
+        max_tokens: Option<u32>,
     },
     
     #[command(about = "Validate user stories and analyze completeness")]
@@ -139,12 +283,15 @@ EXAMPLES:
 VALIDATION OPTIONS:
   --story           Validate user story format and business value
   --completeness    Analyze completeness and identify gaps
+  --ears            Classify requirement sentences into EARS patterns
   --all             Run all validation checks
 
 EXAMPLES:
   prism validate \"As a user, I want to login\" --story
   prism validate --file story.txt --completeness
-  prism validate --dir ./stories --all")]
+  prism validate --file spec.md --ears --format markdown
+  prism validate --dir ./stories --all
+  cat story.txt | prism validate --story")]
     Validate {
         #[arg(help = "Direct requirement text to validate (use quotes for multi-word text)")]
         text: Option<String>,
@@ -164,11 +311,17 @@ EXAMPLES:
         #[arg(long, help = "Analyze completeness and identify gaps")]
         completeness: bool,
         
+        #[arg(long, help = "Check requirement sentences against the EARS patterns and suggest rewrites")]
+        ears: bool,
+        
         #[arg(long, help = "Run all validation checks")]
         all: bool,
         
         #[arg(long, help = "Output format", value_enum)]
         format: Option<OutputFormat>,
+        
+        #[arg(long, help = "Maximum characters per GitHub comment; larger github reports are split into parts", default_value = "65536")]
+        max_comment_size: usize,
     },
 
     #[command(about = "Trace requirements to source code and tests")]
@@ -232,6 +385,647 @@ EXAMPLES:
         executive_summary: bool,
     },
 
+    #[command(about = "Ask questions about an analyzed requirements document")]
+    #[command(long_about = "Interactive question-and-answer session over one requirements document. The document is
+analyzed first; answers are grounded in the analysis results and the source text and cite
+line numbers. With AI configured the model answers from the most relevant excerpts,
+otherwise PRISM answers with built-in lookups.
+
+EXAMPLE QUESTIONS:
+  which requirements mention payments?
+  what's still ambiguous?
+  who are the actors?
+  how many requirements are there?
+
+EXAMPLES:
+  prism chat --file spec.md
+  prism chat --file spec.md --index requirements/")]
+    Chat {
+        #[arg(short, long, help = "Requirements document to ask about")]
+        file: PathBuf,
+        
+        #[arg(long, help = "Requirements directory whose index supplies related requirements from other files")]
+        index: Option<PathBuf>,
+    },
+
+    #[command(about = "Build a local search index over a requirements corpus")]
+    #[command(long_about = "Build or refresh a local embeddings index over every requirement file in a directory.
+The index is stored in <dir>/.prism-index.json and updated incrementally: only new and
+changed files are re-embedded, deleted files are dropped. Embeddings are computed locally
+from hashed content terms, no AI provider is needed.
+
+The index is used by 'prism chat --index <dir>' and by --report, which lists duplicate and
+conflicting requirements across the corpus.
+
+EXAMPLES:
+  prism index --dir requirements/
+  prism index --dir requirements/ --report --output corpus.md")]
+    Index {
+        #[arg(short, long, help = "Directory containing the requirements corpus")]
+        dir: PathBuf,
+        
+        #[arg(long, help = "Report duplicate and conflicting requirements across the corpus")]
+        report: bool,
+        
+        #[arg(short, long, help = "Save the report to file")]
+        output: Option<PathBuf>,
+        
+        #[arg(long, help = "Report format (markdown or json)", value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    #[command(about = "Write a new requirement with a guided wizard")]
+    #[command(long_about = "Interactive wizard that asks for the actor, goal, benefit, acceptance criteria and
+non-functional requirements, checks each answer as you go, and writes a well-formed
+requirement file.
+
+TEMPLATES:
+  The file is rendered with a Handlebars template. The default produces a user story
+  with Acceptance Criteria and Non-Functional Requirements sections. Custom templates can
+  use {{title}}, {{article}}, {{actor}}, {{goal}}, {{benefit}}, {{acceptance_criteria}} and {{nfrs}}.
+
+EXAMPLES:
+  prism new
+  prism new --output stories/password-reset.md
+  prism new --template team-story.hbs")]
+    New {
+        #[arg(short, long, help = "Requirement file to write (default: derived from the goal)")]
+        output: Option<PathBuf>,
+        
+        #[arg(long, help = "Handlebars template for the requirement file")]
+        template: Option<PathBuf>,
+    },
+
+    #[command(about = "Report the epic → feature → story hierarchy with quality scores")]
+    #[command(long_about = "Detect the requirement hierarchy in a directory and report aggregated quality scores at every level.
+
+HIERARCHY DETECTION:
+  Folders           Each folder level becomes a node (e.g. epics/feature folders)
+  Headings          Markdown headings inside a file become nested nodes
+  ID prefixes       EPIC-1, FEAT-2, US-3 or dotted numbers (1, 1.2, 1.2.3) set the level
+
+EXAMPLES:
+  prism hierarchy --dir ./requirements
+  prism hierarchy --dir ./requirements --format json --output hierarchy.json")]
+    Hierarchy {
+        #[arg(short, long, help = "Directory containing the requirement hierarchy")]
+        dir: PathBuf,
+        
+        #[arg(short, long, help = "Save hierarchy report to file")]
+        output: Option<PathBuf>,
+        
+        #[arg(long, help = "Output format (markdown or json)", value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    #[command(about = "Write a requirement quality badge for your README")]
+    #[command(long_about = "Score the project's requirements (0-100, as in prism hierarchy) and write an SVG badge
+plus a JSON file for the shields.io endpoint badge. Commit them or publish them from CI.
+
+README:
+  ![Requirements](docs/prism-badge.svg)
+  ![Requirements](https://img.shields.io/endpoint?url=<public URL of prism-badge.json>)
+
+EXAMPLES:
+  prism badge --dir ./requirements
+  prism badge --dir ./requirements --svg docs/prism-badge.svg --json docs/prism-badge.json
+  prism badge --file requirements.md --label \"Spec quality\"")]
+    Badge {
+        #[arg(short, long, help = "Requirement file to score")]
+        file: Option<PathBuf>,
+
+        #[arg(short, long, help = "Directory of requirement files to score")]
+        dir: Option<PathBuf>,
+
+        #[arg(long, default_value = "prism-badge.svg", help = "Where to write the SVG badge")]
+        svg: PathBuf,
+
+        #[arg(long, default_value = "prism-badge.json", help = "Where to write the shields.io endpoint JSON")]
+        json: PathBuf,
+
+        #[arg(long, default_value = crate::badge::DEFAULT_BADGE_LABEL, help = "Text on the left of the badge")]
+        label: String,
+    },
+
+    #[command(about = "Rank requirement documents by cost of delay against their quality")]
+    #[command(long_about = "Score each requirement document in a directory and plot its cost of delay against its
+quality score, so the low-quality requirements holding up the most valuable work get fixed first.
+
+ANNOTATIONS (the sidecar wins when both are given):
+  Front matter    ---
+                  cost_of_delay: 8000        # or value, time_criticality and risk_reduction
+                  ---
+  CSV sidecar     cost-of-delay.csv in the directory, or --sidecar:
+                  file,cost_of_delay
+                  checkout/payments.md,8000
+
+EXAMPLES:
+  prism prioritize --dir ./requirements
+  prism prioritize --dir ./requirements --format html --output cost-of-delay.html
+  prism prioritize --dir ./requirements --sidecar planning/cod.csv --format json")]
+    Prioritize {
+        #[arg(short, long, help = "Directory of requirement documents")]
+        dir: PathBuf,
+
+        #[arg(long, help = "CSV with cost-of-delay annotations by file [default: cost-of-delay.csv in the directory]")]
+        sidecar: Option<PathBuf>,
+
+        #[arg(short, long, help = "Save the report to file")]
+        output: Option<PathBuf>,
+
+        #[arg(long, help = "Output format (markdown, html or json)", value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    #[command(about = "Benchmark AI providers and models on the same document: findings, latency and cost")]
+    #[command(long_about = "Analyze one document with every configured provider, or with the given
+providers and models, at the same time, and compare side by side what each found, how
+long it took and what it would cost - to choose a model for your domain.
+
+MODELS:
+  (none)                       The configured provider plus every provider under
+                               llm.providers with a model or api_key of its own
+  --models claude              A provider with its configured (or default) model
+  --models openai:gpt-4o-mini  A provider with a specific model; name the same
+                               provider several times to compare its models
+  Providers other than llm.provider take their API key and base URL from llm.providers
+
+RUNS:
+  --runs N     Analyze N times per model and report the median response time
+
+EXAMPLES:
+  prism bench-providers --file spec.md
+  prism bench-providers --file spec.md --models openai:gpt-4o,openai:gpt-4o-mini,ollama:llama3.1:8b
+  prism bench-providers --file spec.md --runs 3 --format json --output bench.json")]
+    BenchProviders {
+        #[arg(help = "Requirement text to benchmark with (use quotes for multi-word text)")]
+        text: Option<String>,
+
+        #[arg(short, long, help = "File to benchmark with")]
+        file: Option<PathBuf>,
+
+        #[arg(long, value_delimiter = ',', help = "Providers or provider:model pairs to compare [default: every configured provider]")]
+        models: Vec<String>,
+
+        #[arg(long, default_value = "1", help = "Analyses per model; the median response time is reported")]
+        runs: usize,
+
+        #[arg(short, long, help = "Save the comparison to file")]
+        output: Option<PathBuf>,
+
+        #[arg(long, help = "Output format (markdown or json)", value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    #[command(about = "Score the analyzer on known-bad and known-good requirements: precision and recall per rule")]
+    #[command(long_about = "Run the analyzer on a suite of requirements with known problems and of
+well-written ones, and report per rule how many findings were right (precision) and how
+many expected findings it reported (recall) - to check a new model, prompt or rule pack
+before rolling it out.
+
+RUNS:
+  The built-in and custom rules, then every configured AI provider (the configured
+  provider plus those under llm.providers with a model or api_key of their own).
+  An AI provider is expected to report every known-bad case as ai-ambiguity.
+
+SUITE:
+  A bundled suite, or your own YAML list of cases with --suite:
+    - id: vague-fast
+      text: The search page shall load fast.
+      expect: [vague-term]
+    - id: good-response-time
+      text: The search page shall return results within 2 seconds.
+  A case without expect is known-good; expect custom rule ids to test a rule pack.
+
+EXAMPLES:
+  prism selftest
+  prism selftest --rules-only
+  prism selftest --suite team_suite.yaml --format json --output selftest.json")]
+    Selftest {
+        #[arg(long, help = "YAML file of cases to run instead of the bundled suite")]
+        suite: Option<PathBuf>,
+
+        #[arg(long, help = "Score the built-in and custom rules only, without the AI providers")]
+        rules_only: bool,
+
+        #[arg(short, long, help = "Save the scores to file")]
+        output: Option<PathBuf>,
+
+        #[arg(long, help = "Output format (markdown or json)", value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    #[command(about = "Merge the JSON results of several files into one")]
+    #[command(long_about = "Combine analysis results saved with prism analyze --format json into one
+result, for CI jobs that analyze shards of a large repository in parallel.
+
+MERGED:
+  Findings and suppressed findings of every file, in the order given
+  Actors, actions and objects, without duplicates
+  Word and requirement counts summed, finding densities recomputed
+  Completeness score averaged by requirement count
+  Generated tests, NFRs and other artifacts appended; UML diagrams and user story
+  validation are dropped, as they describe a single document
+
+EXAMPLES:
+  prism merge results/*.json -o combined.json
+  prism merge shard-1.json shard-2.json --format sarif -o prism.sarif")]
+    Merge {
+        #[arg(required = true, help = "JSON results to merge")]
+        files: Vec<PathBuf>,
+
+        #[arg(short, long, help = "Save the merged result to file")]
+        output: Option<PathBuf>,
+
+        #[arg(long, help = "Output format [default: json]", value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    #[command(about = "Work with saved analysis reports")]
+    Report {
+        #[command(subcommand)]
+        action: ReportAction,
+    },
+
+    #[command(about = "Find and analyze the requirement projects of a monorepo")]
+    Projects {
+        #[command(subcommand)]
+        action: ProjectsAction,
+    },
+
+    #[command(about = "Check that every epic capability is covered by a story")]
+    #[command(long_about = "Match the capabilities stated in an epic against a directory of stories.
+
+Capabilities are the list items, user stories and requirement sentences (shall, must,
+should, can...) of the epic. Each one is compared with every story by content-word
+similarity; stories at or above the threshold cover it.
+
+REPORTS:
+  Uncovered statements   Epic capabilities no story covers
+  Orphan stories         Stories that don't cover any epic capability
+
+EXAMPLES:
+  prism coverage --epic epic.md --stories ./stories
+  prism coverage --epic epic.md --stories ./stories --threshold 0.5 --format json")]
+    Coverage {
+        #[arg(short, long, help = "Epic document listing the capabilities")]
+        epic: PathBuf,
+        
+        #[arg(short, long, help = "Directory containing the stories")]
+        stories: PathBuf,
+        
+        #[arg(long, help = "Minimum similarity (0-1) for a story to cover a capability [default: 0.35]")]
+        threshold: Option<f32>,
+        
+        #[arg(short, long, help = "Save coverage report to file")]
+        output: Option<PathBuf>,
+        
+        #[arg(long, help = "Output format (markdown or json)", value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    #[command(about = "Link requirements to the architecture decision records that cite them")]
+    #[command(long_about = "Scan a directory of architecture decision records (ADRs) for requirement IDs
+(REQ-12, US-3, ...) and link them to the requirements that define those IDs.
+
+ADRs are looked for in docs/adr, docs/decisions, doc/adr, adr and
+docs/architecture/decisions unless --adr-dir is given. Nygard-style and MADR records
+are supported; the title is the first heading and the status comes from a 'Status:'
+line or a '## Status' section.
+
+REPORTS:
+  Constrained requirements   Requirements cited by at least one ADR
+  Undriven ADRs              Active ADRs that cite no known requirement
+  Unknown references         IDs an ADR cites that no requirement document defines
+
+EXAMPLES:
+  prism adr --requirements requirements/
+  prism adr --requirements specs.md --adr-dir architecture/decisions --format json")]
+    Adr {
+        #[arg(short, long, help = "Requirement document or directory of documents")]
+        requirements: PathBuf,
+
+        #[arg(long, help = "Directory containing the ADRs [default: docs/adr or another conventional location]")]
+        adr_dir: Option<PathBuf>,
+
+        #[arg(short, long, help = "Save the report to file")]
+        output: Option<PathBuf>,
+
+        #[arg(long, help = "Output format (markdown or json)", value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    #[command(about = "Go/no-go release readiness check against configurable gates")]
+    #[command(long_about = "Combine completeness, traceability coverage, open clarification questions and Critical
+findings into a single go/no-go release readiness report. Exits with an error on no-go.
+
+GATES (set under release_gate in ~/.prism/config.yml):
+  min_completeness        Minimum completeness score (default: 70)
+  min_coverage            Minimum % of epic capabilities covered by stories (default: 80)
+  max_open_questions      Maximum TBD/TODO/open questions left in the text (default: 0)
+  max_critical_findings   Maximum Critical findings (default: 0)
+
+The coverage gate is only checked when --epic is given, against the stories in --dir.
+
+EXAMPLES:
+  prism release-check --dir ./stories --epic epic.md
+  prism release-check --file release-scope.md --format json --output readiness.json")]
+    ReleaseCheck {
+        #[arg(help = "Requirement text to check")]
+        text: Option<String>,
+        
+        #[arg(short, long, help = "Read requirements from file")]
+        file: Option<PathBuf>,
+        
+        #[arg(short, long, help = "Read stories from directory")]
+        dir: Option<PathBuf>,
+        
+        #[arg(short, long, help = "Epic document to check story coverage against (needs --dir)")]
+        epic: Option<PathBuf>,
+        
+        #[arg(short, long, help = "Save readiness report to file")]
+        output: Option<PathBuf>,
+        
+        #[arg(long, help = "Output format (markdown or json)", value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    #[command(about = "Package audit evidence for requirements into a ZIP")]
+    #[command(long_about = "Analyze requirements and package the evidence an audit asks for into one timestamped ZIP,
+with the SHA-256 of every file in MANIFEST.json and SHA256SUMS.
+
+CONTENTS:
+  requirements/          The analyzed requirements as they were
+  analysis/report.md     Analysis report (also report.json)
+  traceability/matrix.md Requirements traced to --source-dir and --test-dir (also matrix.json)
+  review/checklist.md    Findings to resolve, review questions and a sign-off table
+  history/improvements.md  Git revisions of the requirements and the quality score of a daemon --job
+
+Verify an unpacked bundle with: sha256sum -c SHA256SUMS
+With --sign the ZIP is also signed with the tool set up under signing: in the config
+(see prism sign), so it can be checked with prism verify.
+
+EXAMPLES:
+  prism evidence --file requirements.md --source-dir ./src --test-dir ./tests
+  prism evidence --dir ./requirements --job nightly --output evidence/release-2.4.zip --sign")]
+    Evidence {
+        #[arg(help = "Requirement text to package")]
+        text: Option<String>,
+
+        #[arg(short, long, help = "Read requirements from file")]
+        file: Option<PathBuf>,
+
+        #[arg(short, long, help = "Read requirements from directory")]
+        dir: Option<PathBuf>,
+
+        #[arg(long, help = "Source code directory to trace to")]
+        source_dir: Option<PathBuf>,
+
+        #[arg(long, help = "Test directory to trace to")]
+        test_dir: Option<PathBuf>,
+
+        #[arg(long, help = "Scheduled job whose quality history to include")]
+        job: Option<String>,
+
+        #[arg(short, long, help = "ZIP file to write (default: prism-evidence-<timestamp>.zip)")]
+        output: Option<PathBuf>,
+
+        #[arg(long, help = "Sign the ZIP with the configured minisign or Sigstore key")]
+        sign: bool,
+    },
+
+    #[command(about = "Sign reports and evidence bundles")]
+    #[command(long_about = "Write a detached signature next to each file, so whoever receives a report or
+evidence bundle can check it was not changed after it was generated. Signing uses
+minisign or Sigstore's cosign, which have to be installed, as set up in ~/.prism/config.yml:
+
+  signing:
+    tool: minisign                     # or sigstore
+    key: ~/.minisign/minisign.key      # cosign: key file or KMS URI; none signs keyless
+    public_key: ~/.minisign/minisign.pub
+
+SIGNATURES:
+  minisign   <file>.minisig, with the file name and signing time as trusted comment
+  sigstore   <file>.sigstore.json, a Sigstore bundle
+
+EXAMPLES:
+  prism analyze --file requirements.md --format html --output report.html
+  prism sign report.html
+  prism sign reports/*.md prism-evidence-20261016-143000.zip")]
+    Sign {
+        #[arg(required = true, help = "Files to sign")]
+        files: Vec<PathBuf>,
+    },
+
+    #[command(about = "Verify the signatures of reports and evidence bundles")]
+    #[command(long_about = "Check each file against the signature prism sign wrote next to it, using
+signing.public_key from the config (or, for keyless Sigstore signatures,
+signing.certificate_identity and signing.certificate_oidc_issuer). Exits with an error
+when a file is unsigned or was changed after signing.
+
+EXAMPLES:
+  prism verify report.html
+  prism verify prism-evidence-20261016-143000.zip")]
+    Verify {
+        #[arg(required = true, help = "Files to verify")]
+        files: Vec<PathBuf>,
+    },
+
+    #[command(about = "Run the full pipeline on bundled sample requirements")]
+    #[command(long_about = "Analyze bundled, anonymized sample requirements with every generator and write the
+results in every output format, plus the individual artifacts. Uses the built-in analysis
+only, so no AI provider or configuration is needed.
+
+OUTPUT (per sample, in its own folder):
+  <sample>.md       The sample requirements
+  report.*          One report per output format (json, md, html, github, jira, sarif...)
+  <sample>_*.md     Individual artifacts and their index, as with --save-artifacts
+  README.md         Overview linking every generated file
+
+EXAMPLES:
+  prism demo
+  prism demo --output-dir ./prism-examples")]
+    Demo {
+        #[arg(long, default_value = "prism-demo", help = "Directory to write the demo reports to")]
+        output_dir: PathBuf,
+    },
+
+    #[command(about = "Analyze requirement pages from a Notion database")]
+    #[command(long_about = "Pull the pages tagged as requirements from a Notion database, convert their blocks to
+markdown and analyze each page. Needs a Notion integration token (notion.token in
+~/.prism/config.yml or NOTION_TOKEN) with access to the database.
+
+WHICH PAGES:
+  --database <ID|URL>  Database to query (default: notion.database_id)
+  --tag <TAG>          Only pages with this value in notion.tag_property (default: notion.tag)
+  --all-pages          Every page of the database, tagged or not
+  --page <ID|URL>      Analyze these pages instead of querying a database
+
+WRITE-BACK:
+  When notion.summary_property is set, a one-line result such as
+  \"PRISM: 3 findings (1 High), 4.2 per 100 words\" is written to that rich-text
+  property of each page. --dry-run skips this.
+
+EXAMPLES:
+  prism notion --database https://www.notion.so/acme/3f2a9c...
+  prism notion --tag Epic --output-dir ./notion-reports --format html
+  prism notion --page 9b1e47d2c0f64a8e8f3e2a1b5c6d7e8f --dry-run")]
+    Notion {
+        #[arg(long, help = "Notion database ID or URL to pull requirement pages from")]
+        database: Option<String>,
+
+        #[arg(long, help = "Notion page ID or URL to analyze instead of querying a database (repeatable)")]
+        page: Vec<String>,
+
+        #[arg(long, conflicts_with = "all_pages", help = "Only analyze pages with this tag")]
+        tag: Option<String>,
+
+        #[arg(long, help = "Analyze every page of the database, not only those tagged as requirements")]
+        all_pages: bool,
+
+        #[arg(long, help = "Write one report per page plus an index.md to this directory")]
+        output_dir: Option<PathBuf>,
+
+        #[arg(long, help = "Report format for --output-dir (default: markdown)", value_enum)]
+        format: Option<OutputFormat>,
+
+        #[arg(long, help = "Do not write the summary back to Notion")]
+        dry_run: bool,
+    },
+
+    #[command(about = "Pull requirements from Polarion, Jama Connect or DOORS Next and score them")]
+    #[command(long_about = "Pull requirements straight from a requirements management tool's REST API, analyze
+each one and report its findings and 0-100 quality score - no manual export needed.
+
+Connections are named entries under rm_tools in ~/.prism/config.yml:
+
+  rm_tools:
+    polarion:
+      tool: polarion            # polarion, jama or doors-next
+      base_url: https://polarion.example.com
+      project: CHECKOUT
+      token: ...                # or PRISM_POLARION_TOKEN; username/password for basic auth
+    dng:
+      tool: doors-next
+      base_url: https://jazz.example.com/rm
+      endpoint: https://jazz.example.com/rm/views?oslc.query=true&projectURL=...
+      fields:                   # JSON pointers into the tool's response
+        text: /dcterms:description
+
+EXAMPLES:
+  prism import polarion
+  prism import jama --project 42 --limit 100
+  prism import dng --output-dir ./dng-reports --format html")]
+    Import {
+        #[arg(help = "Name of the connection under rm_tools in ~/.prism/config.yml")]
+        connection: String,
+
+        #[arg(long, help = "Project to pull requirements from, instead of the connection's project")]
+        project: Option<String>,
+
+        #[arg(long, help = "Pull at most this many requirements")]
+        limit: Option<usize>,
+
+        #[arg(long, help = "Write one report per requirement plus an index.md to this directory")]
+        output_dir: Option<PathBuf>,
+
+        #[arg(long, help = "Report format for --output-dir (default: markdown)", value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    #[command(about = "Run configured analyses on a schedule and notify on quality regressions")]
+    #[command(long_about = "Keep running and analyze the requirements of each job under daemon in
+~/.prism/config.yml on its cron schedule. Every run's quality score and finding counts are
+recorded in the analysis history (~/.prism/history.jsonl), and a notification is posted to
+daemon.webhook_url when a run is worse than the previous one beyond the thresholds.
+
+  daemon:
+    webhook_url: https://hooks.slack.com/services/...
+    thresholds:
+      max_score_drop: 5         # quality score points
+      max_new_findings: 5
+    jobs:
+      - name: checkout
+        schedule: \"0 6 * * 1-5\"   # minute hour day month weekday
+        path: ./projects/checkout/requirements
+        output_dir: ./reports/checkout
+    metrics_bind: 0.0.0.0:9187       # optional: Prometheus metrics at /metrics
+
+EXAMPLES:
+  prism daemon
+  prism daemon --job checkout
+  prism daemon --once             # run every job now, e.g. from an existing scheduler")]
+    Daemon {
+        #[arg(long, help = "Only run this job (repeatable)")]
+        job: Vec<String>,
+
+        #[arg(long, help = "Run the jobs once now and exit instead of waiting for their schedules")]
+        once: bool,
+    },
+
+    #[command(about = "Serve analyses over HTTP, with a webhook for GitHub and GitLab pushes")]
+    #[command(long_about = "Run PRISM as a service. Settings are under server in ~/.prism/config.yml.
+
+ENDPOINTS:
+  GET  /health     Liveness check
+  POST /analyze    Requirement text as the body (or {\"text\": \"...\"}); returns the analysis as JSON
+  GET  /usage      Requests, rejections and LLM usage of the calling API key
+  GET  /metrics    Prometheus metrics: analyses, LLM latency, failures, token usage
+  POST /webhook    GitHub or GitLab push events: the changed requirement files are
+                   analyzed and the results commented on the pushed commit
+
+API KEYS:
+  server:
+    api_keys:                # /analyze and /usage then need \"Authorization: Bearer <key>\"
+      - name: payments
+        key: ...
+        requests_per_minute: 30
+        requests_per_day: 2000
+
+WEBHOOK SETUP:
+  server:
+    webhook:
+      secret: ...            # or PRISM_WEBHOOK_SECRET; the same secret as on the webhook
+      paths: [docs/requirements]
+      github_token: ...      # or GITHUB_TOKEN; gitlab_token / GITLAB_TOKEN for GitLab
+
+  GitHub: Settings > Webhooks, content type application/json, \"Just the push event\"
+  GitLab: Settings > Webhooks, secret token, \"Push events\"
+
+EXAMPLES:
+  prism serve
+  prism serve --bind 0.0.0.0:8787")]
+    Serve {
+        #[arg(long, help = "Address to listen on (default: server.bind, 127.0.0.1:8787)")]
+        bind: Option<String>,
+    },
+
+    #[command(about = "Show or clear the cache of AI analysis results")]
+    #[command(long_about = "AI analyses are cached in ~/.prism/cache, keyed by the requirement text, provider,
+model and analysis options, so re-running prism analyze on unchanged documents costs nothing.
+
+EXAMPLES:
+  prism cache info
+  prism cache clear")]
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+
+    #[command(about = "Accept the current findings so that only new ones are reported")]
+    #[command(long_about = "Write the fingerprints of the current findings to .prismignore-findings. Commands
+that analyze requirements in that directory or below it leave these findings out of
+their reports and quality gates, so CI only fails on new findings. Fingerprints
+survive edits elsewhere in the document; delete a line to report its finding again.
+
+EXAMPLES:
+  prism baseline create --dir ./requirements
+  prism baseline create --file srs.md --output docs/.prismignore-findings")]
+    Baseline {
+        #[command(subcommand)]
+        action: BaselineAction,
+    },
+
     #[command(about = "Setup and manage AI configuration")]
     #[command(long_about = "Configure PRISM for AI-powered analysis. This tool is designed to work with AI providers for enhanced analysis.
 
@@ -241,6 +1035,9 @@ SUPPORTED AI PROVIDERS:
   • Anthropic Claude (claude-3-opus, claude-3-sonnet, claude-3-haiku)
   • Azure OpenAI
   • Local Ollama (llama2, codellama, mistral, etc.)
+  • OpenAI-compatible endpoints (LiteLLM, OpenRouter, Groq, Together, vLLM, etc.)
+  • Anthropic-compatible endpoints
+  • Mock - canned responses for tests and rule development (llm.mock_responses)
 
 QUICK SETUP:
   prism config --setup            # Interactive setup wizard
@@ -253,8 +1050,12 @@ MANUAL SETUP:
   prism config --api-key \"your-key\" --model \"gpt-4\" --provider openai
   prism config --api-key \"your-key\" --model \"gemini-1.5-pro\" --provider gemini
   prism config --api-key \"your-key\" --model \"claude-3-sonnet\" --provider claude
+  prism config --provider openai-compatible --base-url https://openrouter.ai/api/v1 \\
+    --api-key \"your-key\" --model \"meta-llama/llama-3.1-70b-instruct\" --header \"HTTP-Referer: https://acme.dev\"
 
-CONFIGURATION FILE: ~/.prism/config.yml")]
+CONFIGURATION FILE: ~/.prism/config.yml
+  API keys set here are kept in the OS keyring (Keychain, Secret Service, Credential
+  Manager) where one is available; move a key already in the file with --migrate-secrets")]
     Config {
         #[arg(short, long, help = "Set API key for your chosen AI provider")]
         api_key: Option<String>,
@@ -283,43 +1084,242 @@ CONFIGURATION FILE: ~/.prism/config.yml")]
         #[arg(long, help = "Test all configured AI providers")]
         test_providers: bool,
         
-        #[arg(long, help = "Set custom template directory")]
+        #[arg(long, help = "Set the template directory whose prompts folder replaces the built-in AI prompts")]
         set_template_dir: Option<PathBuf>,
+
+        #[arg(long, help = "Write the built-in AI prompts to the template directory's prompts folder for editing")]
+        init_prompts: bool,
+        
+        #[arg(long, value_name = "true|false", help = "Turn the end-of-run hints on or off")]
+        hints: Option<bool>,
+
+        #[arg(long, help = "Move the API key from the config file into the OS keyring")]
+        migrate_secrets: bool,
+
+        #[arg(long, help = "Set the endpoint URL, for azure and the openai-compatible and anthropic-compatible providers")]
+        base_url: Option<String>,
+
+        #[arg(long, value_name = "NAME: VALUE", help = "Send an extra HTTP header with every LLM request (repeatable); an empty value removes it")]
+        header: Vec<String>,
     },
 }
 
-#[derive(clap::ValueEnum, Clone, Debug)]
+#[derive(Subcommand, Clone, Debug)]
+pub enum BaselineAction {
+    #[command(about = "Write the current findings to .prismignore-findings")]
+    Create {
+        #[arg(help = "Requirement text to analyze")]
+        text: Option<String>,
+
+        #[arg(short, long, help = "File to analyze")]
+        file: Option<PathBuf>,
+
+        #[arg(short, long, help = "Directory to analyze, file by file")]
+        dir: Option<PathBuf>,
+
+        #[arg(short, long, help = "Where to write the baseline (default: .prismignore-findings in the current directory)")]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum CacheAction {
+    #[command(about = "Show where the cache is and how much it holds")]
+    Info,
+    #[command(about = "Remove every cached analysis")]
+    Clear,
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum ReportAction {
+    #[command(about = "Compare two saved JSON reports: findings added and resolved, score and entity changes")]
+    #[command(long_about = "Compare two analysis results saved with prism analyze --format json, for instance
+from two releases, and summarize what changed. No git history is needed.
+
+FINDINGS:
+  New         Findings only the new report has
+  Resolved    Findings of the old report that are gone
+  Severity    Findings in both whose severity changed
+  Findings are matched by fingerprint, then by rule and text, so a requirement moved
+  to another section keeps its findings
+
+SCORES AND ENTITIES:
+  Quality score, finding and requirement counts and completeness, old against new,
+  plus the actors, actions and objects that were added or removed
+
+EXAMPLES:
+  prism analyze --file requirements.md --format json --output v1.json
+  prism report diff v1.json v2.json
+  prism report diff v1.json v2.json --format json --output diff.json")]
+    Diff {
+        #[arg(help = "The earlier JSON report")]
+        old: PathBuf,
+
+        #[arg(help = "The later JSON report")]
+        new: PathBuf,
+
+        #[arg(short, long, help = "Save the comparison to file")]
+        output: Option<PathBuf>,
+
+        #[arg(long, help = "Output format (markdown or json)", value_enum)]
+        format: Option<OutputFormat>,
+    },
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum ProjectsAction {
+    #[command(about = "List the projects with requirements, and with --analyze score each one")]
+    #[command(long_about = "Find the requirement roots of a monorepo and, with --analyze, analyze each project with
+its own settings and report per-project quality scores with a combined summary.
+
+DISCOVERY:
+  .prism.yml        A directory with a .prism.yml is a project. It can set a name, its
+                    requirement directories and rule settings for the project
+  Convention        Otherwise a directory with requirements/, docs/requirements/, specs/
+                    or stories/ is a project (see projects.conventions in the config)
+
+EXAMPLES:
+  prism projects discover
+  prism projects discover ./monorepo --analyze
+  prism projects discover --analyze --format json --output projects.json")]
+    Discover {
+        #[arg(default_value = ".", help = "Repository to search")]
+        path: PathBuf,
+
+        #[arg(long, help = "Analyze every project and report per-project scores")]
+        analyze: bool,
+
+        #[arg(short, long, help = "Save the report to file")]
+        output: Option<PathBuf>,
+
+        #[arg(long, help = "Output format (markdown or json)", value_enum)]
+        format: Option<OutputFormat>,
+    },
+}
+
+/// Architecture the generated pseudocode is laid out in.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArchitectureStyle {
+    /// Controllers, services and repositories
+    Layered,
+    /// Use-case ports, application services and adapters
+    Hexagonal,
+    /// Models, views and controllers
+    Mvc,
+    /// Commands, events, handlers and an event bus
+    EventDriven,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum OutputFormat {
     Json,
     Markdown,
     Jira,
+    JiraAdf,
     Github,
     Plain,
+    Sarif,
+    Html,
+    Annotated,
+    Diagnostics,
+    Pdf,
+    Csv,
 }
 
-#[derive(clap::ValueEnum, Clone, Debug)]
+impl OutputFormat {
+    /// Whether the report is a document for another program (a parser, a
+    /// spreadsheet, a viewer) rather than text for a person to read, so
+    /// nothing else may be printed to stdout along with it.
+    pub fn is_machine_readable(&self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Json
+                | OutputFormat::JiraAdf
+                | OutputFormat::Sarif
+                | OutputFormat::Diagnostics
+                | OutputFormat::Html
+                | OutputFormat::Pdf
+                | OutputFormat::Csv
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum AnalysisPreset {
     Basic,
     Standard,
     Full,
     Report,
+    /// A preset defined under `presets` in the config file
+    Custom(String),
 }
 
-#[derive(clap::ValueEnum, Clone, Debug)]
+impl FromStr for AnalysisPreset {
+    type Err = Infallible;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(match name.to_lowercase().as_str() {
+            "basic" => AnalysisPreset::Basic,
+            "standard" => AnalysisPreset::Standard,
+            "full" => AnalysisPreset::Full,
+            "report" => AnalysisPreset::Report,
+            _ => AnalysisPreset::Custom(name.to_string()),
+        })
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum GenerateOptions {
     All,
     Uml,
     Pseudo,
     Tests,
+    Gherkin,
     Improve,
     Nfr,
+    Permissions,
+    Suggestions,
+    Dependencies,
+    Reviewers,
+    DomainModel,
+    Smart,
+    EventStorming,
+    LoadTests,
+    SecurityTests,
 }
 
 #[derive(clap::ValueEnum, Clone, Debug)]
 pub enum AiProvider {
+    #[value(name = "openai", alias = "open-ai")]
     OpenAI,
     Gemini,
     Azure,
     Claude,
     Ollama,
+    /// A gateway or server with the OpenAI chat completions API
+    #[value(name = "openai-compatible")]
+    OpenAiCompatible,
+    /// A gateway or server with the Anthropic messages API
+    AnthropicCompatible,
+    /// Canned responses, without network or API key
+    Mock,
+}
+
+impl AiProvider {
+    /// Provider name as stored in `llm.provider`
+    pub fn name(&self) -> &'static str {
+        match self {
+            AiProvider::OpenAI => "openai",
+            AiProvider::Gemini => "gemini",
+            AiProvider::Azure => "azure",
+            AiProvider::Claude => "claude",
+            AiProvider::Ollama => "ollama",
+            AiProvider::OpenAiCompatible => "openai-compatible",
+            AiProvider::AnthropicCompatible => "anthropic-compatible",
+            AiProvider::Mock => "mock",
+        }
+    }
 }
\ No newline at end of file
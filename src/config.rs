@@ -1,33 +1,865 @@
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use tokio::fs;
 
+use crate::analyzer::AmbiguitySeverity;
+use crate::cli::{ArchitectureStyle, GenerateOptions, OutputFormat};
+use crate::paths;
+use crate::plantuml::ImageFormat;
+use crate::rm_tools::RmTool;
+use crate::secrets;
+use crate::signing::SigningTool;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub llm: LlmConfig,
     pub analysis: AnalysisConfig,
+    #[serde(default)]
+    pub release_gate: ReleaseGateConfig,
+    /// Custom presets selectable with `--preset <name>`, next to the built-in ones
+    #[serde(default)]
+    pub presets: BTreeMap<String, PresetConfig>,
+    #[serde(default)]
+    pub notion: NotionConfig,
+    /// Requirements management tool connections for `prism import <name>`
+    #[serde(default)]
+    pub rm_tools: BTreeMap<String, RmConnection>,
+    #[serde(default)]
+    pub daemon: DaemonConfig,
+    #[serde(default)]
+    pub server: ServerConfig,
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+    #[serde(default)]
+    pub plantuml: PlantUmlConfig,
+    #[serde(default)]
+    pub projects: ProjectsConfig,
+    #[serde(default)]
+    pub review: ReviewConfig,
+    #[serde(default)]
+    pub output: OutputConfig,
+    #[serde(default)]
+    pub templates: TemplateConfig,
+    #[serde(default)]
+    pub signing: SigningConfig,
+}
+
+/// Defaults for what commands print or write
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputConfig {
+    /// Output format used when neither `--format` nor the preset sets one
+    pub format: Option<OutputFormat>,
+    /// Locale of the numbers, percentages and dates in reports, e.g. "de-DE"
+    pub locale: Option<String>,
+    /// chrono format of dates in reports, e.g. "%d %B %Y", instead of the locale's
+    pub date_format: Option<String>,
+    /// Number the sections and findings of Markdown and HTML reports and
+    /// add a table of contents, as `--toc` does
+    pub toc: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PresetConfig {
+    /// Artifacts to generate, as accepted by `--generate`
+    pub generate: Vec<GenerateOptions>,
+    /// Output format used when `--format` is not given
+    pub format: Option<OutputFormat>,
+    /// Findings below this severity are left out of the report
+    pub min_severity: Option<AmbiguitySeverity>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LlmConfig {
     pub api_key: Option<String>,
+    /// The API key is kept in the OS keyring instead of in this file
+    #[serde(default)]
+    pub keyring: bool,
     pub model: String,
     #[serde(default = "default_provider")]
     pub provider: String,
     pub base_url: Option<String>,
+    /// Seconds an LLM request may take; 0 waits as long as the provider does
     pub timeout: u64,
+    /// Retries of rate-limited and temporarily unavailable requests
+    #[serde(default)]
+    pub retry: RetryConfig,
+    /// Sampling temperature; low values keep the analysis repeatable
+    #[serde(default = "default_temperature")]
+    pub temperature: f32,
+    /// Longest response, in tokens
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: u32,
+    /// Persona and terminology every request starts with, instead of the
+    /// built-in requirements analyst
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    /// Temperature, response length and system prompt of single providers,
+    /// by provider name, for when they differ from the ones above
+    #[serde(default)]
+    pub providers: BTreeMap<String, ProviderSettings>,
+    /// Extra HTTP headers sent with every LLM request, such as OpenRouter's
+    /// `HTTP-Referer` or a gateway's tenant header
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// YAML file of the canned responses of the `mock` provider; without one
+    /// it answers every AI task with an empty result
+    #[serde(default)]
+    pub mock_responses: Option<PathBuf>,
+    /// Keep-alive, context window and model pulling of the Ollama provider
+    #[serde(default)]
+    pub ollama: OllamaConfig,
+    /// How documents too long for one request are split up
+    #[serde(default)]
+    pub chunking: ChunkingConfig,
+}
+
+/// Documents longer than `max_chars` are analyzed by the LLM in chunks,
+/// split at headings or paragraphs, several at a time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChunkingConfig {
+    /// Longest chunk in characters, about a quarter as many tokens; 0 sends
+    /// every document whole
+    pub max_chars: usize,
+    /// Chunks analyzed at the same time
+    pub concurrency: usize,
+}
+
+impl Default for ChunkingConfig {
+    fn default() -> Self {
+        Self { max_chars: 16_000, concurrency: 4 }
+    }
+}
+
+/// Settings of the Ollama provider
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OllamaConfig {
+    /// How long the model stays loaded after a request; Ollama's default of
+    /// five minutes when unset
+    pub keep_alive: Option<KeepAlive>,
+    /// Context window in tokens, for documents longer than the model's default
+    pub num_ctx: Option<u32>,
+    /// Pull a missing model without asking, e.g. in containers and CI
+    pub auto_pull: bool,
+}
+
+/// `llm.ollama.keep_alive`: seconds, a negative number to keep the model
+/// loaded, or a duration such as "10m"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeepAlive {
+    Seconds(i64),
+    Duration(String),
+}
+
+/// Settings of one provider in `llm.providers`; unset ones are taken from `llm`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProviderSettings {
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    pub system_prompt: Option<String>,
+    /// Model, API key and endpoint of a provider other than `llm.provider`,
+    /// for `prism analyze --compare-providers`
+    pub model: Option<String>,
+    pub api_key: Option<String>,
+    pub base_url: Option<String>,
+}
+
+/// What LLM requests are sent with for the configured provider
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GenerationSettings {
+    pub temperature: f32,
+    pub max_tokens: u32,
+    pub system_prompt: String,
+}
+
+pub const DEFAULT_SYSTEM_PROMPT: &str = "You are an expert software requirements analyst. Provide detailed, accurate analysis in the requested JSON format.";
+
+impl LlmConfig {
+    /// The settings of `llm.providers` for the configured provider, falling
+    /// back to those of `llm`.
+    pub fn generation(&self) -> GenerationSettings {
+        let provider = self.providers.get(&self.provider).cloned().unwrap_or_default();
+        GenerationSettings {
+            temperature: provider.temperature.unwrap_or(self.temperature),
+            max_tokens: provider.max_tokens.unwrap_or(self.max_tokens),
+            system_prompt: provider.system_prompt
+                .or_else(|| self.system_prompt.clone())
+                .filter(|p| !p.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_SYSTEM_PROMPT.to_string()),
+        }
+    }
+}
+
+/// How LLM requests answered with 429, 502, 503 or 504 are retried
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// Attempts per request, including the first; 1 disables retries
+    pub max_attempts: u32,
+    /// Wait before the first retry, doubled for every further retry
+    pub initial_backoff_ms: u64,
+    /// Longest wait between attempts, also for a provider's `Retry-After`
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_backoff_ms: 1000,
+            max_backoff_ms: 30_000,
+        }
+    }
 }
 
 fn default_provider() -> String {
     "none".to_string()
 }
 
+fn default_temperature() -> f32 {
+    0.1
+}
+
+fn default_max_tokens() -> u32 {
+    2000
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalysisConfig {
-    pub custom_rules: Vec<String>,
+    /// Finding rules of the team's own, merged with those of the rules files
+    #[serde(default)]
+    pub custom_rules: Vec<CustomRule>,
     pub ambiguity_threshold: f32,
     pub enable_interactive: bool,
+    /// Severity to report for a rule instead of its built-in one, keyed by rule id
+    #[serde(default)]
+    pub severity_overrides: BTreeMap<String, AmbiguitySeverity>,
+    /// Rule ids whose findings are never reported
+    #[serde(default)]
+    pub disabled_rules: Vec<String>,
+    /// Print "what you could also do" hints at the end of a run
+    #[serde(default = "default_show_hints")]
+    pub show_hints: bool,
+    /// Owners file for the by-team breakdown of `--dir` batches, in CODEOWNERS
+    /// syntax; the repository's CODEOWNERS is used when unset
+    #[serde(default)]
+    pub owners_file: Option<PathBuf>,
+    /// Similarity (0-1) from which statements in different files of a `--dir`
+    /// batch are reported as duplicates
+    #[serde(default = "default_duplicate_threshold")]
+    pub duplicate_threshold: f32,
+    /// Architecture generated pseudocode is laid out in when `--arch` is not given
+    #[serde(default)]
+    pub architecture: Option<ArchitectureStyle>,
+    /// Rules file read after ~/.prism/rules.yml and the project's .prism/rules.yml
+    #[serde(default)]
+    pub rules_file: Option<PathBuf>,
+    /// Globs of files `--dir` batches skip, in .gitignore syntax relative to
+    /// the directory analyzed
+    #[serde(default)]
+    pub ignore: Vec<String>,
+}
+
+/// A finding rule of the team's own, reported next to the built-in vague-term
+/// and passive-voice rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomRule {
+    /// Rule id the findings are reported under, also for `disabled_rules`
+    /// and `severity_overrides`
+    pub id: String,
+    /// Regular expression a finding matches
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// Words or phrases a finding matches as whole words, on top of `pattern`
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    #[serde(default = "default_rule_severity")]
+    pub severity: AmbiguitySeverity,
+    /// Why a match is a problem
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub suggestions: Vec<String>,
+    /// Match letter case exactly; rules ignore case by default
+    #[serde(default)]
+    pub case_sensitive: bool,
+}
+
+fn default_rule_severity() -> AmbiguitySeverity {
+    AmbiguitySeverity::Medium
+}
+
+fn default_show_hints() -> bool {
+    true
+}
+
+fn default_duplicate_threshold() -> f32 {
+    crate::duplicates::DEFAULT_DUPLICATE_THRESHOLD
+}
+
+/// Criteria `prism release-check` requires for a go decision
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReleaseGateConfig {
+    /// Minimum completeness score (0-100)
+    pub min_completeness: f32,
+    /// Minimum percentage of epic capabilities covered by stories
+    pub min_coverage: f32,
+    pub max_open_questions: usize,
+    pub max_critical_findings: usize,
+}
+
+impl Default for ReleaseGateConfig {
+    fn default() -> Self {
+        Self {
+            min_completeness: 70.0,
+            min_coverage: 80.0,
+            max_open_questions: 0,
+            max_critical_findings: 0,
+        }
+    }
+}
+
+/// Where `prism notion` pulls requirement pages from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotionConfig {
+    /// Integration token; `NOTION_TOKEN` is used when unset
+    pub token: Option<String>,
+    /// Database queried when `--database` is not given
+    pub database_id: Option<String>,
+    /// Select, multi-select or status property that marks requirement pages
+    pub tag_property: String,
+    pub tag: String,
+    /// Rich-text property the analysis summary is written to; nothing is written back when unset
+    pub summary_property: Option<String>,
+}
+
+impl Default for NotionConfig {
+    fn default() -> Self {
+        Self {
+            token: None,
+            database_id: None,
+            tag_property: "Tags".to_string(),
+            tag: "Requirement".to_string(),
+            summary_property: None,
+        }
+    }
+}
+
+impl NotionConfig {
+    pub fn token(&self) -> Option<String> {
+        self.token.clone().or_else(|| std::env::var("NOTION_TOKEN").ok()).filter(|t| !t.trim().is_empty())
+    }
+}
+
+/// A requirements management tool `prism import` pulls requirements from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RmConnection {
+    pub tool: RmTool,
+    /// Server URL, e.g. https://polarion.example.com
+    pub base_url: String,
+    /// Project pulled when `--project` is not given
+    #[serde(default)]
+    pub project: Option<String>,
+    /// Bearer token; `PRISM_<NAME>_TOKEN` is used when unset
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Basic authentication, used when there is no token
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Item list path or URL instead of the tool's default; `{project}` is replaced
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// Query parameters added to the item list request, replacing defaults of the same name
+    #[serde(default)]
+    pub query: BTreeMap<String, String>,
+    #[serde(default)]
+    pub fields: FieldMapping,
+}
+
+impl RmConnection {
+    pub fn token(&self, name: &str) -> Option<String> {
+        let variable = format!("PRISM_{}_TOKEN", name.to_uppercase().replace(|c: char| !c.is_ascii_alphanumeric(), "_"));
+        self.token.clone().or_else(|| std::env::var(variable).ok()).filter(|t| !t.trim().is_empty())
+    }
+}
+
+/// JSON pointers overriding where a tool's response keeps the item list and
+/// each item's id, title and text
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FieldMapping {
+    pub items: Option<String>,
+    pub id: Option<String>,
+    pub title: Option<String>,
+    pub text: Option<String>,
+}
+
+/// Analyses `prism daemon` runs on a schedule
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DaemonConfig {
+    pub jobs: Vec<ScheduledAnalysis>,
+    /// Where run results are recorded; ~/.prism/history.jsonl when unset
+    pub history_path: Option<PathBuf>,
+    /// Receives a `{"text": ...}` POST when a run regresses (Slack, Teams, Mattermost...)
+    pub webhook_url: Option<String>,
+    pub thresholds: RegressionThresholds,
+    /// Address to serve Prometheus metrics on at `/metrics`, e.g. "0.0.0.0:9187"
+    pub metrics_bind: Option<String>,
+}
+
+/// A file or directory of requirements analyzed on a cron schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledAnalysis {
+    pub name: String,
+    /// Cron expression (minute hour day month weekday), e.g. "0 6 * * 1-5"
+    pub schedule: String,
+    pub path: PathBuf,
+    /// Reports of the latest run are written here when set
+    #[serde(default)]
+    pub output_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub format: Option<OutputFormat>,
+    /// Thresholds for this job instead of `daemon.thresholds`
+    #[serde(default)]
+    pub thresholds: Option<RegressionThresholds>,
+}
+
+/// How much worse than the previous run a scheduled run may get before a
+/// notification is sent. More critical findings always notify.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RegressionThresholds {
+    /// Quality score points (0-100) the score may drop
+    pub max_score_drop: f32,
+    pub max_new_findings: usize,
+    /// Notify when the score falls below this
+    pub min_score: Option<f32>,
+}
+
+impl Default for RegressionThresholds {
+    fn default() -> Self {
+        Self {
+            max_score_drop: 5.0,
+            max_new_findings: 5,
+            min_score: None,
+        }
+    }
+}
+
+/// Settings of `prism serve`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    /// Address to listen on when `--bind` is not given
+    pub bind: String,
+    /// Clients of `/analyze` must send one of these keys; anyone may call it when empty
+    pub api_keys: Vec<ApiKeyConfig>,
+    /// Appends one JSON line per keyed request (key, endpoint, status, LLM usage)
+    pub usage_log: Option<PathBuf>,
+    pub webhook: WebhookConfig,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            bind: "127.0.0.1:8787".to_string(),
+            api_keys: vec![],
+            usage_log: None,
+            webhook: WebhookConfig::default(),
+        }
+    }
+}
+
+/// A client of `prism serve`, e.g. one team
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiKeyConfig {
+    /// Shown in usage reports and logs instead of the key
+    pub name: String,
+    /// Sent as `Authorization: Bearer <key>` or `X-API-Key: <key>`
+    pub key: String,
+    /// Requests allowed in any 60 seconds; unlimited when unset
+    pub requests_per_minute: Option<u32>,
+    /// Requests allowed per calendar day (local time); unlimited when unset
+    pub requests_per_day: Option<u32>,
+}
+
+/// How `/webhook` handles GitHub and GitLab push events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookConfig {
+    /// Secret configured on the webhook; `PRISM_WEBHOOK_SECRET` is used when
+    /// unset. Events without a matching signature or token are rejected.
+    pub secret: Option<String>,
+    /// Only changed files under these paths are analyzed, e.g. "docs/requirements";
+    /// every supported file when empty
+    pub paths: Vec<String>,
+    /// Reads files and comments on commits; `GITHUB_TOKEN` is used when unset
+    pub github_token: Option<String>,
+    pub github_api_url: String,
+    /// `GITLAB_TOKEN` is used when unset
+    pub gitlab_token: Option<String>,
+    /// GitLab API URL, e.g. https://gitlab.example.com/api/v4 for a
+    /// self-managed instance. Never taken from the event, which anyone can
+    /// send, so the token only goes to this server.
+    pub gitlab_api_url: String,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            secret: None,
+            paths: vec![],
+            github_token: None,
+            github_api_url: "https://api.github.com".to_string(),
+            gitlab_token: None,
+            gitlab_api_url: "https://gitlab.com/api/v4".to_string(),
+        }
+    }
+}
+
+impl WebhookConfig {
+    pub fn secret(&self) -> Option<String> {
+        env_fallback(&self.secret, "PRISM_WEBHOOK_SECRET")
+    }
+
+    pub fn github_token(&self) -> Option<String> {
+        env_fallback(&self.github_token, "GITHUB_TOKEN")
+    }
+
+    pub fn gitlab_token(&self) -> Option<String> {
+        env_fallback(&self.gitlab_token, "GITLAB_TOKEN")
+    }
+}
+
+/// Rendering of the UML artifacts saved with `--save-artifacts` to images
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlantUmlConfig {
+    /// PlantUML server, e.g. https://www.plantuml.com/plantuml or a self-hosted one.
+    /// Diagrams are sent to it, so prefer your own server for private requirements.
+    pub server_url: Option<String>,
+    /// Local plantuml.jar, run with `java`; used instead of the server when set
+    pub jar: Option<PathBuf>,
+    /// Image formats written next to the `.puml` file
+    pub formats: Vec<ImageFormat>,
+}
+
+impl Default for PlantUmlConfig {
+    fn default() -> Self {
+        Self {
+            server_url: None,
+            jar: None,
+            formats: vec![ImageFormat::Svg],
+        }
+    }
+}
+
+/// Signing of reports and evidence bundles for `prism sign`, `prism verify`
+/// and `prism evidence --sign`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SigningConfig {
+    /// `minisign` or `sigstore` (cosign); nothing is signed until it is set
+    pub tool: Option<SigningTool>,
+    /// minisign secret key file, or cosign key reference (file or KMS URI).
+    /// Without one minisign uses ~/.minisign/minisign.key and cosign signs
+    /// keyless through a Sigstore OIDC login.
+    pub key: Option<String>,
+    /// Public key signatures are verified against
+    pub public_key: Option<String>,
+    /// Signer identity, e.g. an email address or CI workflow URL, that
+    /// keyless Sigstore signatures must carry
+    pub certificate_identity: Option<String>,
+    /// OIDC issuer of that identity, e.g. https://token.actions.githubusercontent.com
+    pub certificate_oidc_issuer: Option<String>,
+}
+
+/// Project discovery in monorepos for `prism projects discover`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectsConfig {
+    /// Directory names that hold requirements; their parent directory is a project
+    pub conventions: Vec<String>,
+}
+
+impl Default for ProjectsConfig {
+    fn default() -> Self {
+        Self {
+            conventions: ["requirements", "docs/requirements", "specs", "stories"].iter().map(|c| c.to_string()).collect(),
+        }
+    }
+}
+
+/// Reviewer suggestions for `--generate reviewers`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReviewConfig {
+    /// Reviewers by finding category, e.g. `security: [Security team]`. The
+    /// categories are NFR categories (`performance`, `security`, ...), the
+    /// completeness gaps (`acceptance-criteria`, `actor-definition`,
+    /// `non-functional-requirements`), `privacy`, `clarity` for ambiguity
+    /// findings, and finding rule ids such as `passive-voice`.
+    pub reviewers: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for ReviewConfig {
+    fn default() -> Self {
+        let reviewers = [
+            ("security", "Security team"),
+            ("privacy", "Privacy/Legal"),
+            ("performance", "SRE"),
+            ("reliability", "SRE"),
+            ("scalability", "SRE"),
+            ("usability", "UX"),
+            ("accessibility", "UX"),
+            ("acceptance-criteria", "QA"),
+            ("actor-definition", "Product owner"),
+            ("clarity", "Product owner"),
+        ];
+        Self {
+            reviewers: reviewers.iter().map(|(category, reviewer)| (category.to_string(), vec![reviewer.to_string()])).collect(),
+        }
+    }
+}
+
+/// A project's own `.prism.yml`, which marks its directory as a project root
+/// in a monorepo and overrides the global configuration for everything run
+/// on files below it. API keys stay in the global configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectConfig {
+    /// Name shown in reports; the directory's path when unset
+    pub name: Option<String>,
+    /// Requirement directories relative to the project root; the conventional
+    /// ones, or the whole project, when empty
+    pub requirements: Vec<PathBuf>,
+    /// Rule ids never reported for this project, on top of the global ones
+    pub disabled_rules: Vec<String>,
+    /// Severity overrides for this project, on top of the global ones
+    pub severity_overrides: BTreeMap<String, AmbiguitySeverity>,
+    /// Findings below this severity are left out for this project
+    pub min_severity: Option<AmbiguitySeverity>,
+    /// Reviewers by finding category for this project, replacing the global
+    /// reviewers of the categories it lists
+    pub reviewers: BTreeMap<String, Vec<String>>,
+    /// AI provider for this project, e.g. ollama for confidential documents
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    /// Presets for this project, replacing global presets of the same name
+    pub presets: BTreeMap<String, PresetConfig>,
+    /// Output format used when neither `--format` nor the preset sets one
+    pub format: Option<OutputFormat>,
+    /// Rules of this project, replacing global rules with the same id
+    pub custom_rules: Vec<CustomRule>,
+    /// Globs of files `--dir` batches skip, on top of the global ones
+    pub ignore: Vec<String>,
+}
+
+impl ProjectConfig {
+    /// Layers this project's settings over `config`.
+    pub fn apply_to(&self, config: &mut Config) {
+        for rule in &self.disabled_rules {
+            if !config.analysis.disabled_rules.contains(rule) {
+                config.analysis.disabled_rules.push(rule.clone());
+            }
+        }
+        config.analysis.severity_overrides.extend(self.severity_overrides.clone());
+        config.review.reviewers.extend(self.reviewers.clone());
+        if let Some(provider) = &self.provider {
+            if *provider != config.llm.provider {
+                // The global model belongs to the global provider
+                config.llm.model.clear();
+                config.set_provider(provider);
+            }
+        }
+        if let Some(model) = &self.model {
+            config.llm.model = model.clone();
+        }
+        config.presets.extend(self.presets.clone());
+        if self.format.is_some() {
+            config.output.format = self.format.clone();
+        }
+        let global_rules = std::mem::take(&mut config.analysis.custom_rules);
+        config.analysis.custom_rules = crate::custom_rules::merge_rules(vec![global_rules, self.custom_rules.clone()]);
+        for glob in &self.ignore {
+            if !config.analysis.ignore.contains(glob) {
+                config.analysis.ignore.push(glob.clone());
+            }
+        }
+    }
+}
+
+/// OpenTelemetry trace export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    /// OTLP/HTTP collector, e.g. http://localhost:4318; `OTEL_EXPORTER_OTLP_ENDPOINT`
+    /// is used when unset. No traces are recorded without one.
+    pub otlp_endpoint: Option<String>,
+    /// Sent with every export, e.g. the API key header of a hosted backend
+    pub headers: BTreeMap<String, String>,
+    pub service_name: String,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: None,
+            headers: BTreeMap::new(),
+            service_name: "prism".to_string(),
+        }
+    }
+}
+
+impl TelemetryConfig {
+    pub fn otlp_endpoint(&self) -> Option<String> {
+        env_fallback(&self.otlp_endpoint, "OTEL_EXPORTER_OTLP_ENDPOINT")
+    }
+}
+
+/// LLM settings that replace the configured ones for a single run: from the
+/// `PRISM_*` environment variables or the `--provider`, `--model`,
+/// `--temperature` and `--max-tokens` flags.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LlmOverrides {
+    pub api_key: Option<String>,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub base_url: Option<String>,
+    pub timeout: Option<u64>,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+}
+
+impl LlmOverrides {
+    /// `PRISM_API_KEY`, `PRISM_PROVIDER`, `PRISM_MODEL`, `PRISM_BASE_URL`,
+    /// `PRISM_TIMEOUT` (seconds), `PRISM_TEMPERATURE` and `PRISM_MAX_TOKENS`;
+    /// empty variables are ignored.
+    pub fn from_env() -> Result<Self> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let get = |name: &str| var(name).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        let provider = get("PRISM_PROVIDER").map(|p| p.to_lowercase());
+        if let Some(provider) = &provider {
+            if !PROVIDERS.contains(&provider.as_str()) {
+                bail!("PRISM_PROVIDER must be one of {}, not '{}'", PROVIDERS.join(", "), provider);
+            }
+        }
+        let timeout = match get("PRISM_TIMEOUT") {
+            Some(t) => Some(t.parse().with_context(|| format!("PRISM_TIMEOUT must be a number of seconds, not '{}'", t))?),
+            None => None,
+        };
+        let temperature = match get("PRISM_TEMPERATURE") {
+            Some(t) => Some(t.parse().with_context(|| format!("PRISM_TEMPERATURE must be a number, not '{}'", t))?),
+            None => None,
+        };
+        let max_tokens = match get("PRISM_MAX_TOKENS") {
+            Some(t) => Some(t.parse().with_context(|| format!("PRISM_MAX_TOKENS must be a number of tokens, not '{}'", t))?),
+            None => None,
+        };
+        let overrides = Self {
+            api_key: get("PRISM_API_KEY"),
+            provider,
+            model: get("PRISM_MODEL"),
+            base_url: get("PRISM_BASE_URL"),
+            timeout,
+            temperature,
+            max_tokens,
+        };
+        overrides.validate()?;
+        Ok(overrides)
+    }
+
+    /// Temperatures must lie between 0 and 2, and responses need a token.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(temperature) = self.temperature {
+            if !(0.0..=2.0).contains(&temperature) {
+                bail!("The temperature must be between 0 and 2, not {}", temperature);
+            }
+        }
+        if self.max_tokens == Some(0) {
+            bail!("The response length must be at least 1 token");
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// A different provider brings its own base URL and default model unless
+    /// those are overridden too.
+    pub fn apply_to(&self, config: &mut Config) {
+        if let Some(provider) = &self.provider {
+            if *provider != config.llm.provider {
+                config.llm.model.clear();
+                config.set_provider(provider);
+            }
+        }
+        if let Some(model) = &self.model {
+            config.llm.model = model.clone();
+        }
+        if let Some(base_url) = &self.base_url {
+            config.llm.base_url = Some(base_url.clone());
+        }
+        if let Some(timeout) = self.timeout {
+            config.llm.timeout = timeout;
+        }
+        // Provider sections would otherwise win over the run's values
+        if let Some(temperature) = self.temperature {
+            config.llm.temperature = temperature;
+            config.llm.providers.values_mut().for_each(|p| p.temperature = None);
+        }
+        if let Some(max_tokens) = self.max_tokens {
+            config.llm.max_tokens = max_tokens;
+            config.llm.providers.values_mut().for_each(|p| p.max_tokens = None);
+        }
+        if let Some(api_key) = &self.api_key {
+            config.llm.api_key = Some(api_key.clone());
+        }
+    }
+}
+
+/// Providers `llm.provider` can name
+pub const PROVIDERS: &[&str] = &["openai", "gemini", "azure", "claude", "ollama", "openai-compatible", "anthropic-compatible", "mock"];
+
+/// API key of a compatible provider configured without one; self-hosted
+/// servers often take any key.
+pub const PLACEHOLDER_API_KEY: &str = "no-key";
+
+/// Whether `provider` is a gateway or self-hosted server speaking the
+/// OpenAI or Anthropic API, whose `base_url` and model the user sets.
+pub fn is_compatible_provider(provider: &str) -> bool {
+    matches!(provider, "openai-compatible" | "anthropic-compatible")
+}
+
+/// A `prism config --header` value, `Name: value`, as name and value.
+pub fn parse_header(spec: &str) -> Result<(String, String)> {
+    match spec.split_once(':') {
+        Some((name, value)) if !name.trim().is_empty() && !name.trim().contains(' ') => {
+            Ok((name.trim().to_string(), value.trim().to_string()))
+        }
+        _ => bail!("Invalid header '{}' - expected 'Name: value'", spec),
+    }
+}
+
+fn env_fallback(value: &Option<String>, variable: &str) -> Option<String> {
+    value.clone().or_else(|| std::env::var(variable).ok()).filter(|v| !v.trim().is_empty())
 }
 
 impl Default for Config {
@@ -35,24 +867,54 @@ impl Default for Config {
         Self {
             llm: LlmConfig {
                 api_key: std::env::var("PRISM_API_KEY").ok(),
+                keyring: false,
                 model: "".to_string(),
                 provider: "none".to_string(),
                 base_url: None,
                 timeout: 30,
+                retry: RetryConfig::default(),
+                temperature: default_temperature(),
+                max_tokens: default_max_tokens(),
+                system_prompt: None,
+                providers: BTreeMap::new(),
+                headers: BTreeMap::new(),
+                mock_responses: None,
+                ollama: OllamaConfig::default(),
+                chunking: ChunkingConfig::default(),
             },
             analysis: AnalysisConfig {
                 custom_rules: vec![],
                 ambiguity_threshold: 0.7,
                 enable_interactive: true,
+                severity_overrides: BTreeMap::new(),
+                disabled_rules: vec![],
+                show_hints: true,
+                owners_file: None,
+                duplicate_threshold: default_duplicate_threshold(),
+                architecture: None,
+                rules_file: None,
+                ignore: vec![],
             },
+            release_gate: ReleaseGateConfig::default(),
+            presets: BTreeMap::new(),
+            notion: NotionConfig::default(),
+            rm_tools: BTreeMap::new(),
+            daemon: DaemonConfig::default(),
+            server: ServerConfig::default(),
+            telemetry: TelemetryConfig::default(),
+            plantuml: PlantUmlConfig::default(),
+            projects: ProjectsConfig::default(),
+            review: ReviewConfig::default(),
+            output: OutputConfig::default(),
+            templates: TemplateConfig::default(),
+            signing: SigningConfig::default(),
         }
     }
 }
 
 impl Config {
     pub fn config_path() -> Result<PathBuf> {
-        let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
-        Ok(home.join(".prism").join("config.yml"))
+        Ok(paths::prism_dir()?.join("config.yml"))
     }
 
     pub async fn load() -> Result<Self> {
@@ -61,6 +923,13 @@ impl Config {
         if config_path.exists() {
             let content = fs::read_to_string(&config_path).await?;
             let mut config: Config = serde_yaml::from_str(&content)?;
+            config.expand_home_paths();
+            if config.llm.keyring && config.llm.api_key.is_none() {
+                match secrets::load_api_key().await {
+                    Ok(key) => config.llm.api_key = key,
+                    Err(e) => eprintln!("⚠️  {:#}", e),
+                }
+            }
             
             // Handle legacy configs that might not have provider field
             if config.llm.provider == "none" && config.llm.api_key.is_some() {
@@ -78,27 +947,43 @@ impl Config {
             
             Ok(config)
         } else {
-            let config = Config::default();
+            let mut config = Config::default();
             config.save().await?;
             Ok(config)
         }
     }
 
-    pub async fn save(&self) -> Result<()> {
+    pub async fn save(&mut self) -> Result<()> {
         let config_path = Self::config_path()?;
         
         if let Some(parent) = config_path.parent() {
             fs::create_dir_all(parent).await?;
         }
         
-        let content = serde_yaml::to_string(self)?;
+        // A key kept in the keyring is left out of the file
+        let mut stored = self.clone();
+        if self.llm.keyring {
+            if let Some(key) = &self.llm.api_key {
+                match secrets::store_api_key(key).await {
+                    Ok(()) => stored.llm.api_key = None,
+                    Err(e) => {
+                        eprintln!("⚠️  {:#} - the API key is saved in {} instead", e, config_path.display());
+                        stored.llm.keyring = false;
+                        self.llm.keyring = false;
+                    }
+                }
+            }
+        }
+        let content = serde_yaml::to_string(&stored)?;
         fs::write(&config_path, content).await?;
         
         Ok(())
     }
 
+    /// Sets the API key, to be saved in the OS keyring where there is one.
     pub fn set_api_key(&mut self, api_key: String) {
         self.llm.api_key = Some(api_key);
+        self.llm.keyring = true;
     }
 
     pub fn set_model(&mut self, model: String) {
@@ -135,7 +1020,7 @@ impl Config {
                 }
             }
             "ollama" => {
-                self.llm.base_url = Some("http://localhost:11434/api/generate".to_string());
+                self.llm.base_url = Some(crate::ollama::chat_url(None));
                 if self.llm.model.is_empty() {
                     // Try to get the first available model dynamically
                     match Self::get_ollama_models() {
@@ -149,12 +1034,74 @@ impl Config {
                     }
                 }
             }
+            // Canned responses, for tests and rule development without network or keys
+            "mock" => {
+                self.llm.base_url = None;
+                if self.llm.model.is_empty() {
+                    self.llm.model = "mock".to_string();
+                }
+                if self.llm.api_key.is_none() {
+                    self.llm.api_key = Some("mock".to_string());
+                }
+            }
+            // Gateways and self-hosted servers keep the base URL and model the user set
+            provider if is_compatible_provider(provider) => {}
             _ => {
                 self.llm.base_url = None;
             }
         }
     }
 
+    /// The providers `prism analyze --compare-providers` runs by default:
+    /// the configured one and those in `llm.providers` with a model or API
+    /// key of their own.
+    pub fn comparable_providers(&self) -> Vec<String> {
+        let mut providers = Vec::new();
+        if self.is_ai_configured() {
+            providers.push(self.llm.provider.clone());
+        }
+        for (name, settings) in &self.llm.providers {
+            if !providers.contains(name) && (settings.model.is_some() || settings.api_key.is_some()) {
+                providers.push(name.clone());
+            }
+        }
+        providers
+    }
+
+    /// A copy of this config that analyzes with `provider`. Another provider
+    /// than `llm.provider` takes its model, API key and base URL from its
+    /// `llm.providers` section, with the provider's default model and URL
+    /// where none is set.
+    pub fn for_provider(&self, provider: &str) -> Result<Config> {
+        if !PROVIDERS.contains(&provider) {
+            bail!("Unknown provider: {}", provider);
+        }
+        let mut config = self.clone();
+        if provider != self.llm.provider {
+            let settings = self.llm.providers.get(provider).cloned().unwrap_or_default();
+            config.llm.model = settings.model.unwrap_or_default();
+            config.llm.api_key = settings.api_key;
+            config.llm.base_url = None;
+            config.set_provider(provider);
+            if settings.base_url.is_some() {
+                config.llm.base_url = settings.base_url;
+            }
+        }
+        match provider {
+            "ollama" if config.llm.api_key.is_none() => config.llm.api_key = Some("ollama-local".to_string()),
+            "mock" if config.llm.api_key.is_none() => config.llm.api_key = Some("mock".to_string()),
+            "azure" if config.llm.base_url.is_none() => bail!("Set llm.providers.azure.base_url to the Azure OpenAI endpoint"),
+            _ if is_compatible_provider(provider) && config.llm.base_url.is_none() => {
+                bail!("Set llm.providers.{}.base_url to the gateway's endpoint", provider)
+            }
+            _ => {}
+        }
+        if config.llm.api_key.is_none() {
+            bail!("No API key for {} - set llm.providers.{}.api_key in ~/.prism/config.yml", provider, provider);
+        }
+        Ok(config)
+    }
+
     pub fn is_ai_configured(&self) -> bool {
         self.llm.api_key.is_some() && 
         !self.llm.model.is_empty() && 
@@ -167,6 +1114,10 @@ impl Config {
             "gemini" => ("Google Gemini".to_string(), vec!["gemini-1.5-pro".to_string(), "gemini-1.5-flash".to_string()]),
             "azure" => ("Azure OpenAI".to_string(), vec!["gpt-4".to_string(), "gpt-3.5-turbo".to_string()]),
             "claude" => ("Anthropic Claude".to_string(), vec!["claude-3-opus-20240229".to_string(), "claude-3-sonnet-20240229".to_string(), "claude-3-haiku-20240307".to_string()]),
+            // The gateway decides which models there are
+            "openai-compatible" => ("OpenAI-compatible endpoint".to_string(), vec![]),
+            "anthropic-compatible" => ("Anthropic-compatible endpoint".to_string(), vec![]),
+            "mock" => ("Mock (canned responses)".to_string(), vec!["mock".to_string()]),
             "ollama" => {
                 // Try to get actual available models, fallback to defaults
                 match Self::get_ollama_models() {
@@ -204,9 +1155,9 @@ impl Config {
             }
         }
 
-        // Fallback: try HTTP API
-        let rt = tokio::runtime::Runtime::new()?;
-        rt.block_on(async {
+        // Fallback: try HTTP API, on a thread of its own since this is also
+        // called from within the runtime
+        std::thread::spawn(|| tokio::runtime::Runtime::new()?.block_on(async {
             let client = reqwest::Client::new();
             match client.get("http://localhost:11434/api/tags").send().await {
                 Ok(response) if response.status().is_success() => {
@@ -232,7 +1183,7 @@ impl Config {
             }
             
             Err(anyhow::anyhow!("Could not fetch Ollama models"))
-        })
+        })).join().map_err(|_| anyhow::anyhow!("Could not fetch Ollama models"))?
     }
 
     pub async fn validate_all_settings(&self) -> Result<ValidationResult> {
@@ -243,10 +1194,10 @@ impl Config {
         if let Some(ref api_key) = self.llm.api_key {
             if api_key.is_empty() {
                 issues.push("API key is empty".to_string());
-            } else if api_key.len() < 10 {
+            } else if api_key.len() < 10 && !is_compatible_provider(&self.llm.provider) && self.llm.provider != "mock" {
                 warnings.push("API key seems too short".to_string());
             }
-        } else if self.llm.provider != "ollama" && self.llm.provider != "none" {
+        } else if !matches!(self.llm.provider.as_str(), "ollama" | "mock" | "none") {
             issues.push("API key is required for the selected provider".to_string());
         }
         
@@ -277,6 +1228,19 @@ impl Config {
                     issues.push("Base URL is required for Azure OpenAI".to_string());
                 }
             }
+            "mock" => {
+                if let Err(e) = self.llm.mock_responses.as_deref().map(crate::mock_provider::MockResponses::load).transpose() {
+                    issues.push(format!("{:#}", e));
+                }
+            }
+            provider if is_compatible_provider(provider) => {
+                if self.llm.base_url.is_none() {
+                    issues.push(format!("Base URL of the gateway or server is required for {}", provider));
+                }
+                if self.llm.model.is_empty() {
+                    issues.push(format!("Model name is required for {}", provider));
+                }
+            }
             "ollama" => {
                 // Check if Ollama is available
                 match Self::get_ollama_models() {
@@ -308,6 +1272,10 @@ impl Config {
             warnings.push("Timeout is very high (>5 minutes), consider reducing it".to_string());
         }
         
+        if let Err(e) = crate::locale::ReportLocale::from_config(&self.output) {
+            issues.push(format!("{:#}", e));
+        }
+
         // Validate analysis settings
         if self.analysis.ambiguity_threshold < 0.0 || self.analysis.ambiguity_threshold > 1.0 {
             issues.push("Ambiguity threshold must be between 0.0 and 1.0".to_string());
@@ -437,35 +1405,53 @@ impl Config {
         }
     }
 
+    /// The configured template directory, `~/.prism/templates` by default
     pub fn get_template_directory(&self) -> Result<PathBuf> {
-        let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
-        Ok(home.join(".prism").join("templates"))
+        if let Some(dir) = &self.templates.template_directory {
+            return Ok(dir.clone());
+        }
+        Ok(paths::prism_dir()?.join("templates"))
+    }
+
+    /// Expands a leading `~` in the configured paths, which the shell does
+    /// not do for `config.yml`.
+    fn expand_home_paths(&mut self) {
+        let optional_paths = [
+            &mut self.llm.mock_responses,
+            &mut self.analysis.owners_file,
+            &mut self.analysis.rules_file,
+            &mut self.daemon.history_path,
+            &mut self.server.usage_log,
+            &mut self.plantuml.jar,
+            &mut self.templates.template_directory,
+        ];
+        for path in optional_paths.into_iter().flatten() {
+            *path = paths::expand_home(path);
+        }
+        for job in &mut self.daemon.jobs {
+            job.path = paths::expand_home(&job.path);
+            if let Some(output_dir) = &mut job.output_dir {
+                *output_dir = paths::expand_home(output_dir);
+            }
+        }
     }
 
-    pub async fn set_template_directory(&mut self, template_dir: PathBuf) -> Result<()> {
-        // Validate the directory
-        if !template_dir.exists() {
+    pub fn set_template_directory(&mut self, template_dir: PathBuf) -> Result<()> {
+        if !template_dir.is_dir() {
             return Err(anyhow::anyhow!("Template directory does not exist: {}", template_dir.display()));
         }
-        
-        // Create a custom field for template directory (would need to add to struct)
-        // For now, we'll save it to a separate config file
-        let config_dir = Self::config_path()?.parent().unwrap().to_path_buf();
-        let template_config_path = config_dir.join("templates.yml");
-        
-        let template_config = TemplateConfig {
-            template_directory: Some(template_dir),
-        };
-        
-        let content = serde_yaml::to_string(&template_config)?;
-        fs::write(template_config_path, content).await?;
-        
+        // Commands run from other folders have to find it too
+        self.templates.template_directory = Some(paths::simplify(&template_dir.canonicalize()?));
         Ok(())
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Where the prompt templates of the AI tasks are kept
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TemplateConfig {
+    /// Folder with a `prompts` folder of `<task>.txt` files, instead of
+    /// `~/.prism/templates`
     pub template_directory: Option<PathBuf>,
 }
 
@@ -0,0 +1,59 @@
+//! Where the CLI's status lines go: the banner, progress and what was saved
+//! where. They share stdout with the report, except while stdout carries a
+//! machine-readable document, so `prism analyze --format json | jq` only sees
+//! the JSON and `--format csv > findings.csv` only gets the rows.
+//! With `--events` they become `status` events instead.
+
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static STATUS_TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// Sends the status lines of this process to stderr from now on.
+pub fn set_status_to_stderr(to_stderr: bool) {
+    STATUS_TO_STDERR.store(to_stderr, Ordering::Relaxed);
+}
+
+pub fn status_to_stderr() -> bool {
+    STATUS_TO_STDERR.load(Ordering::Relaxed)
+}
+
+/// Whether the terminal understands escape sequences such as colors. The
+/// Windows console has to be switched over to them first, which consoles
+/// before Windows 10 cannot do.
+pub fn supports_ansi() -> bool {
+    #[cfg(windows)]
+    {
+        crossterm::ansi_support::supports_ansi()
+    }
+    #[cfg(not(windows))]
+    {
+        true
+    }
+}
+
+/// Whether reports on stdout are colored: on a terminal that supports it,
+/// unless NO_COLOR asks otherwise.
+pub fn stdout_color() -> bool {
+    std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none() && supports_ansi()
+}
+
+/// `println!` for status lines, which go to stderr while stdout carries a
+/// machine-readable document, or out as events.
+#[macro_export]
+macro_rules! status {
+    () => {
+        $crate::status!("")
+    };
+    ($($arg:tt)*) => {
+        if $crate::events::events_enabled() {
+            if let Some(event) = $crate::events::status_event(&format!($($arg)*)) {
+                $crate::events::emit(&event);
+            }
+        } else if $crate::console::status_to_stderr() {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
@@ -0,0 +1,228 @@
+//! Contract test stubs for API-style requirements: endpoints written out
+//! ("GET /orders/{id}") or implied by a sentence about an API ("the API
+//! shall allow clients to create an order" is POST /orders), each with
+//! Pact-style interactions and a Schemathesis command to fuzz it against
+//! the OpenAPI document.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Sentences with one of these words describe an API.
+const API_WORDS: &str = r"(?i)\b(api|apis|endpoint|endpoints|rest|restful|http|https|webhook)\b";
+
+/// An endpoint written out in the requirement.
+const EXPLICIT_ENDPOINT: &str = r"\b(GET|POST|PUT|PATCH|DELETE)\s+(/[\w/{}:.\-]*)";
+
+/// HTTP method for the verbs of API sentences.
+const VERB_METHODS: &[(&str, &str)] = &[
+    ("create", "POST"), ("add", "POST"), ("submit", "POST"), ("register", "POST"), ("upload", "POST"), ("place", "POST"),
+    ("get", "GET"), ("retrieve", "GET"), ("fetch", "GET"), ("read", "GET"), ("view", "GET"), ("return", "GET"),
+    ("list", "GET"), ("search", "GET"), ("query", "GET"), ("download", "GET"),
+    ("update", "PUT"), ("edit", "PUT"), ("replace", "PUT"), ("modify", "PATCH"), ("change", "PATCH"),
+    ("delete", "DELETE"), ("remove", "DELETE"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractCase {
+    /// "a request to create an order with an invalid body"
+    pub description: String,
+    /// Pact provider state, e.g. "an order with id 1 exists"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_state: Option<String>,
+    pub status: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractTest {
+    pub method: String,
+    /// "/orders/{id}"
+    pub path: String,
+    /// 1-based line of the requirement
+    pub line: usize,
+    pub requirement: String,
+    /// False when the endpoint was inferred from the wording
+    pub explicit: bool,
+    /// The expected interaction first, then the error cases
+    pub cases: Vec<ContractCase>,
+}
+
+impl ContractTest {
+    /// Path with example values for its parameters, for Pact requests.
+    pub fn example_path(&self) -> String {
+        Regex::new(r"\{[^}]*\}|:\w+").unwrap().replace_all(&self.path, "1").to_string()
+    }
+
+    pub fn schemathesis_command(&self) -> String {
+        format!("schemathesis run openapi.yaml --include-method {} --include-path '{}'", self.method, self.path)
+    }
+}
+
+/// "order" -> "orders", "category" -> "categories"
+fn plural(noun: &str) -> String {
+    if noun.ends_with('s') {
+        noun.to_string()
+    } else if let Some(stem) = noun.strip_suffix('y').filter(|s| !s.ends_with(['a', 'e', 'i', 'o', 'u'])) {
+        format!("{}ies", stem)
+    } else if noun.ends_with("ch") || noun.ends_with("sh") || noun.ends_with('x') {
+        format!("{}es", noun)
+    } else {
+        format!("{}s", noun)
+    }
+}
+
+fn singular(noun: &str) -> String {
+    if let Some(stem) = noun.strip_suffix("ies") {
+        format!("{}y", stem)
+    } else if noun.ends_with("ss") || noun.ends_with("us") {
+        noun.to_string()
+    } else {
+        noun.strip_suffix('s').unwrap_or(noun).to_string()
+    }
+}
+
+/// The status a successful request gets, unless the sentence names one.
+fn success_status(method: &str, sentence: &str) -> u16 {
+    let named = Regex::new(r"(?i)\b(?:return|returns|respond|responds|status|with)\s+(?:an?\s+|http\s+|status\s+)*([1-5]\d\d)\b").unwrap();
+    if let Some(status) = named.captures(sentence).and_then(|c| c[1].parse().ok()) {
+        return status;
+    }
+    match method {
+        "POST" => 201,
+        "DELETE" => 204,
+        _ => 200,
+    }
+}
+
+/// The error cases worth pinning down for an endpoint: invalid bodies,
+/// unknown ids and missing credentials when the requirement asks for them.
+fn cases(method: &str, path: &str, resource: &str, sentence: &str) -> Vec<ContractCase> {
+    let has_id = path.contains('{') || path.contains(':');
+    let article = if resource.starts_with(['a', 'e', 'i', 'o', 'u']) { "an" } else { "a" };
+    let action = match method {
+        "POST" => format!("create {} {}", article, resource),
+        "GET" if has_id => format!("get {} {}", article, resource),
+        "GET" => format!("list {}", plural(resource)),
+        "PUT" | "PATCH" => format!("update {} {}", article, resource),
+        _ => format!("delete {} {}", article, resource),
+    };
+    let existing = has_id.then(|| format!("{} {} with id 1 exists", article, resource));
+    let mut cases = vec![ContractCase {
+        description: format!("a request to {}", action),
+        provider_state: existing.clone(),
+        status: success_status(method, sentence),
+    }];
+    if matches!(method, "POST" | "PUT" | "PATCH") {
+        cases.push(ContractCase {
+            description: format!("a request to {} with an invalid body", action),
+            provider_state: existing,
+            status: 400,
+        });
+    }
+    if has_id {
+        cases.push(ContractCase {
+            description: format!("a request to {} that does not exist", action),
+            provider_state: Some(format!("no {} with id 1 exists", resource)),
+            status: 404,
+        });
+    }
+    if Regex::new(r"(?i)\b(authenticated|authorized|authorised|logged in|token|api key|credentials)\b").unwrap().is_match(sentence) {
+        cases.push(ContractCase { description: format!("a request to {} without credentials", action), provider_state: None, status: 401 });
+    }
+    cases
+}
+
+/// Endpoint implied by an API sentence: its first CRUD verb and the noun
+/// after it, or the owner of a single thing ("the address of an order" is
+/// on the order). Listing and searching work on the collection, the other
+/// reads, updates and deletes on one item.
+fn implied_endpoint(sentence: &str) -> Option<(String, String, String)> {
+    let verbs = VERB_METHODS.iter().map(|(v, _)| *v).collect::<Vec<_>>().join("|");
+    let pattern = Regex::new(&format!(
+        r"(?i)\b({})(?:s|es|d|ed)?\s+(?:(?:a|an|the|all|their|its|one|new|existing|list\s+of|multiple|single)\s+)*([a-z][a-z-]*)(?:(?:\s+[a-z-]+)?\s+of\s+(?:(?:a|an|the|their|its|each)\s+)?([a-z][a-z-]*))?",
+        verbs
+    )).unwrap();
+    let caps = pattern.captures(sentence)?;
+    let verb = caps[1].to_lowercase();
+    let method = VERB_METHODS.iter().find(|(v, _)| *v == verb).map(|(_, m)| *m)?;
+    let mut noun = caps[2].to_lowercase();
+    if let Some(owner) = caps.get(3).filter(|_| singular(&noun) == noun) {
+        noun = owner.as_str().to_lowercase();
+    }
+    let resource = singular(&noun);
+    let collection = plural(&resource);
+    let whole_collection = method == "POST" || verb == "list" || verb == "search" || verb == "query"
+        || (method == "GET" && noun != resource);
+    let path = if whole_collection { format!("/{}", collection) } else { format!("/{}/{{id}}", collection) };
+    Some((method.to_string(), path, resource))
+}
+
+/// Contract tests for the API-style requirements of `text`; empty when no
+/// sentence writes out an endpoint or talks about an API.
+pub fn generate_contract_tests(text: &str) -> Vec<ContractTest> {
+    let api_words = Regex::new(API_WORDS).unwrap();
+    let explicit = Regex::new(EXPLICIT_ENDPOINT).unwrap();
+    let mut tests: Vec<ContractTest> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        for sentence in line.split_inclusive(['.', '!', '?', ';']).map(str::trim).filter(|s| !s.is_empty()) {
+            let found: Vec<(String, String, String, bool)> = if explicit.is_match(sentence) {
+                explicit.captures_iter(sentence).map(|caps| {
+                    let path = caps[2].trim_end_matches(['.', ',']).to_string();
+                    let resource = path.split('/').rev()
+                        .find(|s| !s.is_empty() && !s.starts_with(['{', ':']))
+                        .map(singular)
+                        .unwrap_or_else(|| "resource".to_string());
+                    (caps[1].to_string(), path, resource, true)
+                }).collect()
+            } else if api_words.is_match(sentence) {
+                implied_endpoint(sentence).map(|(m, p, r)| (m, p, r, false)).into_iter().collect()
+            } else {
+                Vec::new()
+            };
+            for (method, path, resource, is_explicit) in found {
+                if tests.iter().any(|t| t.method == method && t.path == path) {
+                    continue;
+                }
+                let cases = cases(&method, &path, &resource.replace('-', " "), sentence);
+                tests.push(ContractTest { method, path, line: index + 1, requirement: sentence.to_string(), explicit: is_explicit, cases });
+            }
+        }
+    }
+    tests
+}
+
+/// A Pact (specification 2.0) file with one interaction per case.
+pub fn format_contract_pact(tests: &[ContractTest], consumer: &str, provider: &str) -> Value {
+    let interactions: Vec<Value> = tests.iter().flat_map(|test| test.cases.iter().map(move |case| {
+        let mut interaction = json!({
+            "description": case.description,
+            "request": { "method": test.method, "path": test.example_path() },
+            "response": { "status": case.status },
+        });
+        if let Some(state) = &case.provider_state {
+            interaction["providerState"] = json!(state);
+        }
+        interaction
+    })).collect();
+    json!({
+        "consumer": { "name": consumer },
+        "provider": { "name": provider },
+        "interactions": interactions,
+        "metadata": { "pactSpecification": { "version": "2.0.0" } },
+    })
+}
+
+pub fn format_contract_tests_markdown(tests: &[ContractTest]) -> String {
+    let mut output = String::new();
+    for test in tests {
+        let origin = if test.explicit { "" } else { " *(inferred)*" };
+        output.push_str(&format!("### `{} {}`{}\n\n", test.method, test.path, origin));
+        output.push_str(&format!("> Line {}: {}\n\n", test.line, test.requirement));
+        for case in &test.cases {
+            let given = case.provider_state.as_ref().map(|s| format!("Given {}, upon", s)).unwrap_or_else(|| "Upon".to_string());
+            output.push_str(&format!("- {} receiving {}, the API responds with **{}**\n", given, case.description, case.status));
+        }
+        output.push_str(&format!("\n```bash\n{}\n```\n\n", test.schemathesis_command()));
+    }
+    output
+}
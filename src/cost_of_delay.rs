@@ -0,0 +1,280 @@
+//! Cost-of-delay annotations on requirement documents and the cost-of-delay
+//! vs quality report that shows which low-quality requirements to fix first.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::hierarchy::QualityScore;
+use crate::html_report::{html_escape, html_page, section};
+use crate::rm_tools::LOW_QUALITY_SCORE;
+
+/// Sidecar with the annotations of a directory's documents, next to them.
+pub const SIDECAR_NAME: &str = "cost-of-delay.csv";
+
+/// Value/cost metadata of a requirement document, from its YAML front matter
+/// or a row of the CSV sidecar. Either a `cost_of_delay` figure or its SAFe
+/// components, which add up to it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CodAnnotation {
+    pub cost_of_delay: Option<f32>,
+    pub value: Option<f32>,
+    pub time_criticality: Option<f32>,
+    pub risk_reduction: Option<f32>,
+}
+
+impl CodAnnotation {
+    /// The cost of delay, `None` when the annotation has no figure at all.
+    pub fn total(&self) -> Option<f32> {
+        self.cost_of_delay.or_else(|| {
+            let parts = [self.value, self.time_criticality, self.risk_reduction];
+            parts.iter().any(|p| p.is_some()).then(|| parts.iter().flatten().sum())
+        })
+    }
+
+    fn set(&mut self, field: &str, value: f32) {
+        match field {
+            "cost_of_delay" | "cost-of-delay" | "cod" => self.cost_of_delay = Some(value),
+            "value" | "business_value" => self.value = Some(value),
+            "time_criticality" | "time-criticality" => self.time_criticality = Some(value),
+            "risk_reduction" | "risk-reduction" => self.risk_reduction = Some(value),
+            _ => {}
+        }
+    }
+}
+
+/// A document's `---` delimited YAML front matter and the text after it.
+pub fn split_front_matter(text: &str) -> (Option<&str>, &str) {
+    let Some(rest) = text.strip_prefix("---\n").or_else(|| text.strip_prefix("---\r\n")) else {
+        return (None, text);
+    };
+    match rest.find("\n---") {
+        Some(end) => {
+            let body = &rest[end + 4..];
+            let body = body.split_once('\n').map(|(_, b)| b).unwrap_or_default();
+            (Some(&rest[..end]), body)
+        }
+        None => (None, text),
+    }
+}
+
+/// The annotation in a document's front matter, if it has one with a figure.
+pub fn front_matter_annotation(text: &str) -> Option<CodAnnotation> {
+    let (front_matter, _) = split_front_matter(text);
+    let annotation: CodAnnotation = serde_yaml::from_str(front_matter?).ok()?;
+    annotation.total().is_some().then_some(annotation)
+}
+
+/// Annotations by file from a sidecar CSV with a header row: a `file`
+/// column, relative to the sidecar's directory, and `cost_of_delay` and/or
+/// `value`, `time_criticality`, `risk_reduction` columns.
+pub fn parse_sidecar(content: &str) -> Result<BTreeMap<String, CodAnnotation>> {
+    let split = |line: &str| -> Vec<String> { line.split(',').map(|c| c.trim().trim_matches('"').to_string()).collect() };
+    let mut lines = content.lines().filter(|l| !l.trim().is_empty() && !l.trim_start().starts_with('#'));
+    let header: Vec<String> = split(lines.next().unwrap_or_default()).iter().map(|h| h.to_lowercase()).collect();
+    let file_column = header.iter().position(|h| h == "file" || h == "path")
+        .ok_or_else(|| anyhow::anyhow!("The cost-of-delay sidecar needs a 'file' column"))?;
+
+    let mut annotations = BTreeMap::new();
+    for (row, line) in lines.enumerate() {
+        let cells = split(line);
+        let Some(file) = cells.get(file_column).filter(|f| !f.is_empty()) else { continue };
+        let mut annotation = CodAnnotation::default();
+        for (column, cell) in cells.iter().enumerate().filter(|(c, cell)| *c != file_column && !cell.is_empty()) {
+            let value: f32 = cell.parse()
+                .map_err(|_| anyhow::anyhow!("Row {} of the cost-of-delay sidecar: '{}' is not a number", row + 2, cell))?;
+            annotation.set(header.get(column).map(|h| h.as_str()).unwrap_or_default(), value);
+        }
+        annotations.insert(file.replace('\\', "/"), annotation);
+    }
+    Ok(annotations)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Quadrant {
+    /// High cost of delay, low quality: unclear requirements holding up valuable work
+    FixFirst,
+    /// High cost of delay, good quality
+    ReadyToDeliver,
+    /// Low cost of delay, low quality
+    FixLater,
+    /// Low cost of delay, good quality
+    Leave,
+}
+
+impl Quadrant {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Quadrant::FixFirst => "🔴 Fix first",
+            Quadrant::ReadyToDeliver => "🟢 Ready to deliver",
+            Quadrant::FixLater => "🟡 Fix later",
+            Quadrant::Leave => "⚪ Leave",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrioritizedDocument {
+    pub file: String,
+    /// `None` for documents without an annotation
+    pub cost_of_delay: Option<f32>,
+    pub quality: QualityScore,
+}
+
+impl PrioritizedDocument {
+    /// The part of the cost of delay put at risk by the document's quality issues.
+    pub fn delay_at_risk(&self) -> f32 {
+        self.cost_of_delay.unwrap_or(0.0) * (100.0 - self.quality.score) / 100.0
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostOfDelayReport {
+    /// Annotated documents, highest cost of delay at risk first, then the unannotated ones
+    pub documents: Vec<PrioritizedDocument>,
+    /// Cost of delay at or above this (the median) counts as high
+    pub high_cost_of_delay: f32,
+}
+
+impl CostOfDelayReport {
+    pub fn new(mut documents: Vec<PrioritizedDocument>) -> Self {
+        let mut costs: Vec<f32> = documents.iter().filter_map(|d| d.cost_of_delay).collect();
+        costs.sort_by(|a, b| a.total_cmp(b));
+        let high_cost_of_delay = costs.get(costs.len() / 2).copied().unwrap_or(0.0);
+
+        documents.sort_by(|a, b| {
+            b.cost_of_delay.is_some().cmp(&a.cost_of_delay.is_some())
+                .then_with(|| b.delay_at_risk().total_cmp(&a.delay_at_risk()))
+                .then_with(|| a.file.cmp(&b.file))
+        });
+        Self { documents, high_cost_of_delay }
+    }
+
+    pub fn quadrant(&self, document: &PrioritizedDocument) -> Option<Quadrant> {
+        let high = document.cost_of_delay? >= self.high_cost_of_delay;
+        let low_quality = document.quality.score < LOW_QUALITY_SCORE;
+        Some(match (high, low_quality) {
+            (true, true) => Quadrant::FixFirst,
+            (true, false) => Quadrant::ReadyToDeliver,
+            (false, true) => Quadrant::FixLater,
+            (false, false) => Quadrant::Leave,
+        })
+    }
+
+    pub fn annotated(&self) -> impl Iterator<Item = &PrioritizedDocument> {
+        self.documents.iter().filter(|d| d.cost_of_delay.is_some())
+    }
+
+    fn max_cost_of_delay(&self) -> f32 {
+        self.annotated().filter_map(|d| d.cost_of_delay).fold(0.0, f32::max).max(1.0)
+    }
+}
+
+/// Ranked table plus a Mermaid quadrant chart of cost of delay vs quality.
+pub fn format_cost_of_delay_markdown(source: &Path, report: &CostOfDelayReport) -> String {
+    let annotated: Vec<&PrioritizedDocument> = report.annotated().collect();
+    let fix_first = annotated.iter().filter(|d| report.quadrant(d) == Some(Quadrant::FixFirst)).count();
+
+    let mut output = String::from("# 💸 Cost of Delay vs Quality\n\n");
+    output.push_str(&format!("**Source:** `{}`\n\n", source.display()));
+    output.push_str(&format!(
+        "**Annotated:** {} of {} documents | **Fix first:** {} (cost of delay ≥ {:.0}, quality < {:.0})\n\n",
+        annotated.len(), report.documents.len(), fix_first, report.high_cost_of_delay, LOW_QUALITY_SCORE
+    ));
+
+    if !annotated.is_empty() {
+        let max = report.max_cost_of_delay();
+        output.push_str("```mermaid\nquadrantChart\n    title Cost of delay vs requirement quality\n");
+        output.push_str("    x-axis Low quality --> High quality\n    y-axis Low cost of delay --> High cost of delay\n");
+        output.push_str("    quadrant-1 Ready to deliver\n    quadrant-2 Fix first\n    quadrant-3 Fix later\n    quadrant-4 Leave\n");
+        for document in &annotated {
+            output.push_str(&format!(
+                "    {}: [{:.2}, {:.2}]\n",
+                document.file.replace([':', '[', ']', ','], " "),
+                document.quality.score / 100.0,
+                document.cost_of_delay.unwrap_or(0.0) / max
+            ));
+        }
+        output.push_str("```\n\n");
+
+        output.push_str("| # | Document | Cost of delay | Quality | Findings | At risk | Priority |\n|---:|---|---:|---:|---:|---:|---|\n");
+        for (i, document) in annotated.iter().enumerate() {
+            output.push_str(&format!(
+                "| {} | `{}` | {:.0} | {:.0} | {} | {:.0} | {} |\n",
+                i + 1, document.file, document.cost_of_delay.unwrap_or(0.0), document.quality.score,
+                document.quality.finding_count, document.delay_at_risk(),
+                report.quadrant(document).map(|q| q.label()).unwrap_or_default()
+            ));
+        }
+        output.push_str("\n*At risk* is the cost of delay scaled by how far the quality score is below 100.\n\n");
+    }
+
+    let unannotated: Vec<&PrioritizedDocument> = report.documents.iter().filter(|d| d.cost_of_delay.is_none()).collect();
+    if !unannotated.is_empty() {
+        output.push_str(&format!("## Not Annotated\n\nAdd `cost_of_delay` to the front matter or to `{}`:\n", SIDECAR_NAME));
+        for document in unannotated {
+            output.push_str(&format!("- `{}` - quality {:.0}\n", document.file, document.quality.score));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// SVG scatter plot of the annotated documents: quality across, cost of delay up.
+pub fn cost_of_delay_scatter(report: &CostOfDelayReport) -> String {
+    let (width, height, margin) = (640.0, 400.0, 50.0);
+    let max = report.max_cost_of_delay();
+    let x = |quality: f32| margin + quality / 100.0 * (width - 2.0 * margin);
+    let y = |cost: f32| height - margin - cost / max * (height - 2.0 * margin);
+
+    let mut svg = format!("<svg viewBox=\"0 0 {w} {h}\" role=\"img\" aria-label=\"Cost of delay vs quality\" style=\"max-width: {w}px; width: 100%\">\n", w = width, h = height);
+    // Fix-first quadrant shaded
+    svg.push_str(&format!(
+        "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"#fdecea\"/>\n",
+        margin, margin, x(LOW_QUALITY_SCORE) - margin, y(report.high_cost_of_delay) - margin
+    ));
+    svg.push_str(&format!(
+        "<line x1=\"{m}\" y1=\"{b}\" x2=\"{r}\" y2=\"{b}\" stroke=\"#52606d\"/><line x1=\"{m}\" y1=\"{m}\" x2=\"{m}\" y2=\"{b}\" stroke=\"#52606d\"/>\n",
+        m = margin, b = height - margin, r = width - margin
+    ));
+    svg.push_str(&format!(
+        "<line x1=\"{q:.1}\" y1=\"{m}\" x2=\"{q:.1}\" y2=\"{b}\" stroke=\"#9aa5b1\" stroke-dasharray=\"4 4\"/><line x1=\"{m}\" y1=\"{c:.1}\" x2=\"{r}\" y2=\"{c:.1}\" stroke=\"#9aa5b1\" stroke-dasharray=\"4 4\"/>\n",
+        q = x(LOW_QUALITY_SCORE), c = y(report.high_cost_of_delay), m = margin, b = height - margin, r = width - margin
+    ));
+    svg.push_str(&format!(
+        "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"13\">Quality score</text><text x=\"14\" y=\"{}\" font-size=\"13\" transform=\"rotate(-90 14 {})\" text-anchor=\"middle\">Cost of delay</text>\n",
+        width / 2.0, height - 12.0, height / 2.0, height / 2.0
+    ));
+    svg.push_str(&format!("<text x=\"{}\" y=\"{}\" font-size=\"12\" fill=\"#c62828\">Fix first</text>\n", margin + 6.0, margin + 16.0));
+
+    for document in report.annotated() {
+        let cost = document.cost_of_delay.unwrap_or(0.0);
+        let color = if report.quadrant(document) == Some(Quadrant::FixFirst) { "#c62828" } else { "#3f7cac" };
+        svg.push_str(&format!(
+            "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"6\" fill=\"{}\"><title>{}: cost of delay {:.0}, quality {:.0}</title></circle>\n<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"11\">{}</text>\n",
+            x(document.quality.score), y(cost), color, html_escape(&document.file), cost, document.quality.score,
+            x(document.quality.score) + 9.0, y(cost) + 4.0, html_escape(&document.file)
+        ));
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+pub fn format_cost_of_delay_html(source: &Path, report: &CostOfDelayReport) -> String {
+    let mut rows = String::from("<table><tr><th>#</th><th>Document</th><th>Cost of delay</th><th>Quality</th><th>Findings</th><th>At risk</th><th>Priority</th></tr>\n");
+    for (i, document) in report.annotated().enumerate() {
+        rows.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{:.0}</td><td>{:.0}</td><td>{}</td><td>{:.0}</td><td>{}</td></tr>\n",
+            i + 1, html_escape(&document.file), document.cost_of_delay.unwrap_or(0.0), document.quality.score,
+            document.quality.finding_count, document.delay_at_risk(), report.quadrant(document).map(|q| q.label()).unwrap_or_default()
+        ));
+    }
+    rows.push_str("</table>");
+
+    let mut body = format!("<h1>💸 Cost of Delay vs Quality</h1>\n<p><strong>Source:</strong> <code>{}</code></p>\n", html_escape(&source.display().to_string()));
+    body.push_str(&section("Scatter", &cost_of_delay_scatter(report)));
+    body.push_str(&section("Priorities", &rows));
+    html_page("PRISM Cost of Delay", "", &body, false)
+}
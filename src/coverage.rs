@@ -0,0 +1,211 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use crate::locale::report_locale;
+
+/// Stories scoring at least this similarity against a capability cover it.
+pub const DEFAULT_COVERAGE_THRESHOLD: f32 = 0.35;
+
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "the", "and", "or", "but", "to", "of", "in", "on", "for", "with", "by", "at", "from",
+    "as", "so", "that", "this", "these", "those", "it", "its", "be", "is", "are", "was", "were",
+    "been", "being", "i", "we", "you", "they", "he", "she", "my", "our", "your", "their", "want",
+    "need", "should", "must", "shall", "will", "can", "could", "would", "may", "able", "allow",
+    "allows", "user", "users", "system", "into", "via", "all", "any", "each", "when", "then",
+    "if", "not", "no", "also",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoryMatch {
+    pub story: String,
+    pub score: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilityCoverage {
+    pub statement: String,
+    pub matches: Vec<StoryMatch>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverageReport {
+    pub threshold: f32,
+    pub covered: Vec<CapabilityCoverage>,
+    pub uncovered: Vec<String>,
+    pub orphan_stories: Vec<String>,
+}
+
+impl CoverageReport {
+    pub fn coverage_percent(&self) -> f32 {
+        let total = self.covered.len() + self.uncovered.len();
+        if total == 0 {
+            100.0
+        } else {
+            self.covered.len() as f32 / total as f32 * 100.0
+        }
+    }
+}
+
+/// Pulls the capability statements out of an epic: list items, user stories
+/// and sentences with a requirement verb. Headings are skipped.
+pub fn extract_capabilities(epic_text: &str) -> Vec<String> {
+    extract_capability_lines(epic_text).into_iter().map(|(_, statement)| statement).collect()
+}
+
+/// The statements of [`extract_capabilities`] with their 1-based line numbers.
+pub fn extract_capability_lines(text: &str) -> Vec<(usize, String)> {
+    let bullet = Regex::new(r"^\s*(?:[-*+]|\d+[.)])\s+(.+)$").unwrap();
+    let requirement = Regex::new(r"(?i)\b(as an?|shall|must|should|will|can|able to|allows?|supports?|provides?)\b").unwrap();
+    let mut capabilities = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(caps) = bullet.captures(line) {
+            capabilities.push((index + 1, caps[1].trim().to_string()));
+            continue;
+        }
+
+        for sentence in line.split(['.', '!', '?', ';']) {
+            let sentence = sentence.trim();
+            if !sentence.is_empty() && requirement.is_match(sentence) {
+                capabilities.push((index + 1, sentence.to_string()));
+            }
+        }
+    }
+
+    capabilities
+}
+
+/// Content words of `text`, lowercased and reduced to a rough stem so that
+/// "exports", "exporting" and "exported" compare equal.
+pub fn content_terms(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() > 2 && !STOP_WORDS.contains(w))
+        .map(stem)
+        .collect()
+}
+
+fn stem(word: &str) -> String {
+    for suffix in ["ations", "ation", "ments", "ment", "ings", "ing", "ied", "ies", "ed", "es", "s"] {
+        if let Some(stripped) = word.strip_suffix(suffix) {
+            if stripped.len() >= 3 {
+                return stripped.to_string();
+            }
+        }
+    }
+    word.to_string()
+}
+
+/// Cosine similarity of the term-frequency vectors of two texts.
+pub fn similarity(a: &str, b: &str) -> f32 {
+    let count = |text: &str| {
+        let mut counts: HashMap<String, f32> = HashMap::new();
+        for term in content_terms(text) {
+            *counts.entry(term).or_insert(0.0) += 1.0;
+        }
+        counts
+    };
+    let (a, b) = (count(a), count(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let dot: f32 = a.iter().filter_map(|(term, x)| b.get(term).map(|y| x * y)).sum();
+    let norm = |v: &HashMap<String, f32>| v.values().map(|x| x * x).sum::<f32>().sqrt();
+    dot / (norm(&a) * norm(&b))
+}
+
+/// Best similarity between a capability and any sentence of a story, so long
+/// stories aren't penalized for covering more than one capability.
+fn story_score(capability: &str, story_text: &str) -> f32 {
+    story_text
+        .split(['.', '!', '?', ';', '\n'])
+        .map(|sentence| similarity(capability, sentence))
+        .fold(similarity(capability, story_text), f32::max)
+}
+
+/// Matches every epic capability against the stories (name, text) and reports
+/// uncovered capabilities and stories that don't cover any capability.
+pub fn check_coverage(capabilities: &[String], stories: &[(String, String)], threshold: f32) -> CoverageReport {
+    let mut covered = Vec::new();
+    let mut uncovered = Vec::new();
+    let mut used_stories: HashSet<String> = HashSet::new();
+
+    for capability in capabilities {
+        let mut matches: Vec<StoryMatch> = stories.iter()
+            .map(|(name, text)| StoryMatch { story: name.clone(), score: story_score(capability, text) })
+            .filter(|m| m.score >= threshold)
+            .collect();
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        if matches.is_empty() {
+            uncovered.push(capability.clone());
+        } else {
+            used_stories.extend(matches.iter().map(|m| m.story.clone()));
+            covered.push(CapabilityCoverage { statement: capability.clone(), matches });
+        }
+    }
+
+    let orphan_stories = stories.iter()
+        .filter(|(name, _)| !used_stories.contains(name))
+        .map(|(name, _)| name.clone())
+        .collect();
+
+    CoverageReport { threshold, covered, uncovered, orphan_stories }
+}
+
+pub fn format_coverage_markdown(report: &CoverageReport, epic_name: &str) -> String {
+    let mut output = String::new();
+
+    output.push_str("# 🧭 PRISM Epic Coverage Report\n\n");
+    output.push_str(&format!("**Epic:** `{}`\n\n", epic_name));
+    output.push_str(&format!(
+        "**Coverage:** {} ({} of {} capabilities covered, match threshold {})\n\n",
+        report_locale().percent(report.coverage_percent(), 0),
+        report.covered.len(),
+        report.covered.len() + report.uncovered.len(),
+        report_locale().number(report.threshold, 2)
+    ));
+
+    output.push_str("## ❌ Uncovered Epic Statements\n\n");
+    if report.uncovered.is_empty() {
+        output.push_str("✅ Every epic capability is covered by at least one story.\n\n");
+    } else {
+        for statement in &report.uncovered {
+            output.push_str(&format!("- {}\n", statement));
+        }
+        output.push('\n');
+    }
+
+    output.push_str("## 👻 Orphan Stories\n\n");
+    if report.orphan_stories.is_empty() {
+        output.push_str("✅ Every story traces back to an epic capability.\n\n");
+    } else {
+        for story in &report.orphan_stories {
+            output.push_str(&format!("- {}\n", story));
+        }
+        output.push('\n');
+    }
+
+    if !report.covered.is_empty() {
+        output.push_str("## ✅ Covered Capabilities\n\n");
+        output.push_str("| Capability | Covered by | Best match |\n|---|---|---:|\n");
+        for coverage in &report.covered {
+            let stories: Vec<&str> = coverage.matches.iter().map(|m| m.story.as_str()).collect();
+            output.push_str(&format!(
+                "| {} | {} | {} |\n",
+                coverage.statement.replace('|', "\\|"),
+                stories.join(", "),
+                report_locale().number(coverage.matches[0].score, 2)
+            ));
+        }
+        output.push('\n');
+    }
+
+    output
+}
@@ -0,0 +1,120 @@
+//! Finding rules of the team's own: a regular expression or a keyword list
+//! with a severity, a reason and suggestions, loaded from
+//! `~/.prism/rules.yml`, the project's `.prism/rules.yml`, the configured
+//! `rules_file` and `analysis.custom_rules`, in that order. A rule replaces
+//! an earlier one with the same id, so a project can tighten a global rule.
+//!
+//! ```yaml
+//! rules:
+//!   - id: tbd
+//!     pattern: '\b(TBD|TODO|to be decided)\b'
+//!     severity: High
+//!     reason: Open decision left in the requirement
+//!     suggestions: [Decide before the requirement is approved]
+//!   - id: weasel-words
+//!     keywords: [simply, just, obviously]
+//! ```
+
+use anyhow::{bail, Context, Result};
+use regex::{Regex, RegexBuilder};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use crate::config::{Config, CustomRule};
+
+/// Rules file in ~/.prism, next to config.yml
+pub const RULES_FILE: &str = "rules.yml";
+
+/// Rules file of the project in the working directory
+pub const PROJECT_RULES_FILE: &str = ".prism/rules.yml";
+
+#[derive(Deserialize)]
+struct RulesFile {
+    #[serde(default)]
+    rules: Vec<CustomRule>,
+}
+
+/// A rule with its pattern and keywords compiled into one expression.
+#[derive(Debug, Clone)]
+pub struct CompiledRule {
+    pub rule: CustomRule,
+    pub regex: Regex,
+}
+
+impl CompiledRule {
+    pub fn new(rule: CustomRule) -> Result<Self> {
+        let mut alternatives = Vec::new();
+        if let Some(pattern) = rule.pattern.as_deref().filter(|p| !p.trim().is_empty()) {
+            alternatives.push(format!("(?:{})", pattern));
+        }
+        let keywords: Vec<String> = rule.keywords.iter()
+            .map(|k| k.trim())
+            .filter(|k| !k.is_empty())
+            .map(regex::escape)
+            .collect();
+        if !keywords.is_empty() {
+            alternatives.push(format!(r"\b(?:{})\b", keywords.join("|")));
+        }
+        if alternatives.is_empty() {
+            bail!("custom rule '{}' needs a pattern or keywords", rule.id);
+        }
+        let regex = RegexBuilder::new(&alternatives.join("|"))
+            .case_insensitive(!rule.case_sensitive)
+            .build()
+            .with_context(|| format!("custom rule '{}' has an invalid pattern", rule.id))?;
+        Ok(Self { rule, regex })
+    }
+}
+
+pub fn parse_rules_file(content: &str) -> Result<Vec<CustomRule>> {
+    if content.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_yaml::from_str::<RulesFile>(content)?.rules)
+}
+
+/// The rules files that are read, whether or not they exist.
+pub fn rules_file_paths(config: &Config) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let Ok(config_path) = Config::config_path() {
+        paths.push(config_path.with_file_name(RULES_FILE));
+    }
+    paths.push(PathBuf::from(PROJECT_RULES_FILE));
+    if let Some(rules_file) = &config.analysis.rules_file {
+        paths.push(rules_file.clone());
+    }
+    paths
+}
+
+fn read_rules_file(path: &Path) -> Result<Vec<CustomRule>> {
+    let content = std::fs::read_to_string(path).with_context(|| format!("Cannot read rules file {}", path.display()))?;
+    parse_rules_file(&content).with_context(|| format!("Invalid rules file {}", path.display()))
+}
+
+/// Rules from every rules file that exists and from the config, a later rule
+/// replacing an earlier one with the same id. A configured `rules_file` has
+/// to exist.
+pub fn load_custom_rules(config: &Config) -> Result<Vec<CustomRule>> {
+    let mut sources = Vec::new();
+    for path in rules_file_paths(config) {
+        let configured = config.analysis.rules_file.as_ref() == Some(&path);
+        if configured || path.is_file() {
+            sources.push(read_rules_file(&path)?);
+        }
+    }
+    sources.push(config.analysis.custom_rules.clone());
+    Ok(merge_rules(sources))
+}
+
+/// Concatenates `sources` in order; a rule replaces an earlier one with its id
+/// in place.
+pub fn merge_rules(sources: Vec<Vec<CustomRule>>) -> Vec<CustomRule> {
+    let mut merged: Vec<CustomRule> = Vec::new();
+    for rule in sources.into_iter().flatten() {
+        match merged.iter_mut().find(|r| r.id == rule.id) {
+            Some(existing) => *existing = rule,
+            None => merged.push(rule),
+        }
+    }
+    merged
+}
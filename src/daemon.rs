@@ -0,0 +1,246 @@
+//! Scheduled analyses for `prism daemon`: cron schedules, the analysis
+//! history and quality regression checks.
+
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::config::RegressionThresholds;
+use crate::hierarchy::QualityScore;
+use crate::paths;
+
+pub const HISTORY_FILE_NAME: &str = "history.jsonl";
+
+/// Runs further out than this are not searched for, so that schedules that
+/// can never match (e.g. February 30th) do not loop forever.
+const MAX_SCHEDULE_DAYS: i64 = 366 * 5;
+
+/// A five-field cron expression: minute, hour, day of month, month and day
+/// of week (0 or 7 is Sunday). `@hourly`, `@daily`, `@weekly` and `@monthly` are accepted too.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    minutes: Vec<bool>,
+    hours: Vec<bool>,
+    days: Vec<bool>,
+    months: Vec<bool>,
+    weekdays: Vec<bool>,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let expression = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(anyhow::anyhow!(
+                "Invalid schedule '{}': expected 5 fields (minute hour day month weekday), e.g. \"0 6 * * 1-5\"",
+                expression
+            ));
+        }
+
+        let mut weekdays = parse_field(fields[4], 0, 7, "weekday")?;
+        if weekdays[7] {
+            weekdays[0] = true;
+        }
+        weekdays.truncate(7);
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59, "minute")?,
+            hours: parse_field(fields[1], 0, 23, "hour")?,
+            days: parse_field(fields[2], 1, 31, "day")?,
+            months: parse_field(fields[3], 1, 12, "month")?,
+            weekdays,
+            any_day: fields[2] == "*",
+            any_weekday: fields[4] == "*",
+        })
+    }
+
+    pub fn matches(&self, time: NaiveDateTime) -> bool {
+        let day = self.days[time.day() as usize];
+        let weekday = self.weekdays[time.weekday().num_days_from_sunday() as usize];
+        // As in cron, a restricted day of month and day of week match either
+        let day_matches = match (self.any_day, self.any_weekday) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        };
+        self.minutes[time.minute() as usize]
+            && self.hours[time.hour() as usize]
+            && self.months[time.month() as usize]
+            && day_matches
+    }
+
+    /// The first minute after `after` the schedule matches.
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut time = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let end = after + Duration::days(MAX_SCHEDULE_DAYS);
+        while time <= end {
+            if !self.months[time.month() as usize] {
+                // Skip to the first day of the next month
+                let (year, month) = if time.month() == 12 { (time.year() + 1, 1) } else { (time.year(), time.month() + 1) };
+                time = chrono::NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.hours[time.hour() as usize] {
+                time = time.with_minute(0)? + Duration::hours(1);
+            } else if self.matches(time) {
+                return Some(time);
+            } else {
+                time += Duration::minutes(1);
+            }
+        }
+        None
+    }
+}
+
+/// Allowed values of one cron field, indexed by value.
+fn parse_field(field: &str, min: u32, max: u32, name: &str) -> Result<Vec<bool>> {
+    let invalid = || anyhow::anyhow!("Invalid {} '{}' in schedule", name, field);
+
+    let mut allowed = vec![false; max as usize + 1];
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|s| *s > 0).ok_or_else(invalid)?),
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (start.parse().map_err(|_| invalid())?, end.parse().map_err(|_| invalid())?),
+                None => {
+                    let value = range.parse().map_err(|_| invalid())?;
+                    (value, if part.contains('/') { max } else { value })
+                }
+            },
+        };
+        if start < min || end > max || start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step as usize) {
+            allowed[value as usize] = true;
+        }
+    }
+    Ok(allowed)
+}
+
+/// One scheduled run of a job, as kept in the analysis history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub job: String,
+    pub timestamp: DateTime<Utc>,
+    pub files: usize,
+    pub requirement_count: usize,
+    pub finding_count: usize,
+    pub critical_count: usize,
+    /// 0-100 quality score over all files
+    pub score: f32,
+    #[serde(default)]
+    pub word_count: usize,
+    /// Findings per 100 words, 0 in runs recorded before it was tracked
+    #[serde(default)]
+    pub density: f32,
+}
+
+impl HistoryEntry {
+    pub fn new(job: &str, timestamp: DateTime<Utc>, files: usize, score: &QualityScore, critical_count: usize, word_count: usize) -> Self {
+        Self {
+            job: job.to_string(),
+            timestamp,
+            files,
+            requirement_count: score.requirement_count,
+            finding_count: score.finding_count,
+            critical_count,
+            score: score.score,
+            word_count,
+            density: if word_count > 0 { score.finding_count as f32 * 100.0 / word_count as f32 } else { 0.0 },
+        }
+    }
+}
+
+/// Results of every scheduled run, one JSON object per line.
+pub struct AnalysisHistory {
+    path: PathBuf,
+}
+
+impl AnalysisHistory {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// `~/.prism/history.jsonl`
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(paths::prism_dir()?.join(HISTORY_FILE_NAME))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Every recorded run, oldest first. Lines that cannot be read are skipped.
+    pub fn entries(&self) -> Result<Vec<HistoryEntry>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&self.path)?;
+        Ok(content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+    }
+
+    /// The most recent run of `job`.
+    pub fn last_run(&self, job: &str) -> Result<Option<HistoryEntry>> {
+        Ok(self.entries()?.into_iter().rev().find(|entry| entry.job == job))
+    }
+
+    pub fn append(&self, entry: &HistoryEntry) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        Ok(())
+    }
+}
+
+/// Why `current` counts as a quality regression from `previous`, if it does.
+pub fn detect_regression(previous: &HistoryEntry, current: &HistoryEntry, thresholds: &RegressionThresholds) -> Vec<String> {
+    let mut reasons = Vec::new();
+
+    let drop = previous.score - current.score;
+    if drop > thresholds.max_score_drop {
+        reasons.push(format!("quality score dropped {:.0} points ({:.0} → {:.0})", drop, previous.score, current.score));
+    }
+    let new_findings = current.finding_count.saturating_sub(previous.finding_count);
+    if new_findings > thresholds.max_new_findings {
+        reasons.push(format!("{} new finding(s) ({} → {})", new_findings, previous.finding_count, current.finding_count));
+    }
+    if current.critical_count > previous.critical_count {
+        reasons.push(format!("critical findings went up ({} → {})", previous.critical_count, current.critical_count));
+    }
+    if let Some(min_score) = thresholds.min_score {
+        if current.score < min_score && previous.score >= min_score {
+            reasons.push(format!("quality score fell below {:.0} ({:.0})", min_score, current.score));
+        }
+    }
+    reasons
+}
+
+/// Notification text for a regression, e.g. for a Slack or Teams channel.
+pub fn format_regression_notification(previous: &HistoryEntry, current: &HistoryEntry, reasons: &[String]) -> String {
+    let mut message = format!(
+        "⚠️ PRISM: requirements quality of '{}' regressed since {}\n",
+        current.job,
+        previous.timestamp.format("%Y-%m-%d %H:%M UTC")
+    );
+    for reason in reasons {
+        message.push_str(&format!("• {}\n", reason));
+    }
+    message.push_str(&format!(
+        "Now: score {:.0}/100, {} finding(s) in {} requirement(s) across {} file(s), {:.1} per 100 words (was {:.1})",
+        current.score, current.finding_count, current.requirement_count, current.files, current.density, previous.density
+    ));
+    message
+}
@@ -0,0 +1,286 @@
+use std::collections::BTreeMap;
+
+use crate::analyzer::{AmbiguitySeverity, AnalysisResult, GapPriority};
+use crate::html_report::{card, findings_table, html_escape, html_page, list, section, severity_class, SEVERITIES};
+use crate::locale::report_locale;
+
+/// Dashboard-only styles, on top of the report styles.
+const DASHBOARD_CSS: &str = "
+.header { display: flex; justify-content: space-between; align-items: baseline; }
+.brand { color: #3f7cac; font-weight: 700; letter-spacing: 0.05em; text-transform: uppercase; }
+.grid { display: grid; grid-template-columns: repeat(auto-fit, minmax(300px, 1fr)); gap: 16px; }
+.panel { background: #fff; border: 1px solid #d9e2ec; border-radius: 8px; padding: 16px; }
+.panel h3 { margin-top: 0; }
+.chart-row { align-items: center; display: grid; grid-template-columns: 130px 1fr 40px; gap: 8px; margin: 6px 0; }
+.chart-row[data-filter] { cursor: pointer; }
+.chart-row .fill { border-radius: 4px; height: 18px; }
+.chart-row .fill.sev-critical { background: #c62828; } .chart-row .fill.sev-high { background: #ef6c00; }
+.chart-row .fill.sev-medium { background: #f9a825; } .chart-row .fill.sev-low { background: #2e7d32; }
+.chart-row .count { text-align: right; font-weight: 600; }
+.gauge { display: block; margin: 0 auto; max-width: 260px; }
+.status { border-radius: 6px; color: #fff; display: inline-block; font-weight: 700; padding: 4px 12px; }
+.status-ready { background: #2e7d32; } .status-work { background: #ef6c00; } .status-risk { background: #c62828; }
+";
+
+/// Clicking a severity bar toggles the matching findings filter.
+const DASHBOARD_JS: &str = "<script>
+document.querySelectorAll('.chart-row[data-filter]').forEach(function (row) {
+  row.addEventListener('click', function () {
+    var box = document.querySelector('.severity-filter[value=\"' + row.dataset.filter + '\"]');
+    if (box) { box.click(); row.style.opacity = box.checked ? '1' : '0.4'; }
+  });
+});
+</script>
+";
+
+/// Completeness below this is "at risk", below `NEEDS_WORK_COMPLETENESS` "needs work".
+const AT_RISK_COMPLETENESS: f32 = 50.0;
+const NEEDS_WORK_COMPLETENESS: f32 = 70.0;
+
+#[derive(Debug, Clone, Default)]
+pub struct DashboardOptions {
+    pub title: String,
+    /// Company or team name shown in the header
+    pub branding: Option<String>,
+    pub executive_summary: bool,
+    pub generated_at: String,
+}
+
+/// Overall readiness shown on the dashboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DashboardStatus {
+    Ready,
+    NeedsWork,
+    AtRisk,
+}
+
+impl DashboardStatus {
+    pub fn of(result: &AnalysisResult) -> Self {
+        let count = |severity| result.ambiguities.iter().filter(|a| a.severity == severity).count();
+        let completeness = result.completeness_analysis.as_ref().map(|c| c.completeness_score);
+
+        if count(AmbiguitySeverity::Critical) > 0 || completeness.is_some_and(|c| c < AT_RISK_COMPLETENESS) {
+            DashboardStatus::AtRisk
+        } else if count(AmbiguitySeverity::High) > 0 || completeness.is_some_and(|c| c < NEEDS_WORK_COMPLETENESS) {
+            DashboardStatus::NeedsWork
+        } else {
+            DashboardStatus::Ready
+        }
+    }
+
+    fn badge(&self) -> &'static str {
+        match self {
+            DashboardStatus::Ready => "<span class=\"status status-ready\">🟢 Ready</span>",
+            DashboardStatus::NeedsWork => "<span class=\"status status-work\">🟡 Needs work</span>",
+            DashboardStatus::AtRisk => "<span class=\"status status-risk\">🔴 At risk</span>",
+        }
+    }
+}
+
+/// Horizontal bar chart; rows are (label, count, severity filter), bars are
+/// scaled to the largest count.
+pub fn bar_chart(rows: &[(String, usize, Option<&str>)]) -> String {
+    let max = rows.iter().map(|(_, count, _)| *count).max().unwrap_or(0).max(1);
+    let mut chart = String::new();
+    for (label, count, filter) in rows {
+        let (filter_attr, fill_class) = match filter {
+            Some(severity) => (format!(" data-filter=\"{}\"", severity), format!(" sev-{}", severity)),
+            None => (String::new(), String::new()),
+        };
+        chart.push_str(&format!(
+            "<div class=\"chart-row\"{}><span>{}</span><div class=\"bar\" style=\"height: 18px\"><div class=\"fill{}\" style=\"width: {:.0}%\"></div></div><span class=\"count\">{}</span></div>\n",
+            filter_attr,
+            html_escape(label),
+            fill_class,
+            *count as f32 / max as f32 * 100.0,
+            count
+        ));
+    }
+    chart
+}
+
+/// Half-circle SVG gauge for a 0-100 score.
+pub fn completeness_gauge(score: f32) -> String {
+    let score = score.clamp(0.0, 100.0);
+    let arc_length = std::f32::consts::PI * 80.0;
+    let color = if score < AT_RISK_COMPLETENESS {
+        "#c62828"
+    } else if score < NEEDS_WORK_COMPLETENESS {
+        "#ef6c00"
+    } else {
+        "#2e7d32"
+    };
+    format!(
+        "<svg class=\"gauge\" viewBox=\"0 0 200 120\" role=\"img\" aria-label=\"Completeness {score:.0}%\">\
+<path d=\"M 20 100 A 80 80 0 0 1 180 100\" fill=\"none\" stroke=\"#e4e7eb\" stroke-width=\"18\"/>\
+<path d=\"M 20 100 A 80 80 0 0 1 180 100\" fill=\"none\" stroke=\"{color}\" stroke-width=\"18\" stroke-dasharray=\"{filled:.1} {arc_length:.1}\"/>\
+<text x=\"100\" y=\"95\" text-anchor=\"middle\" font-size=\"28\" font-weight=\"700\">{score:.0}%</text></svg>",
+        score = score,
+        color = color,
+        filled = arc_length * score / 100.0,
+        arc_length = arc_length
+    )
+}
+
+fn executive_summary(result: &AnalysisResult) -> String {
+    let metrics = &result.metrics;
+    let count = |severity| result.ambiguities.iter().filter(|a| a.severity == severity).count();
+    let locale = report_locale();
+    let mut summary = format!(
+        "<p>{}</p><p>{} requirement(s) and {} words analyzed. {} finding(s) ({} Critical, {} High), {} per 100 words.",
+        DashboardStatus::of(result).badge(),
+        metrics.requirement_count,
+        locale.integer(metrics.word_count),
+        result.ambiguities.len(),
+        count(AmbiguitySeverity::Critical),
+        count(AmbiguitySeverity::High),
+        locale.number(metrics.findings_per_100_words, 1)
+    );
+    if let Some(completeness) = &result.completeness_analysis {
+        summary.push_str(&format!(" Completeness is {} with {} gap(s).", locale.percent(completeness.completeness_score, 0), completeness.gaps_identified.len()));
+    }
+    summary.push_str("</p>");
+
+    let mut actions: Vec<String> = result.ambiguities.iter()
+        .filter(|a| a.severity >= AmbiguitySeverity::High)
+        .map(|a| match &a.location {
+            Some(location) => format!("Clarify \"{}\" (line {}): {}", a.text, location.line, a.reason),
+            None => format!("Clarify \"{}\": {}", a.text, a.reason),
+        })
+        .collect();
+    if let Some(completeness) = &result.completeness_analysis {
+        actions.extend(completeness.gaps_identified.iter()
+            .filter(|gap| matches!(gap.priority, GapPriority::Critical | GapPriority::High))
+            .map(|gap| format!("Close the {} gap: {}", gap.category, gap.description)));
+    }
+    if !actions.is_empty() {
+        actions.truncate(3);
+        summary.push_str(&format!("<p><strong>Top actions:</strong></p>{}", list(&actions)));
+    }
+    summary
+}
+
+/// Self-contained HTML dashboard for stakeholders: key figures, finding
+/// charts, a completeness gauge, entity tables and the NFR breakdown.
+pub fn format_dashboard_html(result: &AnalysisResult, input_text: &str, options: &DashboardOptions) -> String {
+    let mut body = String::new();
+
+    body.push_str(&format!(
+        "<div class=\"header\"><h1>📊 {}</h1><span class=\"brand\">{}</span></div>\n<p class=\"subtitle\">Requirements quality dashboard · generated {}</p>\n",
+        html_escape(&options.title),
+        html_escape(options.branding.as_deref().unwrap_or("PRISM")),
+        html_escape(&options.generated_at)
+    ));
+
+    let severe = result.ambiguities.iter().filter(|a| a.severity >= AmbiguitySeverity::High).count();
+    body.push_str("<div class=\"cards\">");
+    body.push_str(&card(&result.ambiguities.len().to_string(), "Findings"));
+    body.push_str(&card(&severe.to_string(), "Critical + High"));
+    body.push_str(&card(&result.metrics.requirement_count.to_string(), "Requirements"));
+    body.push_str(&card(&result.entities.actors.len().to_string(), "Actors"));
+    if let Some(nfrs) = &result.nfr_suggestions {
+        body.push_str(&card(&nfrs.len().to_string(), "Suggested NFRs"));
+    }
+    if let Some(tests) = &result.test_cases {
+        let total = tests.happy_path.len() + tests.negative_cases.len() + tests.edge_cases.len();
+        body.push_str(&card(&total.to_string(), "Test cases"));
+    }
+    body.push_str(&card(DashboardStatus::of(result).badge(), "Status"));
+    body.push_str("</div>\n");
+
+    if options.executive_summary {
+        body.push_str(&section("📈 Executive Summary", &executive_summary(result)));
+    }
+
+    // Charts
+    let by_severity: Vec<(String, usize, Option<&str>)> = SEVERITIES.iter()
+        .map(|severity| (
+            severity.to_string(),
+            result.ambiguities.iter().filter(|a| a.severity == *severity).count(),
+            Some(severity_class(severity)),
+        ))
+        .collect();
+    let mut by_rule: BTreeMap<&str, usize> = BTreeMap::new();
+    for ambiguity in &result.ambiguities {
+        let rule = if ambiguity.rule_id.is_empty() { "other" } else { ambiguity.rule_id.as_str() };
+        *by_rule.entry(rule).or_default() += 1;
+    }
+    let by_rule: Vec<(String, usize, Option<&str>)> = by_rule.into_iter().map(|(rule, count)| (rule.to_string(), count, None)).collect();
+
+    let mut charts = String::from("<div class=\"grid\">");
+    charts.push_str(&format!("<div class=\"panel\"><h3>Findings by severity</h3>{}<p class=\"subtitle\">Click a bar to show or hide those findings.</p></div>", bar_chart(&by_severity)));
+    charts.push_str(&format!(
+        "<div class=\"panel\"><h3>Findings by rule</h3>{}</div>",
+        if by_rule.is_empty() { "<p>✅ No ambiguities detected.</p>".to_string() } else { bar_chart(&by_rule) }
+    ));
+    if let Some(completeness) = &result.completeness_analysis {
+        charts.push_str(&format!("<div class=\"panel\"><h3>Completeness</h3>{}</div>", completeness_gauge(completeness.completeness_score)));
+    }
+    charts.push_str("</div>\n");
+    body.push_str(&section("📉 Overview", &charts));
+
+    body.push_str(&section(&format!("⚠️ Findings ({})", result.ambiguities.len()), &findings_table(&result.ambiguities)));
+
+    let entities = &result.entities;
+    body.push_str(&section("🎯 Entities", &format!(
+        "<table><tr><th>👥 Actors ({})</th><th>⚡ Actions ({})</th><th>📦 Objects ({})</th></tr><tr><td>{}</td><td>{}</td><td>{}</td></tr></table>",
+        entities.actors.len(), entities.actions.len(), entities.objects.len(),
+        list(&entities.actors), list(&entities.actions), list(&entities.objects)
+    )));
+
+    if let Some(completeness) = &result.completeness_analysis {
+        let mut gaps = String::new();
+        if completeness.gaps_identified.is_empty() {
+            gaps.push_str("<p>✅ No gaps identified.</p>");
+        } else {
+            gaps.push_str("<table><tr><th>Gap</th><th>Priority</th><th>Issue</th><th>Suggestions</th></tr>\n");
+            for gap in &completeness.gaps_identified {
+                gaps.push_str(&format!(
+                    "<tr><td>{}</td><td>{:?}</td><td>{}</td><td>{}</td></tr>\n",
+                    html_escape(&gap.category), gap.priority, html_escape(&gap.description), list(&gap.suggestions)
+                ));
+            }
+            gaps.push_str("</table>");
+        }
+        body.push_str(&section(&format!("📊 Completeness Gaps ({})", completeness.gaps_identified.len()), &gaps));
+    }
+
+    if let Some(nfrs) = &result.nfr_suggestions {
+        let mut by_category: BTreeMap<String, usize> = BTreeMap::new();
+        let mut by_priority: BTreeMap<String, usize> = BTreeMap::new();
+        for nfr in nfrs {
+            *by_category.entry(format!("{:?}", nfr.category)).or_default() += 1;
+            *by_priority.entry(format!("{:?}", nfr.priority)).or_default() += 1;
+        }
+        let to_rows = |counts: BTreeMap<String, usize>| -> Vec<(String, usize, Option<&str>)> {
+            counts.into_iter().map(|(label, count)| (label, count, None)).collect()
+        };
+
+        let mut breakdown = format!(
+            "<div class=\"grid\"><div class=\"panel\"><h3>By category</h3>{}</div><div class=\"panel\"><h3>By priority</h3>{}</div></div>",
+            bar_chart(&to_rows(by_category)),
+            bar_chart(&to_rows(by_priority))
+        );
+        breakdown.push_str("<table><tr><th>Category</th><th>Priority</th><th>Requirement</th><th>Rationale</th></tr>\n");
+        for nfr in nfrs {
+            breakdown.push_str(&format!(
+                "<tr><td>{:?}</td><td>{:?}</td><td>{}</td><td>{}</td></tr>\n",
+                nfr.category, nfr.priority, html_escape(&nfr.requirement), html_escape(&nfr.rationale)
+            ));
+        }
+        breakdown.push_str("</table>");
+        body.push_str(&section(&format!("🔒 NFR Breakdown ({})", nfrs.len()), &breakdown));
+    }
+
+    if let Some(tests) = &result.test_cases {
+        body.push_str(&section("🧪 Test Cases", &format!(
+            "<h3>Happy path</h3>{}<h3>Negative cases</h3>{}<h3>Edge cases</h3>{}",
+            list(&tests.happy_path), list(&tests.negative_cases), list(&tests.edge_cases)
+        )));
+    }
+
+    body.push_str(&section("📝 Analyzed Requirements", &format!("<pre>{}</pre>", html_escape(input_text.trim()))));
+    body.push_str(DASHBOARD_JS);
+
+    html_page(&format!("{} - Dashboard", options.title), DASHBOARD_CSS, &body, false)
+}
@@ -0,0 +1,100 @@
+use clap::ValueEnum;
+
+use crate::cli::OutputFormat;
+
+/// Anonymized sample requirements bundled with the binary for `prism demo`.
+#[derive(Debug, Clone, Copy)]
+pub struct DemoSample {
+    /// Folder and file stem used for the sample's outputs
+    pub name: &'static str,
+    pub description: &'static str,
+    pub text: &'static str,
+}
+
+pub const DEMO_SAMPLES: [DemoSample; 3] = [
+    DemoSample {
+        name: "checkout_story",
+        description: "A single user story with acceptance criteria",
+        text: include_str!("../samples/checkout_story.md"),
+    },
+    DemoSample {
+        name: "clinic_portal_spec",
+        description: "A numbered specification with several actors and an open question",
+        text: include_str!("../samples/clinic_portal_spec.md"),
+    },
+    DemoSample {
+        name: "expense_reporting_epic",
+        description: "An epic made of short user stories",
+        text: include_str!("../samples/expense_reporting_epic.md"),
+    },
+];
+
+/// Every output format, written once per sample.
+pub const DEMO_FORMATS: [OutputFormat; 12] = [
+    OutputFormat::Json,
+    OutputFormat::Markdown,
+    OutputFormat::Html,
+    OutputFormat::Github,
+    OutputFormat::Jira,
+    OutputFormat::JiraAdf,
+    OutputFormat::Plain,
+    OutputFormat::Sarif,
+    OutputFormat::Annotated,
+    OutputFormat::Diagnostics,
+    OutputFormat::Pdf,
+    OutputFormat::Csv,
+];
+
+/// File name of a sample's report in the given format.
+pub fn demo_report_name(format: &OutputFormat) -> &'static str {
+    match format {
+        OutputFormat::Json => "report.json",
+        OutputFormat::Markdown => "report.md",
+        OutputFormat::Html => "report.html",
+        OutputFormat::Github => "report.github.md",
+        OutputFormat::Jira => "report.jira.txt",
+        OutputFormat::JiraAdf => "report.jira-adf.json",
+        OutputFormat::Plain => "report.txt",
+        OutputFormat::Sarif => "report.sarif",
+        OutputFormat::Annotated => "report.annotated.txt",
+        OutputFormat::Diagnostics => "report.diagnostics.txt",
+        OutputFormat::Pdf => "report.pdf",
+        OutputFormat::Csv => "report.csv",
+    }
+}
+
+/// What the demo produced for one sample, for the overview page.
+#[derive(Debug, Clone)]
+pub struct DemoOutput {
+    pub sample: DemoSample,
+    pub findings: usize,
+    /// Artifact index written by `--save-artifacts`, relative to the sample folder
+    pub artifact_index: String,
+}
+
+pub fn format_demo_readme(outputs: &[DemoOutput]) -> String {
+    let mut output = String::new();
+
+    output.push_str("# 🎬 PRISM Demo\n\n");
+    output.push_str("These reports were generated by `prism demo` from bundled, anonymized sample requirements, ");
+    output.push_str("using the built-in analysis only. Configure an AI provider (`prism config --setup`) for deeper results.\n\n");
+
+    for demo in outputs {
+        let sample = &demo.sample;
+        output.push_str(&format!("## {}\n\n", sample.name));
+        output.push_str(&format!("{} - **{} finding(s)**\n\n", sample.description, demo.findings));
+        output.push_str(&format!("- Input: [{0}/{0}.md]({0}/{0}.md)\n", sample.name));
+        for format in DEMO_FORMATS {
+            let report = demo_report_name(&format);
+            let flag = format.to_possible_value().map(|v| v.get_name().to_string()).unwrap_or_default();
+            output.push_str(&format!("- `--format {}`: [{}/{}]({}/{})\n", flag, sample.name, report, sample.name, report));
+        }
+        output.push_str(&format!("- `--save-artifacts`: [{0}/{1}]({0}/{1})\n", sample.name, demo.artifact_index));
+        output.push('\n');
+    }
+
+    output.push_str("Run the same analysis on your own requirements with:\n\n");
+    output.push_str("```bash\nprism analyze --file requirements.md --preset full --format html --output report.html\n```\n");
+
+    output
+}
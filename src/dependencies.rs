@@ -0,0 +1,289 @@
+//! Dependencies between the requirements of a document: explicit "depends
+//! on REQ-3" statements, references to other requirement IDs and objects or
+//! actions several requirements share, as a DOT or Mermaid graph with the
+//! dependency cycles that make an implementation order impossible.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::analyzer::ExtractedEntities;
+use crate::coverage::extract_capability_lines;
+
+/// A term shared by more requirements than this is too common to link them.
+const MAX_SHARED_TERM_REQUIREMENTS: usize = 4;
+
+/// A requirement ID at the start of a line, after any list marker or emphasis.
+const LEADING_ID: &str = r"^(?:[-*+]|\d+[.)])?\s*[*_\[(]*([A-Z][A-Z0-9]*-\d+)\b";
+
+/// Longest requirement text shown in a graph node.
+const LABEL_LENGTH: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DependencyKind {
+    /// "depends on", "requires", "after", "blocked by" ... another requirement's ID
+    DependsOn,
+    /// Mentions another requirement's ID
+    References,
+    /// Both requirements mention the same object or action; undirected
+    SharesTerms,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequirementNode {
+    /// The ID written at the start of the requirement (REQ-12, US-3, ...),
+    /// else "line N"
+    pub id: String,
+    pub text: String,
+    /// 1-based; 0 for IDs referenced but not defined in the document
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: DependencyKind,
+    /// The shared objects and actions of a `SharesTerms` edge
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub terms: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DependencyGraph {
+    pub requirements: Vec<RequirementNode>,
+    pub edges: Vec<DependencyEdge>,
+    /// Dependency cycles as requirement IDs, the first one repeated at the end
+    pub cycles: Vec<Vec<String>>,
+}
+
+impl DependencyGraph {
+    /// Whether `id` is mentioned but not defined in the document.
+    pub fn is_external(&self, id: &str) -> bool {
+        self.requirements.iter().any(|r| r.id == id && r.line == 0)
+    }
+
+    fn in_cycle(&self, edge: &DependencyEdge) -> bool {
+        edge.kind != DependencyKind::SharesTerms
+            && self.cycles.iter().any(|cycle| cycle.windows(2).any(|w| w[0] == edge.from && w[1] == edge.to))
+    }
+}
+
+/// The requirements of `text`: lines with list items, user stories,
+/// requirement verbs or a leading ID, in document order.
+pub fn extract_requirement_nodes(text: &str) -> Vec<RequirementNode> {
+    let leading_id = Regex::new(LEADING_ID).unwrap();
+    let bullet = Regex::new(r"^(?:[-*+]|\d+[.)])\s+").unwrap();
+
+    let capability_lines: Vec<usize> = extract_capability_lines(text).into_iter().map(|(line, _)| line).collect();
+    let mut requirements: Vec<RequirementNode> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        let own_id = leading_id.captures(line).map(|caps| caps[1].to_string());
+        if line.starts_with('#') || (own_id.is_none() && !capability_lines.contains(&(index + 1))) {
+            continue;
+        }
+        let id = match own_id {
+            Some(id) if !requirements.iter().any(|r| r.id == id) => id,
+            Some(id) => format!("{} (line {})", id, index + 1),
+            None => format!("line {}", index + 1),
+        };
+        requirements.push(RequirementNode { id, text: bullet.replace(line, "").to_string(), line: index + 1 });
+    }
+    requirements
+}
+
+/// The requirements of `text` (see [`extract_requirement_nodes`]) and the
+/// dependencies between them.
+pub fn build_dependency_graph(text: &str, entities: &ExtractedEntities) -> DependencyGraph {
+    let id_pattern = Regex::new(r"\b([A-Z][A-Z0-9]*-\d+)\b").unwrap();
+    let leading_id = Regex::new(LEADING_ID).unwrap();
+    let depends = Regex::new(r"(?i)\b(depends? on|dependent on|requires?|requiring|needs?|after|once|blocked by|builds? on|prerequisites?)\b").unwrap();
+
+    let mut graph = DependencyGraph { requirements: extract_requirement_nodes(text), ..Default::default() };
+
+    // References to other requirement IDs, a dependency when a dependency phrase comes first in the clause
+    let mut external = Vec::new();
+    for requirement in &graph.requirements {
+        let own = leading_id.captures(&requirement.text).map(|caps| caps[1].to_string());
+        for id_match in id_pattern.find_iter(&requirement.text) {
+            let target = id_match.as_str();
+            if own.as_deref() == Some(target) {
+                continue;
+            }
+            let clause = requirement.text[..id_match.start()].rsplit(['.', ';', ',']).next().unwrap_or_default();
+            let kind = if depends.is_match(clause) { DependencyKind::DependsOn } else { DependencyKind::References };
+            if graph.edges.iter().any(|e| e.from == requirement.id && e.to == target && e.kind == kind) {
+                continue;
+            }
+            if !graph.requirements.iter().any(|r| r.id == target) && !external.contains(&target.to_string()) {
+                external.push(target.to_string());
+            }
+            graph.edges.push(DependencyEdge { from: requirement.id.clone(), to: target.to_string(), kind, terms: Vec::new() });
+        }
+    }
+    graph.requirements.extend(external.into_iter().map(|id| RequirementNode { id, text: String::new(), line: 0 }));
+
+    // Requirements sharing a specific object or action
+    let mut shared: BTreeMap<(usize, usize), Vec<String>> = BTreeMap::new();
+    let mut terms: Vec<String> = entities.objects.iter().chain(&entities.actions).map(|t| t.to_lowercase()).collect();
+    terms.sort();
+    terms.dedup();
+    for term in terms.iter().filter(|t| t.len() > 2) {
+        let Ok(word) = Regex::new(&format!(r"(?i)\b{}\b", regex::escape(term))) else { continue };
+        let mentions: Vec<usize> = graph.requirements.iter().enumerate()
+            .filter(|(_, r)| r.line > 0 && word.is_match(&r.text))
+            .map(|(i, _)| i)
+            .collect();
+        if mentions.len() < 2 || mentions.len() > MAX_SHARED_TERM_REQUIREMENTS {
+            continue;
+        }
+        for (n, &a) in mentions.iter().enumerate() {
+            for &b in &mentions[n + 1..] {
+                shared.entry((a, b)).or_default().push(term.clone());
+            }
+        }
+    }
+    for ((a, b), terms) in shared {
+        graph.edges.push(DependencyEdge {
+            from: graph.requirements[a].id.clone(),
+            to: graph.requirements[b].id.clone(),
+            kind: DependencyKind::SharesTerms,
+            terms,
+        });
+    }
+
+    graph.cycles = find_cycles(&graph);
+    graph
+}
+
+/// Cycles over the directed (depends-on and reference) edges, each found once.
+fn find_cycles(graph: &DependencyGraph) -> Vec<Vec<String>> {
+    let mut next: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in graph.edges.iter().filter(|e| e.kind != DependencyKind::SharesTerms) {
+        next.entry(edge.from.as_str()).or_default().push(edge.to.as_str());
+    }
+
+    fn visit<'a>(node: &'a str, next: &HashMap<&str, Vec<&'a str>>, stack: &mut Vec<&'a str>, done: &mut Vec<&'a str>, cycles: &mut Vec<Vec<String>>) {
+        stack.push(node);
+        for &target in next.get(node).into_iter().flatten() {
+            if let Some(start) = stack.iter().position(|n| *n == target) {
+                let mut cycle: Vec<String> = stack[start..].iter().map(|n| n.to_string()).collect();
+                let mut members = cycle.clone();
+                members.sort();
+                if !cycles.iter().any(|c| { let mut m = c[1..].to_vec(); m.sort(); m == members }) {
+                    cycle.push(target.to_string());
+                    cycles.push(cycle);
+                }
+            } else if !done.contains(&target) {
+                visit(target, next, stack, done, cycles);
+            }
+        }
+        stack.pop();
+        done.push(node);
+    }
+
+    let mut cycles = Vec::new();
+    let mut done = Vec::new();
+    for requirement in &graph.requirements {
+        if !done.contains(&requirement.id.as_str()) {
+            visit(&requirement.id, &next, &mut Vec::new(), &mut done, &mut cycles);
+        }
+    }
+    cycles
+}
+
+fn node_label(requirement: &RequirementNode) -> String {
+    let text: String = requirement.text.chars().take(LABEL_LENGTH).collect();
+    let ellipsis = if requirement.text.chars().count() > LABEL_LENGTH { "..." } else { "" };
+    if requirement.line == 0 {
+        format!("{} (not in document)", requirement.id)
+    } else if requirement.text.starts_with(&requirement.id) {
+        format!("{}{}", text, ellipsis)
+    } else {
+        format!("{}: {}{}", requirement.id, text, ellipsis)
+    }
+}
+
+/// The graph in Graphviz DOT: dependencies solid, references dashed, shared
+/// terms as dotted undirected edges and cycle edges in red.
+pub fn format_dependency_dot(graph: &DependencyGraph) -> String {
+    let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut output = String::from("digraph requirements {\n    rankdir=LR;\n    node [shape=box, fontname=\"Helvetica\"];\n\n");
+    for requirement in &graph.requirements {
+        let style = if requirement.line == 0 { ", style=dashed" } else { "" };
+        output.push_str(&format!("    {} [label={}{}];\n", quote(&requirement.id), quote(&node_label(requirement)), style));
+    }
+    output.push('\n');
+    for edge in &graph.edges {
+        let mut attributes = match edge.kind {
+            DependencyKind::DependsOn => vec!["label=\"depends on\"".to_string()],
+            DependencyKind::References => vec!["style=dashed".to_string()],
+            DependencyKind::SharesTerms => vec!["dir=none".to_string(), "style=dotted".to_string(), format!("label={}", quote(&edge.terms.join(", ")))],
+        };
+        if graph.in_cycle(edge) {
+            attributes.push("color=red".to_string());
+        }
+        output.push_str(&format!("    {} -> {} [{}];\n", quote(&edge.from), quote(&edge.to), attributes.join(", ")));
+    }
+    output.push_str("}\n");
+    output
+}
+
+/// The graph as a Mermaid flowchart, with cycle edges in red.
+pub fn format_dependency_mermaid(graph: &DependencyGraph) -> String {
+    let node_ids: HashMap<&str, String> = graph.requirements.iter().enumerate()
+        .map(|(i, r)| (r.id.as_str(), format!("R{}", i + 1)))
+        .collect();
+    let mut output = String::from("flowchart LR\n");
+    for requirement in &graph.requirements {
+        let label = node_label(requirement).replace('"', "'");
+        let shape = if requirement.line == 0 { format!("([\"{}\"])", label) } else { format!("[\"{}\"]", label) };
+        output.push_str(&format!("    {}{}\n", node_ids[requirement.id.as_str()], shape));
+    }
+    let mut cycle_links = Vec::new();
+    for (i, edge) in graph.edges.iter().enumerate() {
+        let arrow = match edge.kind {
+            DependencyKind::DependsOn => "-->|depends on|".to_string(),
+            DependencyKind::References => "-.->".to_string(),
+            DependencyKind::SharesTerms => format!("---|{}|", edge.terms.join(", ").replace('"', "'")),
+        };
+        output.push_str(&format!("    {} {} {}\n", node_ids[edge.from.as_str()], arrow, node_ids[edge.to.as_str()]));
+        if graph.in_cycle(edge) {
+            cycle_links.push(i.to_string());
+        }
+    }
+    if !cycle_links.is_empty() {
+        output.push_str(&format!("    linkStyle {} stroke:red,stroke-width:2px\n", cycle_links.join(",")));
+    }
+    output
+}
+
+/// "REQ-1 → REQ-2 → REQ-1"
+pub fn format_cycle(cycle: &[String]) -> String {
+    cycle.join(" → ")
+}
+
+/// Report section: counts, cycle warnings and the Mermaid graph.
+pub fn format_dependency_markdown(graph: &DependencyGraph) -> String {
+    let count = |kind| graph.edges.iter().filter(|e| e.kind == kind).count();
+    let mut output = format!(
+        "**Requirements:** {} | **Dependencies:** {} | **ID references:** {} | **Shared objects/actions:** {}\n\n",
+        graph.requirements.iter().filter(|r| r.line > 0).count(),
+        count(DependencyKind::DependsOn), count(DependencyKind::References), count(DependencyKind::SharesTerms)
+    );
+    for cycle in &graph.cycles {
+        output.push_str(&format!("> ⚠️ **Dependency cycle:** {} - no implementation order satisfies these requirements\n\n", format_cycle(cycle)));
+    }
+    let external: Vec<&str> = graph.requirements.iter().filter(|r| r.line == 0).map(|r| r.id.as_str()).collect();
+    if !external.is_empty() {
+        output.push_str(&format!("**Referenced but not defined here:** {}\n\n", external.join(", ")));
+    }
+    if !graph.edges.is_empty() {
+        output.push_str("```mermaid\n");
+        output.push_str(&format_dependency_mermaid(graph));
+        output.push_str("```\n\n");
+    }
+    output
+}
@@ -0,0 +1,112 @@
+//! Compiler-style diagnostics for the terminal: each finding as a block
+//! with its severity and rule, the file and position, the source line with
+//! the span underlined and the suggestions as help notes.
+
+use crate::analyzer::{rule_description, Ambiguity, AmbiguitySeverity, AnalysisResult};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const BLUE: &str = "\x1b[1;34m";
+
+/// ANSI color of a severity.
+fn severity_color(severity: &AmbiguitySeverity) -> &'static str {
+    match severity {
+        AmbiguitySeverity::Critical => "\x1b[1;35m",
+        AmbiguitySeverity::High => "\x1b[1;31m",
+        AmbiguitySeverity::Medium => "\x1b[1;33m",
+        AmbiguitySeverity::Low => "\x1b[1;36m",
+    }
+}
+
+/// Wraps text in ANSI styles, or leaves it plain.
+struct Painter {
+    color: bool,
+}
+
+impl Painter {
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.color {
+            format!("{}{}{}", style, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Every finding of `result` as a diagnostic on `input_text`, then a count
+/// by severity. `color` adds ANSI colors, for a terminal.
+pub fn format_diagnostics(result: &AnalysisResult, input_text: &str, source_name: Option<&str>, color: bool) -> String {
+    let painter = Painter { color };
+    let lines: Vec<&str> = input_text.lines().collect();
+    let width = result.ambiguities.iter()
+        .filter_map(|a| a.location.as_ref().map(|l| l.line.to_string().len()))
+        .max()
+        .unwrap_or(1);
+    let gutter = |number: &str| painter.paint(BLUE, &format!("{:>width$} |", number, width = width));
+
+    let mut output = String::new();
+    for ambiguity in &result.ambiguities {
+        let severity_color = severity_color(&ambiguity.severity);
+        let level = ambiguity.severity.to_string().to_lowercase();
+        output.push_str(&format!(
+            "{}{}\n",
+            painter.paint(severity_color, &format!("{}[{}]", level, ambiguity.rule_id)),
+            painter.paint(BOLD, &format!(": {}", ambiguity.reason)),
+        ));
+
+        let file = ambiguity.location.as_ref().and_then(|l| l.file.as_deref()).or(source_name);
+        let position = ambiguity.location.as_ref().map(|l| format!(":{}:{}", l.line, l.column)).unwrap_or_default();
+        output.push_str(&format!("{}{} {}{}\n", " ".repeat(width), painter.paint(BLUE, "-->"), file.unwrap_or("input"), position));
+
+        let source_line = ambiguity.location.as_ref().and_then(|l| lines.get(l.line.checked_sub(1)?).map(|line| (l, line)));
+        if let Some((location, line)) = source_line {
+            output.push_str(&format!("{}\n", gutter("")));
+            output.push_str(&format!("{} {}\n", gutter(&location.line.to_string()), line));
+            output.push_str(&format!("{} {}\n", gutter(""), underline(line, location.column, ambiguity, &painter)));
+        } else {
+            output.push_str(&format!("{} \"{}\" was not found in the text\n", gutter(""), ambiguity.text));
+        }
+
+        if !ambiguity.suggestions.is_empty() {
+            output.push_str(&format!("{}\n", gutter("")));
+        }
+        for suggestion in &ambiguity.suggestions {
+            output.push_str(&format!("{} {} {} {}\n", " ".repeat(width), painter.paint(BLUE, "="), painter.paint(BOLD, "help:"), suggestion));
+        }
+        output.push('\n');
+    }
+
+    output.push_str(&summary(result, &painter));
+    output
+}
+
+/// Carets under the finding, from `column` for as long as its text runs on
+/// this line, labelled with the rule's description when the reason is not
+/// already it.
+fn underline(line: &str, column: usize, ambiguity: &Ambiguity, painter: &Painter) -> String {
+    let indent: String = line.chars().take(column.saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let rest = line.chars().count().saturating_sub(column.saturating_sub(1));
+    let carets = "^".repeat(ambiguity.text.chars().count().min(rest).max(1));
+    let description = rule_description(&ambiguity.rule_id);
+    let label = if description == ambiguity.reason { String::new() } else { format!(" {}", description) };
+    format!("{}{}", indent, painter.paint(severity_color(&ambiguity.severity), &format!("{}{}", carets, label)))
+}
+
+/// E.g. "3 findings: 1 high, 2 medium", with what the report leaves out.
+fn summary(result: &AnalysisResult, painter: &Painter) -> String {
+    let hidden = result.hidden_findings.summary().map(|hidden| format!(" (not shown: {})", hidden)).unwrap_or_default();
+    if result.ambiguities.is_empty() {
+        return format!("{}{}\n", painter.paint(BOLD, "No ambiguities detected"), hidden);
+    }
+    let counts: Vec<String> = [AmbiguitySeverity::Critical, AmbiguitySeverity::High, AmbiguitySeverity::Medium, AmbiguitySeverity::Low]
+        .iter()
+        .filter_map(|severity| {
+            let count = result.ambiguities.iter().filter(|a| a.severity == *severity).count();
+            (count > 0).then(|| painter.paint(severity_color(severity), &format!("{} {}", count, severity.to_string().to_lowercase())))
+        })
+        .collect();
+    let total = result.ambiguities.len();
+    format!("{}: {}{}\n", painter.paint(BOLD, &format!("{} finding{}", total, if total == 1 { "" } else { "s" })), counts.join(", "), hidden)
+}
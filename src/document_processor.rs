@@ -1,23 +1,147 @@
 use anyhow::{Result, anyhow};
-use std::path::Path;
+use flate2::read::DeflateDecoder;
+use regex::Regex;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{ErrorKind, Read};
+use std::path::{Path, PathBuf};
 use std::fs;
+use std::sync::Arc;
 
-pub struct DocumentProcessor;
+use crate::telemetry::{start_span, SpanKind, Tracer};
+
+/// Every compound (OLE) file starts with this signature. Office stores
+/// password-protected .docx/.xlsx files in one.
+const COMPOUND_FILE_SIGNATURE: [u8; 8] = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+/// Uncompressed size an archive may expand to, so a zip bomb can't fill the disk.
+const MAX_ARCHIVE_SIZE: u64 = 512 * 1024 * 1024;
+
+/// Text recovered from the parts of a damaged .docx/.xlsx is held in memory,
+/// so it gets a smaller share of the archive limit.
+const MAX_RECOVERED_SIZE: u64 = MAX_ARCHIVE_SIZE / 32;
+
+/// A `.zip` input unpacked into a temporary directory, which is removed again on drop.
+#[derive(Debug)]
+pub struct ExtractedArchive {
+    pub dir: PathBuf,
+    /// Supported documents that were unpacked
+    pub files: usize,
+    /// Entries left out: unsupported formats, hidden files and unsafe paths
+    pub skipped: Vec<String>,
+}
+
+impl Drop for ExtractedArchive {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// A new directory under the system's temporary directory with a random
+/// name starting with `prefix`, which only the current user can access.
+/// An existing path is never reused, so nothing planted there is written into.
+pub(crate) fn create_private_temp_dir(prefix: &str) -> Result<PathBuf> {
+    for _ in 0..16 {
+        let random = std::collections::hash_map::RandomState::new().build_hasher().finish();
+        let dir = std::env::temp_dir().join(format!("{}-{:016x}", prefix, random));
+        let mut builder = fs::DirBuilder::new();
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+        match builder.create(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(anyhow!("Could not create a temporary directory in {}: {}", std::env::temp_dir().display(), e)),
+        }
+    }
+    Err(anyhow!("Could not create a temporary directory in {}", std::env::temp_dir().display()))
+}
+
+/// Document problems callers can act on, e.g. by asking for a password.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DocumentError {
+    PasswordRequired(PathBuf),
+    IncorrectPassword(PathBuf),
+    UnsupportedEncryption(PathBuf),
+    EncryptedOfficeDocument(PathBuf),
+}
+
+impl fmt::Display for DocumentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DocumentError::PasswordRequired(path) => write!(f,
+                "{} is password-protected - pass the password with --doc-password", path.display()),
+            DocumentError::IncorrectPassword(path) => write!(f,
+                "The password for {} is incorrect", path.display()),
+            DocumentError::UnsupportedEncryption(path) => write!(f,
+                "{} is encrypted with a scheme PRISM cannot read - remove the password or print it to a new PDF", path.display()),
+            DocumentError::EncryptedOfficeDocument(path) => write!(f,
+                "{} is password-protected - remove the password in Office (File > Info > Protect) or export it to PDF", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for DocumentError {}
+
+#[derive(Clone)]
+pub struct DocumentProcessor {
+    password: Option<String>,
+    tracer: Option<Arc<Tracer>>,
+}
+
+impl Default for DocumentProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl DocumentProcessor {
     pub fn new() -> Self {
-        Self
+        Self { password: None, tracer: None }
+    }
+
+    /// Password tried on password-protected PDFs.
+    pub fn with_password(mut self, password: Option<String>) -> Self {
+        self.password = password;
+        self
+    }
+
+    /// Traces every extraction as a `prism.document.extract` span.
+    pub fn with_tracer(mut self, tracer: Option<Arc<Tracer>>) -> Self {
+        self.tracer = tracer;
+        self
     }
 
     pub async fn extract_text_from_file<P: AsRef<Path>>(&self, file_path: P) -> Result<String> {
-        let path = file_path.as_ref();
+        self.extract_text(file_path.as_ref(), self.password.as_deref()).await
+    }
+
+    /// Extracts text with a password given for this file only, e.g. one the user was prompted for.
+    pub async fn extract_text_with_password<P: AsRef<Path>>(&self, file_path: P, password: &str) -> Result<String> {
+        self.extract_text(file_path.as_ref(), Some(password)).await
+    }
+
+    async fn extract_text(&self, path: &Path, password: Option<&str>) -> Result<String> {
+        let mut span = start_span(&self.tracer, "prism.document.extract", SpanKind::Internal);
+        let text = self.extract_text_by_format(path, password).await;
+        if let Some(span) = &mut span {
+            span.set_attribute("file.name", path.file_name().unwrap_or_default().to_string_lossy().to_string());
+            span.set_attribute("file.extension", path.extension().unwrap_or_default().to_string_lossy().to_lowercase());
+            match &text {
+                Ok(text) => span.set_attribute("prism.text_length", text.len()),
+                Err(e) => span.set_error(e),
+            }
+        }
+        text
+    }
+
+    async fn extract_text_by_format(&self, path: &Path, password: Option<&str>) -> Result<String> {
         let extension = path.extension()
             .and_then(|ext| ext.to_str())
             .ok_or_else(|| anyhow!("Unable to determine file extension"))?
             .to_lowercase();
 
         match extension.as_str() {
-            "pdf" => self.extract_pdf_text(path).await,
+            "pdf" => self.extract_pdf_text(path, password).await,
             "docx" => self.extract_docx_text(path).await,
             "xlsx" => self.extract_xlsx_text(path).await,
             "txt" | "md" | "rst" => {
@@ -28,20 +152,46 @@ impl DocumentProcessor {
         }
     }
 
-    async fn extract_pdf_text<P: AsRef<Path>>(&self, path: P) -> Result<String> {
+    async fn extract_pdf_text(&self, path: &Path, password: Option<&str>) -> Result<String> {
         let bytes = fs::read(path)?;
-        let text = pdf_extract::extract_text_from_mem(&bytes)
-            .map_err(|e| anyhow!("Failed to extract PDF text: {}", e))?;
+        let text = if is_encrypted_pdf(&bytes) {
+            self.decrypt_pdf_text(path, &bytes, password)?
+        } else {
+            pdf_extract::extract_text_from_mem(&bytes)
+                .map_err(|e| anyhow!("Failed to extract PDF text: {}", e))?
+        };
         
         // Clean up extracted text
         let cleaned_text = self.clean_extracted_text(&text);
         Ok(cleaned_text)
     }
 
+    fn decrypt_pdf_text(&self, path: &Path, bytes: &[u8], password: Option<&str>) -> Result<String> {
+        // PDFs restricted only by an owner password open with an empty user password
+        let error = match pdf_extract::extract_text_from_mem_encrypted(bytes, "") {
+            Ok(text) => return Ok(text),
+            Err(e) => e.to_string(),
+        };
+        if !error.contains("password is incorrect") {
+            return Err(DocumentError::UnsupportedEncryption(path.to_path_buf()).into());
+        }
+
+        let password = password.ok_or_else(|| DocumentError::PasswordRequired(path.to_path_buf()))?;
+        pdf_extract::extract_text_from_mem_encrypted(bytes, password)
+            .map_err(|_| DocumentError::IncorrectPassword(path.to_path_buf()).into())
+    }
+
     async fn extract_docx_text<P: AsRef<Path>>(&self, path: P) -> Result<String> {
+        let path = path.as_ref();
         let bytes = fs::read(path)?;
-        let docx = docx_rs::read_docx(&bytes)
-            .map_err(|e| anyhow!("Failed to read DOCX file: {}", e))?;
+        check_office_encryption(path, &bytes)?;
+        let docx = match docx_rs::read_docx(&bytes) {
+            Ok(docx) => docx,
+            Err(e) => {
+                let recovered = self.recover_office_text(path, &bytes, "word/document.xml", "w:t", &["w:p"]);
+                return recovered.ok_or_else(|| anyhow!("Failed to read DOCX file: {}", e));
+            }
+        };
         
         // Extract text from all paragraphs
         let mut text = String::new();
@@ -70,9 +220,17 @@ impl DocumentProcessor {
     async fn extract_xlsx_text<P: AsRef<Path>>(&self, path: P) -> Result<String> {
         use calamine::{Reader, Xlsx, open_workbook};
         
-        let mut workbook: Xlsx<_> = open_workbook(path)
-            .map_err(|e| anyhow!("Failed to open XLSX file: {}", e))?;
-        
+        let path = path.as_ref();
+        let mut workbook: Xlsx<_> = match open_workbook(path) {
+            Ok(workbook) => workbook,
+            Err(e) => {
+                let bytes = fs::read(path)?;
+                check_office_encryption(path, &bytes)?;
+                // Cell text lives in the shared strings table and in inline strings of each sheet
+                let recovered = self.recover_office_text(path, &bytes, "xl/", "t", &["si", "row"]);
+                return recovered.ok_or_else(|| anyhow!("Failed to open XLSX file: {}", e));
+            }
+        };
         let mut text = String::new();
         
         // Process all worksheets
@@ -113,6 +271,37 @@ impl DocumentProcessor {
         Ok(cleaned_text)
     }
 
+    /// Recovers the text of a damaged .docx/.xlsx from whatever parts of the
+    /// zip can still be read. Returns None when nothing could be recovered.
+    fn recover_office_text(&self, path: &Path, bytes: &[u8], part_prefix: &str, text_tag: &str, break_tags: &[&str]) -> Option<String> {
+        let (entries, truncated) = recover_zip_entries(bytes, part_prefix);
+        let text: String = entries
+            .iter()
+            .filter(|(name, _)| name.ends_with(".xml"))
+            .map(|(_, xml)| xml_text(xml, text_tag, break_tags))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let cleaned_text = self.clean_extracted_text(&text);
+        if cleaned_text.is_empty() {
+            return None;
+        }
+
+        eprintln!(
+            "⚠️  {} is damaged - recovered {} characters of text, check the results against the original",
+            path.display(),
+            cleaned_text.len()
+        );
+        if let Some(part) = truncated {
+            eprintln!(
+                "⚠️  {} of {} expands to more than {} MB - only text before that was recovered",
+                part,
+                path.display(),
+                MAX_RECOVERED_SIZE / (1024 * 1024)
+            );
+        }
+        Some(cleaned_text)
+    }
+
     fn clean_extracted_text(&self, text: &str) -> String {
         // Remove excessive whitespace and clean up text
         text.lines()
@@ -122,6 +311,69 @@ impl DocumentProcessor {
             .join("\n")
     }
 
+    pub fn is_archive<P: AsRef<Path>>(&self, file_path: P) -> bool {
+        file_path.as_ref().extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+    }
+
+    /// Unpacks the supported documents of a `.zip` archive (e.g. a zipped
+    /// requirements folder) into a temporary directory, keeping its folders.
+    pub fn extract_archive<P: AsRef<Path>>(&self, archive_path: P) -> Result<ExtractedArchive> {
+        let archive_path = archive_path.as_ref();
+        let file = fs::File::open(archive_path)?;
+        let mut archive = zip::ZipArchive::new(file)
+            .map_err(|e| anyhow!("Failed to open ZIP archive {}: {}", archive_path.display(), e))?;
+
+        let stem = archive_path.file_stem().unwrap_or_default().to_string_lossy();
+        let dir = create_private_temp_dir(&format!("prism-{}", stem))?;
+        let mut extracted = ExtractedArchive { dir, files: 0, skipped: Vec::new() };
+
+        let mut total_size = 0;
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index)?;
+            if entry.is_dir() {
+                continue;
+            }
+
+            // enclosed_name rejects absolute paths and `..` that would escape the directory
+            let relative = match entry.enclosed_name() {
+                Some(relative) => relative.to_path_buf(),
+                None => {
+                    extracted.skipped.push(entry.name().to_string());
+                    continue;
+                }
+            };
+            let hidden = relative.components().any(|c| {
+                let part = c.as_os_str().to_string_lossy();
+                part.starts_with('.') || part == "__MACOSX"
+            });
+            if hidden || !self.is_supported_format(&relative) {
+                extracted.skipped.push(entry.name().to_string());
+                continue;
+            }
+
+            total_size += entry.size();
+            if total_size > MAX_ARCHIVE_SIZE {
+                return Err(anyhow!(
+                    "{} expands to more than {} MB - unpack it and use --dir instead",
+                    archive_path.display(),
+                    MAX_ARCHIVE_SIZE / (1024 * 1024)
+                ));
+            }
+
+            let target = extracted.dir.join(&relative);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let size = entry.size();
+            std::io::copy(&mut (&mut entry).take(size), &mut fs::File::create(&target)?)?;
+            extracted.files += 1;
+        }
+
+        Ok(extracted)
+    }
+
     pub fn is_supported_format<P: AsRef<Path>>(&self, file_path: P) -> bool {
         if let Some(extension) = file_path.as_ref().extension() {
             if let Some(ext_str) = extension.to_str() {
@@ -138,6 +390,112 @@ impl DocumentProcessor {
     }
 }
 
+/// Whether the trailer names an encryption dictionary. A content stream or
+/// metadata that merely contains the text `/Encrypt` does not count.
+fn is_encrypted_pdf(bytes: &[u8]) -> bool {
+    pdf_extract::Document::load_mem(bytes).is_ok_and(|doc| doc.is_encrypted())
+}
+
+/// Office saves password-protected .docx/.xlsx files as a compound file
+/// holding an EncryptionInfo stream instead of a zip.
+fn check_office_encryption(path: &Path, bytes: &[u8]) -> Result<()> {
+    if !bytes.starts_with(&COMPOUND_FILE_SIGNATURE) {
+        return Ok(());
+    }
+
+    let stream_name: Vec<u8> = "EncryptionInfo".encode_utf16().flat_map(|c| c.to_le_bytes()).collect();
+    if bytes.windows(stream_name.len()).any(|w| w == stream_name.as_slice()) {
+        return Err(DocumentError::EncryptedOfficeDocument(path.to_path_buf()).into());
+    }
+    Err(anyhow!("{} is a legacy Office (.doc/.xls) file - save it as .docx/.xlsx", path.display()))
+}
+
+/// Reads the zip entries whose name starts with `prefix` by walking the local
+/// file headers, so a missing central directory or a truncated entry still
+/// yields whatever data precedes the damage. Inflating stops once the entries
+/// add up to `MAX_RECOVERED_SIZE`; the entry it stopped in is returned too.
+fn recover_zip_entries(bytes: &[u8], prefix: &str) -> (Vec<(String, String)>, Option<String>) {
+    const LOCAL_HEADER: &[u8] = b"PK\x03\x04";
+    const LOCAL_HEADER_LEN: usize = 30;
+    let read_u16 = |at: usize| u16::from_le_bytes([bytes[at], bytes[at + 1]]) as usize;
+
+    let mut entries = Vec::new();
+    let mut remaining = MAX_RECOVERED_SIZE as usize;
+    let mut offset = 0;
+    while let Some(found) = bytes[offset..].windows(LOCAL_HEADER.len()).position(|w| w == LOCAL_HEADER) {
+        let start = offset + found;
+        offset = start + LOCAL_HEADER.len();
+        if start + LOCAL_HEADER_LEN > bytes.len() {
+            break;
+        }
+
+        let method = read_u16(start + 8);
+        let compressed_size = u32::from_le_bytes([bytes[start + 18], bytes[start + 19], bytes[start + 20], bytes[start + 21]]) as usize;
+        let name_start = start + LOCAL_HEADER_LEN;
+        let name_end = name_start + read_u16(start + 26);
+        let data_start = name_end + read_u16(start + 28);
+        let (Some(name), Some(data)) = (bytes.get(name_start..name_end), bytes.get(data_start..)) else {
+            break;
+        };
+        let name = String::from_utf8_lossy(name).to_string();
+        if !name.starts_with(prefix) {
+            continue;
+        }
+
+        let mut content = Vec::new();
+        match method {
+            0 => content.extend_from_slice(&data[..compressed_size.min(data.len())]),
+            8 => {
+                // One byte past the limit tells a part that fills it from one that exceeds it
+                let mut decoder = DeflateDecoder::new(data).take(remaining as u64 + 1);
+                let mut buffer = [0u8; 8192];
+                // Keep everything inflated before the stream breaks off
+                while let Ok(read) = decoder.read(&mut buffer) {
+                    if read == 0 {
+                        break;
+                    }
+                    content.extend_from_slice(&buffer[..read]);
+                }
+            }
+            _ => continue,
+        }
+        let truncated = content.len() > remaining;
+        content.truncate(remaining);
+        remaining -= content.len();
+        entries.push((name.clone(), String::from_utf8_lossy(&content).to_string()));
+        if truncated {
+            return (entries, Some(name));
+        }
+    }
+
+    (entries, None)
+}
+
+/// Text inside `<text_tag>` elements of an Office XML part, with a line
+/// break after each of `break_tags`.
+fn xml_text(xml: &str, text_tag: &str, break_tags: &[&str]) -> String {
+    let breaks = break_tags.iter().map(|tag| format!("</{}>", regex::escape(tag))).collect::<Vec<_>>().join("|");
+    let pattern = format!(r"<{0}(?:\s[^>]*)?>([^<]*)</{0}>|{1}", regex::escape(text_tag), breaks);
+    let regex = Regex::new(&pattern).unwrap();
+
+    let mut text = String::new();
+    for captures in regex.captures_iter(xml) {
+        match captures.get(1) {
+            Some(run) => text.push_str(&unescape_xml(run.as_str())),
+            None => text.push('\n'),
+        }
+    }
+    text
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,240 @@
+//! A DDD-style domain model proposed from the extracted entities: objects
+//! that actions change become aggregate roots, value-like objects (address,
+//! amount, date, ...) value objects, the remaining objects entities inside
+//! the aggregate they are mentioned with, and every action a command on its
+//! aggregate that publishes a domain event.
+
+use serde::{Deserialize, Serialize};
+
+use crate::analyzer::ExtractedEntities;
+use crate::architecture::{operations, pascal_case, FALLBACK_OBJECT};
+
+/// Objects named with one of these words are described by their value, not
+/// tracked by identity.
+const VALUE_WORDS: &[&str] = &[
+    "address", "amount", "price", "cost", "fee", "money", "currency", "total", "balance", "email", "phone",
+    "date", "time", "deadline", "period", "duration", "range", "quantity", "name", "location", "coordinates",
+    "rating", "score", "percentage", "rate", "status", "color", "colour", "size", "weight", "dimensions",
+];
+
+/// Past tenses the -ed rules get wrong.
+const IRREGULAR_PAST: &[(&str, &str)] = &[
+    ("buy", "bought"), ("build", "built"), ("choose", "chosen"), ("find", "found"), ("get", "got"),
+    ("give", "given"), ("hold", "held"), ("make", "made"), ("pay", "paid"), ("put", "put"), ("run", "run"),
+    ("see", "seen"), ("sell", "sold"), ("send", "sent"), ("set", "set"), ("take", "taken"), ("write", "written"),
+    ("cancel", "cancelled"), ("submit", "submitted"), ("commit", "committed"), ("transfer", "transferred"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DomainCommand {
+    /// The action, e.g. "place"
+    pub action: String,
+    /// "PlaceOrder"
+    pub command: String,
+    /// "OrderPlaced"
+    pub event: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Aggregate {
+    /// The aggregate root, e.g. "order"
+    pub root: String,
+    /// Entities with their own identity inside the aggregate
+    pub entities: Vec<String>,
+    pub value_objects: Vec<String>,
+    pub commands: Vec<DomainCommand>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DomainModel {
+    pub aggregates: Vec<Aggregate>,
+    /// Events of actions whose requirement names no object to put them on
+    #[serde(default)]
+    pub unassigned_events: Vec<String>,
+}
+
+/// "place" -> "placed", "pay" -> "paid", "log in" -> "logged in"
+pub fn past_tense(action: &str) -> String {
+    let mut words = action.split_whitespace();
+    let verb = words.next().unwrap_or_default().to_lowercase();
+    let rest: Vec<&str> = words.collect();
+    let vowel = |c: char| "aeiou".contains(c);
+    let chars: Vec<char> = verb.chars().collect();
+
+    let past = if let Some((_, past)) = IRREGULAR_PAST.iter().find(|(v, _)| *v == verb) {
+        past.to_string()
+    } else if verb.ends_with('e') {
+        format!("{}d", verb)
+    } else if verb.ends_with('y') && chars.len() > 1 && !vowel(chars[chars.len() - 2]) {
+        format!("{}ied", &verb[..verb.len() - 1])
+    } else if chars.len() >= 3 && chars.len() <= 4
+        && !vowel(chars[chars.len() - 1]) && !"wxy".contains(chars[chars.len() - 1])
+        && vowel(chars[chars.len() - 2]) && !vowel(chars[chars.len() - 3]) {
+        // stop -> stopped, log -> logged
+        format!("{}{}ed", verb, chars[chars.len() - 1])
+    } else {
+        format!("{}ed", verb)
+    };
+    std::iter::once(past.as_str()).chain(rest).collect::<Vec<&str>>().join(" ")
+}
+
+fn is_value_object(object: &str) -> bool {
+    object.to_lowercase().split(|c: char| !c.is_alphanumeric())
+        .any(|w| VALUE_WORDS.contains(&w) || VALUE_WORDS.contains(&w.trim_end_matches('s')))
+}
+
+/// Sentences of `text` that mention both `a` and `b`.
+fn co_mentions(sentences: &[String], a: &str, b: &str) -> usize {
+    sentences.iter().filter(|s| s.contains(&a.to_lowercase()) && s.contains(&b.to_lowercase())).count()
+}
+
+/// Proposes aggregates for the objects and actions of `text`. Other objects
+/// and value objects join the aggregate whose root they are mentioned with
+/// most, else the first one.
+pub fn build_domain_model(text: &str, entities: &ExtractedEntities) -> DomainModel {
+    let sentences: Vec<String> = text.split(['.', '!', '?', '\n']).map(|s| s.to_lowercase()).collect();
+    let operations = operations(text, entities);
+    let mut objects: Vec<String> = Vec::new();
+    for object in entities.objects.iter().map(|o| o.to_lowercase()) {
+        if !objects.contains(&object) {
+            objects.push(object);
+        }
+    }
+
+    let mut roots: Vec<String> = Vec::new();
+    for op in &operations {
+        if op.object != FALLBACK_OBJECT && !roots.contains(&op.object) && !is_value_object(&op.object) {
+            roots.push(op.object.clone());
+        }
+    }
+    if roots.is_empty() {
+        if let Some(first) = objects.iter().find(|o| !is_value_object(o)) {
+            roots.push(first.clone());
+        }
+    }
+
+    let mut aggregates: Vec<Aggregate> = roots.iter().map(|root| Aggregate {
+        root: root.clone(),
+        entities: Vec::new(),
+        value_objects: Vec::new(),
+        commands: Vec::new(),
+    }).collect();
+    if aggregates.is_empty() {
+        return DomainModel {
+            aggregates,
+            unassigned_events: operations.iter().map(|op| pascal_case(&past_tense(&op.action))).collect(),
+        };
+    }
+
+    for object in objects.iter().filter(|o| !roots.contains(o)) {
+        // The first aggregate takes what no root is mentioned with
+        let owner = (0..aggregates.len())
+            .map(|i| (i, co_mentions(&sentences, &aggregates[i].root, object)))
+            .filter(|(_, mentions)| *mentions > 0)
+            .max_by_key(|(i, mentions)| (*mentions, std::cmp::Reverse(*i)))
+            .map_or(0, |(i, _)| i);
+        let aggregate = &mut aggregates[owner];
+        if is_value_object(object) {
+            aggregate.value_objects.push(object.clone());
+        } else {
+            aggregate.entities.push(object.clone());
+        }
+    }
+
+    // Actions on an entity or value object go through its aggregate root
+    let mut unassigned_events = Vec::new();
+    for op in &operations {
+        let owner = aggregates.iter().position(|a| a.root == op.object)
+            .or_else(|| aggregates.iter().position(|a| a.entities.contains(&op.object) || a.value_objects.contains(&op.object)));
+        match owner.map(|i| &mut aggregates[i]) {
+            Some(aggregate) => aggregate.commands.push(DomainCommand {
+                action: op.action.clone(),
+                command: pascal_case(&format!("{} {}", op.action, op.object)),
+                event: pascal_case(&format!("{} {}", op.object, past_tense(&op.action))),
+            }),
+            None => unassigned_events.push(pascal_case(&past_tense(&op.action))),
+        }
+    }
+    DomainModel { aggregates, unassigned_events }
+}
+
+/// "order item" -> "orderItem"
+fn member_name(name: &str) -> String {
+    let type_name = pascal_case(name);
+    let mut chars = type_name.chars();
+    chars.next().map(|first| first.to_lowercase().collect::<String>() + chars.as_str()).unwrap_or_default()
+}
+
+fn code_list(names: &[String]) -> String {
+    names.iter().map(|n| format!("`{}`", pascal_case(n))).collect::<Vec<_>>().join(", ")
+}
+
+pub fn format_domain_model_markdown(model: &DomainModel) -> String {
+    if model.aggregates.is_empty() {
+        return "No objects were found to build a domain model from.\n\n".to_string();
+    }
+    let mut output = String::new();
+    for aggregate in &model.aggregates {
+        let root = pascal_case(&aggregate.root);
+        output.push_str(&format!("### 🏛️ {} Aggregate\n\n", root));
+        output.push_str(&format!("- **Aggregate root:** `{}` (identity `{}Id`)\n", root, root));
+        if !aggregate.entities.is_empty() {
+            output.push_str(&format!("- **Entities:** {}\n", code_list(&aggregate.entities)));
+        }
+        if !aggregate.value_objects.is_empty() {
+            output.push_str(&format!("- **Value objects:** {}\n", code_list(&aggregate.value_objects)));
+        }
+        if aggregate.commands.is_empty() {
+            output.push_str("- **Commands:** none found; add the actions that change it\n");
+        } else {
+            output.push_str("\n| Command | Domain event |\n|---|---|\n");
+            for command in &aggregate.commands {
+                output.push_str(&format!("| `{}` | `{}` |\n", command.command, command.event));
+            }
+        }
+        output.push('\n');
+    }
+    if !model.unassigned_events.is_empty() {
+        output.push_str(&format!(
+            "**Events without an aggregate:** {} - name the object these actions change to place them.\n\n",
+            model.unassigned_events.iter().map(|e| format!("`{}`", e)).collect::<Vec<_>>().join(", ")
+        ));
+    }
+    output
+}
+
+/// PlantUML class diagram with one package per aggregate.
+pub fn format_domain_model_plantuml(model: &DomainModel) -> String {
+    let mut uml = String::from("@startuml\nhide empty members\n");
+    for aggregate in &model.aggregates {
+        let root = pascal_case(&aggregate.root);
+        uml.push_str(&format!("\npackage \"{} Aggregate\" {{\n", root));
+        uml.push_str(&format!("  class {} <<Aggregate Root>> {{\n    id: {}Id\n", root, root));
+        for value_object in &aggregate.value_objects {
+            uml.push_str(&format!("    {}: {}\n", member_name(value_object), pascal_case(value_object)));
+        }
+        for command in &aggregate.commands {
+            uml.push_str(&format!("    {}()\n", member_name(&command.action)));
+        }
+        uml.push_str("  }\n");
+        for entity in &aggregate.entities {
+            let name = pascal_case(entity);
+            uml.push_str(&format!("  class {} <<Entity>> {{\n    id: {}Id\n  }}\n", name, name));
+            uml.push_str(&format!("  {} *-- \"0..*\" {}\n", root, name));
+        }
+        for value_object in &aggregate.value_objects {
+            uml.push_str(&format!("  class {} <<Value Object>>\n", pascal_case(value_object)));
+        }
+        for command in &aggregate.commands {
+            uml.push_str(&format!("  class {} <<Domain Event>> {{\n    {}Id: {}Id\n    occurredAt: Instant\n  }}\n",
+                command.event, member_name(&aggregate.root), root));
+            uml.push_str(&format!("  {} ..> {} : publishes\n", root, command.event));
+        }
+        uml.push_str("}\n");
+    }
+    for event in &model.unassigned_events {
+        uml.push_str(&format!("\nclass {} <<Domain Event>>\n", event));
+    }
+    uml.push_str("@enduml\n");
+    uml
+}
@@ -0,0 +1,155 @@
+//! Near-duplicate requirements across the documents of a batch, so large
+//! specification repositories can be consolidated.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+use crate::coverage::{content_terms, extract_capability_lines};
+use crate::locale::report_locale;
+
+/// Statements at least this similar are reported as duplicates.
+pub const DEFAULT_DUPLICATE_THRESHOLD: f32 = 0.8;
+
+/// Name of the deduplication report written next to a batch's reports.
+pub const DUPLICATES_REPORT_NAME: &str = "duplicates.md";
+
+/// Statements with fewer content words are too generic to compare.
+const MIN_TERMS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequirementStatement {
+    pub file: String,
+    /// 1-based
+    pub line: usize,
+    pub text: String,
+}
+
+/// Statements that say the same thing in different documents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateCluster {
+    pub statements: Vec<RequirementStatement>,
+    /// Highest similarity between two statements of the cluster
+    pub similarity: f32,
+}
+
+impl DuplicateCluster {
+    pub fn files(&self) -> BTreeSet<&str> {
+        self.statements.iter().map(|s| s.file.as_str()).collect()
+    }
+}
+
+/// The requirement statements of a document (list items, user stories and
+/// sentences with a requirement verb) with their line numbers.
+pub fn extract_statements(file: &str, text: &str) -> Vec<RequirementStatement> {
+    extract_capability_lines(text).into_iter()
+        .map(|(line, text)| RequirementStatement { file: file.to_string(), line, text })
+        .collect()
+}
+
+struct TermVector {
+    counts: HashMap<String, f32>,
+    norm: f32,
+}
+
+impl TermVector {
+    fn new(text: &str) -> Self {
+        let mut counts: HashMap<String, f32> = HashMap::new();
+        for term in content_terms(text) {
+            *counts.entry(term).or_insert(0.0) += 1.0;
+        }
+        let norm = counts.values().map(|x| x * x).sum::<f32>().sqrt();
+        Self { counts, norm }
+    }
+
+    fn cosine(&self, other: &TermVector) -> f32 {
+        let dot: f32 = self.counts.iter().filter_map(|(term, x)| other.counts.get(term).map(|y| x * y)).sum();
+        dot / (self.norm * other.norm)
+    }
+}
+
+fn find(parents: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    parents[i] = root;
+    root
+}
+
+/// Groups statements whose normalized text similarity (cosine of stemmed
+/// content words) is at least `threshold`, directly or through another
+/// statement of the group. Only groups spanning two or more files are
+/// returned, most similar first.
+pub fn find_duplicate_clusters(statements: &[RequirementStatement], threshold: f32) -> Vec<DuplicateCluster> {
+    let vectors: Vec<TermVector> = statements.iter().map(|s| TermVector::new(&s.text)).collect();
+    let comparable: Vec<usize> = (0..statements.len()).filter(|&i| vectors[i].counts.len() >= MIN_TERMS).collect();
+
+    // Only statements sharing a term can be similar
+    let mut by_term: HashMap<&str, Vec<usize>> = HashMap::new();
+    for &i in &comparable {
+        for term in vectors[i].counts.keys() {
+            by_term.entry(term.as_str()).or_default().push(i);
+        }
+    }
+
+    let mut parents: Vec<usize> = (0..statements.len()).collect();
+    let mut best = vec![0.0f32; statements.len()];
+    for &i in &comparable {
+        let mut candidates: Vec<usize> = vectors[i].counts.keys()
+            .flat_map(|term| by_term[term.as_str()].iter().copied())
+            .filter(|&j| j > i)
+            .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+        for j in candidates {
+            let score = vectors[i].cosine(&vectors[j]);
+            if score >= threshold {
+                let (a, b) = (find(&mut parents, i), find(&mut parents, j));
+                parents[a] = b;
+                let link = best[a].max(best[b]).max(score);
+                best[b] = link;
+            }
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for &i in &comparable {
+        let root = find(&mut parents, i);
+        groups.entry(root).or_default().push(i);
+    }
+
+    let mut clusters: Vec<DuplicateCluster> = groups.into_iter()
+        .map(|(root, members)| DuplicateCluster {
+            statements: members.into_iter().map(|i| statements[i].clone()).collect(),
+            similarity: best[root],
+        })
+        .filter(|cluster| cluster.files().len() > 1)
+        .collect();
+    clusters.sort_by(|a, b| {
+        b.similarity.total_cmp(&a.similarity)
+            .then_with(|| (&a.statements[0].file, a.statements[0].line).cmp(&(&b.statements[0].file, b.statements[0].line)))
+    });
+    clusters
+}
+
+pub fn format_duplicates_markdown(source: &Path, clusters: &[DuplicateCluster], threshold: f32) -> String {
+    let mut output = String::from("# 🔁 Duplicate Requirements\n\n");
+    output.push_str(&format!("**Source:** `{}`\n\n", source.display()));
+    output.push_str(&format!(
+        "**Clusters:** {} (statements at least {} similar across files)\n\n",
+        clusters.len(), report_locale().percent(threshold * 100.0, 0)
+    ));
+
+    for (i, cluster) in clusters.iter().enumerate() {
+        output.push_str(&format!(
+            "## Cluster {} - {} statements in {} files ({} similar)\n\n",
+            i + 1, cluster.statements.len(), cluster.files().len(), report_locale().percent(cluster.similarity * 100.0, 0)
+        ));
+        for statement in &cluster.statements {
+            output.push_str(&format!("- `{}:{}` - {}\n", statement.file, statement.line, statement.text));
+        }
+        output.push('\n');
+    }
+    output
+}
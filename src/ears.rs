@@ -0,0 +1,262 @@
+//! EARS (Easy Approach to Requirements Syntax) conformance: which pattern
+//! each requirement sentence follows, and a rewrite into the closest
+//! pattern for the sentences that follow none.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use crate::locale::report_locale;
+use crate::text::capitalize;
+
+/// Sentences with one of these words state a requirement.
+const REQUIREMENT_WORDS: &str = r"(?i)\b(shall|must|should|will|can|may|needs? to|has to|have to|is required to|are required to|wants? to|want|would like)\b";
+
+/// Subjects that are people rather than the system: "users should be able
+/// to export" becomes "the system shall allow the users to export".
+const PEOPLE: &[&str] = &[
+    "user", "customer", "admin", "administrator", "manager", "operator", "visitor", "member", "staff",
+    "employee", "guest", "client", "owner", "agent", "reviewer", "author", "editor", "buyer", "seller",
+    "i", "we", "you", "they", "people", "person", "everyone", "anyone",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EarsPattern {
+    /// The <system> shall <response>
+    Ubiquitous,
+    /// When <trigger>, the <system> shall <response>
+    EventDriven,
+    /// While <state>, the <system> shall <response>
+    StateDriven,
+    /// If <condition>, then the <system> shall <response>
+    UnwantedBehavior,
+    /// Where <feature is included>, the <system> shall <response>
+    Optional,
+}
+
+impl EarsPattern {
+    pub const ALL: [EarsPattern; 5] = [
+        EarsPattern::Ubiquitous,
+        EarsPattern::EventDriven,
+        EarsPattern::StateDriven,
+        EarsPattern::UnwantedBehavior,
+        EarsPattern::Optional,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EarsPattern::Ubiquitous => "Ubiquitous",
+            EarsPattern::EventDriven => "Event-driven",
+            EarsPattern::StateDriven => "State-driven",
+            EarsPattern::UnwantedBehavior => "Unwanted behavior",
+            EarsPattern::Optional => "Optional feature",
+        }
+    }
+
+    pub fn template(&self) -> &'static str {
+        match self {
+            EarsPattern::Ubiquitous => "The <system> shall <response>",
+            EarsPattern::EventDriven => "When <trigger>, the <system> shall <response>",
+            EarsPattern::StateDriven => "While <state>, the <system> shall <response>",
+            EarsPattern::UnwantedBehavior => "If <condition>, then the <system> shall <response>",
+            EarsPattern::Optional => "Where <feature>, the <system> shall <response>",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EarsSentence {
+    /// 1-based line the sentence is on
+    pub line: usize,
+    pub text: String,
+    /// None when the sentence fits no pattern
+    pub pattern: Option<EarsPattern>,
+    /// The sentence rewritten into the closest pattern, when it fits none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EarsReport {
+    pub sentences: Vec<EarsSentence>,
+}
+
+impl EarsReport {
+    pub fn nonconforming(&self) -> Vec<&EarsSentence> {
+        self.sentences.iter().filter(|s| s.pattern.is_none()).collect()
+    }
+
+    pub fn count(&self, pattern: EarsPattern) -> usize {
+        self.sentences.iter().filter(|s| s.pattern == Some(pattern)).count()
+    }
+
+    /// Percentage of requirement sentences that follow a pattern; 100 when there are none.
+    pub fn conformance(&self) -> f32 {
+        if self.sentences.is_empty() {
+            return 100.0;
+        }
+        (self.sentences.len() - self.nonconforming().len()) as f32 * 100.0 / self.sentences.len() as f32
+    }
+}
+
+/// Splits a line after sentence-ending punctuation followed by whitespace.
+fn split_sentences(line: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if matches!(c, '.' | '!' | '?') && chars.peek().is_some_and(|(_, next)| next.is_whitespace()) {
+            sentences.push(&line[start..=i]);
+            start = i + 1;
+        }
+    }
+    sentences.push(&line[start..]);
+    sentences.into_iter().map(|s| s.trim()).filter(|s| !s.is_empty()).collect()
+}
+
+/// The EARS pattern `sentence` follows, if any. A ubiquitous requirement
+/// with a trailing "when"/"if"/"while" clause is really event-driven,
+/// unwanted-behavior or state-driven written in the wrong order, so it
+/// does not count.
+pub fn classify_sentence(sentence: &str) -> Option<EarsPattern> {
+    let sentence = sentence.trim().trim_end_matches(['.', '!', ';']);
+    let preamble = Regex::new(r"(?i)^(where|if|when|while)\s+.+?,\s*(then\s+)?the\s+\S.*?\s+shall\s+\S").unwrap();
+    if let Some(caps) = preamble.captures(sentence) {
+        let then = caps.get(2).is_some();
+        return match (caps[1].to_lowercase().as_str(), then) {
+            ("where", false) => Some(EarsPattern::Optional),
+            ("if", true) => Some(EarsPattern::UnwantedBehavior),
+            ("when", false) => Some(EarsPattern::EventDriven),
+            ("while", false) => Some(EarsPattern::StateDriven),
+            _ => None,
+        };
+    }
+    let ubiquitous = Regex::new(r"(?i)^the\s+[^,]+?\s+shall\s+(.+)$").unwrap();
+    let trailing_condition = Regex::new(r"(?i)\b(when|whenever|if|while)\b").unwrap();
+    ubiquitous.captures(sentence)
+        .filter(|caps| !trailing_condition.is_match(&caps[1]))
+        .map(|_| EarsPattern::Ubiquitous)
+}
+
+fn without_article(text: &str) -> &str {
+    let lower = text.to_lowercase();
+    ["the ", "a ", "an "].iter()
+        .find(|a| lower.starts_with(*a))
+        .map(|a| text[a.len()..].trim())
+        .unwrap_or(text)
+}
+
+/// "the system shall ..." for the main clause of a sentence: its subject
+/// becomes the system unless it is a person, whose ability becomes
+/// something the system allows.
+fn system_clause(clause: &str) -> String {
+    let modal = Regex::new(r"(?i)^(.+?)\s+(?:shall|must|should|will|can|may|needs? to|has to|have to|is required to|are required to|wants? to)\s+(?:be able to\s+)?(.+)$").unwrap();
+    let Some(caps) = modal.captures(clause) else {
+        // An imperative such as "show an error"
+        let response = clause.chars().next().map(|c| c.to_lowercase().collect::<String>() + &clause[c.len_utf8()..]).unwrap_or_default();
+        return format!("the system shall {}", response);
+    };
+    let subject = without_article(caps[1].trim());
+    let response = caps[2].trim();
+    let subject_lower = subject.to_lowercase();
+    let head = subject_lower.split_whitespace().last().unwrap_or_default().trim_end_matches('s');
+    if PEOPLE.contains(&head) || PEOPLE.contains(&subject_lower.as_str()) {
+        let person = if ["i", "we", "you", "they"].contains(&subject_lower.as_str()) { "user".to_string() } else { subject_lower.clone() };
+        format!("the system shall allow the {} to {}", person, response)
+    } else {
+        format!("the {} shall {}", subject, response)
+    }
+}
+
+/// `sentence` rewritten into the EARS pattern its conditions point to:
+/// "if"/"in case" clauses become unwanted behavior, "when"/"once"/"after"/
+/// "upon" event-driven, "while"/"during" state-driven, "where" optional,
+/// and anything else ubiquitous. User stories become "the system shall
+/// allow the <actor> to <goal>".
+pub fn suggest_ears_rewrite(sentence: &str) -> String {
+    let sentence = sentence.trim().trim_end_matches(['.', '!', ';']);
+    let story = Regex::new(r"(?i)^as an?\s+(.+?),\s*i\s+(?:want|need|would like)\s+(?:to\s+)?(.+?)(?:,?\s+so\s+that\b.*)?$").unwrap();
+    if let Some(caps) = story.captures(sentence) {
+        let goal = Regex::new(r"(?i)\bmy\b").unwrap().replace_all(caps[2].trim(), "their");
+        return format!("The system shall allow the {} to {}.", without_article(caps[1].trim()).to_lowercase(), goal);
+    }
+
+    let leading = Regex::new(r"(?i)^(if|in case|when|whenever|once|after|upon|while|during|where)\s+(.+?),\s*(?:then\s+)?(.+)$").unwrap();
+    let trailing = Regex::new(r"(?i)^(.+?),?\s+(if|in case|when|whenever|once|after|while|during)\s+(.+)$").unwrap();
+    let (keyword, condition, main) = if let Some(caps) = leading.captures(sentence) {
+        (caps[1].to_lowercase(), caps[2].to_string(), caps[3].to_string())
+    } else if let Some(caps) = trailing.captures(sentence) {
+        (caps[2].to_lowercase(), caps[3].to_string(), caps[1].to_string())
+    } else {
+        (String::new(), String::new(), sentence.to_string())
+    };
+    let clause = system_clause(main.trim());
+
+    let rewrite = match keyword.as_str() {
+        "if" | "in case" => format!("If {}, then {}", condition, clause),
+        "when" | "whenever" | "once" | "upon" => format!("When {}, {}", condition, clause),
+        "after" => format!("When {} has completed, {}", condition, clause),
+        "while" => format!("While {}, {}", condition, clause),
+        "during" => format!("While {} is in progress, {}", condition, clause),
+        "where" => format!("Where {}, {}", condition, clause),
+        _ => clause,
+    };
+    format!("{}.", capitalize(&rewrite))
+}
+
+/// Classifies every requirement sentence of `text`: sentences with a modal
+/// verb ("shall", "must", "should", ...) or a user story's "I want",
+/// outside headings and code blocks.
+pub fn check_ears(text: &str) -> EarsReport {
+    let requirement_words = Regex::new(REQUIREMENT_WORDS).unwrap();
+    let list_marker = Regex::new(r"^(?:[-*+]|\d+[.)])\s+").unwrap();
+    let leading_id = Regex::new(r"^[*_\[(]*[A-Z][A-Z0-9]*-\d+[*_\])]*\s*[:.)-]?\s*").unwrap();
+
+    let mut sentences = Vec::new();
+    let mut in_code = false;
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code || line.starts_with('#') || line.starts_with('|') {
+            continue;
+        }
+        let line = list_marker.replace(line, "");
+        let line = leading_id.replace(&line, "");
+        for sentence in split_sentences(&line) {
+            if !requirement_words.is_match(sentence) {
+                continue;
+            }
+            let pattern = classify_sentence(sentence);
+            let suggestion = pattern.is_none().then(|| suggest_ears_rewrite(sentence));
+            sentences.push(EarsSentence { line: index + 1, text: sentence.to_string(), pattern, suggestion });
+        }
+    }
+    EarsReport { sentences }
+}
+
+pub fn format_ears_markdown(report: &EarsReport) -> String {
+    let nonconforming = report.nonconforming();
+    let mut output = format!(
+        "**Conforming:** {} of {} requirement sentences ({})\n\n",
+        report.sentences.len() - nonconforming.len(), report.sentences.len(), report_locale().percent(report.conformance(), 0)
+    );
+    output.push_str("| Pattern | Template | Sentences |\n|---|---|---|\n");
+    for pattern in EarsPattern::ALL {
+        output.push_str(&format!("| {} | {} | {} |\n", pattern.label(), pattern.template().replace('<', "&lt;").replace('>', "&gt;"), report.count(pattern)));
+    }
+    output.push('\n');
+
+    if !nonconforming.is_empty() {
+        output.push_str("### Sentences Fitting No Pattern\n\n");
+        for sentence in nonconforming {
+            output.push_str(&format!("- **Line {}:** {}\n", sentence.line, sentence.text));
+            if let Some(suggestion) = &sentence.suggestion {
+                output.push_str(&format!("  - Suggested: {}\n", suggestion));
+            }
+        }
+        output.push('\n');
+    }
+    output
+}
@@ -0,0 +1,269 @@
+//! An event-storming board proposed from the requirements: actors issue
+//! commands that record domain events, policies turn events into further
+//! commands ("whenever the order is placed, send a confirmation"), and read
+//! models are what actors look at to decide. Drawn as a Mermaid flowchart
+//! and exported as sticky notes for a Miro board.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::analyzer::{ActorActionLink, ExtractedEntities};
+use crate::architecture::{operations, FALLBACK_OBJECT};
+use crate::text::capitalize;
+use crate::domain_model::past_tense;
+
+/// Actions that only look at data; they need a read model, not a command.
+const READ_VERBS: &[&str] = &[
+    "view", "see", "list", "display", "show", "search", "browse", "read", "find", "check", "review", "track", "monitor",
+];
+
+/// Words that open the clause of a sentence an event reacts to.
+const TRIGGERS: &str = r"(?i)\b(whenever|when|once|after|as soon as|upon)\b";
+
+/// Horizontal and vertical spacing of the stickies on the Miro board.
+const COLUMN_WIDTH: i64 = 250;
+const LANE_HEIGHT: i64 = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardCommand {
+    /// "Place order"
+    pub name: String,
+    /// Who issues it; None when only a policy does
+    pub actor: Option<String>,
+    /// The object it changes, when its requirement names one
+    pub aggregate: Option<String>,
+    /// "Order placed"
+    pub event: String,
+    /// Position on the timeline, shared with the read models
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Policy {
+    /// The event it reacts to
+    pub trigger: String,
+    /// The command it issues
+    pub command: String,
+    /// The sentence it was found in
+    pub sentence: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadModel {
+    /// "Order details"
+    pub name: String,
+    pub actor: Option<String>,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventStormingBoard {
+    pub commands: Vec<BoardCommand>,
+    pub policies: Vec<Policy>,
+    pub read_models: Vec<ReadModel>,
+}
+
+impl EventStormingBoard {
+    /// Domain events in timeline order.
+    pub fn events(&self) -> Vec<&str> {
+        self.commands.iter().map(|c| c.event.as_str()).collect()
+    }
+
+    /// Everyone who issues a command or uses a read model, once each.
+    pub fn actors(&self) -> Vec<&str> {
+        let mut actors: Vec<&str> = Vec::new();
+        let named = self.commands.iter().filter_map(|c| c.actor.as_deref())
+            .chain(self.read_models.iter().filter_map(|r| r.actor.as_deref()));
+        for actor in named {
+            if !actors.contains(&actor) {
+                actors.push(actor);
+            }
+        }
+        actors
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty() && self.read_models.is_empty()
+    }
+}
+
+/// Whether `clause` uses `action` in any of its inflections:
+/// "place" matches "places", "placed" and "placing".
+fn mentions_action(clause: &str, action: &str) -> bool {
+    let verb = action.split_whitespace().next().unwrap_or_default();
+    let stem = verb.strip_suffix('e').unwrap_or(verb);
+    let past = past_tense(verb);
+    let forms = [verb.to_string(), format!("{}s", verb), format!("{}es", verb), past, format!("{}ing", stem)];
+    forms.iter().any(|form| {
+        Regex::new(&format!(r"\b{}\b", regex::escape(form))).is_ok_and(|re| re.is_match(clause))
+    })
+}
+
+/// Builds the board for `text`. `links` says which actor performs which
+/// action; actions with a read verb become read models and the rest
+/// commands, in the order the requirements mention them.
+pub fn build_event_storming(text: &str, entities: &ExtractedEntities, links: &[ActorActionLink]) -> EventStormingBoard {
+    let mut board = EventStormingBoard::default();
+    for (column, op) in operations(text, entities).into_iter().enumerate() {
+        let actor = links.iter().find(|l| l.action == op.action).map(|l| l.actor.clone());
+        let named = op.object != FALLBACK_OBJECT;
+        let verb = op.action.split_whitespace().next().unwrap_or_default();
+        if READ_VERBS.contains(&verb) {
+            let name = if named { capitalize(&format!("{} details", op.object)) } else { capitalize(&op.action) };
+            board.read_models.push(ReadModel { name, actor, column });
+            continue;
+        }
+        let (name, event) = if named {
+            (capitalize(&format!("{} {}", op.action, op.object)), capitalize(&format!("{} {}", op.object, past_tense(&op.action))))
+        } else {
+            (capitalize(&op.action), capitalize(&past_tense(&op.action)))
+        };
+        board.commands.push(BoardCommand { name, actor, aggregate: named.then_some(op.object), event, column });
+    }
+
+    // "When <event>, <command>" in either order: the command whose action
+    // is in the trigger clause records the event, one outside it reacts
+    let trigger = Regex::new(TRIGGERS).unwrap();
+    for sentence in text.split(['.', '!', '?', ';', '\n']).map(str::trim).filter(|s| !s.is_empty()) {
+        let lower = sentence.to_lowercase();
+        let Some(found) = trigger.find(&lower) else { continue };
+        let after = &lower[found.end()..];
+        let (condition, reaction) = match after.find(',') {
+            Some(comma) => (&after[..comma], format!("{} {}", &lower[..found.start()], &after[comma + 1..])),
+            None => (after, lower[..found.start()].to_string()),
+        };
+        let action = |c: &BoardCommand| c.name.split_whitespace().next().unwrap_or_default().to_lowercase();
+        let Some(cause) = board.commands.iter().find(|c| mentions_action(condition, &action(c))) else { continue };
+        let reactions: Vec<&BoardCommand> = board.commands.iter()
+            .filter(|c| c.name != cause.name && mentions_action(&reaction, &action(c)))
+            .collect();
+        let mut policies = Vec::new();
+        for command in reactions {
+            if !board.policies.iter().any(|p| p.trigger == cause.event && p.command == command.name) {
+                policies.push(Policy { trigger: cause.event.clone(), command: command.name.clone(), sentence: sentence.to_string() });
+            }
+        }
+        board.policies.extend(policies);
+    }
+    board
+}
+
+fn label(text: &str) -> String {
+    text.replace('"', "'")
+}
+
+/// Mermaid flowchart in the usual sticky colors: yellow actors, blue
+/// commands, orange events, lilac policies and green read models.
+pub fn format_event_storming_mermaid(board: &EventStormingBoard) -> String {
+    let mut output = String::from("flowchart LR\n");
+    output.push_str("    classDef actor fill:#fff59d,stroke:#c9b200\n");
+    output.push_str("    classDef command fill:#90caf9,stroke:#1e6fb8\n");
+    output.push_str("    classDef event fill:#ffb74d,stroke:#c77700\n");
+    output.push_str("    classDef policy fill:#ce93d8,stroke:#7b3f8c\n");
+    output.push_str("    classDef readModel fill:#a5d6a7,stroke:#3f8c45\n");
+
+    let actors = board.actors();
+    let actor_id = |name: &str| format!("A{}", actors.iter().position(|a| *a == name).unwrap_or_default() + 1);
+    for (i, actor) in actors.iter().enumerate() {
+        output.push_str(&format!("    A{}([\"👤 {}\"]):::actor\n", i + 1, label(actor)));
+    }
+    for (i, command) in board.commands.iter().enumerate() {
+        output.push_str(&format!("    C{}[\"{}\"]:::command\n", i + 1, label(&command.name)));
+        output.push_str(&format!("    E{}[\"{}\"]:::event\n", i + 1, label(&command.event)));
+        if let Some(actor) = &command.actor {
+            output.push_str(&format!("    {} --> C{}\n", actor_id(actor), i + 1));
+        }
+        output.push_str(&format!("    C{} --> E{}\n", i + 1, i + 1));
+    }
+    let command_index = |name: &str| board.commands.iter().position(|c| c.name == name).unwrap_or_default() + 1;
+    let event_index = |event: &str| board.commands.iter().position(|c| c.event == event).unwrap_or_default() + 1;
+    for (i, policy) in board.policies.iter().enumerate() {
+        output.push_str(&format!("    P{}{{{{\"Whenever {}\"}}}}:::policy\n", i + 1, label(&policy.trigger.to_lowercase())));
+        output.push_str(&format!("    E{} --> P{} --> C{}\n", event_index(&policy.trigger), i + 1, command_index(&policy.command)));
+    }
+    for (i, read_model) in board.read_models.iter().enumerate() {
+        output.push_str(&format!("    R{}[/\"{}\"/]:::readModel\n", i + 1, label(&read_model.name)));
+        if let Some(actor) = &read_model.actor {
+            output.push_str(&format!("    R{} -.-> {}\n", i + 1, actor_id(actor)));
+        }
+    }
+    output
+}
+
+fn sticky(content: &str, color: &str, column: usize, lane: i64) -> Value {
+    json!({
+        "data": { "content": content, "shape": "square" },
+        "style": { "fillColor": color, "textAlign": "center", "textAlignVertical": "middle" },
+        "position": { "x": column as i64 * COLUMN_WIDTH, "y": lane * LANE_HEIGHT, "origin": "center" },
+    })
+}
+
+/// The board as sticky notes for Miro: every entry of `sticky_notes` is the
+/// body of a `POST /v2/boards/{board_id}/sticky_notes` request. Time runs
+/// left to right; each kind of sticky has its own lane.
+pub fn format_event_storming_miro(board: &EventStormingBoard, title: &str) -> Value {
+    let mut stickies = Vec::new();
+    for command in &board.commands {
+        if let Some(actor) = &command.actor {
+            stickies.push(sticky(actor, "light_yellow", command.column, 0));
+        }
+        stickies.push(sticky(&command.name, "light_blue", command.column, 1));
+        stickies.push(sticky(&command.event, "orange", command.column, 2));
+    }
+    for policy in &board.policies {
+        let column = board.commands.iter().find(|c| c.event == policy.trigger).map_or(0, |c| c.column);
+        stickies.push(sticky(&format!("Whenever {}, {}", policy.trigger.to_lowercase(), policy.command.to_lowercase()), "violet", column, 3));
+    }
+    for read_model in &board.read_models {
+        if let Some(actor) = &read_model.actor {
+            stickies.push(sticky(actor, "light_yellow", read_model.column, 0));
+        }
+        stickies.push(sticky(&read_model.name, "light_green", read_model.column, 4));
+    }
+    json!({
+        "title": title,
+        "lanes": ["Actors", "Commands", "Domain events", "Policies", "Read models"],
+        "sticky_notes": stickies,
+    })
+}
+
+/// Report section: counts, the timeline as a table, policies, read models
+/// and the Mermaid board.
+pub fn format_event_storming_markdown(board: &EventStormingBoard) -> String {
+    if board.is_empty() {
+        return "No actions were found to put on an event-storming board.\n\n".to_string();
+    }
+    let mut output = format!(
+        "**Actors:** {} | **Commands:** {} | **Domain events:** {} | **Policies:** {} | **Read models:** {}\n\n",
+        board.actors().len(), board.commands.len(), board.events().len(), board.policies.len(), board.read_models.len()
+    );
+    if !board.commands.is_empty() {
+        output.push_str("| Actor | Command | Domain event |\n|---|---|---|\n");
+        for command in &board.commands {
+            output.push_str(&format!("| {} | {} | {} |\n", command.actor.as_deref().unwrap_or("-"), command.name, command.event));
+        }
+        output.push('\n');
+    }
+    if !board.policies.is_empty() {
+        output.push_str("**Policies:**\n");
+        for policy in &board.policies {
+            output.push_str(&format!("- Whenever *{}*, **{}**\n", policy.trigger, policy.command));
+        }
+        output.push('\n');
+    }
+    if !board.read_models.is_empty() {
+        output.push_str("**Read models:**\n");
+        for read_model in &board.read_models {
+            match &read_model.actor {
+                Some(actor) => output.push_str(&format!("- {} (used by {})\n", read_model.name, actor)),
+                None => output.push_str(&format!("- {}\n", read_model.name)),
+            }
+        }
+        output.push('\n');
+    }
+    output.push_str("```mermaid\n");
+    output.push_str(&format_event_storming_mermaid(board));
+    output.push_str("```\n\n");
+    output
+}
@@ -0,0 +1,61 @@
+//! Machine-readable progress for editor extensions and GUIs. With `--events`
+//! a run reports itself as JSON lines on stderr, one event per line, instead
+//! of the human status lines; the report itself still goes where `--output`
+//! says.
+
+use serde::Serialize;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::analyzer::{Ambiguity, AnalysisResult};
+
+static EVENTS: AtomicBool = AtomicBool::new(false);
+
+/// Reports the run of this process as events from now on.
+pub fn set_events(enabled: bool) {
+    EVENTS.store(enabled, Ordering::Relaxed);
+}
+
+pub fn events_enabled() -> bool {
+    EVENTS.load(Ordering::Relaxed)
+}
+
+/// One line of the stream, tagged with its `event` name.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    /// A status line, without its decoration
+    Status { message: String },
+    /// A file of a batch is about to be reported, `index` counting from 1
+    FileStarted { file: &'a str, index: usize, total: usize },
+    Finding { finding: &'a Ambiguity },
+    FileFinished { file: Option<&'a str>, findings: usize },
+    FileFailed { file: &'a str, error: String },
+    ReportSaved { path: &'a Path },
+    Finished { files: usize, findings: usize },
+    Error { message: String },
+}
+
+/// Writes `event` to stderr, when events are on.
+pub fn emit(event: &Event) {
+    if events_enabled() {
+        if let Ok(line) = serde_json::to_string(event) {
+            eprintln!("{}", line);
+        }
+    }
+}
+
+/// A `finding` event for each finding of `result`, then `file_finished`.
+pub fn emit_result(file: Option<&str>, result: &AnalysisResult) {
+    for finding in &result.ambiguities {
+        emit(&Event::Finding { finding });
+    }
+    emit(&Event::FileFinished { file, findings: result.ambiguities.len() });
+}
+
+/// The `status` event of a status line: its text without the blank lines
+/// around it. `None` for a line with no text.
+pub fn status_event(line: &str) -> Option<Event<'static>> {
+    let message = line.trim();
+    (!message.is_empty() && !message.chars().all(|c| c == '=')).then(|| Event::Status { message: message.to_string() })
+}
@@ -0,0 +1,181 @@
+//! Audit evidence bundles: the requirements, their analysis, traceability
+//! matrix, review checklist and improvement history in one timestamped ZIP,
+//! with a manifest of every file's SHA-256 so the bundle can be checked
+//! later.
+
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{Seek, Write};
+
+use crate::analyzer::AnalysisResult;
+use crate::daemon::HistoryEntry;
+use crate::git_integration::CommitInfo;
+use crate::locale::report_locale;
+
+pub const MANIFEST_NAME: &str = "MANIFEST.json";
+pub const CHECKSUMS_NAME: &str = "SHA256SUMS";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvidenceFile {
+    /// Path inside the bundle
+    pub path: String,
+    pub sha256: String,
+    pub bytes: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvidenceManifest {
+    pub generated_at: DateTime<Utc>,
+    pub prism_version: String,
+    /// The requirements file or directory the evidence is about
+    pub source: String,
+    pub files: Vec<EvidenceFile>,
+}
+
+#[derive(Debug, Default)]
+pub struct EvidenceBundle {
+    files: Vec<(String, Vec<u8>)>,
+}
+
+pub fn sha256_hex(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+impl EvidenceBundle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, path: &str, content: impl Into<Vec<u8>>) {
+        self.files.push((path.to_string(), content.into()));
+    }
+
+    pub fn manifest(&self, source: &str, generated_at: DateTime<Utc>) -> EvidenceManifest {
+        EvidenceManifest {
+            generated_at,
+            prism_version: env!("CARGO_PKG_VERSION").to_string(),
+            source: source.to_string(),
+            files: self.files.iter()
+                .map(|(path, content)| EvidenceFile { path: path.clone(), sha256: sha256_hex(content), bytes: content.len() })
+                .collect(),
+        }
+    }
+
+    /// Writes the files, `MANIFEST.json` and `SHA256SUMS` as a ZIP, every
+    /// entry dated `manifest.generated_at`.
+    pub fn write_zip<W: Write + Seek>(&self, writer: W, manifest: &EvidenceManifest) -> Result<()> {
+        let time = manifest.generated_at;
+        let modified = zip::DateTime::from_date_and_time(
+            time.year() as u16, time.month() as u8, time.day() as u8,
+            time.hour() as u8, time.minute() as u8, time.second() as u8,
+        ).unwrap_or_default();
+        let options = zip::write::FileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .last_modified_time(modified);
+
+        let mut zip = zip::ZipWriter::new(writer);
+        let listings = [
+            (MANIFEST_NAME.to_string(), serde_json::to_vec_pretty(manifest)?),
+            (CHECKSUMS_NAME.to_string(), format_checksums(manifest).into_bytes()),
+        ];
+        for (path, content) in self.files.iter().chain(&listings) {
+            zip.start_file(path.as_str(), options)?;
+            zip.write_all(content)?;
+        }
+        zip.finish()?;
+        Ok(())
+    }
+}
+
+/// `sha256sum -c` input for the files of `manifest`.
+pub fn format_checksums(manifest: &EvidenceManifest) -> String {
+    manifest.files.iter().map(|f| format!("{}  {}\n", f.sha256, f.path)).collect()
+}
+
+/// "prism-evidence-20261016-143000.zip"
+pub fn default_bundle_name(now: DateTime<Utc>) -> String {
+    format!("prism-evidence-{}.zip", now.format("%Y%m%d-%H%M%S"))
+}
+
+/// A checklist for the review of the analyzed requirements: the findings to
+/// resolve, the usual review questions and a sign-off table.
+pub fn format_review_checklist(
+    result: &AnalysisResult,
+    open_questions: &[String],
+    untraced: Option<usize>,
+    source: &str,
+    generated_at: DateTime<Utc>,
+) -> String {
+    let mut output = format!(
+        "# Requirements Review Checklist: {}\n*Generated by PRISM on {} UTC - tick each item during the review and sign below*\n\n## Findings to Resolve\n\n",
+        source, report_locale().date_time(&generated_at)
+    );
+    let mut findings: Vec<_> = result.ambiguities.iter().collect();
+    findings.sort_by_key(|f| std::cmp::Reverse(f.severity));
+    if findings.is_empty() {
+        output.push_str("No findings.\n");
+    }
+    for finding in findings {
+        let location = finding.location.as_ref().map(|l| format!("Line {}: ", l.line)).unwrap_or_default();
+        output.push_str(&format!("- [ ] **{}** {}\"{}\" - {}", finding.severity, location, finding.text, finding.reason));
+        if let Some(suggestion) = finding.suggestions.first() {
+            output.push_str(&format!(" (suggestion: {})", suggestion));
+        }
+        output.push('\n');
+    }
+
+    output.push_str("\n## Review Questions\n\n");
+    output.push_str("- [ ] Every requirement says who does what, with an outcome that can be tested\n");
+    output.push_str("- [ ] Every requirement has acceptance criteria\n");
+    output.push_str("- [ ] Performance, security and availability requirements are stated with numbers\n");
+    match open_questions.len() {
+        0 => output.push_str("- [ ] No open questions (TBD, TODO) are left\n"),
+        n => {
+            output.push_str(&format!("- [ ] The {} open question(s) are resolved:\n", n));
+            for question in open_questions {
+                output.push_str(&format!("  - {}\n", question));
+            }
+        }
+    }
+    match untraced {
+        Some(0) => output.push_str("- [ ] Every requirement is traced to code and tests (see traceability/matrix.md)\n"),
+        Some(n) => output.push_str(&format!("- [ ] The {} requirement(s) without code or tests in traceability/matrix.md are accepted or traced\n", n)),
+        None => output.push_str("- [ ] Every requirement is traced to code and tests\n"),
+    }
+
+    output.push_str("\n## Sign-off\n\n| Role | Name | Date | Signature |\n|---|---|---|---|\n| Author | | | |\n| Reviewer | | | |\n| Approver | | | |\n");
+    output
+}
+
+/// How the requirements got to where they are: the commits that changed them
+/// and the quality score of the scheduled analyses over time.
+pub fn format_improvement_history(commits: &[CommitInfo], runs: &[HistoryEntry], source: &str) -> String {
+    let mut output = format!("# Improvement History: {}\n*Generated by PRISM - AI-Powered Requirement Analyzer*\n\n## Revisions\n\n", source);
+    if commits.is_empty() {
+        output.push_str("No git history for the requirements.\n");
+    } else {
+        output.push_str("| Date | Commit | Author | Change |\n|---|---|---|---|\n");
+        for commit in commits {
+            output.push_str(&format!(
+                "| {} | `{}` | {} | {} |\n",
+                commit.date, &commit.hash[..commit.hash.len().min(10)], commit.author, commit.message.replace('|', "\\|")
+            ));
+        }
+    }
+
+    output.push_str("\n## Quality Over Time\n\n");
+    if runs.is_empty() {
+        output.push_str("No scheduled analyses recorded. Pass `--job` with a `prism daemon` job to include its runs.\n");
+    } else {
+        output.push_str("| Run | Files | Requirements | Findings | Critical | Per 100 Words | Score |\n|---|---:|---:|---:|---:|---:|---:|\n");
+        for run in runs {
+            output.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {:.1} | {:.0} |\n",
+                report_locale().date_time(&run.timestamp), run.files, run.requirement_count, run.finding_count, run.critical_count, run.density, run.score
+            ));
+        }
+    }
+    output
+}
@@ -0,0 +1,110 @@
+//! Golden files of LLM responses (`--record DIR`, `--replay DIR`): a
+//! recording run saves every response next to its prompt, and a replaying
+//! run answers the same prompts from those files without calling the
+//! provider, for deterministic CI runs and offline demos of AI analysis.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixtureMode {
+    Record,
+    Replay,
+}
+
+/// One recorded LLM call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmFixture {
+    pub provider: String,
+    pub model: String,
+    pub prompt: String,
+    pub response: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct LlmFixtures {
+    dir: PathBuf,
+    mode: FixtureMode,
+}
+
+impl LlmFixtures {
+    pub fn new(dir: impl Into<PathBuf>, mode: FixtureMode) -> Self {
+        Self { dir: dir.into(), mode }
+    }
+
+    /// The fixtures of the `--record` or `--replay` flag, if either is given.
+    pub fn from_flags(record: Option<PathBuf>, replay: Option<PathBuf>) -> Option<Self> {
+        match (record, replay) {
+            (Some(dir), _) => Some(Self::new(dir, FixtureMode::Record)),
+            (None, Some(dir)) => Some(Self::new(dir, FixtureMode::Replay)),
+            (None, None) => None,
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn mode(&self) -> FixtureMode {
+        self.mode
+    }
+
+    /// File stem of the response to `prompt` from `provider` and `model`
+    /// with `system_prompt`. Prompt templates or a PRISM version that word
+    /// the prompt differently need recording again.
+    pub fn key(provider: &str, model: &str, system_prompt: &str, prompt: &str) -> String {
+        let mut hasher = Sha256::new();
+        for part in [provider, model, system_prompt, prompt] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+
+    pub fn save(&self, system_prompt: &str, fixture: &LlmFixture) -> Result<()> {
+        std::fs::create_dir_all(&self.dir).with_context(|| format!("Cannot create {}", self.dir.display()))?;
+        let key = Self::key(&fixture.provider, &fixture.model, system_prompt, &fixture.prompt);
+        // Written under a temporary name first so a parallel batch never reads half a fixture
+        let temporary = self.dir.join(format!("{}.tmp", key));
+        std::fs::write(&temporary, serde_json::to_string_pretty(fixture)?)?;
+        std::fs::rename(temporary, self.path(&key))?;
+        Ok(())
+    }
+
+    /// The recorded response to `prompt`; fails when there is none.
+    pub fn replay(&self, provider: &str, model: &str, system_prompt: &str, prompt: &str) -> Result<String> {
+        let path = self.path(&Self::key(provider, model, system_prompt, prompt));
+        if !path.exists() {
+            bail!(
+                "No recorded response to this prompt from {} ({}) in {} - record it with --record {}",
+                provider, model, self.dir.display(), self.dir.display()
+            );
+        }
+        let content = std::fs::read_to_string(&path).with_context(|| format!("Cannot read {}", path.display()))?;
+        let fixture: LlmFixture = serde_json::from_str(&content).with_context(|| format!("{} is not a recorded LLM response", path.display()))?;
+        Ok(fixture.response)
+    }
+
+    /// Provider and model of a recorded response, the first by file name,
+    /// so a replaying run without an AI provider configured uses them.
+    pub fn recorded_model(&self) -> Result<(String, String)> {
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(&self.dir)
+            .with_context(|| format!("Cannot read the recorded responses in {}", self.dir.display()))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|e| e == "json"))
+            .collect();
+        paths.sort();
+        for path in paths {
+            if let Ok(fixture) = serde_json::from_str::<LlmFixture>(&std::fs::read_to_string(&path)?) {
+                return Ok((fixture.provider, fixture.model));
+            }
+        }
+        bail!("No recorded LLM responses in {} - record them first with --record {}", self.dir.display(), self.dir.display())
+    }
+}
@@ -0,0 +1,947 @@
+//! Renders an [`AnalysisResult`] in each `--format` of `prism analyze`.
+
+use anyhow::Result;
+
+use crate::analyzer::{rule_description, AmbiguitySeverity, Analyzer, AnalysisResult, PermissionMatrix, RewriteSuggestion};
+use crate::cli::OutputFormat;
+use crate::annotated::format_annotated_source;
+use crate::artifacts::suggestion_anchor;
+use crate::changelog::format_changelog_markdown;
+use crate::html_report;
+use crate::locale::report_locale;
+use crate::diagnostics::format_diagnostics;
+use crate::pdf_report::format_as_pdf;
+use crate::spreadsheet::format_findings_csv;
+use crate::dependencies::{format_cycle, format_dependency_markdown, format_dependency_mermaid};
+use crate::reviewers::format_reviewer_table;
+use crate::ears::format_ears_markdown;
+use crate::smart::format_smart_markdown;
+use crate::domain_model::{format_domain_model_markdown, format_domain_model_plantuml};
+use crate::contract_tests::format_contract_tests_markdown;
+use crate::test_data::format_test_data_markdown;
+use crate::test_coverage::format_test_coverage_markdown;
+use crate::load_tests::format_load_tests_markdown;
+use crate::security_tests::format_security_tests_markdown;
+use crate::event_storming::{format_event_storming_markdown, format_event_storming_mermaid};
+
+/// Sections with more lines or items than this are collapsed in Jira output.
+const JIRA_EXPAND_THRESHOLD: usize = 10;
+
+/// Sections with more items than this are wrapped in `<details>` in GitHub output.
+const GITHUB_DETAILS_THRESHOLD: usize = 10;
+
+/// "2.5 per 100 words, 0.50 per requirement" in the report locale.
+fn density(result: &AnalysisResult) -> String {
+    let locale = report_locale();
+    format!("{} per 100 words, {} per requirement",
+        locale.number(result.metrics.findings_per_100_words, 1), locale.number(result.metrics.findings_per_requirement, 2))
+}
+
+/// The report for `result` in `format`. `input_text` is the analyzed text;
+/// `source_name` (the analyzed file) is used by the SARIF, HTML, PDF, CSV,
+/// annotated and diagnostics reports. Diagnostics are without colors here.
+pub fn format_report(result: &AnalysisResult, input_text: &str, format: &OutputFormat, source_name: Option<&str>) -> Result<String> {
+    Ok(match format {
+        OutputFormat::Json => serde_json::to_string_pretty(result)?,
+        OutputFormat::Markdown => format_as_markdown(result, input_text),
+        OutputFormat::Jira => format_as_jira(result, input_text),
+        OutputFormat::JiraAdf => format_as_jira_adf(result, input_text)?,
+        OutputFormat::Github => format_as_github(result, input_text),
+        OutputFormat::Plain => format_as_plain(result, input_text),
+        OutputFormat::Sarif => format_as_sarif(result, source_name)?,
+        OutputFormat::Html => format_as_html(result, input_text, source_name)?,
+        OutputFormat::Annotated => format_annotated_source(result, input_text, source_name),
+        OutputFormat::Diagnostics => format_diagnostics(result, input_text, source_name, false),
+        OutputFormat::Pdf => format_as_pdf(result, input_text, source_name),
+        OutputFormat::Csv => format_findings_csv(result, source_name),
+    })
+}
+
+/// File extension for a report in `format`, e.g. "sarif" or "jira.txt".
+pub fn report_extension(format: &OutputFormat) -> &'static str {
+    match format {
+        OutputFormat::Json => "json",
+        OutputFormat::Markdown => "md",
+        OutputFormat::Html => "html",
+        OutputFormat::Github => "github.md",
+        OutputFormat::Jira => "jira.txt",
+        OutputFormat::JiraAdf => "jira-adf.json",
+        OutputFormat::Plain => "txt",
+        OutputFormat::Sarif => "sarif",
+        OutputFormat::Annotated => "annotated.txt",
+        OutputFormat::Diagnostics => "diagnostics.txt",
+        OutputFormat::Pdf => "pdf",
+        OutputFormat::Csv => "csv",
+    }
+}
+
+pub fn format_as_html(result: &AnalysisResult, input_text: &str, source_name: Option<&str>) -> Result<String> {
+    let entities = &result.entities;
+    let mut diagrams = if entities.actors.is_empty() && entities.actions.is_empty() {
+        Vec::new()
+    } else {
+        // Diagrams are drawn from the extracted entities, which needs no AI
+        let analyzer = Analyzer::new()?;
+        vec![
+            ("Use Case Diagram", analyzer.generate_mermaid_use_case(input_text, entities)),
+            ("Sequence Diagram", analyzer.generate_mermaid_sequence(input_text, entities)),
+        ]
+    };
+    if let Some(graph) = result.dependency_graph.as_ref().filter(|g| !g.edges.is_empty()) {
+        diagrams.push(("Requirement Dependencies", format_dependency_mermaid(graph)));
+    }
+    if let Some(board) = result.event_storming.as_ref().filter(|b| !b.is_empty()) {
+        diagrams.push(("Event Storming", format_event_storming_mermaid(board)));
+    }
+    Ok(html_report::format_html_report(result, input_text, source_name, &diagrams))
+}
+
+pub fn format_as_sarif(result: &AnalysisResult, source_name: Option<&str>) -> Result<String> {
+    let findings = result.ambiguities.iter().map(|a| (a, false))
+        .chain(result.suppressed_ambiguities.iter().map(|a| (a, true)));
+
+    let mut rule_ids: Vec<&str> = findings.clone().map(|(a, _)| a.rule_id.as_str()).collect();
+    rule_ids.sort();
+    rule_ids.dedup();
+
+    let rules: Vec<serde_json::Value> = rule_ids.iter().map(|id| {
+        serde_json::json!({
+            "id": id,
+            "shortDescription": { "text": rule_description(id) },
+        })
+    }).collect();
+
+    let results: Vec<serde_json::Value> = findings.map(|(ambiguity, suppressed)| {
+        let level = match ambiguity.severity {
+            AmbiguitySeverity::Critical | AmbiguitySeverity::High => "error",
+            AmbiguitySeverity::Medium => "warning",
+            AmbiguitySeverity::Low => "note",
+        };
+
+        let mut sarif_result = serde_json::json!({
+            "ruleId": ambiguity.rule_id,
+            "level": level,
+            "message": { "text": format!("{}: \"{}\"", ambiguity.reason, ambiguity.text) },
+            "partialFingerprints": { "prismFindingHash/v1": ambiguity.fingerprint },
+        });
+
+        if let Some(location) = &ambiguity.location {
+            sarif_result["locations"] = serde_json::json!([{
+                "physicalLocation": {
+                    "artifactLocation": { "uri": source_name.unwrap_or("requirements.txt") },
+                    "region": {
+                        "startLine": location.line,
+                        "startColumn": location.column,
+                        "charLength": ambiguity.text.chars().count(),
+                    },
+                },
+            }]);
+        }
+
+        if suppressed {
+            sarif_result["suppressions"] = serde_json::json!([{ "kind": "inSource" }]);
+        }
+
+        sarif_result
+    }).collect();
+
+    let log = serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "PRISM",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/humaxai2025/prism",
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    });
+
+    Ok(serde_json::to_string_pretty(&log)?)
+}
+
+pub fn format_as_markdown(result: &AnalysisResult, input_text: &str) -> String {
+    let mut output = String::new();
+    
+    output.push_str("# 🔍 PRISM Requirement Analysis Report\n\n");
+
+    output.push_str("## 📝 Analyzed Requirement\n\n");
+    output.push_str(&format!("> {}\n\n", input_text.trim()));
+
+    output.push_str("## 📊 Analysis Summary\n\n");
+    output.push_str(&format!("- **Ambiguities Found:** {}\n", result.ambiguities.len()));
+    output.push_str(&format!("- **Ambiguity Density:** {} ({} words, {} requirements)\n",
+        density(result), report_locale().integer(result.metrics.word_count), result.metrics.requirement_count));
+    if !result.suppressed_ambiguities.is_empty() {
+        output.push_str(&format!("- **Suppressed Findings:** {}\n", result.suppressed_ambiguities.len()));
+    }
+    if let Some(hidden) = result.hidden_findings.summary() {
+        output.push_str(&format!("- **Hidden Findings:** {}\n", hidden));
+    }
+    output.push_str(&format!("- **Actors Identified:** {}\n", result.entities.actors.len()));
+    output.push_str(&format!("- **Actions Identified:** {}\n", result.entities.actions.len()));
+    output.push_str(&format!("- **Objects Identified:** {}\n\n", result.entities.objects.len()));
+
+    output.push_str("## ⚠️ Detected Ambiguities\n\n");
+    if result.ambiguities.is_empty() {
+        output.push_str("✅ **No ambiguities detected - your requirements are clear!**\n\n");
+    } else {
+        for (i, ambiguity) in result.ambiguities.iter().enumerate() {
+            let severity_icon = match ambiguity.severity {
+                crate::analyzer::AmbiguitySeverity::Critical => "🔴",
+                crate::analyzer::AmbiguitySeverity::High => "🟠",
+                crate::analyzer::AmbiguitySeverity::Medium => "🟡",
+                crate::analyzer::AmbiguitySeverity::Low => "🟢",
+            };
+            output.push_str(&format!("### {} Issue #{}: \"{}\"\n", severity_icon, i + 1, ambiguity.text));
+            if let Some(location) = &ambiguity.location {
+                output.push_str(&format!("- **Location:** {}\n", location));
+            }
+            output.push_str(&format!("- **Problem:** {}\n", ambiguity.reason));
+            output.push_str(&format!("- **Severity:** {:?}\n", ambiguity.severity));
+            output.push_str("- **Suggested Improvements:**\n");
+            for suggestion in &ambiguity.suggestions {
+                output.push_str(&format!("  - {}\n", suggestion));
+            }
+            output.push('\n');
+        }
+    }
+
+    output.push_str("## 🎯 Extracted Entities\n\n");
+    
+    output.push_str("### 👥 Actors (Who performs actions)\n");
+    if result.entities.actors.is_empty() {
+        output.push_str("- *No actors identified*\n\n");
+    } else {
+        for actor in &result.entities.actors {
+            output.push_str(&format!("- **{}**\n", actor));
+        }
+        output.push('\n');
+    }
+    
+    output.push_str("### ⚡ Actions (What is being done)\n");
+    if result.entities.actions.is_empty() {
+        output.push_str("- *No actions identified*\n\n");
+    } else {
+        for action in &result.entities.actions {
+            output.push_str(&format!("- **{}**\n", action));
+        }
+        output.push('\n');
+    }
+    
+    output.push_str("### 📦 Objects (What is being acted upon)\n");
+    if result.entities.objects.is_empty() {
+        output.push_str("- *No objects identified*\n\n");
+    } else {
+        for object in &result.entities.objects {
+            output.push_str(&format!("- **{}**\n", object));
+        }
+        output.push('\n');
+    }
+
+    if let Some(uml) = &result.uml_diagrams {
+        output.push_str("## 🎨 UML Diagrams\n\n");
+        
+        if let Some(use_case) = &uml.use_case {
+            output.push_str("### Use Case Diagram\n\n");
+            output.push_str("```plantuml\n");
+            output.push_str(use_case);
+            output.push_str("\n```\n\n");
+        }
+        
+        if let Some(sequence) = &uml.sequence {
+            output.push_str("### Sequence Diagram\n\n");
+            output.push_str("```plantuml\n");
+            output.push_str(sequence);
+            output.push_str("\n```\n\n");
+        }
+        
+        if let Some(class_diagram) = &uml.class_diagram {
+            output.push_str("### Class Diagram\n\n");
+            output.push_str("```plantuml\n");
+            output.push_str(class_diagram);
+            output.push_str("\n```\n\n");
+        }
+    }
+
+    if let Some(pseudocode) = &result.pseudocode {
+        output.push_str("## Generated Pseudocode\n\n");
+        output.push_str("```\n");
+        output.push_str(pseudocode);
+        output.push_str("\n```\n\n");
+    }
+
+    if let Some(gherkin) = &result.gherkin {
+        output.push_str("## Gherkin Features\n\n");
+        output.push_str("```gherkin\n");
+        output.push_str(gherkin);
+        output.push_str("```\n\n");
+    }
+
+    if let Some(tests) = &result.test_cases {
+        output.push_str("## Suggested Test Cases\n\n");
+        output.push_str("### Happy Path\n");
+        for test in &tests.happy_path {
+            output.push_str(&format!("- {}\n", test));
+        }
+        output.push_str("\n### Negative Cases\n");
+        for test in &tests.negative_cases {
+            output.push_str(&format!("- {}\n", test));
+        }
+        output.push_str("\n### Edge Cases\n");
+        for test in &tests.edge_cases {
+            output.push_str(&format!("- {}\n", test));
+        }
+    }
+
+    if let Some(test_data) = &result.test_data {
+        output.push_str("\n### Test Data\n\n");
+        output.push_str(&format_test_data_markdown(test_data));
+    }
+
+    if let Some(contracts) = &result.contract_tests {
+        output.push_str("\n## 🤝 API Contract Tests\n\n");
+        output.push_str(&format_contract_tests_markdown(contracts));
+    }
+
+    if let Some(coverage) = &result.test_coverage {
+        output.push_str("\n## 🧭 Test Coverage Matrix\n\n");
+        output.push_str(&format_test_coverage_markdown(coverage));
+    }
+
+    if let Some(improved) = &result.improved_requirements {
+        output.push_str("## ✨ Improved Requirements\n\n");
+        output.push_str("```\n");
+        output.push_str(improved);
+        output.push_str("\n```\n\n");
+        if let Some(changes) = result.improvement_changelog.as_ref().filter(|changes| !changes.is_empty()) {
+            output.push_str("### 🧾 Changelog\n\n");
+            output.push_str(&format_changelog_markdown(changes, &result.ambiguities));
+            output.push('\n');
+        }
+    }
+
+    if let Some(suggestions) = &result.rewrite_suggestions {
+        output.push_str(&format_rewrite_suggestions(suggestions));
+    }
+
+    if let Some(completeness) = &result.completeness_analysis {
+        output.push_str("## 📊 Completeness Analysis\n\n");
+        output.push_str(&format!("**Completeness Score: {}**\n\n", report_locale().percent(completeness.completeness_score, 1)));
+        
+        if !completeness.gaps_identified.is_empty() {
+            output.push_str("### Identified Gaps\n\n");
+            for gap in &completeness.gaps_identified {
+                let priority_emoji = match gap.priority {
+                    crate::analyzer::GapPriority::Critical => "🔴",
+                    crate::analyzer::GapPriority::High => "🟠", 
+                    crate::analyzer::GapPriority::Medium => "🟡",
+                    crate::analyzer::GapPriority::Low => "🟢",
+                };
+                output.push_str(&format!("#### {} {} - {:?}\n\n", priority_emoji, gap.category, gap.priority));
+                output.push_str(&format!("**Issue:** {}\n\n", gap.description));
+                output.push_str("**Suggestions:**\n");
+                for suggestion in &gap.suggestions {
+                    output.push_str(&format!("- {}\n", suggestion));
+                }
+                output.push_str("\n");
+            }
+        }
+    }
+
+    if let Some(user_story) = &result.user_story_validation {
+        output.push_str("## ✅ User Story Validation\n\n");
+        if user_story.is_valid_format {
+            output.push_str("✅ **Valid user story format detected**\n\n");
+            let locale = report_locale();
+            output.push_str(&format!("**Business Value Score: {}**\n\n", locale.percent(user_story.business_value_score, 1)));
+            
+            output.push_str("### Component Analysis\n\n");
+            output.push_str(&format!("**Actor Quality:** {} - {}\n", locale.percent(user_story.actor_quality.score, 1),
+                if user_story.actor_quality.is_valid { "✅ Valid" } else { "❌ Issues found" }));
+            output.push_str(&format!("**Goal Quality:** {} - {}\n", locale.percent(user_story.goal_quality.score, 1),
+                if user_story.goal_quality.is_valid { "✅ Valid" } else { "❌ Issues found" }));
+            output.push_str(&format!("**Reason Quality:** {} - {}\n\n", locale.percent(user_story.reason_quality.score, 1),
+                if user_story.reason_quality.is_valid { "✅ Valid" } else { "❌ Issues found" }));
+        } else {
+            output.push_str("❌ **Not in valid user story format**\n\n");
+        }
+        
+        if !user_story.recommendations.is_empty() {
+            output.push_str("### Recommendations\n\n");
+            for rec in &user_story.recommendations {
+                output.push_str(&format!("- {}\n", rec));
+            }
+            output.push_str("\n");
+        }
+    }
+
+    if let Some(nfrs) = &result.nfr_suggestions {
+        output.push_str("## 🔒 Non-Functional Requirements\n\n");
+        let mut categories = std::collections::BTreeMap::new();
+        
+        // Group NFRs by category
+        for nfr in nfrs {
+            categories.entry(&nfr.category).or_insert(Vec::new()).push(nfr);
+        }
+        
+        for (category, category_nfrs) in categories {
+            let category_emoji = match category {
+                crate::analyzer::NfrCategory::Performance => "⚡",
+                crate::analyzer::NfrCategory::Security => "🔒",
+                crate::analyzer::NfrCategory::Usability => "👤",
+                crate::analyzer::NfrCategory::Reliability => "🛡️",
+                crate::analyzer::NfrCategory::Scalability => "📈",
+                crate::analyzer::NfrCategory::Maintainability => "🔧",
+                crate::analyzer::NfrCategory::Compatibility => "🔗",
+                crate::analyzer::NfrCategory::Accessibility => "♿",
+            };
+            output.push_str(&format!("### {} {:?}\n\n", category_emoji, category));
+            
+            for nfr in category_nfrs {
+                let priority_text = match nfr.priority {
+                    crate::analyzer::NfrPriority::MustHave => "🔴 Must Have",
+                    crate::analyzer::NfrPriority::ShouldHave => "🟠 Should Have",
+                    crate::analyzer::NfrPriority::CouldHave => "🟡 Could Have",
+                    crate::analyzer::NfrPriority::WontHave => "⚫ Won't Have",
+                };
+                output.push_str(&format!("**{}**\n\n", priority_text));
+                output.push_str(&format!("**Requirement:** {}\n\n", nfr.requirement));
+                output.push_str(&format!("**Rationale:** {}\n\n", nfr.rationale));
+                
+                if !nfr.acceptance_criteria.is_empty() {
+                    output.push_str("**Acceptance Criteria:**\n");
+                    for criteria in &nfr.acceptance_criteria {
+                        output.push_str(&format!("- {}\n", criteria));
+                    }
+                    output.push_str("\n");
+                }
+            }
+        }
+    }
+
+    if let Some(matrix) = &result.permission_matrix {
+        output.push_str("## 🔐 Actor Permission Matrix\n\n");
+        output.push_str(&format_permission_table(matrix));
+    }
+
+    if let Some(graph) = &result.dependency_graph {
+        output.push_str("## 🔗 Requirement Dependencies\n\n");
+        output.push_str(&format_dependency_markdown(graph));
+    }
+
+    if let Some(smart) = &result.smart_analysis {
+        output.push_str("## 🎯 SMART Analysis\n\n");
+        output.push_str(&format_smart_markdown(smart));
+    }
+
+    if let Some(model) = &result.domain_model {
+        output.push_str("## 🏛️ Domain Model\n\n");
+        output.push_str(&format_domain_model_markdown(model));
+        if !model.aggregates.is_empty() {
+            output.push_str(&format!("```plantuml\n{}```\n\n", format_domain_model_plantuml(model)));
+        }
+    }
+
+    if let Some(board) = &result.event_storming {
+        output.push_str("## 🟧 Event Storming\n\n");
+        output.push_str(&format_event_storming_markdown(board));
+    }
+
+    if let Some(scenarios) = result.load_tests.as_ref().filter(|s| !s.is_empty()) {
+        output.push_str("## 🏋️ Load Tests\n\n");
+        output.push_str(&format_load_tests_markdown(scenarios));
+    }
+
+    if let Some(tests) = result.security_tests.as_ref().filter(|t| !t.is_empty()) {
+        output.push_str("## 🛡️ Security Tests\n\n");
+        output.push_str(&format_security_tests_markdown(tests));
+    }
+
+    if let Some(reviewers) = &result.reviewer_suggestions {
+        output.push_str("## 👀 Suggested Reviewers\n\n");
+        output.push_str(&format_reviewer_table(reviewers));
+    }
+
+    if let Some(ears) = &result.ears_validation {
+        output.push_str("## 📐 EARS Conformance\n\n");
+        output.push_str(&format_ears_markdown(ears));
+    }
+
+    output
+}
+
+pub fn format_as_jira(result: &AnalysisResult, input_text: &str) -> String {
+    let mut output = String::new();
+    
+    output.push_str("h1. 🔍 PRISM Analysis Report\n\n");
+
+    // Input echo section
+    output.push_str("h2. 📝 Analyzed Requirement\n");
+    let quoted = format!("{{quote}}\n{}\n{{quote}}\n", input_text.trim());
+    output.push_str(&jira_expand_if_long("Show full requirement text", input_text.lines().count(), &quoted));
+    output.push('\n');
+
+    // Summary section
+    output.push_str("{panel:title=📊 Analysis Summary|borderStyle=solid|titleBGColor=#DEEBFF}\n");
+    output.push_str(&format!("* Ambiguities Found: {}\n", result.ambiguities.len()));
+    output.push_str(&format!("* Ambiguity Density: {}\n", density(result)));
+    if !result.suppressed_ambiguities.is_empty() {
+        output.push_str(&format!("* Suppressed Findings: {}\n", result.suppressed_ambiguities.len()));
+    }
+    if let Some(hidden) = result.hidden_findings.summary() {
+        output.push_str(&format!("* Hidden Findings: {}\n", hidden));
+    }
+    output.push_str(&format!("* Actors Identified: {}\n", result.entities.actors.len()));
+    output.push_str(&format!("* Actions Identified: {}\n", result.entities.actions.len()));
+    output.push_str(&format!("* Objects Identified: {}\n", result.entities.objects.len()));
+    output.push_str("{panel}\n\n");
+
+    // Entities section
+    output.push_str("h2. 🎯 Extracted Entities\n");
+    for (title, values, empty) in [
+        ("👥 Actors (Who)", &result.entities.actors, "No actors identified"),
+        ("⚡ Actions (What)", &result.entities.actions, "No actions identified"),
+        ("📦 Objects (What On)", &result.entities.objects, "No objects identified"),
+    ] {
+        output.push_str(&format!("h3. {}\n", title));
+        if values.is_empty() {
+            output.push_str(&format!("* {}\n", empty));
+        } else {
+            let list: String = values.iter().map(|v| format!("* {}\n", v)).collect();
+            output.push_str(&jira_expand_if_long(&format!("Show all {} items", values.len()), values.len(), &list));
+        }
+        output.push('\n');
+    }
+
+    // Ambiguities section
+    output.push_str("h2. ⚠️ Detected Ambiguities\n");
+    if result.ambiguities.is_empty() {
+        output.push_str("{panel:bgColor=#E3FCEF}\n✅ *No ambiguities detected - your requirements are clear!*\n{panel}\n\n");
+    } else {
+        let mut panels = String::new();
+        for (i, ambiguity) in result.ambiguities.iter().enumerate() {
+            let (severity_icon, background) = match ambiguity.severity {
+                AmbiguitySeverity::Critical => ("🔴", "#FFEBE6"),
+                AmbiguitySeverity::High => ("🟠", "#FFF0E0"),
+                AmbiguitySeverity::Medium => ("🟡", "#FFFAE6"),
+                AmbiguitySeverity::Low => ("🟢", "#E3FCEF"),
+            };
+            panels.push_str(&format!("{{panel:title={} Issue #{}: {}|bgColor={}}}\n",
+                severity_icon, i + 1, jira_escape_title(&ambiguity.text), background));
+            if let Some(location) = &ambiguity.location {
+                panels.push_str(&format!("* *Location:* {}\n", location));
+            }
+            panels.push_str(&format!("* *Problem:* {}\n", ambiguity.reason));
+            panels.push_str(&format!("* *Severity:* {:?}\n", ambiguity.severity));
+            panels.push_str("* *Suggested Improvements:*\n");
+            for suggestion in &ambiguity.suggestions {
+                panels.push_str(&format!("** {}\n", suggestion));
+            }
+            panels.push_str("{panel}\n");
+        }
+        output.push_str(&jira_expand_if_long(
+            &format!("Show all {} ambiguities", result.ambiguities.len()),
+            result.ambiguities.len(),
+            &panels,
+        ));
+        output.push('\n');
+    }
+
+    // Test cases section (only if generated)
+    if let Some(tests) = &result.test_cases {
+        output.push_str("h2. ✅ Suggested Test Cases\n");
+        for (title, cases, empty) in [
+            ("😊 Happy Path Tests", &tests.happy_path, "No happy path tests generated"),
+            ("❌ Negative Test Cases", &tests.negative_cases, "No negative test cases generated"),
+            ("🔍 Edge Case Tests", &tests.edge_cases, "No edge case tests generated"),
+        ] {
+            output.push_str(&format!("h3. {}\n", title));
+            if cases.is_empty() {
+                output.push_str(&format!("* {}\n", empty));
+            } else {
+                let list: String = cases.iter().map(|c| format!("- [ ] {}\n", c)).collect();
+                output.push_str(&jira_expand_if_long(&format!("Show all {} tests", cases.len()), cases.len(), &list));
+            }
+            output.push('\n');
+        }
+    }
+
+    output
+}
+
+/// Wraps `body` in a collapsed `{expand}` macro once it has more than
+/// `JIRA_EXPAND_THRESHOLD` lines or items, so long sections don't swamp the ticket.
+fn jira_expand_if_long(title: &str, size: usize, body: &str) -> String {
+    if size > JIRA_EXPAND_THRESHOLD {
+        format!("{{expand:{}}}\n{}{{expand}}\n", title, body)
+    } else {
+        body.to_string()
+    }
+}
+
+// Panel titles are macro parameters, so `|`, `{` and `}` would break them
+fn jira_escape_title(title: &str) -> String {
+    title.replace(['|', '{', '}'], " ")
+}
+
+/// Renders the report as an Atlassian Document Format (ADF) document that
+/// can be sent as the `description` of an issue through the Jira REST API.
+pub fn format_as_jira_adf(result: &AnalysisResult, input_text: &str) -> Result<String> {
+    use serde_json::{json, Value};
+
+    let text = |value: &str| json!({ "type": "text", "text": value });
+    let strong = |value: &str| json!({ "type": "text", "text": value, "marks": [{ "type": "strong" }] });
+    let paragraph = |content: Vec<Value>| json!({ "type": "paragraph", "content": content });
+    let heading = |level: u8, value: &str| json!({ "type": "heading", "attrs": { "level": level }, "content": [text(value)] });
+    let bullets = |items: Vec<Vec<Value>>| json!({
+        "type": "bulletList",
+        "content": items.into_iter()
+            .map(|item| json!({ "type": "listItem", "content": [paragraph(item)] }))
+            .collect::<Vec<_>>(),
+    });
+    let expand_if_long = |title: String, size: usize, content: Vec<Value>| -> Vec<Value> {
+        if size > JIRA_EXPAND_THRESHOLD {
+            vec![json!({ "type": "expand", "attrs": { "title": title }, "content": content })]
+        } else {
+            content
+        }
+    };
+
+    let mut content: Vec<Value> = Vec::new();
+    content.push(heading(1, "🔍 PRISM Analysis Report"));
+
+    content.push(heading(2, "📝 Analyzed Requirement"));
+    let quote: Vec<Value> = input_text.trim().lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| paragraph(vec![text(line)]))
+        .collect();
+    content.extend(expand_if_long(
+        "Show full requirement text".to_string(),
+        quote.len(),
+        vec![json!({ "type": "blockquote", "content": quote })],
+    ));
+
+    let mut summary = vec![
+        vec![strong("Ambiguities Found: "), text(&result.ambiguities.len().to_string())],
+        vec![strong("Ambiguity Density: "), text(&density(result))],
+    ];
+    if !result.suppressed_ambiguities.is_empty() {
+        summary.push(vec![strong("Suppressed Findings: "), text(&result.suppressed_ambiguities.len().to_string())]);
+    }
+    if let Some(hidden) = result.hidden_findings.summary() {
+        summary.push(vec![strong("Hidden Findings: "), text(&hidden)]);
+    }
+    summary.push(vec![strong("Actors Identified: "), text(&result.entities.actors.len().to_string())]);
+    summary.push(vec![strong("Actions Identified: "), text(&result.entities.actions.len().to_string())]);
+    summary.push(vec![strong("Objects Identified: "), text(&result.entities.objects.len().to_string())]);
+    content.push(heading(2, "📊 Analysis Summary"));
+    content.push(json!({ "type": "panel", "attrs": { "panelType": "info" }, "content": [bullets(summary)] }));
+
+    content.push(heading(2, "🎯 Extracted Entities"));
+    for (title, values) in [
+        ("👥 Actors (Who)", &result.entities.actors),
+        ("⚡ Actions (What)", &result.entities.actions),
+        ("📦 Objects (What On)", &result.entities.objects),
+    ] {
+        content.push(heading(3, title));
+        if values.is_empty() {
+            content.push(paragraph(vec![text("None identified")]));
+        } else {
+            let list = bullets(values.iter().map(|v| vec![text(v)]).collect());
+            content.extend(expand_if_long(format!("Show all {} items", values.len()), values.len(), vec![list]));
+        }
+    }
+
+    content.push(heading(2, "⚠️ Detected Ambiguities"));
+    if result.ambiguities.is_empty() {
+        content.push(json!({
+            "type": "panel",
+            "attrs": { "panelType": "success" },
+            "content": [paragraph(vec![text("No ambiguities detected - your requirements are clear!")])],
+        }));
+    } else {
+        let panels: Vec<Value> = result.ambiguities.iter().enumerate().map(|(i, ambiguity)| {
+            let panel_type = match ambiguity.severity {
+                AmbiguitySeverity::Critical | AmbiguitySeverity::High => "error",
+                AmbiguitySeverity::Medium => "warning",
+                AmbiguitySeverity::Low => "note",
+            };
+            let mut items: Vec<Vec<Value>> = ambiguity.location.iter()
+                .map(|location| vec![strong("Location: "), text(&location.to_string())])
+                .collect();
+            items.push(vec![strong("Problem: "), text(&ambiguity.reason)]);
+            items.push(vec![strong("Severity: "), text(&format!("{:?}", ambiguity.severity))]);
+            items.extend(ambiguity.suggestions.iter().map(|s| vec![strong("Suggestion: "), text(s)]));
+            json!({
+                "type": "panel",
+                "attrs": { "panelType": panel_type },
+                "content": [
+                    paragraph(vec![strong(&format!("Issue #{}: \"{}\"", i + 1, ambiguity.text))]),
+                    bullets(items),
+                ],
+            })
+        }).collect();
+        content.extend(expand_if_long(
+            format!("Show all {} ambiguities", result.ambiguities.len()),
+            result.ambiguities.len(),
+            panels,
+        ));
+    }
+
+    if let Some(tests) = &result.test_cases {
+        content.push(heading(2, "✅ Suggested Test Cases"));
+        for (title, cases) in [
+            ("😊 Happy Path Tests", &tests.happy_path),
+            ("❌ Negative Test Cases", &tests.negative_cases),
+            ("🔍 Edge Case Tests", &tests.edge_cases),
+        ] {
+            content.push(heading(3, title));
+            if cases.is_empty() {
+                content.push(paragraph(vec![text("None generated")]));
+            } else {
+                let list = bullets(cases.iter().map(|c| vec![text(c)]).collect());
+                content.extend(expand_if_long(format!("Show all {} tests", cases.len()), cases.len(), vec![list]));
+            }
+        }
+    }
+
+    let mut document = json!({ "version": 1, "type": "doc", "content": content });
+    prune_empty_adf_nodes(&mut document);
+    Ok(serde_json::to_string_pretty(&document)?)
+}
+
+/// Drops what Jira rejects in an ADF document: text nodes without text, and
+/// blocks such as a blockquote or a list that are left without content.
+fn prune_empty_adf_nodes(node: &mut serde_json::Value) {
+    let Some(content) = node.get_mut("content").and_then(|content| content.as_array_mut()) else { return };
+    for child in content.iter_mut() {
+        prune_empty_adf_nodes(child);
+    }
+    content.retain(|child| match child["type"].as_str() {
+        Some("text") => child["text"].as_str().is_some_and(|text| !text.is_empty()),
+        Some("blockquote" | "bulletList" | "listItem" | "panel" | "expand") => child["content"].as_array().is_some_and(|c| !c.is_empty()),
+        _ => true,
+    });
+}
+
+pub fn format_as_github(result: &AnalysisResult, _input_text: &str) -> String {
+    let mut output = String::new();
+    
+    output.push_str("# Requirement Analysis Report\n\n");
+    output.push_str(&format!("**Ambiguity density:** {}\n\n", density(result)));
+
+    if !result.ambiguities.is_empty() {
+        output.push_str("## :warning: Detected Ambiguities\n\n");
+        let collapse = result.ambiguities.len() > GITHUB_DETAILS_THRESHOLD;
+        if collapse {
+            output.push_str(&format!("<details>\n<summary>{} ambiguities (click to expand)</summary>\n\n", result.ambiguities.len()));
+        }
+        for ambiguity in &result.ambiguities {
+            let emoji = match ambiguity.severity {
+                crate::analyzer::AmbiguitySeverity::Critical => ":red_circle:",
+                crate::analyzer::AmbiguitySeverity::High => ":orange_circle:",
+                crate::analyzer::AmbiguitySeverity::Medium => ":yellow_circle:",
+                crate::analyzer::AmbiguitySeverity::Low => ":green_circle:",
+            };
+            output.push_str(&format!("### {} {}\n", emoji, ambiguity.text));
+            if let Some(location) = &ambiguity.location {
+                output.push_str(&format!("**Location:** `{}`\n\n", location));
+            }
+            output.push_str(&format!("**Reason:** {}\n\n", ambiguity.reason));
+            output.push_str("**Suggestions:**\n");
+            for suggestion in &ambiguity.suggestions {
+                output.push_str(&format!("- {}\n", suggestion));
+            }
+            output.push('\n');
+        }
+        if collapse {
+            output.push_str("</details>\n\n");
+        }
+    }
+
+    if !result.suppressed_ambiguities.is_empty() {
+        output.push_str(&format!("> :mute: {} finding(s) suppressed by inline `prism:ignore` / `[prism-ok]` markers\n\n", result.suppressed_ambiguities.len()));
+    }
+    if let Some(hidden) = result.hidden_findings.summary() {
+        output.push_str(&format!("> :see_no_evil: Not shown: {}\n\n", hidden));
+    }
+
+    output.push_str("## :mag: Extracted Entities\n\n");
+    output.push_str(&format!("**:bust_in_silhouette: Actors:** {}\n\n", result.entities.actors.join(", ")));
+    output.push_str(&format!("**:zap: Actions:** {}\n\n", result.entities.actions.join(", ")));
+    output.push_str(&format!("**:package: Objects:** {}\n\n", result.entities.objects.join(", ")));
+
+    if let Some(tests) = &result.test_cases {
+        output.push_str("## :white_check_mark: Test Cases Checklist\n\n");
+        let total = tests.happy_path.len() + tests.negative_cases.len() + tests.edge_cases.len();
+        let collapse = total > GITHUB_DETAILS_THRESHOLD;
+        if collapse {
+            output.push_str(&format!("<details>\n<summary>{} test cases (click to expand)</summary>\n\n", total));
+        }
+        output.push_str("### Happy Path\n");
+        for test in &tests.happy_path {
+            output.push_str(&format!("- [ ] {}\n", test));
+        }
+        output.push_str("\n### Negative Cases\n");
+        for test in &tests.negative_cases {
+            output.push_str(&format!("- [ ] {}\n", test));
+        }
+        output.push_str("\n### Edge Cases\n");
+        for test in &tests.edge_cases {
+            output.push_str(&format!("- [ ] {}\n", test));
+        }
+        if collapse {
+            output.push_str("\n</details>\n");
+        }
+    }
+
+    if let Some(coverage) = &result.test_coverage {
+        output.push_str(&format!("\n## :compass: Test Coverage: {} of {} requirements without tests\n\n", coverage.untested().len(), coverage.requirements.len()));
+        output.push_str("<details>\n<summary>Requirement-to-test matrix (click to expand)</summary>\n\n");
+        output.push_str(&format_test_coverage_markdown(coverage));
+        output.push_str("</details>\n\n");
+    }
+
+    if let Some(suggestions) = &result.rewrite_suggestions {
+        if !suggestions.is_empty() {
+            output.push_str("## :pencil2: Suggested Rewrites\n\n");
+            for suggestion in suggestions {
+                output.push_str(&format!("**`{}`**{}\n", suggestion.finding,
+                    suggestion.line.map(|l| format!(" (line {})", l)).unwrap_or_default()));
+                output.push_str(&format!("```diff\n- {}\n+ {}\n```\n\n", suggestion.original, suggestion.proposed));
+            }
+        }
+    }
+
+    if let Some(graph) = &result.dependency_graph {
+        for cycle in &graph.cycles {
+            output.push_str(&format!("> :warning: **Dependency cycle:** {}\n\n", format_cycle(cycle)));
+        }
+    }
+
+    if let Some(reviewers) = &result.reviewer_suggestions {
+        output.push_str("## :eyes: Suggested Reviewers\n\n");
+        output.push_str(&format_reviewer_table(reviewers));
+    }
+
+    if let Some(ears) = &result.ears_validation {
+        output.push_str("## :triangular_ruler: EARS Conformance\n\n");
+        output.push_str(&format_ears_markdown(ears));
+    }
+
+    output
+}
+
+pub fn format_as_plain(result: &AnalysisResult, _input_text: &str) -> String {
+    let mut output = String::new();
+    
+    output.push_str("REQUIREMENT ANALYSIS REPORT\n");
+    output.push_str("===========================\n\n");
+
+    output.push_str(&format!("AMBIGUITY DENSITY: {}\n\n", density(result)));
+
+    output.push_str("DETECTED AMBIGUITIES:\n");
+    for (i, ambiguity) in result.ambiguities.iter().enumerate() {
+        output.push_str(&format!("{}. {}\n", i + 1, ambiguity.text));
+        if let Some(location) = &ambiguity.location {
+            output.push_str(&format!("   Location: {}\n", location));
+        }
+        output.push_str(&format!("   Reason: {}\n", ambiguity.reason));
+        output.push_str(&format!("   Severity: {:?}\n", ambiguity.severity));
+        output.push_str("   Suggestions:\n");
+        for suggestion in &ambiguity.suggestions {
+            output.push_str(&format!("   - {}\n", suggestion));
+        }
+        output.push('\n');
+    }
+
+    if !result.suppressed_ambiguities.is_empty() {
+        output.push_str(&format!("SUPPRESSED FINDINGS: {}\n\n", result.suppressed_ambiguities.len()));
+    }
+    if let Some(hidden) = result.hidden_findings.summary() {
+        output.push_str(&format!("HIDDEN FINDINGS: {}\n\n", hidden));
+    }
+
+    output.push_str("EXTRACTED ENTITIES:\n");
+    output.push_str(&format!("Actors: {}\n", result.entities.actors.join(", ")));
+    output.push_str(&format!("Actions: {}\n", result.entities.actions.join(", ")));
+    output.push_str(&format!("Objects: {}\n\n", result.entities.objects.join(", ")));
+
+    if let Some(tests) = &result.test_cases {
+        output.push_str("SUGGESTED TEST CASES:\n");
+        output.push_str("Happy Path:\n");
+        for test in &tests.happy_path {
+            output.push_str(&format!("- {}\n", test));
+        }
+        output.push_str("\nNegative Cases:\n");
+        for test in &tests.negative_cases {
+            output.push_str(&format!("- {}\n", test));
+        }
+        output.push_str("\nEdge Cases:\n");
+        for test in &tests.edge_cases {
+            output.push_str(&format!("- {}\n", test));
+        }
+    }
+
+    output
+}
+
+pub fn format_rewrite_suggestions(suggestions: &[RewriteSuggestion]) -> String {
+    let mut output = String::from("## ✏️ Suggested Rewrites\n\n");
+    if suggestions.is_empty() {
+        output.push_str("No findings to rewrite.\n\n");
+        return output;
+    }
+
+    for (i, suggestion) in suggestions.iter().enumerate() {
+        let line = suggestion.line.map(|l| format!(" (line {})", l)).unwrap_or_default();
+        output.push_str(&format!("<a id=\"{}\"></a>\n\n", suggestion_anchor(i + 1)));
+        output.push_str(&format!("### {}. \"{}\"{}\n\n", i + 1, suggestion.finding, line));
+        output.push_str(&format!("**Original:** {}\n\n", suggestion.original));
+        output.push_str(&format!("**Proposed:** {}\n\n", suggestion.proposed));
+    }
+    output
+}
+
+pub fn format_permission_table(matrix: &PermissionMatrix) -> String {
+    let mut output = String::new();
+
+    if matrix.actors.is_empty() || matrix.actions.is_empty() {
+        output.push_str("*No actors or actions could be identified.*\n\n");
+    } else {
+        output.push_str("| Action |");
+        for actor in &matrix.actors {
+            output.push_str(&format!(" {} |", actor));
+        }
+        output.push_str("\n|---|");
+        for _ in &matrix.actors {
+            output.push_str(":---:|");
+        }
+        output.push('\n');
+
+        for action in &matrix.actions {
+            output.push_str(&format!("| {} |", action));
+            for actor in &matrix.actors {
+                let cell = if matrix.is_granted(actor, action) { "✅" } else { "" };
+                output.push_str(&format!(" {} |", cell));
+            }
+            output.push('\n');
+        }
+        output.push('\n');
+    }
+
+    if !matrix.unassigned_actions.is_empty() {
+        output.push_str("### ⚠️ Actions Without a Responsible Actor\n\n");
+        for action in &matrix.unassigned_actions {
+            output.push_str(&format!("- {}\n", action));
+        }
+        output.push('\n');
+    }
+
+    output
+}
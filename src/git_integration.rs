@@ -1,9 +1,11 @@
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use tokio::fs;
+
+use crate::html_report::{card, html_escape, html_page, list, section};
+use crate::locale::report_locale;
+use crate::paths;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitDiffAnalysis {
@@ -59,6 +61,17 @@ pub enum RegressionRisk {
     Critical,
 }
 
+impl RegressionRisk {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            RegressionRisk::Low => "🟢",
+            RegressionRisk::Medium => "🟡",
+            RegressionRisk::High => "🟠",
+            RegressionRisk::Critical => "🔴",
+        }
+    }
+}
+
 pub struct GitIntegration {
     repo_path: PathBuf,
 }
@@ -68,6 +81,25 @@ impl GitIntegration {
         Self { repo_path }
     }
 
+    pub fn repo_path(&self) -> &Path {
+        &self.repo_path
+    }
+
+    /// Integration for the repository containing `path`, found with `git rev-parse`.
+    pub fn discover(path: &Path) -> Result<Self> {
+        let output = Command::new("git")
+            .args(["rev-parse", "--show-toplevel"])
+            .current_dir(path)
+            .output()
+            .map_err(|_| anyhow!("Git command not available"))?;
+
+        if !output.status.success() {
+            return Err(anyhow!("Not a git repository: {}", path.display()));
+        }
+
+        Ok(Self::new(PathBuf::from(String::from_utf8(output.stdout)?.trim())))
+    }
+
     pub async fn analyze_requirement_changes(
         &self,
         from_commit: &str,
@@ -111,7 +143,7 @@ impl GitIntegration {
 
     pub fn get_current_branch(&self) -> Result<String> {
         let output = Command::new("git")
-            .args(&["branch", "--show-current"])
+            .args(["branch", "--show-current"])
             .current_dir(&self.repo_path)
             .output()?;
 
@@ -125,7 +157,7 @@ impl GitIntegration {
 
     pub fn get_recent_commits(&self, count: usize) -> Result<Vec<CommitInfo>> {
         let output = Command::new("git")
-            .args(&["log", &format!("-{}", count), "--pretty=format:%H|%s|%an|%ad", "--date=iso"])
+            .args(["log", &format!("-{}", count), "--pretty=format:%H|%s|%an|%ad", "--date=iso"])
             .current_dir(&self.repo_path)
             .output()?;
 
@@ -134,46 +166,54 @@ impl GitIntegration {
                 String::from_utf8_lossy(&output.stderr)));
         }
 
-        let commits_text = String::from_utf8(output.stdout)?;
-        let mut commits = Vec::new();
-
-        for line in commits_text.lines() {
-            let parts: Vec<&str> = line.split('|').collect();
-            if parts.len() >= 4 {
-                commits.push(CommitInfo {
-                    hash: parts[0].to_string(),
-                    message: parts[1].to_string(),
-                    author: parts[2].to_string(),
-                    date: parts[3].to_string(),
-                });
-            }
-        }
-
-        Ok(commits)
+        Ok(parse_commits(&String::from_utf8(output.stdout)?))
     }
 
-    pub fn get_modified_requirements_since_commit(&self, since_commit: &str) -> Result<Vec<PathBuf>> {
+    /// Commits that changed `path`, newest first, following renames.
+    pub fn file_history(&self, path: &Path) -> Result<Vec<CommitInfo>> {
         let output = Command::new("git")
-            .args(&["diff", "--name-only", since_commit, "HEAD"])
+            .args(["log", "--follow", "--pretty=format:%H|%s|%an|%ad", "--date=iso", "--"])
+            // git does not take Windows' `\\?\` paths
+            .arg(paths::simplify(&std::fs::canonicalize(path)?))
             .current_dir(&self.repo_path)
             .output()?;
 
         if !output.status.success() {
-            return Err(anyhow!("Failed to get modified files: {}", 
-                String::from_utf8_lossy(&output.stderr)));
+            return Err(anyhow!("Failed to get the history of {}: {}",
+                path.display(), String::from_utf8_lossy(&output.stderr)));
         }
 
-        let files_text = String::from_utf8(output.stdout)?;
-        let mut requirement_files = Vec::new();
+        Ok(parse_commits(&String::from_utf8(output.stdout)?))
+    }
 
-        for line in files_text.lines() {
-            let path = PathBuf::from(line.trim());
-            if self.is_requirement_file(&path) {
-                requirement_files.push(path);
-            }
+    /// The requirement files added or modified between `since_commit` and
+    /// `HEAD`, under the repository root. Deleted files are left out.
+    pub fn get_modified_requirements_since_commit(&self, since_commit: &str) -> Result<Vec<PathBuf>> {
+        Ok(self.changed_files_since(since_commit)?
+            .into_iter()
+            .filter(|path| self.is_requirement_file(path.strip_prefix(&self.repo_path).unwrap_or(path)))
+            .collect())
+    }
+
+    /// Every file added or modified between `since_commit` and `HEAD`, under
+    /// the repository root. Deleted files are left out.
+    pub fn changed_files_since(&self, since_commit: &str) -> Result<Vec<PathBuf>> {
+        let output = Command::new("git")
+            .args(["diff", "--name-only", "-z", "--diff-filter=d", since_commit, "HEAD"])
+            .current_dir(&self.repo_path)
+            .output()
+            .map_err(|_| anyhow!("Git command not available"))?;
+
+        if !output.status.success() {
+            return Err(anyhow!("Failed to get modified files since {}: {}", since_commit,
+                String::from_utf8_lossy(&output.stderr).trim()));
         }
 
-        Ok(requirement_files)
+        Ok(String::from_utf8(output.stdout)?
+            .split('\0')
+            .filter(|name| !name.is_empty())
+            .map(|name| self.repo_path.join(name))
+            .collect())
     }
 
     fn validate_git_repo(&self) -> Result<()> {
@@ -184,7 +224,7 @@ impl GitIntegration {
 
         // Check if git command is available
         let output = Command::new("git")
-            .args(&["status", "--porcelain"])
+            .args(["status", "--porcelain"])
             .current_dir(&self.repo_path)
             .output();
 
@@ -197,7 +237,7 @@ impl GitIntegration {
 
     fn get_changed_files(&self, from_commit: &str, to_commit: &str) -> Result<Vec<FileChange>> {
         let output = Command::new("git")
-            .args(&["diff", "--name-status", from_commit, to_commit])
+            .args(["diff", "--name-status", from_commit, to_commit])
             .current_dir(&self.repo_path)
             .output()?;
 
@@ -210,10 +250,11 @@ impl GitIntegration {
         let mut changes = Vec::new();
 
         for line in diff_text.lines() {
-            let parts: Vec<&str> = line.split_whitespace().collect();
+            // `<status>\t<path>`, or `<status>\t<old path>\t<new path>` for renames
+            let parts: Vec<&str> = line.split('\t').collect();
             if parts.len() >= 2 {
                 let status = parts[0];
-                let file_path = PathBuf::from(parts[1]);
+                let file_path = PathBuf::from(parts[parts.len() - 1]);
 
                 let change_type = match status {
                     "A" => ChangeType::Added,
@@ -243,7 +284,7 @@ impl GitIntegration {
 
     fn get_file_diff(&self, file_path: &Path, from_commit: &str, to_commit: &str) -> Result<String> {
         let output = Command::new("git")
-            .args(&["diff", from_commit, to_commit, "--", file_path.to_str().unwrap_or("")])
+            .args(["diff", from_commit, to_commit, "--", file_path.to_str().unwrap_or("")])
             .current_dir(&self.repo_path)
             .output()?;
 
@@ -317,7 +358,7 @@ impl GitIntegration {
         let impact_score = self.calculate_change_impact_score(&old_content, &new_content);
 
         // Extract affected requirements (simplified)
-        let affected_requirements = self.extract_requirement_ids(&new_content.as_deref().unwrap_or(""));
+        let affected_requirements = self.extract_requirement_ids(new_content.as_deref().unwrap_or(""));
 
         Ok(RequirementChange {
             file_path: file_path.to_path_buf(),
@@ -331,7 +372,7 @@ impl GitIntegration {
 
     async fn get_file_content_at_commit(&self, file_path: &Path, commit: &str) -> Result<Option<String>> {
         let output = Command::new("git")
-            .args(&["show", &format!("{}:{}", commit, file_path.display())])
+            .args(["show", &format!("{}:{}", commit, file_path.display())])
             .current_dir(&self.repo_path)
             .output()?;
 
@@ -529,4 +570,198 @@ pub struct CommitInfo {
     pub message: String,
     pub author: String,
     pub date: String,
-}
\ No newline at end of file
+}
+
+/// `git log --pretty=format:%H|%s|%an|%ad` lines as commits.
+fn parse_commits(text: &str) -> Vec<CommitInfo> {
+    text.lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split('|').collect();
+            (parts.len() >= 4).then(|| CommitInfo {
+                hash: parts[0].to_string(),
+                message: parts[1].to_string(),
+                author: parts[2].to_string(),
+                date: parts[3].to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Recommendation lines come pre-formatted: headings, then "  • " details.
+fn recommendation_item(line: &str) -> (bool, &str) {
+    match line.strip_prefix("  • ") {
+        Some(detail) => (true, detail),
+        None => (false, line),
+    }
+}
+
+pub fn format_git_trace_markdown(analysis: &GitDiffAnalysis) -> String {
+    let mut output = String::new();
+    let impact = &analysis.impact_analysis;
+
+    output.push_str(&format!("# 🔀 PRISM Requirement Change Trace: `{}`..`{}`\n\n", analysis.from_commit, analysis.to_commit));
+    output.push_str(&format!("**Regression risk:** {} {:?}\n\n", impact.regression_risk.icon(), impact.regression_risk));
+    output.push_str(&format!("**Impact score:** {}\n\n", report_locale().percent(impact.estimated_impact_score * 100.0, 0)));
+    output.push_str(&format!(
+        "**Files changed:** {} ({} requirement file{})\n\n",
+        impact.total_files_changed,
+        impact.requirement_files_changed,
+        if impact.requirement_files_changed == 1 { "" } else { "s" }
+    ));
+
+    if !analysis.requirement_changes.is_empty() {
+        output.push_str("## 📄 Requirement Changes\n\n");
+        output.push_str("| File | Change | Impact | Requirement IDs |\n|---|---|---:|---|\n");
+        for change in &analysis.requirement_changes {
+            output.push_str(&format!(
+                "| `{}` | {:?} | {} | {} |\n",
+                change.file_path.display(),
+                change.change_type,
+                report_locale().percent(change.impact_score * 100.0, 0),
+                if change.affected_requirements.is_empty() { "—".to_string() } else { change.affected_requirements.join(", ") }
+            ));
+        }
+        output.push('\n');
+    }
+
+    if !impact.high_impact_changes.is_empty() {
+        output.push_str("## ⚠️ High-Impact Changes\n\n");
+        for change in &impact.high_impact_changes {
+            output.push_str(&format!("- {}\n", change));
+        }
+        output.push('\n');
+    }
+
+    output.push_str("## 💡 Recommendations\n\n");
+    for line in &analysis.recommendations {
+        match recommendation_item(line) {
+            (true, detail) => output.push_str(&format!("  - {}\n", detail)),
+            (false, heading) => output.push_str(&format!("- **{}**\n", heading.trim_end_matches(':'))),
+        }
+    }
+    output.push('\n');
+
+    if !analysis.changed_files.is_empty() {
+        output.push_str("## 📁 All Changed Files\n\n");
+        output.push_str("| File | Change | Added | Removed |\n|---|---|---:|---:|\n");
+        for file in &analysis.changed_files {
+            output.push_str(&format!(
+                "| `{}` | {:?} | +{} | -{} |\n",
+                file.file_path.display(), file.change_type, file.lines_added, file.lines_removed
+            ));
+        }
+        output.push('\n');
+    }
+
+    output
+}
+
+pub fn format_git_trace_jira(analysis: &GitDiffAnalysis) -> String {
+    let mut output = String::new();
+    let impact = &analysis.impact_analysis;
+
+    output.push_str(&format!("h1. PRISM Requirement Change Trace: {{{{{}}}}}..{{{{{}}}}}\n\n", analysis.from_commit, analysis.to_commit));
+    output.push_str(&format!("*Regression risk:* {:?}\n", impact.regression_risk));
+    output.push_str(&format!("*Impact score:* {}\n", report_locale().percent(impact.estimated_impact_score * 100.0, 0)));
+    output.push_str(&format!("*Files changed:* {} ({} requirement files)\n\n", impact.total_files_changed, impact.requirement_files_changed));
+
+    if !analysis.requirement_changes.is_empty() {
+        output.push_str("h2. Requirement Changes\n\n||File||Change||Impact||Requirement IDs||\n");
+        for change in &analysis.requirement_changes {
+            output.push_str(&format!(
+                "|{}|{:?}|{}|{}|\n",
+                change.file_path.display(),
+                change.change_type,
+                report_locale().percent(change.impact_score * 100.0, 0),
+                if change.affected_requirements.is_empty() { "-".to_string() } else { change.affected_requirements.join(", ") }
+            ));
+        }
+        output.push('\n');
+    }
+
+    output.push_str("h2. Recommendations\n\n");
+    for line in &analysis.recommendations {
+        match recommendation_item(line) {
+            (true, detail) => output.push_str(&format!("** {}\n", detail)),
+            (false, heading) => output.push_str(&format!("* {}\n", heading.trim_end_matches(':'))),
+        }
+    }
+
+    output
+}
+
+pub fn format_git_trace_plain(analysis: &GitDiffAnalysis) -> String {
+    let mut output = String::new();
+    let impact = &analysis.impact_analysis;
+
+    output.push_str(&format!("PRISM REQUIREMENT CHANGE TRACE: {}..{}\n", analysis.from_commit, analysis.to_commit));
+    output.push_str("========================================\n\n");
+    output.push_str(&format!("Regression risk: {:?}\n", impact.regression_risk));
+    output.push_str(&format!("Impact score: {}\n", report_locale().percent(impact.estimated_impact_score * 100.0, 0)));
+    output.push_str(&format!("Files changed: {} ({} requirement files)\n\n", impact.total_files_changed, impact.requirement_files_changed));
+
+    if !analysis.requirement_changes.is_empty() {
+        output.push_str("REQUIREMENT CHANGES:\n");
+        for change in &analysis.requirement_changes {
+            output.push_str(&format!("- {} ({:?}, impact {})\n", change.file_path.display(), change.change_type, report_locale().percent(change.impact_score * 100.0, 0)));
+            if !change.affected_requirements.is_empty() {
+                output.push_str(&format!("  Requirements: {}\n", change.affected_requirements.join(", ")));
+            }
+        }
+        output.push('\n');
+    }
+
+    output.push_str("RECOMMENDATIONS:\n");
+    for line in &analysis.recommendations {
+        output.push_str(&format!("{}\n", line));
+    }
+
+    output
+}
+
+pub fn format_git_trace_html(analysis: &GitDiffAnalysis) -> String {
+    let impact = &analysis.impact_analysis;
+    let mut body = format!(
+        "<h1>🔀 PRISM Requirement Change Trace</h1>\n<p class=\"subtitle\"><code>{}</code>..<code>{}</code></p>\n",
+        html_escape(&analysis.from_commit), html_escape(&analysis.to_commit)
+    );
+
+    body.push_str("<div class=\"cards\">");
+    body.push_str(&card(&format!("{} {:?}", impact.regression_risk.icon(), impact.regression_risk), "Regression risk"));
+    body.push_str(&card(&report_locale().percent(impact.estimated_impact_score * 100.0, 0), "Impact score"));
+    body.push_str(&card(&impact.total_files_changed.to_string(), "Files changed"));
+    body.push_str(&card(&impact.requirement_files_changed.to_string(), "Requirement files"));
+    body.push_str("</div>\n");
+
+    if !analysis.requirement_changes.is_empty() {
+        let mut table = String::from("<table><tr><th>File</th><th>Change</th><th>Impact</th><th>Requirement IDs</th></tr>\n");
+        for change in &analysis.requirement_changes {
+            table.push_str(&format!(
+                "<tr><td><code>{}</code></td><td>{:?}</td><td>{}</td><td>{}</td></tr>\n",
+                html_escape(&change.file_path.display().to_string()),
+                change.change_type,
+                report_locale().percent(change.impact_score * 100.0, 0),
+                html_escape(&change.affected_requirements.join(", "))
+            ));
+        }
+        table.push_str("</table>");
+        body.push_str(&section("📄 Requirement Changes", &table));
+    }
+
+    let mut recommendations = String::from("<ul>");
+    for line in &analysis.recommendations {
+        match recommendation_item(line) {
+            (true, detail) => recommendations.push_str(&format!("<li style=\"margin-left: 24px\">{}</li>", html_escape(detail))),
+            (false, heading) => recommendations.push_str(&format!("<li><strong>{}</strong></li>", html_escape(heading.trim_end_matches(':')))),
+        }
+    }
+    recommendations.push_str("</ul>");
+    body.push_str(&section("💡 Recommendations", &recommendations));
+
+    let changed: Vec<String> = analysis.changed_files.iter()
+        .map(|file| format!("{} ({:?}, +{} -{})", file.file_path.display(), file.change_type, file.lines_added, file.lines_removed))
+        .collect();
+    body.push_str(&section("📁 All Changed Files", &list(&changed)));
+
+    html_page(&format!("PRISM Trace - {}..{}", analysis.from_commit, analysis.to_commit), "", &body, false)
+}
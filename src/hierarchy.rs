@@ -0,0 +1,320 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::analyzer::{AmbiguitySeverity, AnalysisResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HierarchyLevel {
+    Root,
+    Epic,
+    Feature,
+    Story,
+}
+
+/// Finding counts and the resulting 0-100 quality score of a node. For inner
+/// nodes the counts include every descendant.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QualityScore {
+    pub requirement_count: usize,
+    pub finding_count: usize,
+    pub penalty: f32,
+    pub score: f32,
+}
+
+impl QualityScore {
+    /// Scores an analysis result: every finding costs 1-4 penalty points by
+    /// severity, and each penalty point per requirement takes 10 points off 100.
+    pub fn from_result(result: &AnalysisResult) -> Self {
+        let penalty = result.ambiguities.iter()
+            .map(|a| match a.severity {
+                AmbiguitySeverity::Critical => 4.0,
+                AmbiguitySeverity::High => 3.0,
+                AmbiguitySeverity::Medium => 2.0,
+                AmbiguitySeverity::Low => 1.0,
+            })
+            .sum();
+
+        let mut score = QualityScore {
+            requirement_count: result.metrics.requirement_count,
+            finding_count: result.ambiguities.len(),
+            penalty,
+            score: 0.0,
+        };
+        score.update_score();
+        score
+    }
+
+    /// Combined score of several results, e.g. the files of a directory.
+    pub fn total<'a>(scores: impl IntoIterator<Item = &'a QualityScore>) -> Self {
+        let mut total = QualityScore::default();
+        for score in scores {
+            total.add(score);
+        }
+        total.update_score();
+        total
+    }
+
+    fn add(&mut self, other: &QualityScore) {
+        self.requirement_count += other.requirement_count;
+        self.finding_count += other.finding_count;
+        self.penalty += other.penalty;
+    }
+
+    fn update_score(&mut self) {
+        self.score = if self.requirement_count == 0 {
+            100.0
+        } else {
+            (100.0 - self.penalty / self.requirement_count as f32 * 10.0).clamp(0.0, 100.0)
+        };
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HierarchyNode {
+    pub name: String,
+    pub level: HierarchyLevel,
+    pub source: Option<String>,
+    /// Requirement text that belongs to this node itself, not to a child
+    #[serde(skip)]
+    pub text: String,
+    pub own_score: QualityScore,
+    pub score: QualityScore,
+    pub children: Vec<HierarchyNode>,
+}
+
+impl HierarchyNode {
+    fn new(name: &str, source: Option<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            level: HierarchyLevel::Root,
+            source,
+            text: String::new(),
+            own_score: QualityScore::default(),
+            score: QualityScore::default(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Builds the epic → feature → story tree for a set of files. Folders and
+    /// files become nodes, and markdown headings inside a file become nested
+    /// nodes below the file. `files` holds paths relative to the root directory.
+    pub fn build(root_name: &str, files: &[(std::path::PathBuf, String)]) -> Self {
+        let mut root = HierarchyNode::new(root_name, None);
+
+        for (relative_path, text) in files {
+            let mut node = &mut root;
+            if let Some(parent) = relative_path.parent() {
+                for component in parent.components() {
+                    let name = component.as_os_str().to_string_lossy().to_string();
+                    node = node.child_mut(&name, None);
+                }
+            }
+
+            let file_name = relative_path.file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let file_node = node.child_mut(&file_name, Some(relative_path.display().to_string()));
+            file_node.add_sections(text);
+        }
+
+        root.assign_levels(0);
+        root
+    }
+
+    fn child_mut(&mut self, name: &str, source: Option<String>) -> &mut HierarchyNode {
+        let index = match self.children.iter().position(|c| c.name == name) {
+            Some(index) => index,
+            None => {
+                self.children.push(HierarchyNode::new(name, source));
+                self.children.len() - 1
+            }
+        };
+        &mut self.children[index]
+    }
+
+    fn add_sections(&mut self, text: &str) {
+        let heading = Regex::new(r"^(#{1,6})\s+(.+?)\s*#*\s*$").unwrap();
+        // Path of child indices from this node to the section currently being filled,
+        // paired with the heading level that opened each section
+        let mut stack: Vec<(usize, usize)> = Vec::new();
+
+        for line in text.lines() {
+            if let Some(caps) = heading.captures(line) {
+                let depth = caps[1].len();
+                while stack.last().is_some_and(|(_, d)| *d >= depth) {
+                    stack.pop();
+                }
+                let parent = self.node_at_mut(&stack);
+                parent.children.push(HierarchyNode::new(caps[2].trim(), parent.source.clone()));
+                let index = parent.children.len() - 1;
+                stack.push((index, depth));
+            } else {
+                let node = self.node_at_mut(&stack);
+                node.text.push_str(line);
+                node.text.push('\n');
+            }
+        }
+    }
+
+    fn node_at_mut(&mut self, stack: &[(usize, usize)]) -> &mut HierarchyNode {
+        let mut node = self;
+        for (index, _) in stack {
+            node = &mut node.children[*index];
+        }
+        node
+    }
+
+    fn assign_levels(&mut self, depth: usize) {
+        self.level = match depth {
+            0 => HierarchyLevel::Root,
+            _ => Self::level_from_id(&self.name).unwrap_or(match depth {
+                1 => HierarchyLevel::Epic,
+                2 => HierarchyLevel::Feature,
+                _ => HierarchyLevel::Story,
+            }),
+        };
+        for child in &mut self.children {
+            child.assign_levels(depth + 1);
+        }
+    }
+
+    /// Recognizes ID prefixes such as `EPIC-1`, `FEAT-12`, `US-7` and dotted
+    /// section numbers (`1` epic, `1.2` feature, `1.2.3` story).
+    fn level_from_id(name: &str) -> Option<HierarchyLevel> {
+        let prefixed = Regex::new(r"(?i)^\W*(epic|ep|feature|feat|ft|story|us|st)[-_ ]?\d+").unwrap();
+        if let Some(caps) = prefixed.captures(name) {
+            return Some(match caps[1].to_lowercase().as_str() {
+                "epic" | "ep" => HierarchyLevel::Epic,
+                "feature" | "feat" | "ft" => HierarchyLevel::Feature,
+                _ => HierarchyLevel::Story,
+            });
+        }
+
+        let dotted = Regex::new(r"^\W*(\d+(?:\.\d+)*)\.?\s").unwrap();
+        dotted.captures(name).map(|caps| match caps[1].split('.').count() {
+            1 => HierarchyLevel::Epic,
+            2 => HierarchyLevel::Feature,
+            _ => HierarchyLevel::Story,
+        })
+    }
+
+    /// Index paths and text of every node that has requirement text of its own.
+    pub fn texts_to_analyze(&self) -> Vec<(Vec<usize>, String)> {
+        let mut texts = Vec::new();
+        self.collect_texts(&mut Vec::new(), &mut texts);
+        texts
+    }
+
+    fn collect_texts(&self, path: &mut Vec<usize>, texts: &mut Vec<(Vec<usize>, String)>) {
+        if !self.text.trim().is_empty() {
+            texts.push((path.clone(), self.text.clone()));
+        }
+        for (i, child) in self.children.iter().enumerate() {
+            path.push(i);
+            child.collect_texts(path, texts);
+            path.pop();
+        }
+    }
+
+    pub fn set_own_score(&mut self, path: &[usize], score: QualityScore) {
+        let mut node = self;
+        for index in path {
+            node = &mut node.children[*index];
+        }
+        node.own_score = score;
+    }
+
+    /// Rolls own scores up the tree so every node reflects its whole subtree.
+    pub fn aggregate(&mut self) {
+        let mut total = self.own_score.clone();
+        for child in &mut self.children {
+            child.aggregate();
+            total.add(&child.score);
+        }
+        total.update_score();
+        self.score = total;
+    }
+
+    pub fn render_tree(&self) -> String {
+        let mut output = format!("{}\n", self.label());
+        self.render_children("", &mut output);
+        output
+    }
+
+    fn render_children(&self, prefix: &str, output: &mut String) {
+        for (i, child) in self.children.iter().enumerate() {
+            let last = i == self.children.len() - 1;
+            output.push_str(&format!("{}{}{}\n", prefix, if last { "└── " } else { "├── " }, child.label()));
+            child.render_children(&format!("{}{}", prefix, if last { "    " } else { "│   " }), output);
+        }
+    }
+
+    fn label(&self) -> String {
+        let kind = match self.level {
+            HierarchyLevel::Root => "📁",
+            HierarchyLevel::Epic => "🏔️ Epic:",
+            HierarchyLevel::Feature => "🧩 Feature:",
+            HierarchyLevel::Story => "📝 Story:",
+        };
+        format!(
+            "{} {} — score {:.0} ({} requirements, {} findings)",
+            kind, self.name, self.score.score, self.score.requirement_count, self.score.finding_count
+        )
+    }
+
+    /// Stories (or the deepest nodes when there are none) that contain
+    /// requirements, lowest score first.
+    pub fn weakest_stories(&self, limit: usize) -> Vec<&HierarchyNode> {
+        let mut stories = Vec::new();
+        self.collect_stories(&mut stories);
+        stories.retain(|node| node.score.requirement_count > 0);
+        stories.sort_by(|a, b| a.score.score.total_cmp(&b.score.score));
+        stories.truncate(limit);
+        stories
+    }
+
+    fn collect_stories<'a>(&'a self, stories: &mut Vec<&'a HierarchyNode>) {
+        if self.children.is_empty() || self.level == HierarchyLevel::Story {
+            stories.push(self);
+            return;
+        }
+        for child in &self.children {
+            child.collect_stories(stories);
+        }
+    }
+}
+
+pub fn format_hierarchy_markdown(root: &HierarchyNode, dir_path: &Path) -> String {
+    let mut output = String::new();
+
+    output.push_str("# 🌳 PRISM Requirement Hierarchy Report\n\n");
+    output.push_str(&format!("**Source:** `{}`\n\n", dir_path.display()));
+    output.push_str(&format!(
+        "**Overall quality score:** {:.0}/100 across {} requirements ({} findings)\n\n",
+        root.score.score, root.score.requirement_count, root.score.finding_count
+    ));
+
+    output.push_str("## Hierarchy\n\n```\n");
+    output.push_str(&root.render_tree());
+    output.push_str("```\n\n");
+
+    let weakest = root.weakest_stories(5);
+    if !weakest.is_empty() {
+        output.push_str("## ⚠️ Lowest Scoring Stories\n\n");
+        output.push_str("| Story | Source | Score | Findings |\n|---|---|---:|---:|\n");
+        for story in weakest {
+            output.push_str(&format!(
+                "| {} | {} | {:.0} | {} |\n",
+                story.name,
+                story.source.as_deref().unwrap_or("-"),
+                story.score.score,
+                story.score.finding_count
+            ));
+        }
+        output.push('\n');
+    }
+
+    output.push_str("*Score: 100 minus 10 points per severity-weighted finding per requirement (Critical 4, High 3, Medium 2, Low 1).*\n");
+    output
+}
@@ -0,0 +1,135 @@
+use crate::analyzer::{AmbiguitySeverity, AnalysisResult};
+
+/// At most this many hints are printed after a run.
+pub const MAX_HINTS: usize = 3;
+
+/// Completeness scores below this get a hint about the gaps.
+const LOW_COMPLETENESS: f32 = 70.0;
+
+/// Findings from which a single-page HTML report is suggested.
+const MANY_FINDINGS: usize = 10;
+
+/// What the run already did, so hints don't suggest it again.
+#[derive(Debug, Clone, Default)]
+pub struct HintContext {
+    /// File the requirements were read from, used in the suggested commands
+    pub source: Option<String>,
+    pub ai_configured: bool,
+    pub saved_artifacts: bool,
+    pub html_report: bool,
+}
+
+impl HintContext {
+    fn input(&self) -> String {
+        match &self.source {
+            Some(file) if file.contains(' ') => format!("--file \"{}\"", file),
+            Some(file) => format!("--file {}", file),
+            None => "\"<requirement>\"".to_string(),
+        }
+    }
+}
+
+/// A next step suggested by what the result contains.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hint {
+    pub reason: String,
+    pub command: String,
+}
+
+impl Hint {
+    fn new(reason: impl Into<String>, command: impl Into<String>) -> Self {
+        Self { reason: reason.into(), command: command.into() }
+    }
+}
+
+/// Hints for an analysis result, most useful first. Everything is derived
+/// locally from the result and the analyzed text.
+pub fn suggest_hints(result: &AnalysisResult, text: &str, context: &HintContext) -> Vec<Hint> {
+    let lower = text.to_lowercase();
+    let input = context.input();
+    let mut hints = Vec::new();
+
+    let severe = result.ambiguities.iter().filter(|a| a.severity >= AmbiguitySeverity::High).count();
+    if severe > 0 && result.improved_requirements.is_none() {
+        hints.push(Hint::new(
+            format!("{} Critical/High finding{}", severe, if severe == 1 { "" } else { "s" }),
+            format!("prism improve {}", input),
+        ));
+    }
+
+    let has_acceptance_criteria = lower.contains("acceptance criteria")
+        || (lower.contains("given ") && lower.contains("then "));
+    if !has_acceptance_criteria && result.test_cases.is_none() {
+        hints.push(Hint::new("no acceptance criteria", format!("prism analyze {} --generate tests", input)));
+    }
+
+    match &result.completeness_analysis {
+        None => hints.push(Hint::new("completeness not checked", format!("prism validate {} --completeness", input))),
+        Some(completeness) if completeness.completeness_score < LOW_COMPLETENESS => hints.push(Hint::new(
+            format!("completeness is {:.0}% with {} gap(s)", completeness.completeness_score, completeness.gaps_identified.len()),
+            "prism new",
+        )),
+        Some(_) => {}
+    }
+
+    let mentions_quality = ["performance", "security", "availability", "response time", "encrypt", "scalab"]
+        .iter()
+        .any(|term| lower.contains(term));
+    if !mentions_quality && result.nfr_suggestions.is_none() {
+        hints.push(Hint::new("no non-functional requirements", format!("prism analyze {} --generate nfr", input)));
+    }
+
+    let actors = result.entities.actors.len();
+    if actors >= 2 && result.permission_matrix.is_none() {
+        hints.push(Hint::new(
+            format!("{} actors", actors),
+            format!("prism analyze {} --generate permissions", input),
+        ));
+    }
+
+    if (lower.contains("as a ") || lower.contains("as an ")) && result.user_story_validation.is_none() {
+        hints.push(Hint::new("looks like a user story", format!("prism validate {} --story", input)));
+    }
+
+    if !result.ambiguities.is_empty() && result.rewrite_suggestions.is_none() {
+        hints.push(Hint::new("findings without proposed rewrites", format!("prism analyze {} --generate suggestions", input)));
+    }
+
+    let generated = [
+        result.uml_diagrams.is_some(),
+        result.pseudocode.is_some(),
+        result.nfr_suggestions.is_some(),
+        result.test_cases.is_some(),
+    ]
+    .iter()
+    .filter(|g| **g)
+    .count();
+    if generated >= 2 && !context.saved_artifacts {
+        hints.push(Hint::new(
+            format!("{} generated artifacts in one report", generated),
+            format!("prism analyze {} --preset full --save-artifacts <name>", input),
+        ));
+    }
+
+    if result.ambiguities.len() >= MANY_FINDINGS && !context.html_report {
+        hints.push(Hint::new(
+            format!("{} findings to share", result.ambiguities.len()),
+            format!("prism analyze {} --format html --output report.html", input),
+        ));
+    }
+
+    if !context.ai_configured {
+        hints.push(Hint::new("built-in analysis only", "prism config --setup"));
+    }
+
+    hints
+}
+
+pub fn format_hints(hints: &[Hint]) -> String {
+    let mut output = String::from("\n💡 What you could also do:\n");
+    for hint in hints.iter().take(MAX_HINTS) {
+        output.push_str(&format!("   • {} — try `{}`\n", hint.reason, hint.command));
+    }
+    output.push_str("   (turn these off with `prism config --hints false`)\n");
+    output
+}
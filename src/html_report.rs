@@ -0,0 +1,408 @@
+use crate::analyzer::{Ambiguity, AmbiguitySeverity, AnalysisResult};
+use crate::test_data::Example;
+use crate::locale::report_locale;
+
+/// Mermaid is loaded from this URL when the report is opened; without network
+/// access the diagram sources are shown as text instead.
+pub const MERMAID_SCRIPT_URL: &str = "https://cdn.jsdelivr.net/npm/mermaid@10/dist/mermaid.esm.min.mjs";
+
+pub const SEVERITIES: [AmbiguitySeverity; 4] = [
+    AmbiguitySeverity::Critical,
+    AmbiguitySeverity::High,
+    AmbiguitySeverity::Medium,
+    AmbiguitySeverity::Low,
+];
+
+pub const REPORT_CSS: &str = "
+body { font-family: -apple-system, 'Segoe UI', Roboto, sans-serif; margin: 0 auto; max-width: 1100px; padding: 24px; color: #1f2933; background: #f7f9fb; }
+h1 { margin-bottom: 4px; }
+.subtitle { color: #616e7c; margin-top: 0; }
+details { background: #fff; border: 1px solid #d9e2ec; border-radius: 8px; margin: 16px 0; padding: 0 16px; }
+summary { cursor: pointer; font-size: 1.2em; font-weight: 600; padding: 12px 0; }
+.cards { display: flex; flex-wrap: wrap; gap: 12px; margin-bottom: 16px; }
+.card { flex: 1 1 140px; background: #fff; border: 1px solid #d9e2ec; border-radius: 8px; padding: 12px; }
+.card .value { font-size: 1.8em; font-weight: 700; }
+.card .label { color: #616e7c; font-size: 0.9em; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 16px; }
+th, td { border-bottom: 1px solid #e4e7eb; padding: 8px; text-align: left; vertical-align: top; }
+th { background: #f0f4f8; }
+.badge { border-radius: 4px; color: #fff; font-size: 0.85em; padding: 2px 8px; white-space: nowrap; }
+.sev-critical { background: #c62828; } .sev-high { background: #ef6c00; } .sev-medium { background: #f9a825; color: #1f2933; } .sev-low { background: #2e7d32; }
+.filters label { margin-right: 16px; }
+.bar { background: #e4e7eb; border-radius: 4px; height: 14px; overflow: hidden; }
+.bar > div { background: #3f7cac; height: 100%; }
+pre { background: #f0f4f8; border-radius: 6px; overflow-x: auto; padding: 12px; white-space: pre-wrap; }
+.toc { background: #fff; border: 1px solid #d9e2ec; border-radius: 8px; padding: 12px 16px; }
+.footer { color: #9aa5b1; font-size: 0.85em; margin-top: 32px; text-align: center; }
+";
+
+pub fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+pub fn severity_class(severity: &AmbiguitySeverity) -> &'static str {
+    match severity {
+        AmbiguitySeverity::Critical => "critical",
+        AmbiguitySeverity::High => "high",
+        AmbiguitySeverity::Medium => "medium",
+        AmbiguitySeverity::Low => "low",
+    }
+}
+
+/// Collapsible section, open by default.
+pub fn section(title: &str, body: &str) -> String {
+    format!("<details open>\n<summary>{}</summary>\n{}\n</details>\n", title, body)
+}
+
+/// Bulleted list of escaped items, or a "none" note.
+pub fn list(items: &[String]) -> String {
+    if items.is_empty() {
+        return "<p><em>None identified</em></p>".to_string();
+    }
+    let items: String = items.iter().map(|item| format!("<li>{}</li>", html_escape(item))).collect();
+    format!("<ul>{}</ul>", items)
+}
+
+/// Metric card with a big value and a label.
+pub fn card(value: &str, label: &str) -> String {
+    format!("<div class=\"card\"><div class=\"value\">{}</div><div class=\"label\">{}</div></div>", value, label)
+}
+
+/// Horizontal bar with `percent` (0-100) filled.
+pub fn percent_bar(percent: f32) -> String {
+    format!("<div class=\"bar\"><div style=\"width: {:.0}%\"></div></div>", percent.clamp(0.0, 100.0))
+}
+
+/// Findings table with the severity filter checkboxes above it.
+pub fn findings_table(ambiguities: &[Ambiguity]) -> String {
+    let mut findings = String::from("<p class=\"filters\">Show: ");
+    for severity in SEVERITIES {
+        findings.push_str(&format!(
+            "<label><input type=\"checkbox\" class=\"severity-filter\" value=\"{}\" checked> <span class=\"badge sev-{}\">{}</span></label>",
+            severity_class(&severity), severity_class(&severity), severity
+        ));
+    }
+    findings.push_str("</p>\n");
+    if ambiguities.is_empty() {
+        findings.push_str("<p>✅ No ambiguities detected.</p>");
+    } else {
+        findings.push_str("<table id=\"findings\"><tr><th>Severity</th><th>Line</th><th>Text</th><th>Problem</th><th>Suggestions</th></tr>\n");
+        for ambiguity in ambiguities {
+            let class = severity_class(&ambiguity.severity);
+            findings.push_str(&format!(
+                "<tr data-severity=\"{}\"><td><span class=\"badge sev-{}\">{}</span></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                class,
+                class,
+                ambiguity.severity,
+                ambiguity.location.as_ref().map(|l| format!("<span title=\"{}\">{}</span>", html_escape(&l.to_string()), l.line)).unwrap_or_default(),
+                html_escape(&ambiguity.text),
+                html_escape(&ambiguity.reason),
+                list(&ambiguity.suggestions)
+            ));
+        }
+        findings.push_str("</table>");
+    }
+    findings
+}
+
+/// One self-contained HTML page for an analysis: collapsible sections, a
+/// client-side severity filter for the findings and Mermaid `diagrams`
+/// (title, source) rendered in the browser.
+pub fn format_html_report(result: &AnalysisResult, input_text: &str, source_name: Option<&str>, diagrams: &[(&str, String)]) -> String {
+    let mut body = String::new();
+    let title = source_name.unwrap_or("Requirement analysis");
+
+    body.push_str(&format!("<h1>🔍 PRISM Requirement Analysis Report</h1>\n<p class=\"subtitle\">{}</p>\n", html_escape(title)));
+
+    let metrics = &result.metrics;
+    body.push_str("<div class=\"cards\">");
+    body.push_str(&card(&result.ambiguities.len().to_string(), "Findings"));
+    for severity in SEVERITIES {
+        let count = result.ambiguities.iter().filter(|a| a.severity == severity).count();
+        body.push_str(&card(&count.to_string(), &severity.to_string()));
+    }
+    body.push_str(&card(&report_locale().number(metrics.findings_per_100_words, 1), "Findings per 100 words"));
+    body.push_str(&card(&metrics.requirement_count.to_string(), "Requirements"));
+    if let Some(completeness) = &result.completeness_analysis {
+        body.push_str(&card(&report_locale().percent(completeness.completeness_score, 0), "Completeness"));
+    }
+    body.push_str("</div>\n");
+
+    body.push_str(&section(&format!("⚠️ Findings ({})", result.ambiguities.len()), &findings_table(&result.ambiguities)));
+
+    if let Some(suggestions) = &result.rewrite_suggestions {
+        let mut rows = String::from("<table><tr><th>Finding</th><th>Original</th><th>Proposed</th></tr>\n");
+        for suggestion in suggestions {
+            rows.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                html_escape(&suggestion.finding), html_escape(&suggestion.original), html_escape(&suggestion.proposed)
+            ));
+        }
+        rows.push_str("</table>");
+        body.push_str(&section("✏️ Suggested Rewrites", &rows));
+    }
+
+    let entities = &result.entities;
+    body.push_str(&section("🎯 Extracted Entities", &format!(
+        "<table><tr><th>👥 Actors</th><th>⚡ Actions</th><th>📦 Objects</th></tr><tr><td>{}</td><td>{}</td><td>{}</td></tr></table>",
+        list(&entities.actors), list(&entities.actions), list(&entities.objects)
+    )));
+
+    if let Some(completeness) = &result.completeness_analysis {
+        let mut gaps = format!("<p><strong>Completeness score: {}</strong></p>{}", report_locale().percent(completeness.completeness_score, 0), percent_bar(completeness.completeness_score));
+        if !completeness.gaps_identified.is_empty() {
+            gaps.push_str("<table><tr><th>Gap</th><th>Priority</th><th>Issue</th><th>Suggestions</th></tr>\n");
+            for gap in &completeness.gaps_identified {
+                gaps.push_str(&format!(
+                    "<tr><td>{}</td><td>{:?}</td><td>{}</td><td>{}</td></tr>\n",
+                    html_escape(&gap.category), gap.priority, html_escape(&gap.description), list(&gap.suggestions)
+                ));
+            }
+            gaps.push_str("</table>");
+        }
+        body.push_str(&section("📊 Completeness", &gaps));
+    }
+
+    if let Some(validation) = &result.user_story_validation {
+        let mut story = format!(
+            "<p>{}</p><table><tr><th>Component</th><th>Score</th><th>Issues</th></tr>",
+            if validation.is_valid_format { "✅ Valid user story format" } else { "❌ Not in user story format" }
+        );
+        for (name, quality) in [("Actor", &validation.actor_quality), ("Goal", &validation.goal_quality), ("Reason", &validation.reason_quality)] {
+            story.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td></tr>", name, report_locale().percent(quality.score, 0), list(&quality.issues)));
+        }
+        story.push_str("</table>");
+        story.push_str(&format!("<p><strong>Business value score:</strong> {}</p>{}", report_locale().percent(validation.business_value_score, 0), list(&validation.recommendations)));
+        body.push_str(&section("✅ User Story Validation", &story));
+    }
+
+    if let Some(nfrs) = &result.nfr_suggestions {
+        let mut rows = String::from("<table><tr><th>Category</th><th>Priority</th><th>Requirement</th><th>Acceptance criteria</th></tr>\n");
+        for nfr in nfrs {
+            rows.push_str(&format!(
+                "<tr><td>{:?}</td><td>{:?}</td><td>{}</td><td>{}</td></tr>\n",
+                nfr.category, nfr.priority, html_escape(&nfr.requirement), list(&nfr.acceptance_criteria)
+            ));
+        }
+        rows.push_str("</table>");
+        body.push_str(&section(&format!("🔒 Non-Functional Requirements ({})", nfrs.len()), &rows));
+    }
+
+    if let Some(matrix) = &result.permission_matrix {
+        let mut table = String::from("<table><tr><th>Action</th>");
+        for actor in &matrix.actors {
+            table.push_str(&format!("<th>{}</th>", html_escape(actor)));
+        }
+        table.push_str("</tr>\n");
+        for action in &matrix.actions {
+            table.push_str(&format!("<tr><td>{}</td>", html_escape(action)));
+            for actor in &matrix.actors {
+                table.push_str(&format!("<td>{}</td>", if matrix.is_granted(actor, action) { "✅" } else { "" }));
+            }
+            table.push_str("</tr>\n");
+        }
+        table.push_str("</table>");
+        if !matrix.unassigned_actions.is_empty() {
+            table.push_str(&format!("<p><strong>Actions without a responsible actor:</strong></p>{}", list(&matrix.unassigned_actions)));
+        }
+        body.push_str(&section("🔐 Actor Permission Matrix", &table));
+    }
+
+    if let Some(reviewers) = &result.reviewer_suggestions {
+        let mut rows = String::from("<table><tr><th>Reviewer</th><th>Categories</th><th>Why</th></tr>\n");
+        for reviewer in reviewers {
+            rows.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                html_escape(&reviewer.reviewer), html_escape(&reviewer.categories.join(", ")), list(&reviewer.reasons)
+            ));
+        }
+        rows.push_str("</table>");
+        body.push_str(&section(&format!("👀 Suggested Reviewers ({})", reviewers.len()), &rows));
+    }
+
+    if let Some(smart) = result.smart_analysis.as_ref().filter(|s| !s.requirements.is_empty()) {
+        let mut rows = String::from("<table><tr><th>Requirement</th><th>S</th><th>M</th><th>A</th><th>R</th><th>T</th><th>Overall</th><th>Suggested rewrite</th></tr>\n");
+        for requirement in &smart.requirements {
+            let s = &requirement.scores;
+            rows.push_str(&format!(
+                "<tr><td><strong>{}</strong> {}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.0}</td><td>{}</td></tr>\n",
+                html_escape(&requirement.id), html_escape(&requirement.text),
+                s.specific, s.measurable, s.achievable, s.relevant, s.time_bound, s.overall(),
+                html_escape(requirement.rewrite.as_deref().unwrap_or_default())
+            ));
+        }
+        rows.push_str("</table>");
+        body.push_str(&section(&format!("🎯 SMART Analysis (overall {:.0})", smart.overall()), &rows));
+    }
+
+    if let Some(model) = result.domain_model.as_ref().filter(|m| !m.aggregates.is_empty()) {
+        let mut rows = String::from("<table><tr><th>Aggregate root</th><th>Entities</th><th>Value objects</th><th>Commands → events</th></tr>\n");
+        for aggregate in &model.aggregates {
+            let commands: Vec<String> = aggregate.commands.iter().map(|c| format!("{} → {}", c.command, c.event)).collect();
+            rows.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                html_escape(&aggregate.root), html_escape(&aggregate.entities.join(", ")),
+                html_escape(&aggregate.value_objects.join(", ")), list(&commands)
+            ));
+        }
+        rows.push_str("</table>");
+        body.push_str(&section(&format!("🏛️ Domain Model ({} aggregates)", model.aggregates.len()), &rows));
+    }
+
+    if let Some(scenarios) = result.load_tests.as_ref().filter(|s| !s.is_empty()) {
+        let mut rows = String::from("<table><tr><th>Scenario</th><th>Request</th><th>Thresholds</th><th>Requirement</th></tr>\n");
+        for scenario in scenarios {
+            let request = if scenario.path.is_some() { format!("<code>{}</code>", html_escape(&scenario.request_name())) } else { "<em>to be filled in</em>".to_string() };
+            let thresholds: Vec<String> = scenario.thresholds.iter().map(|t| t.to_string()).collect();
+            rows.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                scenario.name, request, list(&thresholds), html_escape(&scenario.requirement)
+            ));
+        }
+        rows.push_str("</table>");
+        body.push_str(&section(&format!("🏋️ Load Tests ({} scenarios)", scenarios.len()), &rows));
+    }
+
+    if let Some(tests) = result.security_tests.as_ref().filter(|t| !t.is_empty()) {
+        let mut rows = String::from("<table><tr><th>ID</th><th>Test</th><th>Steps</th><th>Expected</th><th>From</th></tr>\n");
+        for test in tests {
+            let mut steps = test.steps.clone();
+            if !test.probes.is_empty() {
+                steps.push(format!("Probes: {}", test.probes.join(", ")));
+            }
+            rows.push_str(&format!(
+                "<tr><td>{}</td><td>{}<br><small>{} - OWASP {}</small></td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                test.id, html_escape(&test.title), test.kind.label(), test.kind.owasp(),
+                list(&steps), html_escape(&test.expected), list(&test.sources)
+            ));
+        }
+        rows.push_str("</table>");
+        body.push_str(&section(&format!("🛡️ Security Tests ({})", tests.len()), &rows));
+    }
+
+    if let Some(ears) = &result.ears_validation {
+        let mut rows = format!("<p><strong>Conforming:</strong> {} of {} requirement sentences</p>", report_locale().percent(ears.conformance(), 0), ears.sentences.len());
+        rows.push_str("<table><tr><th>Line</th><th>Sentence</th><th>Pattern</th><th>Suggested rewrite</th></tr>\n");
+        for sentence in &ears.sentences {
+            rows.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                sentence.line, html_escape(&sentence.text),
+                sentence.pattern.map(|p| p.label()).unwrap_or("❌ None"),
+                html_escape(sentence.suggestion.as_deref().unwrap_or_default())
+            ));
+        }
+        rows.push_str("</table>");
+        body.push_str(&section("📐 EARS Conformance", &rows));
+    }
+
+    if let Some(tests) = &result.test_cases {
+        body.push_str(&section("🧪 Test Cases", &format!(
+            "<h3>Happy path</h3>{}<h3>Negative cases</h3>{}<h3>Edge cases</h3>{}",
+            list(&tests.happy_path), list(&tests.negative_cases), list(&tests.edge_cases)
+        )));
+    }
+
+    if let Some(test_data) = &result.test_data {
+        let mut rows = String::new();
+        for set in test_data {
+            rows.push_str(&format!("<h3>{}</h3>\n<table><tr><th>Field</th><th>Valid</th><th>Boundary</th><th>Invalid</th></tr>\n", html_escape(&set.action)));
+            for field in &set.fields {
+                let examples = |examples: &[Example]| -> Vec<String> {
+                    examples.iter().map(|e| format!("{:?} {}", e.value, e.note)).collect()
+                };
+                rows.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    html_escape(&field.name), list(&examples(&field.valid)), list(&examples(&field.boundary)), list(&examples(&field.invalid))
+                ));
+            }
+            rows.push_str("</table>\n");
+        }
+        body.push_str(&section("🧾 Test Data", &rows));
+    }
+
+    if let Some(contracts) = &result.contract_tests {
+        let mut rows = String::from("<table><tr><th>Endpoint</th><th>Requirement</th><th>Interactions</th></tr>\n");
+        for contract in contracts {
+            let interactions: Vec<String> = contract.cases.iter().map(|c| format!("{} → {}", c.description, c.status)).collect();
+            rows.push_str(&format!(
+                "<tr><td><code>{} {}</code></td><td>{}</td><td>{}</td></tr>\n",
+                contract.method, html_escape(&contract.path), html_escape(&contract.requirement), list(&interactions)
+            ));
+        }
+        rows.push_str("</table>");
+        body.push_str(&section(&format!("🤝 API Contract Tests ({} endpoints)", contracts.len()), &rows));
+    }
+
+    if let Some(coverage) = &result.test_coverage {
+        let mut rows = String::from("<table><tr><th>Line</th><th>Requirement</th><th>Tests</th><th>Findings</th></tr>\n");
+        for row in &coverage.requirements {
+            let tests = if row.tests.is_empty() { "<span class=\"badge sev-high\">no tests</span>".to_string() } else { list(&row.tests) };
+            let findings = if row.findings.is_empty() { "-".to_string() } else { list(&row.findings) };
+            rows.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n", row.line, html_escape(&row.requirement), tests, findings));
+        }
+        rows.push_str("</table>");
+        let title = format!("🧭 Test Coverage ({} of requirements, {} without tests)", report_locale().percent(coverage.coverage_percentage(), 0), coverage.untested().len());
+        body.push_str(&section(&title, &rows));
+    }
+
+    if !diagrams.is_empty() {
+        let mut rendered = String::new();
+        for (title, source) in diagrams {
+            rendered.push_str(&format!("<h3>{}</h3>\n<pre class=\"mermaid\">\n{}</pre>\n", title, html_escape(source)));
+        }
+        body.push_str(&section("🎨 Diagrams", &rendered));
+    }
+
+    if let Some(improved) = &result.improved_requirements {
+        body.push_str(&section("✨ Improved Requirements", &format!("<pre>{}</pre>", html_escape(improved))));
+    }
+
+    body.push_str(&section("📝 Analyzed Requirement", &format!("<pre>{}</pre>", html_escape(input_text.trim()))));
+
+    html_page(&format!("PRISM Report - {}", title), "", &body, !diagrams.is_empty())
+}
+
+/// Wraps `body` in a page with the report styles plus `extra_css`, the
+/// severity filter script and, when `mermaid` is set, the Mermaid renderer.
+pub fn html_page(title: &str, extra_css: &str, body: &str, mermaid: bool) -> String {
+    let mermaid_script = if mermaid {
+        format!("<script type=\"module\">import mermaid from '{}'; mermaid.initialize({{ startOnLoad: true }});</script>\n", MERMAID_SCRIPT_URL)
+    } else {
+        String::new()
+    };
+
+    format!(
+        "<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+<meta charset=\"utf-8\">
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">
+<title>{}</title>
+<style>{}{}</style>
+</head>
+<body>
+{}
+<p class=\"footer\">Generated by PRISM - AI-Powered Requirement Analyzer</p>
+<script>
+document.querySelectorAll('.severity-filter').forEach(function (box) {{
+  box.addEventListener('change', function () {{
+    document.querySelectorAll('tr[data-severity=\"' + box.value + '\"]').forEach(function (row) {{
+      row.style.display = box.checked ? '' : 'none';
+    }});
+  }});
+}});
+</script>
+{}</body>
+</html>
+",
+        html_escape(title),
+        REPORT_CSS,
+        extra_css,
+        body,
+        mermaid_script
+    )
+}
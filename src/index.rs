@@ -0,0 +1,296 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::coverage::content_terms;
+use crate::locale::report_locale;
+
+/// Index file written into the indexed directory.
+pub const INDEX_FILE_NAME: &str = ".prism-index.json";
+
+/// Identifies the embedding scheme; indexes built with another one are rebuilt.
+pub const EMBEDDING_MODEL: &str = "prism-hashed-terms-v1";
+
+/// Size of the hashed feature space; vectors are stored sparse.
+pub const EMBEDDING_DIMENSIONS: usize = 1 << 20;
+
+/// Pairs at or above this similarity are reported as duplicates.
+pub const DUPLICATE_SIMILARITY: f32 = 0.9;
+
+/// Pairs at or above this similarity are checked for contradicting statements.
+pub const CONFLICT_SIMILARITY: f32 = 0.6;
+
+/// Sentences with fewer content terms than this (headings, fragments) are not indexed.
+const MIN_CHUNK_TERMS: usize = 3;
+
+/// Non-empty sentences of `text` with the 1-based line they start on.
+pub fn source_sentences(text: &str) -> Vec<(usize, String)> {
+    let mut sentences = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        for sentence in line.split_inclusive(['.', '!', '?', ';']) {
+            let sentence = sentence.trim().trim_start_matches(['#', '-', '*', '+', ' ']);
+            if !sentence.is_empty() {
+                sentences.push((i + 1, sentence.to_string()));
+            }
+        }
+    }
+    sentences
+}
+
+/// Local embedding of a text: stemmed content terms and term pairs hashed into
+/// a sparse vector of (dimension, weight) entries sorted by dimension,
+/// L2-normalized so the dot product is cosine similarity.
+pub fn embed(text: &str) -> Vec<(u32, f32)> {
+    let mut weights: BTreeMap<u32, f32> = BTreeMap::new();
+    let terms = content_terms(text);
+
+    let mut add = |feature: &str, weight: f32| {
+        let hash = fnv1a(feature);
+        *weights.entry((hash % EMBEDDING_DIMENSIONS as u64) as u32).or_insert(0.0) += weight;
+    };
+    for term in &terms {
+        add(term, 1.0);
+    }
+    for pair in terms.windows(2) {
+        add(&format!("{} {}", pair[0], pair[1]), 0.5);
+    }
+
+    let norm = weights.values().map(|x| x * x).sum::<f32>().sqrt();
+    weights.into_iter().map(|(dimension, weight)| (dimension, weight / norm)).collect()
+}
+
+/// FNV-1a, used because its output is stable across builds and platforms.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+/// Cosine similarity of two embeddings.
+pub fn cosine(a: &[(u32, f32)], b: &[(u32, f32)]) -> f32 {
+    let (mut i, mut j, mut dot) = (0, 0, 0.0);
+    while i < a.len() && j < b.len() {
+        match a[i].0.cmp(&b[j].0) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                dot += a[i].1 * b[j].1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    dot
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedChunk {
+    pub line: usize,
+    pub text: String,
+    pub vector: Vec<(u32, f32)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedFile {
+    pub content_hash: String,
+    pub chunks: Vec<IndexedChunk>,
+}
+
+/// Files added, re-embedded, kept and dropped by an index update.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexUpdate {
+    pub added: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+}
+
+/// A chunk returned by a search, with the file it came from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexHit {
+    pub source: String,
+    pub line: usize,
+    pub text: String,
+    pub score: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequirementIndex {
+    pub model: String,
+    /// Indexed files keyed by path relative to the indexed directory
+    pub files: BTreeMap<String, IndexedFile>,
+}
+
+impl Default for RequirementIndex {
+    fn default() -> Self {
+        Self { model: EMBEDDING_MODEL.to_string(), files: BTreeMap::new() }
+    }
+}
+
+impl RequirementIndex {
+    pub fn index_path(dir: &Path) -> PathBuf {
+        dir.join(INDEX_FILE_NAME)
+    }
+
+    /// Loads the index of `dir`, or an empty one when there is none yet or it
+    /// was built with a different embedding model.
+    pub fn load(dir: &Path) -> Self {
+        std::fs::read_to_string(Self::index_path(dir))
+            .ok()
+            .and_then(|content| serde_json::from_str::<RequirementIndex>(&content).ok())
+            .filter(|index| index.model == EMBEDDING_MODEL)
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, dir: &Path) -> anyhow::Result<()> {
+        std::fs::write(Self::index_path(dir), serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Brings the index in line with `files` (relative path, content): only
+    /// new and changed files are embedded, and files no longer present are dropped.
+    pub fn update(&mut self, files: &[(String, String)]) -> IndexUpdate {
+        let mut update = IndexUpdate::default();
+        let present: HashSet<&str> = files.iter().map(|(path, _)| path.as_str()).collect();
+
+        let before = self.files.len();
+        self.files.retain(|path, _| present.contains(path.as_str()));
+        update.removed = before - self.files.len();
+
+        for (path, content) in files {
+            let content_hash = format!("{:x}", Sha256::digest(content.as_bytes()));
+            match self.files.get(path) {
+                Some(existing) if existing.content_hash == content_hash => {
+                    update.unchanged += 1;
+                    continue;
+                }
+                Some(_) => update.updated += 1,
+                None => update.added += 1,
+            }
+
+            let chunks = source_sentences(content)
+                .into_iter()
+                .filter(|(_, text)| content_terms(text).len() >= MIN_CHUNK_TERMS)
+                .map(|(line, text)| IndexedChunk { line, vector: embed(&text), text })
+                .collect();
+            self.files.insert(path.clone(), IndexedFile { content_hash, chunks });
+        }
+
+        update
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.files.values().map(|f| f.chunks.len()).sum()
+    }
+
+    fn hits(&self) -> impl Iterator<Item = (&String, &IndexedChunk)> {
+        self.files.iter().flat_map(|(path, file)| file.chunks.iter().map(move |chunk| (path, chunk)))
+    }
+
+    /// Chunks most similar to `query`, best first, skipping `exclude_source`.
+    pub fn search(&self, query: &str, limit: usize, exclude_source: Option<&str>) -> Vec<IndexHit> {
+        let query = embed(query);
+        let mut hits: Vec<IndexHit> = self.hits()
+            .filter(|(path, _)| Some(path.as_str()) != exclude_source)
+            .map(|(path, chunk)| IndexHit {
+                source: path.clone(),
+                line: chunk.line,
+                text: chunk.text.clone(),
+                score: cosine(&query, &chunk.vector),
+            })
+            .filter(|hit| hit.score > 0.0)
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(limit);
+        hits
+    }
+
+    /// Near-duplicate and contradicting requirement pairs across the corpus.
+    pub fn find_duplicates_and_conflicts(&self) -> CorpusReport {
+        let chunks: Vec<(&String, &IndexedChunk)> = self.hits().collect();
+        let mut report = CorpusReport::default();
+
+        for (i, (path_a, a)) in chunks.iter().enumerate() {
+            for (path_b, b) in &chunks[i + 1..] {
+                let score = cosine(&a.vector, &b.vector);
+                if score < CONFLICT_SIMILARITY {
+                    continue;
+                }
+
+                let hit = |path: &String, chunk: &IndexedChunk| IndexHit {
+                    source: path.to_string(),
+                    line: chunk.line,
+                    text: chunk.text.clone(),
+                    score,
+                };
+                let pair = RequirementPair { first: hit(path_a, a), second: hit(path_b, b), score, reason: String::new() };
+
+                if let Some(reason) = conflict_reason(&a.text, &b.text) {
+                    report.conflicts.push(RequirementPair { reason, ..pair });
+                } else if score >= DUPLICATE_SIMILARITY {
+                    report.duplicates.push(RequirementPair { reason: "Near-identical wording".to_string(), ..pair });
+                }
+            }
+        }
+
+        report
+    }
+}
+
+/// Why two similar statements contradict each other, if they do: one is
+/// negated and the other isn't, or they state different numbers.
+fn conflict_reason(a: &str, b: &str) -> Option<String> {
+    let negation = Regex::new(r"(?i)\b(not|never|no|cannot|can't|mustn't|won't|shouldn't|shall not|must not)\b").unwrap();
+    if negation.is_match(a) != negation.is_match(b) {
+        return Some("One statement negates the other".to_string());
+    }
+
+    let number = Regex::new(r"\d+(?:\.\d+)?").unwrap();
+    let numbers = |text: &str| number.find_iter(text).map(|m| m.as_str().to_string()).collect::<Vec<_>>();
+    let (numbers_a, numbers_b) = (numbers(a), numbers(b));
+    if !numbers_a.is_empty() && !numbers_b.is_empty() && numbers_a != numbers_b {
+        return Some(format!("Different values ({} vs {})", numbers_a.join(", "), numbers_b.join(", ")));
+    }
+
+    None
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequirementPair {
+    pub first: IndexHit,
+    pub second: IndexHit,
+    pub score: f32,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CorpusReport {
+    pub duplicates: Vec<RequirementPair>,
+    pub conflicts: Vec<RequirementPair>,
+}
+
+pub fn format_corpus_report_markdown(report: &CorpusReport, dir_path: &Path) -> String {
+    let mut output = String::new();
+
+    output.push_str("# 🗃️ PRISM Corpus Report\n\n");
+    output.push_str(&format!("**Source:** `{}`\n\n", dir_path.display()));
+
+    let sections = [
+        ("⚔️ Conflicting Requirements", &report.conflicts, "✅ No conflicting requirements found."),
+        ("👯 Duplicate Requirements", &report.duplicates, "✅ No duplicate requirements found."),
+    ];
+    for (title, pairs, empty) in sections {
+        output.push_str(&format!("## {}\n\n", title));
+        if pairs.is_empty() {
+            output.push_str(&format!("{}\n\n", empty));
+            continue;
+        }
+        for pair in pairs {
+            output.push_str(&format!("### {} (similarity {})\n\n", pair.reason, report_locale().number(pair.score, 2)));
+            output.push_str(&format!("- `{}:{}` {}\n", pair.first.source, pair.first.line, pair.first.text));
+            output.push_str(&format!("- `{}:{}` {}\n\n", pair.second.source, pair.second.line, pair.second.text));
+        }
+    }
+
+    output
+}
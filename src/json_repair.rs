@@ -0,0 +1,251 @@
+//! Repairs the JSON of LLM responses: even with good prompts, models wrap it
+//! in prose or code fences, leave trailing commas, quote with single quotes
+//! or stop mid-array when they run out of tokens. Every AI task parses its
+//! response through `parse_llm_json`, which tries the response as it is,
+//! then repaired, then keeps the valid items of its array.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+/// What it took to read a response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonRepair {
+    /// Valid JSON, apart from prose or code fences around it
+    Clean,
+    /// Valid after fixing commas, quotes, keys or a truncated end
+    Repaired,
+    /// Some items of the array were dropped because they stayed invalid
+    Salvaged,
+}
+
+impl JsonRepair {
+    pub fn label(&self) -> &'static str {
+        match self {
+            JsonRepair::Clean => "clean",
+            JsonRepair::Repaired => "repaired",
+            JsonRepair::Salvaged => "salvaged",
+        }
+    }
+}
+
+/// `response` read as `T`, where `items` names the array of `T` whose invalid
+/// entries may be dropped. The error is the one of the response as it was.
+pub fn parse_llm_json<T: DeserializeOwned>(response: &str, items: &str) -> Result<(T, JsonRepair), serde_json::Error> {
+    let json = extract_json(response);
+    let error = match serde_json::from_str(json) {
+        Ok(parsed) => return Ok((parsed, JsonRepair::Clean)),
+        Err(e) => e,
+    };
+    let Some(mut value) = repair_json(json) else { return Err(error) };
+    // A bare array of the items the object should have held
+    if value.is_array() && !items.is_empty() {
+        value = serde_json::json!({ items: value });
+    }
+    if let Ok(parsed) = serde_json::from_value(value.clone()) {
+        return Ok((parsed, JsonRepair::Repaired));
+    }
+
+    let Some(entries) = value.get(items).and_then(Value::as_array).cloned() else { return Err(error) };
+    let valid: Vec<Value> = entries.iter()
+        .filter(|entry| {
+            let mut single = value.clone();
+            single[items] = Value::Array(vec![(*entry).clone()]);
+            serde_json::from_value::<T>(single).is_ok()
+        })
+        .cloned()
+        .collect();
+    if valid.is_empty() && !entries.is_empty() {
+        return Err(error);
+    }
+    value[items] = Value::Array(valid);
+    serde_json::from_value(value).map(|parsed| (parsed, JsonRepair::Salvaged)).map_err(|_| error)
+}
+
+/// The JSON object or array in `response`: the content of its code fence,
+/// without the prose around it, up to the bracket closing the first one.
+pub fn extract_json(response: &str) -> &str {
+    let mut text = response;
+    if let Some((_, fenced)) = response.split_once("```") {
+        // The language tag, e.g. "json", runs to the end of the fence line
+        let fenced = match fenced.split_once('\n') {
+            Some((tag, rest)) if !tag.contains(['{', '[']) => rest,
+            _ => fenced,
+        };
+        text = fenced.split("```").next().unwrap_or(fenced);
+    }
+    let Some(start) = text.find(['{', '[']) else { return text.trim() };
+    let text = &text[start..];
+    let mut depth = 0;
+    let mut scanner = Scanner::default();
+    for (i, c) in text.char_indices() {
+        if scanner.in_string(c) {
+            continue;
+        }
+        match c {
+            '{' | '[' => depth += 1,
+            '}' | ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return &text[..=i];
+                }
+            }
+            _ => {}
+        }
+    }
+    // Truncated: everything there is
+    text.trim_end()
+}
+
+/// Follows double-quoted strings, to tell brackets in text from the
+/// structure.
+#[derive(Default)]
+struct Scanner {
+    in_string: bool,
+    escaped: bool,
+}
+
+impl Scanner {
+    /// Whether `c` is part of a string, including its quotes.
+    fn in_string(&mut self, c: char) -> bool {
+        if self.escaped {
+            self.escaped = false;
+            return true;
+        }
+        match (self.in_string, c) {
+            (true, '\\') => self.escaped = true,
+            (true, '"') => self.in_string = false,
+            (false, '"') => self.in_string = true,
+            (false, _) => return false,
+            _ => {}
+        }
+        true
+    }
+}
+
+/// `json` with the usual mistakes of models fixed, as a value: trailing and
+/// doubled commas, single quotes, unquoted keys, unescaped quotes and line
+/// breaks in strings, Python's True/False/None, missing commas between
+/// objects and a truncated end, which is cut back to the last complete item.
+pub fn repair_json(json: &str) -> Option<Value> {
+    let repaired = fix_up(json);
+    std::iter::once((repaired.text.len(), repaired.open.clone()))
+        .chain(repaired.cuts.iter().rev().take(50).cloned())
+        .find_map(|(end, open)| {
+            let mut candidate = repaired.text[..end].trim_end().trim_end_matches([',', ':']).to_string();
+            candidate.extend(open.chars().rev().map(|bracket| if bracket == '{' { '}' } else { ']' }));
+            serde_json::from_str(&candidate).ok()
+        })
+}
+
+struct FixedUp {
+    text: String,
+    /// Brackets still open at the end, innermost last
+    open: String,
+    /// Ends of the text before each comma, with the brackets open there
+    cuts: Vec<(usize, String)>,
+}
+
+fn fix_up(json: &str) -> FixedUp {
+    let chars: Vec<char> = json.chars().collect();
+    let mut text = String::with_capacity(json.len());
+    let mut open = String::new();
+    let mut cuts = Vec::new();
+    // The quote of the string we are in
+    let mut quote: Option<char> = None;
+    let mut i = 0;
+
+    let next_significant = |from: usize| chars[from..].iter().find(|c| !c.is_whitespace()).copied();
+    let last_significant = |text: &str| text.trim_end().chars().last();
+
+    while i < chars.len() {
+        let c = chars[i];
+        if let Some(q) = quote {
+            match c {
+                '\\' if q == '\'' && chars.get(i + 1) == Some(&'\'') => {
+                    text.push('\'');
+                    i += 1;
+                }
+                '\\' => {
+                    text.push('\\');
+                    if let Some(&escaped) = chars.get(i + 1) {
+                        text.push(escaped);
+                        i += 1;
+                    }
+                }
+                // A quote ends the string only where a string can end
+                _ if c == q && matches!(next_significant(i + 1), None | Some(',' | ':' | '}' | ']')) => {
+                    text.push('"');
+                    quote = None;
+                }
+                '"' => text.push_str("\\\""),
+                '\n' => text.push_str("\\n"),
+                '\r' => {}
+                '\t' => text.push_str("\\t"),
+                _ => text.push(c),
+            }
+            i += 1;
+            continue;
+        }
+
+        match c {
+            '"' | '\'' => {
+                if matches!(last_significant(&text), Some('}' | ']')) {
+                    text.push(',');
+                }
+                text.push('"');
+                quote = Some(c);
+            }
+            '{' | '[' => {
+                if c == '{' && matches!(last_significant(&text), Some('}')) {
+                    cuts.push((text.len(), open.clone()));
+                    text.push(',');
+                }
+                open.push(c);
+                text.push(c);
+            }
+            '}' | ']' => {
+                let expected = if c == '}' { '{' } else { '[' };
+                if open.ends_with(expected) {
+                    open.pop();
+                    text.push(c);
+                    if open.is_empty() {
+                        break;
+                    }
+                }
+            }
+            ',' => {
+                let doubled = matches!(last_significant(&text), Some(',' | '[' | '{'));
+                let trailing = matches!(next_significant(i + 1), None | Some('}' | ']'));
+                if !doubled && !trailing {
+                    cuts.push((text.len(), open.clone()));
+                    text.push(',');
+                }
+            }
+            _ if c.is_alphabetic() || c == '_' => {
+                let end = (i..chars.len()).find(|&j| !(chars[j].is_alphanumeric() || chars[j] == '_')).unwrap_or(chars.len());
+                let word: String = chars[i..end].iter().collect();
+                if next_significant(end) == Some(':') {
+                    text.push_str(&format!("\"{}\"", word));
+                } else {
+                    text.push_str(match word.as_str() {
+                        "True" => "true",
+                        "False" => "false",
+                        "None" => "null",
+                        _ => &word,
+                    });
+                }
+                i = end;
+                continue;
+            }
+            _ => text.push(c),
+        }
+        i += 1;
+    }
+
+    if quote.is_some() {
+        text.push('"');
+    }
+    FixedUp { text, open, cuts }
+}
@@ -1,6 +1,103 @@
+//! PRISM analyzes requirement documents for ambiguities, missing pieces
+//! and quality issues. The `prism` binary is a thin wrapper around this
+//! library, so everything the CLI does can also be done from Rust:
+//!
+//! ```no_run
+//! use prism::{format_report, Analyzer, DocumentProcessor, OutputFormat};
+//!
+//! # async fn run() -> anyhow::Result<()> {
+//! let text = DocumentProcessor::new().extract_text_from_file("requirements.pdf").await?;
+//! let result = Analyzer::new()?.analyze(&text).await?;
+//! println!("{}", format_report(&result, &text, &OutputFormat::Markdown, Some("requirements.pdf"))?);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Built-in analysis needs no configuration. Pass [`Config::load`] to
+//! [`Analyzer::with_config`] to use the AI provider set up with `prism config`.
+
 pub mod analyzer;
 pub mod cli;
 pub mod config;
 pub mod app;
 pub mod ui;
-pub mod document_processor;
\ No newline at end of file
+pub mod document_processor;
+pub mod adr;
+pub mod annotated;
+pub mod api_keys;
+pub mod architecture;
+pub mod artifacts;
+pub mod authoring;
+pub mod badge;
+pub mod baseline;
+pub mod batch;
+pub mod budget;
+pub mod cache;
+pub mod changelog;
+pub mod chat;
+pub mod console;
+pub mod cost_of_delay;
+pub mod custom_rules;
+pub mod coverage;
+pub mod daemon;
+pub mod dashboard;
+pub mod demo;
+pub mod dependencies;
+pub mod diagnostics;
+pub mod domain_model;
+pub mod duplicates;
+pub mod ears;
+pub mod events;
+pub mod fixtures;
+pub mod git_integration;
+pub mod hierarchy;
+pub mod hints;
+pub mod html_report;
+pub mod index;
+pub mod json_repair;
+pub mod llm_stream;
+pub mod locale;
+pub mod merge;
+pub mod metrics;
+pub mod mock_provider;
+pub mod ollama;
+pub mod owners;
+pub mod paths;
+pub mod pdf_report;
+pub mod plantuml;
+pub mod projects;
+pub mod prompts;
+pub mod provider_comparison;
+pub mod quality_gate;
+pub mod redline;
+pub mod release;
+pub mod report_diff;
+pub mod retry;
+pub mod reviewers;
+pub mod selftest;
+pub mod server;
+pub mod smart;
+pub mod spreadsheet;
+pub mod event_storming;
+pub mod contract_tests;
+pub mod test_data;
+pub mod test_coverage;
+pub mod load_tests;
+pub mod security_tests;
+pub mod evidence;
+pub mod secrets;
+pub mod signing;
+pub mod telemetry;
+pub mod text;
+pub mod toc;
+pub mod traceability;
+pub mod webhook;
+pub mod formatters;
+pub mod notion;
+pub mod rm_tools;
+
+pub use analyzer::{AnalysisResult, Analyzer};
+pub use cli::OutputFormat;
+pub use config::Config;
+pub use document_processor::{DocumentError, DocumentProcessor};
+pub use formatters::format_report;
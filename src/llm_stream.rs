@@ -0,0 +1,145 @@
+//! Streamed LLM responses: decoding the providers' event streams and showing
+//! the text as it arrives.
+
+use anyhow::Result;
+use serde_json::Value;
+use std::io::{IsTerminal, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crate::console::supports_ansi;
+
+/// What a streamed LLM call reports while it runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamEvent<'a> {
+    Started,
+    /// Text added to the response
+    Delta(&'a str),
+    Finished,
+}
+
+/// Receives the events of every streamed call made by an analyzer.
+pub type StreamHandler = Arc<dyn Fn(StreamEvent<'_>) + Send + Sync>;
+
+/// Wire format of a provider's streamed response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamFormat {
+    /// Server-sent events with `choices[0].delta.content` (OpenAI, Azure OpenAI)
+    OpenAi,
+    /// Server-sent `content_block_delta` events (Claude)
+    Claude,
+    /// One JSON object per line with `message.content`, or `response` from
+    /// the generate endpoint (Ollama)
+    Ollama,
+}
+
+/// Splits streamed bytes into lines, keeping multi-byte characters that
+/// straddle two chunks intact.
+#[derive(Debug, Default)]
+pub struct LineBuffer {
+    pending: Vec<u8>,
+}
+
+impl LineBuffer {
+    /// Complete lines in `chunk` and what was left over from earlier chunks.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(chunk);
+        let mut lines = Vec::new();
+        while let Some(end) = self.pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            lines.push(String::from_utf8_lossy(&line).trim_end_matches(['\r', '\n']).to_string());
+        }
+        lines
+    }
+
+    /// The last line, if the stream did not end with a newline.
+    pub fn finish(&mut self) -> Option<String> {
+        let line = String::from_utf8_lossy(&std::mem::take(&mut self.pending)).trim().to_string();
+        (!line.is_empty()).then_some(line)
+    }
+}
+
+/// Text added by one line of a streamed response. Errors reported inside the
+/// stream are returned as errors.
+pub fn stream_delta(format: StreamFormat, line: &str) -> Result<Option<String>> {
+    let payload = match format {
+        StreamFormat::OpenAi | StreamFormat::Claude => match line.strip_prefix("data:") {
+            Some(data) => data.trim(),
+            // `event:` lines, comments and keep-alives
+            None => return Ok(None),
+        },
+        StreamFormat::Ollama => line.trim(),
+    };
+    if payload.is_empty() || payload == "[DONE]" {
+        return Ok(None);
+    }
+
+    let event: Value = serde_json::from_str(payload)?;
+    if let Some(error) = event.get("error") {
+        let message = error["message"].as_str().or(error.as_str()).unwrap_or("unknown error");
+        return Err(anyhow::anyhow!("LLM stream failed: {}", message));
+    }
+
+    let delta = match format {
+        StreamFormat::OpenAi => event.pointer("/choices/0/delta/content"),
+        StreamFormat::Claude if event["type"] == "content_block_delta" => event.pointer("/delta/text"),
+        StreamFormat::Claude => None,
+        StreamFormat::Ollama => event.pointer("/message/content").or(event.get("response")),
+    };
+    Ok(delta.and_then(Value::as_str).filter(|s| !s.is_empty()).map(str::to_string))
+}
+
+/// Characters of the latest response line shown next to the progress.
+const PREVIEW_LENGTH: usize = 50;
+
+#[derive(Default)]
+struct Progress {
+    active: usize,
+    chars: usize,
+    started: Option<Instant>,
+    last_line: String,
+}
+
+/// A handler that keeps one status line on stderr up to date while AI
+/// responses stream in, e.g. `🤖 AI responding... 1840 chars, 6s │ "reason": "The term`.
+/// Returns `None` when stderr is not a terminal that can redraw the line.
+pub fn terminal_progress() -> Option<StreamHandler> {
+    if !std::io::stderr().is_terminal() || !supports_ansi() {
+        return None;
+    }
+
+    let progress = Mutex::new(Progress::default());
+    Some(Arc::new(move |event| {
+        let mut progress = progress.lock().unwrap();
+        let mut stderr = std::io::stderr();
+        match event {
+            StreamEvent::Started => {
+                if progress.active == 0 {
+                    *progress = Progress { started: Some(Instant::now()), ..Progress::default() };
+                }
+                progress.active += 1;
+            }
+            StreamEvent::Delta(text) => {
+                progress.chars += text.chars().count();
+                match text.rsplit_once('\n') {
+                    Some((_, tail)) => progress.last_line = tail.to_string(),
+                    None => progress.last_line.push_str(text),
+                }
+            }
+            StreamEvent::Finished => {
+                progress.active = progress.active.saturating_sub(1);
+                if progress.active == 0 {
+                    let _ = write!(stderr, "\r\x1b[2K");
+                    let _ = stderr.flush();
+                }
+                return;
+            }
+        }
+
+        let elapsed = progress.started.map(|s| s.elapsed().as_secs()).unwrap_or_default();
+        let line = progress.last_line.trim();
+        let preview: String = line.chars().skip(line.chars().count().saturating_sub(PREVIEW_LENGTH)).collect();
+        let _ = write!(stderr, "\r\x1b[2K🤖 AI responding... {} chars, {}s │ {}", progress.chars, elapsed, preview);
+        let _ = stderr.flush();
+    }))
+}
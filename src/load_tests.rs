@@ -0,0 +1,341 @@
+//! Load-test scaffolding for performance requirements: every sentence or
+//! performance NFR that states a response time, throughput, number of
+//! concurrent users or error rate becomes a scenario, written out as a k6
+//! script and a Gatling simulation with the stated numbers as thresholds.
+//! The requests are stubs where the requirement names no endpoint.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::analyzer::{NfrCategory, NonFunctionalRequirement};
+use crate::contract_tests::generate_contract_tests;
+
+/// Virtual users when the requirement states no load
+const DEFAULT_USERS: u32 = 10;
+
+/// Seconds each scenario runs
+const DURATION_SECONDS: u32 = 60;
+
+/// Percentile of a response time limit that names none
+const DEFAULT_PERCENTILE: f64 = 95.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum LoadThreshold {
+    /// The `percentile` of response times stays under `max_ms`
+    ResponseTime { percentile: f64, max_ms: f64 },
+    /// Requests per second the system sustains
+    Throughput { per_second: f64 },
+    ConcurrentUsers { users: u32 },
+    /// Failed requests stay under this share, in percent
+    ErrorRate { max_percent: f64 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadScenario {
+    /// "scenario_1"
+    pub name: String,
+    /// The sentence or NFR the thresholds come from
+    pub requirement: String,
+    /// 1-based line of the requirement; `None` for a suggested NFR
+    pub line: Option<usize>,
+    pub method: String,
+    /// Request path, or `None` when the requirement names no endpoint
+    pub path: Option<String>,
+    pub thresholds: Vec<LoadThreshold>,
+}
+
+impl LoadScenario {
+    pub fn users(&self) -> u32 {
+        self.thresholds.iter()
+            .find_map(|t| match t { LoadThreshold::ConcurrentUsers { users } => Some(*users), _ => None })
+            .unwrap_or(DEFAULT_USERS)
+    }
+
+    pub fn per_second(&self) -> Option<f64> {
+        self.thresholds.iter().find_map(|t| match t { LoadThreshold::Throughput { per_second } => Some(*per_second), _ => None })
+    }
+
+    /// Path with example values for its parameters, "/todo" without one
+    pub fn example_path(&self) -> String {
+        match &self.path {
+            Some(path) => Regex::new(r"\{[^}]*\}|:\w+").unwrap().replace_all(path, "1").to_string(),
+            None => "/todo".to_string(),
+        }
+    }
+
+    /// "GET /orders/{id}", or "scenario_1" without an endpoint
+    pub fn request_name(&self) -> String {
+        match &self.path {
+            Some(path) => format!("{} {}", self.method, path),
+            None => self.name.clone(),
+        }
+    }
+}
+
+fn parse_number(text: &str) -> Option<f64> {
+    text.replace(',', "").parse().ok()
+}
+
+/// The thresholds `sentence` states: "within 200 ms", "95% of requests in
+/// under 2 seconds", "p99 below 1s", "500 requests per second", "1,000
+/// concurrent users", "an error rate below 1%".
+pub fn find_thresholds(sentence: &str) -> Vec<LoadThreshold> {
+    let lower = sentence.to_lowercase();
+    let mut thresholds = Vec::new();
+
+    let time = Regex::new(r"\b(?:within|under|in less than|less than|below|at most|no more than|faster than|in)\s+(\d+(?:\.\d+)?)\s*(ms|milliseconds?|s|secs?|seconds?)\b").unwrap();
+    if let Some(caps) = time.captures(&lower) {
+        let value = parse_number(&caps[1]).unwrap_or_default();
+        let max_ms = if caps[2].starts_with('m') { value } else { value * 1000.0 };
+        let percentile = Regex::new(r"\bp(\d{2}(?:\.\d+)?)\b|\b(\d{2}(?:\.\d+)?)(?:\s*%|\s*percent|th percentile)").unwrap()
+            .captures(&lower)
+            .and_then(|c| parse_number(c.get(1).or(c.get(2))?.as_str()))
+            .filter(|p| *p > 0.0 && *p <= 100.0)
+            .unwrap_or(DEFAULT_PERCENTILE);
+        thresholds.push(LoadThreshold::ResponseTime { percentile, max_ms });
+    }
+
+    let throughput = Regex::new(r"\b(\d[\d,]*(?:\.\d+)?)\s+(?:requests|transactions|orders|operations|messages|queries|calls|req)\s*(?:per|/|a)\s*(second|sec|s|minute|min|hour)\b").unwrap();
+    if let Some(caps) = throughput.captures(&lower) {
+        let value = parse_number(&caps[1]).unwrap_or_default();
+        let per_second = match &caps[2] {
+            unit if unit.starts_with("min") => value / 60.0,
+            "hour" => value / 3600.0,
+            _ => value,
+        };
+        thresholds.push(LoadThreshold::Throughput { per_second });
+    }
+
+    let concurrent = Regex::new(r"\b(\d[\d,]*)\s+(?:concurrent|simultaneous|parallel|active)\s+(?:users|sessions|connections|requests|clients)\b").unwrap();
+    if let Some(users) = concurrent.captures(&lower).and_then(|c| parse_number(&c[1])) {
+        thresholds.push(LoadThreshold::ConcurrentUsers { users: users as u32 });
+    }
+
+    let errors = Regex::new(r"\berror rate\s+(?:of\s+)?(?:below|under|less than|at most|no more than|<)?\s*(\d+(?:\.\d+)?)\s*%").unwrap();
+    if let Some(max_percent) = errors.captures(&lower).and_then(|c| parse_number(&c[1])) {
+        thresholds.push(LoadThreshold::ErrorRate { max_percent });
+    }
+
+    thresholds
+}
+
+/// Scenarios for the performance statements of `text` and the performance
+/// NFRs in `nfrs` that state numbers.
+pub fn generate_load_scenarios(text: &str, nfrs: &[NonFunctionalRequirement]) -> Vec<LoadScenario> {
+    let mut found: Vec<(String, Option<usize>, Vec<LoadThreshold>)> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        for sentence in line.split_inclusive(['.', '!', '?', ';']).map(str::trim).filter(|s| !s.is_empty()) {
+            let thresholds = find_thresholds(sentence);
+            if !thresholds.is_empty() {
+                found.push((sentence.to_string(), Some(index + 1), thresholds));
+            }
+        }
+    }
+    for nfr in nfrs.iter().filter(|n| n.category == NfrCategory::Performance) {
+        let statement = std::iter::once(&nfr.requirement).chain(&nfr.acceptance_criteria).cloned().collect::<Vec<_>>().join(". ");
+        let thresholds = find_thresholds(&statement);
+        if !thresholds.is_empty() && !found.iter().any(|(requirement, _, _)| *requirement == nfr.requirement) {
+            found.push((nfr.requirement.clone(), None, thresholds));
+        }
+    }
+
+    found.into_iter().enumerate().map(|(i, (requirement, line, thresholds))| {
+        let endpoint = generate_contract_tests(&requirement).into_iter().next();
+        LoadScenario {
+            name: format!("scenario_{}", i + 1),
+            requirement,
+            line,
+            method: endpoint.as_ref().map_or("GET".to_string(), |e| e.method.clone()),
+            path: endpoint.map(|e| e.path),
+            thresholds,
+        }
+    }).collect()
+}
+
+fn source(scenario: &LoadScenario) -> String {
+    match scenario.line {
+        Some(line) => format!("Line {}: {}", line, scenario.requirement),
+        None => format!("Suggested NFR: {}", scenario.requirement),
+    }
+}
+
+fn k6_request(scenario: &LoadScenario) -> String {
+    let url = format!("`${{BASE_URL}}{}`", scenario.example_path());
+    let body = "JSON.stringify({}), { headers: { 'Content-Type': 'application/json' } }";
+    match scenario.method.as_str() {
+        "POST" => format!("http.post({}, {})", url, body),
+        "PUT" => format!("http.put({}, {})", url, body),
+        "PATCH" => format!("http.patch({}, {})", url, body),
+        "DELETE" => format!("http.del({})", url),
+        _ => format!("http.get({})", url),
+    }
+}
+
+/// A k6 script with one scenario per requirement; the thresholds fail the
+/// run when a stated limit is missed.
+pub fn format_k6_script(scenarios: &[LoadScenario]) -> String {
+    let mut options = String::new();
+    let mut thresholds = String::new();
+    let mut functions = String::new();
+    for scenario in scenarios {
+        let name = &scenario.name;
+        match scenario.per_second() {
+            Some(rate) => options.push_str(&format!(
+                "    {}: {{ executor: 'constant-arrival-rate', exec: '{}', rate: {}, timeUnit: '1s', duration: '{}s', preAllocatedVUs: {} }},\n",
+                name, name, rate.ceil(), DURATION_SECONDS, scenario.users().max(rate.ceil() as u32)
+            )),
+            None => options.push_str(&format!(
+                "    {}: {{ executor: 'constant-vus', exec: '{}', vus: {}, duration: '{}s' }},\n",
+                name, name, scenario.users(), DURATION_SECONDS
+            )),
+        }
+        let mut checks = vec!["    'status is 2xx': (r) => r.status >= 200 && r.status < 300,".to_string()];
+        let mut limits = Vec::new();
+        for threshold in &scenario.thresholds {
+            match threshold {
+                LoadThreshold::ResponseTime { percentile, max_ms } => {
+                    limits.push(format!("    'http_req_duration{{scenario:{}}}': ['p({})<{}'],", name, percentile, max_ms));
+                    checks.push(format!("    'responds within {} ms': (r) => r.timings.duration < {},", max_ms, max_ms));
+                }
+                LoadThreshold::ErrorRate { max_percent } => {
+                    limits.push(format!("    'http_req_failed{{scenario:{}}}': ['rate<{}'],", name, max_percent / 100.0));
+                }
+                LoadThreshold::Throughput { per_second } => {
+                    limits.push(format!("    'http_reqs{{scenario:{}}}': ['rate>={}'],", name, per_second));
+                }
+                LoadThreshold::ConcurrentUsers { .. } => {}
+            }
+        }
+        for limit in limits {
+            thresholds.push_str(&limit);
+            thresholds.push('\n');
+        }
+        functions.push_str(&format!("\n// {}\n", source(scenario)));
+        if scenario.path.is_none() {
+            functions.push_str("// TODO: replace /todo with the request this requirement is about\n");
+        }
+        // An arrival rate sets the pace itself; virtual users pause like a user would
+        let pause = if scenario.per_second().is_some() { "" } else { "  sleep(1);\n" };
+        functions.push_str(&format!(
+            "export function {}() {{\n  const res = {};\n  check(res, {{\n{}\n  }});\n{}}}\n",
+            name, k6_request(scenario), checks.join("\n"), pause
+        ));
+    }
+
+    format!(
+        "// Load test generated by PRISM from the performance requirements.\n\
+         // Run: k6 run -e BASE_URL=https://staging.example.com <this file>\n\
+         import http from 'k6/http';\n\
+         import {{ check, sleep }} from 'k6';\n\n\
+         const BASE_URL = __ENV.BASE_URL || 'http://localhost:8080';\n\n\
+         export const options = {{\n  scenarios: {{\n{}  }},\n  thresholds: {{\n{}  }},\n}};\n{}",
+        options, thresholds, functions
+    )
+}
+
+/// "orders-api" -> "OrdersApi"
+pub fn simulation_class_name(base: &str) -> String {
+    let name: String = base.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut chars = w.chars();
+            chars.next().map(|c| c.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+        })
+        .collect();
+    let name = if name.starts_with(|c: char| c.is_ascii_alphabetic()) { name } else { format!("Requirements{}", name) };
+    format!("{}Simulation", name)
+}
+
+fn gatling_request(scenario: &LoadScenario) -> String {
+    let path = scenario.example_path();
+    let request = match scenario.method.as_str() {
+        "POST" => format!(".post(\"{}\").body(StringBody(\"{{}}\")).asJson()", path),
+        "PUT" => format!(".put(\"{}\").body(StringBody(\"{{}}\")).asJson()", path),
+        "PATCH" => format!(".patch(\"{}\").body(StringBody(\"{{}}\")).asJson()", path),
+        "DELETE" => format!(".delete(\"{}\")", path),
+        _ => format!(".get(\"{}\")", path),
+    };
+    format!("http(\"{}\"){}.check(status().in(200, 201, 202, 204))", scenario.request_name(), request)
+}
+
+/// A Gatling simulation (Java DSL) named `class_name`, with the thresholds
+/// as assertions.
+pub fn format_gatling_simulation(scenarios: &[LoadScenario], class_name: &str) -> String {
+    let mut builders = String::new();
+    let mut injections = Vec::new();
+    let mut assertions = Vec::new();
+    for (i, scenario) in scenarios.iter().enumerate() {
+        let variable = format!("scenario{}", i + 1);
+        builders.push_str(&format!("\n    // {}\n", source(scenario)));
+        if scenario.path.is_none() {
+            builders.push_str("    // TODO: replace /todo with the request this requirement is about\n");
+        }
+        builders.push_str(&format!(
+            "    ScenarioBuilder {} = scenario(\"{}\")\n        .exec({});\n",
+            variable, scenario.name, gatling_request(scenario)
+        ));
+        injections.push(match scenario.per_second() {
+            Some(rate) => format!("{}.injectOpen(constantUsersPerSec({}).during({}))", variable, rate, DURATION_SECONDS),
+            None => format!("{}.injectClosed(constantConcurrentUsers({}).during({}))", variable, scenario.users(), DURATION_SECONDS),
+        });
+        let details = format!("details(\"{}\")", scenario.request_name());
+        for threshold in &scenario.thresholds {
+            match threshold {
+                LoadThreshold::ResponseTime { percentile, max_ms } => {
+                    assertions.push(format!("{}.responseTime().percentile({:.1}).lt({})", details, percentile, max_ms.round() as u64));
+                }
+                LoadThreshold::ErrorRate { max_percent } => {
+                    assertions.push(format!("{}.failedRequests().percent().lt({:.1})", details, max_percent));
+                }
+                LoadThreshold::Throughput { per_second } => {
+                    assertions.push(format!("{}.requestsPerSec().gte({:.1})", details, per_second));
+                }
+                LoadThreshold::ConcurrentUsers { .. } => {}
+            }
+        }
+    }
+
+    let mut setup = format!("        setUp(\n            {}\n        ).protocols(httpProtocol)", injections.join(",\n            "));
+    if !assertions.is_empty() {
+        setup.push_str(&format!("\n         .assertions(\n            {}\n         )", assertions.join(",\n            ")));
+    }
+    format!(
+        "// Load test generated by PRISM from the performance requirements.\n\
+         // Run: mvn gatling:test -Dgatling.simulationClass={class} -DbaseUrl=https://staging.example.com\n\
+         import static io.gatling.javaapi.core.CoreDsl.*;\n\
+         import static io.gatling.javaapi.http.HttpDsl.*;\n\n\
+         import io.gatling.javaapi.core.*;\n\
+         import io.gatling.javaapi.http.*;\n\n\
+         public class {class} extends Simulation {{\n\n\
+         \x20   HttpProtocolBuilder httpProtocol = http.baseUrl(System.getProperty(\"baseUrl\", \"http://localhost:8080\"));\n\
+         {builders}\n\
+         \x20   {{\n{setup};\n    }}\n}}\n",
+        class = class_name, builders = builders, setup = setup
+    )
+}
+
+impl std::fmt::Display for LoadThreshold {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadThreshold::ResponseTime { percentile, max_ms } => write!(f, "p{} response time < {} ms", percentile, max_ms),
+            LoadThreshold::Throughput { per_second } => write!(f, "≥ {} requests/s", per_second),
+            LoadThreshold::ConcurrentUsers { users } => write!(f, "{} concurrent users", users),
+            LoadThreshold::ErrorRate { max_percent } => write!(f, "error rate < {}%", max_percent),
+        }
+    }
+}
+
+pub fn format_load_tests_markdown(scenarios: &[LoadScenario]) -> String {
+    let mut output = String::from("| Scenario | Request | Thresholds | Requirement |\n|---|---|---|---|\n");
+    for scenario in scenarios {
+        let request = if scenario.path.is_some() { format!("`{}`", scenario.request_name()) } else { "*to be filled in*".to_string() };
+        let thresholds: Vec<String> = scenario.thresholds.iter().map(|t| t.to_string()).collect();
+        output.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            scenario.name, request, thresholds.join("<br>"), source(scenario).replace('|', "\\|")
+        ));
+    }
+    output.push('\n');
+    output
+}
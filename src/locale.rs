@@ -0,0 +1,153 @@
+//! Numbers, percentages and dates in reports as the team's locale writes
+//! them (`output.locale`), for reports embedded in official documents.
+//! JSON and SARIF reports keep plain numbers for the tools that read them.
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, TimeZone};
+use std::fmt::Display;
+use std::sync::RwLock;
+
+use crate::config::OutputConfig;
+
+/// Locales with their own conventions; a tag with another region uses its
+/// language's first entry, so "de-LU" writes numbers as "de-DE" does.
+const LOCALES: &[(&str, char, Option<char>, bool, &str)] = &[
+    // tag, decimal separator, thousands separator, space before %, date format
+    ("en-US", '.', Some(','), false, "%m/%d/%Y"),
+    ("en-GB", '.', Some(','), false, "%d/%m/%Y"),
+    ("en-CA", '.', Some(','), false, "%Y-%m-%d"),
+    ("en-AU", '.', Some(','), false, "%d/%m/%Y"),
+    ("en-IN", '.', Some(','), false, "%d/%m/%Y"),
+    ("de-DE", ',', Some('.'), true, "%d.%m.%Y"),
+    ("de-AT", ',', Some(' '), true, "%d.%m.%Y"),
+    ("de-CH", '.', Some('’'), false, "%d.%m.%Y"),
+    ("fr-FR", ',', Some('\u{202f}'), true, "%d/%m/%Y"),
+    ("fr-CA", ',', Some(' '), true, "%Y-%m-%d"),
+    ("fr-CH", ',', Some('\u{202f}'), true, "%d.%m.%Y"),
+    ("es-ES", ',', Some('.'), true, "%d/%m/%Y"),
+    ("es-MX", '.', Some(','), false, "%d/%m/%Y"),
+    ("it-IT", ',', Some('.'), false, "%d/%m/%Y"),
+    ("nl-NL", ',', Some('.'), false, "%d-%m-%Y"),
+    ("pt-BR", ',', Some('.'), false, "%d/%m/%Y"),
+    ("pt-PT", ',', Some(' '), false, "%d/%m/%Y"),
+    ("da-DK", ',', Some('.'), true, "%d.%m.%Y"),
+    ("sv-SE", ',', Some(' '), true, "%Y-%m-%d"),
+    ("nb-NO", ',', Some(' '), true, "%d.%m.%Y"),
+    ("fi-FI", ',', Some(' '), true, "%d.%m.%Y"),
+    ("pl-PL", ',', Some(' '), false, "%d.%m.%Y"),
+    ("cs-CZ", ',', Some(' '), true, "%d.%m.%Y"),
+    ("ja-JP", '.', Some(','), false, "%Y/%m/%d"),
+    ("zh-CN", '.', Some(','), false, "%Y/%m/%d"),
+    ("ko-KR", '.', Some(','), false, "%Y.%m.%d"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportLocale {
+    pub decimal_separator: char,
+    /// Groups the digits of numbers from 1000 on
+    pub thousands_separator: Option<char>,
+    /// "45,0 %" rather than "45,0%"
+    pub spaced_percent: bool,
+    /// chrono format of dates, e.g. "%d.%m.%Y"
+    pub date_format: String,
+}
+
+/// Without `output.locale`, reports keep the numbers and ISO dates they
+/// always had.
+impl Default for ReportLocale {
+    fn default() -> Self {
+        Self { decimal_separator: '.', thousands_separator: None, spaced_percent: false, date_format: "%Y-%m-%d".to_string() }
+    }
+}
+
+impl ReportLocale {
+    /// The locale of a tag like "de-DE"; "de_DE.UTF-8" as in `$LANG` and a
+    /// bare language like "fr" work too.
+    pub fn from_tag(tag: &str) -> Result<Self> {
+        let tag = tag.split('.').next().unwrap_or_default().replace('_', "-");
+        let (language, region) = tag.split_once('-').unwrap_or((&tag, ""));
+        let language = language.to_lowercase();
+        let exact = format!("{}-{}", language, region.to_uppercase());
+        let Some(&(_, decimal_separator, thousands_separator, spaced_percent, date_format)) = LOCALES.iter()
+            .find(|(known, ..)| *known == exact)
+            .or_else(|| LOCALES.iter().find(|(known, ..)| known.split('-').next() == Some(language.as_str())))
+        else {
+            bail!(
+                "Unknown locale '{}'; use one of {}",
+                tag, LOCALES.iter().map(|(known, ..)| *known).collect::<Vec<_>>().join(", ")
+            );
+        };
+        Ok(Self { decimal_separator, thousands_separator, spaced_percent, date_format: date_format.to_string() })
+    }
+
+    /// The locale of `output.locale`, with `output.date_format` overriding
+    /// its date format.
+    pub fn from_config(output: &OutputConfig) -> Result<Self> {
+        let mut locale = match &output.locale {
+            Some(tag) => Self::from_tag(tag)?,
+            None => Self::default(),
+        };
+        if let Some(date_format) = &output.date_format {
+            locale.date_format = date_format.clone();
+        }
+        Ok(locale)
+    }
+
+    pub fn number(&self, value: impl Into<f64>, decimals: usize) -> String {
+        let formatted = format!("{:.*}", decimals, value.into());
+        let (sign, digits) = match formatted.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", formatted.as_str()),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        let mut output = sign.to_string();
+        for (i, digit) in whole.chars().enumerate() {
+            if i > 0 && (whole.len() - i) % 3 == 0 {
+                if let Some(separator) = self.thousands_separator {
+                    output.push(separator);
+                }
+            }
+            output.push(digit);
+        }
+        if !fraction.is_empty() {
+            output.push(self.decimal_separator);
+            output.push_str(fraction);
+        }
+        output
+    }
+
+    pub fn integer(&self, value: usize) -> String {
+        self.number(value as f64, 0)
+    }
+
+    pub fn percent(&self, value: impl Into<f64>, decimals: usize) -> String {
+        format!("{}{}%", self.number(value, decimals), if self.spaced_percent { "\u{a0}" } else { "" })
+    }
+
+    pub fn date<Tz: TimeZone>(&self, at: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: Display,
+    {
+        at.format(&self.date_format).to_string()
+    }
+
+    /// The date and time of day, e.g. "16.10.2026 14:05"
+    pub fn date_time<Tz: TimeZone>(&self, at: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: Display,
+    {
+        format!("{} {}", self.date(at), at.format("%H:%M"))
+    }
+}
+
+static REPORT_LOCALE: RwLock<Option<ReportLocale>> = RwLock::new(None);
+
+/// Makes reports written from now on use `locale`.
+pub fn set_report_locale(locale: ReportLocale) {
+    *REPORT_LOCALE.write().unwrap_or_else(|e| e.into_inner()) = Some(locale);
+}
+
+/// The locale reports are written in, by default none in particular.
+pub fn report_locale() -> ReportLocale {
+    REPORT_LOCALE.read().unwrap_or_else(|e| e.into_inner()).clone().unwrap_or_default()
+}
@@ -1,27 +1,34 @@
-mod app;
-mod cli;
-mod analyzer;
-mod ui;
-mod config;
-mod document_processor;
-
 #[cfg(test)]
 mod test_git;
 
 use anyhow::Result;
 use clap::{Parser, CommandFactory};
 
-use crate::app::App;
-use crate::cli::Cli;
+use prism::app::App;
+use prism::cli::Cli;
+use prism::events::{emit, set_events, Event};
+use prism::fixtures::LlmFixtures;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
+    set_events(cli.events);
     
     match cli.command {
         Some(cmd) => {
-            let mut app = App::new().await?;
-            app.run_command(cmd).await?;
+            let mut app = App::new().await?
+                .with_doc_password(cli.doc_password)
+                .with_llm_fixtures(LlmFixtures::from_flags(cli.record, cli.replay))
+                .with_no_banner(cli.no_banner || cli.events)
+                .with_paths_relative(cli.paths_relative);
+            if let Err(e) = app.run_command(cmd).await {
+                if !cli.events {
+                    return Err(e);
+                }
+                // The error event is the last line of the stream
+                emit(&Event::Error { message: format!("{:#}", e) });
+                std::process::exit(1);
+            }
         }
         None => {
             // Default to showing help when no command is specified
@@ -0,0 +1,123 @@
+//! Combines the analysis results of several files (`prism analyze --format
+//! json`) into one, for CI jobs that analyze shards of a repository in
+//! parallel and report on the whole.
+
+use anyhow::{bail, Result};
+
+use crate::analyzer::{AnalysisMetrics, AnalysisResult};
+
+/// Appends `more` to `total`, or takes it when `total` has nothing yet.
+fn combine<T>(total: &mut Option<T>, more: Option<T>, append: impl FnOnce(&mut T, T)) {
+    match (total.as_mut(), more) {
+        (Some(total), Some(more)) => append(total, more),
+        (None, Some(more)) => *total = Some(more),
+        _ => {}
+    }
+}
+
+fn join_text(total: &mut String, more: String) {
+    total.push_str("\n\n");
+    total.push_str(&more);
+}
+
+/// Sorted, without entries that differ only in letter case.
+fn union(total: &mut Vec<String>, more: Vec<String>) {
+    total.extend(more);
+    total.sort_by_key(|item| item.to_lowercase());
+    total.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+}
+
+/// Findings, entities, metrics and the generated artifacts of `results`
+/// as one result. Counts are summed and the densities recomputed; the
+/// completeness score is the mean weighted by requirement count. UML
+/// diagrams and user story validation describe a single document, so they
+/// are only kept when one result is merged.
+pub fn merge_results(results: Vec<AnalysisResult>) -> Result<AnalysisResult> {
+    let single = results.len() == 1;
+    let mut results = results.into_iter();
+    let Some(mut merged) = results.next() else {
+        bail!("No analysis results to merge");
+    };
+    let weight = |metrics: &AnalysisMetrics| metrics.requirement_count.max(1) as f32;
+    let mut completeness_weight = merged.completeness_analysis.as_ref().map(|_| weight(&merged.metrics)).unwrap_or(0.0);
+
+    for result in results {
+        merged.ambiguities.extend(result.ambiguities);
+        merged.suppressed_ambiguities.extend(result.suppressed_ambiguities);
+        merged.hidden_findings.duplicates += result.hidden_findings.duplicates;
+        merged.hidden_findings.below_min_severity += result.hidden_findings.below_min_severity;
+        merged.hidden_findings.baselined += result.hidden_findings.baselined;
+        merged.hidden_findings.min_severity = merged.hidden_findings.min_severity.max(result.hidden_findings.min_severity);
+        union(&mut merged.entities.actors, result.entities.actors);
+        union(&mut merged.entities.actions, result.entities.actions);
+        union(&mut merged.entities.objects, result.entities.objects);
+        merged.metrics.word_count += result.metrics.word_count;
+        merged.metrics.requirement_count += result.metrics.requirement_count;
+        merged.ai_failed |= result.ai_failed;
+
+        if let Some(completeness) = result.completeness_analysis {
+            let (before, added) = (completeness_weight, weight(&result.metrics));
+            completeness_weight += added;
+            let score = completeness.completeness_score;
+            combine(&mut merged.completeness_analysis, Some(completeness), |total, more| {
+                total.completeness_score = (total.completeness_score * before + score * added) / (before + added);
+                union(&mut total.missing_actors, more.missing_actors);
+                union(&mut total.missing_success_criteria, more.missing_success_criteria);
+                union(&mut total.missing_nf_considerations, more.missing_nf_considerations);
+                total.gaps_identified.extend(more.gaps_identified);
+            });
+        }
+
+        combine(&mut merged.pseudocode, result.pseudocode, join_text);
+        combine(&mut merged.gherkin, result.gherkin, join_text);
+        combine(&mut merged.improved_requirements, result.improved_requirements, join_text);
+        combine(&mut merged.test_cases, result.test_cases, |total, more| {
+            total.happy_path.extend(more.happy_path);
+            total.negative_cases.extend(more.negative_cases);
+            total.edge_cases.extend(more.edge_cases);
+        });
+        combine(&mut merged.contract_tests, result.contract_tests, Vec::extend);
+        combine(&mut merged.test_data, result.test_data, Vec::extend);
+        combine(&mut merged.nfr_suggestions, result.nfr_suggestions, Vec::extend);
+        combine(&mut merged.load_tests, result.load_tests, Vec::extend);
+        combine(&mut merged.security_tests, result.security_tests, Vec::extend);
+        combine(&mut merged.reviewer_suggestions, result.reviewer_suggestions, Vec::extend);
+        combine(&mut merged.rewrite_suggestions, result.rewrite_suggestions, Vec::extend);
+        combine(&mut merged.test_coverage, result.test_coverage, |total, more| total.requirements.extend(more.requirements));
+        combine(&mut merged.smart_analysis, result.smart_analysis, |total, more| total.requirements.extend(more.requirements));
+        combine(&mut merged.ears_validation, result.ears_validation, |total, more| total.sentences.extend(more.sentences));
+        combine(&mut merged.permission_matrix, result.permission_matrix, |total, more| {
+            union(&mut total.actors, more.actors);
+            union(&mut total.actions, more.actions);
+            total.grants.extend(more.grants);
+            union(&mut total.unassigned_actions, more.unassigned_actions);
+        });
+        combine(&mut merged.dependency_graph, result.dependency_graph, |total, more| {
+            total.requirements.extend(more.requirements);
+            total.edges.extend(more.edges);
+            total.cycles.extend(more.cycles);
+        });
+        combine(&mut merged.domain_model, result.domain_model, |total, more| {
+            total.aggregates.extend(more.aggregates);
+            total.unassigned_events.extend(more.unassigned_events);
+        });
+        combine(&mut merged.event_storming, result.event_storming, |total, more| {
+            total.commands.extend(more.commands);
+            total.policies.extend(more.policies);
+            total.read_models.extend(more.read_models);
+        });
+    }
+
+    if !single {
+        merged.uml_diagrams = None;
+        merged.user_story_validation = None;
+    }
+    let per = |count: usize, base: f32| if base > 0.0 { count as f32 / base } else { 0.0 };
+    let findings = merged.ambiguities.len();
+    merged.metrics = AnalysisMetrics {
+        findings_per_100_words: per(findings, merged.metrics.word_count as f32 / 100.0),
+        findings_per_requirement: per(findings, merged.metrics.requirement_count as f32),
+        ..merged.metrics
+    };
+    Ok(merged)
+}
@@ -0,0 +1,179 @@
+//! Prometheus metrics of `prism serve` and `prism daemon`: analyses run, LLM
+//! latency, failures and estimated token usage, in the text exposition format.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::budget::estimate_tokens;
+
+/// Upper bounds in seconds of the latency histogram buckets. LLM calls take
+/// from well under a second (Ollama, small models) to minutes (long prompts).
+const LATENCY_BUCKETS: [f64; 10] = [0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 20.0, 30.0, 60.0, 120.0];
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    /// Observations per bucket of `LATENCY_BUCKETS`, not cumulative
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|bound| seconds <= *bound) {
+            self.buckets[bucket] += 1;
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+}
+
+#[derive(Default)]
+struct MetricsState {
+    /// By (source, outcome)
+    analyses: BTreeMap<(String, &'static str), u64>,
+    analysis_duration: BTreeMap<String, Histogram>,
+    /// By (provider, outcome)
+    llm_requests: BTreeMap<(String, &'static str), u64>,
+    llm_duration: BTreeMap<String, Histogram>,
+    /// By (provider, direction)
+    llm_tokens: BTreeMap<(String, &'static str), u64>,
+    /// By (provider, outcome of reading the JSON)
+    llm_json: BTreeMap<(String, &'static str), u64>,
+    /// By (path, status)
+    http_requests: BTreeMap<(String, u16), u64>,
+    /// By (job, outcome)
+    daemon_runs: BTreeMap<(String, &'static str), u64>,
+    daemon_scores: BTreeMap<String, f64>,
+}
+
+/// Counters shared by the server or daemon and its analyzer.
+#[derive(Default)]
+pub struct Metrics {
+    state: Mutex<MetricsState>,
+}
+
+fn outcome(ok: bool) -> &'static str {
+    if ok { "success" } else { "failure" }
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// One analysis of a document; `source` is what asked for it, e.g. "api".
+    pub fn record_analysis(&self, source: &str, ok: bool, duration: Duration) {
+        let mut state = self.state.lock().unwrap();
+        *state.analyses.entry((source.to_string(), outcome(ok))).or_default() += 1;
+        state.analysis_duration.entry(source.to_string()).or_default().observe(duration.as_secs_f64());
+    }
+
+    /// One LLM call, with the sizes of its prompt and response.
+    pub fn record_llm_request(&self, provider: &str, ok: bool, duration: Duration, prompt_chars: usize, response_chars: usize) {
+        let mut state = self.state.lock().unwrap();
+        *state.llm_requests.entry((provider.to_string(), outcome(ok))).or_default() += 1;
+        state.llm_duration.entry(provider.to_string()).or_default().observe(duration.as_secs_f64());
+        *state.llm_tokens.entry((provider.to_string(), "prompt")).or_default() += estimate_tokens(prompt_chars);
+        *state.llm_tokens.entry((provider.to_string(), "completion")).or_default() += estimate_tokens(response_chars);
+    }
+
+    /// One LLM response read as JSON; `outcome` is "clean", "repaired",
+    /// "salvaged" or "failed".
+    pub fn record_llm_json(&self, provider: &str, outcome: &'static str) {
+        *self.state.lock().unwrap().llm_json.entry((provider.to_string(), outcome)).or_default() += 1;
+    }
+
+    pub fn record_http_request(&self, path: &str, status: u16) {
+        *self.state.lock().unwrap().http_requests.entry((path.to_string(), status)).or_default() += 1;
+    }
+
+    /// One run of a scheduled job, with its quality score when it succeeded.
+    pub fn record_daemon_run(&self, job: &str, score: Option<f64>) {
+        let mut state = self.state.lock().unwrap();
+        *state.daemon_runs.entry((job.to_string(), outcome(score.is_some()))).or_default() += 1;
+        if let Some(score) = score {
+            state.daemon_scores.insert(job.to_string(), score);
+        }
+    }
+
+    /// All metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let state = self.state.lock().unwrap();
+        let mut output = String::new();
+
+        header(&mut output, "prism_analyses_total", "counter", "Requirement documents analyzed");
+        for ((source, outcome), count) in &state.analyses {
+            sample(&mut output, "prism_analyses_total", &[("source", source), ("outcome", outcome)], *count as f64);
+        }
+        header(&mut output, "prism_analysis_duration_seconds", "histogram", "Time taken to analyze a document");
+        for (source, histogram) in &state.analysis_duration {
+            histogram_samples(&mut output, "prism_analysis_duration_seconds", ("source", source), histogram);
+        }
+
+        header(&mut output, "prism_llm_requests_total", "counter", "LLM requests, after retries");
+        for ((provider, outcome), count) in &state.llm_requests {
+            sample(&mut output, "prism_llm_requests_total", &[("provider", provider), ("outcome", outcome)], *count as f64);
+        }
+        header(&mut output, "prism_llm_request_duration_seconds", "histogram", "LLM request latency, including retries");
+        for (provider, histogram) in &state.llm_duration {
+            histogram_samples(&mut output, "prism_llm_request_duration_seconds", ("provider", provider), histogram);
+        }
+        header(&mut output, "prism_llm_tokens_total", "counter", "Tokens sent to and received from LLMs, estimated from their length");
+        for ((provider, direction), count) in &state.llm_tokens {
+            sample(&mut output, "prism_llm_tokens_total", &[("provider", provider), ("direction", direction)], *count as f64);
+        }
+        header(&mut output, "prism_llm_json_responses_total", "counter", "LLM responses read as JSON, by whether they needed repair");
+        for ((provider, outcome), count) in &state.llm_json {
+            sample(&mut output, "prism_llm_json_responses_total", &[("provider", provider), ("outcome", outcome)], *count as f64);
+        }
+
+        if !state.http_requests.is_empty() {
+            header(&mut output, "prism_http_requests_total", "counter", "HTTP requests answered by prism serve");
+            for ((path, status), count) in &state.http_requests {
+                sample(&mut output, "prism_http_requests_total", &[("path", path), ("status", &status.to_string())], *count as f64);
+            }
+        }
+
+        if !state.daemon_runs.is_empty() {
+            header(&mut output, "prism_daemon_runs_total", "counter", "Runs of scheduled analyses");
+            for ((job, outcome), count) in &state.daemon_runs {
+                sample(&mut output, "prism_daemon_runs_total", &[("job", job), ("outcome", outcome)], *count as f64);
+            }
+            header(&mut output, "prism_daemon_quality_score", "gauge", "Quality score (0-100) of the latest successful run of a job");
+            for (job, score) in &state.daemon_scores {
+                sample(&mut output, "prism_daemon_quality_score", &[("job", job)], *score);
+            }
+        }
+        output
+    }
+}
+
+fn header(output: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(output, "# HELP {} {}\n# TYPE {} {}", name, help, name, kind);
+}
+
+fn sample(output: &mut String, name: &str, labels: &[(&str, &str)], value: f64) {
+    let labels: Vec<String> = labels.iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+        .collect();
+    let _ = writeln!(output, "{}{{{}}} {}", name, labels.join(","), value);
+}
+
+fn histogram_samples(output: &mut String, name: &str, label: (&str, &str), histogram: &Histogram) {
+    let bucket_name = format!("{}_bucket", name);
+    let mut cumulative = 0;
+    for (bound, count) in LATENCY_BUCKETS.iter().zip(histogram.buckets) {
+        cumulative += count;
+        sample(output, &bucket_name, &[label, ("le", &bound.to_string())], cumulative as f64);
+    }
+    sample(output, &bucket_name, &[label, ("le", "+Inf")], histogram.count as f64);
+    sample(output, &format!("{}_sum", name), &[label], histogram.sum);
+    sample(output, &format!("{}_count", name), &[label], histogram.count as f64);
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
@@ -0,0 +1,69 @@
+//! The `mock` provider: canned responses instead of an LLM, so rule and
+//! prompt template authors and integration tests can run the whole AI code
+//! path without network access or API keys.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::prompts::PromptTask;
+
+/// The responses file of `llm.mock_responses`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MockResponses {
+    /// Responses to prompts containing a text, checked first; the first match wins
+    pub matches: Vec<MockMatch>,
+    /// Responses by AI task: ambiguities, entities, completeness, nfrs,
+    /// improvement or rewrites
+    pub tasks: BTreeMap<String, String>,
+    /// Response to any other prompt, such as a chat question
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MockMatch {
+    pub contains: String,
+    pub response: String,
+}
+
+impl MockResponses {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).with_context(|| format!("Cannot read the mock responses {}", path.display()))?;
+        let responses: Self = serde_yaml::from_str(&content).with_context(|| format!("Invalid mock responses {}", path.display()))?;
+        for task in responses.tasks.keys() {
+            if !PromptTask::ALL.iter().any(|t| t.name() == task) {
+                bail!(
+                    "Unknown AI task '{}' in {}; use {}",
+                    task, path.display(), PromptTask::ALL.iter().map(|t| t.name()).collect::<Vec<_>>().join(", ")
+                );
+            }
+        }
+        Ok(responses)
+    }
+
+    /// The response to `prompt`, rendered for `task`, falling back to the
+    /// built-in response.
+    pub fn respond(&self, task: Option<PromptTask>, prompt: &str) -> String {
+        self.matches.iter()
+            .find(|m| prompt.contains(&m.contains))
+            .map(|m| m.response.clone())
+            .or_else(|| task.and_then(|task| self.tasks.get(task.name()).cloned()))
+            .or_else(|| self.default.clone())
+            .unwrap_or_else(|| built_in_response(task).to_string())
+    }
+}
+
+/// What the mock provider answers without a responses file: valid for
+/// every task and adding nothing to the analysis.
+pub fn built_in_response(task: Option<PromptTask>) -> &'static str {
+    match task {
+        Some(PromptTask::Ambiguities) => r#"{"ambiguities": []}"#,
+        Some(PromptTask::Entities) => r#"{"actors": [], "actions": [], "objects": []}"#,
+        Some(PromptTask::Completeness) => r#"{"gaps": []}"#,
+        Some(PromptTask::Nfrs) => r#"{"nfrs": []}"#,
+        Some(PromptTask::Rewrites) => r#"{"rewrites": []}"#,
+        Some(PromptTask::Improvement) | None => "Canned response of the mock provider.",
+    }
+}
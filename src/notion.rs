@@ -0,0 +1,268 @@
+//! Pulls requirement pages from Notion for `prism notion`.
+
+use anyhow::Result;
+use reqwest::{Client, Method};
+use serde_json::{json, Value};
+
+use crate::analyzer::{AmbiguitySeverity, AnalysisResult};
+use crate::locale::report_locale;
+
+pub const NOTION_API_URL: &str = "https://api.notion.com/v1";
+pub const NOTION_VERSION: &str = "2022-06-28";
+
+/// Notion rejects rich text longer than this.
+const MAX_RICH_TEXT_LENGTH: usize = 2000;
+
+/// Nested blocks below this depth are not fetched.
+const MAX_BLOCK_DEPTH: usize = 3;
+
+/// A requirement page pulled from Notion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotionPage {
+    pub id: String,
+    pub title: String,
+    pub url: String,
+}
+
+impl NotionPage {
+    pub fn from_json(page: &Value) -> Self {
+        let title = page["properties"]
+            .as_object()
+            .and_then(|properties| properties.values().find(|p| p["type"] == "title"))
+            .map(|p| rich_text_plain(&p["title"]))
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or_else(|| "Untitled".to_string());
+        Self {
+            id: page["id"].as_str().unwrap_or_default().to_string(),
+            title,
+            url: page["url"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+
+    /// Report file name, e.g. "checkout-flow.md".
+    pub fn file_name(&self, extension: &str) -> String {
+        let slug: Vec<String> = self.title.split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .take(6)
+            .map(|w| w.to_lowercase())
+            .collect();
+        if slug.is_empty() {
+            format!("{}.{}", self.id.replace('-', ""), extension)
+        } else {
+            format!("{}.{}", slug.join("-"), extension)
+        }
+    }
+}
+
+pub struct NotionClient {
+    http_client: Client,
+    token: String,
+}
+
+impl NotionClient {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self { http_client: Client::new(), token: token.into() }
+    }
+
+    async fn request(&self, method: Method, path: &str, body: Option<Value>) -> Result<Value> {
+        let mut request = self.http_client
+            .request(method, format!("{}/{}", NOTION_API_URL, path))
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Notion-Version", NOTION_VERSION);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error: Value = response.json().await.unwrap_or_default();
+            let message = error["message"].as_str().unwrap_or("no details");
+            return Err(anyhow::anyhow!("Notion API request failed ({}): {}", status, message));
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Pages of `database_id`, only those tagged `tag` in `tag_property` when given.
+    pub async fn query_database(&self, database_id: &str, tag: Option<(&str, &str)>) -> Result<Vec<NotionPage>> {
+        let filter = match tag {
+            Some((property, tag)) => {
+                let database = self.request(Method::GET, &format!("databases/{}", database_id), None).await?;
+                let property_type = database["properties"][property]["type"].as_str()
+                    .ok_or_else(|| anyhow::anyhow!("Notion database has no '{}' property to filter requirements by", property))?;
+                Some(tag_filter(property, property_type, tag)?)
+            }
+            None => None,
+        };
+
+        let mut pages = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut body = json!({ "page_size": 100 });
+            if let Some(filter) = &filter {
+                body["filter"] = filter.clone();
+            }
+            if let Some(cursor) = &cursor {
+                body["start_cursor"] = json!(cursor);
+            }
+
+            let response = self.request(Method::POST, &format!("databases/{}/query", database_id), Some(body)).await?;
+            pages.extend(response["results"].as_array().into_iter().flatten().map(NotionPage::from_json));
+            match response["next_cursor"].as_str() {
+                Some(next) if response["has_more"] == true => cursor = Some(next.to_string()),
+                _ => return Ok(pages),
+            }
+        }
+    }
+
+    pub async fn page(&self, page_id: &str) -> Result<NotionPage> {
+        let page = self.request(Method::GET, &format!("pages/{}", page_id), None).await?;
+        Ok(NotionPage::from_json(&page))
+    }
+
+    /// The page's content as markdown, titled with the page title.
+    pub async fn page_markdown(&self, page: &NotionPage) -> Result<String> {
+        let blocks = self.block_children(&page.id, 0).await?;
+        Ok(format!("# {}\n\n{}", page.title, blocks_to_markdown(&blocks)))
+    }
+
+    /// Child blocks of `block_id`, with their own children under `"children"`.
+    async fn block_children(&self, block_id: &str, depth: usize) -> Result<Vec<Value>> {
+        let mut blocks = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut path = format!("blocks/{}/children?page_size=100", block_id);
+            if let Some(cursor) = &cursor {
+                path.push_str(&format!("&start_cursor={}", cursor));
+            }
+
+            let response = self.request(Method::GET, &path, None).await?;
+            for mut block in response["results"].as_array().cloned().unwrap_or_default() {
+                // Child pages and databases are pulled on their own, not inlined
+                let inline = !matches!(block["type"].as_str(), Some("child_page" | "child_database"));
+                if block["has_children"] == true && inline && depth + 1 < MAX_BLOCK_DEPTH {
+                    let id = block["id"].as_str().unwrap_or_default().to_string();
+                    block["children"] = Value::Array(Box::pin(self.block_children(&id, depth + 1)).await?);
+                }
+                blocks.push(block);
+            }
+            match response["next_cursor"].as_str() {
+                Some(next) if response["has_more"] == true => cursor = Some(next.to_string()),
+                _ => return Ok(blocks),
+            }
+        }
+    }
+
+    /// Writes `summary` to the rich-text `property` of the page.
+    pub async fn update_summary(&self, page_id: &str, property: &str, summary: &str) -> Result<()> {
+        self.request(Method::PATCH, &format!("pages/{}", page_id), Some(summary_update(property, summary))).await?;
+        Ok(())
+    }
+}
+
+/// Page or database ID from an ID or a Notion URL, in dashed form.
+pub fn notion_id(input: &str) -> Option<String> {
+    let path = input.split(['?', '#']).next().unwrap_or_default();
+    let hex: Vec<char> = path.chars().filter(|c| *c != '-').collect();
+    if hex.len() < 32 {
+        return None;
+    }
+    let id: String = hex[hex.len() - 32..].iter().collect();
+    if !id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(format!("{}-{}-{}-{}-{}", &id[..8], &id[8..12], &id[12..16], &id[16..20], &id[20..]))
+}
+
+/// Database query filter matching `tag` in a select, multi-select or status property.
+pub fn tag_filter(property: &str, property_type: &str, tag: &str) -> Result<Value> {
+    let condition = match property_type {
+        "multi_select" => json!({ "contains": tag }),
+        "select" | "status" => json!({ "equals": tag }),
+        "rich_text" | "title" => json!({ "contains": tag }),
+        other => return Err(anyhow::anyhow!("Cannot filter requirements by the '{}' property: {} properties are not supported", property, other)),
+    };
+    Ok(json!({ "property": property, property_type: condition }))
+}
+
+pub fn rich_text_plain(rich_text: &Value) -> String {
+    rich_text.as_array()
+        .map(|parts| parts.iter().filter_map(|part| part["plain_text"].as_str()).collect())
+        .unwrap_or_default()
+}
+
+/// Markdown for Notion blocks, as fetched with their children.
+pub fn blocks_to_markdown(blocks: &[Value]) -> String {
+    let mut output = String::new();
+    push_blocks(&mut output, blocks, 0);
+    output
+}
+
+fn push_blocks(output: &mut String, blocks: &[Value], depth: usize) {
+    let indent = "  ".repeat(depth);
+    for block in blocks {
+        let kind = block["type"].as_str().unwrap_or_default();
+        let content = &block[kind];
+        let text = rich_text_plain(&content["rich_text"]);
+
+        let line = match kind {
+            "paragraph" if text.is_empty() => None,
+            "paragraph" | "toggle" => Some(text),
+            "heading_1" => Some(format!("# {}", text)),
+            "heading_2" => Some(format!("## {}", text)),
+            "heading_3" => Some(format!("### {}", text)),
+            "bulleted_list_item" => Some(format!("- {}", text)),
+            "numbered_list_item" => Some(format!("1. {}", text)),
+            "to_do" => Some(format!("- [{}] {}", if content["checked"] == true { "x" } else { " " }, text)),
+            "quote" | "callout" => Some(format!("> {}", text)),
+            "code" => Some(format!("```{}\n{}\n```", content["language"].as_str().unwrap_or_default(), text)),
+            "divider" => Some("---".to_string()),
+            "table_row" => {
+                let cells: Vec<String> = content["cells"].as_array().into_iter().flatten().map(rich_text_plain).collect();
+                Some(format!("| {} |", cells.join(" | ")))
+            }
+            "child_page" => content["title"].as_str().map(|title| format!("📄 {}", title)),
+            _ => None,
+        };
+
+        let is_list = matches!(kind, "bulleted_list_item" | "numbered_list_item" | "to_do" | "table_row");
+        if let Some(line) = line {
+            output.push_str(&indent);
+            output.push_str(&line);
+            output.push_str(if is_list { "\n" } else { "\n\n" });
+        }
+        if let Some(children) = block["children"].as_array() {
+            push_blocks(output, children, if is_list { depth + 1 } else { depth });
+        }
+    }
+}
+
+/// One-line result written back to the page, e.g.
+/// "PRISM: 3 findings (1 Critical, 1 High), 4.2 per 100 words".
+pub fn format_notion_summary(result: &AnalysisResult) -> String {
+    let findings = result.ambiguities.len();
+    if findings == 0 {
+        return "PRISM: no findings".to_string();
+    }
+
+    let count = |severity: AmbiguitySeverity| result.ambiguities.iter().filter(|a| a.severity == severity).count();
+    let severe: Vec<String> = [AmbiguitySeverity::Critical, AmbiguitySeverity::High]
+        .into_iter()
+        .map(|severity| (count(severity), severity))
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, severity)| format!("{} {}", n, severity))
+        .collect();
+
+    let mut summary = format!("PRISM: {} finding{}", findings, if findings == 1 { "" } else { "s" });
+    if !severe.is_empty() {
+        summary.push_str(&format!(" ({})", severe.join(", ")));
+    }
+    summary.push_str(&format!(", {} per 100 words", report_locale().number(result.metrics.findings_per_100_words, 1)));
+    summary
+}
+
+/// Page update setting the rich-text `property` to `summary`.
+pub fn summary_update(property: &str, summary: &str) -> Value {
+    let content: String = summary.chars().take(MAX_RICH_TEXT_LENGTH).collect();
+    json!({ "properties": { property: { "rich_text": [{ "type": "text", "text": { "content": content } }] } } })
+}
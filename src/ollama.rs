@@ -0,0 +1,93 @@
+//! Ollama's native API: the server behind `llm.base_url`, the models it has
+//! installed and pulling a missing one.
+
+use anyhow::{bail, Result};
+use reqwest::Client;
+use serde_json::Value;
+
+use crate::config::KeepAlive;
+
+pub const DEFAULT_SERVER: &str = "http://localhost:11434";
+
+/// The server of `base_url`, which may also name one of its endpoints, as
+/// the `/api/generate` URLs saved by older versions do.
+pub fn server_url(base_url: Option<&str>) -> String {
+    let url = base_url.unwrap_or(DEFAULT_SERVER).trim_end_matches('/');
+    let url = ["/api/generate", "/api/chat"].iter().fold(url, |url, endpoint| url.strip_suffix(endpoint).unwrap_or(url));
+    url.trim_end_matches('/').to_string()
+}
+
+pub fn chat_url(base_url: Option<&str>) -> String {
+    format!("{}/api/chat", server_url(base_url))
+}
+
+/// `llm.ollama.keep_alive` as Ollama takes it; a duration without a unit
+/// is a number of seconds.
+pub fn keep_alive_value(keep_alive: &KeepAlive) -> Value {
+    match keep_alive {
+        KeepAlive::Seconds(seconds) => Value::from(*seconds),
+        KeepAlive::Duration(duration) => match duration.trim().parse::<i64>() {
+            Ok(seconds) => Value::from(seconds),
+            Err(_) => Value::from(duration.trim()),
+        },
+    }
+}
+
+/// Whether `model` is one of `installed`, where "llama3.1" stands for "llama3.1:latest".
+pub fn is_installed(installed: &[String], model: &str) -> bool {
+    let tagged = |name: &str| if name.contains(':') { name.to_string() } else { format!("{}:latest", name) };
+    installed.iter().any(|name| tagged(name) == tagged(model))
+}
+
+/// Whether an Ollama error response says the model is not installed.
+pub fn is_missing_model_error(error: &str) -> bool {
+    let error = error.to_lowercase();
+    error.contains("model") && error.contains("not found")
+}
+
+/// The models installed on `server`.
+pub async fn installed_models(client: &Client, server: &str) -> Result<Vec<String>> {
+    let response = client.get(format!("{}/api/tags", server)).send().await?;
+    if !response.status().is_success() {
+        bail!("Ollama server responded with {}", response.status());
+    }
+    let tags: Value = response.json().await?;
+    Ok(tags["models"].as_array().into_iter().flatten()
+        .filter_map(|model| model["name"].as_str().map(str::to_string))
+        .collect())
+}
+
+/// Downloads `model` to `server`, telling `progress` each status Ollama
+/// reports, such as "pulling manifest" or "downloading 42%".
+pub async fn pull_model(client: &Client, server: &str, model: &str, mut progress: impl FnMut(&str)) -> Result<()> {
+    let mut response = client.post(format!("{}/api/pull", server))
+        .json(&serde_json::json!({ "model": model, "stream": true }))
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        let error = response.text().await.unwrap_or_default();
+        bail!("Pulling {} failed: {}", model, error.trim());
+    }
+    let mut lines = crate::llm_stream::LineBuffer::default();
+    let mut report = |line: &str| -> Result<()> {
+        let Ok(event) = serde_json::from_str::<Value>(line) else { return Ok(()) };
+        if let Some(error) = event["error"].as_str() {
+            bail!("Pulling {} failed: {}", model, error);
+        }
+        let status = event["status"].as_str().unwrap_or_default();
+        match (event["completed"].as_u64(), event["total"].as_u64()) {
+            (Some(completed), Some(total)) if total > 0 => progress(&format!("{} {}%", status, completed * 100 / total)),
+            _ => progress(status),
+        }
+        Ok(())
+    };
+    while let Some(chunk) = response.chunk().await? {
+        for line in lines.push(&chunk) {
+            report(&line)?;
+        }
+    }
+    if let Some(line) = lines.finish() {
+        report(&line)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,213 @@
+//! Ownership of requirement files from a CODEOWNERS file, so batch findings
+//! can be broken down by the team that has to fix them.
+
+use anyhow::Result;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::analyzer::{rule_description, Ambiguity, AmbiguitySeverity};
+
+/// Where GitHub and GitLab look for CODEOWNERS, relative to the repository.
+const CODEOWNERS_LOCATIONS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS", ".gitlab/CODEOWNERS"];
+
+/// Owner of files no CODEOWNERS rule matches.
+pub const UNOWNED: &str = "(unowned)";
+
+/// Name of the by-owner report written next to a batch's reports.
+pub const OWNERSHIP_REPORT_NAME: &str = "findings-by-owner.md";
+
+struct OwnershipRule {
+    pattern: Regex,
+    owners: Vec<String>,
+}
+
+pub struct CodeOwners {
+    /// Directory the patterns are relative to
+    root: PathBuf,
+    rules: Vec<OwnershipRule>,
+}
+
+/// The gitignore-style CODEOWNERS `pattern` as a regex over paths relative to the root.
+pub(crate) fn pattern_regex(pattern: &str) -> Result<Regex> {
+    let dir_only = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    // Patterns with a slash before the end are relative to the root, others match at any depth
+    let anchored = trimmed.contains('/');
+    let trimmed = trimmed.trim_start_matches('/');
+
+    let mut regex = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+    let mut rest = trimmed;
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix("**/") {
+            regex.push_str("(?:.*/)?");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("**") {
+            regex.push_str(".*");
+            rest = after;
+        } else {
+            match c {
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                c => regex.push_str(&regex::escape(&c.to_string())),
+            }
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    // A pattern naming a directory owns everything below it
+    regex.push_str(if dir_only { "/.*$" } else { "(?:/.*)?$" });
+    Ok(Regex::new(&regex)?)
+}
+
+impl CodeOwners {
+    /// Parses CODEOWNERS `content` whose patterns are relative to `root`.
+    /// GitLab `[Section]` headers are skipped; their rules still apply.
+    pub fn parse(content: &str, root: PathBuf) -> Result<Self> {
+        let mut rules = Vec::new();
+        for line in content.lines().map(|l| l.trim()) {
+            if line.is_empty() || line.starts_with('#') || line.starts_with('[') || line.starts_with("^[") {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let Some(pattern) = parts.next() else { continue };
+            let owners = parts.take_while(|p| !p.starts_with('#')).map(|p| p.to_string()).collect();
+            rules.push(OwnershipRule {
+                pattern: pattern_regex(pattern).map_err(|e| anyhow::anyhow!("Invalid CODEOWNERS pattern '{}': {}", pattern, e))?,
+                owners,
+            });
+        }
+        Ok(Self { root, rules })
+    }
+
+    /// Ownership for files under `dir`: `owners_file` when given, with
+    /// patterns relative to the enclosing git repository (or `dir` outside
+    /// of one); otherwise the CODEOWNERS of the nearest enclosing repository
+    /// that has one. `None` when there is no CODEOWNERS.
+    pub fn load(dir: &Path, owners_file: Option<&Path>) -> Result<Option<Self>> {
+        let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        if let Some(owners_file) = owners_file {
+            let content = std::fs::read_to_string(owners_file)
+                .map_err(|e| anyhow::anyhow!("Could not read owners file {}: {}", owners_file.display(), e))?;
+            let root = dir.ancestors().find(|a| a.join(".git").exists()).unwrap_or(&dir).to_path_buf();
+            return Self::parse(&content, root).map(Some);
+        }
+
+        for ancestor in dir.ancestors() {
+            for location in CODEOWNERS_LOCATIONS {
+                let path = ancestor.join(location);
+                if path.is_file() {
+                    return Self::parse(&std::fs::read_to_string(&path)?, ancestor.to_path_buf()).map(Some);
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Owners of `path`, absolute or relative to the root. The last matching
+    /// rule wins, as on GitHub; a rule without owners makes the file unowned.
+    pub fn owners_of(&self, path: &Path) -> Vec<String> {
+        let absolute = if path.is_absolute() { path.to_path_buf() } else { self.root.join(path) };
+        let absolute = absolute.canonicalize().unwrap_or(absolute);
+        let relative = absolute.strip_prefix(&self.root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+
+        self.rules.iter().rev()
+            .find(|rule| rule.pattern.is_match(&relative))
+            .map(|rule| rule.owners.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// The findings of the files one owner is responsible for.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OwnerFindings {
+    pub owner: String,
+    pub files: usize,
+    pub findings: usize,
+    /// Critical and high severity findings
+    pub high_severity: usize,
+    /// Finding counts by rule id
+    pub rules: BTreeMap<String, usize>,
+    /// Finding counts by file
+    pub file_findings: BTreeMap<String, usize>,
+}
+
+impl OwnerFindings {
+    /// Rule ids with their counts, most frequent first.
+    pub fn top_rules(&self, limit: usize) -> Vec<(&str, usize)> {
+        let mut rules: Vec<(&str, usize)> = self.rules.iter().map(|(rule, count)| (rule.as_str(), *count)).collect();
+        rules.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        rules.truncate(limit);
+        rules
+    }
+}
+
+/// Batch findings attributed to the owners of their files.
+#[derive(Debug, Clone, Default)]
+pub struct OwnershipReport {
+    owners: BTreeMap<String, OwnerFindings>,
+}
+
+impl OwnershipReport {
+    /// Adds a file's findings to each of its owners; shared files count for every owner.
+    pub fn add(&mut self, owners: &[String], file: &str, findings: &[Ambiguity]) {
+        let unowned = [UNOWNED.to_string()];
+        let owners = if owners.is_empty() { &unowned[..] } else { owners };
+        for owner in owners {
+            let entry = self.owners.entry(owner.clone()).or_insert_with(|| OwnerFindings {
+                owner: owner.clone(),
+                ..Default::default()
+            });
+            entry.files += 1;
+            entry.findings += findings.len();
+            entry.high_severity += findings.iter().filter(|f| f.severity >= AmbiguitySeverity::High).count();
+            entry.file_findings.insert(file.to_string(), findings.len());
+            for finding in findings {
+                *entry.rules.entry(finding.rule_id.clone()).or_default() += 1;
+            }
+        }
+    }
+
+    /// Owners with the most findings first.
+    pub fn owners(&self) -> Vec<&OwnerFindings> {
+        let mut owners: Vec<&OwnerFindings> = self.owners.values().collect();
+        owners.sort_by(|a, b| b.findings.cmp(&a.findings).then_with(|| a.owner.cmp(&b.owner)));
+        owners
+    }
+}
+
+/// "Top issues by team" report of a batch.
+pub fn format_ownership_markdown(source: &Path, report: &OwnershipReport) -> String {
+    let owners = report.owners();
+    let mut output = String::from("# 👥 Findings by Owner\n\n");
+    output.push_str(&format!("**Source:** `{}`\n\n", source.display()));
+
+    output.push_str("| Owner | Files | Findings | High/Critical | Top issues |\n|---|---:|---:|---:|---|\n");
+    for owner in &owners {
+        let top: Vec<String> = owner.top_rules(3).iter().map(|(rule, count)| format!("{} ({})", rule, count)).collect();
+        output.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            owner.owner, owner.files, owner.findings, owner.high_severity,
+            if top.is_empty() { "—".to_string() } else { top.join(", ") }
+        ));
+    }
+
+    for owner in owners.iter().filter(|o| o.findings > 0) {
+        output.push_str(&format!("\n## {}\n\n", owner.owner));
+        output.push_str("**Top issues:**\n");
+        for (rule, count) in owner.top_rules(5) {
+            output.push_str(&format!("- `{}` × {} - {}\n", rule, count, rule_description(rule)));
+        }
+        let mut files: Vec<(&String, &usize)> = owner.file_findings.iter().filter(|(_, count)| **count > 0).collect();
+        files.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        output.push_str("\n**Files with the most findings:**\n");
+        for (file, count) in files.into_iter().take(5) {
+            output.push_str(&format!("- `{}` - {}\n", file, count));
+        }
+    }
+    output
+}
@@ -0,0 +1,104 @@
+//! Paths as people read them, on every platform: without the `\\?\` prefix
+//! `canonicalize` puts in front of paths on Windows, with `~` expanded in the
+//! configuration, and relative with `/` separators in reports that are
+//! shared across machines (`--paths-relative`).
+
+use anyhow::Result;
+use std::path::{Component, Path, PathBuf};
+
+/// `path` without a Windows verbatim prefix: `\\?\C:\docs` is `C:\docs` and
+/// `\\?\UNC\server\share` is `\\server\share`. Other paths are unchanged.
+pub fn simplify(path: &Path) -> PathBuf {
+    let text = path.to_string_lossy();
+    if let Some(share) = text.strip_prefix(r"\\?\UNC\") {
+        return PathBuf::from(format!(r"\\{}", share));
+    }
+    match text.strip_prefix(r"\\?\") {
+        // Only drive paths; other verbatim paths mean nothing without the prefix
+        Some(rest) if rest.as_bytes().get(1) == Some(&b':') && rest.as_bytes()[0].is_ascii_alphabetic() => PathBuf::from(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// The absolute path of an existing `path`, for showing where something was
+/// saved; `path` itself when it cannot be resolved.
+pub fn absolute(path: impl AsRef<Path>) -> PathBuf {
+    let path = path.as_ref();
+    std::fs::canonicalize(path).map(|resolved| simplify(&resolved)).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// `~/.prism`, where the configuration, cache and history are kept.
+pub fn prism_dir() -> Result<PathBuf> {
+    let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
+    Ok(home.join(".prism"))
+}
+
+/// A configured path with a leading `~` replaced by the home directory, so
+/// `~/docs/owners.yml` works in `config.yml` on every platform.
+pub fn expand_home(path: &Path) -> PathBuf {
+    let text = path.to_string_lossy();
+    let rest = match text.strip_prefix('~') {
+        Some("") => "",
+        Some(rest) if rest.starts_with(['/', '\\']) => &rest[1..],
+        _ => return path.to_path_buf(),
+    };
+    match dirs::home_dir() {
+        Some(home) if rest.is_empty() => home,
+        Some(home) => home.join(rest),
+        None => path.to_path_buf(),
+    }
+}
+
+/// `path` relative to the directory `base` with `/` separators, stepping up
+/// with `..` where needed. Both are taken as they are, without looking at the
+/// file system; a path on another drive stays absolute.
+pub fn relative_to(path: &Path, base: &Path) -> String {
+    let path = normalize(path);
+    let base = normalize(base);
+    let common = path.components().zip(base.components()).take_while(|(a, b)| a == b).count();
+    if common == 0 && path.has_root() {
+        return slashed(&path);
+    }
+    let mut relative = PathBuf::new();
+    for _ in base.components().skip(common) {
+        relative.push("..");
+    }
+    for component in path.components().skip(common) {
+        relative.push(component);
+    }
+    match slashed(&relative) {
+        empty if empty.is_empty() => ".".to_string(),
+        relative => relative,
+    }
+}
+
+/// How `--paths-relative` names `path` in a report: relative to the current
+/// directory with `/` separators, the same on every machine and platform.
+pub fn portable(path: &Path) -> String {
+    let Ok(current_dir) = std::env::current_dir() else {
+        return slashed(path);
+    };
+    let absolute_path = if path.is_absolute() { path.to_path_buf() } else { current_dir.join(path) };
+    // Resolved when they exist, so symbolic links and `\\?\` prefixes compare alike
+    relative_to(&absolute(&absolute_path), &absolute(&current_dir))
+}
+
+/// `path` without `.` and with `..` applied to the components before it.
+fn normalize(path: &Path) -> PathBuf {
+    let path = simplify(path);
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if matches!(normalized.components().next_back(), Some(Component::Normal(_))) => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+fn slashed(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
@@ -0,0 +1,463 @@
+//! PDF export of the analysis report, for review meetings and audits: the
+//! Markdown report laid out on A4 pages in the standard PDF fonts, so no
+//! font files or external renderer are needed. The PDF is uncompressed
+//! 7-bit text, which lets it go through the same paths as the other reports.
+
+use crate::analyzer::AnalysisResult;
+use crate::formatters::format_as_markdown;
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+const TOP: f32 = PAGE_HEIGHT - 64.0;
+const BOTTOM: f32 = 64.0;
+const TEXT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+const MONO_SIZE: f32 = 8.0;
+
+/// Helvetica widths of the printable ASCII characters, in 1/1000 of the font size.
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Font {
+    Regular,
+    Bold,
+    Italic,
+    Mono,
+}
+
+impl Font {
+    const ALL: [Font; 4] = [Font::Regular, Font::Bold, Font::Italic, Font::Mono];
+
+    fn resource(self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+            Font::Italic => "F3",
+            Font::Mono => "F4",
+        }
+    }
+
+    fn base_font(self) -> &'static str {
+        match self {
+            Font::Regular => "Helvetica",
+            Font::Bold => "Helvetica-Bold",
+            Font::Italic => "Helvetica-Oblique",
+            Font::Mono => "Courier",
+        }
+    }
+
+    /// Width of `text` at `size` points. Bold runs are estimated a little
+    /// wider than regular ones.
+    fn text_width(self, text: &str, size: f32) -> f32 {
+        let units: f32 = text.chars()
+            .map(|c| match (self, c) {
+                (Font::Mono, _) => 600.0,
+                (_, ' '..='~') => HELVETICA_WIDTHS[c as usize - 32] as f32,
+                (_, '—') => 1000.0,
+                _ => 556.0,
+            })
+            .sum();
+        let bold = if self == Font::Bold { 1.08 } else { 1.0 };
+        units * bold * size / 1000.0
+    }
+}
+
+/// What is drawn on a page.
+enum Mark {
+    Text { font: Font, size: f32, x: f32, y: f32, text: String },
+    Rule { y: f32 },
+}
+
+/// Places text top to bottom, starting a new page when one is full.
+struct Layout {
+    pages: Vec<Vec<Mark>>,
+    y: f32,
+}
+
+impl Layout {
+    fn new() -> Self {
+        Self { pages: vec![Vec::new()], y: TOP }
+    }
+
+    /// Vertical space, left out at the top of a page.
+    fn space(&mut self, points: f32) {
+        if self.y < TOP {
+            self.y -= points;
+        }
+    }
+
+    /// Starts a new page unless `points` still fit on this one.
+    fn keep(&mut self, points: f32) {
+        if self.y - points < BOTTOM {
+            self.pages.push(Vec::new());
+            self.y = TOP;
+        }
+    }
+
+    /// Moves down a line of `leading`, to a new page if it does not fit.
+    fn next_line(&mut self, leading: f32) -> f32 {
+        self.keep(leading);
+        self.y -= leading;
+        self.y
+    }
+
+    fn mark(&mut self, mark: Mark) {
+        self.pages.last_mut().expect("a layout has a page").push(mark);
+    }
+
+    /// `text` wrapped to the text width from `indent`, with `marker` (a
+    /// bullet) hanging before the first line.
+    fn paragraph(&mut self, font: Font, size: f32, indent: f32, marker: Option<&str>, text: &str) {
+        let leading = size * 1.3;
+        for (i, line) in wrap(text, font, size, TEXT_WIDTH - indent).into_iter().enumerate() {
+            let y = self.next_line(leading);
+            if let (0, Some(marker)) = (i, marker) {
+                self.mark(Mark::Text { font: Font::Regular, size, x: MARGIN + indent - 10.0, y, text: marker.to_string() });
+            }
+            self.mark(Mark::Text { font, size, x: MARGIN + indent, y, text: line });
+        }
+    }
+
+    /// A heading, kept on a page with the first lines below it.
+    fn heading(&mut self, size: f32, text: &str, rule: bool) {
+        self.space(size * 0.8);
+        self.keep(size * 1.3 + 40.0);
+        self.paragraph(Font::Bold, size, 0.0, None, text);
+        if rule {
+            let y = self.y - 4.0;
+            self.mark(Mark::Rule { y });
+            self.y -= 6.0;
+        }
+        self.space(2.0);
+    }
+
+    /// A line of a code block, broken where it is wider than the page.
+    fn code(&mut self, line: &str) {
+        let columns = (TEXT_WIDTH / Font::Mono.text_width(" ", MONO_SIZE)) as usize;
+        let chars: Vec<char> = line.chars().collect();
+        for chunk in chars.chunks(columns.max(1)) {
+            let y = self.next_line(MONO_SIZE * 1.35);
+            self.mark(Mark::Text { font: Font::Mono, size: MONO_SIZE, x: MARGIN, y, text: chunk.iter().collect() });
+        }
+        if chars.is_empty() {
+            self.next_line(MONO_SIZE * 1.35);
+        }
+    }
+
+    /// A Markdown table in aligned monospace columns, long cells wrapped
+    /// inside their column.
+    fn table(&mut self, rows: &[&str]) {
+        let rows: Vec<Vec<String>> = rows.iter()
+            .map(|row| row.trim().trim_matches('|').split('|').map(|cell| inline_text(cell.trim())).collect::<Vec<_>>())
+            .filter(|cells| !cells.iter().all(|cell| !cell.is_empty() && cell.chars().all(|c| matches!(c, '-' | ':'))))
+            .collect();
+        let count = rows.iter().map(Vec::len).max().unwrap_or(0);
+        if count == 0 {
+            return;
+        }
+        let mut widths: Vec<usize> = (0..count)
+            .map(|column| rows.iter().filter_map(|row| row.get(column)).map(|cell| cell.chars().count()).max().unwrap_or(0).max(1))
+            .collect();
+        let columns = (TEXT_WIDTH / Font::Mono.text_width(" ", MONO_SIZE)) as usize;
+        let available = columns.saturating_sub(2 * (count - 1));
+        while widths.iter().sum::<usize>() > available {
+            let widest = (0..count).max_by_key(|&column| widths[column]).unwrap_or(0);
+            if widths[widest] <= 8 {
+                break;
+            }
+            widths[widest] -= 1;
+        }
+
+        self.space(3.0);
+        for (i, row) in rows.iter().enumerate() {
+            let cells: Vec<Vec<String>> = (0..count)
+                .map(|column| wrap_columns(row.get(column).map(String::as_str).unwrap_or(""), widths[column]))
+                .collect();
+            let height = cells.iter().map(Vec::len).max().unwrap_or(1);
+            for line in 0..height {
+                let text = (0..count)
+                    .map(|column| format!("{:<width$}", cells[column].get(line).map(String::as_str).unwrap_or(""), width = widths[column]))
+                    .collect::<Vec<_>>()
+                    .join("  ");
+                self.code(text.trim_end());
+            }
+            if i == 0 && rows.len() > 1 {
+                let rule = widths.iter().map(|width| "-".repeat(*width)).collect::<Vec<_>>().join("  ");
+                self.code(&rule);
+            }
+        }
+        self.space(3.0);
+    }
+}
+
+/// The analysis report of `result` as a PDF document.
+pub fn format_as_pdf(result: &AnalysisResult, input_text: &str, source_name: Option<&str>) -> String {
+    markdown_to_pdf(&format_as_markdown(result, input_text), &report_title(source_name))
+}
+
+/// The document title of the analysis report of `source_name`.
+pub fn report_title(source_name: Option<&str>) -> String {
+    match source_name {
+        Some(source) => format!("PRISM Requirement Analysis - {}", source),
+        None => "PRISM Requirement Analysis".to_string(),
+    }
+}
+
+/// Lays out a Markdown report as a PDF titled `title`: headings, paragraphs,
+/// quotes, nested lists, tables and code blocks. Characters outside the
+/// Windows-1252 set of the standard fonts, such as emoji, are left out.
+pub fn markdown_to_pdf(markdown: &str, title: &str) -> String {
+    let mut layout = Layout::new();
+    let mut lines = markdown.lines().peekable();
+    let mut in_code = false;
+    while let Some(line) = lines.next() {
+        let line = line.trim_end();
+        let content = line.trim_start();
+        if content.starts_with("```") {
+            in_code = !in_code;
+            layout.space(4.0);
+            continue;
+        }
+        if in_code {
+            layout.code(&encodable(line));
+            continue;
+        }
+        if content.starts_with('|') {
+            let mut rows = vec![line];
+            while let Some(row) = lines.next_if(|row| row.trim_start().starts_with('|')) {
+                rows.push(row);
+            }
+            layout.table(&rows);
+            continue;
+        }
+
+        let level = (line.len() - content.len()) / 2;
+        if let Some(heading) = content.strip_prefix("# ") {
+            layout.heading(18.0, &inline_text(heading), true);
+        } else if let Some(heading) = content.strip_prefix("## ") {
+            layout.heading(14.0, &inline_text(heading), true);
+        } else if let Some(heading) = content.strip_prefix("### ").or_else(|| content.strip_prefix("#### ")) {
+            layout.heading(11.5, &inline_text(heading), false);
+        } else if let Some(quote) = content.strip_prefix('>') {
+            layout.paragraph(Font::Italic, 10.0, 12.0, None, &inline_text(quote));
+        } else if let Some(item) = content.strip_prefix("- ").or_else(|| content.strip_prefix("* ")) {
+            layout.paragraph(Font::Regular, 10.0, 14.0 + 14.0 * level as f32, Some("\u{2022}"), &inline_text(item));
+        } else if content.is_empty() || content.chars().all(|c| c == '-') {
+            layout.space(5.0);
+        } else {
+            layout.paragraph(Font::Regular, 10.0, 14.0 * level as f32, None, &inline_text(content));
+        }
+    }
+    write_pdf(&layout.pages, title)
+}
+
+/// Markdown inline text as it is printed: no emphasis markers, code ticks
+/// or link targets, spaces collapsed.
+fn inline_text(text: &str) -> String {
+    let mut plain = String::new();
+    let mut rest = text;
+    // [label](target) keeps its label
+    while let Some(open) = rest.find('[') {
+        let link = rest[open..].find("](").and_then(|middle| rest[open + middle..].find(')').map(|close| (middle, middle + close)));
+        match link {
+            Some((middle, close)) => {
+                plain.push_str(&rest[..open]);
+                plain.push_str(&rest[open + 1..open + middle]);
+                rest = &rest[open + close + 1..];
+            }
+            None => break,
+        }
+    }
+    plain.push_str(rest);
+
+    let plain = encodable(&plain.replace("**", "").replace('`', ""));
+    let plain = plain.split_whitespace().collect::<Vec<_>>().join(" ");
+    match plain.strip_prefix('*').and_then(|p| p.strip_suffix('*')) {
+        Some(italic) if !italic.is_empty() => italic.to_string(),
+        _ => plain,
+    }
+}
+
+/// `text` with the characters the standard fonts cannot show replaced or
+/// left out.
+fn encodable(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\t' => encoded.push_str("    "),
+            '→' => encoded.push_str("->"),
+            '←' => encoded.push_str("<-"),
+            '≥' => encoded.push_str(">="),
+            '≤' => encoded.push_str("<="),
+            '✓' | '✔' | '✅' => encoded.push_str("OK"),
+            '❌' | '✗' => encoded.push('X'),
+            _ if win_ansi(c).is_some() => encoded.push(c),
+            _ => {}
+        }
+    }
+    encoded
+}
+
+/// The Windows-1252 code of `c`, the encoding of the standard fonts.
+fn win_ansi(c: char) -> Option<u8> {
+    Some(match c {
+        ' '..='~' | '\u{a0}'..='\u{ff}' => c as u8,
+        '€' => 0x80,
+        '‚' => 0x82,
+        'ƒ' => 0x83,
+        '„' => 0x84,
+        '…' => 0x85,
+        '†' => 0x86,
+        '‡' => 0x87,
+        'ˆ' => 0x88,
+        '‰' => 0x89,
+        'Š' => 0x8a,
+        '‹' => 0x8b,
+        'Œ' => 0x8c,
+        'Ž' => 0x8e,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        '˜' => 0x98,
+        '™' => 0x99,
+        'š' => 0x9a,
+        '›' => 0x9b,
+        'œ' => 0x9c,
+        'ž' => 0x9e,
+        'Ÿ' => 0x9f,
+        _ => return None,
+    })
+}
+
+/// Words of `text` on lines no wider than `width` points; a word wider than
+/// a line is broken.
+fn wrap(text: &str, font: Font, size: f32, width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+        if font.text_width(&candidate, size) <= width {
+            line = candidate;
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+        for c in word.chars() {
+            if !line.is_empty() && font.text_width(&format!("{}{}", line, c), size) > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push(c);
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Words of `text` on lines of at most `width` characters.
+fn wrap_columns(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        if !line.is_empty() && line.chars().count() + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        while word.len() > width {
+            lines.push(word.drain(..width).collect());
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.extend(word);
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// `text` as a PDF string literal in Windows-1252, non-ASCII characters as
+/// octal escapes.
+fn pdf_string(text: &str) -> String {
+    let mut literal = String::from("(");
+    for byte in text.chars().filter_map(win_ansi) {
+        match byte {
+            b'(' | b')' | b'\\' => {
+                literal.push('\\');
+                literal.push(byte as char);
+            }
+            b' '..=b'~' => literal.push(byte as char),
+            _ => literal.push_str(&format!("\\{:03o}", byte)),
+        }
+    }
+    literal.push(')');
+    literal
+}
+
+fn text_operator(font: Font, size: f32, x: f32, y: f32, text: &str) -> String {
+    format!("BT /{} {:.1} Tf {:.2} {:.2} Td {} Tj ET\n", font.resource(), size, x, y, pdf_string(text))
+}
+
+/// The PDF file: catalog, page tree, fonts and document info, then a page
+/// and its content stream for each page, with a footer naming the report
+/// and the page.
+fn write_pdf(pages: &[Vec<Mark>], title: &str) -> String {
+    const FIRST_PAGE: usize = 4 + Font::ALL.len();
+    let kids: Vec<String> = (0..pages.len()).map(|i| format!("{} 0 R", FIRST_PAGE + 2 * i)).collect();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()),
+        format!("<< /Title {} /Producer (PRISM) /Creator (PRISM) >>", pdf_string(title)),
+    ];
+    for font in Font::ALL {
+        objects.push(format!("<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>", font.base_font()));
+    }
+    let fonts: Vec<String> = Font::ALL.iter().enumerate().map(|(i, font)| format!("/{} {} 0 R", font.resource(), 4 + i)).collect();
+
+    for (i, marks) in pages.iter().enumerate() {
+        let mut content = String::new();
+        for mark in marks {
+            match mark {
+                Mark::Text { font, size, x, y, text } => content.push_str(&text_operator(*font, *size, *x, *y, text)),
+                Mark::Rule { y } => content.push_str(&format!("0.6 G 0.5 w {:.2} {:.2} m {:.2} {:.2} l S 0 G\n", MARGIN, y, PAGE_WIDTH - MARGIN, y)),
+            }
+        }
+        let page_label = format!("Page {} of {}", i + 1, pages.len());
+        content.push_str(&text_operator(Font::Regular, 8.0, MARGIN, 36.0, &encodable(title)));
+        content.push_str(&text_operator(Font::Regular, 8.0, PAGE_WIDTH - MARGIN - Font::Regular.text_width(&page_label, 8.0), 36.0, &page_label));
+
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << {} >> >> /Contents {} 0 R >>",
+            PAGE_WIDTH, PAGE_HEIGHT, fonts.join(" "), FIRST_PAGE + 2 * i + 1,
+        ));
+        objects.push(format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content));
+    }
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, object));
+    }
+    let xref = pdf.len();
+    pdf.push_str(&format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1));
+    for offset in offsets {
+        pdf.push_str(&format!("{:010} 00000 n \n", offset));
+    }
+    pdf.push_str(&format!("trailer\n<< /Size {} /Root 1 0 R /Info 3 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref));
+    pdf
+}
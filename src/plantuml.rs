@@ -0,0 +1,122 @@
+//! Renders PlantUML diagrams to SVG or PNG, through a PlantUML server or a
+//! local `plantuml.jar`, so saved UML artifacts can be viewed directly.
+
+use anyhow::Result;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+
+use crate::config::PlantUmlConfig;
+
+/// PlantUML's variant of base64, used to put diagrams in server URLs.
+const PLANTUML_ALPHABET: &[u8; 64] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz-_";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    Svg,
+    Png,
+}
+
+impl ImageFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Svg => "svg",
+            ImageFormat::Png => "png",
+        }
+    }
+}
+
+/// Where diagrams are rendered.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlantUmlRenderer {
+    Server(String),
+    Jar(PathBuf),
+}
+
+impl PlantUmlRenderer {
+    /// The configured renderer; a local jar is preferred, since it keeps the
+    /// requirements on this machine. `None` when neither is set.
+    pub fn from_config(config: &PlantUmlConfig) -> Option<Self> {
+        if let Some(jar) = &config.jar {
+            return Some(PlantUmlRenderer::Jar(jar.clone()));
+        }
+        config.server_url.as_ref()
+            .filter(|url| !url.trim().is_empty())
+            .map(|url| PlantUmlRenderer::Server(url.trim_end_matches('/').to_string()))
+    }
+
+    pub async fn render(&self, source: &str, format: ImageFormat) -> Result<Vec<u8>> {
+        match self {
+            PlantUmlRenderer::Server(url) => render_on_server(url, source, format).await,
+            PlantUmlRenderer::Jar(jar) => render_with_jar(jar, source, format).await,
+        }
+    }
+}
+
+/// URL path segment of `source` for a PlantUML server: deflated, then
+/// base64-encoded with PlantUML's alphabet.
+pub fn encode_plantuml(source: &str) -> String {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    // Writing to a Vec can't fail
+    let _ = encoder.write_all(source.as_bytes());
+    let compressed = encoder.finish().unwrap_or_default();
+
+    let mut encoded = String::with_capacity(compressed.len() * 4 / 3 + 4);
+    for chunk in compressed.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let indices = [
+            bytes[0] >> 2,
+            ((bytes[0] & 0x3) << 4) | (bytes[1] >> 4),
+            ((bytes[1] & 0xF) << 2) | (bytes[2] >> 6),
+            bytes[2] & 0x3F,
+        ];
+        for index in indices {
+            encoded.push(PLANTUML_ALPHABET[index as usize] as char);
+        }
+    }
+    encoded
+}
+
+async fn render_on_server(url: &str, source: &str, format: ImageFormat) -> Result<Vec<u8>> {
+    let request_url = format!("{}/{}/{}", url, format.extension(), encode_plantuml(source));
+    let response = Client::new().get(&request_url).send().await
+        .map_err(|e| anyhow::anyhow!("Could not reach the PlantUML server at {}: {}", url, e))?;
+    // The server still answers with an image for diagrams with syntax errors, but flags them
+    if let Some(error) = response.headers().get("X-PlantUML-Diagram-Error").and_then(|v| v.to_str().ok()) {
+        return Err(anyhow::anyhow!("PlantUML could not render the diagram: {}", error));
+    }
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!("PlantUML server answered {}", response.status()));
+    }
+    Ok(response.bytes().await?.to_vec())
+}
+
+async fn render_with_jar(jar: &std::path::Path, source: &str, format: ImageFormat) -> Result<Vec<u8>> {
+    let mut child = tokio::process::Command::new("java")
+        .arg("-Djava.awt.headless=true")
+        .arg("-jar")
+        .arg(jar)
+        .arg(format!("-t{}", format.extension()))
+        .arg("-pipe")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Could not run java for {}: {}", jar.display(), e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(source.as_bytes()).await?;
+    }
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!("plantuml.jar failed: {}", stderr.trim()));
+    }
+    Ok(output.stdout)
+}
@@ -0,0 +1,289 @@
+//! Monorepo support: finds the projects with requirements in a repository,
+//! by `.prism.yml` markers or by conventional directory names, so each one is
+//! analyzed with its own settings and reported on its own. The nearest
+//! `.prism.yml` above a command's input also layers its settings over the
+//! global configuration.
+
+use anyhow::Result;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::analyzer::AnalysisResult;
+use crate::config::{Config, ProjectConfig};
+use crate::hierarchy::QualityScore;
+use crate::owners::pattern_regex;
+
+/// A directory holding this file is a project root.
+pub const PROJECT_MARKER: &str = ".prism.yml";
+
+/// Directories never searched for projects.
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "vendor", "dist", "build"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Discovery {
+    /// A `.prism.yml` marks the project
+    Marker,
+    /// A conventional requirement directory, e.g. `requirements/`
+    Convention,
+}
+
+#[derive(Debug, Clone)]
+pub struct Project {
+    pub name: String,
+    /// Relative to the repository, empty for the repository itself
+    pub root: PathBuf,
+    pub discovered_by: Discovery,
+    /// The project's `.prism.yml`, default for convention projects
+    pub config: ProjectConfig,
+    /// Where the project's requirements are, relative to the repository
+    pub requirement_dirs: Vec<PathBuf>,
+}
+
+impl Project {
+    /// The global configuration with this project's overrides applied.
+    pub fn effective_config(&self, config: &Config) -> Config {
+        let mut config = config.clone();
+        self.config.apply_to(&mut config);
+        config
+    }
+}
+
+/// Reads a `.prism.yml`; an empty one has the default settings.
+pub fn read_project_config(marker: &Path) -> Result<ProjectConfig> {
+    let content = std::fs::read_to_string(marker)?;
+    if content.trim().is_empty() {
+        return Ok(ProjectConfig::default());
+    }
+    serde_yaml::from_str(&content).map_err(|e| anyhow::anyhow!("Invalid {}: {}", marker.display(), e))
+}
+
+/// The nearest `.prism.yml` in `start`'s directory or above it, with its path.
+pub fn find_project_config(start: &Path) -> Result<Option<(PathBuf, ProjectConfig)>> {
+    let start = start.canonicalize().unwrap_or_else(|_| start.to_path_buf());
+    let first = if start.is_dir() { Some(start.as_path()) } else { start.parent() };
+    for dir in first.into_iter().flat_map(Path::ancestors) {
+        let marker = dir.join(PROJECT_MARKER);
+        if marker.is_file() {
+            return Ok(Some((marker.clone(), read_project_config(&marker)?)));
+        }
+    }
+    Ok(None)
+}
+
+/// The `ignore` globs of the configuration, matched against paths relative
+/// to the directory analyzed.
+#[derive(Debug, Default)]
+pub struct IgnoreGlobs {
+    patterns: Vec<Regex>,
+}
+
+impl IgnoreGlobs {
+    pub fn new(globs: &[String]) -> Result<Self> {
+        let patterns = globs.iter()
+            .map(|glob| pattern_regex(glob).map_err(|e| anyhow::anyhow!("Invalid ignore glob '{}': {}", glob, e)))
+            .collect::<Result<_>>()?;
+        Ok(Self { patterns })
+    }
+
+    pub fn is_ignored(&self, relative: &Path) -> bool {
+        let path = relative.to_string_lossy().replace('\\', "/");
+        self.patterns.iter().any(|p| p.is_match(&path))
+    }
+}
+
+fn is_skipped(entry: &walkdir::DirEntry) -> bool {
+    let name = entry.file_name().to_string_lossy();
+    entry.depth() > 0 && entry.file_type().is_dir() && (name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref()))
+}
+
+fn project_name(repo: &Path, root: &Path) -> String {
+    if root.as_os_str().is_empty() {
+        repo.canonicalize().ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_else(|| ".".to_string())
+    } else {
+        root.to_string_lossy().replace('\\', "/")
+    }
+}
+
+/// Finds the projects below `repo`. Every directory with a `.prism.yml` is a
+/// project and owns its subtree, except for nested `.prism.yml` projects.
+/// Outside of those, a directory with one of the `conventions` directories
+/// (e.g. `requirements` or `docs/requirements`) is a project with those as its
+/// requirements. Projects are sorted by root.
+pub fn discover_projects(repo: &Path, conventions: &[String]) -> Result<Vec<Project>> {
+    if !repo.is_dir() {
+        return Err(anyhow::anyhow!("Directory does not exist: {:?}", repo));
+    }
+
+    let dirs: Vec<PathBuf> = WalkDir::new(repo)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| !is_skipped(e))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_dir())
+        .map(|e| e.path().strip_prefix(repo).unwrap_or(e.path()).to_path_buf())
+        .collect();
+    let existing_conventions = |root: &Path| -> Vec<PathBuf> {
+        conventions.iter()
+            .map(|c| root.join(c))
+            .filter(|dir| repo.join(dir).is_dir())
+            .collect()
+    };
+
+    let mut projects: Vec<Project> = Vec::new();
+    for dir in dirs.iter().filter(|dir| repo.join(dir).join(PROJECT_MARKER).is_file()) {
+        let config = read_project_config(&repo.join(dir).join(PROJECT_MARKER))?;
+        let mut requirement_dirs: Vec<PathBuf> = config.requirements.iter().map(|r| dir.join(r)).collect();
+        if requirement_dirs.is_empty() {
+            requirement_dirs = existing_conventions(dir);
+        }
+        if requirement_dirs.is_empty() {
+            requirement_dirs.push(dir.clone());
+        }
+        projects.push(Project {
+            name: config.name.clone().unwrap_or_else(|| project_name(repo, dir)),
+            root: dir.clone(),
+            discovered_by: Discovery::Marker,
+            config,
+            requirement_dirs,
+        });
+    }
+
+    let claimed = |dir: &Path, projects: &[Project]| {
+        projects.iter().any(|p| {
+            (p.discovered_by == Discovery::Marker && dir.starts_with(&p.root))
+                || p.requirement_dirs.iter().any(|r| dir.starts_with(r))
+        })
+    };
+    for dir in &dirs {
+        // e.g. docs/requirements is already the requirements of docs/'s parent
+        let requirement_dirs: Vec<PathBuf> = existing_conventions(dir).into_iter()
+            .filter(|r| !claimed(r, &projects))
+            .collect();
+        if requirement_dirs.is_empty() || claimed(dir, &projects) {
+            continue;
+        }
+        projects.push(Project {
+            name: project_name(repo, dir),
+            root: dir.clone(),
+            discovered_by: Discovery::Convention,
+            config: ProjectConfig::default(),
+            requirement_dirs,
+        });
+    }
+
+    projects.sort_by(|a, b| a.root.cmp(&b.root));
+    Ok(projects)
+}
+
+/// The requirement files of `projects[index]`, relative to `repo` and sorted.
+/// Files below a nested project's root belong to that project instead.
+pub fn project_files(repo: &Path, projects: &[Project], index: usize, is_supported: impl Fn(&Path) -> bool) -> Vec<PathBuf> {
+    let project = &projects[index];
+    let nested: Vec<&Path> = projects.iter()
+        .filter(|p| p.root != project.root && p.root.starts_with(&project.root))
+        .map(|p| p.root.as_path())
+        .collect();
+
+    let mut files: Vec<PathBuf> = project.requirement_dirs.iter()
+        .flat_map(|dir| WalkDir::new(repo.join(dir)).into_iter().filter_entry(|e| !is_skipped(e)).filter_map(|e| e.ok()))
+        .filter(|e| e.file_type().is_file() && e.file_name() != PROJECT_MARKER)
+        .map(|e| e.path().strip_prefix(repo).unwrap_or(e.path()).to_path_buf())
+        .filter(|path| is_supported(path) && !nested.iter().any(|root| path.starts_with(root)))
+        .collect();
+    files.sort();
+    files.dedup();
+    files
+}
+
+/// Analysis outcome of one project of a monorepo.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectSummary {
+    pub name: String,
+    pub root: String,
+    pub discovered_by: Discovery,
+    pub files: usize,
+    /// Files that could not be read or analyzed
+    pub failed: usize,
+    pub score: QualityScore,
+    /// Finding counts by rule id, most frequent first
+    pub top_rules: Vec<(String, usize)>,
+}
+
+impl ProjectSummary {
+    pub fn new(project: &Project, files: usize, failed: usize, results: &[AnalysisResult]) -> Self {
+        let scores: Vec<QualityScore> = results.iter().map(QualityScore::from_result).collect();
+        let mut rules: BTreeMap<String, usize> = BTreeMap::new();
+        for finding in results.iter().flat_map(|r| &r.ambiguities) {
+            *rules.entry(finding.rule_id.clone()).or_default() += 1;
+        }
+        let mut top_rules: Vec<(String, usize)> = rules.into_iter().collect();
+        top_rules.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top_rules.truncate(3);
+
+        Self {
+            name: project.name.clone(),
+            root: if project.root.as_os_str().is_empty() { ".".to_string() } else { project.root.to_string_lossy().replace('\\', "/") },
+            discovered_by: project.discovered_by,
+            files,
+            failed,
+            score: QualityScore::total(&scores),
+            top_rules,
+        }
+    }
+}
+
+/// Markdown table of the discovered projects, without analysis.
+pub fn format_projects_list(projects: &[Project], file_counts: &[usize]) -> String {
+    let mut output = String::from("| Project | Root | Found by | Requirement dirs | Files |\n|---|---|---|---|---|\n");
+    for (project, files) in projects.iter().zip(file_counts) {
+        let dirs: Vec<String> = project.requirement_dirs.iter().map(|d| format!("`{}`", d.display())).collect();
+        output.push_str(&format!(
+            "| {} | `{}` | {} | {} | {} |\n",
+            project.name,
+            if project.root.as_os_str().is_empty() { ".".to_string() } else { project.root.display().to_string() },
+            match project.discovered_by {
+                Discovery::Marker => PROJECT_MARKER,
+                Discovery::Convention => "convention",
+            },
+            dirs.join(", "),
+            files
+        ));
+    }
+    output
+}
+
+/// Per-project quality scores with a combined summary, weakest project first.
+pub fn format_projects_summary(repo: &Path, summaries: &[ProjectSummary]) -> String {
+    let total = QualityScore::total(summaries.iter().map(|s| &s.score));
+    let files: usize = summaries.iter().map(|s| s.files).sum();
+
+    let mut output = format!("# Requirement Quality by Project: {}\n\n", repo.display());
+    output.push_str(&format!(
+        "**Combined:** {:.0}/100 across {} project(s), {} file(s), {} requirement(s), {} finding(s)\n\n",
+        total.score, summaries.len(), files, total.requirement_count, total.finding_count
+    ));
+
+    let mut sorted: Vec<&ProjectSummary> = summaries.iter().collect();
+    sorted.sort_by(|a, b| a.score.score.total_cmp(&b.score.score).then_with(|| a.name.cmp(&b.name)));
+    output.push_str("| Project | Root | Files | Requirements | Findings | Score | Top issues |\n|---|---|---|---|---|---|---|\n");
+    for summary in sorted {
+        let top: Vec<String> = summary.top_rules.iter().map(|(rule, count)| format!("{} ({})", rule, count)).collect();
+        let files = if summary.failed > 0 { format!("{} ({} failed)", summary.files, summary.failed) } else { summary.files.to_string() };
+        output.push_str(&format!(
+            "| {} | `{}` | {} | {} | {} | {:.0} | {} |\n",
+            summary.name, summary.root, files, summary.score.requirement_count, summary.score.finding_count,
+            summary.score.score, if top.is_empty() { "-".to_string() } else { top.join(", ") }
+        ));
+    }
+    output.push_str(&format!(
+        "| **Combined** | | {} | {} | {} | **{:.0}** | |\n",
+        files, total.requirement_count, total.finding_count, total.score
+    ));
+    output
+}
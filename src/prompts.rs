@@ -0,0 +1,310 @@
+//! The prompts of the AI tasks. Each can be replaced by a `<task>.txt` file
+//! in the `prompts` folder of the template directory (`~/.prism/templates`
+//! unless `prism config --set-template-dir` says otherwise) or of the
+//! project's `.prism` folder. `{name}` placeholders are filled in with the
+//! task's variables; other braces, such as those of a JSON example, are
+//! left alone.
+
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+
+/// Folder of the project's prompt templates, read after the global ones
+pub const PROJECT_PROMPTS_DIR: &str = ".prism/prompts";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PromptTask {
+    Ambiguities,
+    Entities,
+    Completeness,
+    Nfrs,
+    Improvement,
+    Rewrites,
+}
+
+impl PromptTask {
+    pub const ALL: [PromptTask; 6] = [
+        PromptTask::Ambiguities,
+        PromptTask::Entities,
+        PromptTask::Completeness,
+        PromptTask::Nfrs,
+        PromptTask::Improvement,
+        PromptTask::Rewrites,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PromptTask::Ambiguities => "ambiguities",
+            PromptTask::Entities => "entities",
+            PromptTask::Completeness => "completeness",
+            PromptTask::Nfrs => "nfrs",
+            PromptTask::Improvement => "improvement",
+            PromptTask::Rewrites => "rewrites",
+        }
+    }
+
+    pub fn file_name(&self) -> String {
+        format!("{}.txt", self.name())
+    }
+
+    /// The placeholders the task fills in; a template needs the first one.
+    pub fn variables(&self) -> &'static [&'static str] {
+        match self {
+            PromptTask::Ambiguities | PromptTask::Entities => &["text"],
+            PromptTask::Completeness | PromptTask::Nfrs => &["text", "entities", "actors", "actions", "objects"],
+            PromptTask::Improvement => &["text", "findings"],
+            PromptTask::Rewrites => &["sentences"],
+        }
+    }
+
+    pub fn built_in(&self) -> &'static str {
+        match self {
+            PromptTask::Ambiguities => AMBIGUITIES,
+            PromptTask::Entities => ENTITIES,
+            PromptTask::Completeness => COMPLETENESS,
+            PromptTask::Nfrs => NFRS,
+            PromptTask::Improvement => IMPROVEMENT,
+            PromptTask::Rewrites => REWRITES,
+        }
+    }
+}
+
+/// The built-in prompts with those of the template files in their place
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PromptTemplates {
+    custom: BTreeMap<PromptTask, (PathBuf, String)>,
+}
+
+impl PromptTemplates {
+    /// Reads the `<task>.txt` files of `dirs` that exist, a later folder
+    /// replacing the prompts of an earlier one.
+    pub fn load(dirs: &[PathBuf]) -> Result<Self> {
+        let mut templates = Self::default();
+        for dir in dirs {
+            for task in PromptTask::ALL {
+                let path = dir.join(task.file_name());
+                if !path.is_file() {
+                    continue;
+                }
+                let content = std::fs::read_to_string(&path)
+                    .with_context(|| format!("Cannot read prompt template {}", path.display()))?;
+                check_template(task, &content).with_context(|| format!("Invalid prompt template {}", path.display()))?;
+                templates.custom.insert(task, (path, content));
+            }
+        }
+        Ok(templates)
+    }
+
+    pub fn template(&self, task: PromptTask) -> &str {
+        self.custom.get(&task).map(|(_, content)| content.as_str()).unwrap_or(task.built_in())
+    }
+
+    /// The prompt of `task` with its placeholders filled in from `values`.
+    pub fn render(&self, task: PromptTask, values: &[(&str, impl AsRef<str>)]) -> String {
+        render_template(self.template(task), values)
+    }
+
+    /// The task `prompt` was rendered for: every piece of the task's template
+    /// between its placeholders appears in the prompt, in order.
+    pub fn task_of(&self, prompt: &str) -> Option<PromptTask> {
+        PromptTask::ALL.into_iter().find(|task| {
+            let mut rest = prompt;
+            placeholder().split(self.template(*task)).filter(|piece| !piece.trim().is_empty()).all(|piece| {
+                match rest.find(piece) {
+                    Some(at) => {
+                        rest = &rest[at + piece.len()..];
+                        true
+                    }
+                    None => false,
+                }
+            })
+        })
+    }
+
+    /// The tasks with a template file, and the file, in task order
+    pub fn custom(&self) -> impl Iterator<Item = (PromptTask, &Path)> {
+        self.custom.iter().map(|(task, (path, _))| (*task, path.as_path()))
+    }
+
+    /// Changes whenever a template does, for cache keys.
+    pub fn fingerprint(&self) -> String {
+        self.custom.iter().map(|(task, (_, content))| format!("{}:{}\n", task.name(), content)).collect()
+    }
+}
+
+fn placeholder() -> Regex {
+    Regex::new(r"\{([a-z_]+)\}").unwrap()
+}
+
+/// Fails on placeholders the task has no value for and on a missing first one.
+pub fn check_template(task: PromptTask, template: &str) -> Result<()> {
+    let variables = task.variables();
+    for capture in placeholder().captures_iter(template) {
+        if !variables.contains(&&capture[1]) {
+            bail!("{{{}}} is not a variable of the {} prompt; use {}", &capture[1], task.name(), list_variables(variables));
+        }
+    }
+    if !template.contains(&format!("{{{}}}", variables[0])) {
+        bail!("the {} prompt has to contain {{{}}}", task.name(), variables[0]);
+    }
+    Ok(())
+}
+
+fn list_variables(variables: &[&str]) -> String {
+    variables.iter().map(|v| format!("{{{}}}", v)).collect::<Vec<_>>().join(", ")
+}
+
+/// Replaces every `{name}` of `values` in `template` in one pass, so values
+/// that contain braces are left as they are.
+pub fn render_template(template: &str, values: &[(&str, impl AsRef<str>)]) -> String {
+    placeholder().replace_all(template, |caps: &regex::Captures| {
+        values.iter()
+            .find(|(name, _)| *name == &caps[1])
+            .map(|(_, value)| value.as_ref().to_string())
+            .unwrap_or_else(|| caps[0].to_string())
+    }).into_owned()
+}
+
+/// The folders prompt templates are read from, whether or not they exist.
+pub fn prompt_dirs(config: &Config) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Ok(template_dir) = config.get_template_directory() {
+        dirs.push(template_dir.join("prompts"));
+    }
+    dirs.push(PathBuf::from(PROJECT_PROMPTS_DIR));
+    dirs
+}
+
+pub fn load_prompt_templates(config: &Config) -> Result<PromptTemplates> {
+    PromptTemplates::load(&prompt_dirs(config))
+}
+
+/// Writes the built-in prompts to `dir` to start from, keeping files that
+/// exist. Returns the files written.
+pub fn write_built_in_prompts(dir: &Path) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Cannot create {}", dir.display()))?;
+    let mut written = Vec::new();
+    for task in PromptTask::ALL {
+        let path = dir.join(task.file_name());
+        if path.exists() {
+            continue;
+        }
+        std::fs::write(&path, task.built_in()).with_context(|| format!("Cannot write {}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+const AMBIGUITIES: &str = r#"Analyze the following requirement text for ambiguities, vague terms, and unclear specifications.
+Look for terms that lack specific criteria, passive voice that hides responsibility,
+incomplete conditional logic, and any other sources of potential miscommunication.
+Quote each ambiguous phrase exactly as it appears in the text, so it can be found there.
+
+Requirement text:
+{text}
+
+Please provide a JSON response with the following structure:
+{
+    "ambiguities": [
+        {
+            "text": "the ambiguous phrase, quoted word for word",
+            "reason": "why it's ambiguous",
+            "suggestions": ["suggestion 1", "suggestion 2"],
+            "severity": "High|Medium|Low|Critical"
+        }
+    ]
+}
+"#;
+
+const ENTITIES: &str = r#"Extract the key entities from the following requirement text. Identify:
+1. Actors (who performs actions - users, administrators, systems, services)
+2. Actions (what is being done - verbs like create, update, delete, login)
+3. Objects (what is being acted upon - nouns like account, profile, data)
+
+Requirement text:
+{text}
+
+Please provide a JSON response with the following structure:
+{
+    "actors": ["actor1", "actor2"],
+    "actions": ["action1", "action2"],
+    "objects": ["object1", "object2"]
+}
+"#;
+
+const COMPLETENESS: &str = r#"Analyze the following requirement for completeness and identify gaps. Consider missing actors, undefined success criteria, missing non-functional requirements, and other completeness issues.
+
+Requirement: {text}
+
+Identified entities:
+{entities}
+
+Please identify gaps and provide suggestions in the following JSON format:
+{
+    "gaps": [
+        {
+            "category": "category name",
+            "description": "what is missing",
+            "suggestions": ["suggestion 1", "suggestion 2"],
+            "priority": "Critical|High|Medium|Low"
+        }
+    ]
+}
+"#;
+
+const NFRS: &str = r#"Based on the following functional requirement, generate relevant non-functional requirements (NFRs) for performance, security, usability, reliability, scalability, maintainability, compatibility, and accessibility.
+
+Functional Requirement: {text}
+
+Identified entities:
+{entities}
+
+Generate NFRs in the following JSON format:
+{
+    "nfrs": [
+        {
+            "category": "Performance|Security|Usability|Reliability|Scalability|Maintainability|Compatibility|Accessibility",
+            "requirement": "specific NFR statement",
+            "rationale": "why this NFR is needed",
+            "acceptance_criteria": ["criterion 1", "criterion 2"],
+            "priority": "MustHave|ShouldHave|CouldHave|WontHave"
+        }
+    ]
+}
+"#;
+
+const IMPROVEMENT: &str = r#"You are a requirements improvement specialist. Please rewrite the following requirements to fix all identified ambiguities and make them clearer, more specific, and more actionable.
+
+ORIGINAL REQUIREMENTS:
+{text}
+
+IDENTIFIED ISSUES TO FIX:
+{findings}
+
+INSTRUCTIONS:
+1. Rewrite the requirements to address all identified issues
+2. Make vague terms specific and measurable
+3. Replace passive voice with active voice
+4. Add missing details and clarifications
+5. Ensure requirements are testable and implementable
+6. Maintain the original intent and scope
+7. Use clear, professional language
+8. Keep the same overall structure and format
+
+Please provide ONLY the improved requirements text, without explanations or comments.
+"#;
+
+const REWRITES: &str = r#"Rewrite each sentence below so that the marked problem is fixed. Change only what is needed, keep the author's wording otherwise, and use [placeholders] for values you don't know.
+
+{sentences}
+
+Please provide a JSON response with the following structure:
+{
+    "rewrites": [
+        { "index": 0, "proposed": "the rewritten sentence" }
+    ]
+}
+"#;
@@ -0,0 +1,186 @@
+//! Side-by-side comparison of one analysis run with several AI providers
+//! (`prism analyze --compare-providers`, `prism bench-providers`): what
+//! each found, how long it took and what it would cost, to choose a model
+//! before committing budget.
+
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use crate::analyzer::{AnalysisResult, RULE_AI_AMBIGUITY};
+use crate::budget::LlmUsage;
+use crate::locale::report_locale;
+use crate::text::capitalize;
+
+/// The analysis of the text with one provider.
+#[derive(Debug, Clone)]
+pub struct ProviderRun {
+    pub provider: String,
+    pub model: String,
+    /// Analyses the figures come from; with more than one, `elapsed` is
+    /// their median and `usage` that of a single analysis
+    pub runs: usize,
+    pub elapsed: Duration,
+    pub usage: LlmUsage,
+    /// The analysis, or why it failed
+    pub result: Result<AnalysisResult, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderSummary {
+    pub provider: String,
+    pub model: String,
+    pub runs: usize,
+    pub response_ms: u128,
+    pub llm_calls: usize,
+    pub estimated_tokens: u64,
+    pub estimated_cost: f64,
+    /// Why the provider's analysis failed; its findings are then the built-in ones
+    pub error: Option<String>,
+    pub findings: usize,
+    pub ai_findings: usize,
+    pub actors: Vec<String>,
+    pub actions: Vec<String>,
+    pub objects: Vec<String>,
+    /// Findings no other provider reported
+    pub only_findings: Vec<String>,
+    /// Entities no other provider extracted, as "actor: customer"
+    pub only_entities: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderComparison {
+    pub providers: Vec<ProviderSummary>,
+    /// Findings every provider that succeeded reported
+    pub shared_findings: Vec<String>,
+}
+
+/// A `prism bench-providers --models` entry: `claude`, `openai:gpt-4o-mini`
+/// or `ollama:llama3.1:8b`, as provider and model.
+pub fn parse_model_target(target: &str) -> (String, Option<String>) {
+    match target.trim().split_once(':') {
+        Some((provider, model)) if !model.is_empty() => (provider.to_lowercase(), Some(model.to_string())),
+        _ => (target.trim().trim_end_matches(':').to_lowercase(), None),
+    }
+}
+
+/// The middle one of `durations`, the mean of the two middle ones for an
+/// even number of them.
+pub fn median_duration(mut durations: Vec<Duration>) -> Duration {
+    durations.sort();
+    match durations.len() {
+        0 => Duration::ZERO,
+        n if n % 2 == 1 => durations[n / 2],
+        n => (durations[n / 2 - 1] + durations[n / 2]) / 2,
+    }
+}
+
+fn finding_texts(result: &AnalysisResult) -> BTreeSet<String> {
+    result.ambiguities.iter().map(|f| f.text.trim().to_lowercase()).collect()
+}
+
+fn entity_labels(result: &AnalysisResult) -> BTreeSet<String> {
+    let entities = &result.entities;
+    let label = |kind: &str, items: &[String]| items.iter().map(|i| format!("{}: {}", kind, i.to_lowercase())).collect::<Vec<_>>();
+    [label("actor", &entities.actors), label("action", &entities.actions), label("object", &entities.objects)]
+        .into_iter().flatten().collect()
+}
+
+/// Items of `own` that are in none of `others`.
+fn only_in(own: &BTreeSet<String>, others: &[&BTreeSet<String>]) -> Vec<String> {
+    own.iter().filter(|item| others.iter().all(|other| !other.contains(*item))).cloned().collect()
+}
+
+/// Compares the runs. Findings and entities are matched by text without
+/// regard to letter case; only the providers whose analysis succeeded are
+/// compared with each other.
+pub fn compare_runs(runs: &[ProviderRun]) -> ProviderComparison {
+    let succeeded: Vec<(usize, &AnalysisResult)> = runs.iter().enumerate()
+        .filter_map(|(i, run)| run.result.as_ref().ok().filter(|r| !r.ai_failed).map(|r| (i, r)))
+        .collect();
+    let findings: Vec<(usize, BTreeSet<String>)> = succeeded.iter().map(|(i, r)| (*i, finding_texts(r))).collect();
+    let entities: Vec<(usize, BTreeSet<String>)> = succeeded.iter().map(|(i, r)| (*i, entity_labels(r))).collect();
+    let others = |sets: &[(usize, BTreeSet<String>)], index: usize| -> Vec<String> {
+        let Some((_, own)) = sets.iter().find(|(i, _)| *i == index) else {
+            return Vec::new();
+        };
+        let rest: Vec<&BTreeSet<String>> = sets.iter().filter(|(i, _)| *i != index).map(|(_, set)| set).collect();
+        if rest.is_empty() { Vec::new() } else { only_in(own, &rest) }
+    };
+
+    let providers = runs.iter().enumerate().map(|(index, run)| {
+        let result = run.result.as_ref().ok();
+        let error = match &run.result {
+            Err(e) => Some(e.clone()),
+            Ok(r) if r.ai_failed => Some("AI analysis failed, built-in findings only".to_string()),
+            Ok(_) => None,
+        };
+        ProviderSummary {
+            provider: run.provider.clone(),
+            model: run.model.clone(),
+            runs: run.runs,
+            response_ms: run.elapsed.as_millis(),
+            llm_calls: run.usage.calls,
+            estimated_tokens: run.usage.estimated_tokens(),
+            estimated_cost: run.usage.estimated_cost(&run.provider, &run.model),
+            error,
+            findings: result.map_or(0, |r| r.ambiguities.len()),
+            ai_findings: result.map_or(0, |r| r.ambiguities.iter().filter(|f| f.rule_id == RULE_AI_AMBIGUITY).count()),
+            actors: result.map(|r| r.entities.actors.clone()).unwrap_or_default(),
+            actions: result.map(|r| r.entities.actions.clone()).unwrap_or_default(),
+            objects: result.map(|r| r.entities.objects.clone()).unwrap_or_default(),
+            only_findings: others(&findings, index),
+            only_entities: others(&entities, index),
+        }
+    }).collect();
+
+    let shared_findings = match findings.split_first() {
+        Some(((_, first), rest)) if !rest.is_empty() => first.iter()
+            .filter(|text| rest.iter().all(|(_, set)| set.contains(*text)))
+            .cloned()
+            .collect(),
+        _ => Vec::new(),
+    };
+    ProviderComparison { providers, shared_findings }
+}
+
+pub fn format_provider_comparison_markdown(comparison: &ProviderComparison) -> String {
+    let mut output = String::from("# ⚖️ PRISM Provider Comparison\n\n");
+    output.push_str("| Provider | Model | Response time | Findings | AI findings | Actors | Actions | Objects | Est. tokens | Est. cost |\n");
+    output.push_str("|---|---|---:|---:|---:|---:|---:|---:|---:|---:|\n");
+    for summary in &comparison.providers {
+        output.push_str(&format!(
+            "| {} | {} | {}s | {} | {} | {} | {} | {} | {} | ${:.4} |\n",
+            summary.provider, summary.model, report_locale().number(summary.response_ms as f64 / 1000.0, 1), summary.findings, summary.ai_findings,
+            summary.actors.len(), summary.actions.len(), summary.objects.len(), summary.estimated_tokens, summary.estimated_cost
+        ));
+    }
+    if let Some(runs) = comparison.providers.iter().map(|s| s.runs).max().filter(|runs| *runs > 1) {
+        output.push_str(&format!("\nResponse times are the median of {} runs; tokens and cost are those of one run.\n", runs));
+    }
+    for summary in comparison.providers.iter().filter(|s| s.error.is_some()) {
+        output.push_str(&format!("\n⚠️ **{} ({})** failed: {}\n", summary.provider, summary.model, summary.error.as_deref().unwrap_or_default()));
+    }
+
+    output.push_str("\n## 🤝 Found by Every Provider\n\n");
+    if comparison.shared_findings.is_empty() {
+        output.push_str("None.\n");
+    }
+    for text in &comparison.shared_findings {
+        output.push_str(&format!("- \"{}\"\n", text));
+    }
+
+    for summary in comparison.providers.iter().filter(|s| s.error.is_none()) {
+        output.push_str(&format!("\n## 🔎 Only {} ({})\n\n", summary.provider, summary.model));
+        if summary.only_findings.is_empty() && summary.only_entities.is_empty() {
+            output.push_str("Nothing the other providers did not find.\n");
+        }
+        for text in &summary.only_findings {
+            output.push_str(&format!("- Finding: \"{}\"\n", text));
+        }
+        for entity in &summary.only_entities {
+            output.push_str(&format!("- {}\n", capitalize(entity)));
+        }
+    }
+    output
+}
@@ -0,0 +1,43 @@
+//! `analyze --fail-on` and `--min-completeness`: a quality gate for CI. The
+//! report is written as usual, then the run exits with an error when the
+//! requirements do not pass, so a pull request check fails.
+
+use crate::analyzer::{AmbiguitySeverity, AnalysisResult};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct QualityGate {
+    /// Fails on a finding of this severity or above
+    pub fail_on: Option<AmbiguitySeverity>,
+    /// Fails on a completeness score below this percentage
+    pub min_completeness: Option<f32>,
+}
+
+impl QualityGate {
+    pub fn is_enabled(&self) -> bool {
+        self.fail_on.is_some() || self.min_completeness.is_some()
+    }
+
+    /// What in `result` fails the gate; empty when it passes. Findings
+    /// accepted in the baseline are no longer in `result` and do not count.
+    pub fn violations(&self, result: &AnalysisResult) -> Vec<String> {
+        let mut violations = Vec::new();
+        if let Some(fail_on) = self.fail_on {
+            let failing = result.ambiguities.iter().filter(|a| a.severity >= fail_on).count();
+            if failing > 0 {
+                let severity = if fail_on == AmbiguitySeverity::Critical { fail_on.to_string() } else { format!("{} or above", fail_on) };
+                violations.push(format!("{} finding(s) of severity {}", failing, severity));
+            }
+        }
+        if let Some(min_completeness) = self.min_completeness {
+            match &result.completeness_analysis {
+                Some(completeness) if completeness.completeness_score < min_completeness => violations.push(format!(
+                    "completeness {:.0}% below the minimum of {:.0}%",
+                    completeness.completeness_score, min_completeness
+                )),
+                Some(_) => {}
+                None => violations.push("completeness was not analyzed".to_string()),
+            }
+        }
+        violations
+    }
+}
@@ -0,0 +1,269 @@
+//! Redline export of `prism improve --redline`: the improved requirements as
+//! a Word document with tracked changes against the original, so reviewers
+//! can accept or reject each of the AI's edits in Word itself.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::io::{Seek, Write};
+
+use crate::text::escape_xml;
+
+/// Above this many cells of the comparison table, paragraphs that differ are
+/// replaced as a whole instead of word by word.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// A piece of a paragraph and whether the improvement kept, added or removed it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Same(String),
+    Inserted(String),
+    Deleted(String),
+}
+
+/// A paragraph of the redline; `inserted` and `deleted` mark paragraphs the
+/// improvement added or removed as a whole.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RedlineParagraph {
+    pub segments: Vec<Segment>,
+    pub inserted: bool,
+    pub deleted: bool,
+    /// 0-based lines of the paragraph in the original and the improved text
+    pub original_line: Option<usize>,
+    pub improved_line: Option<usize>,
+}
+
+enum Edit<'a> {
+    Same(&'a str),
+    Deleted(&'a str),
+    Inserted(&'a str),
+}
+
+/// The shortest edit turning `old` into `new`, from their longest common
+/// subsequence.
+fn diff<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Edit<'a>> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..].iter().rev().zip(new[prefix..].iter().rev()).take_while(|(a, b)| a == b).count();
+    let (old_middle, new_middle) = (&old[prefix..old.len() - suffix], &new[prefix..new.len() - suffix]);
+
+    let mut edits: Vec<Edit> = old[..prefix].iter().map(|token| Edit::Same(token)).collect();
+    let (n, m) = (old_middle.len(), new_middle.len());
+    if (n + 1) * (m + 1) > MAX_DIFF_CELLS {
+        edits.extend(old_middle.iter().map(|token| Edit::Deleted(token)));
+        edits.extend(new_middle.iter().map(|token| Edit::Inserted(token)));
+    } else {
+        // common[i][j]: length of the common subsequence of old_middle[i..] and new_middle[j..]
+        let mut common = vec![vec![0u32; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                common[i][j] = if old_middle[i] == new_middle[j] {
+                    common[i + 1][j + 1] + 1
+                } else {
+                    common[i + 1][j].max(common[i][j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && old_middle[i] == new_middle[j] {
+                edits.push(Edit::Same(old_middle[i]));
+                i += 1;
+                j += 1;
+            } else if j == m || (i < n && common[i + 1][j] >= common[i][j + 1]) {
+                edits.push(Edit::Deleted(old_middle[i]));
+                i += 1;
+            } else {
+                edits.push(Edit::Inserted(new_middle[j]));
+                j += 1;
+            }
+        }
+    }
+    edits.extend(old[old.len() - suffix..].iter().map(|token| Edit::Same(token)));
+    edits
+}
+
+/// Words with the whitespace after them, which together are the text.
+fn tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut in_space = false;
+    for (i, c) in text.char_indices() {
+        if in_space && !c.is_whitespace() && i > start {
+            tokens.push(&text[start..i]);
+            start = i;
+        }
+        in_space = c.is_whitespace();
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+/// `old` changed into `new` word by word. A single unchanged word between
+/// two changes becomes part of them, so a rewritten phrase reads as one
+/// deletion and one insertion rather than a patchwork.
+fn diff_words(old: &str, new: &str) -> Vec<Segment> {
+    let edits = diff(&tokens(old), &tokens(new));
+    let mut segments = Vec::new();
+    let (mut deleted, mut inserted) = (String::new(), String::new());
+    for (i, edit) in edits.iter().enumerate() {
+        match edit {
+            Edit::Deleted(token) => deleted.push_str(token),
+            Edit::Inserted(token) => inserted.push_str(token),
+            Edit::Same(token) => {
+                let pending = !deleted.is_empty() || !inserted.is_empty();
+                if pending && matches!(edits.get(i + 1), Some(Edit::Deleted(_) | Edit::Inserted(_))) {
+                    deleted.push_str(token);
+                    inserted.push_str(token);
+                    continue;
+                }
+                push_change(&mut segments, &mut deleted, &mut inserted);
+                match segments.last_mut() {
+                    Some(Segment::Same(text)) => text.push_str(token),
+                    _ => segments.push(Segment::Same(token.to_string())),
+                }
+            }
+        }
+    }
+    push_change(&mut segments, &mut deleted, &mut inserted);
+    segments
+}
+
+fn push_change(segments: &mut Vec<Segment>, deleted: &mut String, inserted: &mut String) {
+    if !deleted.is_empty() {
+        segments.push(Segment::Deleted(std::mem::take(deleted)));
+    }
+    if !inserted.is_empty() {
+        segments.push(Segment::Inserted(std::mem::take(inserted)));
+    }
+}
+
+/// The paragraphs of `improved` with the changes from `original` marked:
+/// lines are matched first, and lines replaced by others are compared word
+/// by word.
+pub fn redline(original: &str, improved: &str) -> Vec<RedlineParagraph> {
+    let old: Vec<&str> = original.lines().map(str::trim_end).collect();
+    let new: Vec<&str> = improved.lines().map(str::trim_end).collect();
+    let mut paragraphs = Vec::new();
+    let (mut deleted, mut inserted) = (Vec::new(), Vec::new());
+    let (mut old_line, mut new_line) = (0, 0);
+    for edit in diff(&old, &new) {
+        match edit {
+            Edit::Deleted(line) => {
+                deleted.push((old_line, line));
+                old_line += 1;
+            }
+            Edit::Inserted(line) => {
+                inserted.push((new_line, line));
+                new_line += 1;
+            }
+            Edit::Same(line) => {
+                replace_lines(&mut paragraphs, &mut deleted, &mut inserted);
+                paragraphs.push(RedlineParagraph {
+                    segments: vec![Segment::Same(line.to_string())],
+                    original_line: Some(old_line),
+                    improved_line: Some(new_line),
+                    ..Default::default()
+                });
+                old_line += 1;
+                new_line += 1;
+            }
+        }
+    }
+    replace_lines(&mut paragraphs, &mut deleted, &mut inserted);
+    paragraphs
+}
+
+/// Lines replaced between two unchanged ones: pairs of a removed and an
+/// added line are compared word by word, the rest are whole paragraphs.
+fn replace_lines(paragraphs: &mut Vec<RedlineParagraph>, deleted: &mut Vec<(usize, &str)>, inserted: &mut Vec<(usize, &str)>) {
+    let pairs = deleted.len().min(inserted.len());
+    for ((old_line, old), (new_line, new)) in deleted.iter().zip(inserted.iter()) {
+        paragraphs.push(RedlineParagraph {
+            segments: diff_words(old, new),
+            original_line: Some(*old_line),
+            improved_line: Some(*new_line),
+            ..Default::default()
+        });
+    }
+    for (line, old) in &deleted[pairs..] {
+        paragraphs.push(RedlineParagraph { segments: vec![Segment::Deleted(old.to_string())], deleted: true, original_line: Some(*line), ..Default::default() });
+    }
+    for (line, new) in &inserted[pairs..] {
+        paragraphs.push(RedlineParagraph { segments: vec![Segment::Inserted(new.to_string())], inserted: true, improved_line: Some(*line), ..Default::default() });
+    }
+    deleted.clear();
+    inserted.clear();
+}
+
+const WORD_NAMESPACE: &str = "http://schemas.openxmlformats.org/wordprocessingml/2006/main";
+
+/// `word/document.xml` of the redline, every change attributed to `author`
+/// at `date`.
+pub fn format_redline_document(paragraphs: &[RedlineParagraph], author: &str, date: DateTime<Utc>) -> String {
+    let mut revision = 0;
+    let mut attributes = || {
+        revision += 1;
+        format!("w:id=\"{}\" w:author=\"{}\" w:date=\"{}\"", revision, escape_xml(author), date.format("%Y-%m-%dT%H:%M:%SZ"))
+    };
+    let mut body = String::new();
+    for paragraph in paragraphs {
+        body.push_str("<w:p>");
+        // The paragraph mark itself, so accepting the change adds or removes the whole paragraph
+        if paragraph.inserted {
+            body.push_str(&format!("<w:pPr><w:rPr><w:ins {}/></w:rPr></w:pPr>", attributes()));
+        } else if paragraph.deleted {
+            body.push_str(&format!("<w:pPr><w:rPr><w:del {}/></w:rPr></w:pPr>", attributes()));
+        }
+        for segment in &paragraph.segments {
+            match segment {
+                Segment::Same(text) => body.push_str(&format!("<w:r><w:t xml:space=\"preserve\">{}</w:t></w:r>", escape_xml(text))),
+                Segment::Inserted(text) => body.push_str(&format!(
+                    "<w:ins {}><w:r><w:t xml:space=\"preserve\">{}</w:t></w:r></w:ins>",
+                    attributes(), escape_xml(text)
+                )),
+                Segment::Deleted(text) => body.push_str(&format!(
+                    "<w:del {}><w:r><w:delText xml:space=\"preserve\">{}</w:delText></w:r></w:del>",
+                    attributes(), escape_xml(text)
+                )),
+            }
+        }
+        body.push_str("</w:p>");
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<w:document xmlns:w=\"{}\"><w:body>{}<w:sectPr/></w:body></w:document>",
+        WORD_NAMESPACE, body
+    )
+}
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/><Override PartName="/word/settings.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.settings+xml"/></Types>"#;
+
+const PACKAGE_RELATIONSHIPS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/></Relationships>"#;
+
+const DOCUMENT_RELATIONSHIPS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/settings" Target="settings.xml"/></Relationships>"#;
+
+/// Keeps Track Changes on, so the reviewer's own edits are tracked too.
+const SETTINGS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:settings xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:trackRevisions/></w:settings>"#;
+
+/// Writes the redline of `original` against `improved` as a DOCX.
+pub fn write_redline_docx<W: Write + Seek>(writer: W, original: &str, improved: &str, author: &str, date: DateTime<Utc>) -> Result<()> {
+    let document = format_redline_document(&redline(original, improved), author, date);
+    let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut zip = zip::ZipWriter::new(writer);
+    for (path, content) in [
+        ("[Content_Types].xml", CONTENT_TYPES),
+        ("_rels/.rels", PACKAGE_RELATIONSHIPS),
+        ("word/_rels/document.xml.rels", DOCUMENT_RELATIONSHIPS),
+        ("word/settings.xml", SETTINGS),
+        ("word/document.xml", document.as_str()),
+    ] {
+        zip.start_file(path, options)?;
+        zip.write_all(content.as_bytes())?;
+    }
+    zip.finish()?;
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::analyzer::{AmbiguitySeverity, AnalysisResult, CompletenessAnalysis};
+use crate::config::ReleaseGateConfig;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GateResult {
+    pub name: String,
+    pub actual: String,
+    pub required: String,
+    /// `None` when the gate could not be evaluated and was skipped
+    pub passed: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseReadiness {
+    pub ready: bool,
+    pub gates: Vec<GateResult>,
+    pub open_questions: Vec<String>,
+    pub critical_findings: Vec<String>,
+    pub completeness_gaps: Vec<String>,
+}
+
+/// Questions left open in the text: lines marked TBD/TBC/TODO, "open question"
+/// notes and sentences ending with a question mark.
+pub fn find_open_questions(text: &str) -> Vec<String> {
+    let marker = Regex::new(r"(?i)\b(TBD|TBC|TODO|to be (decided|confirmed|defined)|open question)\b|\?\?").unwrap();
+    let mut questions = Vec::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("===") {
+            continue;
+        }
+        let line = line.trim_start_matches(['-', '*', '+', '#', ' ']);
+        if marker.is_match(line) || line.ends_with('?') {
+            questions.push(line.to_string());
+        }
+    }
+
+    questions
+}
+
+/// Applies the gate criteria to the analysis of the release scope. `coverage`
+/// is the epic coverage percentage, when an epic was given.
+pub fn evaluate_release(
+    result: &AnalysisResult,
+    completeness: &CompletenessAnalysis,
+    coverage: Option<f32>,
+    open_questions: Vec<String>,
+    gate: &ReleaseGateConfig,
+) -> ReleaseReadiness {
+    let critical_findings: Vec<String> = result.ambiguities.iter()
+        .filter(|a| a.severity == AmbiguitySeverity::Critical)
+        .map(|a| format!("{} — {}", a.text, a.reason))
+        .collect();
+
+    let gates = vec![
+        GateResult {
+            name: "Completeness".to_string(),
+            actual: format!("{:.0}%", completeness.completeness_score),
+            required: format!("≥ {:.0}%", gate.min_completeness),
+            passed: Some(completeness.completeness_score >= gate.min_completeness),
+        },
+        GateResult {
+            name: "Traceability coverage".to_string(),
+            actual: coverage.map(|c| format!("{:.0}%", c)).unwrap_or_else(|| "not checked".to_string()),
+            required: format!("≥ {:.0}%", gate.min_coverage),
+            passed: coverage.map(|c| c >= gate.min_coverage),
+        },
+        GateResult {
+            name: "Open clarification questions".to_string(),
+            actual: open_questions.len().to_string(),
+            required: format!("≤ {}", gate.max_open_questions),
+            passed: Some(open_questions.len() <= gate.max_open_questions),
+        },
+        GateResult {
+            name: "Critical findings".to_string(),
+            actual: critical_findings.len().to_string(),
+            required: format!("≤ {}", gate.max_critical_findings),
+            passed: Some(critical_findings.len() <= gate.max_critical_findings),
+        },
+    ];
+
+    ReleaseReadiness {
+        ready: gates.iter().all(|g| g.passed != Some(false)),
+        gates,
+        open_questions,
+        critical_findings,
+        completeness_gaps: completeness.gaps_identified.iter()
+            .map(|gap| format!("{}: {}", gap.category, gap.description))
+            .collect(),
+    }
+}
+
+pub fn format_release_markdown(readiness: &ReleaseReadiness) -> String {
+    let mut output = String::new();
+
+    output.push_str("# 🚦 PRISM Release Readiness Report\n\n");
+    if readiness.ready {
+        output.push_str("## ✅ GO — all release gates passed\n\n");
+    } else {
+        output.push_str("## ⛔ NO-GO — release gates failed\n\n");
+    }
+
+    output.push_str("| Gate | Actual | Required | Status |\n|---|---:|---:|---|\n");
+    for gate in &readiness.gates {
+        let status = match gate.passed {
+            Some(true) => "✅ Pass",
+            Some(false) => "❌ Fail",
+            None => "⏭️ Skipped",
+        };
+        output.push_str(&format!("| {} | {} | {} | {} |\n", gate.name, gate.actual, gate.required, status));
+    }
+    output.push('\n');
+
+    let sections = [
+        ("🔴 Critical Findings", &readiness.critical_findings),
+        ("❓ Open Clarification Questions", &readiness.open_questions),
+        ("🧩 Completeness Gaps", &readiness.completeness_gaps),
+    ];
+    for (title, items) in sections {
+        if items.is_empty() {
+            continue;
+        }
+        output.push_str(&format!("## {}\n\n", title));
+        for item in items {
+            output.push_str(&format!("- {}\n", item));
+        }
+        output.push('\n');
+    }
+
+    output
+}
@@ -0,0 +1,196 @@
+//! Compares two saved analysis results (`prism analyze --format json`):
+//! the findings added and resolved, how the scores moved and which actors,
+//! actions and objects came and went. Works on any two reports, whether
+//! or not the requirements are in git.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::analyzer::{Ambiguity, AmbiguitySeverity, AnalysisResult};
+use crate::hierarchy::QualityScore;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeverityChange {
+    pub finding: Ambiguity,
+    pub from: AmbiguitySeverity,
+}
+
+/// A score in the old and in the new report
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScoreChange {
+    pub old: f32,
+    pub new: f32,
+}
+
+impl ScoreChange {
+    pub fn delta(&self) -> f32 {
+        self.new - self.old
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListChange {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl ListChange {
+    /// Compared without regard to letter case; the new spelling is kept.
+    fn between(old: &[String], new: &[String]) -> Self {
+        let lower = |items: &[String]| items.iter().map(|i| i.to_lowercase()).collect::<BTreeSet<_>>();
+        let (old_set, new_set) = (lower(old), lower(new));
+        Self {
+            added: new.iter().filter(|i| !old_set.contains(&i.to_lowercase())).cloned().collect(),
+            removed: old.iter().filter(|i| !new_set.contains(&i.to_lowercase())).cloned().collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportDiff {
+    /// Findings only the new report has
+    pub added: Vec<Ambiguity>,
+    /// Findings of the old report that are gone
+    pub resolved: Vec<Ambiguity>,
+    /// Findings in both whose severity changed, as in the new report
+    pub severity_changes: Vec<SeverityChange>,
+    pub unchanged: usize,
+    pub quality: ScoreChange,
+    pub findings: ScoreChange,
+    pub requirements: ScoreChange,
+    /// Only when both reports have a completeness analysis
+    pub completeness: Option<ScoreChange>,
+    pub actors: ListChange,
+    pub actions: ListChange,
+    pub objects: ListChange,
+}
+
+impl ReportDiff {
+    /// The new report has no new findings and its quality score did not drop.
+    pub fn is_improvement(&self) -> bool {
+        self.added.is_empty() && self.quality.delta() >= 0.0
+    }
+}
+
+fn finding_key(finding: &Ambiguity) -> (String, String) {
+    (finding.rule_id.clone(), finding.text.trim().to_lowercase())
+}
+
+/// Findings are matched by fingerprint first, and the rest by rule and text,
+/// so a finding whose requirement moved to another section still counts as
+/// the same finding.
+pub fn diff_results(old: &AnalysisResult, new: &AnalysisResult) -> ReportDiff {
+    let mut unmatched_old: Vec<Option<&Ambiguity>> = old.ambiguities.iter().map(Some).collect();
+    let mut pairs: Vec<(&Ambiguity, Option<&Ambiguity>)> = new.ambiguities.iter().map(|f| (f, None)).collect();
+
+    let passes: [fn(&Ambiguity, &Ambiguity) -> bool; 2] = [
+        |a, b| !a.fingerprint.is_empty() && a.fingerprint == b.fingerprint,
+        |a, b| finding_key(a) == finding_key(b),
+    ];
+    for matches in passes {
+        for (finding, previous) in pairs.iter_mut().filter(|(_, previous)| previous.is_none()) {
+            if let Some(slot) = unmatched_old.iter_mut().find(|slot| slot.is_some_and(|o| matches(o, finding))) {
+                *previous = slot.take();
+            }
+        }
+    }
+
+    let mut diff = ReportDiff {
+        added: Vec::new(),
+        resolved: unmatched_old.into_iter().flatten().cloned().collect(),
+        severity_changes: Vec::new(),
+        unchanged: 0,
+        quality: ScoreChange { old: QualityScore::from_result(old).score, new: QualityScore::from_result(new).score },
+        findings: ScoreChange { old: old.ambiguities.len() as f32, new: new.ambiguities.len() as f32 },
+        requirements: ScoreChange { old: old.metrics.requirement_count as f32, new: new.metrics.requirement_count as f32 },
+        completeness: old.completeness_analysis.as_ref().zip(new.completeness_analysis.as_ref())
+            .map(|(o, n)| ScoreChange { old: o.completeness_score, new: n.completeness_score }),
+        actors: ListChange::between(&old.entities.actors, &new.entities.actors),
+        actions: ListChange::between(&old.entities.actions, &new.entities.actions),
+        objects: ListChange::between(&old.entities.objects, &new.entities.objects),
+    };
+    for (finding, previous) in pairs {
+        match previous {
+            None => diff.added.push(finding.clone()),
+            Some(previous) if previous.severity != finding.severity => {
+                diff.severity_changes.push(SeverityChange { finding: finding.clone(), from: previous.severity });
+            }
+            Some(_) => diff.unchanged += 1,
+        }
+    }
+    diff.added.sort_by_key(|f| std::cmp::Reverse(f.severity));
+    diff.resolved.sort_by_key(|f| std::cmp::Reverse(f.severity));
+    diff
+}
+
+fn signed(delta: f32) -> String {
+    if delta > 0.0 { format!("+{:.0}", delta) } else { format!("{:.0}", delta) }
+}
+
+fn finding_line(finding: &Ambiguity) -> String {
+    let location = finding.location.as_ref().map(|l| format!(" (line {})", l.line)).unwrap_or_default();
+    format!("- **{}** \"{}\"{} - {}\n", finding.severity, finding.text, location, finding.reason)
+}
+
+pub fn format_report_diff_markdown(diff: &ReportDiff, old_name: &str, new_name: &str) -> String {
+    let mut output = format!("# 🔀 PRISM Report Comparison\n\n**Old:** `{}`  \n**New:** `{}`\n\n", old_name, new_name);
+    let verdict = if diff.is_improvement() { "✅ Improved or unchanged" } else { "⚠️ Regressed" };
+    output.push_str(&format!(
+        "**{}** - {} new, {} resolved, {} unchanged finding(s)\n\n",
+        verdict, diff.added.len(), diff.resolved.len(), diff.unchanged + diff.severity_changes.len()
+    ));
+
+    output.push_str("## 📊 Scores\n\n| Metric | Old | New | Change |\n|---|---:|---:|---:|\n");
+    let mut rows = vec![("Quality score", diff.quality), ("Findings", diff.findings), ("Requirements", diff.requirements)];
+    if let Some(completeness) = diff.completeness {
+        rows.push(("Completeness %", completeness));
+    }
+    for (name, change) in rows {
+        output.push_str(&format!("| {} | {:.0} | {:.0} | {} |\n", name, change.old, change.new, signed(change.delta())));
+    }
+
+    output.push_str("\n## 🆕 New Findings\n\n");
+    if diff.added.is_empty() {
+        output.push_str("None.\n");
+    }
+    for finding in &diff.added {
+        output.push_str(&finding_line(finding));
+    }
+
+    output.push_str("\n## ✅ Resolved Findings\n\n");
+    if diff.resolved.is_empty() {
+        output.push_str("None.\n");
+    }
+    for finding in &diff.resolved {
+        output.push_str(&finding_line(finding));
+    }
+
+    if !diff.severity_changes.is_empty() {
+        output.push_str("\n## ↕️ Severity Changes\n\n");
+        for change in &diff.severity_changes {
+            output.push_str(&format!("- \"{}\": {} → {}\n", change.finding.text, change.from, change.finding.severity));
+        }
+    }
+
+    output.push_str("\n## 🎯 Entity Changes\n\n");
+    let entity_lists = [("Actors", &diff.actors), ("Actions", &diff.actions), ("Objects", &diff.objects)];
+    if entity_lists.iter().all(|(_, change)| change.is_empty()) {
+        output.push_str("No actors, actions or objects were added or removed.\n");
+    }
+    for (name, change) in entity_lists.iter().filter(|(_, change)| !change.is_empty()) {
+        output.push_str(&format!("- **{}:**", name));
+        if !change.added.is_empty() {
+            output.push_str(&format!(" added {}", change.added.join(", ")));
+        }
+        if !change.removed.is_empty() {
+            let separator = if change.added.is_empty() { "" } else { ";" };
+            output.push_str(&format!("{} removed {}", separator, change.removed.join(", ")));
+        }
+        output.push('\n');
+    }
+    output
+}
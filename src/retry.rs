@@ -0,0 +1,58 @@
+//! Retry policy for LLM requests that hit rate limits or temporary outages.
+
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use std::time::Duration;
+
+use crate::config::RetryConfig;
+
+/// Whether a response with `status` is worth retrying.
+pub fn is_retryable(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS | StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Wait before retry number `retry` (1 for the first retry): the provider's
+/// `Retry-After` when given, otherwise exponential backoff. Never longer
+/// than `max_backoff_ms`.
+pub fn retry_delay(policy: &RetryConfig, retry: u32, retry_after: Option<&str>, now: DateTime<Utc>) -> Duration {
+    let backoff = policy.initial_backoff_ms.saturating_mul(1u64 << retry.saturating_sub(1).min(32));
+    let delay = retry_after
+        .and_then(|value| parse_retry_after(value, now))
+        .unwrap_or(Duration::from_millis(backoff));
+    delay.min(Duration::from_millis(policy.max_backoff_ms))
+}
+
+/// `Retry-After` in seconds or as an HTTP date.
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<f64>() {
+        // Too long for a Duration, e.g. "inf": as long as `retry_delay` allows
+        return (seconds >= 0.0).then(|| Duration::try_from_secs_f64(seconds).unwrap_or(Duration::MAX));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&Utc) - now).to_std().unwrap_or_default())
+}
+
+/// What to try when a request to `provider` (an `llm.provider` value) times out.
+pub fn timeout_hint(provider: &str) -> &'static str {
+    match provider {
+        "ollama" => "the model may still be loading - raise the timeout with 'prism config --timeout 120' or PRISM_TIMEOUT, or use a smaller model",
+        "azure" => "check the deployment's base URL, or raise the timeout with 'prism config --timeout <seconds>' or PRISM_TIMEOUT",
+        "gemini" => "Gemini can be slow on long inputs - raise the timeout with 'prism config --timeout <seconds>' or PRISM_TIMEOUT",
+        "claude" => "raise the timeout with 'prism config --timeout <seconds>' or PRISM_TIMEOUT, or try a faster model such as claude-3-haiku-20240307",
+        _ => "raise the timeout with 'prism config --timeout <seconds>' or PRISM_TIMEOUT, or try a faster model such as gpt-3.5-turbo",
+    }
+}
+
+/// What to try when `provider` cannot be reached at all.
+pub fn connect_hint(provider: &str) -> &'static str {
+    match provider {
+        "ollama" => "is Ollama running? Start it with 'ollama serve'",
+        "azure" => "check the deployment's base URL and your network connection",
+        "openai-compatible" | "anthropic-compatible" => "check llm.base_url and that the gateway or server is running",
+        _ => "check your network connection and any proxy settings",
+    }
+}
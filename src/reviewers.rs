@@ -0,0 +1,114 @@
+//! Suggested reviewers for a document, from the categories of what the
+//! analysis found (security gaps go to the security team, performance
+//! requirements to SRE) and a configurable category-to-reviewer mapping.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::analyzer::AnalysisResult;
+
+/// Words that put a document in a review category on their own, even when
+/// no generator flagged it.
+const TOPIC_KEYWORDS: &[(&str, &[&str])] = &[
+    ("security", &["password", "encrypt", "encryption", "authentication", "authorization", "token", "vulnerability", "audit log"]),
+    ("privacy", &["personal data", "gdpr", "consent", "pii", "retention", "anonymize"]),
+    ("performance", &["latency", "response time", "throughput", "under load", "concurrent"]),
+    ("accessibility", &["wcag", "screen reader", "keyboard navigation", "contrast"]),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewerSuggestion {
+    pub reviewer: String,
+    /// Categories of the document this reviewer is suggested for
+    pub categories: Vec<String>,
+    /// Why, one line per category
+    pub reasons: Vec<String>,
+}
+
+/// "Non-Functional Requirements" as "non-functional-requirements".
+fn category_key(name: &str) -> String {
+    name.split_whitespace().map(|w| w.to_lowercase()).collect::<Vec<_>>().join("-")
+}
+
+/// The review categories of a document with the reason for each, in the
+/// order they were found.
+fn document_categories(text: &str, result: &AnalysisResult) -> Vec<(String, String)> {
+    let mut categories: Vec<(String, String)> = Vec::new();
+    let mut add = |category: String, reason: String| {
+        if !categories.iter().any(|(c, _)| *c == category) {
+            categories.push((category, reason));
+        }
+    };
+
+    if let Some(nfrs) = &result.nfr_suggestions {
+        for nfr in nfrs {
+            let category = format!("{:?}", nfr.category).to_lowercase();
+            add(category.clone(), format!("{} requirement: {}", category, nfr.requirement));
+        }
+    }
+    if let Some(completeness) = &result.completeness_analysis {
+        for gap in &completeness.gaps_identified {
+            add(category_key(&gap.category), format!("Gap: {}", gap.description));
+        }
+    }
+
+    let lower = text.to_lowercase();
+    for (category, keywords) in TOPIC_KEYWORDS {
+        if let Some(keyword) = keywords.iter().find(|k| lower.contains(*k)) {
+            add(category.to_string(), format!("Mentions \"{}\"", keyword));
+        }
+    }
+
+    if !result.ambiguities.is_empty() {
+        add("clarity".to_string(), format!("{} ambiguity finding(s)", result.ambiguities.len()));
+    }
+    let mut rules: BTreeMap<&str, usize> = BTreeMap::new();
+    for finding in &result.ambiguities {
+        *rules.entry(finding.rule_id.as_str()).or_default() += 1;
+    }
+    for (rule, count) in rules.into_iter().filter(|(rule, _)| !rule.is_empty()) {
+        add(rule.to_string(), format!("{} `{}` finding(s)", count, rule));
+    }
+    categories
+}
+
+/// Reviewers for the document, from the categories of `result` and the
+/// `reviewers` mapping (category → reviewers). Reviewers covering the most
+/// categories come first; categories without a reviewer are left out.
+pub fn suggest_reviewers(text: &str, result: &AnalysisResult, reviewers: &BTreeMap<String, Vec<String>>) -> Vec<ReviewerSuggestion> {
+    let mut suggestions: Vec<ReviewerSuggestion> = Vec::new();
+    for (category, reason) in document_categories(text, result) {
+        for reviewer in reviewers.get(&category).into_iter().flatten() {
+            let index = match suggestions.iter().position(|s| s.reviewer == *reviewer) {
+                Some(index) => index,
+                None => {
+                    suggestions.push(ReviewerSuggestion { reviewer: reviewer.clone(), categories: Vec::new(), reasons: Vec::new() });
+                    suggestions.len() - 1
+                }
+            };
+            suggestions[index].categories.push(category.clone());
+            suggestions[index].reasons.push(reason.clone());
+        }
+    }
+    // Stable, so equally relevant reviewers keep the order their categories were found in
+    suggestions.sort_by_key(|s| std::cmp::Reverse(s.categories.len()));
+    suggestions
+}
+
+/// Markdown table of the suggested reviewers, for reports and PR comments.
+pub fn format_reviewer_table(suggestions: &[ReviewerSuggestion]) -> String {
+    if suggestions.is_empty() {
+        return "No reviewer is mapped to the categories of this document.\n\n".to_string();
+    }
+    let mut output = String::from("| Reviewer | Categories | Why |\n|---|---|---|\n");
+    for suggestion in suggestions {
+        output.push_str(&format!(
+            "| {} | {} | {} |\n",
+            suggestion.reviewer,
+            suggestion.categories.join(", "),
+            suggestion.reasons.join("<br>").replace('|', "\\|")
+        ));
+    }
+    output.push('\n');
+    output
+}
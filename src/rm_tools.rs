@@ -0,0 +1,281 @@
+//! Pulls requirements from requirements management tools for `prism import`.
+
+use anyhow::Result;
+use regex::Regex;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::{FieldMapping, RmConnection};
+
+/// Imported requirements scoring below this are counted as needing work.
+pub const LOW_QUALITY_SCORE: f32 = 70.0;
+
+/// Requirements management tools with a built-in REST mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RmTool {
+    Polarion,
+    Jama,
+    DoorsNext,
+}
+
+impl RmTool {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RmTool::Polarion => "Polarion",
+            RmTool::Jama => "Jama Connect",
+            RmTool::DoorsNext => "DOORS Next",
+        }
+    }
+
+    /// Item list endpoint relative to the base URL. DOORS Next query
+    /// capabilities differ per server, so they must be configured.
+    fn default_endpoint(&self) -> Option<&'static str> {
+        match self {
+            RmTool::Polarion => Some("/polarion/rest/v1/projects/{project}/workitems"),
+            RmTool::Jama => Some("/rest/v1/items"),
+            RmTool::DoorsNext => None,
+        }
+    }
+
+    fn default_query(&self, project: Option<&str>) -> Vec<(String, String)> {
+        let pairs: Vec<(&str, String)> = match self {
+            RmTool::Polarion => vec![
+                ("fields[workitems]", "title,description".to_string()),
+                ("page[size]", "100".to_string()),
+                ("query", "type:requirement".to_string()),
+            ],
+            RmTool::Jama => {
+                let mut pairs = vec![("maxResults", "50".to_string())];
+                pairs.extend(project.map(|p| ("project", p.to_string())));
+                pairs
+            }
+            RmTool::DoorsNext => vec![
+                ("oslc.select", "dcterms:identifier,dcterms:title,dcterms:description".to_string()),
+                ("oslc.paging", "true".to_string()),
+                ("oslc.pageSize", "100".to_string()),
+            ],
+        };
+        pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect()
+    }
+
+    /// JSON pointers to the item list and to each item's fields.
+    pub fn default_fields(&self) -> ResolvedFields {
+        let (items, id, title, text) = match self {
+            RmTool::Polarion => ("/data", "/id", "/attributes/title", "/attributes/description/value"),
+            RmTool::Jama => ("/data", "/documentKey", "/fields/name", "/fields/description"),
+            RmTool::DoorsNext => ("/oslc:results", "/dcterms:identifier", "/dcterms:title", "/dcterms:description"),
+        };
+        ResolvedFields { items: items.to_string(), id: id.to_string(), title: title.to_string(), text: text.to_string() }
+    }
+}
+
+/// Field pointers after applying a connection's overrides to the tool defaults.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedFields {
+    pub items: String,
+    pub id: String,
+    pub title: String,
+    pub text: String,
+}
+
+impl ResolvedFields {
+    pub fn resolve(tool: RmTool, overrides: &FieldMapping) -> Self {
+        let defaults = tool.default_fields();
+        Self {
+            items: overrides.items.clone().unwrap_or(defaults.items),
+            id: overrides.id.clone().unwrap_or(defaults.id),
+            title: overrides.title.clone().unwrap_or(defaults.title),
+            text: overrides.text.clone().unwrap_or(defaults.text),
+        }
+    }
+}
+
+/// One requirement pulled from a tool.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RmRequirement {
+    pub id: String,
+    pub title: String,
+    /// Plain text of the requirement body
+    pub text: String,
+}
+
+impl RmRequirement {
+    /// Text analyzed for the requirement: its title followed by its body.
+    pub fn analysis_text(&self) -> String {
+        if self.text.trim().is_empty() {
+            self.title.clone()
+        } else {
+            format!("{}\n\n{}", self.title, self.text)
+        }
+    }
+
+    /// Report file name, e.g. "PRJ-REQ-12.md".
+    pub fn file_name(&self, extension: &str) -> String {
+        let name: String = self.id.chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        format!("{}.{}", name, extension)
+    }
+}
+
+/// Requirements in one page of an item list response.
+pub fn parse_items(response: &Value, fields: &ResolvedFields) -> Vec<RmRequirement> {
+    response.pointer(&fields.items)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|item| {
+            let id = field_text(item, &fields.id)?;
+            let title = field_text(item, &fields.title).unwrap_or_default();
+            let text = field_text(item, &fields.text).map(|t| html_to_text(&t)).unwrap_or_default();
+            Some(RmRequirement { id, title: html_to_text(&title), text })
+        })
+        .collect()
+}
+
+fn field_text(item: &Value, pointer: &str) -> Option<String> {
+    match item.pointer(pointer)? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        // Rich text fields are often {"type": "text/html", "value": "..."}
+        Value::Object(o) => o.get("value").and_then(Value::as_str).map(str::to_string),
+        _ => None,
+    }
+}
+
+/// Where the next page of an item list is, if there is one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NextPage {
+    Url(String),
+    Offset(u64),
+}
+
+pub fn next_page(tool: RmTool, response: &Value) -> Option<NextPage> {
+    match tool {
+        RmTool::Polarion => response.pointer("/links/next").and_then(Value::as_str).map(|url| NextPage::Url(url.to_string())),
+        RmTool::Jama => {
+            let info = response.pointer("/meta/pageInfo")?;
+            let start = info["startIndex"].as_u64()?;
+            let count = info["resultCount"].as_u64()?;
+            let total = info["totalResults"].as_u64()?;
+            (count > 0 && start + count < total).then_some(NextPage::Offset(start + count))
+        }
+        RmTool::DoorsNext => {
+            let next = response.pointer("/oslc:responseInfo/oslc:nextPage")?;
+            next.as_str()
+                .or_else(|| next["rdf:resource"].as_str())
+                .map(|url| NextPage::Url(url.to_string()))
+        }
+    }
+}
+
+/// Plain text of an HTML rich-text field, one line per paragraph or list item.
+pub fn html_to_text(html: &str) -> String {
+    let breaks = Regex::new(r"(?i)<br\s*/?>|</(p|div|li|h[1-6]|tr)>").unwrap();
+    let items = Regex::new(r"(?i)<li[^>]*>").unwrap();
+    let tags = Regex::new(r"<[^>]+>").unwrap();
+
+    let text = breaks.replace_all(html, "\n");
+    let text = items.replace_all(&text, "- ");
+    let text = tags.replace_all(&text, "");
+    let text = text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+
+    text.lines().map(str::trim).filter(|l| !l.is_empty()).collect::<Vec<_>>().join("\n")
+}
+
+pub struct RmClient {
+    http_client: Client,
+    connection: RmConnection,
+    token: Option<String>,
+    fields: ResolvedFields,
+}
+
+impl RmClient {
+    pub fn new(connection: RmConnection, token: Option<String>) -> Self {
+        let fields = ResolvedFields::resolve(connection.tool, &connection.fields);
+        Self { http_client: Client::new(), connection, token, fields }
+    }
+
+    /// Item list URL for `project`, with the configured query parameters.
+    pub fn list_url(&self, project: Option<&str>) -> Result<String> {
+        let tool = self.connection.tool;
+        let endpoint = self.connection.endpoint.as_deref().or(tool.default_endpoint()).ok_or_else(|| anyhow::anyhow!(
+            "{} has no default endpoint - set endpoint to the OSLC query capability URL of the project area",
+            tool.name()
+        ))?;
+        if endpoint.contains("{project}") && project.is_none() {
+            return Err(anyhow::anyhow!("{} needs a project - set project in the connection or use --project", tool.name()));
+        }
+        let endpoint = endpoint.replace("{project}", project.unwrap_or_default());
+
+        let mut url = if endpoint.starts_with("http://") || endpoint.starts_with("https://") {
+            reqwest::Url::parse(&endpoint)?
+        } else {
+            reqwest::Url::parse(&format!("{}{}", self.connection.base_url.trim_end_matches('/'), endpoint))?
+        };
+        {
+            let mut pairs = url.query_pairs_mut();
+            for (key, value) in tool.default_query(project) {
+                if !self.connection.query.contains_key(&key) {
+                    pairs.append_pair(&key, &value);
+                }
+            }
+            for (key, value) in &self.connection.query {
+                pairs.append_pair(key, value);
+            }
+        }
+        Ok(url.to_string())
+    }
+
+    async fn get(&self, url: &str) -> Result<Value> {
+        let mut request = self.http_client.get(url).header("Accept", "application/json");
+        if self.connection.tool == RmTool::DoorsNext {
+            request = request.header("OSLC-Core-Version", "2.0");
+        }
+        request = match (&self.token, &self.connection.username) {
+            (Some(token), _) => request.header("Authorization", format!("Bearer {}", token)),
+            (None, Some(username)) => request.basic_auth(username, self.connection.password.as_deref()),
+            (None, None) => request,
+        };
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("{} request failed ({}): {}", self.connection.tool.name(), status, error_text.trim()));
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Every requirement of `project`, following pagination, up to `limit`.
+    pub async fn fetch_requirements(&self, project: Option<&str>, limit: Option<usize>) -> Result<Vec<RmRequirement>> {
+        let first_url = self.list_url(project)?;
+        let mut url = first_url.clone();
+        let mut requirements = Vec::new();
+        loop {
+            let response = self.get(&url).await?;
+            requirements.extend(parse_items(&response, &self.fields));
+            if limit.is_some_and(|limit| requirements.len() >= limit) {
+                requirements.truncate(limit.unwrap_or_default());
+                return Ok(requirements);
+            }
+
+            url = match next_page(self.connection.tool, &response) {
+                Some(NextPage::Url(next)) => reqwest::Url::parse(&url)?.join(&next)?.to_string(),
+                Some(NextPage::Offset(start)) => {
+                    let mut next = reqwest::Url::parse(&first_url)?;
+                    next.query_pairs_mut().append_pair("startAt", &start.to_string());
+                    next.to_string()
+                }
+                None => return Ok(requirements),
+            };
+        }
+    }
+}
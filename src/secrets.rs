@@ -0,0 +1,34 @@
+//! The AI provider's API key in the operating system's credential store
+//! (Keychain on macOS, the Secret Service on Linux, Credential Manager on
+//! Windows) instead of in `~/.prism/config.yml`.
+
+use anyhow::{Context, Result};
+
+/// Service the key is stored under, as shown by the OS keyring tools
+pub const KEYRING_SERVICE: &str = "prism";
+
+pub const KEYRING_ACCOUNT: &str = "llm-api-key";
+
+fn entry() -> Result<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_ACCOUNT).context("Cannot open the OS keyring")
+}
+
+/// Keyring calls block, and the Secret Service backend must not wait on a
+/// runtime thread.
+async fn blocking<T: Send + 'static>(call: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T> {
+    tokio::task::spawn_blocking(call).await?
+}
+
+pub async fn store_api_key(key: &str) -> Result<()> {
+    let key = key.to_string();
+    blocking(move || entry()?.set_password(&key).context("Cannot store the API key in the OS keyring")).await
+}
+
+/// The stored key, or `None` when there is none.
+pub async fn load_api_key() -> Result<Option<String>> {
+    blocking(|| match entry()?.get_password() {
+        Ok(key) => Ok(Some(key)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e).context("Cannot read the API key from the OS keyring"),
+    }).await
+}
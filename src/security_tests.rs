@@ -0,0 +1,371 @@
+//! Security test cases for the requirements that touch authentication,
+//! authorization, user input, money or sensitive data, and for the security
+//! NFRs: negative auth tests, injection probes and abuse cases, each with the
+//! sentences it comes from and the findings on them. The checklist export
+//! says how to run every test with OWASP ZAP or Burp Suite.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::analyzer::{Ambiguity, Analyzer, NfrCategory, NonFunctionalRequirement};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SecurityTestKind {
+    Authentication,
+    Authorization,
+    Injection,
+    AbuseCase,
+    DataExposure,
+}
+
+impl SecurityTestKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SecurityTestKind::Authentication => "Authentication",
+            SecurityTestKind::Authorization => "Authorization",
+            SecurityTestKind::Injection => "Injection",
+            SecurityTestKind::AbuseCase => "Abuse case",
+            SecurityTestKind::DataExposure => "Data exposure",
+        }
+    }
+
+    /// The OWASP Top 10 (2021) category the test guards against
+    pub fn owasp(&self) -> &'static str {
+        match self {
+            SecurityTestKind::Authentication => "A07:2021 Identification and Authentication Failures",
+            SecurityTestKind::Authorization => "A01:2021 Broken Access Control",
+            SecurityTestKind::Injection => "A03:2021 Injection",
+            SecurityTestKind::AbuseCase => "A04:2021 Insecure Design",
+            SecurityTestKind::DataExposure => "A02:2021 Cryptographic Failures",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityTestCase {
+    /// "SEC-1"
+    pub id: String,
+    pub kind: SecurityTestKind,
+    pub title: String,
+    pub steps: Vec<String>,
+    /// Inputs to send, for injection probes and tampering
+    pub probes: Vec<String>,
+    pub expected: String,
+    /// How to run the test with OWASP ZAP or Burp Suite
+    pub tooling: String,
+    /// The sentences and NFRs the test comes from, as "Line 3: ..." or "Security NFR: ..."
+    pub sources: Vec<String>,
+    /// Findings on those sentences, as "rule-id: text"
+    pub findings: Vec<String>,
+}
+
+struct Template {
+    kind: SecurityTestKind,
+    /// "{action}" is replaced by what the sentence lets the user do
+    title: &'static str,
+    steps: &'static [&'static str],
+    probes: &'static [&'static str],
+    expected: &'static str,
+    tooling: &'static str,
+}
+
+const AUTHENTICATION: &str = r"\b(log ?ins?|logs? in|logged in|sign(?:s|ed)? ?in|sign-in|authenticat\w*|passwords?|credentials?|passcodes?|two-factor|2fa|mfa|otp|one-time (?:code|password)|sessions?)\b";
+const PASSWORD_RESET: &str = r"\b(reset|forgot(?:ten)?|recover)\w*\b.*\bpassword|\bpassword\b.*\b(reset|recovery)\b";
+const AUTHORIZATION: &str = r"\b(admins?|administrators?|roles?|permissions?|authori[sz]\w*|privileges?|only (?:the |an? )?(?:admins?|administrators?|managers?|owners?|authori[sz]ed|signed-in|logged-in|registered)|their own|own (?:account|data|records?|orders?|profile))\b";
+const INPUT: &str = r"\b(search\w*|enters?|entered|inputs?|submits?|submitted|forms?|comments?|messages?|quer(?:y|ies)|filters?|reviews?|descriptions?|free text|text fields?)\b";
+const UPLOAD: &str = r"\b(upload\w*|attach\w*|imports?|imported)\b";
+const MONEY: &str = r"\b(pay\w*|transfers?|transferred|refunds?|checkout|coupons?|discounts?|vouchers?|amounts?|balances?|prices?|credits?)\b";
+const SENSITIVE: &str = r"\b(personal data|personally identifiable|pii|credit cards?|card numbers?|ssn|social security|encrypt\w*|sensitive|confidential|gdpr|hipaa|medical|health records?|bank details)\b";
+
+/// Action labels that make a sentence about authentication
+const AUTH_ACTIONS: &[&str] = &["log", "login", "logout", "sign", "authenticate", "verify", "register", "reset", "unlock"];
+
+const AUTHENTICATION_TESTS: &[Template] = &[
+    Template {
+        kind: SecurityTestKind::Authentication,
+        title: "Wrong credentials are rejected without revealing which part was wrong",
+        steps: &["Sign in with a registered user name and a wrong password", "Sign in with a user name that does not exist"],
+        probes: &[],
+        expected: "Both attempts are refused with the same message, status code and timing, so accounts cannot be enumerated",
+        tooling: "Burp Repeater: compare the two responses; ZAP: check the 'Username Enumeration' scan rule",
+    },
+    Template {
+        kind: SecurityTestKind::AbuseCase,
+        title: "Repeated failed sign-ins are throttled",
+        steps: &["Send 20 failed sign-in attempts for one account within a minute", "Then sign in with the correct password"],
+        probes: &[],
+        expected: "Attempts are rate limited or the account is locked, and the correct password is not accepted while locked",
+        tooling: "Burp Intruder: replay the sign-in request with a password list; ZAP: Fuzzer on the password field",
+    },
+    Template {
+        kind: SecurityTestKind::Authentication,
+        title: "Sessions end on sign-out and expire",
+        steps: &["Sign in and note the session cookie or token", "Sign out", "Replay an authenticated request with the old cookie or token"],
+        probes: &[],
+        expected: "The old session is refused with 401 and the user has to sign in again",
+        tooling: "Burp Repeater: replay the request after signing out; ZAP: check the 'Session Fixation' and cookie scan rules",
+    },
+];
+
+const PASSWORD_RESET_TESTS: &[Template] = &[Template {
+    kind: SecurityTestKind::Authentication,
+    title: "Password reset links are single-use and expire",
+    steps: &["Request a password reset and use the link", "Use the same link a second time", "Use a link older than its stated lifetime"],
+    probes: &[],
+    expected: "Only the first use within the lifetime succeeds, and the reset request answers the same for known and unknown accounts",
+    tooling: "Burp Repeater: replay the reset request; Burp Sequencer: check that the reset tokens are unpredictable",
+}];
+
+const AUTHORIZATION_TESTS: &[Template] = &[
+    Template {
+        kind: SecurityTestKind::Authorization,
+        title: "Unauthenticated requests to {action} are refused",
+        steps: &["Send the request to {action} without a session cookie or token"],
+        probes: &[],
+        expected: "401, no data in the response and nothing changes",
+        tooling: "Burp Repeater: remove the Cookie and Authorization headers; ZAP: run the request as an unauthenticated context user",
+    },
+    Template {
+        kind: SecurityTestKind::Authorization,
+        title: "Users without the required role cannot {action}",
+        steps: &["Sign in as a user who lacks the role or permission the requirement names", "Send the request to {action}, including by calling the API directly"],
+        probes: &[],
+        expected: "403 and nothing changes; the option is not offered in the UI either",
+        tooling: "Burp: the Autorize extension replays the privileged requests with a low-privilege session; ZAP: Access Control Testing add-on",
+    },
+    Template {
+        kind: SecurityTestKind::Authorization,
+        title: "Other users' records cannot be reached by changing the id ({action})",
+        steps: &["Sign in as user A and send the request to {action} for one of A's records", "Replace the record id in the request with one of user B's"],
+        probes: &[],
+        expected: "403 or 404, and B's record is neither shown nor changed",
+        tooling: "Burp Repeater or Intruder: iterate over ids with user A's session",
+    },
+];
+
+const INJECTION_TESTS: &[Template] = &[
+    Template {
+        kind: SecurityTestKind::Injection,
+        title: "SQL injection in the input is treated as data",
+        steps: &["Send each probe in every field of the input"],
+        probes: &["' OR '1'='1", "1; DROP TABLE users--", "' UNION SELECT NULL--"],
+        expected: "No extra rows, no database error and no change in behaviour compared with ordinary text",
+        tooling: "ZAP: Active Scan with the SQL Injection rule (40018); Burp Scanner or Intruder with the probes",
+    },
+    Template {
+        kind: SecurityTestKind::Injection,
+        title: "Script injection is escaped wherever the input is shown",
+        steps: &["Send each probe in every field of the input", "Open every page that shows the input"],
+        probes: &["<script>alert(1)</script>", "\"><img src=x onerror=alert(1)>"],
+        expected: "The text is shown escaped and no script runs",
+        tooling: "ZAP: Active Scan with the Cross Site Scripting rules (40012, 40014); Burp Scanner",
+    },
+];
+
+const UPLOAD_TESTS: &[Template] = &[Template {
+    kind: SecurityTestKind::Injection,
+    title: "Uploaded files are checked by content, name and size",
+    steps: &["Upload a PHP or JSP script renamed to image.jpg", "Upload a file named ../../etc/passwd", "Upload a file over the stated size limit"],
+    probes: &[],
+    expected: "Unexpected types, path names and sizes are refused, and accepted files are never executed or served from their uploaded path",
+    tooling: "Burp Repeater: edit the file name and Content-Type of the upload request; ZAP: Path Traversal rule (6)",
+}];
+
+const MONEY_TESTS: &[Template] = &[
+    Template {
+        kind: SecurityTestKind::AbuseCase,
+        title: "Amounts and prices cannot be tampered with",
+        steps: &["Change the amount, price or quantity in the request to each probe"],
+        probes: &["-100", "0", "0.001", "99999999999"],
+        expected: "The server recalculates the total from its own data and refuses negative, zero and out-of-range values",
+        tooling: "Burp Repeater: edit the amount in the intercepted request; ZAP: Breakpoints on the request",
+    },
+    Template {
+        kind: SecurityTestKind::AbuseCase,
+        title: "A replayed or duplicated request takes effect once",
+        steps: &["Send the same request twice, once after the other and once at the same time"],
+        probes: &[],
+        expected: "It is processed once: no double charge, transfer or refund",
+        tooling: "Burp Repeater: send the requests in parallel as a group (race condition)",
+    },
+];
+
+const SENSITIVE_TESTS: &[Template] = &[Template {
+    kind: SecurityTestKind::DataExposure,
+    title: "Sensitive data is protected in transit, in responses and in logs",
+    steps: &["Use the feature over plain HTTP", "Inspect its responses, error messages and logs"],
+    probes: &[],
+    expected: "HTTP is redirected to HTTPS, and sensitive values are masked in responses, error messages and logs",
+    tooling: "ZAP: Passive Scan (information disclosure and missing security header rules); Burp: search the proxy history for the values",
+}];
+
+fn sentences(text: &str) -> Vec<(usize, String)> {
+    text.lines().enumerate()
+        .flat_map(|(index, line)| {
+            line.split_inclusive(['.', '!', '?', ';'])
+                .map(str::trim)
+                .filter(|s| s.split_whitespace().count() > 2)
+                .map(move |s| (index + 1, s.to_string()))
+        })
+        .collect()
+}
+
+/// What `sentence` lets someone do: "delete user accounts" from "Only admins
+/// can delete user accounts."
+fn permitted_action(sentence: &str) -> Option<String> {
+    let modal = Regex::new(r"(?i)\b(?:can|may|must|shall|should|will|is allowed to|are allowed to|be able to)\s+(?:only\s+|be able to\s+)?([a-z]+(?:\s+[a-z]+){0,3})").unwrap();
+    let words = modal.captures(sentence)?.get(1)?.as_str().to_lowercase();
+    let stop = ["by", "with", "from", "to", "in", "at", "on", "within", "using", "via", "when", "if", "once", "after", "before", "and", "or", "for"];
+    let action: Vec<&str> = words.split_whitespace().take_while(|w| !stop.contains(w)).collect();
+    (!action.is_empty() && !["be", "not", "have"].contains(&action[0])).then(|| action.join(" "))
+}
+
+fn is_auth_action(action: &str, sentence: &str) -> bool {
+    let Some(label) = Analyzer::action_label(action) else { return false };
+    let verb = label.split_whitespace().next().unwrap_or_default();
+    AUTH_ACTIONS.contains(&verb) && Regex::new(&format!(r"(?i)\b{}\b", regex::escape(&label))).is_ok_and(|r| r.is_match(sentence))
+}
+
+fn templates_for(sentence: &str, actions: &[String]) -> Vec<&'static Template> {
+    let lower = sentence.to_lowercase();
+    let matches = |pattern: &str| Regex::new(pattern).unwrap().is_match(&lower);
+    let mut templates = Vec::new();
+    if matches(AUTHENTICATION) || actions.iter().any(|a| is_auth_action(a, sentence)) {
+        templates.extend(AUTHENTICATION_TESTS);
+    }
+    if matches(PASSWORD_RESET) {
+        templates.extend(PASSWORD_RESET_TESTS);
+    }
+    if matches(AUTHORIZATION) {
+        templates.extend(AUTHORIZATION_TESTS);
+    }
+    if matches(INPUT) {
+        templates.extend(INJECTION_TESTS);
+    }
+    if matches(UPLOAD) {
+        templates.extend(UPLOAD_TESTS);
+    }
+    if matches(MONEY) {
+        templates.extend(MONEY_TESTS);
+    }
+    if matches(SENSITIVE) {
+        templates.extend(SENSITIVE_TESTS);
+    }
+    templates
+}
+
+/// Security tests for the sentences of `text` and the security NFRs in
+/// `nfrs`. A sentence that mentions an authentication action from `actions`
+/// counts as one about authentication. Tests that come out the same for
+/// several sentences are listed once, with every sentence as a source.
+pub fn generate_security_tests(
+    text: &str,
+    actions: &[String],
+    nfrs: &[NonFunctionalRequirement],
+    findings: &[Ambiguity],
+) -> Vec<SecurityTestCase> {
+    let mut sources: Vec<(String, String, Vec<String>)> = sentences(text).into_iter()
+        .map(|(line, sentence)| {
+            let on_sentence = findings.iter()
+                .filter(|f| f.location.as_ref().is_some_and(|l| l.line == line) && sentence.contains(f.text.as_str()))
+                .map(|f| format!("{}: {}", f.rule_id, f.text))
+                .collect();
+            (format!("Line {}: {}", line, sentence), sentence, on_sentence)
+        })
+        .collect();
+    for nfr in nfrs.iter().filter(|n| n.category == NfrCategory::Security) {
+        let statement = std::iter::once(&nfr.requirement).chain(&nfr.acceptance_criteria).cloned().collect::<Vec<_>>().join(". ");
+        sources.push((format!("Security NFR: {}", nfr.requirement), statement, Vec::new()));
+    }
+
+    let mut tests: Vec<SecurityTestCase> = Vec::new();
+    for (source, sentence, on_sentence) in sources {
+        let action = permitted_action(&sentence).unwrap_or_else(|| "perform the action".to_string());
+        for template in templates_for(&sentence, actions) {
+            let fill = |s: &str| s.replace("{action}", &action);
+            let title = fill(template.title);
+            let test = match tests.iter_mut().find(|t| t.title == title) {
+                Some(test) => test,
+                None => {
+                    tests.push(SecurityTestCase {
+                        id: format!("SEC-{}", tests.len() + 1),
+                        kind: template.kind,
+                        title,
+                        steps: template.steps.iter().map(|s| fill(s)).collect(),
+                        probes: template.probes.iter().map(|p| p.to_string()).collect(),
+                        expected: template.expected.to_string(),
+                        tooling: template.tooling.to_string(),
+                        sources: Vec::new(),
+                        findings: Vec::new(),
+                    });
+                    tests.last_mut().unwrap()
+                }
+            };
+            if !test.sources.contains(&source) {
+                test.sources.push(source.clone());
+            }
+            for finding in &on_sentence {
+                if !test.findings.contains(finding) {
+                    test.findings.push(finding.clone());
+                }
+            }
+        }
+    }
+    tests
+}
+
+pub fn format_security_tests_markdown(tests: &[SecurityTestCase]) -> String {
+    let mut output = String::new();
+    for test in tests {
+        output.push_str(&format!("### {} {}\n*{} - OWASP {}*\n\n", test.id, test.title, test.kind.label(), test.kind.owasp()));
+        output.push_str("**Steps:**\n");
+        for (i, step) in test.steps.iter().enumerate() {
+            output.push_str(&format!("{}. {}\n", i + 1, step));
+        }
+        if !test.probes.is_empty() {
+            let probes: Vec<String> = test.probes.iter().map(|p| format!("`{}`", p)).collect();
+            output.push_str(&format!("\n**Probes:** {}\n", probes.join(", ")));
+        }
+        output.push_str(&format!("\n**Expected:** {}\n\n", test.expected));
+        output.push_str(&format!("**From:** {}\n", test.sources.join("; ")));
+        if !test.findings.is_empty() {
+            output.push_str(&format!("\n**Findings:** {}\n", test.findings.join("; ")));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// A checklist to work through in OWASP ZAP or Burp Suite, one box per test.
+pub fn format_security_checklist(tests: &[SecurityTestCase], title: &str) -> String {
+    let mut output = format!(
+        "# Security Test Checklist: {}\n*Generated by PRISM - AI-Powered Requirement Analyzer*\n\n\
+         Proxy the application through OWASP ZAP or Burp Suite, sign in as the users the tests name and tick each test off.\n",
+        title
+    );
+    for kind in [
+        SecurityTestKind::Authentication,
+        SecurityTestKind::Authorization,
+        SecurityTestKind::Injection,
+        SecurityTestKind::AbuseCase,
+        SecurityTestKind::DataExposure,
+    ] {
+        let of_kind: Vec<&SecurityTestCase> = tests.iter().filter(|t| t.kind == kind).collect();
+        if of_kind.is_empty() {
+            continue;
+        }
+        output.push_str(&format!("\n## {} ({})\n\n", kind.label(), kind.owasp()));
+        for test in of_kind {
+            output.push_str(&format!("- [ ] **{}** {}\n", test.id, test.title));
+            output.push_str(&format!("  - Tools: {}\n", test.tooling));
+            if !test.probes.is_empty() {
+                let probes: Vec<String> = test.probes.iter().map(|p| format!("`{}`", p)).collect();
+                output.push_str(&format!("  - Probes: {}\n", probes.join(", ")));
+            }
+            output.push_str(&format!("  - Pass when: {}\n", test.expected));
+            output.push_str(&format!("  - From: {}\n", test.sources.join("; ")));
+        }
+    }
+    output
+}
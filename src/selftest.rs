@@ -0,0 +1,200 @@
+//! The regression suite of `prism selftest`: known-bad and known-good
+//! requirements the analyzer is run on, scored by precision and recall per
+//! rule, to check a new model, prompt or rule pack before rolling it out.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::analyzer::{AnalysisResult, RULE_AI_AMBIGUITY};
+use crate::locale::report_locale;
+
+/// The suite bundled with the binary.
+pub const BUILT_IN_SUITE: &str = include_str!("../samples/selftest_suite.yaml");
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelftestCase {
+    pub id: String,
+    pub text: String,
+    /// Rules that should report the case; none for a known-good requirement
+    #[serde(default)]
+    pub expect: Vec<String>,
+}
+
+impl SelftestCase {
+    pub fn is_known_bad(&self) -> bool {
+        !self.expect.is_empty()
+    }
+}
+
+/// The cases of a suite file, a YAML list of `id`, `text` and `expect`.
+pub fn parse_suite(yaml: &str) -> Result<Vec<SelftestCase>> {
+    let cases: Vec<SelftestCase> = serde_yaml::from_str(yaml).context("Invalid self-test suite")?;
+    if cases.is_empty() {
+        bail!("The self-test suite has no cases");
+    }
+    let mut ids = BTreeSet::new();
+    for case in &cases {
+        if !ids.insert(case.id.as_str()) {
+            bail!("The self-test suite has two cases with the id '{}'", case.id);
+        }
+    }
+    Ok(cases)
+}
+
+/// What one analysis of a case was expected to report and reported.
+#[derive(Debug, Clone, Serialize)]
+pub struct CaseOutcome {
+    pub case_id: String,
+    pub expected: BTreeSet<String>,
+    pub detected: BTreeSet<String>,
+}
+
+impl CaseOutcome {
+    /// With `ai`, the AI is expected to report every known-bad case too.
+    pub fn new(case: &SelftestCase, result: &AnalysisResult, ai: bool) -> Self {
+        let mut expected: BTreeSet<String> = case.expect.iter().cloned().collect();
+        if ai && case.is_known_bad() {
+            expected.insert(RULE_AI_AMBIGUITY.to_string());
+        }
+        Self {
+            case_id: case.id.clone(),
+            expected,
+            detected: result.ambiguities.iter().map(|f| f.rule_id.clone()).collect(),
+        }
+    }
+
+    pub fn missed(&self) -> Vec<&String> {
+        self.expected.difference(&self.detected).collect()
+    }
+
+    pub fn unexpected(&self) -> Vec<&String> {
+        self.detected.difference(&self.expected).collect()
+    }
+}
+
+/// Cases a rule reported rightly (true positives), wrongly (false
+/// positives) and failed to report (false negatives).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RuleScore {
+    pub rule_id: String,
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub false_negatives: usize,
+    /// Share of the rule's findings that were right; None when it reported nothing
+    pub precision: Option<f32>,
+    /// Share of the expected findings it reported; None when no case expects the rule
+    pub recall: Option<f32>,
+}
+
+impl RuleScore {
+    fn count(&mut self, expected: bool, detected: bool) {
+        match (expected, detected) {
+            (true, true) => self.true_positives += 1,
+            (false, true) => self.false_positives += 1,
+            (true, false) => self.false_negatives += 1,
+            (false, false) => {}
+        }
+    }
+
+    fn scored(mut self) -> Self {
+        let share = |right: usize, all: usize| (all > 0).then(|| right as f32 / all as f32);
+        self.precision = share(self.true_positives, self.true_positives + self.false_positives);
+        self.recall = share(self.true_positives, self.true_positives + self.false_negatives);
+        self
+    }
+}
+
+/// The suite's outcome with the built-in rules or one provider.
+#[derive(Debug, Clone, Serialize)]
+pub struct SelftestRun {
+    /// "built-in", or the AI provider
+    pub provider: String,
+    pub model: Option<String>,
+    /// Why the run failed; it then has no scores
+    pub error: Option<String>,
+    /// Whether known-bad cases got a finding and known-good ones none
+    pub overall: RuleScore,
+    pub rules: Vec<RuleScore>,
+    pub cases: Vec<CaseOutcome>,
+}
+
+impl SelftestRun {
+    pub fn new(provider: &str, model: Option<String>, cases: Vec<CaseOutcome>) -> Self {
+        let mut overall = RuleScore { rule_id: "any finding".to_string(), ..Default::default() };
+        let mut rules: BTreeMap<&str, RuleScore> = BTreeMap::new();
+        for case in &cases {
+            overall.count(!case.expected.is_empty(), !case.detected.is_empty());
+            for rule in case.expected.union(&case.detected) {
+                rules.entry(rule).or_insert_with(|| RuleScore { rule_id: rule.clone(), ..Default::default() })
+                    .count(case.expected.contains(rule), case.detected.contains(rule));
+            }
+        }
+        let rules = rules.into_values().map(RuleScore::scored).collect();
+        Self { provider: provider.to_string(), model, error: None, overall: overall.scored(), rules, cases }
+    }
+
+    pub fn failed(provider: &str, model: Option<String>, error: String) -> Self {
+        Self { error: Some(error), ..Self::new(provider, model, Vec::new()) }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SelftestReport {
+    /// "built-in" or the suite file
+    pub suite: String,
+    pub known_bad: usize,
+    pub known_good: usize,
+    pub runs: Vec<SelftestRun>,
+}
+
+fn percent(value: Option<f32>) -> String {
+    value.map(|v| report_locale().percent(v * 100.0, 0)).unwrap_or_else(|| "-".to_string())
+}
+
+fn score_row(name: &str, score: &RuleScore) -> String {
+    format!(
+        "| {} | {} | {} | {} | {} | {} |\n",
+        name, percent(score.precision), percent(score.recall), score.true_positives, score.false_positives, score.false_negatives
+    )
+}
+
+pub fn format_selftest_markdown(report: &SelftestReport) -> String {
+    let mut output = String::from("# 🧪 PRISM Self-Test\n\n");
+    output.push_str(&format!(
+        "**Suite:** {} - {} known-bad and {} known-good requirement(s)\n",
+        report.suite, report.known_bad, report.known_good
+    ));
+
+    for run in &report.runs {
+        let model = run.model.as_ref().map(|m| format!(" ({})", m)).unwrap_or_default();
+        output.push_str(&format!("\n## {}{}\n\n", run.provider, model));
+        if let Some(error) = &run.error {
+            output.push_str(&format!("⚠️ Failed: {}\n", error));
+            continue;
+        }
+        output.push_str("| Rule | Precision | Recall | Right | Wrong | Missed |\n|---|---:|---:|---:|---:|---:|\n");
+        output.push_str(&score_row("**Any finding**", &run.overall));
+        for score in &run.rules {
+            output.push_str(&score_row(&format!("`{}`", score.rule_id), score));
+        }
+
+        let mismatches: Vec<&CaseOutcome> = run.cases.iter().filter(|c| c.expected != c.detected).collect();
+        if mismatches.is_empty() {
+            output.push_str("\nEvery case reported as expected.\n");
+            continue;
+        }
+        output.push_str("\n**Mismatches:**\n\n");
+        for case in mismatches {
+            let mut problems = Vec::new();
+            if !case.missed().is_empty() {
+                problems.push(format!("missed {}", case.missed().iter().map(|r| format!("`{}`", r)).collect::<Vec<_>>().join(", ")));
+            }
+            if !case.unexpected().is_empty() {
+                problems.push(format!("unexpected {}", case.unexpected().iter().map(|r| format!("`{}`", r)).collect::<Vec<_>>().join(", ")));
+            }
+            output.push_str(&format!("- `{}`: {}\n", case.case_id, problems.join("; ")));
+        }
+    }
+    output
+}
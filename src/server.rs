@@ -0,0 +1,396 @@
+//! `prism serve`: analysis over HTTP and a push webhook that checks changed
+//! requirement files.
+//!
+//! Endpoints:
+//! - `GET /health`
+//! - `POST /analyze` with the requirement text as the body (or `{"text": "..."}`),
+//!   answered with the analysis result as JSON
+//! - `GET /usage` with what the calling API key has used
+//! - `GET /metrics` in the Prometheus text format
+//! - `POST /webhook` for GitHub and GitLab push events, only with a webhook
+//!   secret configured
+//!
+//! With `server.api_keys` configured, `/analyze` and `/usage` need one of the
+//! keys and `/analyze` is held to the key's rate limits.
+
+use anyhow::Result;
+use hyper::body::HttpBody;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use std::net::TcpListener;
+use std::path::{Component, Path};
+use std::sync::Arc;
+
+use crate::analyzer::{AnalysisResult, Analyzer};
+use crate::api_keys::{ApiKeys, UsageRecord};
+use crate::config::{ApiKeyConfig, Config};
+use crate::custom_rules::load_custom_rules;
+use crate::prompts::load_prompt_templates;
+use crate::document_processor::{create_private_temp_dir, DocumentProcessor};
+use crate::metrics::Metrics;
+use crate::telemetry::{instrument, start_span, SpanKind, Tracer};
+use crate::webhook::{format_push_comment, Forge, ForgeClient, PushEvent};
+
+/// Request bodies larger than this are rejected.
+const MAX_BODY_SIZE: usize = 5 * 1024 * 1024;
+
+/// How often finished spans are sent to the OTLP collector.
+const TRACE_EXPORT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+pub struct ServerState {
+    pub config: Config,
+    pub analyzer: Analyzer,
+    pub document_processor: DocumentProcessor,
+    pub api_keys: ApiKeys,
+    pub metrics: Arc<Metrics>,
+    pub tracer: Option<Arc<Tracer>>,
+}
+
+impl ServerState {
+    pub fn new(config: Config) -> Result<Self> {
+        let metrics = Arc::new(Metrics::new());
+        let tracer = Tracer::from_config(&config.telemetry);
+        let analyzer = Analyzer::new()?
+            .with_config(config.clone())
+            .with_custom_rules(load_custom_rules(&config)?)?
+            .with_prompts(load_prompt_templates(&config)?)
+            .with_metrics(metrics.clone())
+            .with_tracer(tracer.clone());
+        let api_keys = ApiKeys::new(&config.server.api_keys, config.server.usage_log.clone())?;
+        let document_processor = DocumentProcessor::new().with_tracer(tracer.clone());
+        Ok(Self { config, analyzer, document_processor, api_keys, metrics, tracer })
+    }
+}
+
+/// Serves requests on `listener` until the process is stopped.
+pub async fn serve(listener: TcpListener, state: Arc<ServerState>) -> Result<()> {
+    listener.set_nonblocking(true)?;
+    if let Some(tracer) = state.tracer.clone() {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(TRACE_EXPORT_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = tracer.flush().await {
+                    eprintln!("⚠️  Traces not exported: {}", e);
+                }
+            }
+        });
+    }
+    let make_service = make_service_fn(move |_| {
+        let state = state.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |request| handle(state.clone(), request))) }
+    });
+    hyper::Server::from_tcp(listener)?.serve(make_service).await?;
+    Ok(())
+}
+
+/// Serves only `GET /metrics` on `listener`, for `prism daemon`.
+pub async fn serve_metrics(listener: TcpListener, metrics: Arc<Metrics>) -> Result<()> {
+    listener.set_nonblocking(true)?;
+    let make_service = make_service_fn(move |_| {
+        let metrics = metrics.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                let response = match (request.method(), request.uri().path()) {
+                    (&Method::GET, "/metrics") => metrics_response(&metrics),
+                    _ => error_response(StatusCode::NOT_FOUND, "Not found"),
+                };
+                async move { Ok::<_, Infallible>(response) }
+            }))
+        }
+    });
+    hyper::Server::from_tcp(listener)?.serve(make_service).await?;
+    Ok(())
+}
+
+async fn handle(state: Arc<ServerState>, request: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let route = (request.method().clone(), request.uri().path().to_string());
+    let metrics = state.metrics.clone();
+    let response = match (route.0, route.1.as_str()) {
+        (Method::GET, "/health") => json_response(StatusCode::OK, json!({ "status": "ok" })),
+        (Method::POST, "/analyze") => {
+            let mut span = start_span(&state.tracer, "POST /analyze", SpanKind::Server);
+            let response = instrument(span.as_ref(), analyze(&state, request)).await;
+            if let Some(span) = &mut span {
+                span.set_attribute("http.request.method", "POST");
+                span.set_attribute("url.path", "/analyze");
+                span.set_attribute("http.response.status_code", response.status().as_u16() as usize);
+            }
+            response
+        }
+        (Method::GET, "/usage") => usage(&state, &request),
+        (Method::GET, "/metrics") => metrics_response(&state.metrics),
+        (Method::POST, "/webhook") => webhook(state, request).await,
+        (_, "/health" | "/analyze" | "/usage" | "/metrics" | "/webhook") => error_response(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed"),
+        _ => error_response(StatusCode::NOT_FOUND, "Not found"),
+    };
+    // Unknown paths share one label, so scanners can't create a series per path
+    let path = match route.1.as_str() {
+        path @ ("/health" | "/analyze" | "/usage" | "/metrics" | "/webhook") => path,
+        _ => "other",
+    };
+    metrics.record_http_request(path, response.status().as_u16());
+    Ok(response)
+}
+
+fn metrics_response(metrics: &Metrics) -> Response<Body> {
+    Response::builder()
+        .header("Content-Type", "text/plain; version=0.0.4")
+        .body(Body::from(metrics.render()))
+        .unwrap()
+}
+
+fn json_response(status: StatusCode, body: Value) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Body> {
+    json_response(status, json!({ "error": message }))
+}
+
+/// Reads the body chunk by chunk and stops as soon as it gets larger than
+/// `MAX_BODY_SIZE`, so an oversized request is never held in memory.
+async fn read_body(mut body: Body) -> Result<Vec<u8>, Response<Body>> {
+    let too_large = || error_response(StatusCode::PAYLOAD_TOO_LARGE, "Request body too large");
+    // The lower size hint is the Content-Length, when the client sent one
+    if body.size_hint().lower() > MAX_BODY_SIZE as u64 {
+        return Err(too_large());
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|e| error_response(StatusCode::BAD_REQUEST, &format!("Could not read the request body: {}", e)))?;
+        if bytes.len() + chunk.len() > MAX_BODY_SIZE {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+/// The API key `request` was made with; `None` when no keys are configured.
+fn authenticate<'a>(state: &'a ServerState, request: &Request<Body>) -> Result<Option<&'a ApiKeyConfig>, Box<Response<Body>>> {
+    if !state.api_keys.is_enabled() {
+        return Ok(None);
+    }
+    let presented = header(request, "Authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or_else(|| header(request, "X-API-Key"));
+    match presented.and_then(|key| state.api_keys.authenticate(key)) {
+        Some(key) => Ok(Some(key)),
+        None => {
+            let mut response = error_response(StatusCode::UNAUTHORIZED, "Missing or unknown API key");
+            response.headers_mut().insert("WWW-Authenticate", hyper::header::HeaderValue::from_static("Bearer"));
+            Err(Box::new(response))
+        }
+    }
+}
+
+async fn analyze(state: &ServerState, request: Request<Body>) -> Response<Body> {
+    let key = match authenticate(state, &request) {
+        Ok(key) => key,
+        Err(response) => return *response,
+    };
+    let body = match read_body(request.into_body()).await {
+        Ok(body) => body,
+        Err(response) => return response,
+    };
+    let Some(key) = key else {
+        return analyze_text(state, &state.analyzer, &body).await;
+    };
+
+    if let Err(exceeded) = state.api_keys.acquire(key, chrono::Local::now().naive_local()) {
+        log_usage(state, key, StatusCode::TOO_MANY_REQUESTS, &Default::default());
+        let mut response = error_response(StatusCode::TOO_MANY_REQUESTS, &exceeded.to_string());
+        response.headers_mut().insert("Retry-After", exceeded.retry_after.into());
+        return response;
+    }
+
+    // A separate usage counter, so the LLM calls of this request are charged to its key
+    let analyzer = state.analyzer.with_separate_usage();
+    let response = analyze_text(state, &analyzer, &body).await;
+    let usage = analyzer.llm_usage();
+    state.api_keys.record(key, &usage, usage.estimated_cost(&state.config.llm.provider, &state.config.llm.model));
+    log_usage(state, key, response.status(), &usage);
+    response
+}
+
+fn log_usage(state: &ServerState, key: &ApiKeyConfig, status: StatusCode, usage: &crate::budget::LlmUsage) {
+    let record = UsageRecord {
+        timestamp: chrono::Local::now().to_rfc3339(),
+        key: &key.name,
+        endpoint: "/analyze",
+        status: status.as_u16(),
+        llm_calls: usage.calls,
+        estimated_tokens: usage.estimated_tokens(),
+    };
+    if let Err(e) = state.api_keys.log(&record) {
+        eprintln!("⚠️  Could not write the usage log: {}", e);
+    }
+}
+
+fn usage(state: &ServerState, request: &Request<Body>) -> Response<Body> {
+    let key = match authenticate(state, request) {
+        Ok(Some(key)) => key,
+        Ok(None) => return error_response(StatusCode::NOT_FOUND, "No API keys configured"),
+        Err(response) => return *response,
+    };
+    json_response(StatusCode::OK, json!({
+        "key": key.name,
+        "limits": {
+            "requests_per_minute": key.requests_per_minute,
+            "requests_per_day": key.requests_per_day,
+        },
+        "usage": state.api_keys.usage(key),
+    }))
+}
+
+async fn analyze_text(state: &ServerState, analyzer: &Analyzer, body: &[u8]) -> Response<Body> {
+    let body = String::from_utf8_lossy(body);
+    let text = match serde_json::from_str::<Value>(&body) {
+        Ok(Value::Object(object)) => object.get("text").and_then(Value::as_str).unwrap_or_default().to_string(),
+        _ => body.to_string(),
+    };
+    if text.trim().is_empty() {
+        return error_response(StatusCode::BAD_REQUEST, "No requirement text given");
+    }
+
+    let started = std::time::Instant::now();
+    let result = analyzer.analyze(&text).await;
+    state.metrics.record_analysis("api", result.is_ok(), started.elapsed());
+    match result {
+        Ok(result) => json_response(StatusCode::OK, serde_json::to_value(&result).unwrap_or_default()),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+fn header<'a>(request: &'a Request<Body>, name: &str) -> Option<&'a str> {
+    request.headers().get(name).and_then(|v| v.to_str().ok())
+}
+
+async fn webhook(state: Arc<ServerState>, request: Request<Body>) -> Response<Body> {
+    // Without a secret anyone could trigger analyses, and comments with the configured tokens
+    let settings = &state.config.server.webhook;
+    let Some(secret) = settings.secret() else {
+        return error_response(StatusCode::FORBIDDEN, "Webhook disabled - set server.webhook.secret");
+    };
+    let Some((forge, event)) = Forge::detect(header(&request, "X-GitHub-Event"), header(&request, "X-Gitlab-Event")) else {
+        return error_response(StatusCode::BAD_REQUEST, "Not a GitHub or GitLab webhook event");
+    };
+    let signature = match forge {
+        Forge::GitHub => header(&request, "X-Hub-Signature-256"),
+        Forge::GitLab => header(&request, "X-Gitlab-Token"),
+    }
+    .map(str::to_string);
+    let body = match read_body(request.into_body()).await {
+        Ok(body) => body,
+        Err(response) => return response,
+    };
+
+    if !crate::webhook::verify_event(forge, &secret, &body, signature.as_deref()) {
+        return error_response(StatusCode::UNAUTHORIZED, "Invalid webhook signature");
+    }
+    if !forge.is_push(&event) {
+        return json_response(StatusCode::OK, json!({ "status": "ignored", "event": event }));
+    }
+
+    let payload: Value = match serde_json::from_slice(&body) {
+        Ok(payload) => payload,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, &format!("Invalid event payload: {}", e)),
+    };
+    let push = match PushEvent::parse(forge, &payload) {
+        Ok(Some(push)) => push,
+        Ok(None) => return json_response(StatusCode::OK, json!({ "status": "ignored", "event": "branch deleted" })),
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, &e.to_string()),
+    };
+
+    let files = push.requirement_files(&settings.paths, |file| state.document_processor.is_supported_format(file));
+    if files.is_empty() {
+        return json_response(StatusCode::OK, json!({ "status": "no requirement files changed" }));
+    }
+
+    // Forges give up on webhooks that take more than a few seconds, so the analysis runs after answering
+    println!("📬 Push to {} ({}): analyzing {} requirement file(s)", push.repository, push.branch, files.len());
+    let response = json_response(StatusCode::ACCEPTED, json!({ "status": "accepted", "commit": push.commit, "files": files }));
+    tokio::spawn(async move {
+        let mut span = start_span(&state.tracer, "prism.webhook.push", SpanKind::Internal);
+        if let Some(span) = &mut span {
+            span.set_attribute("prism.repository", push.repository.as_str());
+            span.set_attribute("prism.commit", push.commit.as_str());
+            span.set_attribute("prism.file_count", files.len());
+        }
+        if let Err(e) = instrument(span.as_ref(), check_push(&state, &push, &files)).await {
+            if let Some(span) = &mut span {
+                span.set_error(&e);
+            }
+            eprintln!("❌ Webhook analysis of {} at {} failed: {}", push.repository, push.commit, e);
+        }
+    });
+    response
+}
+
+/// Analyzes the pushed requirement files and comments the results on the commit.
+async fn check_push(state: &ServerState, push: &PushEvent, files: &[String]) -> Result<()> {
+    let settings = &state.config.server.webhook;
+    let client = match push.forge {
+        Forge::GitHub => ForgeClient::new(Forge::GitHub, &settings.github_api_url, settings.github_token()),
+        Forge::GitLab => ForgeClient::new(Forge::GitLab, &settings.gitlab_api_url, settings.gitlab_token()),
+    };
+
+    let work_dir = create_private_temp_dir(&format!("prism-webhook-{}", push.commit))?;
+    let mut results = Vec::new();
+    for file in files {
+        match analyze_pushed_file(state, &client, push, file, &work_dir).await {
+            Ok(result) => {
+                println!("✅ {}: {} finding(s)", file, result.ambiguities.len());
+                results.push((file.clone(), result));
+            }
+            Err(e) => eprintln!("⚠️  Could not analyze {}: {}", file, e),
+        }
+    }
+    let _ = std::fs::remove_dir_all(&work_dir);
+    if results.is_empty() {
+        return Err(anyhow::anyhow!("None of the changed requirement files could be analyzed"));
+    }
+
+    let has_token = match push.forge {
+        Forge::GitHub => settings.github_token().is_some(),
+        Forge::GitLab => settings.gitlab_token().is_some(),
+    };
+    if has_token {
+        client.comment_on_commit(push, &format_push_comment(&push.commit, &results)).await?;
+        println!("💬 Results commented on {} at {}", push.repository, &push.commit[..push.commit.len().min(7)]);
+    } else {
+        println!("💡 Set a GitHub or GitLab token under server.webhook to comment the results on the commit");
+    }
+    Ok(())
+}
+
+async fn analyze_pushed_file(
+    state: &ServerState,
+    client: &ForgeClient,
+    push: &PushEvent,
+    file: &str,
+    work_dir: &Path,
+) -> Result<AnalysisResult> {
+    if !Path::new(file).components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(anyhow::anyhow!("Unexpected file path"));
+    }
+    let content = client.fetch_file(push, file).await?;
+    // Documents are read from disk, so PDF, Word and Excel files work as well as text
+    let local_path = work_dir.join(file);
+    if let Some(parent) = local_path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(&local_path, content).await?;
+    let text = state.document_processor.extract_text_from_file(&local_path).await?;
+    let started = std::time::Instant::now();
+    let result = state.analyzer.analyze(&text).await;
+    state.metrics.record_analysis("webhook", result.is_ok(), started.elapsed());
+    result
+}
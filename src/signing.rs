@@ -0,0 +1,166 @@
+//! Detached signatures for reports and evidence bundles, made with
+//! minisign or Sigstore's cosign, so whoever receives a report can check
+//! that it was not changed after PRISM generated it.
+
+use anyhow::{bail, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use crate::config::SigningConfig;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SigningTool {
+    Minisign,
+    Sigstore,
+}
+
+impl SigningTool {
+    pub fn program(&self) -> &'static str {
+        match self {
+            SigningTool::Minisign => "minisign",
+            SigningTool::Sigstore => "cosign",
+        }
+    }
+
+    fn install_hint(&self) -> &'static str {
+        match self {
+            SigningTool::Minisign => "install it from https://jedisct1.github.io/minisign/",
+            SigningTool::Sigstore => "install cosign from https://docs.sigstore.dev/cosign/system_config/installation/",
+        }
+    }
+
+    /// The signature written next to `file`: `report.md.minisig` or
+    /// `report.md.sigstore.json`.
+    pub fn signature_path(&self, file: &Path) -> PathBuf {
+        let extension = match self {
+            SigningTool::Minisign => "minisig",
+            SigningTool::Sigstore => "sigstore.json",
+        };
+        let mut path = file.as_os_str().to_owned();
+        path.push(format!(".{}", extension));
+        PathBuf::from(path)
+    }
+}
+
+/// Signs and verifies files with the configured tool.
+#[derive(Debug, Clone)]
+pub struct Signer {
+    tool: SigningTool,
+    config: SigningConfig,
+}
+
+impl Signer {
+    /// Fails when `signing.tool` is not set.
+    pub fn from_config(config: &SigningConfig) -> Result<Self> {
+        let Some(tool) = config.tool else {
+            bail!("Signing is not set up - set signing.tool to minisign or sigstore in ~/.prism/config.yml");
+        };
+        Ok(Self { tool, config: config.clone() })
+    }
+
+    pub fn tool(&self) -> SigningTool {
+        self.tool
+    }
+
+    /// Arguments that sign `file`. minisign's trusted comment names the file
+    /// and the time of signing, and is covered by the signature too.
+    pub fn sign_args(&self, file: &Path) -> Vec<OsString> {
+        let signature = self.tool.signature_path(file);
+        let mut args: Vec<OsString> = Vec::new();
+        match self.tool {
+            SigningTool::Minisign => {
+                args.extend(["-S".into(), "-m".into(), file.into(), "-x".into(), signature.into()]);
+                if let Some(key) = &self.config.key {
+                    args.extend(["-s".into(), key.into()]);
+                }
+                let name = file.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+                let comment = format!("prism {} file:{} signed:{}", env!("CARGO_PKG_VERSION"), name, Utc::now().format("%Y-%m-%dT%H:%M:%SZ"));
+                args.extend(["-t".into(), comment.into()]);
+            }
+            SigningTool::Sigstore => {
+                args.extend(["sign-blob".into(), "--yes".into()]);
+                // Without a key cosign signs keyless, with a certificate for the OIDC login
+                if let Some(key) = &self.config.key {
+                    args.extend(["--key".into(), key.into()]);
+                }
+                args.extend(["--bundle".into(), signature.into(), file.into()]);
+            }
+        }
+        args
+    }
+
+    /// Arguments that verify `file` against its signature. Fails without a
+    /// public key, or for keyless Sigstore signatures without the identity
+    /// they have to carry.
+    pub fn verify_args(&self, file: &Path) -> Result<Vec<OsString>> {
+        let signature = self.tool.signature_path(file);
+        let mut args: Vec<OsString> = Vec::new();
+        match self.tool {
+            SigningTool::Minisign => {
+                let Some(public_key) = &self.config.public_key else {
+                    bail!("Set signing.public_key to the minisign public key (.pub) to verify with");
+                };
+                args.extend(["-V".into(), "-m".into(), file.into(), "-x".into(), signature.into(), "-p".into(), public_key.into()]);
+            }
+            SigningTool::Sigstore => {
+                args.extend(["verify-blob".into(), "--bundle".into(), signature.into()]);
+                match (&self.config.public_key, &self.config.certificate_identity, &self.config.certificate_oidc_issuer) {
+                    (Some(public_key), _, _) => args.extend(["--key".into(), public_key.into()]),
+                    (None, Some(identity), Some(issuer)) => args.extend([
+                        "--certificate-identity".into(), identity.into(),
+                        "--certificate-oidc-issuer".into(), issuer.into(),
+                    ]),
+                    _ => bail!("Set signing.public_key, or signing.certificate_identity and signing.certificate_oidc_issuer for keyless signatures, to verify with"),
+                }
+                args.push(file.into());
+            }
+        }
+        Ok(args)
+    }
+
+    /// Signs `file`, returning the signature's path.
+    pub async fn sign(&self, file: &Path) -> Result<PathBuf> {
+        if !file.is_file() {
+            bail!("Cannot sign {}: no such file", file.display());
+        }
+        self.run(self.sign_args(file)).await?;
+        Ok(self.tool.signature_path(file))
+    }
+
+    /// Verifies `file`, returning what the tool reports, such as minisign's
+    /// trusted comment.
+    pub async fn verify(&self, file: &Path) -> Result<String> {
+        let signature = self.tool.signature_path(file);
+        if !signature.is_file() {
+            bail!("No signature for {}: {} is missing", file.display(), signature.display());
+        }
+        self.run(self.verify_args(file)?).await
+    }
+
+    /// Runs the tool, leaving the terminal to it so it can ask for a key's
+    /// password or an OIDC login and report its own errors.
+    async fn run(&self, args: Vec<OsString>) -> Result<String> {
+        let program = self.tool.program();
+        let output = tokio::process::Command::new(program)
+            .args(&args)
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => anyhow::anyhow!("{} is not installed - {}", program, self.tool.install_hint()),
+                _ => anyhow::anyhow!("Could not run {}: {}", program, e),
+            })?
+            .wait_with_output()
+            .await?;
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !output.status.success() {
+            bail!("{} failed ({}){}", program, output.status, if stdout.is_empty() { String::new() } else { format!(": {}", stdout) });
+        }
+        Ok(stdout)
+    }
+}
@@ -0,0 +1,306 @@
+//! SMART scoring of individual requirements: how Specific, Measurable,
+//! Achievable, Relevant and Time-bound each one is, with what to change
+//! and a rewrite that has placeholders for the missing detail.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::analyzer::Analyzer;
+use crate::dependencies::extract_requirement_nodes;
+
+/// Words that leave open what exactly is meant.
+const VAGUE: &str = r"(?i)\b(fast|quick|quickly|slow|easy|easily|hard|user-friendly|intuitive|robust|scalable|efficient|efficiently|better|good|nice|appropriate|adequate|flexible|seamless|many|few|some|several|various|multiple)\b";
+
+/// Qualities that need a threshold before they can be tested.
+const QUALITATIVE: &str = r"(?i)\b(fast|quick|quickly|slow|responsive|scalable|efficient|efficiently|performant|reliable|secure|available|easy|easily|user-friendly|intuitive|robust|high|low|large|small)\b";
+
+/// Promises no system keeps.
+const ABSOLUTES: &str = r"(?i)(\b(?:always|never|instantly|instantaneously|immediately|unlimited|infinite|guaranteed?|zero downtime|at all times)\b|\b100\s?%)";
+
+const RATIONALE: &str = r"(?i)\b(so that|in order to|because|to ensure|to allow|to enable|to comply|to meet|to reduce|to prevent|to avoid|to support)\b";
+
+const TIME_FRAME: &str = r"(?i)\b(within|until|no later than|daily|weekly|monthly|hourly|yearly|annually|every|deadline|sprint|release|milestone|q[1-4]|by\s+(?:the\s+end\s+of|\d|q[1-4]\b|release|v\d|(?:jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)[a-z]*)|per\s+(?:second|minute|hour|day|week|month)|\d+\s*(?:ms|milliseconds?|s|secs?|seconds?|mins?|minutes?|h|hrs?|hours?|days?|weeks?|months?|years?))\b";
+
+const MODALS: &str = r"(?i)\b(shall|must|should|will)\b";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SmartAxis {
+    Specific,
+    Measurable,
+    Achievable,
+    Relevant,
+    TimeBound,
+}
+
+impl SmartAxis {
+    pub const ALL: [SmartAxis; 5] = [SmartAxis::Specific, SmartAxis::Measurable, SmartAxis::Achievable, SmartAxis::Relevant, SmartAxis::TimeBound];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SmartAxis::Specific => "Specific",
+            SmartAxis::Measurable => "Measurable",
+            SmartAxis::Achievable => "Achievable",
+            SmartAxis::Relevant => "Relevant",
+            SmartAxis::TimeBound => "Time-bound",
+        }
+    }
+}
+
+/// 0-100 per axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmartScores {
+    pub specific: u8,
+    pub measurable: u8,
+    pub achievable: u8,
+    pub relevant: u8,
+    pub time_bound: u8,
+}
+
+impl SmartScores {
+    pub fn get(&self, axis: SmartAxis) -> u8 {
+        match axis {
+            SmartAxis::Specific => self.specific,
+            SmartAxis::Measurable => self.measurable,
+            SmartAxis::Achievable => self.achievable,
+            SmartAxis::Relevant => self.relevant,
+            SmartAxis::TimeBound => self.time_bound,
+        }
+    }
+
+    /// The mean of the five axes.
+    pub fn overall(&self) -> f32 {
+        SmartAxis::ALL.iter().map(|a| self.get(*a) as f32).sum::<f32>() / SmartAxis::ALL.len() as f32
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmartIssue {
+    pub axis: SmartAxis,
+    /// What to change, e.g. "'fast' can't be tested: give a threshold"
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmartRequirement {
+    /// The requirement's own ID, else "line N"
+    pub id: String,
+    pub line: usize,
+    pub text: String,
+    pub scores: SmartScores,
+    pub issues: Vec<SmartIssue>,
+    /// The requirement with placeholders for what is missing; None when nothing is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rewrite: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SmartAnalysis {
+    pub requirements: Vec<SmartRequirement>,
+}
+
+impl SmartAnalysis {
+    /// Mean score of `axis` over the requirements; 0 when there are none.
+    pub fn average(&self, axis: SmartAxis) -> f32 {
+        if self.requirements.is_empty() {
+            return 0.0;
+        }
+        self.requirements.iter().map(|r| r.scores.get(axis) as f32).sum::<f32>() / self.requirements.len() as f32
+    }
+
+    pub fn overall(&self) -> f32 {
+        if self.requirements.is_empty() {
+            return 0.0;
+        }
+        self.requirements.iter().map(|r| r.scores.overall()).sum::<f32>() / self.requirements.len() as f32
+    }
+}
+
+fn matches(pattern: &Regex, text: &str) -> Vec<String> {
+    let mut found: Vec<String> = Vec::new();
+    for m in pattern.find_iter(text) {
+        let term = m.as_str().to_lowercase();
+        if !found.contains(&term) {
+            found.push(term);
+        }
+    }
+    found
+}
+
+fn penalized(penalty: usize) -> u8 {
+    100usize.saturating_sub(penalty).max(10) as u8
+}
+
+/// What an absolute promise becomes in a rewrite, and what to add at the
+/// end of the sentence: "shall always respond" -> "shall respond in
+/// [99.9]% of cases".
+fn absolute_replacement(term: &str) -> Option<(&'static str, &'static str)> {
+    match term {
+        "always" | "at all times" => Some(("", " in [99.9]% of cases")),
+        "never" => Some(("not", " in more than [0.1]% of cases")),
+        "instantly" | "instantaneously" | "immediately" => Some(("within [N] milliseconds", "")),
+        "unlimited" | "infinite" => Some(("up to [N]", "")),
+        "zero downtime" => Some(("[99.9]% availability", "")),
+        t if t.starts_with("100") => Some(("[99.9]%", "")),
+        _ => None,
+    }
+}
+
+fn replace_term(text: &str, term: &str, replacement: &str) -> String {
+    let end = if term.ends_with(|c: char| c.is_alphanumeric()) { r"\b" } else { "" };
+    let replaced = Regex::new(&format!(r"(?i)\b{}{}", regex::escape(term), end)).unwrap().replace_all(text, replacement).to_string();
+    replaced.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Scores one requirement statement and writes what each weak axis needs.
+pub fn score_requirement(id: &str, line: usize, text: &str) -> SmartRequirement {
+    let vague = Regex::new(VAGUE).unwrap();
+    let qualitative = Regex::new(QUALITATIVE).unwrap();
+    let absolutes = Regex::new(ABSOLUTES).unwrap();
+    let rationale = Regex::new(RATIONALE).unwrap();
+    let time_frame = Regex::new(TIME_FRAME).unwrap();
+    let modals = Regex::new(MODALS).unwrap();
+    let leading_id = Regex::new(r"^[*_\[(]*[A-Z][A-Z0-9]*-\d+[*_\])]*\s*[:.)-]?\s*").unwrap();
+
+    let statement = leading_id.replace(text.trim(), "").trim().to_string();
+    let mut issues = Vec::new();
+    let mut issue = |axis: SmartAxis, message: String| issues.push(SmartIssue { axis, message });
+
+    // Specific: nothing vague, open-ended or bundled
+    let vague_terms = matches(&vague, &statement);
+    for term in &vague_terms {
+        issue(SmartAxis::Specific, format!("'{}' is vague: say exactly what is meant", term));
+    }
+    let open_ended = matches(&Regex::new(r"(?i)(\betc\b\.?|\band/or\b|\be\.g\.)").unwrap(), &statement);
+    for term in &open_ended {
+        issue(SmartAxis::Specific, format!("'{}' leaves the list open: spell out every case", term));
+    }
+    let pronoun_start = Regex::new(r"(?i)^(it|this|that|they|these|those)\b").unwrap().is_match(&statement);
+    if pronoun_start {
+        issue(SmartAxis::Specific, "Starts with a pronoun: name the system or actor".to_string());
+    }
+    let bundled = modals.find_iter(&statement).count() > 1;
+    if bundled {
+        issue(SmartAxis::Specific, "States more than one requirement: split it".to_string());
+    }
+    let specific = penalized(vague_terms.len() * 20 + open_ended.len() * 15 + pronoun_start as usize * 25 + bundled as usize * 20);
+
+    // Measurable: a number, else at least no quality without a threshold
+    let has_number = statement.chars().any(|c| c.is_ascii_digit());
+    let qualities = matches(&qualitative, &statement);
+    let measurable = if has_number {
+        100
+    } else if let Some(quality) = qualities.first() {
+        let threshold = Analyzer::vague_term_replacement(quality);
+        let example = if threshold.starts_with("[measurable") { String::new() } else { format!(" ({})", threshold) };
+        issue(SmartAxis::Measurable, format!("'{}' can't be tested: give a threshold{}", quality, example));
+        20
+    } else {
+        80
+    };
+
+    // Achievable: no absolute promises
+    let absolute_terms = matches(&absolutes, &statement);
+    for term in &absolute_terms {
+        issue(SmartAxis::Achievable, format!("'{}' is an absolute no system keeps: state the target", term));
+    }
+    let achievable = penalized(absolute_terms.len() * 35);
+
+    // Relevant: says why it matters
+    let relevant = if rationale.is_match(&statement) {
+        100
+    } else {
+        issue(SmartAxis::Relevant, "Doesn't say why it matters: add \"so that [benefit]\"".to_string());
+        50
+    };
+
+    // Time-bound: says by when or how often
+    let time_bound = if time_frame.is_match(&statement) {
+        100
+    } else {
+        issue(SmartAxis::TimeBound, "Doesn't say when: add a response time, frequency or delivery date".to_string());
+        40
+    };
+
+    let rewrite = (!issues.is_empty()).then(|| {
+        let mut rewrite = statement.trim_end_matches(['.', '!', ';']).to_string();
+        if pronoun_start {
+            rewrite = Regex::new(r"^\w+").unwrap().replace(&rewrite, "[The system]").to_string();
+        }
+        for term in &vague_terms {
+            rewrite = replace_term(&rewrite, term, &Analyzer::vague_term_replacement(term));
+        }
+        for term in &open_ended {
+            let replacement = if term.starts_with("etc") { "and [the remaining cases]" } else if term == "and/or" { "[and | or]" } else { "[exactly]" };
+            rewrite = replace_term(&rewrite, term.trim_end_matches('.'), replacement);
+        }
+        for term in &absolute_terms {
+            if let Some((replacement, suffix)) = absolute_replacement(term) {
+                rewrite = replace_term(&rewrite, term, replacement);
+                rewrite.push_str(suffix);
+            }
+        }
+        if !time_frame.is_match(&rewrite) {
+            rewrite.push_str(" within [time frame]");
+        }
+        if !rationale.is_match(&rewrite) {
+            rewrite.push_str(" so that [benefit]");
+        }
+        format!("{}.", rewrite)
+    });
+
+    SmartRequirement {
+        id: id.to_string(),
+        line,
+        text: statement,
+        scores: SmartScores { specific, measurable, achievable, relevant, time_bound },
+        issues,
+        rewrite,
+    }
+}
+
+/// Scores every requirement of `text`: lines with list items, user stories,
+/// requirement verbs or a leading ID.
+pub fn analyze_smart(text: &str) -> SmartAnalysis {
+    let requirements = extract_requirement_nodes(text).into_iter()
+        .map(|node| score_requirement(&node.id, node.line, &node.text))
+        .collect();
+    SmartAnalysis { requirements }
+}
+
+pub fn format_smart_markdown(analysis: &SmartAnalysis) -> String {
+    if analysis.requirements.is_empty() {
+        return "No requirements found to score.\n\n".to_string();
+    }
+    let averages: Vec<String> = SmartAxis::ALL.iter().map(|a| format!("{} {:.0}", a.label(), analysis.average(*a))).collect();
+    let mut output = format!(
+        "**Average over {} requirements:** {} | **Overall {:.0}**\n\n",
+        analysis.requirements.len(), averages.join(" · "), analysis.overall()
+    );
+    output.push_str("| Requirement | S | M | A | R | T | Overall |\n|---|---|---|---|---|---|---|\n");
+    for requirement in &analysis.requirements {
+        let s = &requirement.scores;
+        output.push_str(&format!(
+            "| **{}** {} | {} | {} | {} | {} | {} | {:.0} |\n",
+            requirement.id, requirement.text.replace('|', "\\|"),
+            s.specific, s.measurable, s.achievable, s.relevant, s.time_bound, s.overall()
+        ));
+    }
+    output.push('\n');
+
+    let weak: Vec<&SmartRequirement> = analysis.requirements.iter().filter(|r| !r.issues.is_empty()).collect();
+    if !weak.is_empty() {
+        output.push_str("### Making Them SMART\n\n");
+        for requirement in weak {
+            output.push_str(&format!("**{}** (line {})\n\n", requirement.id, requirement.line));
+            for issue in &requirement.issues {
+                output.push_str(&format!("- **{}:** {}\n", issue.axis.label(), issue.message));
+            }
+            if let Some(rewrite) = &requirement.rewrite {
+                output.push_str(&format!("\n> {}\n", rewrite));
+            }
+            output.push('\n');
+        }
+    }
+    output
+}
@@ -0,0 +1,206 @@
+//! Findings for spreadsheets: one row per ambiguity, completeness gap and
+//! suggested NFR, as CSV (`--format csv`) or as an Excel workbook
+//! (`--output findings.xlsx`), so product managers can sort, filter and
+//! assign them.
+
+use anyhow::Result;
+use std::io::{Seek, Write};
+use std::path::Path;
+
+use crate::analyzer::{AnalysisResult, NfrPriority};
+use crate::text::escape_xml;
+
+pub const FINDING_COLUMNS: [&str; 10] = [
+    "Type", "Severity", "Category", "Text", "Details", "Suggestions", "File", "Line", "Column", "Fingerprint",
+];
+
+/// One finding, in the order of [`FINDING_COLUMNS`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FindingRow {
+    /// "Ambiguity", "Gap" or "NFR"
+    pub kind: &'static str,
+    pub severity: String,
+    pub category: String,
+    pub text: String,
+    pub details: String,
+    pub suggestions: Vec<String>,
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub fingerprint: String,
+}
+
+impl FindingRow {
+    /// The cells of the row, suggestions joined with `separator`.
+    fn cells(&self, separator: &str) -> [String; 10] {
+        [
+            self.kind.to_string(),
+            self.severity.clone(),
+            self.category.clone(),
+            self.text.clone(),
+            self.details.clone(),
+            self.suggestions.join(separator),
+            self.file.clone().unwrap_or_default(),
+            self.line.map(|line| line.to_string()).unwrap_or_default(),
+            self.column.map(|column| column.to_string()).unwrap_or_default(),
+            self.fingerprint.clone(),
+        ]
+    }
+}
+
+fn nfr_priority(priority: &NfrPriority) -> &'static str {
+    match priority {
+        NfrPriority::MustHave => "Must Have",
+        NfrPriority::ShouldHave => "Should Have",
+        NfrPriority::CouldHave => "Could Have",
+        NfrPriority::WontHave => "Won't Have",
+    }
+}
+
+/// The rows of `result`: its ambiguities, then its completeness gaps and
+/// NFR suggestions when they were generated. `source_name` is the file of
+/// rows without a location of their own.
+pub fn finding_rows(result: &AnalysisResult, source_name: Option<&str>) -> Vec<FindingRow> {
+    let mut rows: Vec<FindingRow> = result.ambiguities.iter()
+        .map(|ambiguity| FindingRow {
+            kind: "Ambiguity",
+            severity: ambiguity.severity.to_string(),
+            category: ambiguity.rule_id.clone(),
+            text: ambiguity.text.clone(),
+            details: ambiguity.reason.clone(),
+            suggestions: ambiguity.suggestions.clone(),
+            file: ambiguity.location.as_ref().and_then(|l| l.file.clone()).or_else(|| source_name.map(str::to_string)),
+            line: ambiguity.location.as_ref().map(|l| l.line),
+            column: ambiguity.location.as_ref().map(|l| l.column),
+            fingerprint: ambiguity.fingerprint.clone(),
+        })
+        .collect();
+
+    let row = |kind, severity: String, category: String, text: &str, details: &str, suggestions: &[String]| FindingRow {
+        kind,
+        severity,
+        category,
+        text: text.to_string(),
+        details: details.to_string(),
+        suggestions: suggestions.to_vec(),
+        file: source_name.map(str::to_string),
+        line: None,
+        column: None,
+        fingerprint: String::new(),
+    };
+    if let Some(completeness) = &result.completeness_analysis {
+        rows.extend(completeness.gaps_identified.iter()
+            .map(|gap| row("Gap", format!("{:?}", gap.priority), gap.category.clone(), &gap.description, "", &gap.suggestions)));
+    }
+    if let Some(nfrs) = &result.nfr_suggestions {
+        rows.extend(nfrs.iter()
+            .map(|nfr| row("NFR", nfr_priority(&nfr.priority).to_string(), format!("{:?}", nfr.category), &nfr.requirement, &nfr.rationale, &nfr.acceptance_criteria)));
+    }
+    rows
+}
+
+/// A CSV cell: quoted when it has to be, and kept from being read as a
+/// formula by spreadsheets.
+pub(crate) fn csv_cell(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) { format!("'{}", value) } else { value.to_string() };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// The findings of `result` as CSV with a header row; suggestions are
+/// separated by "; ".
+pub fn format_findings_csv(result: &AnalysisResult, source_name: Option<&str>) -> String {
+    let mut output = FINDING_COLUMNS.join(",");
+    output.push_str("\r\n");
+    for row in finding_rows(result, source_name) {
+        output.push_str(&row.cells("; ").iter().map(|cell| csv_cell(cell)).collect::<Vec<_>>().join(","));
+        output.push_str("\r\n");
+    }
+    output
+}
+
+/// Whether `path` names an Excel workbook.
+pub fn is_xlsx_path(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("xlsx"))
+}
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/></Types>"#;
+
+const PACKAGE_RELATIONSHIPS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+
+const WORKBOOK: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Findings" sheetId="1" r:id="rId1"/></sheets></workbook>"#;
+
+const WORKBOOK_RELATIONSHIPS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>"#;
+
+/// Style 1 is the bold header, style 2 wraps long text at the top of the cell.
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><fonts count="2"><font><sz val="11"/><name val="Calibri"/></font><font><b/><sz val="11"/><name val="Calibri"/></font></fonts><fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills><borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders><cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs><cellXfs count="3"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/><xf numFmtId="0" fontId="1" fillId="0" borderId="0" xfId="0" applyFont="1"/><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0" applyAlignment="1"><alignment vertical="top" wrapText="1"/></xf></cellXfs></styleSheet>"#;
+
+/// Column widths in characters, in the order of [`FINDING_COLUMNS`].
+const COLUMN_WIDTHS: [u8; 10] = [11, 10, 16, 36, 48, 48, 24, 7, 8, 34];
+
+/// The spreadsheet column letter of column `index`, counting from 0.
+fn column_letter(index: usize) -> char {
+    (b'A' + index as u8) as char
+}
+
+fn format_sheet(rows: &[FindingRow]) -> String {
+    let mut sheet = String::from(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetViews><sheetView workbookViewId="0"><pane ySplit="1" topLeftCell="A2" activePane="bottomLeft" state="frozen"/></sheetView></sheetViews><cols>"#);
+    for (i, width) in COLUMN_WIDTHS.iter().enumerate() {
+        sheet.push_str(&format!(r#"<col min="{0}" max="{0}" width="{1}" customWidth="1"/>"#, i + 1, width));
+    }
+    sheet.push_str("</cols><sheetData>");
+
+    sheet.push_str(r#"<row r="1">"#);
+    for (i, header) in FINDING_COLUMNS.iter().enumerate() {
+        sheet.push_str(&format!(r#"<c r="{}1" s="1" t="inlineStr"><is><t>{}</t></is></c>"#, column_letter(i), header));
+    }
+    sheet.push_str("</row>");
+    for (number, row) in rows.iter().enumerate().map(|(i, row)| (i + 2, row)) {
+        sheet.push_str(&format!(r#"<row r="{}">"#, number));
+        for (i, cell) in row.cells("\n").iter().enumerate() {
+            let reference = format!("{}{}", column_letter(i), number);
+            let numeric = matches!(FINDING_COLUMNS[i], "Line" | "Column");
+            if cell.is_empty() {
+                continue;
+            } else if numeric {
+                sheet.push_str(&format!(r#"<c r="{}" s="2"><v>{}</v></c>"#, reference, cell));
+            } else {
+                sheet.push_str(&format!(r#"<c r="{}" s="2" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#, reference, escape_xml(cell)));
+            }
+        }
+        sheet.push_str("</row>");
+    }
+    let last = column_letter(FINDING_COLUMNS.len() - 1);
+    sheet.push_str(&format!(r#"</sheetData><autoFilter ref="A1:{}{}"/></worksheet>"#, last, rows.len() + 1));
+    sheet
+}
+
+/// Writes `rows` as an Excel workbook with one "Findings" sheet: a bold,
+/// frozen header row with filters, then one row per finding.
+pub fn write_findings_xlsx<W: Write + Seek>(writer: W, rows: &[FindingRow]) -> Result<()> {
+    let sheet = format_sheet(rows);
+    let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut zip = zip::ZipWriter::new(writer);
+    for (path, content) in [
+        ("[Content_Types].xml", CONTENT_TYPES),
+        ("_rels/.rels", PACKAGE_RELATIONSHIPS),
+        ("xl/workbook.xml", WORKBOOK),
+        ("xl/_rels/workbook.xml.rels", WORKBOOK_RELATIONSHIPS),
+        ("xl/styles.xml", STYLES),
+        ("xl/worksheets/sheet1.xml", sheet.as_str()),
+    ] {
+        zip.start_file(path, options)?;
+        zip.write_all(content.as_bytes())?;
+    }
+    zip.finish()?;
+    Ok(())
+}
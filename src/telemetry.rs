@@ -0,0 +1,310 @@
+//! Optional OpenTelemetry traces, exported as OTLP/HTTP JSON: spans around
+//! document processing, each detector and each LLM call, so slow analyses can
+//! be looked at in Jaeger, Tempo, Honeycomb or any other OTLP backend.
+//!
+//! The current span is kept in a task-local, so code called inside
+//! [`instrument`] opens child spans without passing the parent around.
+
+use anyhow::Result;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::collections::hash_map::RandomState;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::hash::BuildHasher;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::TelemetryConfig;
+
+/// Finished spans kept while waiting for an export; older ones are dropped
+/// when a backend is unreachable for long.
+const MAX_BUFFERED_SPANS: usize = 10_000;
+
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+tokio::task_local! {
+    static CURRENT_SPAN: SpanContext;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpanContext {
+    pub trace_id: u128,
+    pub span_id: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanKind {
+    Internal,
+    /// Handling an incoming request
+    Server,
+    /// A call to another service, such as an LLM
+    Client,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+    String(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+impl From<&str> for AttributeValue {
+    fn from(value: &str) -> Self {
+        AttributeValue::String(value.to_string())
+    }
+}
+
+impl From<String> for AttributeValue {
+    fn from(value: String) -> Self {
+        AttributeValue::String(value)
+    }
+}
+
+impl From<usize> for AttributeValue {
+    fn from(value: usize) -> Self {
+        AttributeValue::Int(value as i64)
+    }
+}
+
+impl From<f64> for AttributeValue {
+    fn from(value: f64) -> Self {
+        AttributeValue::Float(value)
+    }
+}
+
+impl From<bool> for AttributeValue {
+    fn from(value: bool) -> Self {
+        AttributeValue::Bool(value)
+    }
+}
+
+/// A span that has ended, waiting to be exported.
+#[derive(Debug, Clone)]
+pub struct SpanData {
+    pub name: String,
+    pub kind: SpanKind,
+    pub context: SpanContext,
+    pub parent_span_id: Option<u64>,
+    pub start: SystemTime,
+    pub end: SystemTime,
+    pub attributes: Vec<(String, AttributeValue)>,
+    /// Set when the operation failed
+    pub error: Option<String>,
+}
+
+pub struct Tracer {
+    endpoint: String,
+    service_name: String,
+    headers: BTreeMap<String, String>,
+    http_client: Client,
+    finished: Mutex<Vec<SpanData>>,
+    random: RandomState,
+    counter: AtomicU64,
+}
+
+impl Tracer {
+    /// `endpoint` is the collector's base URL, e.g. http://localhost:4318, or
+    /// its full `/v1/traces` URL.
+    pub fn new(endpoint: &str, service_name: &str, headers: BTreeMap<String, String>) -> Self {
+        let endpoint = endpoint.trim_end_matches('/');
+        let endpoint = if endpoint.ends_with("/v1/traces") {
+            endpoint.to_string()
+        } else {
+            format!("{}/v1/traces", endpoint)
+        };
+        Self {
+            endpoint,
+            service_name: service_name.to_string(),
+            headers,
+            http_client: Client::new(),
+            finished: Mutex::new(Vec::new()),
+            random: RandomState::new(),
+            counter: AtomicU64::new(0),
+        }
+    }
+
+    /// The configured tracer, or `None` when no OTLP endpoint is set.
+    pub fn from_config(config: &TelemetryConfig) -> Option<Arc<Self>> {
+        let endpoint = config.otlp_endpoint()?;
+        Some(Arc::new(Self::new(&endpoint, &config.service_name, config.headers.clone())))
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn random_u64(&self) -> u64 {
+        // RandomState is randomly keyed, so hashing a counter gives unpredictable, non-repeating ids
+        self.random.hash_one(self.counter.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Starts a span, as a child of the current span when there is one and as
+    /// the root of a new trace otherwise. It ends when dropped.
+    pub fn start_span(self: &Arc<Self>, name: &str, kind: SpanKind) -> Span {
+        let parent = CURRENT_SPAN.try_with(|context| *context).ok();
+        let trace_id = parent.map(|p| p.trace_id)
+            .unwrap_or_else(|| ((self.random_u64() as u128) << 64) | self.random_u64() as u128);
+        Span {
+            tracer: self.clone(),
+            data: Some(SpanData {
+                name: name.to_string(),
+                kind,
+                context: SpanContext { trace_id, span_id: self.random_u64() },
+                parent_span_id: parent.map(|p| p.span_id),
+                start: SystemTime::now(),
+                end: SystemTime::now(),
+                attributes: Vec::new(),
+                error: None,
+            }),
+        }
+    }
+
+    fn finish(&self, span: SpanData) {
+        let mut finished = self.finished.lock().unwrap();
+        if finished.len() >= MAX_BUFFERED_SPANS {
+            finished.remove(0);
+        }
+        finished.push(span);
+    }
+
+    /// Spans that ended and were not exported yet.
+    pub fn finished_spans(&self) -> Vec<SpanData> {
+        self.finished.lock().unwrap().clone()
+    }
+
+    /// Sends the finished spans to the collector and returns how many were sent.
+    /// Spans that could not be sent are kept for the next export.
+    pub async fn flush(&self) -> Result<usize> {
+        let spans = std::mem::take(&mut *self.finished.lock().unwrap());
+        if spans.is_empty() {
+            return Ok(0);
+        }
+
+        let mut request = self.http_client.post(&self.endpoint)
+            .timeout(EXPORT_TIMEOUT)
+            .json(&format_otlp_traces(&self.service_name, &spans));
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        let sent = match request.send().await {
+            Ok(response) if response.status().is_success() => Ok(spans.len()),
+            Ok(response) => Err(anyhow::anyhow!("OTLP collector at {} answered {}", self.endpoint, response.status())),
+            Err(e) => Err(anyhow::anyhow!("Could not reach the OTLP collector at {}: {}", self.endpoint, e)),
+        };
+        if sent.is_err() {
+            let mut finished = self.finished.lock().unwrap();
+            let newer = std::mem::replace(&mut *finished, spans);
+            finished.extend(newer);
+            let excess = finished.len().saturating_sub(MAX_BUFFERED_SPANS);
+            finished.drain(..excess);
+        }
+        sent
+    }
+}
+
+/// A span being recorded. Ends, and is queued for export, when dropped.
+pub struct Span {
+    tracer: Arc<Tracer>,
+    data: Option<SpanData>,
+}
+
+impl Span {
+    pub fn context(&self) -> SpanContext {
+        self.data.as_ref().map(|d| d.context).unwrap_or(SpanContext { trace_id: 0, span_id: 0 })
+    }
+
+    pub fn set_attribute(&mut self, key: &str, value: impl Into<AttributeValue>) {
+        if let Some(data) = &mut self.data {
+            data.attributes.push((key.to_string(), value.into()));
+        }
+    }
+
+    /// Marks the operation as failed.
+    pub fn set_error(&mut self, message: impl std::fmt::Display) {
+        if let Some(data) = &mut self.data {
+            data.error = Some(message.to_string());
+        }
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if let Some(mut data) = self.data.take() {
+            data.end = SystemTime::now();
+            self.tracer.finish(data);
+        }
+    }
+}
+
+/// Runs `future` with `span` as the current span, so spans started inside it
+/// become its children. Without a span the future simply runs.
+pub async fn instrument<F: Future>(span: Option<&Span>, future: F) -> F::Output {
+    match span {
+        Some(span) => CURRENT_SPAN.scope(span.context(), future).await,
+        None => future.await,
+    }
+}
+
+/// Starts a span when tracing is enabled.
+pub fn start_span(tracer: &Option<Arc<Tracer>>, name: &str, kind: SpanKind) -> Option<Span> {
+    tracer.as_ref().map(|tracer| tracer.start_span(name, kind))
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos().to_string()
+}
+
+/// `spans` as an OTLP `ExportTraceServiceRequest` in its JSON encoding.
+pub fn format_otlp_traces(service_name: &str, spans: &[SpanData]) -> Value {
+    let spans: Vec<Value> = spans.iter().map(|span| {
+        let attributes: Vec<Value> = span.attributes.iter().map(|(key, value)| {
+            let value = match value {
+                AttributeValue::String(s) => json!({ "stringValue": s }),
+                AttributeValue::Int(i) => json!({ "intValue": i.to_string() }),
+                AttributeValue::Float(f) => json!({ "doubleValue": f }),
+                AttributeValue::Bool(b) => json!({ "boolValue": b }),
+            };
+            json!({ "key": key, "value": value })
+        }).collect();
+        let status = match &span.error {
+            Some(message) => json!({ "code": 2, "message": message }),
+            None => json!({ "code": 0 }),
+        };
+        let mut value = json!({
+            "traceId": format!("{:032x}", span.context.trace_id),
+            "spanId": format!("{:016x}", span.context.span_id),
+            "name": span.name,
+            "kind": match span.kind {
+                SpanKind::Internal => 1,
+                SpanKind::Server => 2,
+                SpanKind::Client => 3,
+            },
+            "startTimeUnixNano": unix_nanos(span.start),
+            "endTimeUnixNano": unix_nanos(span.end),
+            "attributes": attributes,
+            "status": status,
+        });
+        if let Some(parent) = span.parent_span_id {
+            value["parentSpanId"] = json!(format!("{:016x}", parent));
+        }
+        value
+    }).collect();
+
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [
+                    { "key": "service.name", "value": { "stringValue": service_name } },
+                    { "key": "service.version", "value": { "stringValue": env!("CARGO_PKG_VERSION") } },
+                ]
+            },
+            "scopeSpans": [{
+                "scope": { "name": "prism", "version": env!("CARGO_PKG_VERSION") },
+                "spans": spans,
+            }]
+        }]
+    })
+}
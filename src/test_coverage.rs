@@ -0,0 +1,119 @@
+//! Requirement-to-test coverage of the generated tests: each requirement
+//! statement with the test cases derived from its actions and the contract
+//! tests of its endpoints, and the findings on its line. Requirements no test
+//! exercises are the ones to look at first.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::analyzer::{Ambiguity, Analyzer, TestCases};
+use crate::contract_tests::ContractTest;
+use crate::coverage::extract_capability_lines;
+use crate::locale::report_locale;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequirementCoverage {
+    /// 1-based line of the requirement
+    pub line: usize,
+    pub requirement: String,
+    pub tests: Vec<String>,
+    /// Findings on the requirement's line, as "rule-id: text"
+    pub findings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestCoverageMatrix {
+    pub requirements: Vec<RequirementCoverage>,
+}
+
+impl TestCoverageMatrix {
+    pub fn untested(&self) -> Vec<&RequirementCoverage> {
+        self.requirements.iter().filter(|r| r.tests.is_empty()).collect()
+    }
+
+    pub fn coverage_percentage(&self) -> f64 {
+        if self.requirements.is_empty() {
+            return 100.0;
+        }
+        let tested = self.requirements.len() - self.untested().len();
+        tested as f64 / self.requirements.len() as f64 * 100.0
+    }
+}
+
+/// Maps the requirements of `text` to the test cases generated for the
+/// `actions` they mention and to the contract tests taken from them.
+pub fn build_test_coverage(
+    text: &str,
+    actions: &[String],
+    tests: &TestCases,
+    contracts: &[ContractTest],
+    findings: &[Ambiguity],
+) -> TestCoverageMatrix {
+    let all_tests: Vec<&String> = tests.happy_path.iter().chain(&tests.negative_cases).chain(&tests.edge_cases).collect();
+    // Test cases name the action as extracted, so they are found by it
+    let action_tests: Vec<(Regex, Vec<String>)> = actions.iter()
+        .filter_map(|action| {
+            let label = Analyzer::action_label(action)?;
+            let mention = Regex::new(&format!(r"\b{}\b", regex::escape(&label))).ok()?;
+            let derived = all_tests.iter().filter(|t| t.contains(action.as_str())).map(|t| t.to_string()).collect();
+            Some((mention, derived))
+        })
+        .collect();
+
+    let requirements = extract_capability_lines(text).into_iter().map(|(line, requirement)| {
+        let lower = requirement.to_lowercase();
+        let mut derived: Vec<String> = Vec::new();
+        for (mention, action_tests) in &action_tests {
+            if mention.is_match(&lower) {
+                for test in action_tests {
+                    if !derived.contains(test) {
+                        derived.push(test.clone());
+                    }
+                }
+            }
+        }
+        for contract in contracts.iter().filter(|c| c.line == line && c.requirement.contains(requirement.as_str())) {
+            derived.extend(contract.cases.iter().map(|case| format!("{} {}: {} → {}", contract.method, contract.path, case.description, case.status)));
+        }
+        let findings = findings.iter()
+            .filter(|f| f.location.as_ref().is_some_and(|l| l.line == line) && requirement.contains(f.text.as_str()))
+            .map(|f| format!("{}: {}", f.rule_id, f.text))
+            .collect();
+        RequirementCoverage { line, requirement, tests: derived, findings }
+    }).collect();
+
+    TestCoverageMatrix { requirements }
+}
+
+pub fn format_test_coverage_markdown(matrix: &TestCoverageMatrix) -> String {
+    let untested = matrix.untested();
+    let mut output = format!(
+        "**Requirements with derived tests:** {}/{} ({})\n\n",
+        matrix.requirements.len() - untested.len(), matrix.requirements.len(), report_locale().percent(matrix.coverage_percentage(), 0)
+    );
+    output.push_str("| Line | Requirement | Tests | Findings |\n|---:|---|---|---|\n");
+    for row in &matrix.requirements {
+        let requirement = row.requirement.replace('|', "\\|");
+        let tests = if row.tests.is_empty() {
+            "**⚠️ none**".to_string()
+        } else {
+            row.tests.iter().map(|t| t.replace('|', "\\|")).collect::<Vec<_>>().join("<br>")
+        };
+        let findings = if row.findings.is_empty() { "-".to_string() } else { row.findings.join("<br>") };
+        let requirement = if row.tests.is_empty() { format!("**{}**", requirement) } else { requirement };
+        output.push_str(&format!("| {} | {} | {} | {} |\n", row.line, requirement, tests, findings.replace('|', "\\|")));
+    }
+    if !untested.is_empty() {
+        output.push_str("\n### ⚠️ Requirements Without Tests\n\n");
+        for row in untested {
+            let findings = match row.findings.len() {
+                0 => String::new(),
+                1 => " (1 finding)".to_string(),
+                n => format!(" ({} findings)", n),
+            };
+            output.push_str(&format!("- Line {}: {}{}\n", row.line, row.requirement, findings));
+        }
+    }
+    output.push('\n');
+    output
+}
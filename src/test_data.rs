@@ -0,0 +1,356 @@
+//! Test data for the generated test cases: the inputs each action's
+//! requirements mention (email, password, quantity, date, ...) with valid,
+//! boundary and invalid examples, taking limits such as "between 8 and 64
+//! characters" or "at most 10" from the same sentence. Shown as tables and
+//! saved as JSON fixtures.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::analyzer::{Analyzer, ExtractedEntities};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FieldKind {
+    Email,
+    Password,
+    Text,
+    Number,
+    Amount,
+    Date,
+    Phone,
+    Url,
+    File,
+    Id,
+}
+
+/// Words that name an input, with its kind. Longer phrases come first so
+/// "user name" is not read as "name".
+const FIELD_WORDS: &[(&str, FieldKind)] = &[
+    ("email address", FieldKind::Email), ("e-mail", FieldKind::Email), ("email", FieldKind::Email),
+    ("password", FieldKind::Password), ("passphrase", FieldKind::Password), ("pin", FieldKind::Password),
+    ("user name", FieldKind::Text), ("username", FieldKind::Text), ("first name", FieldKind::Text),
+    ("last name", FieldKind::Text), ("name", FieldKind::Text), ("title", FieldKind::Text),
+    ("description", FieldKind::Text), ("comment", FieldKind::Text), ("message", FieldKind::Text),
+    ("address", FieldKind::Text), ("search term", FieldKind::Text), ("query", FieldKind::Text),
+    ("quantity", FieldKind::Number), ("age", FieldKind::Number), ("count", FieldKind::Number),
+    ("number of", FieldKind::Number), ("rating", FieldKind::Number),
+    ("amount", FieldKind::Amount), ("price", FieldKind::Amount), ("total", FieldKind::Amount),
+    ("payment", FieldKind::Amount), ("balance", FieldKind::Amount), ("fee", FieldKind::Amount),
+    ("date of birth", FieldKind::Date), ("birth date", FieldKind::Date), ("date", FieldKind::Date),
+    ("deadline", FieldKind::Date), ("due date", FieldKind::Date),
+    ("phone number", FieldKind::Phone), ("phone", FieldKind::Phone), ("mobile number", FieldKind::Phone),
+    ("url", FieldKind::Url), ("link", FieldKind::Url), ("website", FieldKind::Url),
+    ("file", FieldKind::File), ("attachment", FieldKind::File), ("document", FieldKind::File),
+    ("image", FieldKind::File), ("photo", FieldKind::File),
+    ("id", FieldKind::Id), ("identifier", FieldKind::Id), ("order number", FieldKind::Id),
+];
+
+/// A limit the requirement states for a field.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Limit {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// The limit is on the length in characters, not on the value
+    pub length: bool,
+    /// Size unit of a file limit, e.g. "MB"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Example {
+    pub value: String,
+    /// Why the value is worth testing, e.g. "minimum length (8)"
+    pub note: String,
+}
+
+impl Example {
+    fn new(value: impl Into<String>, note: impl Into<String>) -> Self {
+        Self { value: value.into(), note: note.into() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestDataField {
+    pub name: String,
+    pub kind: FieldKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<Limit>,
+    pub valid: Vec<Example>,
+    pub boundary: Vec<Example>,
+    pub invalid: Vec<Example>,
+}
+
+/// The test data for the test cases of one action.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestDataSet {
+    pub action: String,
+    pub fields: Vec<TestDataField>,
+}
+
+fn number(value: f64) -> String {
+    if value.fract() == 0.0 { format!("{}", value as i64) } else { format!("{:.2}", value) }
+}
+
+/// Units after a limit: a length in characters or a file size.
+const UNITS: &str = r"\s*(characters?|chars?|letters?|digits?|bytes?|[kmg]b)?\b";
+
+/// The limit stated in `clause`: "between 8 and 64 characters", "at least
+/// 3", "up to 10 MB", "no more than 5 items".
+fn find_limit(clause: &str) -> Option<Limit> {
+    let number = r"(\d+(?:\.\d+)?)";
+    let range = Regex::new(&format!(r"(?i)\b(?:between|from)\s+{n}\s+(?:and|to)\s+{n}{}", UNITS, n = number)).unwrap();
+    let min = Regex::new(&format!(r"(?i)\b(?:at least|minimum(?: of)?|min\.?|no (?:less|fewer) than)\s+{}{}", number, UNITS)).unwrap();
+    let max = Regex::new(&format!(r"(?i)\b(?:at most|maximum(?: of)?|max\.?|up to|no more than|not exceed(?:ing)?|under)\s+{}{}", number, UNITS)).unwrap();
+    let parse = |s: &str| s.parse::<f64>().ok();
+    let (min, max, unit) = if let Some(caps) = range.captures(clause) {
+        (parse(&caps[1]), parse(&caps[2]), caps.get(3).map(|u| u.as_str().to_string()))
+    } else {
+        let min = min.captures(clause);
+        let max = max.captures(clause);
+        if min.is_none() && max.is_none() {
+            return None;
+        }
+        let unit = min.iter().chain(max.iter()).find_map(|c| c.get(2)).map(|u| u.as_str().to_string());
+        (min.and_then(|c| parse(&c[1])), max.and_then(|c| parse(&c[1])), unit)
+    };
+    let (length, unit) = match unit {
+        Some(unit) if unit.starts_with(['c', 'l', 'd']) => (true, None),
+        Some(unit) if unit.starts_with('b') => (false, Some("bytes".to_string())),
+        Some(unit) => (false, Some(unit.to_uppercase())),
+        None => (false, None),
+    };
+    Some(Limit { min, max, length, unit })
+}
+
+/// `n` characters of filler; passwords mix character classes so only the
+/// length is under test.
+fn filler(kind: FieldKind, n: usize) -> String {
+    let pattern = if kind == FieldKind::Password { "Aa1!" } else { "a" };
+    pattern.chars().cycle().take(n).collect()
+}
+
+fn length_examples(kind: FieldKind, limit: &Limit, valid: &mut Vec<Example>, boundary: &mut Vec<Example>, invalid: &mut Vec<Example>) {
+    let text = |n: usize| filler(kind, n);
+    if let Some(min) = limit.min.map(|m| m as usize) {
+        boundary.push(Example::new(text(min), format!("minimum length ({})", min)));
+        if min > 0 {
+            invalid.push(Example::new(text(min - 1), format!("one character too short ({})", min - 1)));
+        }
+    }
+    if let Some(max) = limit.max.map(|m| m as usize) {
+        boundary.push(Example::new(text(max), format!("maximum length ({})", max)));
+        invalid.push(Example::new(text(max + 1), format!("one character too long ({})", max + 1)));
+    }
+    if let (Some(min), Some(max)) = (limit.min, limit.max) {
+        let middle = ((min + max) / 2.0) as usize;
+        valid.push(Example::new(text(middle), format!("within the limits ({})", middle)));
+    }
+}
+
+fn value_examples(limit: &Limit, step: f64, boundary: &mut Vec<Example>, invalid: &mut Vec<Example>) {
+    if let Some(min) = limit.min {
+        boundary.push(Example::new(number(min), "minimum"));
+        invalid.push(Example::new(number(min - step), "just below the minimum"));
+    }
+    if let Some(max) = limit.max {
+        boundary.push(Example::new(number(max), "maximum"));
+        invalid.push(Example::new(number(max + step), "just above the maximum"));
+    }
+}
+
+/// Valid, boundary and invalid examples for a field of `kind`, with the
+/// stated `limit` replacing the generic boundaries.
+pub fn examples_for(kind: FieldKind, limit: Option<&Limit>) -> (Vec<Example>, Vec<Example>, Vec<Example>) {
+    let (mut valid, mut boundary, mut invalid) = match kind {
+        FieldKind::Email => (
+            vec![Example::new("jane.doe@example.com", "typical address")],
+            vec![Example::new("a@b.co", "shortest usual address"), Example::new("jane.doe+test@sub.example.co.uk", "plus tag and subdomain")],
+            vec![Example::new("jane.doe@", "missing domain"), Example::new("jane.doe.example.com", "missing @"), Example::new("", "empty")],
+        ),
+        FieldKind::Password => (
+            vec![Example::new("Str0ng!Passw0rd", "mixed letters, digits and symbols")],
+            vec![],
+            vec![Example::new("", "empty"), Example::new("password", "common password"), Example::new("12345678", "digits only")],
+        ),
+        FieldKind::Text => (
+            vec![Example::new("Jane Doe", "typical value")],
+            vec![Example::new("J", "single character"), Example::new("Zoë O'Brien-Łukasz", "accents and punctuation")],
+            vec![Example::new("", "empty"), Example::new("   ", "whitespace only"), Example::new("<script>alert(1)</script>", "markup injection")],
+        ),
+        FieldKind::Number => (
+            vec![Example::new("5", "typical value")],
+            vec![Example::new("0", "zero"), Example::new("1", "smallest positive")],
+            vec![Example::new("-1", "negative"), Example::new("2.5", "not a whole number"), Example::new("abc", "not a number")],
+        ),
+        FieldKind::Amount => (
+            vec![Example::new("19.99", "typical amount")],
+            vec![Example::new("0.01", "smallest amount"), Example::new("0.00", "zero")],
+            vec![Example::new("-5.00", "negative"), Example::new("12.345", "too many decimals"), Example::new("abc", "not a number")],
+        ),
+        FieldKind::Date => (
+            vec![Example::new("2026-03-15", "typical date")],
+            vec![Example::new("2028-02-29", "leap day"), Example::new("2026-12-31", "end of year")],
+            vec![Example::new("2026-02-30", "day that does not exist"), Example::new("not-a-date", "not a date"), Example::new("", "empty")],
+        ),
+        FieldKind::Phone => (
+            vec![Example::new("+44 20 7946 0958", "international format")],
+            vec![Example::new("+1 202 555 0100", "other country code")],
+            vec![Example::new("12345", "too short"), Example::new("phone", "letters"), Example::new("", "empty")],
+        ),
+        FieldKind::Url => (
+            vec![Example::new("https://example.com/path", "typical URL")],
+            vec![Example::new("http://localhost:8080", "host with port"), Example::new("https://example.com/a?b=c#d", "query and fragment")],
+            vec![Example::new("example", "no scheme"), Example::new("javascript:alert(1)", "script URL"), Example::new("", "empty")],
+        ),
+        FieldKind::File => (
+            vec![Example::new("report.pdf (1 MB)", "typical file")],
+            vec![Example::new("empty.pdf (0 bytes)", "empty file")],
+            vec![Example::new("report.exe", "unexpected file type"), Example::new("report.pdf.exe", "double extension")],
+        ),
+        FieldKind::Id => (
+            vec![Example::new("42", "existing id")],
+            vec![Example::new("1", "first id")],
+            vec![Example::new("999999", "unknown id"), Example::new("-1", "negative"), Example::new("abc", "wrong type")],
+        ),
+    };
+    if let Some(limit) = limit {
+        match kind {
+            FieldKind::File if !limit.length => {
+                if let Some(max) = limit.max {
+                    let size = format!("{} {}", number(max), limit.unit.as_deref().unwrap_or("bytes"));
+                    boundary.push(Example::new(format!("file of exactly {}", size), "maximum size"));
+                    invalid.push(Example::new(format!("file just over {}", size), "over the size limit"));
+                }
+            }
+            _ if limit.length || matches!(kind, FieldKind::Text | FieldKind::Password) => {
+                length_examples(kind, limit, &mut valid, &mut boundary, &mut invalid);
+            }
+            FieldKind::Amount => value_examples(limit, 0.01, &mut boundary, &mut invalid),
+            _ => value_examples(limit, 1.0, &mut boundary, &mut invalid),
+        }
+    }
+    (valid, boundary, invalid)
+}
+
+/// The fields `sentence` mentions with the limit stated after each, in the
+/// order they appear.
+fn fields_in(sentence: &str) -> Vec<(String, FieldKind, Option<Limit>)> {
+    let lower = sentence.to_lowercase();
+    let mut found: Vec<(usize, usize, &str, FieldKind)> = Vec::new();
+    for (word, kind) in FIELD_WORDS {
+        let Ok(re) = Regex::new(&format!(r"\b{}s?\b", regex::escape(word))) else { continue };
+        for m in re.find_iter(&lower) {
+            // A longer phrase already covers this position
+            if !found.iter().any(|(start, end, _, _)| m.start() < *end && *start < m.end()) {
+                found.push((m.start(), m.end(), word, *kind));
+            }
+        }
+    }
+    found.sort_by_key(|(start, _, _, _)| *start);
+    found.iter().enumerate().map(|(i, (_, end, word, kind))| {
+        let next = found.get(i + 1).map_or(lower.len(), |(start, _, _, _)| *start);
+        (word.to_string(), *kind, find_limit(&lower[*end..next]))
+    }).collect()
+}
+
+/// Test data for each action of `entities`, from the inputs its sentences
+/// mention; actions whose sentences mention none get no set.
+pub fn suggest_test_data(text: &str, entities: &ExtractedEntities) -> Vec<TestDataSet> {
+    let sentences: Vec<&str> = text.split(['.', '!', '?', ';', '\n']).map(str::trim).filter(|s| !s.is_empty()).collect();
+    let mut sets: Vec<TestDataSet> = Vec::new();
+    for action in &entities.actions {
+        let Some(label) = Analyzer::action_label(action) else { continue };
+        if sets.iter().any(|s| s.action == label) {
+            continue;
+        }
+        let Ok(mention) = Regex::new(&format!(r"\b{}", regex::escape(&label))) else { continue };
+        let mut fields: Vec<TestDataField> = Vec::new();
+        for sentence in sentences.iter().filter(|s| mention.is_match(&s.to_lowercase())) {
+            for (name, kind, limit) in fields_in(sentence).into_iter().filter(|(name, _, _)| *name != label) {
+                if let Some(field) = fields.iter_mut().find(|f| f.name == name) {
+                    // A later sentence may state the limit
+                    if field.limit.is_none() && limit.is_some() {
+                        (field.valid, field.boundary, field.invalid) = examples_for(kind, limit.as_ref());
+                        field.limit = limit;
+                    }
+                    continue;
+                }
+                let (valid, boundary, invalid) = examples_for(kind, limit.as_ref());
+                fields.push(TestDataField { name, kind, limit, valid, boundary, invalid });
+            }
+        }
+        if !fields.is_empty() {
+            sets.push(TestDataSet { action: label, fields });
+        }
+    }
+    sets
+}
+
+/// "aaaaaaaaaaaaaaaaaaaaaaaa" -> "aaaaaaaa… (24 chars)"
+fn shorten(value: &str) -> String {
+    let chars = value.chars().count();
+    if chars > 32 && !value.contains(' ') {
+        format!("{}… ({} chars)", value.chars().take(8).collect::<String>(), chars)
+    } else if value.is_empty() {
+        "(empty)".to_string()
+    } else {
+        value.replace('|', "\\|")
+    }
+}
+
+fn cell(examples: &[Example]) -> String {
+    if examples.is_empty() {
+        return "-".to_string();
+    }
+    examples.iter().map(|e| format!("`{}` {}", shorten(&e.value), e.note)).collect::<Vec<_>>().join("<br>")
+}
+
+pub fn format_test_data_markdown(sets: &[TestDataSet]) -> String {
+    let mut output = String::new();
+    for set in sets {
+        output.push_str(&format!("#### {}\n\n| Field | Valid | Boundary | Invalid |\n|---|---|---|---|\n", set.action));
+        for field in &set.fields {
+            output.push_str(&format!("| {} | {} | {} | {} |\n", field.name, cell(&field.valid), cell(&field.boundary), cell(&field.invalid)));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// JSON fixtures per action: one all-valid record, then a record per
+/// boundary and per invalid value with the other fields valid. Invalid
+/// records say what the system should reject in `_expect`.
+pub fn format_test_data_fixtures(sets: &[TestDataSet]) -> Value {
+    let mut fixtures = Map::new();
+    for set in sets {
+        let valid_record = || -> Map<String, Value> {
+            set.fields.iter().map(|f| (f.name.clone(), json!(f.valid.first().map_or("", |e| e.value.as_str())))).collect()
+        };
+        let variant = |field: &TestDataField, example: &Example, expect: Option<String>| {
+            let mut record = valid_record();
+            record.insert(field.name.clone(), json!(example.value));
+            record.insert("_note".to_string(), json!(format!("{}: {}", field.name, example.note)));
+            if let Some(expect) = expect {
+                record.insert("_expect".to_string(), json!(expect));
+            }
+            Value::Object(record)
+        };
+        let boundary: Vec<Value> = set.fields.iter()
+            .flat_map(|f| f.boundary.iter().map(move |e| (f, e)))
+            .map(|(f, e)| variant(f, e, None))
+            .collect();
+        let invalid: Vec<Value> = set.fields.iter()
+            .flat_map(|f| f.invalid.iter().map(move |e| (f, e)))
+            .map(|(f, e)| variant(f, e, Some(format!("rejected: {} {}", f.name, e.note))))
+            .collect();
+        fixtures.insert(set.action.clone(), json!({
+            "valid": [Value::Object(valid_record())],
+            "boundary": boundary,
+            "invalid": invalid,
+        }));
+    }
+    Value::Object(fixtures)
+}
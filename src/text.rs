@@ -0,0 +1,19 @@
+//! Small text helpers shared by the generators and exporters.
+
+/// `text` with its first letter in upper case: "order placed" -> "Order placed".
+pub(crate) fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars.next().map(|first| first.to_uppercase().collect::<String>() + chars.as_str()).unwrap_or_default()
+}
+
+/// `text` escaped for XML text and attribute values. Control characters
+/// other than tab and line breaks are not allowed in XML and are left out.
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+        .collect::<String>()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,117 @@
+//! Section numbers and a table of contents for long Markdown and HTML
+//! reports (`--toc`, `output.toc`), so reviewers can point at "finding 3.2"
+//! in a meeting and jump to it.
+
+use crate::cli::OutputFormat;
+
+/// `report` in `format` numbered with a table of contents; other formats
+/// are returned as they are.
+pub fn add_toc(report: &str, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Markdown => number_markdown(report),
+        OutputFormat::Html => number_html(report),
+        _ => report.to_string(),
+    }
+}
+
+fn anchor(number: &str) -> String {
+    format!("section-{}", number.replace('.', "-"))
+}
+
+/// Numbers the `##` sections and the `###` headings inside them, such as
+/// the findings, and lists them after the `#` title.
+pub fn number_markdown(markdown: &str) -> String {
+    let mut lines = Vec::new();
+    let mut toc = Vec::new();
+    let (mut section, mut heading) = (0, 0);
+    let mut in_code = false;
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~") {
+            in_code = !in_code;
+        }
+        let numbered = if in_code {
+            None
+        } else if let Some(title) = line.strip_prefix("## ") {
+            section += 1;
+            heading = 0;
+            Some(("##", section.to_string(), title))
+        } else if let Some(title) = line.strip_prefix("### ").filter(|_| section > 0) {
+            heading += 1;
+            Some(("###", format!("{}.{}", section, heading), title))
+        } else {
+            None
+        };
+        match numbered {
+            Some((level, number, title)) => {
+                let label = if level == "##" { format!("{}.", number) } else { number.clone() };
+                let indent = if level == "##" { "" } else { "  " };
+                toc.push(format!("{}- [{} {}](#{})", indent, label, title.trim().replace('[', "\\[").replace(']', "\\]"), anchor(&number)));
+                lines.push(format!("{} <a id=\"{}\"></a>{} {}", level, anchor(&number), label, title));
+            }
+            None => lines.push(line.to_string()),
+        }
+    }
+    if toc.is_empty() {
+        return markdown.to_string();
+    }
+
+    let contents = format!("## Contents\n\n{}\n", toc.join("\n"));
+    // After the title and the blank line below it, or first without a title
+    let position = match lines.iter().position(|line| line.starts_with("# ")) {
+        Some(title) => (title + 1..lines.len()).find(|&i| !lines[i].trim().is_empty()).unwrap_or(lines.len()),
+        None => 0,
+    };
+    lines.insert(position, contents);
+    let mut output = lines.join("\n");
+    if markdown.ends_with('\n') {
+        output.push('\n');
+    }
+    output
+}
+
+const SECTION_START: &str = "<details open>\n<summary>";
+const FINDING_ROW: &str = "<tr data-severity=";
+
+/// Numbers the collapsible sections of an HTML report and the rows of its
+/// findings table, with a linked list of the sections above the first one.
+pub fn number_html(html: &str) -> String {
+    let mut output = String::new();
+    let mut toc = Vec::new();
+    let mut rest = html;
+    let mut section = 0;
+    let mut first_section = None;
+    while let Some(start) = rest.find(SECTION_START) {
+        output.push_str(&number_findings(&rest[..start], section));
+        section += 1;
+        let after = &rest[start + SECTION_START.len()..];
+        let title = after.find("</summary>").map(|end| &after[..end]).unwrap_or_default();
+        first_section.get_or_insert(output.len());
+        toc.push(format!("<li><a href=\"#section-{}\">{}</a></li>", section, title));
+        output.push_str(&format!("<details open id=\"section-{}\">\n<summary>{}. ", section, section));
+        rest = after;
+    }
+    output.push_str(&number_findings(rest, section));
+
+    if let Some(position) = first_section {
+        output.insert_str(position, &format!("<nav class=\"toc\"><strong>Contents</strong>\n<ol>\n{}\n</ol></nav>\n", toc.join("\n")));
+    }
+    output
+}
+
+/// Numbers the finding rows of section `section` as `section.row`.
+fn number_findings(html: &str, section: usize) -> String {
+    if !html.contains(FINDING_ROW) {
+        return html.to_string();
+    }
+    let html = html.replace("<table id=\"findings\"><tr>", "<table id=\"findings\"><tr><th>#</th>");
+    let mut output = String::new();
+    let mut parts = html.split(FINDING_ROW);
+    output.push_str(parts.next().unwrap_or_default());
+    for (i, part) in parts.enumerate() {
+        let number = format!("{}.{}", section, i + 1);
+        // The row's attributes end at the first '>'
+        let (attributes, cells) = part.split_at(part.find('>').map(|end| end + 1).unwrap_or(0));
+        output.push_str(&format!("<tr id=\"finding-{}\" data-severity={}<td>{}</td>{}", number.replace('.', "-"), attributes, number, cells));
+    }
+    output
+}
@@ -1,4 +1,4 @@
-use anyhow::{Result, anyhow};
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
@@ -6,6 +6,12 @@ use tokio::fs;
 use walkdir::WalkDir;
 use regex::Regex;
 
+use crate::coverage::{content_terms, extract_capabilities};
+use crate::locale::report_locale;
+
+/// Orphaned functions listed in the Markdown report; the JSON report has all of them.
+const MAX_LISTED_ORPHANS: usize = 50;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TraceabilityMatrix {
     pub requirements: Vec<RequirementTrace>,
@@ -77,7 +83,17 @@ pub struct TraceabilityAnalyzer {
     source_extensions: HashSet<String>,
     test_extensions: HashSet<String>,
     comment_patterns: HashMap<String, Regex>,
-    keyword_patterns: Vec<Regex>,
+    requirement_id_pattern: Regex,
+    test_patterns: Vec<Regex>,
+    test_attribute: Regex,
+    test_declaration: Regex,
+    function_patterns: Vec<Regex>,
+}
+
+impl Default for TraceabilityAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TraceabilityAnalyzer {
@@ -98,49 +114,75 @@ impl TraceabilityAnalyzer {
         test_extensions.insert("js".to_string());  // *.test.js
         test_extensions.insert("ts".to_string());  // *.test.ts
         test_extensions.insert("java".to_string()); // *Test.java
+        test_extensions.insert("go".to_string());  // *_test.go
 
         let mut comment_patterns = HashMap::new();
-        comment_patterns.insert("rs".to_string(), Regex::new(r"//\s*(.+)|/\*\s*(.+?)\s*\*/").unwrap());
-        comment_patterns.insert("py".to_string(), Regex::new(r"#\s*(.+)|'''\s*(.+?)\s*'''|"""\s*(.+?)\s*"""").unwrap());
-        comment_patterns.insert("js".to_string(), Regex::new(r"//\s*(.+)|/\*\s*(.+?)\s*\*/").unwrap());
-        comment_patterns.insert("ts".to_string(), Regex::new(r"//\s*(.+)|/\*\s*(.+?)\s*\*/").unwrap());
-        comment_patterns.insert("java".to_string(), Regex::new(r"//\s*(.+)|/\*\s*(.+?)\s*\*/").unwrap());
-
-        let keyword_patterns = vec![
-            Regex::new(r"(?i)req(?:uirement)?[_\s-]*(?:id|num|number)?[_\s-]*(\w+)").unwrap(),
-            Regex::new(r"(?i)user[_\s-]*story[_\s-]*(\w+)").unwrap(),
-            Regex::new(r"(?i)feature[_\s-]*(\w+)").unwrap(),
-            Regex::new(r"(?i)as[_\s]+a[_\s]+(\w+)").unwrap(),
-            Regex::new(r"(?i)i[_\s]+want[_\s]+to[_\s]+(\w+)").unwrap(),
+        let c_style = Regex::new(r"//\s*(.+)|/\*\s*(.+?)\s*\*/").unwrap();
+        for extension in ["rs", "js", "ts", "java", "cpp", "c", "go"] {
+            comment_patterns.insert(extension.to_string(), c_style.clone());
+        }
+        comment_patterns.insert("py".to_string(), Regex::new(r#"#\s*(.+)|'''\s*(.+?)\s*'''|"""\s*(.+?)\s*""""#).unwrap());
+
+        let test_patterns = vec![
+            Regex::new(r"(?i)fn\s+(test_\w+)").unwrap(),              // Rust
+            Regex::new(r"(?i)def\s+(test_\w+)").unwrap(),             // Python
+            Regex::new(r"func\s+(Test\w+)").unwrap(),                 // Go
+            Regex::new(r#"\bit\s*\(\s*['"`](.+?)['"`]"#).unwrap(),     // JS/TS
+            Regex::new(r#"\btest\s*\(\s*['"`](.+?)['"`]"#).unwrap(),   // JS/TS
+        ];
+
+        let function_patterns = vec![
+            Regex::new(r"\bfn\s+(\w+)").unwrap(),                     // Rust
+            Regex::new(r"\bdef\s+(\w+)").unwrap(),                    // Python
+            Regex::new(r"\bfunction\s+(\w+)").unwrap(),               // JavaScript
+            Regex::new(r"\bfunc\s+(?:\([^)]*\)\s*)?(\w+)").unwrap(),  // Go
+            Regex::new(r"\bpublic\s+\w+\s+(\w+)\s*\(").unwrap(),      // Java
         ];
 
         Self {
             source_extensions,
             test_extensions,
             comment_patterns,
-            keyword_patterns,
+            requirement_id_pattern: Regex::new(r"\b([A-Z][A-Z0-9]*-\d+)\b").unwrap(),
+            test_patterns,
+            // Rust #[test] / #[tokio::test] and Java @Test mark the next declaration as a test
+            test_attribute: Regex::new(r"#\[(?:\w+::)?test\]|@Test\b").unwrap(),
+            test_declaration: Regex::new(r"\b(?:fn|void|def)\s+(\w+)\s*\(").unwrap(),
+            function_patterns,
         }
     }
 
+    /// Splits requirements text into individual requirements (list items,
+    /// user stories and sentences with a requirement verb).
+    pub fn extract_requirements(&self, text: &str) -> Vec<String> {
+        extract_capabilities(text)
+    }
+
+    /// Traces each requirement to the code under `source_dir` and the tests
+    /// under `test_dir`. Test-named files inside `source_dir` count as tests.
     pub async fn analyze_traceability(
         &self,
         requirements: &[String],
-        source_paths: &[PathBuf],
+        source_dir: Option<&Path>,
+        test_dir: Option<&Path>,
     ) -> Result<TraceabilityMatrix> {
         let mut requirement_traces = Vec::new();
         let mut all_code_files = Vec::new();
         let mut all_test_files = Vec::new();
 
         // Collect all source and test files
-        for source_path in source_paths {
-            let (code_files, test_files) = self.collect_files(source_path).await?;
+        if let Some(source_dir) = source_dir {
+            let (code_files, test_files) = self.collect_files(source_dir, test_dir);
             all_code_files.extend(code_files);
             all_test_files.extend(test_files);
         }
+        if let Some(test_dir) = test_dir {
+            all_test_files.extend(self.collect_test_files(test_dir));
+        }
 
         // Analyze each requirement
         for (idx, requirement) in requirements.iter().enumerate() {
-            let requirement_id = format!("REQ-{:03}", idx + 1);
+            let requirement_id = self.requirement_id(requirement, idx);
             let trace = self.trace_requirement(
                 &requirement_id,
                 requirement,
@@ -175,23 +217,30 @@ impl TraceabilityAnalyzer {
         })
     }
 
-    async fn collect_files(&self, source_path: &Path) -> Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+    /// The ID written in the requirement (REQ-12, US-3, ...) or a generated one.
+    fn requirement_id(&self, requirement: &str, idx: usize) -> String {
+        self.requirement_id_pattern
+            .captures(requirement)
+            .map(|caps| caps[1].to_string())
+            .unwrap_or_else(|| format!("REQ-{:03}", idx + 1))
+    }
+
+    /// Code and test-named files under `source_path`, skipping `test_dir` if it is nested inside.
+    fn collect_files(&self, source_path: &Path, test_dir: Option<&Path>) -> (Vec<PathBuf>, Vec<PathBuf>) {
         let mut code_files = Vec::new();
         let mut test_files = Vec::new();
 
-        if !source_path.exists() {
-            return Ok((code_files, test_files));
-        }
-
         for entry in WalkDir::new(source_path)
             .follow_links(true)
+            .sort_by_file_name()
             .into_iter()
+            .filter_entry(|e| test_dir.is_none_or(|dir| e.path() != dir))
             .filter_map(|e| e.ok())
         {
             let path = entry.path();
             if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
                 if self.source_extensions.contains(extension) {
-                    if self.is_test_file(path) {
+                    if self.is_test_file(path.strip_prefix(source_path).unwrap_or(path)) {
                         test_files.push(path.to_path_buf());
                     } else {
                         code_files.push(path.to_path_buf());
@@ -200,18 +249,34 @@ impl TraceabilityAnalyzer {
             }
         }
 
-        Ok((code_files, test_files))
+        (code_files, test_files)
     }
 
+    fn collect_test_files(&self, test_path: &Path) -> Vec<PathBuf> {
+        WalkDir::new(test_path)
+            .follow_links(true)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().and_then(|e| e.to_str()).is_some_and(|ext| self.test_extensions.contains(ext)))
+            .map(|e| e.path().to_path_buf())
+            .collect()
+    }
+
+    /// Whether a path relative to the scanned directory names a test file.
     fn is_test_file(&self, path: &Path) -> bool {
-        let path_str = path.to_string_lossy().to_lowercase();
-        path_str.contains("test") || 
-        path_str.contains("spec") ||
-        path_str.ends_with("_test.rs") ||
-        path_str.ends_with("_test.py") ||
-        path_str.ends_with(".test.js") ||
-        path_str.ends_with(".test.ts") ||
-        path_str.ends_with("test.java")
+        let in_test_dir = path.parent().is_some_and(|parent| {
+            parent.components().any(|c| matches!(c.as_os_str().to_str(), Some("test" | "tests" | "__tests__" | "spec")))
+        });
+        let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let lower = stem.to_lowercase();
+        in_test_dir ||
+        lower.starts_with("test_") ||
+        lower.ends_with("_test") ||
+        lower.ends_with("_spec") ||
+        lower.ends_with(".test") ||
+        lower.ends_with(".spec") ||
+        stem.ends_with("Test")
     }
 
     async fn trace_requirement(
@@ -226,15 +291,14 @@ impl TraceabilityAnalyzer {
 
         // Extract keywords from requirement
         let keywords = self.extract_keywords(requirement_text);
+        let id_pattern = id_regex(requirement_id);
 
         // Search in code files
         for file_path in code_files {
             let references = self.search_file_for_requirement(
                 file_path,
-                requirement_id,
-                requirement_text,
+                &id_pattern,
                 &keywords,
-                false,
             ).await?;
             code_references.extend(references);
         }
@@ -243,8 +307,7 @@ impl TraceabilityAnalyzer {
         for file_path in test_files {
             let references = self.search_test_file(
                 file_path,
-                requirement_id,
-                requirement_text,
+                &id_pattern,
                 &keywords,
             ).await?;
             test_references.extend(references);
@@ -265,54 +328,37 @@ impl TraceabilityAnalyzer {
     async fn search_file_for_requirement(
         &self,
         file_path: &Path,
-        requirement_id: &str,
-        requirement_text: &str,
+        id_pattern: &Regex,
         keywords: &[String],
-        is_test: bool,
     ) -> Result<Vec<CodeReference>> {
         let content = match fs::read_to_string(file_path).await {
             Ok(content) => content,
             Err(_) => return Ok(Vec::new()),
         };
 
+        let comment_regex = file_path.extension()
+            .and_then(|e| e.to_str())
+            .and_then(|extension| self.comment_patterns.get(extension));
         let mut references = Vec::new();
-        let lines: Vec<&str> = content.lines().collect();
 
-        for (line_num, line) in lines.iter().enumerate() {
+        for (line_num, line) in content.lines().enumerate() {
             let mut max_confidence = 0.0;
             let mut match_type = MatchType::KeywordMatch;
 
             // Check for exact requirement ID match
-            if line.contains(requirement_id) {
+            if id_pattern.is_match(line) {
                 max_confidence = 0.95;
                 match_type = MatchType::ExactMatch;
             }
             // Check for keyword matches
-            else {
-                let mut keyword_matches = 0;
-                for keyword in keywords {
-                    if line.to_lowercase().contains(&keyword.to_lowercase()) {
-                        keyword_matches += 1;
-                    }
-                }
-
-                if keyword_matches > 0 {
-                    max_confidence = (keyword_matches as f64 / keywords.len() as f64) * 0.8;
-                    match_type = MatchType::KeywordMatch;
-                }
-            }
-
-            // Check comments for requirement references
-            if let Some(extension) = file_path.extension().and_then(|e| e.to_str()) {
-                if let Some(comment_regex) = self.comment_patterns.get(extension) {
-                    if comment_regex.is_match(line) {
-                        for keyword in keywords {
-                            if line.to_lowercase().contains(&keyword.to_lowercase()) {
-                                max_confidence = max_confidence.max(0.7);
-                                match_type = MatchType::CommentMatch;
-                            }
-                        }
-                    }
+            else if let Some(confidence) = keyword_confidence(line, keywords) {
+                max_confidence = confidence;
+                match_type = MatchType::KeywordMatch;
+
+                // Comments describing the requirement are a stronger signal than identifiers
+                if comment_regex.is_some_and(|regex| regex.is_match(line)) && confidence < 0.7 {
+                    max_confidence = 0.7;
+                    match_type = MatchType::CommentMatch;
                 }
             }
 
@@ -320,7 +366,7 @@ impl TraceabilityAnalyzer {
                 references.push(CodeReference {
                     file_path: file_path.to_path_buf(),
                     line_number: line_num + 1,
-                    code_snippet: line.to_string(),
+                    code_snippet: line.trim().to_string(),
                     confidence: max_confidence,
                     match_type,
                 });
@@ -333,8 +379,7 @@ impl TraceabilityAnalyzer {
     async fn search_test_file(
         &self,
         file_path: &Path,
-        requirement_id: &str,
-        requirement_text: &str,
+        id_pattern: &Regex,
         keywords: &[String],
     ) -> Result<Vec<TestReference>> {
         let content = match fs::read_to_string(file_path).await {
@@ -345,43 +390,28 @@ impl TraceabilityAnalyzer {
         let mut references = Vec::new();
         let lines: Vec<&str> = content.lines().collect();
 
-        // Common test function patterns
-        let test_patterns = vec![
-            Regex::new(r"(?i)fn\s+test_(\w+)").unwrap(),      // Rust
-            Regex::new(r"(?i)def\s+test_(\w+)").unwrap(),     // Python
-            Regex::new(r"(?i)it\s*\(\s*['\"](.+?)['\"]").unwrap(), // JS/TS
-            Regex::new(r"(?i)test\s*\(\s*['\"](.+?)['\"]").unwrap(), // JS/TS
-            Regex::new(r"(?i)@Test.*?public\s+void\s+(\w+)").unwrap(), // Java
-        ];
-
         for (line_num, line) in lines.iter().enumerate() {
-            let mut confidence = 0.0;
-            let mut test_name = String::new();
+            // Comments and attributes just above a test belong to it
+            let context = lines[line_num.saturating_sub(3)..=line_num].join("\n");
+            let preceded_by_attribute = lines[line_num.saturating_sub(3)..line_num]
+                .iter()
+                .any(|l| self.test_attribute.is_match(l));
 
             // Check if this line contains a test function
-            for pattern in &test_patterns {
-                if let Some(captures) = pattern.captures(line) {
-                    test_name = captures.get(1).map_or(String::new(), |m| m.as_str().to_string());
-                    
-                    // Check for requirement references in test name or line
-                    if line.contains(requirement_id) {
-                        confidence = 0.95;
-                    } else {
-                        let mut keyword_matches = 0;
-                        for keyword in keywords {
-                            if line.to_lowercase().contains(&keyword.to_lowercase()) {
-                                keyword_matches += 1;
-                            }
-                        }
-                        
-                        if keyword_matches > 0 {
-                            confidence = (keyword_matches as f64 / keywords.len() as f64) * 0.8;
-                        }
-                    }
-                    
-                    break;
-                }
-            }
+            let test_name = self.test_patterns.iter()
+                .find_map(|pattern| pattern.captures(line))
+                .or_else(|| preceded_by_attribute.then(|| self.test_declaration.captures(line)).flatten())
+                .and_then(|captures| captures.get(1).map(|m| m.as_str().to_string()));
+            let Some(test_name) = test_name else {
+                continue;
+            };
+
+            // Check for requirement references in test name or the lines above it
+            let confidence = if id_pattern.is_match(&context) {
+                0.95
+            } else {
+                keyword_confidence(&format!("{}\n{}", context, test_name.replace('_', " ")), keywords).unwrap_or(0.0)
+            };
 
             if confidence > 0.5 {
                 let test_type = self.determine_test_type(file_path, &test_name);
@@ -398,37 +428,15 @@ impl TraceabilityAnalyzer {
         Ok(references)
     }
 
+    /// Stemmed content words of the requirement, without the requirement ID itself.
     fn extract_keywords(&self, requirement_text: &str) -> Vec<String> {
+        let text = self.requirement_id_pattern.replace_all(requirement_text, " ");
         let mut keywords = Vec::new();
-        
-        // Extract common action verbs
-        let action_words = vec![
-            "login", "register", "authenticate", "create", "update", "delete", "save",
-            "search", "filter", "sort", "upload", "download", "send", "receive",
-            "validate", "verify", "process", "generate", "calculate", "display",
-        ];
-
-        for word in action_words {
-            if requirement_text.to_lowercase().contains(word) {
-                keywords.push(word.to_string());
+        for term in content_terms(&text) {
+            if !keywords.contains(&term) && !term.chars().all(|c| c.is_ascii_digit()) {
+                keywords.push(term);
             }
         }
-
-        // Extract nouns (simplified)
-        let noun_patterns = vec![
-            Regex::new(r"(?i)\b(user|admin|customer|account|profile|data|file|document|report)\b").unwrap(),
-            Regex::new(r"(?i)\b(system|application|interface|dashboard|form|button|field)\b").unwrap(),
-        ];
-
-        for pattern in noun_patterns {
-            for cap in pattern.captures_iter(requirement_text) {
-                if let Some(word) = cap.get(1) {
-                    keywords.push(word.as_str().to_lowercase());
-                }
-            }
-        }
-
-        keywords.dedup();
         keywords
     }
 
@@ -453,22 +461,14 @@ impl TraceabilityAnalyzer {
         
         // Base coverage from code references
         if !code_refs.is_empty() {
-            coverage += 40.0;
+            coverage += 40.0 * best_confidence(code_refs.iter().map(|r| r.confidence));
         }
         
         // Additional coverage from test references
         if !test_refs.is_empty() {
-            coverage += 60.0;
+            coverage += 60.0 * best_confidence(test_refs.iter().map(|r| r.confidence));
         }
         
-        // Bonus for high-confidence matches
-        let avg_code_confidence: f64 = code_refs.iter().map(|r| r.confidence).sum::<f64>() 
-            / code_refs.len().max(1) as f64;
-        let avg_test_confidence: f64 = test_refs.iter().map(|r| r.confidence).sum::<f64>() 
-            / test_refs.len().max(1) as f64;
-        
-        coverage *= (avg_code_confidence + avg_test_confidence) / 2.0;
-        
         coverage.min(100.0)
     }
 
@@ -491,26 +491,17 @@ impl TraceabilityAnalyzer {
         for file_path in code_files {
             if !traced_files.contains(file_path) {
                 if let Ok(content) = fs::read_to_string(file_path).await {
-                    // Simple function detection (can be improved for each language)
-                    let function_patterns = vec![
-                        Regex::new(r"fn\s+(\w+)").unwrap(),        // Rust
-                        Regex::new(r"def\s+(\w+)").unwrap(),       // Python  
-                        Regex::new(r"function\s+(\w+)").unwrap(),  // JavaScript
-                        Regex::new(r"public\s+\w+\s+(\w+)\s*\(").unwrap(), // Java
-                    ];
-
                     for (line_num, line) in content.lines().enumerate() {
-                        for pattern in &function_patterns {
-                            if let Some(captures) = pattern.captures(line) {
-                                if let Some(func_name) = captures.get(1) {
-                                    orphaned.push(OrphanedCode {
-                                        file_path: file_path.clone(),
-                                        function_name: func_name.as_str().to_string(),
-                                        line_number: line_num + 1,
-                                        description: format!("Function '{}' has no clear requirement traceability", func_name.as_str()),
-                                    });
-                                }
-                            }
+                        let function = self.function_patterns.iter()
+                            .find_map(|pattern| pattern.captures(line))
+                            .and_then(|captures| captures.get(1));
+                        if let Some(func_name) = function {
+                            orphaned.push(OrphanedCode {
+                                file_path: file_path.clone(),
+                                function_name: func_name.as_str().to_string(),
+                                line_number: line_num + 1,
+                                description: format!("Function '{}' has no clear requirement traceability", func_name.as_str()),
+                            });
                         }
                     }
                 }
@@ -519,4 +510,111 @@ impl TraceabilityAnalyzer {
 
         Ok(orphaned)
     }
-}
\ No newline at end of file
+}
+
+/// Matches a requirement ID in code, test names and comments: `REQ-12`,
+/// `req_12` and `REQ12` all match `REQ-12`, but `REQ-120` does not.
+fn id_regex(requirement_id: &str) -> Regex {
+    let pattern = requirement_id
+        .split('-')
+        .map(regex::escape)
+        .collect::<Vec<_>>()
+        .join("[-_]?");
+    Regex::new(&format!(r"(?i)(?:^|[^A-Za-z0-9]){}(?:$|[^A-Za-z0-9])", pattern)).unwrap()
+}
+
+/// Confidence that a line implements a requirement from the keywords it
+/// mentions. A single shared word is too weak unless the requirement has only one.
+fn keyword_confidence(line: &str, keywords: &[String]) -> Option<f64> {
+    if keywords.is_empty() {
+        return None;
+    }
+    let lower = line.to_lowercase();
+    let matches = keywords.iter().filter(|keyword| lower.contains(keyword.as_str())).count();
+    if matches < keywords.len().min(2) {
+        return None;
+    }
+    Some((0.5 + 0.4 * matches as f64 / keywords.len() as f64).min(0.9))
+}
+
+fn best_confidence(confidences: impl Iterator<Item = f64>) -> f64 {
+    confidences.fold(0.0, f64::max)
+}
+
+pub fn format_traceability_markdown(matrix: &TraceabilityMatrix) -> String {
+    let mut output = String::new();
+    let summary = &matrix.coverage_summary;
+
+    let locale = report_locale();
+    output.push_str("# 🔗 PRISM Traceability Matrix\n\n");
+    output.push_str(&format!(
+        "**Traced requirements:** {}/{} ({})\n\n",
+        summary.traced_requirements, summary.total_requirements, locale.percent(summary.coverage_percentage, 0)
+    ));
+    output.push_str(&format!(
+        "**Files analyzed:** {} code, {} test\n\n",
+        summary.code_files_analyzed, summary.test_files_analyzed
+    ));
+
+    output.push_str("## 📊 Matrix\n\n");
+    output.push_str("| ID | Requirement | Code | Tests | Coverage |\n|---|---|---:|---:|---:|\n");
+    for trace in &matrix.requirements {
+        output.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            trace.requirement_id,
+            trace.requirement_text.replace('|', "\\|"),
+            trace.code_references.len(),
+            trace.test_references.len(),
+            locale.percent(trace.coverage_percentage, 0)
+        ));
+    }
+    output.push('\n');
+
+    let traced: Vec<_> = matrix.requirements.iter()
+        .filter(|r| !r.code_references.is_empty() || !r.test_references.is_empty())
+        .collect();
+    if !traced.is_empty() {
+        output.push_str("## 🔍 References\n\n");
+        for trace in traced {
+            output.push_str(&format!("### {}\n\n", trace.requirement_id));
+            for code in &trace.code_references {
+                output.push_str(&format!(
+                    "- 💻 `{}:{}` ({:?}, {}): `{}`\n",
+                    code.file_path.display(), code.line_number, code.match_type, locale.percent(code.confidence * 100.0, 0),
+                    code.code_snippet.replace('`', "'")
+                ));
+            }
+            for test in &trace.test_references {
+                output.push_str(&format!(
+                    "- 🧪 `{}:{}` {} ({:?}, {})\n",
+                    test.file_path.display(), test.line_number, test.test_name, test.test_type, locale.percent(test.confidence * 100.0, 0)
+                ));
+            }
+            output.push('\n');
+        }
+    }
+
+    let untested: Vec<_> = matrix.requirements.iter()
+        .filter(|r| r.test_references.is_empty())
+        .collect();
+    if !untested.is_empty() {
+        output.push_str("## ⚠️ Requirements Without Tests\n\n");
+        for trace in untested {
+            output.push_str(&format!("- **{}**: {}\n", trace.requirement_id, trace.requirement_text));
+        }
+        output.push('\n');
+    }
+
+    if !matrix.orphaned_code.is_empty() {
+        output.push_str(&format!("## 🧩 Orphaned Code ({} functions)\n\n", matrix.orphaned_code.len()));
+        for orphan in matrix.orphaned_code.iter().take(MAX_LISTED_ORPHANS) {
+            output.push_str(&format!("- `{}` in `{}:{}`\n", orphan.function_name, orphan.file_path.display(), orphan.line_number));
+        }
+        if matrix.orphaned_code.len() > MAX_LISTED_ORPHANS {
+            output.push_str(&format!("- ... and {} more (use `--format json` for the full list)\n", matrix.orphaned_code.len() - MAX_LISTED_ORPHANS));
+        }
+        output.push('\n');
+    }
+
+    output
+}
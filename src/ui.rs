@@ -4,7 +4,12 @@ use crossterm::{
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use std::{io, time::Duration};
+use std::{
+    io,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::task::JoinHandle;
 use tui::{
     backend::{Backend, CrosstermBackend},
     layout::{Alignment, Constraint, Direction, Layout},
@@ -18,12 +23,14 @@ use tui::{
 
 use crate::analyzer::{Analyzer, AnalysisResult, AmbiguitySeverity};
 use crate::config::Config;
+use crate::llm_stream::StreamEvent;
 
-#[derive(Clone)]
 pub struct TuiApp {
     analyzer: Analyzer,
     config: Config,
     state: AppState,
+    /// Analysis running in the background while the UI keeps redrawing
+    analysis_task: Option<JoinHandle<Result<AnalysisResult>>>,
 }
 
 #[derive(Clone)]
@@ -32,6 +39,8 @@ struct AppState {
     current_tab: usize,
     analysis_result: Option<AnalysisResult>,
     is_analyzing: bool,
+    /// AI response text streamed in by the running analysis
+    ai_response: Arc<Mutex<String>>,
     selected_ambiguity: usize,
     show_help: bool,
     cursor_position: usize,
@@ -64,6 +73,7 @@ impl TuiApp {
                 current_tab: 0,
                 analysis_result: None,
                 is_analyzing: false,
+                ai_response: Arc::new(Mutex::new(String::new())),
                 selected_ambiguity: 0,
                 show_help: false,
                 cursor_position: 0,
@@ -71,6 +81,7 @@ impl TuiApp {
                 clarification_questions: Vec::new(),
                 current_question: 0,
             },
+            analysis_task: None,
         })
     }
 
@@ -98,6 +109,12 @@ impl TuiApp {
         loop {
             terminal.draw(|f| self.ui(f))?;
 
+            if self.analysis_task.as_ref().is_some_and(|task| task.is_finished()) {
+                if let Some(task) = self.analysis_task.take() {
+                    self.finish_analysis(task.await?);
+                }
+            }
+
             if event::poll(Duration::from_millis(100))? {
                 if let Event::Key(key) = event::read()? {
                     match self.state.input_mode {
@@ -130,7 +147,7 @@ impl TuiApp {
             KeyCode::Char('i') => self.state.input_mode = InputMode::Editing,
             KeyCode::Char('a') => {
                 if !self.state.input_text.is_empty() && !self.state.is_analyzing {
-                    self.analyze_input().await?;
+                    self.start_analysis();
                 }
             }
             KeyCode::Char('c') => {
@@ -184,8 +201,8 @@ impl TuiApp {
             KeyCode::Enter => {
                 if key.modifiers.contains(KeyModifiers::CONTROL) {
                     self.state.input_mode = InputMode::Normal;
-                    if !self.state.input_text.is_empty() {
-                        self.analyze_input().await?;
+                    if !self.state.input_text.is_empty() && !self.state.is_analyzing {
+                        self.start_analysis();
                     }
                 } else {
                     self.state.input_text.insert(self.state.cursor_position, '\n');
@@ -208,14 +225,31 @@ impl TuiApp {
         Ok(false)
     }
 
-    async fn analyze_input(&mut self) -> Result<()> {
+    /// Starts analyzing the input in the background. AI responses stream into
+    /// `ai_response`, which the Output tab shows until the analysis is done.
+    fn start_analysis(&mut self) {
         self.state.is_analyzing = true;
-        
-        match self.analyzer.analyze(&self.state.input_text).await {
+        self.state.ai_response.lock().unwrap().clear();
+
+        let response = self.state.ai_response.clone();
+        let analyzer = self.analyzer.clone().with_stream_handler(Arc::new(move |event| {
+            let mut response = response.lock().unwrap();
+            match event {
+                StreamEvent::Started if !response.is_empty() => response.push_str("\n\n"),
+                StreamEvent::Delta(text) => response.push_str(text),
+                _ => {}
+            }
+        }));
+        let text = self.state.input_text.clone();
+        self.analysis_task = Some(tokio::spawn(async move { analyzer.analyze(&text).await }));
+    }
+
+    fn finish_analysis(&mut self, analysis: Result<AnalysisResult>) {
+        match analysis {
             Ok(mut result) => {
                 self.generate_clarification_questions(&result);
                 
-                let use_case = self.analyzer.generate_uml_use_case(&result.entities);
+                let use_case = self.analyzer.generate_uml_use_case(&self.state.input_text, &result.entities);
                 result.uml_diagrams = Some(crate::analyzer::UmlDiagrams {
                     use_case: Some(use_case),
                     sequence: None,
@@ -236,7 +270,6 @@ impl TuiApp {
         }
         
         self.state.is_analyzing = false;
-        Ok(())
     }
 
     fn generate_clarification_questions(&mut self, result: &AnalysisResult) {
@@ -347,10 +380,16 @@ impl TuiApp {
         f.render_widget(input_widget, input_layout[0]);
 
         if self.state.is_analyzing {
+            let streamed = self.state.ai_response.lock().unwrap().chars().count();
+            let label = if streamed > 0 {
+                format!("Analyzing... {} chars from AI (see the Output tab)", streamed)
+            } else {
+                "Analyzing...".to_string()
+            };
             let progress = Gauge::default()
                 .block(Block::default().borders(Borders::ALL).title("Status"))
                 .gauge_style(Style::default().fg(Color::Yellow))
-                .label("Analyzing...")
+                .label(label)
                 .ratio(0.5);
             f.render_widget(progress, input_layout[1]);
         } else {
@@ -508,7 +547,22 @@ impl TuiApp {
     }
 
     fn render_output_tab<B: Backend>(&self, f: &mut Frame<B>, area: tui::layout::Rect) {
-        if let Some(result) = &self.state.analysis_result {
+        if self.state.is_analyzing {
+            let response = self.state.ai_response.lock().unwrap();
+            let text = if response.is_empty() {
+                "⏳ Analyzing... AI responses appear here as they stream in.".to_string()
+            } else {
+                // Keep the latest lines in view
+                let visible = area.height.saturating_sub(2) as usize;
+                let lines: Vec<&str> = response.lines().collect();
+                lines[lines.len().saturating_sub(visible)..].join("\n")
+            };
+            let streaming = Paragraph::new(text)
+                .style(Style::default().fg(Color::Cyan))
+                .block(Block::default().borders(Borders::ALL).title("🤖 AI Response (streaming)"))
+                .wrap(Wrap { trim: false });
+            f.render_widget(streaming, area);
+        } else if let Some(result) = &self.state.analysis_result {
             let layout = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
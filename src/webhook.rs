@@ -0,0 +1,275 @@
+//! GitHub and GitLab push events for `prism serve`: checking where they come
+//! from, finding the changed requirement files and commenting the results
+//! back on the pushed commit.
+
+use anyhow::Result;
+use reqwest::Client;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+
+use crate::analyzer::{AmbiguitySeverity, AnalysisResult};
+use crate::hierarchy::QualityScore;
+
+/// GitHub rejects commit comments longer than this.
+const MAX_COMMENT_LENGTH: usize = 65_000;
+
+/// Findings listed per file in a commit comment.
+const MAX_FINDINGS_PER_FILE: usize = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Forge {
+    GitHub,
+    GitLab,
+}
+
+impl Forge {
+    /// The forge that sent an event, from its `X-GitHub-Event` or `X-Gitlab-Event` header.
+    pub fn detect(github_event: Option<&str>, gitlab_event: Option<&str>) -> Option<(Self, String)> {
+        match (github_event, gitlab_event) {
+            (Some(event), _) => Some((Forge::GitHub, event.to_string())),
+            (None, Some(event)) => Some((Forge::GitLab, event.to_string())),
+            (None, None) => None,
+        }
+    }
+
+    pub fn is_push(&self, event: &str) -> bool {
+        match self {
+            Forge::GitHub => event == "push",
+            Forge::GitLab => event == "Push Hook",
+        }
+    }
+}
+
+/// Whether an event really comes from the webhook configured with `secret`:
+/// GitHub signs the body (`X-Hub-Signature-256`), GitLab sends the secret as
+/// `X-Gitlab-Token`.
+pub fn verify_event(forge: Forge, secret: &str, body: &[u8], signature: Option<&str>) -> bool {
+    let Some(signature) = signature else {
+        return false;
+    };
+    match forge {
+        Forge::GitHub => {
+            let expected = format!("sha256={}", hex(&hmac_sha256(secret.as_bytes(), body)));
+            constant_time_eq(expected.as_bytes(), signature.trim().as_bytes())
+        }
+        Forge::GitLab => constant_time_eq(secret.as_bytes(), signature.as_bytes()),
+    }
+}
+
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK_SIZE: usize = 64;
+    let mut key_block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        key_block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        key_block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(key_block.map(|b| b ^ 0x36));
+    inner.update(message);
+    let mut outer = Sha256::new();
+    outer.update(key_block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// A push, reduced to what is needed to analyze and comment on it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PushEvent {
+    pub forge: Forge,
+    /// `owner/repo` on GitHub, `group/project` on GitLab
+    pub repository: String,
+    /// GitLab's numeric project id
+    pub project_id: Option<u64>,
+    pub branch: String,
+    /// Commit the branch was pushed to
+    pub commit: String,
+    /// Files added or modified by the push and still present after it
+    pub changed_files: Vec<String>,
+}
+
+impl PushEvent {
+    /// `None` for pushes that delete a branch or tag.
+    pub fn parse(forge: Forge, payload: &Value) -> Result<Option<Self>> {
+        let commit = payload["after"].as_str().unwrap_or_default();
+        if commit.is_empty() || commit.chars().all(|c| c == '0') || payload["deleted"] == true {
+            return Ok(None);
+        }
+        if !commit.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(anyhow::anyhow!("Push event has an invalid commit id"));
+        }
+
+        let repository = match forge {
+            Forge::GitHub => payload.pointer("/repository/full_name"),
+            Forge::GitLab => payload.pointer("/project/path_with_namespace"),
+        };
+        let repository = repository.and_then(Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Push event has no repository name"))?;
+
+        // Later commits of the push win: a file added and then removed is not analyzed
+        let mut files = BTreeSet::new();
+        for commit in payload["commits"].as_array().into_iter().flatten() {
+            let paths = |key: &str| commit[key].as_array().cloned().unwrap_or_default();
+            for path in paths("added").iter().chain(&paths("modified")).filter_map(Value::as_str) {
+                files.insert(path.to_string());
+            }
+            for path in paths("removed").iter().filter_map(Value::as_str) {
+                files.remove(path);
+            }
+        }
+
+        Ok(Some(Self {
+            forge,
+            repository: repository.to_string(),
+            project_id: payload["project_id"].as_u64().or_else(|| payload.pointer("/project/id").and_then(Value::as_u64)),
+            branch: payload["ref"].as_str().unwrap_or_default().trim_start_matches("refs/heads/").to_string(),
+            commit: commit.to_string(),
+            changed_files: files.into_iter().collect(),
+        }))
+    }
+
+    /// Changed files under one of `paths` (all when empty) that `is_supported` accepts.
+    pub fn requirement_files(&self, paths: &[String], is_supported: impl Fn(&str) -> bool) -> Vec<String> {
+        self.changed_files.iter()
+            .filter(|file| {
+                paths.is_empty() || paths.iter().any(|path| {
+                    let path = path.trim_matches('/');
+                    file.as_str() == path || file.starts_with(&format!("{}/", path))
+                })
+            })
+            .filter(|file| is_supported(file))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Commit comment summarizing the analysis of the pushed requirement files.
+pub fn format_push_comment(commit: &str, results: &[(String, AnalysisResult)]) -> String {
+    let scores: Vec<QualityScore> = results.iter().map(|(_, result)| QualityScore::from_result(result)).collect();
+    let total = QualityScore::total(&scores);
+
+    let mut output = String::from("## 🔍 PRISM requirements check\n\n");
+    output.push_str(&format!(
+        "Commit `{}`: {} requirement file(s), {} finding(s), quality score **{:.0}/100**\n\n",
+        &commit[..commit.len().min(7)], results.len(), total.finding_count, total.score
+    ));
+
+    output.push_str("| File | Findings | Critical / High | Score |\n|---|---:|---:|---:|\n");
+    for ((file, result), score) in results.iter().zip(&scores) {
+        let severe = result.ambiguities.iter()
+            .filter(|a| matches!(a.severity, AmbiguitySeverity::Critical | AmbiguitySeverity::High))
+            .count();
+        output.push_str(&format!("| `{}` | {} | {} | {:.0} |\n", file, result.ambiguities.len(), severe, score.score));
+    }
+    output.push('\n');
+
+    for (file, result) in results.iter().filter(|(_, result)| !result.ambiguities.is_empty()) {
+        let mut section = format!("<details>\n<summary><code>{}</code>: {} finding(s)</summary>\n\n", file, result.ambiguities.len());
+        let mut findings: Vec<_> = result.ambiguities.iter().collect();
+        findings.sort_by_key(|a| std::cmp::Reverse(a.severity));
+        for finding in findings.iter().take(MAX_FINDINGS_PER_FILE) {
+            section.push_str(&format!("- **{}** \"{}\": {}\n", finding.severity, finding.text, finding.reason));
+        }
+        if findings.len() > MAX_FINDINGS_PER_FILE {
+            section.push_str(&format!("- ... and {} more\n", findings.len() - MAX_FINDINGS_PER_FILE));
+        }
+        section.push_str("\n</details>\n\n");
+
+        if output.len() + section.len() > MAX_COMMENT_LENGTH {
+            output.push_str("_Further files left out to keep the comment within GitHub's size limit._\n");
+            break;
+        }
+        output.push_str(&section);
+    }
+    output
+}
+
+/// Reads pushed files from, and comments on commits in, GitHub or GitLab.
+pub struct ForgeClient {
+    http_client: Client,
+    forge: Forge,
+    api_url: String,
+    token: Option<String>,
+}
+
+impl ForgeClient {
+    pub fn new(forge: Forge, api_url: &str, token: Option<String>) -> Self {
+        Self { http_client: Client::new(), forge, api_url: api_url.trim_end_matches('/').to_string(), token }
+    }
+
+    fn request(&self, method: reqwest::Method, url: reqwest::Url) -> reqwest::RequestBuilder {
+        let request = self.http_client.request(method, url).header("User-Agent", "prism");
+        match (self.forge, &self.token) {
+            (Forge::GitHub, Some(token)) => request.header("Authorization", format!("Bearer {}", token)),
+            (Forge::GitLab, Some(token)) => request.header("PRIVATE-TOKEN", token),
+            (_, None) => request,
+        }
+    }
+
+    /// API URL of `segments` below the repository, each segment escaped as a whole.
+    fn repository_url(&self, event: &PushEvent, segments: &[&str]) -> Result<reqwest::Url> {
+        let mut url = reqwest::Url::parse(&self.api_url)?;
+        {
+            let mut path = url.path_segments_mut().map_err(|_| anyhow::anyhow!("Invalid API URL: {}", self.api_url))?;
+            match self.forge {
+                Forge::GitHub => {
+                    path.push("repos").extend(event.repository.split('/'));
+                }
+                Forge::GitLab => {
+                    let project = event.project_id.map(|id| id.to_string()).unwrap_or_else(|| event.repository.clone());
+                    path.push("projects").push(&project);
+                }
+            }
+            path.extend(segments);
+        }
+        Ok(url)
+    }
+
+    /// Content of `path` at the pushed commit.
+    pub async fn fetch_file(&self, event: &PushEvent, path: &str) -> Result<Vec<u8>> {
+        let request = match self.forge {
+            Forge::GitHub => {
+                let mut url = self.repository_url(event, &["contents"])?;
+                url.path_segments_mut().map_err(|_| anyhow::anyhow!("Invalid API URL"))?.extend(path.split('/'));
+                url.query_pairs_mut().append_pair("ref", &event.commit);
+                self.request(reqwest::Method::GET, url).header("Accept", "application/vnd.github.raw")
+            }
+            Forge::GitLab => {
+                let mut url = self.repository_url(event, &["repository", "files", path, "raw"])?;
+                url.query_pairs_mut().append_pair("ref", &event.commit);
+                self.request(reqwest::Method::GET, url)
+            }
+        };
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Could not fetch {} at {} ({})", path, event.commit, response.status()));
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    pub async fn comment_on_commit(&self, event: &PushEvent, body: &str) -> Result<()> {
+        let (url, payload) = match self.forge {
+            Forge::GitHub => (self.repository_url(event, &["commits", &event.commit, "comments"])?, json!({ "body": body })),
+            Forge::GitLab => (self.repository_url(event, &["repository", "commits", &event.commit, "comments"])?, json!({ "note": body })),
+        };
+
+        let response = self.request(reqwest::Method::POST, url).json(&payload).send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Could not comment on commit {} ({}): {}", event.commit, status, error_text.trim()));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use prism::adr::{format_adr_markdown, AdrLinkReport, DecisionRecord};
+use std::path::Path;
+
+#[test]
+fn test_requirements_are_linked_to_citing_adrs() {
+    let stripe = DecisionRecord::parse("0001-use-stripe.md", "# ADR-0001: Use Stripe\n\n## Status\n\nAccepted\n\n## Context\nREQ-2 needs card payments; see also SEC-7 and REQ-2.\n");
+    assert_eq!(stripe.title, "ADR-0001: Use Stripe");
+    assert_eq!(stripe.status.as_deref(), Some("Accepted"));
+    assert_eq!(stripe.requirement_ids, vec!["REQ-2", "SEC-7"]);
+
+    let postgres = DecisionRecord::parse("0002-use-postgres.md", "# 2. Use PostgreSQL\n\n* Status: accepted\n\nWe need a relational database.\n");
+    assert_eq!(postgres.status.as_deref(), Some("accepted"));
+    let mysql = DecisionRecord::parse("0003-use-mysql.md", "Status: Superseded by ADR-0002\n");
+    assert_eq!(mysql.title, "0003-use-mysql");
+    assert!(mysql.requirement_ids.is_empty());
+    assert!(!mysql.is_active());
+
+    let documents = vec![("checkout.md".to_string(), "- REQ-1: The customer can add items to the cart.\n- REQ-2: The customer can pay by card.\n".to_string())];
+    let report = AdrLinkReport::new(&documents, vec![stripe, postgres, mysql]);
+
+    let constrained: Vec<&str> = report.constrained().iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(constrained, vec!["REQ-2"]);
+    // Superseded decisions are not reported as undriven
+    let undriven: Vec<&str> = report.undriven().iter().map(|d| d.file.as_str()).collect();
+    assert_eq!(undriven, vec!["0002-use-postgres.md"]);
+
+    let markdown = format_adr_markdown(Path::new("requirements"), Path::new("docs/adr"), &report);
+    assert!(markdown.contains("**Summary:** 1 of 2 requirements constrained by ADRs; 1 of 3 ADRs with no driving requirement"));
+    assert!(markdown.contains("| REQ-2: The customer can pay by card. | `checkout.md` | ADR-0001: Use Stripe (`0001-use-stripe.md`) |"));
+    assert!(markdown.contains("- `0001-use-stripe.md` cites **SEC-7**"));
+}
@@ -28,6 +28,169 @@ async fn test_ambiguity_detection_passive_voice() {
     assert_eq!(passive_ambiguity.unwrap().severity, AmbiguitySeverity::High);
 }
 
+#[tokio::test]
+async fn test_finding_fingerprints_are_stable() {
+    let analyzer = Analyzer::new().unwrap();
+    let original = "# Login\nThe system should be fast.\n# Search\nResults should be fast.";
+    let edited = "Intro paragraph added later.\n\n# Login\nThe system should be fast.\n# Search\nResults should be fast.";
+
+    let first = analyzer.analyze(original).await.unwrap();
+    let second = analyzer.analyze(edited).await.unwrap();
+
+    let fingerprints = |result: &AnalysisResult| -> Vec<String> {
+        result.ambiguities.iter().map(|a| a.fingerprint.clone()).collect()
+    };
+    assert!(first.ambiguities.iter().all(|a| !a.fingerprint.is_empty() && !a.rule_id.is_empty()));
+    assert_eq!(fingerprints(&first), fingerprints(&second));
+
+    // The same term in two different sections must not collide
+    let fast: Vec<&Ambiguity> = first.ambiguities.iter().filter(|a| a.text == "fast").collect();
+    assert_eq!(fast.len(), 2);
+    assert_ne!(fast[0].fingerprint, fast[1].fingerprint);
+    assert_eq!(fast[0].location.as_ref().unwrap().line, 2);
+}
+
+#[tokio::test]
+async fn test_numbered_requirements_are_not_fingerprint_sections() {
+    let analyzer = Analyzer::new().unwrap();
+    let fast_fingerprints = |result: AnalysisResult| -> Vec<String> {
+        result.ambiguities.into_iter().filter(|a| a.text == "fast").map(|a| a.fingerprint).collect()
+    };
+
+    // Renumbering the requirements of a section keeps their fingerprints
+    let original = analyzer.analyze("# Login\n1. The page should be fast.").await.unwrap();
+    let renumbered = analyzer.analyze("# Login\n1. Passwords must be hashed.\n2. The page should be fast.").await.unwrap();
+    assert_eq!(fast_fingerprints(original), fast_fingerprints(renumbered));
+
+    // Numbered titles still start a section
+    let fast = fast_fingerprints(analyzer.analyze("1 Login\nThe page should be fast.\n2.1 Search\nResults should be fast.").await.unwrap());
+    assert_eq!(fast.len(), 2);
+    assert_ne!(fast[0], fast[1]);
+}
+
+#[tokio::test]
+async fn test_inline_suppression_markers() {
+    let analyzer = Analyzer::new().unwrap();
+    let text = "The page should be fast. <!-- prism:ignore vague-term \"fast\" -->\n\
+                Search must be quick [prism-ok]\n\
+                <!-- prism:ignore -->\n\
+                Reports should be generated nightly.\n\
+                Exports should be easy.";
+
+    let result = analyzer.analyze(text).await.unwrap();
+
+    let active: Vec<&str> = result.ambiguities.iter().map(|a| a.text.as_str()).collect();
+    let suppressed: Vec<&str> = result.suppressed_ambiguities.iter().map(|a| a.text.as_str()).collect();
+    assert_eq!(active, vec!["easy"]);
+    assert!(suppressed.contains(&"fast"));
+    assert!(suppressed.contains(&"quick"));
+    assert!(suppressed.contains(&"should be generated"));
+    assert_eq!(suppressed.len(), 3);
+}
+
+#[tokio::test]
+async fn test_rule_severity_overrides_and_disabled_rules() {
+    let mut config = Config::default();
+    config.llm.api_key = None;
+    config.analysis.severity_overrides.insert("passive-voice".to_string(), AmbiguitySeverity::Low);
+    config.analysis.disabled_rules.push("vague-term".to_string());
+    let analyzer = Analyzer::new().unwrap().with_config(config);
+
+    let result = analyzer.analyze("The report should be generated quickly and fast").await.unwrap();
+
+    assert!(result.ambiguities.iter().all(|a| a.rule_id != "vague-term"));
+    let passive = result.ambiguities.iter().find(|a| a.rule_id == "passive-voice").unwrap();
+    assert_eq!(passive.severity, AmbiguitySeverity::Low);
+}
+
+#[test]
+fn test_deduplicate_overlapping_findings() {
+    let analyzer = Analyzer::new().unwrap();
+    let finding = |rule_id: &str, severity: AmbiguitySeverity, suggestion: &str| Ambiguity {
+        text: "fast".to_string(),
+        reason: format!("{} reason", rule_id),
+        suggestions: vec![suggestion.to_string()],
+        severity,
+        rule_id: rule_id.to_string(),
+        fingerprint: String::new(),
+        location: Some(SourceLocation { file: None, line: 1, column: 13, offset: 12 }),
+    };
+
+    let merged = analyzer.deduplicate_findings(vec![
+        finding("vague-term", AmbiguitySeverity::Medium, "Define specific metrics"),
+        finding("ai-ambiguity", AmbiguitySeverity::High, "Specify a response time"),
+        finding("vague-term", AmbiguitySeverity::Medium, "Define specific metrics"),
+    ]);
+
+    assert_eq!(merged.len(), 1);
+    assert_eq!(merged[0].severity, AmbiguitySeverity::High);
+    assert_eq!(merged[0].rule_id, "ai-ambiguity");
+    assert_eq!(merged[0].suggestions, vec!["Define specific metrics", "Specify a response time"]);
+
+    // Overlapping findings of another kind or on other text are not duplicates
+    let mut passive = finding("passive-voice", AmbiguitySeverity::Low, "Name the actor");
+    let mut wider = finding("vague-term", AmbiguitySeverity::Medium, "Define specific metrics");
+    wider.text = "fast enough".to_string();
+    let kept = analyzer.deduplicate_findings(vec![
+        finding("vague-term", AmbiguitySeverity::Medium, "Define specific metrics"),
+        passive.clone(),
+        wider,
+    ]);
+    assert_eq!(kept.len(), 3);
+
+    passive.location = None;
+    let kept = analyzer.deduplicate_findings(vec![finding("passive-voice", AmbiguitySeverity::Low, "Name the actor"), passive]);
+    assert_eq!(kept.len(), 1);
+}
+
+#[test]
+fn test_quoted_findings_are_located_despite_case_and_reflowed_lines() {
+    let analyzer = Analyzer::new().unwrap();
+    let text = "Login must be quick.\nSearch must be quick.\nReports are sent to the\nright people.";
+    let quoted = |quote: &str| Ambiguity {
+        text: quote.to_string(),
+        reason: "reason".to_string(),
+        suggestions: vec![],
+        severity: AmbiguitySeverity::Medium,
+        rule_id: RULE_AI_AMBIGUITY.to_string(),
+        fingerprint: String::new(),
+        location: None,
+    };
+
+    let mut findings = vec![quoted("quick"), quoted("quick"), quoted("\"Sent to the right people\""), quoted("nowhere")];
+    analyzer.locate_findings(text, &mut findings);
+    let lines: Vec<Option<(usize, usize)>> = findings.iter().map(|f| f.location.as_ref().map(|l| (l.line, l.column))).collect();
+    assert_eq!(lines, [Some((1, 15)), Some((2, 16)), Some((3, 13)), None]);
+    assert_eq!(findings[2].location.as_ref().unwrap().offset, text.find("sent").unwrap());
+}
+
+#[tokio::test]
+async fn test_reports_show_the_file_line_and_column_of_findings() {
+    let mut result = Analyzer::new().unwrap().analyze("# Login\nThe system should be fast.").await.unwrap();
+    result.set_source_file("specs/login.md");
+    let location = result.ambiguities.iter().find(|a| a.text == "fast").unwrap().location.clone().unwrap();
+    assert_eq!((location.line, location.column), (2, 22));
+    assert_eq!(location.to_string(), "specs/login.md:2:22");
+
+    let json = serde_json::to_value(&result).unwrap();
+    assert!(json["ambiguities"].as_array().unwrap().iter().any(|a| a["location"]["file"] == "specs/login.md"));
+    let markdown = prism::formatters::format_as_markdown(&result, "");
+    assert!(markdown.contains("- **Location:** specs/login.md:2:22"), "{}", markdown);
+    assert!(prism::formatters::format_as_plain(&result, "").contains("   Location: specs/login.md:2:22"));
+}
+
+#[tokio::test]
+async fn test_ambiguity_density_metrics() {
+    let analyzer = Analyzer::new().unwrap();
+    let result = analyzer.analyze("The system must be fast. Users should see several reports.").await.unwrap();
+
+    assert_eq!(result.metrics.word_count, 10);
+    assert_eq!(result.metrics.requirement_count, 2);
+    assert_eq!(result.ambiguities.len(), 2);
+    assert!((result.metrics.findings_per_100_words - 20.0).abs() < 0.01);
+    assert!((result.metrics.findings_per_requirement - 1.0).abs() < 0.01);
+}
+
 #[tokio::test]
 async fn test_entity_extraction_basic() {
     let analyzer = Analyzer::new().unwrap();
@@ -57,7 +220,7 @@ async fn test_uml_generation() {
         objects: vec!["account".to_string()],
     };
     
-    let uml = analyzer.generate_uml_use_case(&entities);
+    let uml = analyzer.generate_uml_use_case("The user can login. The admin can logout.", &entities);
     assert!(uml.contains("@startuml"));
     assert!(uml.contains("@enduml"));
     assert!(uml.contains("actor user"));
@@ -65,6 +228,58 @@ async fn test_uml_generation() {
     assert!(uml.contains("usecase UC1"));
 }
 
+#[tokio::test]
+async fn test_uml_use_case_links_actors_by_sentence() {
+    let analyzer = Analyzer::new().unwrap();
+    let entities = ExtractedEntities {
+        actors: vec!["user".to_string(), "admin".to_string()],
+        actions: vec!["login".to_string(), "delete".to_string()],
+        objects: vec![],
+    };
+
+    let uml = analyzer.generate_uml_use_case("The user can login. The admin can delete accounts.", &entities);
+    assert!(uml.contains("user --> UC1"));
+    assert!(uml.contains("admin --> UC2"));
+    assert!(!uml.contains("user --> UC2"));
+    assert!(!uml.contains("admin --> UC1"));
+}
+
+#[tokio::test]
+async fn test_permission_matrix_generation() {
+    let analyzer = Analyzer::new().unwrap();
+    let text = "The admin can delete accounts. The user can view reports. Invoices are archived nightly.";
+    let entities = ExtractedEntities {
+        actors: vec!["admin".to_string(), "user".to_string()],
+        actions: vec!["delete".to_string(), "view".to_string(), "archived".to_string()],
+        objects: vec!["accounts".to_string(), "reports".to_string()],
+    };
+
+    let matrix = analyzer.generate_permission_matrix(text, &entities);
+    assert!(matrix.is_granted("admin", "delete"));
+    assert!(matrix.is_granted("user", "view"));
+    assert!(!matrix.is_granted("user", "delete"));
+    assert!(!matrix.is_granted("admin", "view"));
+    assert_eq!(matrix.unassigned_actions, vec!["archived".to_string()]);
+}
+
+#[tokio::test]
+async fn test_permission_matrix_first_person_takes_the_actor_of_its_story() {
+    let analyzer = Analyzer::new().unwrap();
+    let text = "As a customer, I want to browse products.\nI can order products.\n\nAs an admin, I want to manage the catalog.\nI can delete products.\n\n## Reports\nWe export the sales.";
+    let entities = ExtractedEntities {
+        actors: vec!["customer".to_string(), "admin".to_string()],
+        actions: vec!["order".to_string(), "delete".to_string(), "export".to_string()],
+        objects: vec!["products".to_string()],
+    };
+
+    let matrix = analyzer.generate_permission_matrix(text, &entities);
+    assert!(matrix.is_granted("customer", "order"));
+    assert!(matrix.is_granted("admin", "delete"));
+    assert!(!matrix.is_granted("customer", "delete"));
+    assert!(!matrix.is_granted("admin", "order"));
+    assert_eq!(matrix.unassigned_actions, vec!["export".to_string()]);
+}
+
 #[tokio::test]
 async fn test_pseudocode_generation_generic() {
     let analyzer = Analyzer::new().unwrap();
@@ -113,6 +328,23 @@ async fn test_test_case_generation() {
     assert!(test_cases.edge_cases.iter().any(|t| t.contains("empty/null")));
 }
 
+#[tokio::test]
+async fn test_gherkin_generation() {
+    let analyzer = Analyzer::new().unwrap();
+    let text = "# Password Reset\n\nAs a customer, I want to reset my password so that I can sign in again.\n\n## Acceptance Criteria\n- Given a registered email, when the customer requests a reset, then a link is emailed\n- The link expires after 30 minutes.\n\nThe admin can view audit logs.";
+    let entities = ExtractedEntities {
+        actors: vec!["customer".to_string(), "admin".to_string()],
+        actions: vec!["reset".to_string(), "view".to_string()],
+        objects: vec!["password".to_string()],
+    };
+
+    let feature = analyzer.generate_gherkin(text, &entities);
+    assert!(feature.starts_with("Feature: Password Reset\n  As a customer\n  I want to reset my password\n  So that I can sign in again\n"));
+    assert!(feature.contains("  Scenario: Acceptance criterion 1\n    Given a registered email\n    When the customer requests a reset\n    Then a link is emailed\n"));
+    assert!(feature.contains("    When the customer tries to reset their password\n    Then the link expires after 30 minutes\n"));
+    assert!(feature.contains("  Scenario: Admin can view audit logs\n    Given an admin\n"));
+}
+
 #[tokio::test]
 async fn test_severity_levels() {
     let analyzer = Analyzer::new().unwrap();
@@ -191,4 +423,22 @@ fn test_ambiguity_severity_enum() {
     assert!(matches!(high, AmbiguitySeverity::High));
     assert!(matches!(medium, AmbiguitySeverity::Medium));
     assert!(matches!(low, AmbiguitySeverity::Low));
-}
\ No newline at end of file
+}
+#[tokio::test]
+async fn test_rewrite_suggestions_per_finding() {
+    let analyzer = Analyzer::new().unwrap();
+    let text = "# Login\nAs a user, I want the login to be fast. The password should be validated before access.";
+    let result = analyzer.analyze(text).await.unwrap();
+
+    let suggestions = analyzer.generate_rewrite_suggestions(text, &result.ambiguities).await.unwrap();
+    assert_eq!(suggestions.len(), result.ambiguities.len());
+
+    let fast = suggestions.iter().find(|s| s.finding == "fast").unwrap();
+    assert_eq!(fast.original, "As a user, I want the login to be fast.");
+    assert_eq!(fast.proposed, "As a user, I want the login to be within [N] seconds.");
+    assert_eq!(fast.line, Some(2));
+
+    let passive = suggestions.iter().find(|s| s.rule_id == "passive-voice").unwrap();
+    assert_eq!(passive.original, "The password should be validated before access.");
+    assert_eq!(passive.proposed, "The password should be validated by [responsible actor] before access.");
+}
@@ -0,0 +1,37 @@
+use prism::annotated::format_annotated_source;
+use prism::{format_report, Analyzer, OutputFormat};
+
+const SPEC: &str = "# Login\nThe system should be fast.\n\tReports should be generated.\nUsers log in.";
+
+#[tokio::test]
+async fn test_findings_are_marked_under_their_lines_with_a_severity_gutter() {
+    let result = Analyzer::new().unwrap().analyze(SPEC).await.unwrap();
+    let annotated = format_annotated_source(&result, SPEC, Some("login.md"));
+    let lines: Vec<&str> = annotated.lines().collect();
+
+    assert_eq!(lines[0], "ANNOTATED REQUIREMENTS: login.md");
+    assert!(lines.contains(&"  1 | # Login"), "{}", annotated);
+    let fast = lines.iter().position(|l| l.ends_with("| The system should be fast.")).unwrap();
+    assert!(lines[fast].starts_with("M 2 |"), "{}", annotated);
+    assert!(lines[fast + 1].starts_with("    |                      ^^^^ Medium [vague-term] "), "{}", annotated);
+    // The marker keeps the tab of its line, so it stays under the finding
+    let reports = lines.iter().position(|l| l.ends_with("| \tReports should be generated.")).unwrap();
+    assert!(lines[reports].starts_with("H 3 |"), "{}", annotated);
+    assert!(lines[reports + 1].starts_with("    | \t        ^^^^^^^^^^^^^^^^^^^ High [passive-voice] "), "{}", annotated);
+    assert!(lines.contains(&"  4 | Users log in."), "{}", annotated);
+}
+
+#[tokio::test]
+async fn test_annotated_format_lists_findings_it_cannot_place() {
+    let mut result = Analyzer::new().unwrap().analyze("Users log in.").await.unwrap();
+    assert!(result.ambiguities.is_empty());
+    let annotated = format_report(&result, "Users log in.", &OutputFormat::Annotated, None).unwrap();
+    assert_eq!(annotated, "ANNOTATED REQUIREMENTS: input\nNo ambiguities detected\n\n  1 | Users log in.\n");
+
+    let mut finding = Analyzer::new().unwrap().analyze("It should be fast.").await.unwrap().ambiguities.remove(0);
+    finding.location = None;
+    result.ambiguities.push(finding);
+    let annotated = format_report(&result, "Users log in.", &OutputFormat::Annotated, None).unwrap();
+    assert!(annotated.contains("1 finding(s): 1 Medium\n"), "{}", annotated);
+    assert!(annotated.ends_with("\nNot found in the text:\nM \"fast\" - Medium [vague-term] Vague or subjective term that lacks specific criteria\n"), "{}", annotated);
+}
@@ -0,0 +1,38 @@
+use prism::analyzer::ExtractedEntities;
+use prism::architecture::generate_architecture_pseudocode;
+use prism::cli::ArchitectureStyle;
+
+fn entities() -> ExtractedEntities {
+    ExtractedEntities {
+        actors: vec!["As a customer".to_string()],
+        actions: vec!["want to place".to_string(), "approve".to_string()],
+        objects: vec!["invoice".to_string(), "order".to_string()],
+    }
+}
+
+const TEXT: &str = "As a customer, I want to place an order.\nThe manager can approve the invoice once the order is paid.";
+
+#[test]
+fn test_actions_are_scaffolded_on_their_objects_per_architecture() {
+    let layered = generate_architecture_pseudocode(TEXT, &entities(), None, ArchitectureStyle::Layered);
+    assert!(layered.contains("// Actors: customer\n"));
+    assert!(layered.contains("class OrderService {"));
+    assert!(layered.contains("    public Order place(String orderId) {\n        Order order = this.orderRepository.findById(orderId);"));
+    assert!(layered.contains("class InvoiceController {"));
+    assert!(layered.contains("        return this.invoiceService.approve(invoiceId);"));
+
+    let hexagonal = generate_architecture_pseudocode(TEXT, &entities(), Some("python"), ArchitectureStyle::Hexagonal);
+    assert!(hexagonal.contains("class PlaceOrderUseCase(ABC):"));
+    assert!(hexagonal.contains("class PlaceOrderService(PlaceOrderUseCase):"));
+    assert!(hexagonal.contains("class OrderPersistenceAdapter(OrderRepository):"));
+    assert!(hexagonal.contains("    def approve(self) -> None:"));
+
+    let mvc = generate_architecture_pseudocode(TEXT, &entities(), None, ArchitectureStyle::Mvc);
+    assert!(mvc.contains("class OrderView {"));
+    assert!(mvc.contains("        return this.invoiceView.render(invoice);"));
+
+    let events = generate_architecture_pseudocode(TEXT, &entities(), Some("python"), ArchitectureStyle::EventDriven);
+    assert!(events.contains("class ApproveInvoiceRequested(Message):"));
+    assert!(events.contains("        self.event_bus.publish(PlaceOrderCompleted(order.id))"));
+    assert!(!events.contains("want to"));
+}
@@ -0,0 +1,80 @@
+use prism::analyzer::{
+    Ambiguity, AmbiguitySeverity, Gap, GapPriority, NfrCategory, NfrPriority, NonFunctionalRequirement, RewriteSuggestion,
+    SourceLocation,
+};
+use prism::artifacts::{
+    artifact_file_name, format_artifact_index, nfrs_for_gap, suggestion_for, ArtifactLinks, SavedArtifact,
+};
+
+fn nfr(category: NfrCategory) -> NonFunctionalRequirement {
+    NonFunctionalRequirement {
+        category,
+        requirement: "Requirement".to_string(),
+        rationale: "Rationale".to_string(),
+        acceptance_criteria: vec![],
+        priority: NfrPriority::ShouldHave,
+    }
+}
+
+fn gap(category: &str, description: &str) -> Gap {
+    Gap {
+        category: category.to_string(),
+        description: description.to_string(),
+        suggestions: vec![],
+        priority: GapPriority::Medium,
+    }
+}
+
+#[test]
+fn test_gaps_link_to_the_nfrs_generated_for_them() {
+    let nfrs = vec![nfr(NfrCategory::Security), nfr(NfrCategory::Performance), nfr(NfrCategory::Security)];
+
+    let general = nfrs_for_gap(&gap("Non-Functional Requirements", "Missing quality attributes"), &nfrs);
+    let ids: Vec<&str> = general.iter().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(ids, vec!["NFR-Performance-01", "NFR-Security-01", "NFR-Security-02"]);
+    assert_eq!(general[2].1, "nfr-security-02");
+
+    let security = nfrs_for_gap(&gap("Access Control", "No security requirements for admin actions"), &nfrs);
+    assert_eq!(security, vec![
+        ("NFR-Security-01".to_string(), "nfr-security-01".to_string()),
+        ("NFR-Security-02".to_string(), "nfr-security-02".to_string()),
+    ]);
+
+    assert!(nfrs_for_gap(&gap("Actor Definition", "No clear actors identified"), &nfrs).is_empty());
+}
+
+#[test]
+fn test_findings_link_to_their_rewrite_and_index_lists_artifacts() {
+    let finding = |text: &str, line: usize| Ambiguity {
+        text: text.to_string(),
+        reason: String::new(),
+        suggestions: vec![],
+        severity: AmbiguitySeverity::Medium,
+        rule_id: "vague-term".to_string(),
+        fingerprint: String::new(),
+        location: Some(SourceLocation { file: None, line, column: 1, offset: 0 }),
+    };
+    let rewrite = |text: &str, line: usize| RewriteSuggestion {
+        finding: text.to_string(),
+        rule_id: "vague-term".to_string(),
+        line: Some(line),
+        original: String::new(),
+        proposed: String::new(),
+    };
+    let rewrites = vec![rewrite("fast", 1), rewrite("fast", 3)];
+
+    assert_eq!(suggestion_for(&finding("fast", 3), &rewrites), Some(2));
+    assert_eq!(suggestion_for(&finding("quickly", 2), &rewrites), None);
+
+    let links = ArtifactLinks { index: Some(artifact_file_name("reports/login flow_Index.md")), ..Default::default() };
+    assert_eq!(links.back_link(), "[← Artifact index](login%20flow_Index.md)\n\n");
+    assert_eq!(ArtifactLinks::default().back_link(), "");
+
+    let index = format_artifact_index("login flow", &[SavedArtifact {
+        title: "🔒 Non-functional requirements".to_string(),
+        file_name: "login flow_NFR.md".to_string(),
+        description: "2 suggested NFRs".to_string(),
+    }]);
+    assert!(index.starts_with("# 🗂️ Artifact Index for: login flow\n"));
+    assert!(index.contains("| 🔒 Non-functional requirements | [login flow_NFR.md](login%20flow_NFR.md) | 2 suggested NFRs |"));
+}
@@ -0,0 +1,45 @@
+use prism::analyzer::Analyzer;
+use prism::authoring::*;
+
+#[test]
+fn test_answers_are_normalized_and_checked() {
+    let analyzer = Analyzer::new().unwrap();
+
+    assert_eq!(normalize_answer(DraftField::Actor, "As a support agent"), "support agent");
+    assert_eq!(normalize_answer(DraftField::Goal, "I want to reset my password."), "reset my password");
+    assert_eq!(normalize_answer(DraftField::Benefit, "so that I can log in again"), "I can log in again");
+
+    assert!(!check_answer(&analyzer, DraftField::Actor, "").errors.is_empty());
+    assert!(!check_answer(&analyzer, DraftField::Actor, "user").warnings.is_empty());
+    assert!(check_answer(&analyzer, DraftField::Actor, "support agent").is_clean());
+
+    let vague = check_answer(&analyzer, DraftField::Goal, "see a fast dashboard");
+    assert!(vague.warnings.iter().any(|w| w.contains("'fast'")));
+
+    assert!(!check_answer(&analyzer, DraftField::AcceptanceCriterion, "it works").warnings.is_empty());
+    assert!(check_answer(&analyzer, DraftField::AcceptanceCriterion, "Given a locked account, when I reset, then it unlocks").is_clean());
+    assert!(!check_answer(&analyzer, DraftField::Nfr, "Pages load quickly").warnings.is_empty());
+}
+
+#[test]
+fn test_draft_renders_with_default_and_custom_templates() {
+    let draft = RequirementDraft {
+        title: RequirementDraft::title_from_goal("reset my password"),
+        actor: "account owner".to_string(),
+        goal: "reset my password".to_string(),
+        benefit: "I can log in again".to_string(),
+        acceptance_criteria: vec!["Given a registered email, when I request a reset, then a link is sent".to_string()],
+        nfrs: vec![],
+    };
+
+    assert_eq!(draft.file_name(), "reset-my-password.md");
+
+    let content = draft.render(None).unwrap();
+    assert!(content.starts_with("# Reset my password\n"));
+    assert!(content.contains("As an account owner, I want to reset my password so that I can log in again."));
+    assert!(content.contains("- Given a registered email, when I request a reset, then a link is sent\n"));
+    assert!(content.contains("## Non-Functional Requirements\n\n- _None specified_\n"));
+
+    let custom = draft.render(Some("{{actor}} -> {{goal}}: {{acceptance_criteria.[0]}}")).unwrap();
+    assert_eq!(custom, "account owner -> reset my password: Given a registered email, when I request a reset, then a link is sent");
+}
@@ -0,0 +1,31 @@
+use prism::badge::{badge_color, format_badge_json, format_badge_svg};
+use prism::hierarchy::QualityScore;
+use serde_json::json;
+
+fn score(value: f32) -> QualityScore {
+    QualityScore { requirement_count: 10, finding_count: 3, penalty: 0.0, score: value }
+}
+
+#[test]
+fn test_badge_shows_score_with_its_color() {
+    assert_eq!(badge_color(95.0).0, "brightgreen");
+    assert_eq!(badge_color(75.0).0, "green");
+    assert_eq!(badge_color(59.9).0, "orange");
+    assert_eq!(badge_color(12.0).0, "red");
+
+    assert_eq!(
+        format_badge_json("Requirements", &score(89.6)),
+        json!({ "schemaVersion": 1, "label": "Requirements", "message": "90/100", "color": "brightgreen" })
+    );
+
+    let svg = format_badge_svg("Specs & Stories", &score(42.0));
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.contains("aria-label=\"Specs &amp; Stories: 42/100\""));
+    assert!(svg.contains(">42/100</text>"));
+    assert!(svg.contains("fill=\"#fe7d37\""));
+
+    // Control characters are not allowed in XML
+    let svg = format_badge_svg("Specs\u{7}", &score(42.0));
+    assert!(!svg.contains('\u{7}'));
+    assert!(svg.contains("aria-label=\"Specs: 42/100\""));
+}
@@ -0,0 +1,41 @@
+use prism::analyzer::Analyzer;
+use prism::baseline::{format_baseline, BaselineEntry, FindingsBaseline};
+
+const SPEC: &str = "# Login\nThe system should be fast.\n\n# Search\nResults should be generated.\n";
+
+#[test]
+fn test_baseline_files_are_read_line_by_line() {
+    let baseline = FindingsBaseline::parse("# accepted\n\n3f1c9a0e docs/login.md 2:22 vague-term \"fast\"\n  77aa01 - -\nb0b0 my%20specs/a.md\n");
+    assert_eq!(baseline.entries, vec![
+        BaselineEntry { fingerprint: "3f1c9a0e".to_string(), file: Some("docs/login.md".to_string()) },
+        BaselineEntry { fingerprint: "77aa01".to_string(), file: None },
+        BaselineEntry { fingerprint: "b0b0".to_string(), file: Some("my specs/a.md".to_string()) },
+    ]);
+}
+
+#[tokio::test]
+async fn test_accepted_findings_are_left_out_and_new_ones_reported() {
+    let analyzer = Analyzer::new().unwrap();
+    let mut accepted = analyzer.analyze(SPEC).await.unwrap();
+    accepted.set_source_file("login.md");
+    let found = accepted.ambiguities.len();
+    assert!(found >= 2);
+    let baseline = FindingsBaseline::parse(&format_baseline(&[(Some("login.md".to_string()), accepted)], "2026-10-16"));
+    assert_eq!(baseline.entries.len(), found);
+
+    // Text added above the reviewed sections brings one new finding
+    let edited = format!("# Export\nExports should be quick.\n\n{}", SPEC);
+    let mut result = analyzer.analyze(&edited).await.unwrap();
+    result.set_source_file("requirements/login.md");
+    baseline.apply(&mut result);
+    let texts: Vec<&str> = result.ambiguities.iter().map(|a| a.text.as_str()).collect();
+    assert_eq!(texts, ["quick"]);
+    assert_eq!(result.hidden_findings.baselined, found);
+    assert_eq!(result.hidden_findings.summary(), Some(format!("{} accepted in the baseline", found)));
+
+    // The same fingerprints in another file are still reported
+    let mut other = analyzer.analyze(SPEC).await.unwrap();
+    other.set_source_file("billing.md");
+    baseline.apply(&mut other);
+    assert_eq!(other.ambiguities.len(), found);
+}
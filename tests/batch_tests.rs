@@ -0,0 +1,99 @@
+use prism::batch::{
+    content_hash, format_batch_index_markdown, relative_name, report_file_name, report_path, suggest_remediation,
+    unique_path, BatchFailure, BatchIndexEntry, FailureStage, ResumeManifest,
+};
+use std::collections::HashSet;
+use std::path::Path;
+
+#[test]
+fn test_resume_manifest_tracks_batch_progress() {
+    let dir = std::env::temp_dir().join("prism_resume_manifest_test");
+    let path = dir.join("manifest.json");
+    let files = ["epic/a.md", "epic/b.md", "c.md"];
+    let mut manifest = ResumeManifest {
+        pending: files.iter().map(|f| relative_name(&dir, &dir.join(f))).collect(),
+        ..Default::default()
+    };
+    assert_eq!(manifest.pending, files);
+    assert!(manifest.has_remaining());
+
+    manifest.mark_completed("epic/a.md", "As a user, I want to log in.");
+    manifest.mark_failed("epic/b.md");
+    manifest.cancelled = true;
+    manifest.save(&path).unwrap();
+
+    let saved: ResumeManifest = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(saved.pending, vec!["c.md"]);
+    assert_eq!(saved.failed, vec!["epic/b.md"]);
+    assert_eq!(saved.completed["epic/a.md"], content_hash("As a user, I want to log in."));
+    assert!(saved.cancelled);
+
+    let loaded = ResumeManifest::load(&path).unwrap();
+    assert!(loaded.is_unchanged("epic/a.md", "As a user, I want to log in."));
+    assert!(!loaded.is_unchanged("epic/a.md", "As a user, I want to log in quickly."));
+    assert!(!loaded.is_unchanged("c.md", ""));
+
+    assert_eq!(relative_name(Path::new("/reqs"), Path::new("/other/x.md")), "/other/x.md");
+
+    // Kept under ~/.prism, one per analyzed directory
+    let kept_at = ResumeManifest::path(&dir).unwrap();
+    assert!(kept_at.starts_with(prism::paths::prism_dir().unwrap().join("resume")));
+    assert_ne!(kept_at, ResumeManifest::path(Path::new("other")).unwrap());
+    let finished = ResumeManifest { completed: loaded.completed.clone(), ..Default::default() };
+    assert!(!finished.has_remaining());
+
+    let markdown = ResumeManifest::settings("Some(Markdown)", "full", None, Some(Path::new("reports")));
+    assert_ne!(markdown, ResumeManifest::settings("Some(Json)", "full", None, Some(Path::new("reports"))));
+    assert_ne!(markdown, ResumeManifest::settings("Some(Markdown)", "full", None, Some(Path::new("out"))));
+
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(ResumeManifest::load(&path).is_none());
+}
+
+#[test]
+fn test_batch_reports_mirror_input_tree_without_collisions() {
+    let dir = Path::new("reqs");
+    let out = Path::new("reports");
+    let mut taken = HashSet::new();
+
+    let mut place = |file: &str| {
+        let file = dir.join(file);
+        unique_path(report_path(dir, &file, Some(out), &report_file_name(&file, None)), &mut taken)
+    };
+    assert_eq!(place("epic/login.md"), out.join("epic/login_analysis.md"));
+    assert_eq!(place("epic/login.txt"), out.join("epic/login_analysis_2.md"));
+    assert_eq!(place("login.md"), out.join("login_analysis.md"));
+
+    // Without an output directory reports go to the working directory, named after --output
+    let file = dir.join("epic/login.md");
+    assert_eq!(report_path(dir, &file, None, &report_file_name(&file, Some(Path::new("out.json")))), Path::new("out_login.json"));
+
+    let entries = vec![
+        BatchIndexEntry { source: "epic/login.md".to_string(), report: "epic/login_analysis.md".to_string(), findings: Some(3), findings_per_100_words: Some(12.5) },
+        BatchIndexEntry { source: "my story.md".to_string(), report: "my story_analysis.md".to_string(), findings: None, findings_per_100_words: None },
+    ];
+    let index = format_batch_index_markdown(dir, &entries);
+    assert!(index.contains("| `epic/login.md` | 3 | 12.5 | [epic/login_analysis.md](epic/login_analysis.md) |"));
+    assert!(index.contains("[my story_analysis.md](my%20story_analysis.md)"));
+    assert!(index.contains("| — | unchanged |"));
+}
+
+#[test]
+fn test_batch_failures_carry_stage_and_remediation() {
+    let failure = BatchFailure::new(
+        "specs/locked.pdf",
+        FailureStage::Extraction,
+        &anyhow::anyhow!("PDF error: file is encrypted").context("Failed to extract PDF text"),
+    );
+    assert_eq!(failure.error, "Failed to extract PDF text: PDF error: file is encrypted");
+    assert!(failure.remediation.contains("password-protected"));
+
+    let json = serde_json::to_value(&failure).unwrap();
+    assert_eq!(json["file"], "specs/locked.pdf");
+    assert_eq!(json["stage"], "extraction");
+
+    assert!(suggest_remediation(FailureStage::Extraction, "stream did not contain valid UTF-8").contains("UTF-8"));
+    assert!(suggest_remediation(FailureStage::Analysis, "request timed out").contains("llm.timeout"));
+    assert!(suggest_remediation(FailureStage::Analysis, "HTTP 429 Too Many Requests").contains("rate-limited"));
+    assert!(suggest_remediation(FailureStage::Analysis, "something odd").contains("prism analyze --file"));
+}
@@ -0,0 +1,44 @@
+use prism::budget::{AnalysisBudget, BudgetTracker, LlmUsage};
+use std::time::Duration;
+
+#[test]
+fn test_budget_parsing() {
+    assert_eq!("60s".parse::<AnalysisBudget>().unwrap(), AnalysisBudget::Time(Duration::from_secs(60)));
+    assert_eq!("5m".parse::<AnalysisBudget>().unwrap(), AnalysisBudget::Time(Duration::from_secs(300)));
+    assert_eq!("1.5h".parse::<AnalysisBudget>().unwrap(), AnalysisBudget::Time(Duration::from_secs(5400)));
+    assert_eq!("$0.50".parse::<AnalysisBudget>().unwrap(), AnalysisBudget::Cost(0.5));
+
+    for invalid in ["", "fast", "10 days", "$", "$-1", "5x"] {
+        let error = invalid.parse::<AnalysisBudget>().unwrap_err();
+        assert!(error.contains("Invalid budget"), "{}", error);
+    }
+}
+
+#[test]
+fn test_out_of_range_budget_is_an_error() {
+    for invalid in ["99999999999999999999999s", "99999999999999999999999h"] {
+        let error = invalid.parse::<AnalysisBudget>().unwrap_err();
+        assert!(error.contains("Invalid budget"), "{}", error);
+    }
+}
+
+#[test]
+fn test_cost_budget_exhausts_and_stays_exhausted() {
+    let mut tracker = BudgetTracker::start(AnalysisBudget::Cost(0.01));
+    let mut usage = LlmUsage::default();
+    assert!(!tracker.is_exhausted(&usage, "openai", "gpt-4o"));
+
+    // 8k characters ≈ 2k tokens each way at $2.50/$10 per million ≈ $0.025
+    usage.calls = 1;
+    usage.prompt_chars = 8_000;
+    usage.response_chars = 8_000;
+    assert!((usage.estimated_cost("openai", "gpt-4o") - 0.025).abs() < 1e-9);
+    assert!(tracker.is_exhausted(&usage, "openai", "gpt-4o"));
+    assert!(tracker.is_exhausted(&LlmUsage::default(), "openai", "gpt-4o"));
+
+    // Local models cost nothing
+    assert_eq!(usage.estimated_cost("ollama", "llama3"), 0.0);
+
+    let mut tracker = BudgetTracker::start(AnalysisBudget::Time(Duration::ZERO));
+    assert!(tracker.is_exhausted(&LlmUsage::default(), "openai", "gpt-4o"));
+}
@@ -0,0 +1,68 @@
+use prism::analyzer::{AmbiguitySeverity, Analyzer};
+use prism::cache::AnalysisCache;
+use prism::config::{Config, CustomRule};
+use prism::custom_rules::parse_rules_file;
+
+fn openai_config(model: &str) -> Config {
+    let mut config = Config::default();
+    config.llm.provider = "openai".to_string();
+    config.llm.model = model.to_string();
+    config
+}
+
+#[test]
+fn test_cache_key_covers_text_model_and_options() {
+    let text = "The system should respond quickly.";
+    let key = AnalysisCache::key(text, &openai_config("gpt-4"), "basic");
+
+    assert_eq!(key, AnalysisCache::key(text, &openai_config("gpt-4"), "basic"));
+    assert_ne!(key, AnalysisCache::key("The system shall respond within 2s.", &openai_config("gpt-4"), "basic"));
+    assert_ne!(key, AnalysisCache::key(text, &openai_config("gpt-4o"), "basic"));
+    assert_ne!(key, AnalysisCache::key(text, &openai_config("gpt-4"), "full"));
+
+    let mut stricter = openai_config("gpt-4");
+    stricter.analysis.disabled_rules.push("passive-voice".to_string());
+    assert_ne!(key, AnalysisCache::key(text, &stricter, "basic"));
+}
+
+#[test]
+fn test_rules_fingerprint_covers_rules_and_their_overrides() {
+    let rules = parse_rules_file("rules:\n  - id: tbd\n    keywords: [TBD]\n").unwrap();
+    let fingerprint = |rules: Vec<CustomRule>, config: Config| {
+        Analyzer::new().unwrap().with_config(config).with_custom_rules(rules).unwrap().rules_fingerprint()
+    };
+    let base = fingerprint(rules.clone(), Config::default());
+
+    assert_eq!(base, fingerprint(rules.clone(), Config::default()));
+    assert_ne!(base, fingerprint(vec![], Config::default()));
+    let stricter = parse_rules_file("rules:\n  - id: tbd\n    keywords: [TBD]\n    severity: High\n").unwrap();
+    assert_ne!(base, fingerprint(stricter, Config::default()));
+
+    let mut config = Config::default();
+    config.analysis.severity_overrides.insert("tbd".to_string(), AmbiguitySeverity::Critical);
+    assert_ne!(base, fingerprint(rules.clone(), config));
+    let mut config = Config::default();
+    config.analysis.disabled_rules.push("tbd".to_string());
+    assert_ne!(base, fingerprint(rules, config));
+}
+
+#[tokio::test]
+async fn test_cached_results_are_returned_until_cleared() {
+    let dir = std::env::temp_dir().join(format!("prism-cache-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let cache = AnalysisCache::new(&dir);
+
+    let text = "The system should respond quickly.";
+    let result = Analyzer::new().unwrap().analyze(text).await.unwrap();
+    let key = AnalysisCache::key(text, &openai_config("gpt-4"), "basic");
+    assert!(cache.get(&key).is_none());
+
+    cache.put(&key, &result).unwrap();
+    let cached = cache.get(&key).unwrap();
+    assert_eq!(cached.ambiguities.len(), result.ambiguities.len());
+    assert_eq!(cache.size().unwrap().0, 1);
+
+    assert_eq!(cache.clear().unwrap(), 1);
+    assert!(cache.get(&key).is_none());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
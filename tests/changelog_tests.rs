@@ -0,0 +1,47 @@
+use prism::analyzer::{Ambiguity, AmbiguitySeverity, SourceLocation, RULE_VAGUE_TERM};
+use prism::changelog::{build_changelog, format_changelog_markdown};
+
+const ORIGINAL: &str = "# Login\nThe system should respond quickly.\nUsers log in with a password.\n";
+const IMPROVED: &str = "# Login\nThe system should respond within 2 seconds.\nUsers log in with a password.\nLocked accounts show an error.\n";
+
+fn quickly() -> Ambiguity {
+    Ambiguity {
+        text: "quickly".to_string(),
+        reason: "How quickly?".to_string(),
+        suggestions: vec![],
+        severity: AmbiguitySeverity::High,
+        rule_id: RULE_VAGUE_TERM.to_string(),
+        fingerprint: "3f9a1c2e7b6d5a40".to_string(),
+        location: Some(SourceLocation { file: None, line: 2, column: 27, offset: ORIGINAL.find("quickly").unwrap() }),
+    }
+}
+
+#[test]
+fn test_edits_are_attributed_to_the_findings_they_change() {
+    let changes = build_changelog(ORIGINAL, IMPROVED, &[quickly()]);
+    assert_eq!(changes.len(), 2, "{:#?}", changes);
+
+    assert_eq!(changes[0].line, 2);
+    assert!(changes[0].original.contains("quickly"), "{:?}", changes[0]);
+    assert!(changes[0].improved.contains("within 2 seconds"), "{:?}", changes[0]);
+    assert_eq!(&ORIGINAL[changes[0].original_offset..][..changes[0].original.len()], changes[0].original);
+    assert_eq!(&IMPROVED[changes[0].improved_offset..][..changes[0].improved.len()], changes[0].improved);
+    assert_eq!(changes[0].findings, ["3f9a1c2e7b6d5a40"]);
+
+    // A paragraph the model added on its own addresses nothing
+    assert_eq!(changes[1].line, 4);
+    assert!(changes[1].original.is_empty());
+    assert!(changes[1].findings.is_empty());
+
+    assert!(build_changelog(ORIGINAL, ORIGINAL, &[quickly()]).is_empty());
+}
+
+#[test]
+fn test_changelog_markdown_footnotes_the_findings() {
+    let findings = [quickly()];
+    let markdown = format_changelog_markdown(&build_changelog(ORIGINAL, IMPROVED, &findings), &findings);
+    assert!(markdown.contains("- Line 2: ~~quickly.~~ → **within 2 seconds.**[^change-1]"), "{}", markdown);
+    assert!(markdown.contains("- Line 4: added **Locked accounts show an error.**\n"), "{}", markdown);
+    assert!(markdown.contains("[^change-1]: Addresses \"quickly\" - How quickly? (`3f9a1c2e`)"), "{}", markdown);
+    assert!(!markdown.contains("[^change-2]"));
+}
@@ -0,0 +1,37 @@
+use prism::analyzer::Analyzer;
+use prism::chat::ChatSession;
+
+const SPEC: &str = "As a customer, I want to pay by card so that checkout is fast.\nRefunds should be processed within 5 days.\n\n# Admin\nAs an administrator, I want to export payment reports.\n";
+
+async fn session() -> ChatSession {
+    let analyzer = Analyzer::new().unwrap();
+    let result = analyzer.analyze(SPEC).await.unwrap();
+    ChatSession::new("spec.md", SPEC, result)
+}
+
+#[tokio::test]
+async fn test_chat_answers_are_grounded_in_source_lines() {
+    let session = session().await;
+
+    let answer = session.answer_builtin("which requirements mention payments?");
+    assert!(answer.contains("line 1: As a customer, I want to pay by card"));
+    assert!(answer.contains("line 5: As an administrator, I want to export payment reports."));
+    assert!(!answer.contains("Refunds"));
+
+    let answer = session.answer_builtin("what's still ambiguous?");
+    assert!(answer.contains("line 1: 'fast'"));
+    assert!(answer.contains("line 2: 'should be processed'"));
+
+    assert!(session.answer_builtin("who are the actors?").contains("customer"));
+    assert!(session.answer_builtin("which requirements mention shipping?").starts_with("I couldn't find"));
+}
+
+#[tokio::test]
+async fn test_chat_llm_prompt_contains_relevant_excerpts() {
+    let session = session().await;
+    let prompt = session.llm_prompt("what is the refund deadline?");
+
+    assert!(prompt.contains("[line 2] Refunds should be processed within 5 days."));
+    assert!(!prompt.contains("[line 5]"));
+    assert!(prompt.contains("QUESTION: what is the refund deadline?"));
+}
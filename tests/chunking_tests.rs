@@ -0,0 +1,70 @@
+use prism::analyzer::{split_into_chunks, Analyzer, RULE_AI_AMBIGUITY};
+use prism::config::Config;
+
+const SPEC: &str = "# Login\n\nThe member should log in quickly with a password.\n\nSessions end after a while.\n\n# Billing\n\nInvoices should be sent quickly to the customer.\n";
+
+fn chunked_mock_config(responses: &str) -> Config {
+    let mut config = Config::default();
+    config.llm.api_key = None;
+    config.set_provider("mock");
+    let path = std::env::temp_dir().join(format!("prism-chunks-{}-{}.yaml", std::process::id(), responses.len()));
+    std::fs::write(&path, responses).unwrap();
+    config.llm.mock_responses = Some(path);
+    config.llm.chunking.max_chars = 110;
+    config
+}
+
+#[test]
+fn test_documents_are_split_at_headings_then_paragraphs() {
+    let chunks = split_into_chunks(SPEC, 110);
+    assert_eq!(chunks.len(), 2);
+    assert!(chunks[1].text.starts_with("# Billing"), "{:?}", chunks);
+    assert_eq!(chunks[1].offset, SPEC.find("# Billing").unwrap());
+    assert_eq!(chunks.iter().map(|c| c.text.as_str()).collect::<String>(), SPEC);
+
+    // A long paragraph without headings breaks at a blank line, else a space
+    let text = format!("{}\n\n{}", "word ".repeat(10), "é".repeat(30));
+    let chunks = split_into_chunks(&text, 40);
+    assert_eq!(chunks[0].text, "word word word word word word word word ");
+    assert!(chunks.iter().all(|c| c.text.len() <= 40));
+    assert_eq!(chunks.iter().map(|c| c.text.as_str()).collect::<String>(), text);
+    assert_eq!(split_into_chunks(SPEC, 0).len(), 1);
+}
+
+#[tokio::test]
+async fn test_chunks_are_analyzed_separately_and_merged_with_document_locations() {
+    let config = chunked_mock_config(r#"
+tasks:
+  ambiguities: |
+    {"ambiguities": [{"text": "quickly", "reason": "How quickly?", "suggestions": ["Give a time"], "severity": "High"}]}
+  entities: '{"actors": ["member", "customer"], "actions": [], "objects": ["invoice"]}'
+"#);
+    let analyzer = Analyzer::new().unwrap().with_config(config);
+    let result = analyzer.analyze(SPEC).await.unwrap();
+    assert!(!result.ai_failed);
+    assert_eq!(analyzer.llm_usage().calls, 4);
+
+    let lines: Vec<usize> = result.ambiguities.iter()
+        .filter(|f| f.rule_id == RULE_AI_AMBIGUITY)
+        .map(|f| f.location.as_ref().unwrap().line)
+        .collect();
+    assert_eq!(lines, [3, 9]);
+    assert_eq!(result.entities.actors.iter().filter(|a| *a == "customer").count(), 1);
+
+    // A failed chunk loses its own findings only
+    let config = chunked_mock_config(r#"
+tasks:
+  ambiguities: |
+    {"ambiguities": [{"text": "quickly", "reason": "How quickly?", "suggestions": [], "severity": "High"}]}
+matches:
+  - contains: "Invoices"
+    response: "Sorry, I cannot help with that."
+"#);
+    let result = Analyzer::new().unwrap().with_config(config).analyze(SPEC).await.unwrap();
+    assert!(result.ai_failed);
+    let lines: Vec<usize> = result.ambiguities.iter()
+        .filter(|f| f.rule_id == RULE_AI_AMBIGUITY)
+        .map(|f| f.location.as_ref().unwrap().line)
+        .collect();
+    assert_eq!(lines, [3]);
+}
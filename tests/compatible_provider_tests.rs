@@ -0,0 +1,77 @@
+use prism::analyzer::Analyzer;
+use prism::config::{parse_header, Config, PLACEHOLDER_API_KEY};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Records each request and answers in the format of the API its path belongs to.
+async fn gateway(requests: Arc<Mutex<Vec<String>>>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0u8; 16384];
+            let read = socket.read(&mut request).await.unwrap();
+            let request = String::from_utf8_lossy(&request[..read]).to_string();
+            let body = if request.starts_with("POST /v1/messages ") {
+                r#"{"content":[{"type":"text","text":"from messages"}]}"#
+            } else {
+                r#"{"choices":[{"message":{"content":"from chat completions"}}]}"#
+            };
+            requests.lock().unwrap().push(request);
+            let response = format!("HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}", body.len(), body);
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+    format!("http://{}/v1", address)
+}
+
+fn compatible_config(provider: &str, base_url: String) -> Config {
+    let mut config = Config::default();
+    config.llm.base_url = Some(base_url);
+    config.llm.model = "meta-llama/llama-3.1-70b-instruct".to_string();
+    config.set_provider(provider);
+    config.llm.api_key = Some("gateway-key".to_string());
+    config.llm.headers.insert("HTTP-Referer".to_string(), "https://acme.dev".to_string());
+    config
+}
+
+#[tokio::test]
+async fn test_compatible_providers_call_the_configured_endpoint() {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let base_url = gateway(requests.clone()).await;
+
+    let config = compatible_config("openai-compatible", base_url.clone());
+    // The base URL and model the user set survive choosing the provider
+    assert_eq!(config.llm.base_url.as_deref(), Some(base_url.as_str()));
+    assert_eq!(config.llm.model, "meta-llama/llama-3.1-70b-instruct");
+    let analyzer = Analyzer::new().unwrap().with_config(config);
+    assert_eq!(analyzer.call_llm("prompt").await.unwrap(), "from chat completions");
+
+    let analyzer = Analyzer::new().unwrap().with_config(compatible_config("anthropic-compatible", base_url));
+    assert_eq!(analyzer.call_llm("prompt").await.unwrap(), "from messages");
+
+    let requests = requests.lock().unwrap();
+    assert!(requests[0].starts_with("POST /v1/chat/completions "), "{}", requests[0]);
+    assert!(requests[0].to_lowercase().contains("authorization: bearer gateway-key"));
+    assert!(requests[1].to_lowercase().contains("x-api-key: gateway-key"));
+    for request in requests.iter() {
+        assert!(request.to_lowercase().contains("http-referer: https://acme.dev"), "{}", request);
+        assert!(request.contains("meta-llama/llama-3.1-70b-instruct"));
+    }
+}
+
+#[tokio::test]
+async fn test_compatible_provider_needs_base_url_and_model() {
+    let mut config = Config::default();
+    config.set_provider("openai-compatible");
+    config.llm.api_key = Some(PLACEHOLDER_API_KEY.to_string());
+    let validation = config.validate_all_settings().await.unwrap();
+    assert!(!validation.is_valid);
+    assert!(validation.issues.iter().any(|issue| issue.contains("Base URL")), "{:?}", validation.issues);
+
+    assert_eq!(parse_header("X-Tenant: acme").unwrap(), ("X-Tenant".to_string(), "acme".to_string()));
+    assert_eq!(parse_header("X-Tenant:").unwrap().1, "");
+    assert!(parse_header("no colon").is_err());
+}
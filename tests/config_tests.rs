@@ -35,4 +35,92 @@ fn test_config_path() {
     let path = Config::config_path().unwrap();
     assert!(path.to_string_lossy().contains(".prism"));
     assert!(path.to_string_lossy().contains("config.yml"));
-}
\ No newline at end of file
+}
+#[test]
+fn test_llm_overrides_from_environment_and_flags() {
+    let vars = |pairs: &'static [(&'static str, &'static str)]| {
+        move |name: &str| pairs.iter().find(|(n, _)| *n == name).map(|(_, v)| v.to_string())
+    };
+    let env = LlmOverrides::from_vars(vars(&[("PRISM_PROVIDER", "Claude"), ("PRISM_TIMEOUT", "90"), ("PRISM_MODEL", " ")])).unwrap();
+    assert_eq!(env.provider.as_deref(), Some("claude"));
+    assert_eq!(env.timeout, Some(90));
+    assert_eq!(env.model, None);
+    assert!(LlmOverrides::from_vars(vars(&[])).unwrap().is_empty());
+    assert!(LlmOverrides::from_vars(vars(&[("PRISM_TIMEOUT", "soon")])).is_err());
+    assert!(LlmOverrides::from_vars(vars(&[("PRISM_PROVIDER", "watson")])).is_err());
+
+    let mut config = Config::default();
+    config.set_provider("openai");
+    config.set_model("gpt-4o".to_string());
+    env.apply_to(&mut config);
+    assert_eq!(config.llm.provider, "claude");
+    assert_eq!(config.llm.model, "claude-3-sonnet-20240229");
+    assert_eq!(config.llm.base_url.as_deref(), Some("https://api.anthropic.com/v1/messages"));
+    assert_eq!(config.llm.timeout, 90);
+
+    let flags = LlmOverrides { model: Some("claude-3-haiku-20240307".to_string()), ..LlmOverrides::default() };
+    flags.apply_to(&mut config);
+    assert_eq!(config.llm.model, "claude-3-haiku-20240307");
+    assert_eq!(config.llm.provider, "claude");
+}
+
+#[test]
+fn test_api_key_kept_in_keyring() {
+    let mut config = Config::default();
+    assert!(!config.llm.keyring);
+    config.set_api_key("sk-test".to_string());
+    assert!(config.llm.keyring);
+
+    // Configs written before the keyring keep their key in the file
+    let legacy: Config = serde_yaml::from_str(&serde_yaml::to_string(&Config::default()).unwrap().replace("  keyring: false\n", "")).unwrap();
+    assert!(!legacy.llm.keyring);
+}
+
+#[test]
+fn test_generation_settings_per_provider() {
+    let mut config = Config::default();
+    config.set_provider("ollama");
+    let defaults = config.llm.generation();
+    assert_eq!((defaults.temperature, defaults.max_tokens), (0.1, 2000));
+    assert_eq!(defaults.system_prompt, DEFAULT_SYSTEM_PROMPT);
+
+    config.llm.system_prompt = Some("You are Acme's requirements analyst. Say 'member', never 'customer'.".to_string());
+    config.llm.providers.insert("ollama".to_string(), ProviderSettings { max_tokens: Some(4096), ..ProviderSettings::default() });
+    config.llm.providers.insert("openai".to_string(), ProviderSettings { temperature: Some(0.7), ..ProviderSettings::default() });
+    let ollama = config.llm.generation();
+    assert_eq!((ollama.temperature, ollama.max_tokens), (0.1, 4096));
+    assert!(ollama.system_prompt.starts_with("You are Acme's"));
+
+    let flags = LlmOverrides { max_tokens: Some(800), temperature: Some(0.0), ..LlmOverrides::default() };
+    flags.validate().unwrap();
+    flags.apply_to(&mut config);
+    let overridden = config.llm.generation();
+    assert_eq!((overridden.temperature, overridden.max_tokens), (0.0, 800));
+
+    assert!(LlmOverrides { temperature: Some(3.5), ..LlmOverrides::default() }.validate().is_err());
+    assert!(LlmOverrides { max_tokens: Some(0), ..LlmOverrides::default() }.validate().is_err());
+}
+
+#[test]
+fn test_configs_of_the_providers_to_compare() {
+    let mut config = Config::default();
+    config.set_provider("openai");
+    config.set_api_key("sk-main".to_string());
+    config.llm.providers.insert("claude".to_string(), ProviderSettings {
+        api_key: Some("sk-ant".to_string()),
+        ..ProviderSettings::default()
+    });
+    config.llm.providers.insert("ollama".to_string(), ProviderSettings { model: Some("llama3.1:8b".to_string()), ..ProviderSettings::default() });
+    config.llm.providers.insert("gemini".to_string(), ProviderSettings { temperature: Some(0.3), ..ProviderSettings::default() });
+    assert_eq!(config.comparable_providers(), ["openai", "claude", "ollama"]);
+
+    let claude = config.for_provider("claude").unwrap();
+    assert_eq!((claude.llm.provider.as_str(), claude.llm.api_key.as_deref()), ("claude", Some("sk-ant")));
+    assert!(claude.llm.model.starts_with("claude"), "{}", claude.llm.model);
+    let ollama = config.for_provider("ollama").unwrap();
+    assert_eq!(ollama.llm.model, "llama3.1:8b");
+    assert!(ollama.is_ai_configured());
+    assert_eq!(config.for_provider("openai").unwrap().llm.api_key.as_deref(), Some("sk-main"));
+    // Another provider's key is never sent to Gemini
+    assert!(config.for_provider("gemini").is_err());
+}
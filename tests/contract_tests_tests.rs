@@ -0,0 +1,50 @@
+use prism::contract_tests::{format_contract_pact, format_contract_tests_markdown, generate_contract_tests};
+
+const ORDERS_API: &str = "# Orders API\n\
+    REQ-1: The API shall allow authenticated clients to create an order.\n\
+    REQ-2: GET /orders/{id} must return the order within 200 ms.\n\
+    REQ-3: The endpoint shall list all orders of a customer.\n\
+    REQ-4: DELETE /orders/{id} returns 202 when the order is cancelled.\n\
+    REQ-5: The REST API must update the shipping address of an order.\n\
+    The user can see the dashboard.\n";
+
+#[test]
+fn test_endpoints_from_api_requirements() {
+    let tests = generate_contract_tests(ORDERS_API);
+    let endpoints: Vec<(&str, &str, bool)> = tests.iter().map(|t| (t.method.as_str(), t.path.as_str(), t.explicit)).collect();
+    assert_eq!(endpoints, vec![
+        ("POST", "/orders", false),
+        ("GET", "/orders/{id}", true),
+        ("GET", "/orders", false),
+        ("DELETE", "/orders/{id}", true),
+        ("PUT", "/orders/{id}", false),
+    ]);
+
+    let statuses = |i: usize| tests[i].cases.iter().map(|c| c.status).collect::<Vec<_>>();
+    assert_eq!(statuses(0), vec![201, 400, 401]);
+    assert_eq!(statuses(1), vec![200, 404]);
+    assert_eq!(statuses(3), vec![202, 404]);
+    assert_eq!(tests[1].cases[0].provider_state.as_deref(), Some("an order with id 1 exists"));
+    assert_eq!(tests[1].line, 3);
+
+    assert!(generate_contract_tests("The user can create an order.\nThe page should load fast.").is_empty());
+}
+
+#[test]
+fn test_pact_and_markdown_stubs() {
+    let tests = generate_contract_tests(ORDERS_API);
+    let pact = format_contract_pact(&tests, "web", "orders-api");
+    assert_eq!(pact["provider"]["name"], "orders-api");
+    assert_eq!(pact["metadata"]["pactSpecification"]["version"], "2.0.0");
+    let interactions = pact["interactions"].as_array().unwrap();
+    assert_eq!(interactions.len(), tests.iter().map(|t| t.cases.len()).sum::<usize>());
+    let missing = interactions.iter().find(|i| i["description"] == "a request to get an order that does not exist").unwrap();
+    assert_eq!(missing["providerState"], "no order with id 1 exists");
+    assert_eq!(missing["request"]["path"], "/orders/1");
+    assert_eq!(missing["response"]["status"], 404);
+
+    let markdown = format_contract_tests_markdown(&tests);
+    assert!(markdown.contains("### `POST /orders` *(inferred)*\n\n> Line 2: REQ-1: The API shall allow authenticated clients to create an order.\n"));
+    assert!(markdown.contains("- Given an order with id 1 exists, upon receiving a request to delete an order, the API responds with **202**\n"));
+    assert!(markdown.contains("schemathesis run openapi.yaml --include-method GET --include-path '/orders/{id}'"));
+}
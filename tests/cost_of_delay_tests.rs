@@ -0,0 +1,54 @@
+use prism::cost_of_delay::{
+    format_cost_of_delay_markdown, front_matter_annotation, parse_sidecar, split_front_matter, CostOfDelayReport,
+    PrioritizedDocument, Quadrant,
+};
+use prism::hierarchy::QualityScore;
+use std::path::Path;
+
+#[test]
+fn test_annotations_from_front_matter_and_sidecar() {
+    let document = "---\ntitle: Payments\nvalue: 5\ntime_criticality: 8\nrisk_reduction: 2\n---\n# Payments\nThe page should be fast.\n";
+    let (front_matter, body) = split_front_matter(document);
+    assert!(front_matter.unwrap().contains("title: Payments"));
+    assert_eq!(body, "# Payments\nThe page should be fast.\n");
+    assert_eq!(front_matter_annotation(document).unwrap().total(), Some(15.0));
+    assert!(front_matter_annotation("---\ntitle: No figures\n---\nText").is_none());
+    assert!(front_matter_annotation("No front matter").is_none());
+
+    let sidecar = parse_sidecar("File,Cost_of_delay,value\n\"checkout\\payments.md\",8000,\nlogin.md,,3\n").unwrap();
+    assert_eq!(sidecar["checkout/payments.md"].total(), Some(8000.0));
+    assert_eq!(sidecar["login.md"].total(), Some(3.0));
+    assert!(parse_sidecar("name,cost_of_delay\nx,1\n").is_err());
+    assert!(parse_sidecar("file,cost_of_delay\nx,lots\n").is_err());
+}
+
+fn document(file: &str, cost_of_delay: Option<f32>, score: f32, findings: usize) -> PrioritizedDocument {
+    PrioritizedDocument {
+        file: file.to_string(),
+        cost_of_delay,
+        quality: QualityScore { requirement_count: 4, finding_count: findings, penalty: 0.0, score },
+    }
+}
+
+#[test]
+fn test_low_quality_requirements_with_high_cost_of_delay_come_first() {
+    let report = CostOfDelayReport::new(vec![
+        document("login.md", Some(300.0), 95.0, 1),
+        document("misc.md", None, 20.0, 9),
+        document("payments.md", Some(8000.0), 40.0, 6),
+        document("reports.md", Some(50.0), 30.0, 7),
+    ]);
+    let order: Vec<&str> = report.documents.iter().map(|d| d.file.as_str()).collect();
+    assert_eq!(order, vec!["payments.md", "reports.md", "login.md", "misc.md"]);
+    assert_eq!(report.high_cost_of_delay, 300.0);
+    assert_eq!(report.quadrant(&report.documents[0]), Some(Quadrant::FixFirst));
+    assert_eq!(report.quadrant(&report.documents[1]), Some(Quadrant::FixLater));
+    assert_eq!(report.quadrant(&report.documents[2]), Some(Quadrant::ReadyToDeliver));
+    assert_eq!(report.quadrant(&report.documents[3]), None);
+
+    let markdown = format_cost_of_delay_markdown(Path::new("requirements"), &report);
+    assert!(markdown.contains("**Annotated:** 3 of 4 documents | **Fix first:** 1 (cost of delay ≥ 300, quality < 70)"));
+    assert!(markdown.contains("    payments.md: [0.40, 1.00]\n"));
+    assert!(markdown.contains("| 1 | `payments.md` | 8000 | 40 | 6 | 4800 | 🔴 Fix first |"));
+    assert!(markdown.contains("- `misc.md` - quality 20\n"));
+}
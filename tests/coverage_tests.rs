@@ -0,0 +1,50 @@
+use prism::coverage::*;
+
+fn stories() -> Vec<(String, String)> {
+    vec![
+        ("login.md".to_string(), "As a customer, I want to log in with my email and password so that I can see my orders.".to_string()),
+        ("export.md".to_string(), "As an analyst, I want to export monthly reports to CSV.".to_string()),
+        ("theme.md".to_string(), "As a visitor, I want a dark colour theme.".to_string()),
+    ]
+}
+
+#[test]
+fn test_extract_capabilities_from_epic() {
+    let epic = "# Reporting Epic\n\nThe portal provides self-service reporting.\n\n- Customers log in with email and password\n- Analysts export reports to CSV\n* Admins manage user roles\n";
+    let capabilities = extract_capabilities(epic);
+
+    assert_eq!(capabilities, vec![
+        "The portal provides self-service reporting",
+        "Customers log in with email and password",
+        "Analysts export reports to CSV",
+        "Admins manage user roles",
+    ]);
+}
+
+#[test]
+fn test_coverage_reports_uncovered_statements_and_orphan_stories() {
+    let capabilities = vec![
+        "Customers log in with email and password".to_string(),
+        "Analysts export reports to CSV".to_string(),
+        "Admins manage user roles".to_string(),
+    ];
+    let report = check_coverage(&capabilities, &stories(), DEFAULT_COVERAGE_THRESHOLD);
+
+    assert_eq!(report.covered.len(), 2);
+    assert_eq!(report.covered[0].matches[0].story, "login.md");
+    assert_eq!(report.covered[1].matches[0].story, "export.md");
+    assert_eq!(report.uncovered, vec!["Admins manage user roles"]);
+    assert_eq!(report.orphan_stories, vec!["theme.md"]);
+    assert!((report.coverage_percent() - 66.67).abs() < 0.1);
+
+    let markdown = format_coverage_markdown(&report, "epic.md");
+    assert!(markdown.contains("## ❌ Uncovered Epic Statements"));
+    assert!(markdown.contains("- Admins manage user roles"));
+    assert!(markdown.contains("- theme.md"));
+}
+
+#[test]
+fn test_similarity_ignores_word_forms_and_stop_words() {
+    assert!(similarity("Export the reports", "exporting a report") > 0.99);
+    assert_eq!(similarity("the and of", "export reports"), 0.0);
+}
@@ -0,0 +1,60 @@
+use prism::analyzer::{AmbiguitySeverity, Analyzer};
+use prism::config::Config;
+use prism::custom_rules::{merge_rules, parse_rules_file};
+
+const RULES: &str = r#"
+rules:
+  - id: tbd
+    pattern: '\b(TBD|to be decided)\b'
+    case_sensitive: true
+    severity: High
+    reason: Open decision left in the requirement
+    suggestions: [Decide before the requirement is approved]
+  - id: weasel-words
+    keywords: [simply, "of course"]
+"#;
+
+#[test]
+fn test_rules_files_are_parsed_and_merged() {
+    let global = parse_rules_file(RULES).unwrap();
+    assert_eq!(global.len(), 2);
+    assert_eq!(global[0].severity, AmbiguitySeverity::High);
+    assert_eq!(global[1].severity, AmbiguitySeverity::Medium);
+    assert!(parse_rules_file("").unwrap().is_empty());
+    assert!(parse_rules_file("rules:\n  - pattern: x\n").is_err());
+
+    let project = parse_rules_file("rules:\n  - id: weasel-words\n    keywords: [just]\n    severity: Low\n").unwrap();
+    let merged = merge_rules(vec![global, project]);
+    let ids: Vec<&str> = merged.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(ids, vec!["tbd", "weasel-words"]);
+    assert_eq!(merged[1].keywords, vec!["just"]);
+    assert_eq!(merged[1].severity, AmbiguitySeverity::Low);
+}
+
+#[test]
+fn test_custom_rules_are_reported_with_the_built_in_findings() {
+    let rules = parse_rules_file(RULES).unwrap();
+    let mut config = Config::default();
+    config.llm.api_key = None;
+    config.analysis.disabled_rules = vec!["vague-term".to_string()];
+    config.analysis.severity_overrides.insert("weasel-words".to_string(), AmbiguitySeverity::Critical);
+    let analyzer = Analyzer::new().unwrap().with_config(config).with_custom_rules(rules).unwrap();
+
+    let findings = analyzer.detect_rule_findings("Users simply export reports. The format is TBD, tbd and Of course fast.");
+    let found: Vec<(&str, &str, AmbiguitySeverity)> = findings.iter()
+        .map(|a| (a.rule_id.as_str(), a.text.as_str(), a.severity))
+        .collect();
+    assert_eq!(found, vec![
+        ("tbd", "TBD", AmbiguitySeverity::High),
+        ("weasel-words", "simply", AmbiguitySeverity::Critical),
+        ("weasel-words", "Of course", AmbiguitySeverity::Critical),
+    ]);
+    assert_eq!(findings[0].reason, "Open decision left in the requirement");
+    assert_eq!(findings[0].location.as_ref().unwrap().line, 1);
+
+    let invalid = parse_rules_file("rules:\n  - id: broken\n    pattern: '(unclosed'\n").unwrap();
+    let error = Analyzer::new().unwrap().with_custom_rules(invalid).err().unwrap();
+    assert!(format!("{:#}", error).contains("custom rule 'broken' has an invalid pattern"));
+    let empty = parse_rules_file("rules:\n  - id: empty\n").unwrap();
+    assert!(Analyzer::new().unwrap().with_custom_rules(empty).is_err());
+}
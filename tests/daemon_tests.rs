@@ -0,0 +1,91 @@
+use chrono::{NaiveDate, NaiveDateTime, TimeZone, Utc};
+use prism::config::RegressionThresholds;
+use prism::daemon::{detect_regression, format_regression_notification, AnalysisHistory, CronSchedule, HistoryEntry};
+use prism::hierarchy::QualityScore;
+
+fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+}
+
+fn entry(job: &str, score: f32, finding_count: usize, critical_count: usize) -> HistoryEntry {
+    HistoryEntry {
+        job: job.to_string(),
+        timestamp: Utc.with_ymd_and_hms(2026, 10, 16, 6, 0, 0).unwrap(),
+        files: 3,
+        requirement_count: 12,
+        finding_count,
+        critical_count,
+        score,
+        word_count: 400,
+        density: finding_count as f32 / 4.0,
+    }
+}
+
+#[test]
+fn test_cron_schedule_next_run() {
+    // Friday 2026-10-16 06:30
+    let now = at(2026, 10, 16, 6, 30);
+
+    let weekdays = CronSchedule::parse("0 6 * * 1-5").unwrap();
+    assert_eq!(weekdays.next_after(now), Some(at(2026, 10, 19, 6, 0)));
+
+    let quarter_hours = CronSchedule::parse("*/15 9-17 * * *").unwrap();
+    assert_eq!(quarter_hours.next_after(now), Some(at(2026, 10, 16, 9, 0)));
+    assert_eq!(quarter_hours.next_after(at(2026, 10, 16, 9, 0)), Some(at(2026, 10, 16, 9, 15)));
+
+    // Day of month and day of week both restricted: either matches
+    let first_or_sunday = CronSchedule::parse("0 0 1 * 7").unwrap();
+    assert_eq!(first_or_sunday.next_after(now), Some(at(2026, 10, 18, 0, 0)));
+    assert_eq!(CronSchedule::parse("@monthly").unwrap().next_after(now), Some(at(2026, 11, 1, 0, 0)));
+
+    assert_eq!(CronSchedule::parse("0 0 30 2 *").unwrap().next_after(now), None);
+    assert!(CronSchedule::parse("0 6 * *").is_err());
+    assert!(CronSchedule::parse("0 25 * * *").is_err());
+    assert!(CronSchedule::parse("*/0 * * * *").is_err());
+}
+
+#[test]
+fn test_regression_detected_beyond_thresholds() {
+    let thresholds = RegressionThresholds { max_score_drop: 5.0, max_new_findings: 2, min_score: Some(75.0) };
+    let previous = entry("checkout", 82.0, 10, 0);
+
+    assert!(detect_regression(&previous, &entry("checkout", 79.0, 12, 0), &thresholds).is_empty());
+
+    let reasons = detect_regression(&previous, &entry("checkout", 70.0, 14, 1), &thresholds);
+    assert_eq!(reasons.len(), 4);
+    assert_eq!(reasons[0], "quality score dropped 12 points (82 → 70)");
+    assert_eq!(reasons[1], "4 new finding(s) (10 → 14)");
+
+    let message = format_regression_notification(&previous, &entry("checkout", 70.0, 14, 1), &reasons);
+    assert!(message.starts_with("⚠️ PRISM: requirements quality of 'checkout' regressed since 2026-10-16 06:00 UTC"));
+    assert!(message.ends_with("Now: score 70/100, 14 finding(s) in 12 requirement(s) across 3 file(s), 3.5 per 100 words (was 2.5)"));
+
+    let score = QualityScore { requirement_count: 12, finding_count: 6, penalty: 9.0, score: 91.0 };
+    let run = HistoryEntry::new("checkout", previous.timestamp, 3, &score, 0, 400);
+    assert_eq!((run.word_count, run.density), (400, 1.5));
+    assert_eq!(HistoryEntry::new("checkout", previous.timestamp, 3, &score, 0, 0).density, 0.0);
+}
+
+#[test]
+fn test_history_returns_last_run_of_job() {
+    let path = std::env::temp_dir().join(format!("prism-history-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let history = AnalysisHistory::new(&path);
+    assert_eq!(history.last_run("checkout").unwrap(), None);
+
+    history.append(&entry("checkout", 80.0, 5, 0)).unwrap();
+    history.append(&entry("billing", 60.0, 9, 1)).unwrap();
+    history.append(&entry("checkout", 85.0, 4, 0)).unwrap();
+
+    assert_eq!(history.entries().unwrap().len(), 3);
+    assert_eq!(history.last_run("checkout").unwrap().map(|e| e.score), Some(85.0));
+    assert_eq!(history.last_run("billing").unwrap().map(|e| e.finding_count), Some(9));
+
+    // Runs recorded before the density was tracked still load
+    let mut old = serde_json::to_value(entry("legacy", 70.0, 6, 0)).unwrap();
+    old.as_object_mut().unwrap().retain(|key, _| key != "word_count" && key != "density");
+    std::fs::write(&path, format!("{}\n", old)).unwrap();
+    let legacy = history.last_run("legacy").unwrap().unwrap();
+    assert_eq!((legacy.finding_count, legacy.word_count, legacy.density), (6, 0, 0.0));
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,53 @@
+use prism::analyzer::Analyzer;
+use prism::dashboard::{bar_chart, completeness_gauge, format_dashboard_html, DashboardOptions, DashboardStatus};
+
+#[tokio::test]
+async fn test_dashboard_renders_every_section() {
+    let analyzer = Analyzer::new().unwrap();
+    let text = "As a user, I want a fast login. The admin should manage accounts <quickly>.";
+    let mut result = analyzer.analyze(text).await.unwrap();
+    result.completeness_analysis = Some(analyzer.analyze_completeness(text, &result.entities).await.unwrap());
+    result.nfr_suggestions = Some(analyzer.generate_nfr_suggestions(text, &result.entities).await.unwrap());
+
+    let options = DashboardOptions {
+        title: "login".to_string(),
+        branding: Some("Acme <Corp>".to_string()),
+        executive_summary: true,
+        generated_at: "2024-01-01 10:00".to_string(),
+    };
+    let html = format_dashboard_html(&result, text, &options);
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<span class=\"brand\">Acme &lt;Corp&gt;</span>"));
+    assert!(html.contains("📈 Executive Summary"));
+    assert!(html.contains("Findings by severity"));
+    assert!(html.contains("<tr data-severity="));
+    assert!(html.contains("<svg class=\"gauge\""));
+    assert!(html.contains("🎯 Entities"));
+    assert!(html.contains(&format!("🔒 NFR Breakdown ({})", result.nfr_suggestions.as_ref().unwrap().len())));
+    assert!(!html.contains("<quickly>"));
+
+    let without_summary = format_dashboard_html(&result, text, &DashboardOptions { executive_summary: false, ..options });
+    assert!(!without_summary.contains("📈 Executive Summary"));
+    assert!(!without_summary.contains("mermaid"));
+}
+
+#[test]
+fn test_dashboard_charts_and_status() {
+    let chart = bar_chart(&[("High".to_string(), 2, Some("high")), ("Low".to_string(), 1, Some("low"))]);
+    assert!(chart.contains("data-filter=\"high\""));
+    assert!(chart.contains("class=\"fill sev-high\" style=\"width: 100%\""));
+    assert!(chart.contains("class=\"fill sev-low\" style=\"width: 50%\""));
+
+    assert!(completeness_gauge(150.0).contains(">100%</text>"));
+    assert!(completeness_gauge(40.0).contains("stroke=\"#c62828\""));
+
+    let result: prism::analyzer::AnalysisResult = serde_json::from_value(serde_json::json!({
+        "ambiguities": [],
+        "entities": { "actors": [], "actions": [], "objects": [] },
+        "uml_diagrams": null, "pseudocode": null, "test_cases": null, "improved_requirements": null,
+        "completeness_analysis": null, "user_story_validation": null, "nfr_suggestions": null
+    }))
+    .unwrap();
+    assert_eq!(DashboardStatus::of(&result), DashboardStatus::Ready);
+}
@@ -0,0 +1,26 @@
+use prism::analyzer::Analyzer;
+use prism::demo::{demo_report_name, format_demo_readme, DemoOutput, DEMO_FORMATS, DEMO_SAMPLES};
+
+#[tokio::test]
+async fn test_demo_samples_produce_findings_without_ai() {
+    let analyzer = Analyzer::new().unwrap();
+    for sample in DEMO_SAMPLES {
+        let result = analyzer.analyze(sample.text).await.unwrap();
+        assert!(!result.ambiguities.is_empty(), "{} should show some findings", sample.name);
+        assert!(!result.entities.actors.is_empty(), "{} should have actors", sample.name);
+    }
+}
+
+#[test]
+fn test_demo_readme_links_every_format() {
+    let outputs = vec![DemoOutput { sample: DEMO_SAMPLES[0], findings: 3, artifact_index: "checkout_story_Index.md".to_string() }];
+    let readme = format_demo_readme(&outputs);
+
+    assert!(readme.contains("## checkout_story\n"));
+    assert!(readme.contains("**3 finding(s)**"));
+    assert!(readme.contains("- `--format jira-adf`: [checkout_story/report.jira-adf.json](checkout_story/report.jira-adf.json)"));
+    assert!(readme.contains("[checkout_story/checkout_story_Index.md](checkout_story/checkout_story_Index.md)"));
+    for format in DEMO_FORMATS {
+        assert!(readme.contains(&format!("checkout_story/{})", demo_report_name(&format))));
+    }
+}
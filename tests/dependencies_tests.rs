@@ -0,0 +1,46 @@
+use prism::analyzer::ExtractedEntities;
+use prism::dependencies::{build_dependency_graph, format_dependency_dot, format_dependency_markdown, DependencyKind};
+
+const CHECKOUT: &str = "\
+# Checkout
+
+- REQ-1: The customer can add a product to the cart.
+- REQ-2: The customer can pay for the cart. Depends on REQ-1 and REQ-4.
+- REQ-3: The system shall email a receipt after REQ-2 completes, see REQ-9.
+- REQ-4: Payment provider setup requires REQ-3.
+- The admin can refund a receipt.
+";
+
+#[test]
+fn test_dependencies_are_inferred_and_cycles_reported() {
+    let entities = ExtractedEntities {
+        actors: vec!["customer".to_string(), "admin".to_string()],
+        actions: vec!["pay".to_string()],
+        objects: vec!["cart".to_string(), "receipt".to_string()],
+    };
+    let graph = build_dependency_graph(CHECKOUT, &entities);
+
+    let ids: Vec<&str> = graph.requirements.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(ids, vec!["REQ-1", "REQ-2", "REQ-3", "REQ-4", "line 7", "REQ-9"]);
+    assert!(graph.is_external("REQ-9"));
+
+    let edges: Vec<(&str, &str, DependencyKind)> = graph.edges.iter().map(|e| (e.from.as_str(), e.to.as_str(), e.kind)).collect();
+    assert!(edges.contains(&("REQ-2", "REQ-4", DependencyKind::DependsOn)));
+    assert!(edges.contains(&("REQ-3", "REQ-2", DependencyKind::DependsOn)));
+    // The clause after the comma has no dependency phrase
+    assert!(edges.contains(&("REQ-3", "REQ-9", DependencyKind::References)));
+    let shared = graph.edges.iter().find(|e| e.from == "REQ-1" && e.to == "REQ-2").filter(|e| e.kind == DependencyKind::SharesTerms);
+    assert_eq!(shared.unwrap().terms, vec!["cart"]);
+    assert!(edges.contains(&("REQ-3", "line 7", DependencyKind::SharesTerms)));
+
+    assert_eq!(graph.cycles, vec![vec!["REQ-2", "REQ-4", "REQ-3", "REQ-2"]]);
+
+    let dot = format_dependency_dot(&graph);
+    assert!(dot.contains("\"REQ-4\" -> \"REQ-3\" [label=\"depends on\", color=red];"));
+    assert!(dot.contains("\"REQ-2\" -> \"REQ-1\" [label=\"depends on\"];"));
+    assert!(dot.contains("\"REQ-9\" [label=\"REQ-9 (not in document)\", style=dashed];"));
+
+    let markdown = format_dependency_markdown(&graph);
+    assert!(markdown.contains("**Dependency cycle:** REQ-2 → REQ-4 → REQ-3 → REQ-2"));
+    assert!(markdown.contains("```mermaid\nflowchart LR\n"));
+}
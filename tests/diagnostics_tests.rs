@@ -0,0 +1,31 @@
+use prism::diagnostics::format_diagnostics;
+use prism::{format_report, Analyzer, OutputFormat};
+
+const SPEC: &str = "# Login\nThe system should be fast.\n";
+
+#[tokio::test]
+async fn test_diagnostics_show_the_rule_position_span_and_help() {
+    let result = Analyzer::new().unwrap().analyze(SPEC).await.unwrap();
+    let report = format_report(&result, SPEC, &OutputFormat::Diagnostics, Some("login.md")).unwrap();
+    let lines: Vec<&str> = report.lines().collect();
+
+    assert!(lines[0].starts_with("medium[vague-term]: "), "{}", report);
+    assert_eq!(lines[1], " --> login.md:2:22");
+    assert_eq!(lines[2], "  |");
+    assert_eq!(lines[3], "2 | The system should be fast.");
+    assert!(lines[4].starts_with("  |                      ^^^^"), "{}", report);
+    assert!(lines.iter().any(|l| l.starts_with("  = help: ")), "{}", report);
+    assert!(report.ends_with("1 finding: 1 medium\n"), "{}", report);
+    assert!(!report.contains('\x1b'));
+}
+
+#[tokio::test]
+async fn test_diagnostics_color_severities_for_a_terminal() {
+    let result = Analyzer::new().unwrap().analyze(SPEC).await.unwrap();
+    let report = format_diagnostics(&result, SPEC, None, true);
+    assert!(report.starts_with("\x1b[1;33mmedium[vague-term]\x1b[0m"), "{:?}", report);
+    assert!(report.contains("\x1b[1;34m-->\x1b[0m input:2:22\n"), "{:?}", report);
+
+    let clean = Analyzer::new().unwrap().analyze("Users log in.").await.unwrap();
+    assert_eq!(format_diagnostics(&clean, "Users log in.", None, false), "No ambiguities detected\n");
+}
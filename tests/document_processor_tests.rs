@@ -0,0 +1,116 @@
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use prism::document_processor::{DocumentError, DocumentProcessor};
+use prism::pdf_report::markdown_to_pdf;
+use std::io::Write;
+
+/// A zip local file header and deflated data, without the central directory
+/// a complete zip ends with.
+fn zip_entry_without_directory(name: &str, content: &str) -> Vec<u8> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(content.as_bytes()).unwrap();
+    let data = encoder.finish().unwrap();
+
+    let mut bytes = b"PK\x03\x04".to_vec();
+    bytes.extend_from_slice(&[20, 0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&(content.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&(name.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(&0u16.to_le_bytes());
+    bytes.extend_from_slice(name.as_bytes());
+    bytes.extend_from_slice(&data);
+    bytes
+}
+
+#[tokio::test]
+async fn test_damaged_docx_text_is_recovered() {
+    let xml = r#"<w:document><w:body><w:p><w:r><w:t>The user shall export reports.</w:t></w:r></w:p><w:p><w:r><w:t xml:space="preserve">Totals &amp; </w:t></w:r><w:r><w:t>taxes are shown.</w:t></w:r></w:p></w:body></w:document>"#;
+    let path = std::env::temp_dir().join("prism_damaged.docx");
+    std::fs::write(&path, zip_entry_without_directory("word/document.xml", xml)).unwrap();
+
+    let text = DocumentProcessor::new().extract_text_from_file(&path).await.unwrap();
+    assert_eq!(text, "The user shall export reports.\nTotals & taxes are shown.");
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_damaged_docx_recovery_stops_at_the_size_limit() {
+    // Deflates to a few kilobytes but would inflate to 20 MB
+    let paragraph = "<w:p><w:r><w:t>The user shall export reports.</w:t></w:r></w:p>";
+    let xml = paragraph.repeat(20 * 1024 * 1024 / paragraph.len());
+    let path = std::env::temp_dir().join("prism_damaged_bomb.docx");
+    std::fs::write(&path, zip_entry_without_directory("word/document.xml", &xml)).unwrap();
+
+    let text = DocumentProcessor::new().extract_text_from_file(&path).await.unwrap();
+    assert!(text.starts_with("The user shall export reports.\n"));
+    assert!(text.len() < 16 * 1024 * 1024);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_password_protected_office_file_is_reported() {
+    let mut bytes = vec![0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+    bytes.extend("EncryptionInfo".encode_utf16().flat_map(|c| c.to_le_bytes()));
+    let path = std::env::temp_dir().join("prism_protected.xlsx");
+    std::fs::write(&path, bytes).unwrap();
+
+    let error = DocumentProcessor::new().extract_text_from_file(&path).await.unwrap_err();
+    assert_eq!(error.downcast_ref::<DocumentError>(), Some(&DocumentError::EncryptedOfficeDocument(path.clone())));
+    assert!(error.to_string().contains("password-protected"));
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_pdf_mentioning_encrypt_is_read_as_plain() {
+    let pdf = markdown_to_pdf("# Export\n\nThe trailer key /Encrypt shall be documented.\n", "Export");
+    let path = std::env::temp_dir().join("prism_mentions_encrypt.pdf");
+    std::fs::write(&path, pdf).unwrap();
+
+    let text = DocumentProcessor::new().extract_text_from_file(&path).await.unwrap();
+    assert!(text.contains("The trailer key /Encrypt shall be documented."), "{}", text);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_zip_archive_unpacks_supported_documents_only() {
+    let archive_path = std::env::temp_dir().join("prism_requirements.zip");
+    let mut writer = zip::ZipWriter::new(std::fs::File::create(&archive_path).unwrap());
+    let options = zip::write::FileOptions::default();
+    for (name, content) in [
+        ("specs/login.md", "As a user, I want to log in"),
+        ("specs/page.html", "<p>Exported page</p>"),
+        ("__MACOSX/specs/._login.md", "resource fork"),
+        ("../outside.md", "Escapes the directory"),
+    ] {
+        writer.start_file(name, options).unwrap();
+        writer.write_all(content.as_bytes()).unwrap();
+    }
+    writer.finish().unwrap();
+
+    let processor = DocumentProcessor::new();
+    assert!(processor.is_archive(&archive_path));
+    let archive = processor.extract_archive(&archive_path).unwrap();
+    let dir = archive.dir.clone();
+
+    assert_eq!(archive.files, 1);
+    assert_eq!(std::fs::read_to_string(dir.join("specs/login.md")).unwrap(), "As a user, I want to log in");
+    assert_eq!(archive.skipped, vec!["specs/page.html", "__MACOSX/specs/._login.md", "../outside.md"]);
+    assert!(!dir.join("../outside.md").exists());
+
+    // Every extraction gets its own private directory, which goes away with the archive
+    let again = processor.extract_archive(&archive_path).unwrap();
+    assert_ne!(again.dir, dir);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(std::fs::metadata(&dir).unwrap().permissions().mode() & 0o777, 0o700);
+    }
+    drop(archive);
+    assert!(!dir.exists());
+    assert!(again.dir.exists());
+    let _ = std::fs::remove_file(&archive_path);
+}
@@ -0,0 +1,44 @@
+use prism::analyzer::ExtractedEntities;
+use prism::domain_model::{build_domain_model, format_domain_model_markdown, format_domain_model_plantuml, past_tense};
+
+#[test]
+fn test_past_tense_of_actions() {
+    assert_eq!(past_tense("place"), "placed");
+    assert_eq!(past_tense("approve"), "approved");
+    assert_eq!(past_tense("pay"), "paid");
+    assert_eq!(past_tense("verify"), "verified");
+    assert_eq!(past_tense("log in"), "logged in");
+    assert_eq!(past_tense("export"), "exported");
+}
+
+#[test]
+fn test_objects_are_grouped_into_aggregates() {
+    let text = "As a customer, I want to place an order with a shipping address.\n\
+                The manager can approve the order.\n\
+                The customer can pay the invoice. The invoice shows the total amount.\n\
+                Each order has order lines. Customers can log in.";
+    let entities = ExtractedEntities {
+        actors: vec!["customer".to_string(), "manager".to_string()],
+        actions: vec!["place".to_string(), "approve".to_string(), "pay".to_string(), "log in".to_string()],
+        objects: vec!["order".to_string(), "invoice".to_string(), "shipping address".to_string(), "total amount".to_string(), "order lines".to_string()],
+    };
+    let model = build_domain_model(text, &entities);
+
+    let roots: Vec<&str> = model.aggregates.iter().map(|a| a.root.as_str()).collect();
+    assert_eq!(roots, vec!["order", "invoice"]);
+    assert_eq!(model.aggregates[0].value_objects, vec!["shipping address"]);
+    assert_eq!(model.aggregates[0].entities, vec!["order lines"]);
+    assert_eq!(model.aggregates[1].value_objects, vec!["total amount"]);
+    assert_eq!(model.unassigned_events, vec!["LoggedIn"]);
+
+    let markdown = format_domain_model_markdown(&model);
+    assert!(markdown.contains("### 🏛️ Invoice Aggregate\n\n- **Aggregate root:** `Invoice` (identity `InvoiceId`)\n- **Value objects:** `TotalAmount`\n"));
+    assert!(markdown.contains("| `PayInvoice` | `InvoicePaid` |"));
+    assert!(markdown.contains("**Events without an aggregate:** `LoggedIn`"));
+
+    let uml = format_domain_model_plantuml(&model);
+    assert!(uml.starts_with("@startuml\n"));
+    assert!(uml.contains("  class Order <<Aggregate Root>> {\n    id: OrderId\n    shippingAddress: ShippingAddress\n    place()\n"));
+    assert!(uml.contains("  Order *-- \"0..*\" OrderLines\n"));
+    assert!(uml.contains("  Order ..> OrderApproved : publishes\n"));
+}
@@ -0,0 +1,28 @@
+use prism::duplicates::{extract_statements, find_duplicate_clusters, format_duplicates_markdown, DEFAULT_DUPLICATE_THRESHOLD};
+use std::path::Path;
+
+#[test]
+fn test_near_duplicates_are_clustered_across_files() {
+    let mut statements = extract_statements("checkout.md", "# Checkout\n\n- The customer must be able to pay with a saved credit card.\n- Orders ship within 2 days.\n");
+    statements.extend(extract_statements("payments/cards.md", "Intro.\n\nCustomers must be able to pay using their saved credit cards.\nThe admin can export monthly sales reports.\n"));
+    statements.extend(extract_statements("reports.txt", "The admin can export the monthly sales report.\nThe admin can export the monthly sales report.\n"));
+    statements.extend(extract_statements("legacy.md", "Users must pay.\nCustomers should pay using saved credit cards."));
+    assert_eq!((statements[0].file.as_str(), statements[0].line), ("checkout.md", 3));
+
+    let clusters = find_duplicate_clusters(&statements, DEFAULT_DUPLICATE_THRESHOLD);
+    assert_eq!(clusters.len(), 2);
+
+    // Exact repeats, including the repeat within reports.txt
+    let reports = &clusters[0];
+    assert!((reports.similarity - 1.0).abs() < 1e-4);
+    assert_eq!(reports.files().into_iter().collect::<Vec<_>>(), vec!["payments/cards.md", "reports.txt"]);
+    assert_eq!(reports.statements.len(), 3);
+
+    let payments: Vec<(&str, usize)> = clusters[1].statements.iter().map(|s| (s.file.as_str(), s.line)).collect();
+    assert_eq!(payments, vec![("checkout.md", 3), ("payments/cards.md", 3), ("legacy.md", 2)]);
+
+    let markdown = format_duplicates_markdown(Path::new("requirements"), &clusters, DEFAULT_DUPLICATE_THRESHOLD);
+    assert!(markdown.contains("**Clusters:** 2 (statements at least 80% similar across files)"));
+    assert!(markdown.contains("## Cluster 2 - 3 statements in 3 files"));
+    assert!(markdown.contains("- `legacy.md:2` - Customers should pay using saved credit cards\n"));
+}
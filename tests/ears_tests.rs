@@ -0,0 +1,30 @@
+use prism::ears::{check_ears, classify_sentence, format_ears_markdown, suggest_ears_rewrite, EarsPattern};
+
+#[test]
+fn test_sentences_are_classified_into_ears_patterns() {
+    assert_eq!(classify_sentence("The system shall log every login."), Some(EarsPattern::Ubiquitous));
+    assert_eq!(classify_sentence("When the user submits the form, the system shall validate it."), Some(EarsPattern::EventDriven));
+    assert_eq!(classify_sentence("While offline, the app shall queue changes."), Some(EarsPattern::StateDriven));
+    assert_eq!(classify_sentence("If the token is invalid, then the system shall reject the request."), Some(EarsPattern::UnwantedBehavior));
+    assert_eq!(classify_sentence("Where SSO is enabled, the system shall skip the password form."), Some(EarsPattern::Optional));
+    // "then" is required after an "if" clause, and trailing conditions belong in front
+    assert_eq!(classify_sentence("If the session expires, the system shall log out."), None);
+    assert_eq!(classify_sentence("The system shall notify the user if the upload fails."), None);
+    assert_eq!(classify_sentence("The system must send an e-mail."), None);
+}
+
+#[test]
+fn test_nonconforming_sentences_get_a_rewrite() {
+    assert_eq!(suggest_ears_rewrite("Users should be able to reset their password."), "The system shall allow the users to reset their password.");
+    assert_eq!(suggest_ears_rewrite("The system must send an e-mail when the password changes."), "When the password changes, the system shall send an e-mail.");
+    assert_eq!(suggest_ears_rewrite("If the upload fails, show an error."), "If the upload fails, then the system shall show an error.");
+    assert_eq!(suggest_ears_rewrite("As a customer, I want to save my cart so that I can buy later."), "The system shall allow the customer to save their cart.");
+
+    let report = check_ears("# Login\n\n- REQ-1: The system shall lock the account. Admins can unlock it.\n\nBackground text.\n");
+    assert_eq!(report.sentences.len(), 2);
+    assert_eq!(report.sentences[1].line, 3);
+    assert_eq!(report.conformance(), 50.0);
+    let markdown = format_ears_markdown(&report);
+    assert!(markdown.contains("**Conforming:** 1 of 2 requirement sentences (50%)"));
+    assert!(markdown.contains("- **Line 3:** Admins can unlock it.\n  - Suggested: The system shall allow the admins to unlock it.\n"));
+}
@@ -0,0 +1,63 @@
+use prism::analyzer::{ActorActionLink, ExtractedEntities};
+use prism::event_storming::{
+    build_event_storming, format_event_storming_markdown, format_event_storming_mermaid, format_event_storming_miro,
+    EventStormingBoard,
+};
+
+fn link(actor: &str, action: &str) -> ActorActionLink {
+    ActorActionLink { actor: actor.to_string(), action: action.to_string(), sentence: String::new() }
+}
+
+fn checkout_board() -> EventStormingBoard {
+    let text = "The customer can place an order.\n\
+                When an order is placed, the system must send an invoice.\n\
+                The manager can view the order.\n\
+                The customer can pay the invoice.";
+    let entities = ExtractedEntities {
+        actors: vec!["customer".to_string(), "manager".to_string()],
+        actions: vec!["place".to_string(), "send".to_string(), "view".to_string(), "pay".to_string()],
+        objects: vec!["order".to_string(), "invoice".to_string()],
+    };
+    let links = vec![link("customer", "place"), link("manager", "view"), link("customer", "pay")];
+    build_event_storming(text, &entities, &links)
+}
+
+#[test]
+fn test_commands_policies_and_read_models() {
+    let board = checkout_board();
+    let commands: Vec<(&str, &str)> = board.commands.iter().map(|c| (c.name.as_str(), c.event.as_str())).collect();
+    assert_eq!(commands, vec![("Place order", "Order placed"), ("Send invoice", "Invoice sent"), ("Pay invoice", "Invoice paid")]);
+    assert_eq!(board.commands[1].actor, None);
+    assert_eq!(board.actors(), vec!["customer", "manager"]);
+
+    assert_eq!(board.policies.len(), 1);
+    assert_eq!(board.policies[0].trigger, "Order placed");
+    assert_eq!(board.policies[0].command, "Send invoice");
+
+    assert_eq!(board.read_models.len(), 1);
+    assert_eq!(board.read_models[0].name, "Order details");
+    assert_eq!(board.read_models[0].actor.as_deref(), Some("manager"));
+    assert_eq!(board.read_models[0].column, 2);
+}
+
+#[test]
+fn test_board_exports() {
+    let board = checkout_board();
+
+    let mermaid = format_event_storming_mermaid(&board);
+    assert!(mermaid.starts_with("flowchart LR\n"));
+    assert!(mermaid.contains("    A1 --> C1\n    C1 --> E1\n"));
+    assert!(mermaid.contains("    P1{{\"Whenever order placed\"}}:::policy\n    E1 --> P1 --> C2\n"));
+    assert!(mermaid.contains("    R1[/\"Order details\"/]:::readModel\n    R1 -.-> A2\n"));
+
+    let miro = format_event_storming_miro(&board, "Checkout");
+    let stickies = miro["sticky_notes"].as_array().unwrap();
+    let event = stickies.iter().find(|s| s["data"]["content"] == "Invoice paid").unwrap();
+    assert_eq!(event["style"]["fillColor"], "orange");
+    assert_eq!(event["position"]["x"], 750);
+    assert!(stickies.iter().any(|s| s["data"]["content"] == "Whenever order placed, send invoice" && s["style"]["fillColor"] == "violet"));
+
+    let markdown = format_event_storming_markdown(&board);
+    assert!(markdown.contains("**Actors:** 2 | **Commands:** 3 | **Domain events:** 3 | **Policies:** 1 | **Read models:** 1"));
+    assert!(markdown.contains("| customer | Place order | Order placed |\n| - | Send invoice | Invoice sent |\n"));
+}
@@ -0,0 +1,27 @@
+use prism::events::{status_event, Event};
+use prism::Analyzer;
+use std::path::Path;
+
+#[tokio::test]
+async fn test_events_are_tagged_json_objects() {
+    let result = Analyzer::new().unwrap().analyze("The system should be fast.").await.unwrap();
+    let finding = serde_json::to_value(Event::Finding { finding: &result.ambiguities[0] }).unwrap();
+    assert_eq!(finding["event"], "finding");
+    assert_eq!(finding["finding"]["rule_id"], "vague-term");
+    assert_eq!(finding["finding"]["location"]["column"], 22);
+
+    let started = serde_json::to_string(&Event::FileStarted { file: "specs/login.md", index: 1, total: 3 }).unwrap();
+    assert_eq!(started, r#"{"event":"file_started","file":"specs/login.md","index":1,"total":3}"#);
+    let saved = serde_json::to_string(&Event::ReportSaved { path: Path::new("reports/login_analysis.md") }).unwrap();
+    assert_eq!(saved, r#"{"event":"report_saved","path":"reports/login_analysis.md"}"#);
+    let finished = serde_json::to_string(&Event::Finished { files: 3, findings: 7 }).unwrap();
+    assert_eq!(finished, r#"{"event":"finished","files":3,"findings":7}"#);
+}
+
+#[test]
+fn test_status_lines_become_status_events_without_their_spacing() {
+    let event = serde_json::to_string(&status_event("\n🎉 Batch processing complete!").unwrap()).unwrap();
+    assert_eq!(event, r#"{"event":"status","message":"🎉 Batch processing complete!"}"#);
+    assert!(status_event("").is_none());
+    assert!(status_event("===========================================").is_none());
+}
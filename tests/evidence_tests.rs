@@ -0,0 +1,46 @@
+use chrono::{TimeZone, Utc};
+use prism::analyzer::Analyzer;
+use prism::evidence::{default_bundle_name, format_checksums, format_review_checklist, sha256_hex, EvidenceBundle, CHECKSUMS_NAME, MANIFEST_NAME};
+use std::io::{Cursor, Read};
+
+#[test]
+fn test_evidence_bundle_is_hash_manifested() {
+    let generated_at = Utc.with_ymd_and_hms(2026, 10, 16, 14, 30, 0).unwrap();
+    let mut bundle = EvidenceBundle::new();
+    bundle.add("requirements/requirements.md", "- Users can export reports.\n");
+    bundle.add("analysis/report.json", "{}");
+    let manifest = bundle.manifest("requirements.md", generated_at);
+    assert_eq!(manifest.files[0].sha256, sha256_hex(b"- Users can export reports.\n"));
+    assert_eq!(manifest.files[1].bytes, 2);
+    assert_eq!(default_bundle_name(generated_at), "prism-evidence-20261016-143000.zip");
+
+    let mut zip_bytes = Cursor::new(Vec::new());
+    bundle.write_zip(&mut zip_bytes, &manifest).unwrap();
+    let mut archive = zip::ZipArchive::new(Cursor::new(zip_bytes.into_inner())).unwrap();
+    let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+    assert_eq!(names.len(), 4);
+    for name in ["requirements/requirements.md", "analysis/report.json", MANIFEST_NAME, CHECKSUMS_NAME] {
+        assert!(names.contains(&name.to_string()), "{} missing", name);
+    }
+
+    let mut checksums = String::new();
+    archive.by_name(CHECKSUMS_NAME).unwrap().read_to_string(&mut checksums).unwrap();
+    assert_eq!(checksums, format_checksums(&manifest));
+    assert!(checksums.ends_with(&format!("{}  analysis/report.json\n", sha256_hex(b"{}"))));
+    let entry = archive.by_name("requirements/requirements.md").unwrap();
+    assert_eq!((entry.last_modified().year(), entry.last_modified().hour()), (2026, 14));
+}
+
+#[tokio::test]
+async fn test_review_checklist_lists_findings_and_open_questions() {
+    let text = "The dashboard should be fast.\nExport formats are TBD.\n";
+    let result = Analyzer::new().unwrap().analyze(text).await.unwrap();
+    let generated_at = Utc.with_ymd_and_hms(2026, 10, 16, 14, 30, 0).unwrap();
+    let checklist = format_review_checklist(&result, &["Export formats are TBD.".to_string()], Some(1), "spec.md", generated_at);
+
+    assert!(checklist.starts_with("# Requirements Review Checklist: spec.md\n*Generated by PRISM on 2026-10-16 14:30 UTC"));
+    assert!(checklist.contains("Line 1: \"fast\""));
+    assert!(checklist.contains("- [ ] The 1 open question(s) are resolved:\n  - Export formats are TBD.\n"));
+    assert!(checklist.contains("- [ ] The 1 requirement(s) without code or tests in traceability/matrix.md are accepted or traced\n"));
+    assert!(checklist.ends_with("| Approver | | | |\n"));
+}
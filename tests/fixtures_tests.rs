@@ -0,0 +1,56 @@
+use prism::analyzer::Analyzer;
+use prism::config::Config;
+use prism::fixtures::{FixtureMode, LlmFixtures};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Answers every chat completion with "recorded answer".
+async fn provider(requests: Arc<AtomicUsize>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0u8; 16384];
+            let _ = socket.read(&mut request).await;
+            requests.fetch_add(1, Ordering::SeqCst);
+            let body = r#"{"choices":[{"message":{"content":"recorded answer"}}]}"#;
+            let response = format!("HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}", body.len(), body);
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+    format!("http://{}/v1/chat/completions", address)
+}
+
+#[tokio::test]
+async fn test_recorded_responses_replayed_without_the_provider() {
+    let dir = std::env::temp_dir().join(format!("prism-fixtures-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let requests = Arc::new(AtomicUsize::new(0));
+    let mut config = Config::default();
+    config.llm.provider = "openai".to_string();
+    config.llm.api_key = Some("test-key".to_string());
+    config.llm.model = "gpt-4".to_string();
+    config.llm.base_url = Some(provider(requests.clone()).await);
+
+    let recording = Analyzer::new().unwrap().with_config(config.clone())
+        .with_fixtures(Some(LlmFixtures::new(&dir, FixtureMode::Record)));
+    assert_eq!(recording.call_llm("Improve: the system should be fast").await.unwrap(), "recorded answer");
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+    let fixtures = LlmFixtures::new(&dir, FixtureMode::Replay);
+    assert_eq!(fixtures.recorded_model().unwrap(), ("openai".to_string(), "gpt-4".to_string()));
+    let replaying = Analyzer::new().unwrap().with_config(config.clone()).with_fixtures(Some(fixtures));
+    assert_eq!(replaying.call_llm("Improve: the system should be fast").await.unwrap(), "recorded answer");
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+    let error = replaying.call_llm("Another prompt").await.unwrap_err().to_string();
+    assert!(error.contains("No recorded response") && error.contains("--record"), "{}", error);
+    // Another model's responses are recorded separately
+    config.llm.model = "gpt-4o".to_string();
+    let other_model = replaying.clone().with_config(config);
+    assert!(other_model.call_llm("Improve: the system should be fast").await.is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
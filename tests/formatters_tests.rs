@@ -0,0 +1,44 @@
+use prism::analyzer::TestCases;
+use prism::formatters::{format_as_jira, format_as_jira_adf, format_as_markdown, format_as_sarif};
+use prism::{format_report, Analyzer, OutputFormat};
+
+#[tokio::test]
+async fn test_format_report_matches_individual_formatters() {
+    let text = "The system should be fast and user-friendly.";
+    let result = Analyzer::new().unwrap().analyze(text).await.unwrap();
+    assert!(!result.ambiguities.is_empty());
+
+    let markdown = format_report(&result, text, &OutputFormat::Markdown, None).unwrap();
+    assert_eq!(markdown, format_as_markdown(&result, text));
+
+    let sarif = format_report(&result, text, &OutputFormat::Sarif, Some("login.md")).unwrap();
+    assert_eq!(sarif, format_as_sarif(&result, Some("login.md")).unwrap());
+    assert!(sarif.contains("login.md"));
+
+    let json = format_report(&result, text, &OutputFormat::Json, None).unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed["ambiguities"].as_array().unwrap().len(), result.ambiguities.len());
+}
+
+#[tokio::test]
+async fn test_jira_formats_leave_out_empty_nodes_and_check_off_test_cases() {
+    let mut result = Analyzer::new().unwrap().analyze("The system should be fast.").await.unwrap();
+    result.ambiguities[0].suggestions.push(String::new());
+    result.test_cases = Some(TestCases {
+        happy_path: vec!["Response within 2s".to_string()],
+        negative_cases: vec![],
+        edge_cases: vec![],
+    });
+
+    fn empty_nodes(node: &serde_json::Value) -> usize {
+        let empty = (node["type"] == "text" && node["text"] == "")
+            || (node["type"] == "blockquote" && node["content"].as_array().is_none_or(|c| c.is_empty()));
+        usize::from(empty) + node["content"].as_array().into_iter().flatten().map(empty_nodes).sum::<usize>()
+    }
+    let document: serde_json::Value = serde_json::from_str(&format_as_jira_adf(&result, "\n").unwrap()).unwrap();
+    assert_eq!(empty_nodes(&document), 0);
+    assert!(!document.to_string().contains("blockquote"));
+
+    let jira = format_as_jira(&result, "The system should be fast.");
+    assert!(jira.contains("- [ ] Response within 2s\n"));
+}
@@ -0,0 +1,76 @@
+use prism::git_integration::{format_git_trace_jira, format_git_trace_markdown, GitIntegration, RegressionRisk};
+use std::path::Path;
+use std::process::Command;
+
+fn git(repo: &Path, args: &[&str]) {
+    let status = Command::new("git").args(args).current_dir(repo).output().unwrap().status;
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+#[tokio::test]
+async fn test_git_trace_reports_requirement_changes_between_commits() {
+    let repo = std::env::temp_dir().join("prism_git_trace_test");
+    let _ = std::fs::remove_dir_all(&repo);
+    std::fs::create_dir_all(repo.join("requirements")).unwrap();
+    git(&repo, &["init", "-q"]);
+    git(&repo, &["config", "user.email", "prism@example.com"]);
+    git(&repo, &["config", "user.name", "PRISM"]);
+    std::fs::write(repo.join("requirements/login.md"), "REQ-1 The user shall log in.\n").unwrap();
+    std::fs::write(repo.join("main.rs"), "fn main() {}\n").unwrap();
+    git(&repo, &["add", "-A"]);
+    git(&repo, &["commit", "-qm", "first"]);
+    std::fs::write(repo.join("requirements/login.md"), "REQ-1 The user shall log in with MFA.\nREQ-2 The admin can reset passwords.\n").unwrap();
+    std::fs::write(repo.join("requirements/password story.md"), "As a user, I want to reset my password.\n").unwrap();
+    git(&repo, &["add", "-A"]);
+    git(&repo, &["commit", "-qm", "second"]);
+
+    let analysis = GitIntegration::discover(&repo.join("requirements")).unwrap()
+        .analyze_requirement_changes("HEAD~1", "HEAD").await.unwrap();
+    assert_eq!(analysis.impact_analysis.total_files_changed, 2);
+    assert_eq!(analysis.impact_analysis.requirement_files_changed, 2);
+    assert!(matches!(analysis.impact_analysis.regression_risk, RegressionRisk::High));
+    let login = analysis.requirement_changes.iter().find(|c| c.file_path == Path::new("requirements/login.md")).unwrap();
+    assert_eq!(login.affected_requirements, vec!["REQ-1", "REQ-2"]);
+
+    let markdown = format_git_trace_markdown(&analysis);
+    assert!(markdown.contains("**Regression risk:** 🟠 High"));
+    assert!(markdown.contains("| `requirements/password story.md` | Added | 80% | — |"));
+    assert!(markdown.contains("  - Run integration tests\n"));
+    assert!(format_git_trace_jira(&analysis).contains("||File||Change||Impact||Requirement IDs||"));
+
+    let _ = std::fs::remove_dir_all(&repo);
+}
+
+#[test]
+fn test_files_changed_since_a_ref_leave_out_deleted_and_other_files() {
+    let repo = std::env::temp_dir().join("prism_git_since_test");
+    let _ = std::fs::remove_dir_all(&repo);
+    std::fs::create_dir_all(repo.join("requirements")).unwrap();
+    git(&repo, &["init", "-q"]);
+    git(&repo, &["config", "user.email", "prism@example.com"]);
+    git(&repo, &["config", "user.name", "PRISM"]);
+    for name in ["requirements/login.md", "requirements/search.md", "requirements/old.md"] {
+        std::fs::write(repo.join(name), "The user shall log in.\n").unwrap();
+    }
+    git(&repo, &["add", "-A"]);
+    git(&repo, &["commit", "-qm", "first"]);
+    git(&repo, &["tag", "v1"]);
+    std::fs::write(repo.join("requirements/login.md"), "The user shall log in with MFA.\n").unwrap();
+    std::fs::write(repo.join("requirements/new story.md"), "As a user, I want to export.\n").unwrap();
+    std::fs::write(repo.join("main.rs"), "fn main() {}\n").unwrap();
+    std::fs::remove_file(repo.join("requirements/old.md")).unwrap();
+    git(&repo, &["add", "-A"]);
+    git(&repo, &["commit", "-qm", "second"]);
+
+    let git_repo = GitIntegration::discover(&repo.join("requirements")).unwrap();
+    let root = git_repo.repo_path().to_path_buf();
+    assert_eq!(git_repo.changed_files_since("v1").unwrap(), vec![
+        root.join("main.rs"), root.join("requirements/login.md"), root.join("requirements/new story.md"),
+    ]);
+    assert_eq!(git_repo.get_modified_requirements_since_commit("v1").unwrap(), vec![
+        root.join("requirements/login.md"), root.join("requirements/new story.md"),
+    ]);
+    assert!(git_repo.changed_files_since("HEAD").unwrap().is_empty());
+    assert!(git_repo.changed_files_since("no-such-ref").is_err());
+    let _ = std::fs::remove_dir_all(&repo);
+}
@@ -0,0 +1,45 @@
+use prism::analyzer::{AmbiguitySeverity, Analyzer, HiddenFindings};
+use prism::config::Config;
+use prism::formatters::{format_as_markdown, format_as_plain};
+
+const SPEC: &str = "The system should be fast.\nReports should be generated nightly.";
+
+#[tokio::test]
+async fn test_findings_the_rules_and_the_ai_share_are_merged_and_counted() {
+    let mut config = Config::default();
+    config.llm.api_key = None;
+    config.set_provider("mock");
+    let path = std::env::temp_dir().join(format!("prism-hidden-{}.yaml", std::process::id()));
+    std::fs::write(&path, r#"
+tasks:
+  ambiguities: |
+    {"ambiguities": [{"text": "Fast", "reason": "How fast?", "suggestions": ["Give a response time"], "severity": "High"},
+                     {"text": "nightly", "reason": "Which time zone?", "suggestions": [], "severity": "Low"}]}
+"#).unwrap();
+    config.llm.mock_responses = Some(path);
+
+    let result = Analyzer::new().unwrap().with_config(config).analyze(SPEC).await.unwrap();
+    let fast: Vec<_> = result.ambiguities.iter().filter(|a| a.text.eq_ignore_ascii_case("fast")).collect();
+    assert_eq!(fast.len(), 1, "{:#?}", result.ambiguities);
+    assert_eq!(fast[0].severity, AmbiguitySeverity::High);
+    assert_eq!(result.hidden_findings, HiddenFindings { duplicates: 1, ..HiddenFindings::default() });
+    assert!(format_as_markdown(&result, SPEC).contains("- **Hidden Findings:** 1 duplicate merged\n"));
+}
+
+#[tokio::test]
+async fn test_findings_below_the_minimum_severity_are_left_out_and_counted() {
+    let mut result = Analyzer::new().unwrap().analyze(SPEC).await.unwrap();
+    let medium = result.ambiguities.iter().filter(|a| a.severity == AmbiguitySeverity::Medium).count();
+    assert!(medium > 0);
+    assert!(result.hidden_findings.summary().is_none());
+
+    result.retain_min_severity(AmbiguitySeverity::High);
+    assert!(result.ambiguities.iter().all(|a| a.severity >= AmbiguitySeverity::High));
+    assert_eq!(result.hidden_findings.below_min_severity, medium);
+    let summary = format!("{} below High", medium);
+    assert_eq!(result.hidden_findings.summary().as_deref(), Some(summary.as_str()));
+    assert!(format_as_plain(&result, SPEC).contains(&format!("HIDDEN FINDINGS: {}\n", summary)));
+
+    let json = serde_json::to_value(&result).unwrap();
+    assert_eq!(json["hidden_findings"]["min_severity"], "High");
+}
@@ -0,0 +1,60 @@
+use prism::analyzer::Analyzer;
+use prism::hierarchy::*;
+use std::path::PathBuf;
+
+fn sample_files() -> Vec<(PathBuf, String)> {
+    vec![
+        (
+            PathBuf::from("EPIC-1 Checkout/payments.md"),
+            "# FEAT-3 Card payments\n## US-7 Pay by card\nAs a customer, I want to pay by card so that checkout is quick.\n## US-8 Save card\nAs a customer, I want to save my card for later.\n".to_string(),
+        ),
+        (
+            PathBuf::from("EPIC-1 Checkout/shipping.md"),
+            "As a customer, I want to choose a delivery date.\n".to_string(),
+        ),
+    ]
+}
+
+#[test]
+fn test_hierarchy_detects_levels_from_folders_headings_and_ids() {
+    let root = HierarchyNode::build("requirements", &sample_files());
+
+    assert_eq!(root.level, HierarchyLevel::Root);
+    let epic = &root.children[0];
+    assert_eq!(epic.name, "EPIC-1 Checkout");
+    assert_eq!(epic.level, HierarchyLevel::Epic);
+
+    let payments = &epic.children[0];
+    let feature = &payments.children[0];
+    assert_eq!(feature.name, "FEAT-3 Card payments");
+    assert_eq!(feature.level, HierarchyLevel::Feature);
+    assert_eq!(feature.children.len(), 2);
+    assert!(feature.children.iter().all(|story| story.level == HierarchyLevel::Story));
+
+    // A file without headings is a leaf holding its own text
+    let shipping = &epic.children[1];
+    assert!(shipping.children.is_empty());
+    assert!(shipping.text.contains("delivery date"));
+}
+
+#[tokio::test]
+async fn test_hierarchy_scores_aggregate_up_the_tree() {
+    let analyzer = Analyzer::new().unwrap();
+    let mut root = HierarchyNode::build("requirements", &sample_files());
+
+    for (path, text) in root.texts_to_analyze() {
+        let result = analyzer.analyze(&text).await.unwrap();
+        root.set_own_score(&path, QualityScore::from_result(&result));
+    }
+    root.aggregate();
+
+    assert_eq!(root.score.requirement_count, 3);
+    assert_eq!(root.score.finding_count, 1);
+    assert!(root.score.score < 100.0);
+
+    let weakest = root.weakest_stories(1);
+    assert_eq!(weakest[0].name, "US-7 Pay by card");
+
+    let tree = root.render_tree();
+    assert!(tree.contains("└── 📝 Story: US-8 Save card"));
+}
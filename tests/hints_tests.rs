@@ -0,0 +1,43 @@
+use prism::analyzer::Analyzer;
+use prism::hints::{format_hints, suggest_hints, HintContext, MAX_HINTS};
+
+#[tokio::test]
+async fn test_hints_follow_the_result_contents() {
+    let analyzer = Analyzer::new().unwrap();
+    let text = "As a user, I want to login quickly. The admin should manage accounts.";
+    let mut result = analyzer.analyze(text).await.unwrap();
+    let context = HintContext { source: Some("login story.md".to_string()), ..Default::default() };
+
+    let hints = suggest_hints(&result, text, &context);
+    let commands: Vec<&str> = hints.iter().map(|h| h.command.as_str()).collect();
+    assert!(commands.contains(&"prism analyze --file \"login story.md\" --generate tests"));
+    assert!(commands.contains(&"prism validate --file \"login story.md\" --story"));
+    assert!(commands.contains(&"prism analyze --file \"login story.md\" --generate permissions"));
+    assert_eq!(commands.last(), Some(&"prism config --setup"));
+
+    // Once generated, the hints for them go away
+    result.test_cases = Some(analyzer.generate_test_cases(&result.entities));
+    result.user_story_validation = Some(analyzer.validate_user_story(text));
+    result.permission_matrix = Some(analyzer.generate_permission_matrix(text, &result.entities));
+    let context = HintContext { ai_configured: true, ..context };
+    let hints = suggest_hints(&result, text, &context);
+    assert!(!hints.iter().any(|h| h.command.ends_with("--generate tests")
+        || h.command.ends_with("--story")
+        || h.command.ends_with("--generate permissions")
+        || h.command == "prism config --setup"));
+}
+
+#[tokio::test]
+async fn test_format_hints_shows_the_top_hints_only() {
+    let analyzer = Analyzer::new().unwrap();
+    let text = "The system should be fast and user-friendly.";
+    let result = analyzer.analyze(text).await.unwrap();
+    let hints = suggest_hints(&result, text, &HintContext::default());
+    assert!(hints.len() > MAX_HINTS);
+
+    let output = format_hints(&hints);
+    assert!(output.starts_with("\n💡 What you could also do:\n"));
+    assert_eq!(output.matches("   • ").count(), MAX_HINTS);
+    assert!(output.contains(&format!("   • {} — try `{}`\n", hints[0].reason, hints[0].command)));
+    assert!(output.contains("prism config --hints false"));
+}
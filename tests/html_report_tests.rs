@@ -0,0 +1,33 @@
+use prism::analyzer::Analyzer;
+use prism::html_report::{format_html_report, html_escape, MERMAID_SCRIPT_URL};
+
+#[tokio::test]
+async fn test_html_report_is_filterable_and_renders_diagrams() {
+    let analyzer = Analyzer::new().unwrap();
+    let text = "As a user, I want a fast, user-friendly login so that I can see my <dashboard>.";
+    let result = analyzer.analyze(text).await.unwrap();
+    assert!(!result.ambiguities.is_empty());
+
+    let diagrams = vec![("Use Case Diagram", analyzer.generate_mermaid_use_case(text, &result.entities))];
+    let html = format_html_report(&result, text, Some("login.md"), &diagrams);
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<title>PRISM Report - login.md</title>"));
+    assert!(html.contains("<details open>"));
+    assert!(html.contains("class=\"severity-filter\""));
+    for ambiguity in &result.ambiguities {
+        let severity = ambiguity.severity.to_string().to_lowercase();
+        assert!(html.contains(&format!("<tr data-severity=\"{}\">", severity)));
+    }
+    assert!(html.contains("<pre class=\"mermaid\">\nflowchart LR\n"));
+    assert!(html.contains(MERMAID_SCRIPT_URL));
+
+    // Requirement text is escaped, not injected as markup
+    assert!(html.contains("my &lt;dashboard&gt;."));
+    assert!(!html.contains("<dashboard>"));
+}
+
+#[test]
+fn test_html_escape() {
+    assert_eq!(html_escape("<a href=\"x\">Tom & Jerry's</a>"), "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;");
+}
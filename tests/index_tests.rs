@@ -0,0 +1,71 @@
+use prism::index::*;
+
+fn corpus() -> Vec<(String, String)> {
+    vec![
+        ("pay/card.md".to_string(), "# Payments\nAs a customer, I want to pay by card so that checkout is quick.\nRefunds must be processed within 5 days by the finance team.\n".to_string()),
+        ("other.md".to_string(), "Refunds must be processed within 10 days by the finance team.\nAs a customer, I want to pay by card so that checkout is quick.\nAdmins can export payment reports as CSV files.\n".to_string()),
+    ]
+}
+
+#[test]
+fn test_index_updates_incrementally() {
+    let mut index = RequirementIndex::default();
+    let mut files = corpus();
+
+    let update = index.update(&files);
+    assert_eq!(update, IndexUpdate { added: 2, updated: 0, unchanged: 0, removed: 0 });
+    // The heading is too short to index
+    assert_eq!(index.chunk_count(), 5);
+
+    files[1].1.push_str("Admins cannot delete payment reports.\n");
+    let update = index.update(&files);
+    assert_eq!(update, IndexUpdate { added: 0, updated: 1, unchanged: 1, removed: 0 });
+    assert_eq!(index.chunk_count(), 6);
+
+    files.remove(0);
+    let update = index.update(&files);
+    assert_eq!(update, IndexUpdate { added: 0, updated: 0, unchanged: 1, removed: 1 });
+    assert!(!index.files.contains_key("pay/card.md"));
+}
+
+#[test]
+fn test_index_search_ranks_related_requirements() {
+    let mut index = RequirementIndex::default();
+    index.update(&corpus());
+
+    let hits = index.search("how long do refunds take", 2, None);
+    assert!(hits.iter().all(|hit| hit.text.starts_with("Refunds must be processed")));
+
+    let hits = index.search("how long do refunds take", 5, Some("other.md"));
+    assert!(hits.iter().all(|hit| hit.source == "pay/card.md"));
+    assert_eq!(hits[0].line, 3);
+}
+
+#[test]
+fn test_index_reports_duplicates_and_conflicts() {
+    let mut index = RequirementIndex::default();
+    index.update(&corpus());
+
+    let report = index.find_duplicates_and_conflicts();
+    assert_eq!(report.duplicates.len(), 1);
+    assert!(report.duplicates[0].first.text.starts_with("As a customer, I want to pay by card"));
+
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].reason, "Different values (10 vs 5)");
+
+    let markdown = format_corpus_report_markdown(&report, std::path::Path::new("requirements"));
+    assert!(markdown.contains("`other.md:1` Refunds must be processed within 10 days"));
+}
+
+#[test]
+fn test_embeddings_are_normalized_and_stable() {
+    let a = embed("Export monthly payment reports");
+    let b = embed("exporting the monthly payment report");
+    let norm: f32 = a.iter().map(|(_, x)| x * x).sum::<f32>().sqrt();
+
+    assert!(a.iter().all(|(dimension, _)| (*dimension as usize) < EMBEDDING_DIMENSIONS));
+    assert!((norm - 1.0).abs() < 1e-5);
+    assert_eq!(a, embed("Export monthly payment reports"));
+    assert!(cosine(&a, &b) > 0.99);
+    assert!(cosine(&a, &embed("Refunds are processed by finance")) < 0.1);
+}
@@ -22,12 +22,29 @@ async fn test_text_analysis_command() {
         generate: vec![],
         format: Some(OutputFormat::Json),
         pseudo_lang: None,
+        arch: None,
         save_artifacts: None,
         template: None,
         branding: None,
+        toc: false,
+        min_severity: None,
+        fail_on: None,
+        min_completeness: None,
         continue_on_error: false,
         skip_invalid: false,
-        parallel: 1,
+        jobs: 1,
+        max_comment_size: 65536,
+        budget: None,
+        resume: false,
+        since: None,
+        output_dir: None,
+        no_cache: false,
+        owners: None,
+        provider: None,
+        model: None,
+        temperature: None,
+        max_tokens: None,
+        compare_providers: None,
     };
     
     let result = app.run_command(command).await;
@@ -51,12 +68,29 @@ async fn test_file_analysis_command() {
         generate: vec![GenerateOptions::Uml, GenerateOptions::Pseudo, GenerateOptions::Tests],
         format: Some(OutputFormat::Markdown),
         pseudo_lang: Some("python".to_string()),
+        arch: None,
         save_artifacts: None,
         template: None,
         branding: None,
+        toc: false,
+        min_severity: None,
+        fail_on: None,
+        min_completeness: None,
         continue_on_error: false,
         skip_invalid: false,
-        parallel: 1,
+        jobs: 1,
+        max_comment_size: 65536,
+        budget: None,
+        resume: false,
+        since: None,
+        output_dir: None,
+        no_cache: false,
+        owners: None,
+        provider: None,
+        model: None,
+        temperature: None,
+        max_tokens: None,
+        compare_providers: None,
     };
     
     let result = app.run_command(command).await;
@@ -80,12 +114,29 @@ async fn test_output_to_file() {
         generate: vec![],
         format: Some(OutputFormat::Markdown),
         pseudo_lang: None,
+        arch: None,
         save_artifacts: None,
         template: None,
         branding: None,
+        toc: false,
+        min_severity: None,
+        fail_on: None,
+        min_completeness: None,
         continue_on_error: false,
         skip_invalid: false,
-        parallel: 1,
+        jobs: 1,
+        max_comment_size: 65536,
+        budget: None,
+        resume: false,
+        since: None,
+        output_dir: None,
+        no_cache: false,
+        owners: None,
+        provider: None,
+        model: None,
+        temperature: None,
+        max_tokens: None,
+        compare_providers: None,
     };
     
     let result = app.run_command(command).await;
@@ -103,6 +154,173 @@ async fn test_output_to_file() {
     let _ = fs::remove_file(&output_file).await;
 }
 
+#[tokio::test]
+async fn test_jira_adf_output_is_valid_document() {
+    let mut app = App::new().await.unwrap();
+    let output_file = PathBuf::from("test_integration_output_adf.json");
+    
+    let command = Commands::Analyze {
+        text: Some("The system should respond fast".to_string()),
+        file: None,
+        dir: None,
+        output: Some(output_file.clone()),
+        preset: Some(AnalysisPreset::Basic),
+        generate: vec![],
+        format: Some(OutputFormat::JiraAdf),
+        pseudo_lang: None,
+        arch: None,
+        save_artifacts: None,
+        template: None,
+        branding: None,
+        toc: false,
+        min_severity: None,
+        fail_on: None,
+        min_completeness: None,
+        continue_on_error: false,
+        skip_invalid: false,
+        jobs: 1,
+        max_comment_size: 65536,
+        budget: None,
+        resume: false,
+        since: None,
+        output_dir: None,
+        no_cache: false,
+        owners: None,
+        provider: None,
+        model: None,
+        temperature: None,
+        max_tokens: None,
+        compare_providers: None,
+    };
+    
+    let result = app.run_command(command).await;
+    assert!(result.is_ok());
+    
+    let content = fs::read_to_string(&output_file).await.unwrap();
+    let document: serde_json::Value = serde_json::from_str(&content).unwrap();
+    assert_eq!(document["type"], "doc");
+    assert_eq!(document["version"], 1);
+    assert!(document["content"].as_array().unwrap().iter().any(|node| node["type"] == "panel"));
+    
+    // Clean up
+    let _ = fs::remove_file(&output_file).await;
+}
+
+#[tokio::test]
+async fn test_github_output_split_into_comments() {
+    let mut app = App::new().await.unwrap();
+    let output_file = PathBuf::from("test_integration_github.md");
+    let part_file = PathBuf::from("test_integration_github.part2.md");
+    
+    let command = Commands::Analyze {
+        text: Some("The system should be fast, robust, scalable and easy. Many users need several quick and efficient reports.".to_string()),
+        file: None,
+        dir: None,
+        output: Some(output_file.clone()),
+        preset: Some(AnalysisPreset::Standard),
+        generate: vec![],
+        format: Some(OutputFormat::Github),
+        pseudo_lang: None,
+        arch: None,
+        save_artifacts: None,
+        template: None,
+        branding: None,
+        toc: false,
+        min_severity: None,
+        fail_on: None,
+        min_completeness: None,
+        continue_on_error: false,
+        skip_invalid: false,
+        jobs: 1,
+        max_comment_size: 1000,
+        budget: None,
+        resume: false,
+        since: None,
+        output_dir: None,
+        no_cache: false,
+        owners: None,
+        provider: None,
+        model: None,
+        temperature: None,
+        max_tokens: None,
+        compare_providers: None,
+    };
+    
+    let result = app.run_command(command).await;
+    assert!(result.is_ok());
+    
+    let first = fs::read_to_string(&output_file).await.unwrap();
+    let second = fs::read_to_string(&part_file).await.unwrap();
+    assert!(first.chars().count() <= 1000);
+    assert!(second.chars().count() <= 1000);
+    assert!(first.contains("part 1 of"));
+    assert!(second.contains("part 2 of"));
+    assert_eq!(first.matches("<details>").count(), first.matches("</details>").count());
+    
+    // Clean up
+    let _ = fs::remove_file(&output_file).await;
+    for i in 2..20 {
+        let _ = fs::remove_file(format!("test_integration_github.part{}.md", i)).await;
+    }
+}
+
+#[test]
+fn test_analyze_reads_piped_stdin() {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let home = std::env::temp_dir().join(format!("prism_stdin_home_{}", std::process::id()));
+    std::fs::create_dir_all(&home).unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_prism"))
+        .args(["analyze", "--format", "plain"])
+        .env("HOME", &home)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"The system should respond fast").unwrap();
+    let output = child.wait_with_output().unwrap();
+    let _ = std::fs::remove_dir_all(&home);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Reading requirements from stdin"));
+    assert!(stdout.contains("REQUIREMENT ANALYSIS REPORT"));
+    assert!(stdout.contains("fast"));
+}
+
+#[test]
+fn test_json_format_keeps_stdout_to_the_json_document() {
+    use std::process::Command;
+
+    let home = std::env::temp_dir().join(format!("prism_json_home_{}", std::process::id()));
+    std::fs::create_dir_all(&home).unwrap();
+    let run = |args: &[&str]| Command::new(env!("CARGO_BIN_EXE_prism")).args(args).env("HOME", &home).output().unwrap();
+    let output = run(&["analyze", "The system should respond fast", "--format", "json"]);
+    let quiet = run(&["--no-banner", "analyze", "The system should respond fast", "--format", "json"]);
+    let csv = run(&["analyze", "The system should respond fast", "--format", "csv"]);
+    let sarif = run(&["analyze", "The system should respond fast", "--format", "sarif"]);
+    let _ = std::fs::remove_dir_all(&home);
+
+    assert!(output.status.success());
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(result["ambiguities"].is_array());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("PRISM - AI-Powered Requirement Analyzer") && stderr.contains("Analysis completed"), "{}", stderr);
+    assert!(!String::from_utf8_lossy(&quiet.stderr).contains("PRISM - AI-Powered Requirement Analyzer"));
+    assert_eq!(quiet.stdout, output.stdout);
+
+    // Other machine-readable formats keep stdout to the document too
+    let rows = String::from_utf8_lossy(&csv.stdout);
+    let findings = result["ambiguities"].as_array().unwrap().len();
+    assert_eq!(rows.trim_end().lines().count(), findings + 1, "{}", rows);
+    assert!(String::from_utf8_lossy(&csv.stderr).contains("Analysis completed"));
+    // Uploaded to code scanning as it is
+    let log: serde_json::Value = serde_json::from_slice(&sarif.stdout).unwrap();
+    assert_eq!(log["version"], "2.1.0");
+    assert_eq!(log["runs"][0]["results"].as_array().unwrap().len(), findings);
+}
+
 #[tokio::test]
 async fn test_config_command() {
     let mut app = App::new().await.unwrap();
@@ -118,6 +336,11 @@ async fn test_config_command() {
         validate_all: false,
         test_providers: false,
         set_template_dir: None,
+        init_prompts: false,
+        hints: None,
+        migrate_secrets: false,
+        base_url: None,
+        header: Vec::new(),
     };
     
     let result = app.run_command(command).await;
@@ -131,6 +354,7 @@ async fn test_all_output_formats() {
         OutputFormat::Markdown,
         OutputFormat::Github,
         OutputFormat::Jira,
+        OutputFormat::JiraAdf,
         OutputFormat::Plain,
     ];
     
@@ -146,12 +370,29 @@ async fn test_all_output_formats() {
             generate: vec![],
             format: Some(format.clone()),
             pseudo_lang: None,
+            arch: None,
             save_artifacts: None,
             template: None,
             branding: None,
+            toc: false,
+            min_severity: None,
+            fail_on: None,
+            min_completeness: None,
             continue_on_error: false,
             skip_invalid: false,
-            parallel: 1,
+            jobs: 1,
+            max_comment_size: 65536,
+            budget: None,
+            resume: false,
+            since: None,
+            output_dir: None,
+            no_cache: false,
+            owners: None,
+            provider: None,
+            model: None,
+            temperature: None,
+            max_tokens: None,
+            compare_providers: None,
         };
         
         let result = app.run_command(command).await;
@@ -172,12 +413,29 @@ async fn test_error_handling_nonexistent_file() {
         generate: vec![],
         format: Some(OutputFormat::Json),
         pseudo_lang: None,
+        arch: None,
         save_artifacts: None,
         template: None,
         branding: None,
+        toc: false,
+        min_severity: None,
+        fail_on: None,
+        min_completeness: None,
         continue_on_error: false,
         skip_invalid: false,
-        parallel: 1,
+        jobs: 1,
+        max_comment_size: 65536,
+        budget: None,
+        resume: false,
+        since: None,
+        output_dir: None,
+        no_cache: false,
+        owners: None,
+        provider: None,
+        model: None,
+        temperature: None,
+        max_tokens: None,
+        compare_providers: None,
     };
     
     let result = app.run_command(command).await;
@@ -197,12 +455,29 @@ async fn test_error_handling_nonexistent_directory() {
         generate: vec![],
         format: Some(OutputFormat::Json),
         pseudo_lang: None,
+        arch: None,
         save_artifacts: None,
         template: None,
         branding: None,
+        toc: false,
+        min_severity: None,
+        fail_on: None,
+        min_completeness: None,
         continue_on_error: false,
         skip_invalid: false,
-        parallel: 1,
+        jobs: 1,
+        max_comment_size: 65536,
+        budget: None,
+        resume: false,
+        since: None,
+        output_dir: None,
+        no_cache: false,
+        owners: None,
+        provider: None,
+        model: None,
+        temperature: None,
+        max_tokens: None,
+        compare_providers: None,
     };
     
     let result = app.run_command(command).await;
@@ -227,12 +502,29 @@ async fn test_directory_analysis() {
         generate: vec![],
         format: Some(OutputFormat::Json),
         pseudo_lang: None,
+        arch: None,
         save_artifacts: None,
         template: None,
         branding: None,
+        toc: false,
+        min_severity: None,
+        fail_on: None,
+        min_completeness: None,
         continue_on_error: false,
         skip_invalid: false,
-        parallel: 1,
+        jobs: 1,
+        max_comment_size: 65536,
+        budget: None,
+        resume: false,
+        since: None,
+        output_dir: None,
+        no_cache: false,
+        owners: None,
+        provider: None,
+        model: None,
+        temperature: None,
+        max_tokens: None,
+        compare_providers: None,
     };
     
     let result = app.run_command(command).await;
@@ -242,6 +534,179 @@ async fn test_directory_analysis() {
     let _ = fs::remove_dir_all("temp_test_dir").await;
 }
 
+#[tokio::test]
+async fn test_parallel_directory_analysis_keeps_file_order() {
+    let input = std::env::temp_dir().join("prism_parallel_input");
+    let reports = std::env::temp_dir().join("prism_parallel_reports");
+    let _ = fs::remove_dir_all(&input).await;
+    let _ = fs::remove_dir_all(&reports).await;
+    fs::create_dir_all(&input).await.unwrap();
+    for i in 1..=5 {
+        fs::write(input.join(format!("story{}.md", i)), format!("As a user, I want to export report {} quickly", i)).await.unwrap();
+    }
+
+    let mut app = App::new().await.unwrap();
+    let command = Commands::Analyze {
+        text: None,
+        file: None,
+        dir: Some(input.clone()),
+        output: None,
+        preset: Some(AnalysisPreset::Basic),
+        generate: vec![],
+        format: Some(OutputFormat::Json),
+        pseudo_lang: None,
+        arch: None,
+        save_artifacts: None,
+        template: None,
+        branding: None,
+        toc: false,
+        min_severity: None,
+        fail_on: None,
+        min_completeness: None,
+        continue_on_error: false,
+        skip_invalid: false,
+        jobs: 3,
+        max_comment_size: 65536,
+        budget: None,
+        resume: false,
+        since: None,
+        output_dir: Some(reports.clone()),
+        no_cache: false,
+        owners: None,
+        provider: None,
+        model: None,
+        temperature: None,
+        max_tokens: None,
+        compare_providers: None,
+    };
+
+    let result = app.run_command(command).await;
+    assert!(result.is_ok());
+
+    let index = fs::read_to_string(reports.join("index.md")).await.unwrap();
+    let positions: Vec<usize> = (1..=5).map(|i| index.find(&format!("story{}.md", i)).unwrap()).collect();
+    assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+    for i in 1..=5 {
+        assert!(reports.join(format!("story{}_analysis.md", i)).exists());
+    }
+
+    let _ = fs::remove_dir_all(&input).await;
+    let _ = fs::remove_dir_all(&reports).await;
+}
+
+#[tokio::test]
+async fn test_directory_analysis_since_a_git_ref_skips_unchanged_files() {
+    let repo = std::env::temp_dir().join("prism_since_repo");
+    let reports = std::env::temp_dir().join("prism_since_reports");
+    let _ = fs::remove_dir_all(&repo).await;
+    let _ = fs::remove_dir_all(&reports).await;
+    fs::create_dir_all(repo.join("specs")).await.unwrap();
+    let git = |args: &[&str]| assert!(std::process::Command::new("git").args(args).current_dir(&repo).output().unwrap().status.success());
+    git(&["init", "-q"]);
+    git(&["config", "user.email", "prism@example.com"]);
+    git(&["config", "user.name", "PRISM"]);
+    fs::write(repo.join("specs/login.md"), "As a user, I want to login").await.unwrap();
+    fs::write(repo.join("specs/export.md"), "As a user, I want to export reports").await.unwrap();
+    git(&["add", "-A"]);
+    git(&["commit", "-qm", "first"]);
+    fs::write(repo.join("specs/export.md"), "As a user, I want to export reports quickly").await.unwrap();
+    git(&["commit", "-qam", "second"]);
+
+    let mut app = App::new().await.unwrap();
+    let command = Commands::Analyze {
+        text: None,
+        file: None,
+        dir: Some(repo.join("specs")),
+        output: None,
+        preset: Some(AnalysisPreset::Basic),
+        generate: vec![],
+        format: Some(OutputFormat::Json),
+        pseudo_lang: None,
+        arch: None,
+        save_artifacts: None,
+        template: None,
+        branding: None,
+        toc: false,
+        min_severity: None,
+        fail_on: None,
+        min_completeness: None,
+        continue_on_error: false,
+        skip_invalid: false,
+        jobs: 1,
+        max_comment_size: 65536,
+        budget: None,
+        resume: false,
+        since: Some("HEAD~1".to_string()),
+        output_dir: Some(reports.clone()),
+        no_cache: true,
+        owners: None,
+        provider: None,
+        model: None,
+        temperature: None,
+        max_tokens: None,
+        compare_providers: None,
+    };
+
+    let result = app.run_command(command).await;
+    assert!(result.is_ok(), "{:?}", result);
+    assert!(reports.join("export_analysis.md").exists());
+    assert!(!reports.join("login_analysis.md").exists());
+
+    let _ = fs::remove_dir_all(&repo).await;
+    let _ = fs::remove_dir_all(&reports).await;
+}
+
+#[tokio::test]
+async fn test_analyze_paths_relative() {
+    let dir = std::env::current_dir().unwrap().join("paths_relative_test");
+    fs::create_dir_all(&dir).await.unwrap();
+    fs::write(dir.join("login.md"), "The system should be fast.").await.unwrap();
+    let report = dir.join("report.json");
+
+    let mut app = App::new().await.unwrap().with_paths_relative(true);
+    let command = Commands::Analyze {
+        text: None,
+        file: Some(dir.join("login.md")),
+        dir: None,
+        output: Some(report.clone()),
+        preset: Some(AnalysisPreset::Basic),
+        generate: vec![],
+        format: Some(OutputFormat::Json),
+        pseudo_lang: None,
+        arch: None,
+        save_artifacts: None,
+        template: None,
+        branding: None,
+        toc: false,
+        min_severity: None,
+        fail_on: None,
+        min_completeness: None,
+        continue_on_error: false,
+        skip_invalid: false,
+        jobs: 1,
+        max_comment_size: 65536,
+        budget: None,
+        resume: false,
+        since: None,
+        output_dir: None,
+        no_cache: true,
+        owners: None,
+        provider: None,
+        model: None,
+        temperature: None,
+        max_tokens: None,
+        compare_providers: None,
+    };
+
+    let result = app.run_command(command).await;
+    assert!(result.is_ok(), "{:?}", result);
+    let report: serde_json::Value = serde_json::from_str(&fs::read_to_string(&report).await.unwrap()).unwrap();
+    // Named from the current directory although it was given as an absolute path
+    assert_eq!(report["ambiguities"][0]["location"]["file"], "paths_relative_test/login.md");
+
+    let _ = fs::remove_dir_all(&dir).await;
+}
+
 #[tokio::test]
 async fn test_comprehensive_analysis_with_all_features() {
     let mut app = App::new().await.unwrap();
@@ -262,12 +727,29 @@ async fn test_comprehensive_analysis_with_all_features() {
         generate: vec![],
         format: Some(OutputFormat::Markdown),
         pseudo_lang: Some("python".to_string()),
+        arch: None,
         save_artifacts: None,
         template: None,
         branding: None,
+        toc: false,
+        min_severity: None,
+        fail_on: None,
+        min_completeness: None,
         continue_on_error: false,
         skip_invalid: false,
-        parallel: 1,
+        jobs: 1,
+        max_comment_size: 65536,
+        budget: None,
+        resume: false,
+        since: None,
+        output_dir: None,
+        no_cache: false,
+        owners: None,
+        provider: None,
+        model: None,
+        temperature: None,
+        max_tokens: None,
+        compare_providers: None,
     };
     
     let result = app.run_command(command).await;
@@ -298,8 +780,10 @@ async fn test_validate_command() {
         output: None,
         story: true,
         completeness: false,
+        ears: false,
         all: false,
         format: Some(OutputFormat::Json),
+        max_comment_size: 65536,
     };
     
     let result = app.run_command(command).await;
@@ -317,8 +801,10 @@ async fn test_validate_all_command() {
         output: None,
         story: false,
         completeness: false,
+        ears: false,
         all: true,
         format: Some(OutputFormat::Json),
+        max_comment_size: 65536,
     };
     
     let result = app.run_command(command).await;
@@ -386,12 +872,29 @@ async fn test_preset_combinations() {
             generate: vec![],
             format: Some(OutputFormat::Json),
             pseudo_lang: None,
+            arch: None,
             save_artifacts: None,
             template: None,
             branding: None,
+            toc: false,
+            min_severity: None,
+            fail_on: None,
+            min_completeness: None,
             continue_on_error: false,
             skip_invalid: false,
-            parallel: 1,
+            jobs: 1,
+            max_comment_size: 65536,
+            budget: None,
+            resume: false,
+            since: None,
+            output_dir: None,
+            no_cache: false,
+            owners: None,
+            provider: None,
+            model: None,
+            temperature: None,
+            max_tokens: None,
+            compare_providers: None,
         };
         
         let result = app.run_command(command).await;
@@ -399,6 +902,95 @@ async fn test_preset_combinations() {
     }
 }
 
+#[tokio::test]
+async fn test_custom_preset_from_config() {
+    let mut app = App::new().await.unwrap();
+    app.config.presets.insert("ci".to_string(), prism::config::PresetConfig {
+        generate: vec![GenerateOptions::Tests],
+        format: Some(OutputFormat::Json),
+        min_severity: Some(prism::analyzer::AmbiguitySeverity::Critical),
+    });
+
+    let command = Commands::Analyze {
+        text: Some("As a user, I want the system to be fast and user-friendly".to_string()),
+        file: None,
+        dir: None,
+        output: Some(PathBuf::from("custom_preset_test.out")),
+        preset: Some("ci".parse().unwrap()),
+        generate: vec![],
+        format: None,
+        pseudo_lang: None,
+        arch: None,
+        save_artifacts: None,
+        template: None,
+        branding: None,
+        toc: false,
+        min_severity: None,
+        fail_on: None,
+        min_completeness: None,
+        continue_on_error: false,
+        skip_invalid: false,
+        jobs: 1,
+        max_comment_size: 65536,
+        budget: None,
+        resume: false,
+        since: None,
+        output_dir: None,
+        no_cache: false,
+        owners: None,
+        provider: None,
+        model: None,
+        temperature: None,
+        max_tokens: None,
+        compare_providers: None,
+    };
+
+    let result = app.run_command(command).await;
+    assert!(result.is_ok());
+
+    let output = fs::read_to_string("custom_preset_test.out").await.unwrap();
+    let _ = fs::remove_file("custom_preset_test.out").await;
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert!(json["test_cases"].is_object());
+    assert!(json["ambiguities"].as_array().unwrap().iter().all(|a| a["severity"] == "Critical"));
+
+    let command = Commands::Analyze {
+        text: Some("Test requirement".to_string()),
+        file: None,
+        dir: None,
+        output: None,
+        preset: Some(AnalysisPreset::Custom("missing".to_string())),
+        generate: vec![],
+        format: None,
+        pseudo_lang: None,
+        arch: None,
+        save_artifacts: None,
+        template: None,
+        branding: None,
+        toc: false,
+        min_severity: None,
+        fail_on: None,
+        min_completeness: None,
+        continue_on_error: false,
+        skip_invalid: false,
+        jobs: 1,
+        max_comment_size: 65536,
+        budget: None,
+        resume: false,
+        since: None,
+        output_dir: None,
+        no_cache: false,
+        owners: None,
+        provider: None,
+        model: None,
+        temperature: None,
+        max_tokens: None,
+        compare_providers: None,
+    };
+    let error = app.run_command(command).await.unwrap_err();
+    assert!(error.to_string().contains("Unknown preset 'missing'"));
+}
+
 #[tokio::test]
 async fn test_custom_generate_options() {
     let mut app = App::new().await.unwrap();
@@ -412,12 +1004,29 @@ async fn test_custom_generate_options() {
         generate: vec![GenerateOptions::Uml, GenerateOptions::Tests, GenerateOptions::Improve],
         format: Some(OutputFormat::Markdown),
         pseudo_lang: None,
+        arch: None,
         save_artifacts: None,
         template: None,
         branding: None,
+        toc: false,
+        min_severity: None,
+        fail_on: None,
+        min_completeness: None,
         continue_on_error: false,
         skip_invalid: false,
-        parallel: 1,
+        jobs: 1,
+        max_comment_size: 65536,
+        budget: None,
+        resume: false,
+        since: None,
+        output_dir: None,
+        no_cache: false,
+        owners: None,
+        provider: None,
+        model: None,
+        temperature: None,
+        max_tokens: None,
+        compare_providers: None,
     };
     
     let result = app.run_command(command).await;
@@ -0,0 +1,53 @@
+use prism::json_repair::{extract_json, parse_llm_json, repair_json, JsonRepair};
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Gaps {
+    gaps: Vec<Gap>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Gap {
+    category: String,
+    priority: String,
+}
+
+fn gap(category: &str, priority: &str) -> Gap {
+    Gap { category: category.to_string(), priority: priority.to_string() }
+}
+
+#[test]
+fn test_common_json_mistakes_are_repaired() {
+    assert_eq!(extract_json("Here you go:\n```json\n{\"a\": [1, 2]}\n```\nAnything else?"), "{\"a\": [1, 2]}");
+    assert_eq!(extract_json("The result is {\"a\": \"}\"} as asked."), "{\"a\": \"}\"}");
+
+    assert_eq!(repair_json("{\"a\": [1, 2,], }"), Some(json!({"a": [1, 2]})));
+    assert_eq!(repair_json("{'text': 'the user's data', ok: True, none: None}"), Some(json!({"text": "the user's data", "ok": true, "none": null})));
+    assert_eq!(repair_json("{\"reason\": \"says \"fast\" only\nonce\"}"), Some(json!({"reason": "says \"fast\" only\nonce"})));
+    assert_eq!(repair_json("[{\"a\": 1}\n{\"a\": 2}]"), Some(json!([{"a": 1}, {"a": 2}])));
+
+    let (parsed, repair) = parse_llm_json::<Gaps>("```json\n{\"gaps\": [{\"category\": \"Error\", \"priority\": \"High\"},]}\n```", "gaps").unwrap();
+    assert_eq!((parsed.gaps, repair), (vec![gap("Error", "High")], JsonRepair::Repaired));
+    // A bare array of the items
+    let (parsed, _) = parse_llm_json::<Gaps>("[{'category': 'Data', 'priority': 'Low'}]", "gaps").unwrap();
+    assert_eq!(parsed.gaps, vec![gap("Data", "Low")]);
+    let (_, repair) = parse_llm_json::<Gaps>("{\"gaps\": []}", "gaps").unwrap();
+    assert_eq!(repair, JsonRepair::Clean);
+}
+
+#[test]
+fn test_valid_items_are_salvaged_from_truncated_or_partly_invalid_responses() {
+    // Cut off in the middle of the third item
+    let truncated = "{\"gaps\": [{\"category\": \"Error\", \"priority\": \"High\"}, {\"category\": \"Security\", \"priority\": \"Critical\"}, {\"category\": \"Perf";
+    let (parsed, repair) = parse_llm_json::<Gaps>(truncated, "gaps").unwrap();
+    assert_eq!(parsed.gaps, vec![gap("Error", "High"), gap("Security", "Critical")]);
+    assert_eq!(repair, JsonRepair::Salvaged);
+
+    let (parsed, repair) = parse_llm_json::<Gaps>("{\"gaps\": [{\"category\": \"Error\"}, {\"category\": \"Data\", \"priority\": \"Low\"}]}", "gaps").unwrap();
+    assert_eq!((parsed.gaps, repair), (vec![gap("Data", "Low")], JsonRepair::Salvaged));
+
+    // Nothing worth keeping fails with the error of the response as it was
+    let error = parse_llm_json::<Gaps>("I could not find any gaps.", "gaps").unwrap_err();
+    assert!(error.to_string().contains("expected value"), "{}", error);
+}
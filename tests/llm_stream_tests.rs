@@ -0,0 +1,86 @@
+use prism::analyzer::Analyzer;
+use prism::config::Config;
+use prism::llm_stream::{stream_delta, LineBuffer, StreamEvent, StreamFormat};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+#[test]
+fn test_stream_lines_decoded_per_provider() {
+    let mut buffer = LineBuffer::default();
+    let bytes = "data: {\"choices\":[{\"delta\":{\"content\":\"Déjà\"}}]}\n\ndata: [DONE]".as_bytes();
+    // Split inside the two-byte "é"
+    let split = bytes.iter().position(|b| *b == 0xC3).unwrap() + 1;
+    assert!(buffer.push(&bytes[..split]).is_empty());
+    let lines = buffer.push(&bytes[split..]);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(stream_delta(StreamFormat::OpenAi, &lines[0]).unwrap().as_deref(), Some("Déjà"));
+    assert_eq!(stream_delta(StreamFormat::OpenAi, &buffer.finish().unwrap()).unwrap(), None);
+
+    assert_eq!(stream_delta(StreamFormat::Claude, "event: content_block_delta").unwrap(), None);
+    let claude = r#"data: {"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"{\"ambig"}}"#;
+    assert_eq!(stream_delta(StreamFormat::Claude, claude).unwrap().as_deref(), Some("{\"ambig"));
+    assert_eq!(stream_delta(StreamFormat::Claude, r#"data: {"type":"message_stop"}"#).unwrap(), None);
+    let overloaded = r#"data: {"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#;
+    assert!(stream_delta(StreamFormat::Claude, overloaded).unwrap_err().to_string().contains("Overloaded"));
+
+    assert_eq!(stream_delta(StreamFormat::Ollama, r#"{"response":"Hi","done":false}"#).unwrap().as_deref(), Some("Hi"));
+    assert_eq!(stream_delta(StreamFormat::Ollama, r#"{"response":"","done":true}"#).unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_openai_response_streamed_to_handler() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let request_body = Arc::new(Mutex::new(String::new()));
+    let seen_request = request_body.clone();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        // Read the headers and the whole body before answering
+        let mut request = Vec::new();
+        let mut chunk = [0u8; 4096];
+        loop {
+            let read = socket.read(&mut chunk).await.unwrap();
+            request.extend_from_slice(&chunk[..read]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            if let Some(header_end) = text.find("\r\n\r\n") {
+                let length = text.lines()
+                    .find_map(|l| l.to_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap()))
+                    .unwrap_or(0);
+                if request.len() >= header_end + 4 + length || read == 0 {
+                    break;
+                }
+            }
+        }
+        *seen_request.lock().unwrap() = String::from_utf8_lossy(&request).to_string();
+
+        let events = ["Improved: ", "respond within ", "2 seconds."]
+            .iter()
+            .map(|t| format!("data: {{\"choices\":[{{\"delta\":{{\"content\":\"{}\"}}}}]}}\n\n", t))
+            .collect::<String>() + "data: [DONE]\n\n";
+        let response = format!("HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ncontent-length: {}\r\n\r\n{}", events.len(), events);
+        socket.write_all(response.as_bytes()).await.unwrap();
+    });
+
+    let mut config = Config::default();
+    config.llm.provider = "openai".to_string();
+    config.llm.api_key = Some("test-key".to_string());
+    config.llm.model = "gpt-4".to_string();
+    config.llm.base_url = Some(format!("http://{}/v1/chat/completions", address));
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    let analyzer = Analyzer::new().unwrap().with_config(config).with_stream_handler(Arc::new(move |event| {
+        recorded.lock().unwrap().push(match event {
+            StreamEvent::Started => "<start>".to_string(),
+            StreamEvent::Delta(text) => text.to_string(),
+            StreamEvent::Finished => "<end>".to_string(),
+        });
+    }));
+
+    let response = analyzer.call_llm("Improve: the system should be fast").await.unwrap();
+    assert_eq!(response, "Improved: respond within 2 seconds.");
+    assert_eq!(*events.lock().unwrap(), ["<start>", "Improved: ", "respond within ", "2 seconds.", "<end>"]);
+    assert!(request_body.lock().unwrap().contains("\"stream\":true"));
+    assert_eq!(analyzer.llm_usage().calls, 1);
+}
@@ -0,0 +1,55 @@
+use prism::analyzer::{NfrCategory, NfrPriority, NonFunctionalRequirement};
+use prism::load_tests::{find_thresholds, format_gatling_simulation, format_k6_script, generate_load_scenarios, simulation_class_name, LoadThreshold};
+
+const REQUIREMENTS: &str = "# Orders API\n\
+    GET /orders/{id} must respond within 200 ms for 99% of requests.\n\
+    The checkout shall support 500 concurrent users with an error rate below 1%.\n\
+    The API must handle 1,200 orders per minute.\n\
+    The user can search products.\n";
+
+#[test]
+fn test_thresholds_from_performance_statements() {
+    assert_eq!(find_thresholds("Pages load in under 2 seconds"), vec![LoadThreshold::ResponseTime { percentile: 95.0, max_ms: 2000.0 }]);
+    assert_eq!(find_thresholds("p99 latency below 350ms"), vec![LoadThreshold::ResponseTime { percentile: 99.0, max_ms: 350.0 }]);
+    assert_eq!(find_thresholds("The API must handle 1,200 orders per minute"), vec![LoadThreshold::Throughput { per_second: 20.0 }]);
+    assert_eq!(find_thresholds("Support 500 concurrent users with an error rate below 0.5%"), vec![
+        LoadThreshold::ConcurrentUsers { users: 500 },
+        LoadThreshold::ErrorRate { max_percent: 0.5 },
+    ]);
+    assert!(find_thresholds("The user can search products").is_empty());
+
+    let nfr = NonFunctionalRequirement {
+        category: NfrCategory::Performance,
+        requirement: "Search results shall be returned quickly".to_string(),
+        rationale: String::new(),
+        acceptance_criteria: vec!["95% of queries answered within 1 second".to_string()],
+        priority: NfrPriority::MustHave,
+    };
+    let scenarios = generate_load_scenarios(REQUIREMENTS, &[nfr]);
+    let summary: Vec<(Option<usize>, &str, Option<&str>)> = scenarios.iter().map(|s| (s.line, s.method.as_str(), s.path.as_deref())).collect();
+    assert_eq!(summary, vec![(Some(2), "GET", Some("/orders/{id}")), (Some(3), "GET", None), (Some(4), "GET", None), (None, "GET", None)]);
+    assert_eq!(scenarios[1].users(), 500);
+    assert_eq!(scenarios[3].thresholds, vec![LoadThreshold::ResponseTime { percentile: 95.0, max_ms: 1000.0 }]);
+}
+
+#[test]
+fn test_k6_script_and_gatling_simulation() {
+    let scenarios = generate_load_scenarios(REQUIREMENTS, &[]);
+
+    let k6 = format_k6_script(&scenarios);
+    assert!(k6.contains("    scenario_2: { executor: 'constant-vus', exec: 'scenario_2', vus: 500, duration: '60s' },\n"));
+    assert!(k6.contains("    scenario_3: { executor: 'constant-arrival-rate', exec: 'scenario_3', rate: 20, timeUnit: '1s', duration: '60s', preAllocatedVUs: 20 },\n"));
+    assert!(k6.contains("    'http_req_duration{scenario:scenario_1}': ['p(99)<200'],\n"));
+    assert!(k6.contains("    'http_req_failed{scenario:scenario_2}': ['rate<0.01'],\n"));
+    assert!(k6.contains("  const res = http.get(`${BASE_URL}/orders/1`);\n"));
+    assert!(k6.contains("// Line 3: The checkout shall support 500 concurrent users with an error rate below 1%.\n// TODO: replace /todo"));
+
+    assert_eq!(simulation_class_name("orders-api"), "OrdersApiSimulation");
+    assert_eq!(simulation_class_name("2024 specs"), "Requirements2024SpecsSimulation");
+    let gatling = format_gatling_simulation(&scenarios, "OrdersApiSimulation");
+    assert!(gatling.contains("public class OrdersApiSimulation extends Simulation {"));
+    assert!(gatling.contains("scenario2.injectClosed(constantConcurrentUsers(500).during(60))"));
+    assert!(gatling.contains("scenario3.injectOpen(constantUsersPerSec(20).during(60))"));
+    assert!(gatling.contains("details(\"GET /orders/{id}\").responseTime().percentile(99.0).lt(200)"));
+    assert!(gatling.contains("details(\"scenario_2\").failedRequests().percent().lt(1.0)"));
+}
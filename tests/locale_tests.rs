@@ -0,0 +1,38 @@
+use chrono::{TimeZone, Utc};
+use prism::analyzer::Analyzer;
+use prism::config::OutputConfig;
+use prism::formatters::format_as_markdown;
+use prism::locale::{set_report_locale, ReportLocale};
+
+#[test]
+fn test_locales_write_numbers_percentages_and_dates_their_way() {
+    let at = Utc.with_ymd_and_hms(2026, 3, 9, 14, 5, 0).unwrap();
+    let german = ReportLocale::from_tag("de_DE.UTF-8").unwrap();
+    assert_eq!(german.number(12345.678, 2), "12.345,68");
+    assert_eq!(german.percent(45.0f32, 1), "45,0\u{a0}%");
+    assert_eq!(german.date_time(&at), "09.03.2026 14:05");
+
+    let american = ReportLocale::from_tag("en-US").unwrap();
+    assert_eq!(american.number(-1234.5, 1), "-1,234.5");
+    assert_eq!(american.percent(45.0, 0), "45%");
+    assert_eq!(american.date(&at), "03/09/2026");
+    // Another region of a known language, and reports without a locale
+    assert_eq!(ReportLocale::from_tag("fr-LU").unwrap().number(2.5, 1), "2,5");
+    assert_eq!(ReportLocale::default().number(12345.678, 2), "12345.68");
+    assert!(ReportLocale::from_tag("xx-YY").unwrap_err().to_string().contains("Unknown locale 'xx-YY'"));
+
+    let output = OutputConfig { locale: Some("en-GB".to_string()), date_format: Some("%d %B %Y".to_string()), ..OutputConfig::default() };
+    assert_eq!(ReportLocale::from_config(&output).unwrap().date(&at), "09 March 2026");
+}
+
+#[tokio::test]
+async fn test_reports_use_the_report_locale() {
+    let text = "The system should be fast and the page should load quickly for users.";
+    let result = Analyzer::new().unwrap().analyze(text).await.unwrap();
+    set_report_locale(ReportLocale::from_tag("de-DE").unwrap());
+    let report = format_as_markdown(&result, text);
+    set_report_locale(ReportLocale::default());
+
+    let density = format!("{:.1}", result.metrics.findings_per_100_words).replace('.', ",");
+    assert!(report.contains(&format!("**Ambiguity Density:** {} per 100 words", density)), "{}", report);
+}
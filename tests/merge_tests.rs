@@ -0,0 +1,31 @@
+use prism::analyzer::Analyzer;
+use prism::merge::merge_results;
+
+#[tokio::test]
+async fn test_merge_results_of_two_shards() {
+    let analyzer = Analyzer::new().unwrap();
+    let auth = analyzer.analyze("The user shall log in fast.\nThe admin shall reset passwords.").await.unwrap();
+    let reports = analyzer.analyze("The customer shall export a user-friendly report.").await.unwrap();
+    let shards = [auth.clone(), reports.clone()];
+    // Results come from JSON files written by other jobs
+    let shards = shards.iter()
+        .map(|result| serde_json::from_str(&serde_json::to_string(result).unwrap()).unwrap())
+        .collect();
+
+    let merged = merge_results(shards).unwrap();
+    assert_eq!(merged.ambiguities.len(), auth.ambiguities.len() + reports.ambiguities.len());
+    assert!(merged.ambiguities.iter().any(|f| f.text.eq_ignore_ascii_case("fast")));
+    assert!(merged.ambiguities.iter().any(|f| f.text.eq_ignore_ascii_case("user-friendly")));
+    assert_eq!(merged.metrics.word_count, auth.metrics.word_count + reports.metrics.word_count);
+    assert_eq!(merged.metrics.requirement_count, 3);
+    let density = merged.ambiguities.len() as f32 / 3.0;
+    assert!((merged.metrics.findings_per_requirement - density).abs() < 0.001);
+    for actor in ["user", "admin", "customer"] {
+        assert_eq!(merged.entities.actors.iter().filter(|a| a.eq_ignore_ascii_case(actor)).count(), 1, "{:?}", merged.entities.actors);
+    }
+}
+
+#[test]
+fn test_merge_needs_a_result() {
+    assert!(merge_results(Vec::new()).is_err());
+}
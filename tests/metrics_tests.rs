@@ -0,0 +1,73 @@
+use prism::config::Config;
+use prism::metrics::Metrics;
+use prism::server::{serve, ServerState};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+#[test]
+fn test_metrics_are_rendered_in_prometheus_format() {
+    let metrics = Metrics::new();
+    metrics.record_llm_request("openai", true, Duration::from_millis(300), 4000, 400);
+    metrics.record_llm_request("openai", false, Duration::from_secs(3), 4000, 0);
+    metrics.record_analysis("api", true, Duration::from_secs(4));
+    metrics.record_daemon_run("checkout \"main\"", Some(82.5));
+
+    let output = metrics.render();
+    assert!(output.contains("# TYPE prism_llm_request_duration_seconds histogram"));
+    assert!(output.contains("prism_llm_requests_total{provider=\"openai\",outcome=\"failure\"} 1"));
+    assert!(output.contains("prism_llm_request_duration_seconds_bucket{provider=\"openai\",le=\"0.25\"} 0"));
+    assert!(output.contains("prism_llm_request_duration_seconds_bucket{provider=\"openai\",le=\"0.5\"} 1"));
+    assert!(output.contains("prism_llm_request_duration_seconds_bucket{provider=\"openai\",le=\"5\"} 2"));
+    assert!(output.contains("prism_llm_request_duration_seconds_bucket{provider=\"openai\",le=\"+Inf\"} 2"));
+    assert!(output.contains("prism_llm_request_duration_seconds_sum{provider=\"openai\"} 3.3"));
+    assert!(output.contains("prism_llm_tokens_total{provider=\"openai\",direction=\"prompt\"} 2000"));
+    assert!(output.contains("prism_analyses_total{source=\"api\",outcome=\"success\"} 1"));
+    assert!(output.contains("prism_daemon_quality_score{job=\"checkout \\\"main\\\"\"} 82.5"));
+    assert!(!output.contains("prism_http_requests_total"));
+}
+
+/// Answers every request with an empty chat completion.
+async fn fake_openai() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0u8; 16384];
+            let _ = socket.read(&mut request).await;
+            let body = r#"{"choices":[{"message":{"content":"{\"ambiguities\":[]}"}}]}"#;
+            let response = format!("HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}", body.len(), body);
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+    format!("http://{}/v1/chat/completions", address)
+}
+
+#[tokio::test]
+async fn test_server_exposes_analysis_and_llm_metrics() {
+    let mut config = Config::default();
+    config.llm.provider = "openai".to_string();
+    config.llm.api_key = Some("test-key".to_string());
+    config.llm.model = "gpt-4o-mini".to_string();
+    config.llm.base_url = Some(fake_openai().await);
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(serve(listener, Arc::new(ServerState::new(config).unwrap())));
+
+    let client = reqwest::Client::new();
+    let analyzed = client.post(format!("{}/analyze", base)).body("The page should load fast.").send().await.unwrap();
+    assert_eq!(analyzed.status(), 200);
+    client.get(format!("{}/wp-login.php", base)).send().await.unwrap();
+
+    let response = client.get(format!("{}/metrics", base)).send().await.unwrap();
+    assert!(response.headers()["content-type"].to_str().unwrap().starts_with("text/plain"));
+    let output = response.text().await.unwrap();
+    assert!(output.contains("prism_analyses_total{source=\"api\",outcome=\"success\"} 1"));
+    assert!(output.contains("prism_llm_requests_total{provider=\"openai\",outcome=\"success\"} 2"));
+    assert!(output.contains("prism_llm_request_duration_seconds_count{provider=\"openai\"} 2"));
+    assert!(output.contains("prism_http_requests_total{path=\"/analyze\",status=\"200\"} 1"));
+    assert!(output.contains("prism_http_requests_total{path=\"other\",status=\"404\"} 1"));
+}
@@ -0,0 +1,52 @@
+use prism::analyzer::{Analyzer, RULE_AI_AMBIGUITY};
+use prism::config::Config;
+use prism::mock_provider::MockResponses;
+use prism::prompts::{PromptTask, PromptTemplates};
+
+fn mock_config(responses: Option<&str>) -> Config {
+    let mut config = Config::default();
+    config.llm.api_key = None;
+    config.set_provider("mock");
+    if let Some(yaml) = responses {
+        let path = std::env::temp_dir().join(format!("prism-mock-{}.yaml", std::process::id()));
+        std::fs::write(&path, yaml).unwrap();
+        config.llm.mock_responses = Some(path);
+    }
+    config
+}
+
+#[tokio::test]
+async fn test_mock_provider_answers_each_task_from_the_responses_file() {
+    let config = mock_config(Some(r#"
+tasks:
+  ambiguities: |
+    {"ambiguities": [{"text": "log in", "reason": "Which credentials?", "suggestions": ["Name the login methods"], "severity": "medium"}]}
+  entities: '{"actors": ["member"], "actions": ["sign in"], "objects": ["account"]}'
+matches:
+  - contains: "What does"
+    response: A canned chat answer.
+"#));
+    assert!(config.is_ai_configured());
+    let analyzer = Analyzer::new().unwrap().with_config(config);
+
+    let result = analyzer.analyze("The user shall log in.").await.unwrap();
+    assert!(!result.ai_failed);
+    assert!(result.ambiguities.iter().any(|f| f.rule_id == RULE_AI_AMBIGUITY && f.reason == "Which credentials?"));
+    assert!(result.entities.actors.iter().any(|a| a == "member"), "{:?}", result.entities.actors);
+    assert_eq!(analyzer.call_llm("What does the spec say?").await.unwrap(), "A canned chat answer.");
+    assert_eq!(analyzer.llm_usage().calls, 3);
+}
+
+#[tokio::test]
+async fn test_mock_provider_without_responses_file_adds_nothing() {
+    let builtin = Analyzer::new().unwrap().analyze("The system should be fast.").await.unwrap();
+    let mocked = Analyzer::new().unwrap().with_config(mock_config(None)).analyze("The system should be fast.").await.unwrap();
+    assert!(!mocked.ai_failed);
+    assert_eq!(mocked.ambiguities.len(), builtin.ambiguities.len());
+
+    let prompts = PromptTemplates::default();
+    let prompt = prompts.render(PromptTask::Nfrs, &[("text", "The system should be fast."), ("entities", ""), ("actors", ""), ("actions", ""), ("objects", "")]);
+    assert_eq!(prompts.task_of(&prompt), Some(PromptTask::Nfrs));
+    assert_eq!(prompts.task_of("Hello"), None);
+    assert_eq!(MockResponses::default().respond(Some(PromptTask::Nfrs), &prompt), r#"{"nfrs": []}"#);
+}
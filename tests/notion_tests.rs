@@ -0,0 +1,55 @@
+use prism::analyzer::Analyzer;
+use prism::notion::{blocks_to_markdown, format_notion_summary, notion_id, summary_update, tag_filter, NotionPage};
+use serde_json::json;
+
+fn text(content: &str) -> serde_json::Value {
+    json!({ "rich_text": [{ "plain_text": content }] })
+}
+
+#[test]
+fn test_notion_ids_and_filters() {
+    let id = "3f2a9c1e-7b4d-4e2a-9c1b-5d6e7f8a9b0c";
+    assert_eq!(notion_id("3f2a9c1e7b4d4e2a9c1b5d6e7f8a9b0c").as_deref(), Some(id));
+    assert_eq!(notion_id("https://www.notion.so/acme/Checkout-Flow-3f2a9c1e7b4d4e2a9c1b5d6e7f8a9b0c?v=123").as_deref(), Some(id));
+    assert_eq!(notion_id("not-a-page"), None);
+
+    assert_eq!(tag_filter("Tags", "multi_select", "Requirement").unwrap(), json!({ "property": "Tags", "multi_select": { "contains": "Requirement" } }));
+    assert_eq!(tag_filter("Type", "select", "Epic").unwrap(), json!({ "property": "Type", "select": { "equals": "Epic" } }));
+    assert!(tag_filter("Due", "date", "Epic").is_err());
+
+    let page = NotionPage::from_json(&json!({
+        "id": id,
+        "url": "https://www.notion.so/Checkout-Flow",
+        "properties": { "Tags": { "type": "multi_select" }, "Name": { "type": "title", "title": [{ "plain_text": "Checkout: Flow" }] } }
+    }));
+    assert_eq!(page.title, "Checkout: Flow");
+    assert_eq!(page.file_name("md"), "checkout-flow.md");
+}
+
+#[test]
+fn test_blocks_convert_to_markdown() {
+    let blocks = vec![
+        json!({ "type": "heading_2", "heading_2": text("Acceptance Criteria") }),
+        json!({ "type": "paragraph", "paragraph": text("The cart should load fast.") }),
+        json!({ "type": "bulleted_list_item", "bulleted_list_item": text("Guest checkout"),
+            "children": [{ "type": "to_do", "to_do": { "rich_text": [{ "plain_text": "Email receipt" }], "checked": true } }] }),
+        json!({ "type": "image", "image": {} }),
+    ];
+
+    assert_eq!(
+        blocks_to_markdown(&blocks),
+        "## Acceptance Criteria\n\nThe cart should load fast.\n\n- Guest checkout\n  - [x] Email receipt\n"
+    );
+}
+
+#[tokio::test]
+async fn test_summary_written_back_to_notion() {
+    let result = Analyzer::new().unwrap().analyze("The cart should load fast and be user-friendly.").await.unwrap();
+    let summary = format_notion_summary(&result);
+    assert!(summary.starts_with(&format!("PRISM: {} finding", result.ambiguities.len())));
+    assert!(summary.ends_with("per 100 words"));
+
+    let update = summary_update("PRISM Summary", &"x".repeat(3000));
+    let content = update["properties"]["PRISM Summary"]["rich_text"][0]["text"]["content"].as_str().unwrap();
+    assert_eq!(content.len(), 2000);
+}
@@ -0,0 +1,68 @@
+use prism::analyzer::Analyzer;
+use prism::config::{Config, KeepAlive};
+use prism::ollama::{chat_url, is_installed, keep_alive_value, pull_model, server_url};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Answers every request with `body`, keeping the last request it received.
+async fn ollama(body: &'static str, received: Arc<Mutex<String>>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0u8; 16384];
+            let read = socket.read(&mut request).await.unwrap_or(0);
+            *received.lock().unwrap() = String::from_utf8_lossy(&request[..read]).to_string();
+            let response = format!("HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}", body.len(), body);
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+    format!("http://{}", address)
+}
+
+#[tokio::test]
+async fn test_ollama_uses_chat_api_with_keep_alive_and_context() {
+    let received = Arc::new(Mutex::new(String::new()));
+    let server = ollama(r#"{"model":"llama3.1","message":{"role":"assistant","content":"chat answer"},"done":true}"#, received.clone()).await;
+    let mut config = Config::default();
+    config.set_provider("ollama");
+    config.llm.api_key = Some("ollama-local".to_string());
+    config.llm.model = "llama3.1".to_string();
+    // Configurations of older versions name the generate endpoint
+    config.llm.base_url = Some(format!("{}/api/generate", server));
+    config.llm.ollama.keep_alive = Some(KeepAlive::Duration("30m".to_string()));
+    config.llm.ollama.num_ctx = Some(8192);
+
+    let analyzer = Analyzer::new().unwrap().with_config(config);
+    assert_eq!(analyzer.call_llm("Improve: the system should be fast").await.unwrap(), "chat answer");
+    let request = received.lock().unwrap().clone();
+    assert!(request.starts_with("POST /api/chat "), "{}", request);
+    let body: serde_json::Value = serde_json::from_str(&request[request.find("\r\n\r\n").unwrap() + 4..]).unwrap();
+    assert_eq!(body["messages"][0]["role"], "system");
+    assert_eq!(body["messages"][1]["content"], "Improve: the system should be fast");
+    assert_eq!(body["keep_alive"], "30m");
+    assert_eq!(body["options"]["num_ctx"], 8192);
+}
+
+#[tokio::test]
+async fn test_ollama_urls_models_and_pull_progress() {
+    assert_eq!(server_url(None), "http://localhost:11434");
+    assert_eq!(server_url(Some("http://gpu-box:11434/api/generate")), "http://gpu-box:11434");
+    assert_eq!(chat_url(Some("http://gpu-box:11434/")), "http://gpu-box:11434/api/chat");
+    let installed = vec!["llama3.1:latest".to_string(), "mistral:7b".to_string()];
+    assert!(is_installed(&installed, "llama3.1"));
+    assert!(is_installed(&installed, "mistral:7b"));
+    assert!(!is_installed(&installed, "mistral"));
+    assert_eq!(keep_alive_value(&KeepAlive::Duration("300".to_string())), serde_json::json!(300));
+    assert_eq!(keep_alive_value(&KeepAlive::Seconds(-1)), serde_json::json!(-1));
+
+    let server = ollama(
+        "{\"status\":\"pulling manifest\"}\n{\"status\":\"downloading\",\"completed\":50,\"total\":200}\n{\"status\":\"success\"}\n",
+        Arc::new(Mutex::new(String::new())),
+    ).await;
+    let mut statuses = Vec::new();
+    pull_model(&reqwest::Client::new(), &server, "llama3.1", |status| statuses.push(status.to_string())).await.unwrap();
+    assert_eq!(statuses, ["pulling manifest", "downloading 25%", "success"]);
+}
@@ -0,0 +1,64 @@
+use prism::analyzer::{Ambiguity, AmbiguitySeverity};
+use prism::owners::{format_ownership_markdown, CodeOwners, OwnershipReport, UNOWNED};
+use std::path::{Path, PathBuf};
+
+const CODEOWNERS: &str = "\
+# Docs team reviews every Markdown file
+*.md @org/docs
+/requirements/payments/ @org/payments
+requirements/auth/**/*.md @org/identity @org/security  # shared
+[Legal]
+contracts/ legal@example.com
+requirements/drafts/
+";
+
+#[test]
+fn test_last_matching_codeowners_rule_wins() {
+    let owners = CodeOwners::parse(CODEOWNERS, PathBuf::from("/repo")).unwrap();
+    let of = |path: &str| owners.owners_of(Path::new(path));
+
+    assert_eq!(of("README.md"), vec!["@org/docs"]);
+    assert_eq!(of("requirements/payments/refunds/partial.md"), vec!["@org/payments"]);
+    assert_eq!(of("requirements/payments.md"), vec!["@org/docs"]);
+    assert_eq!(of("requirements/auth/login/sso.md"), vec!["@org/identity", "@org/security"]);
+    assert_eq!(of("requirements/auth/login/sso.txt"), Vec::<String>::new());
+    assert_eq!(of("services/contracts/sla.txt"), vec!["legal@example.com"]);
+    // An owner-less rule takes ownership away again
+    assert!(of("requirements/drafts/idea.md").is_empty());
+    assert_eq!(of("/repo/requirements/payments/checkout.md"), vec!["@org/payments"]);
+}
+
+fn finding(rule: &str, severity: AmbiguitySeverity) -> Ambiguity {
+    Ambiguity {
+        text: "fast".to_string(),
+        reason: "vague".to_string(),
+        suggestions: vec![],
+        severity,
+        rule_id: rule.to_string(),
+        fingerprint: String::new(),
+        location: None,
+    }
+}
+
+#[test]
+fn test_findings_are_attributed_to_every_owner() {
+    let mut report = OwnershipReport::default();
+    report.add(&["@org/payments".to_string()], "payments/checkout.md", &[
+        finding("vague-term", AmbiguitySeverity::Medium),
+        finding("vague-term", AmbiguitySeverity::High),
+        finding("passive-voice", AmbiguitySeverity::Low),
+    ]);
+    report.add(&["@org/identity".to_string(), "@org/payments".to_string()], "auth/login.md", &[finding("passive-voice", AmbiguitySeverity::Critical)]);
+    report.add(&[], "misc/notes.md", &[]);
+
+    let owners = report.owners();
+    assert_eq!(owners.iter().map(|o| o.owner.as_str()).collect::<Vec<_>>(), vec!["@org/payments", "@org/identity", UNOWNED]);
+    assert_eq!((owners[0].files, owners[0].findings, owners[0].high_severity), (2, 4, 2));
+    assert_eq!(owners[0].top_rules(1), vec![("passive-voice", 2)]);
+
+    let markdown = format_ownership_markdown(Path::new("requirements"), &report);
+    assert!(markdown.contains("| @org/payments | 2 | 4 | 2 | passive-voice (2), vague-term (2) |"));
+    assert!(markdown.contains("| (unowned) | 1 | 0 | 0 | — |"));
+    assert!(markdown.contains("## @org/identity\n"));
+    assert!(!markdown.contains("## (unowned)"));
+}
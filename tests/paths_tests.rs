@@ -0,0 +1,24 @@
+use prism::paths::{expand_home, relative_to, simplify};
+use std::path::{Path, PathBuf};
+
+#[test]
+fn test_verbatim_prefixes_are_stripped_from_windows_paths() {
+    assert_eq!(simplify(Path::new(r"\\?\C:\Users\dana\specs\login.md")), PathBuf::from(r"C:\Users\dana\specs\login.md"));
+    assert_eq!(simplify(Path::new(r"\\?\UNC\fileserver\specs\login.md")), PathBuf::from(r"\\fileserver\specs\login.md"));
+    // Verbatim paths that are not drive paths need their prefix
+    assert_eq!(simplify(Path::new(r"\\?\Volume{1234}\login.md")), PathBuf::from(r"\\?\Volume{1234}\login.md"));
+    assert_eq!(simplify(Path::new("/home/dana/specs/login.md")), PathBuf::from("/home/dana/specs/login.md"));
+
+    let home = dirs::home_dir().unwrap();
+    assert_eq!(expand_home(Path::new("~/specs/owners.yml")), home.join("specs/owners.yml"));
+    assert_eq!(expand_home(Path::new("~")), home);
+    assert_eq!(expand_home(Path::new("~dana/owners.yml")), PathBuf::from("~dana/owners.yml"));
+}
+
+#[test]
+fn test_relative_paths_use_forward_slashes_and_step_up() {
+    assert_eq!(relative_to(Path::new("/work/repo/docs/specs/login.md"), Path::new("/work/repo")), "docs/specs/login.md");
+    assert_eq!(relative_to(Path::new("/work/repo/./docs/../specs/login.md"), Path::new("/work/repo/")), "specs/login.md");
+    assert_eq!(relative_to(Path::new("/work/shared/login.md"), Path::new("/work/repo/docs")), "../../shared/login.md");
+    assert_eq!(relative_to(Path::new("/work/repo"), Path::new("/work/repo")), ".");
+}
@@ -0,0 +1,42 @@
+use prism::pdf_report::markdown_to_pdf;
+use prism::{format_report, Analyzer, OutputFormat};
+
+const SPEC: &str = "# Login\nThe system should be fast.\nReports should be generated.\n";
+
+/// The byte offset of every object, from the cross-reference table.
+fn xref_offsets(pdf: &str) -> Vec<usize> {
+    let start: usize = pdf.rsplit("startxref\n").next().unwrap().lines().next().unwrap().parse().unwrap();
+    assert!(pdf[start..].starts_with("xref\n"));
+    pdf[start..].lines().skip(3).take_while(|line| line.ends_with(" n ")).map(|line| line[..10].parse().unwrap()).collect()
+}
+
+#[tokio::test]
+async fn test_pdf_report_is_a_valid_document_with_the_findings() {
+    let mut result = Analyzer::new().unwrap().analyze(SPEC).await.unwrap();
+    result.set_source_file("login.md");
+    let pdf = format_report(&result, SPEC, &OutputFormat::Pdf, Some("login.md")).unwrap();
+    assert!(pdf.starts_with("%PDF-1.4\n"));
+    assert!(pdf.ends_with("%%EOF\n"));
+    assert!(pdf.is_ascii());
+    for (i, offset) in xref_offsets(&pdf).into_iter().enumerate() {
+        assert!(pdf[offset..].starts_with(&format!("{} 0 obj\n", i + 1)), "object {} is not at {}", i + 1, offset);
+    }
+    assert!(pdf.contains("/Title (PRISM Requirement Analysis - login.md)"));
+
+    let text = pdf_extract::extract_text_from_mem(pdf.as_bytes()).unwrap();
+    assert!(text.contains("Detected Ambiguities"), "{}", text);
+    assert!(text.contains("Location: login.md:2:22"), "{}", text);
+    assert!(text.contains("Page 1 of 1"), "{}", text);
+}
+
+#[test]
+fn test_long_reports_break_into_pages_and_keep_latin_characters() {
+    let items: String = (1..=120).map(|i| format!("- Requirement {} – the café “menu” shows 🍕 prices\n", i)).collect();
+    let pdf = markdown_to_pdf(&format!("# Menu\n\n{}", items), "Menu");
+    assert!(pdf.contains("/Count 3 >>"));
+    // Windows-1252 characters become octal escapes, emoji are left out
+    assert!(pdf.contains(r"(Requirement 7 \226 the caf\351 \223menu\224 shows prices)"), "{}", &pdf[..2000]);
+    assert!(pdf.contains("(Page 3 of 3)"));
+    let text = pdf_extract::extract_text_from_mem(pdf.as_bytes()).unwrap();
+    assert!(text.contains("Requirement 120"));
+}
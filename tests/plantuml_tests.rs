@@ -0,0 +1,74 @@
+use flate2::read::DeflateDecoder;
+use prism::config::PlantUmlConfig;
+use prism::plantuml::{encode_plantuml, ImageFormat, PlantUmlRenderer};
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+fn decode_plantuml(encoded: &str) -> String {
+    const ALPHABET: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz-_";
+    let sextets: Vec<u8> = encoded.chars().map(|c| ALPHABET.find(c).unwrap() as u8).collect();
+    let mut bytes = Vec::new();
+    for chunk in sextets.chunks(4) {
+        bytes.push((chunk[0] << 2) | (chunk[1] >> 4));
+        bytes.push((chunk[1] << 4) | (chunk[2] >> 2));
+        bytes.push((chunk[2] << 6) | chunk[3]);
+    }
+    let mut source = String::new();
+    // Padding bytes after the end of the deflate stream are ignored
+    DeflateDecoder::new(&bytes[..]).read_to_string(&mut source).unwrap();
+    source
+}
+
+#[test]
+fn test_diagrams_are_encoded_for_plantuml_servers() {
+    for source in ["@startuml\nBob -> Alice : hello\n@enduml", "@startuml\nactor \"Customer\" as C\n(Checkout) <-- C\n@enduml\n"] {
+        let encoded = encode_plantuml(source);
+        assert!(encoded.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(decode_plantuml(&encoded), source);
+    }
+
+    let mut config = PlantUmlConfig::default();
+    assert_eq!(PlantUmlRenderer::from_config(&config), None);
+    config.server_url = Some("https://plantuml.example.com/plantuml/".to_string());
+    assert_eq!(PlantUmlRenderer::from_config(&config), Some(PlantUmlRenderer::Server("https://plantuml.example.com/plantuml".to_string())));
+    config.jar = Some(PathBuf::from("/opt/plantuml.jar"));
+    assert_eq!(PlantUmlRenderer::from_config(&config), Some(PlantUmlRenderer::Jar(PathBuf::from("/opt/plantuml.jar"))));
+}
+
+/// Answers like a PlantUML server: an SVG, flagged as an error for diagrams containing "oops".
+async fn fake_plantuml(paths: Arc<Mutex<Vec<String>>>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0u8; 16384];
+            let n = socket.read(&mut request).await.unwrap();
+            let path = String::from_utf8_lossy(&request[..n]).split(' ').nth(1).unwrap_or_default().to_string();
+            let source = decode_plantuml(path.rsplit('/').next().unwrap());
+            paths.lock().unwrap().push(path);
+            let error = if source.contains("oops") { "x-plantuml-diagram-error: Syntax Error?\r\n" } else { "" };
+            let body = "<svg xmlns=\"http://www.w3.org/2000/svg\"/>";
+            let response = format!("HTTP/1.1 200 OK\r\ncontent-type: image/svg+xml\r\n{}content-length: {}\r\nconnection: close\r\n\r\n{}", error, body.len(), body);
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+    format!("http://{}/plantuml", address)
+}
+
+#[tokio::test]
+async fn test_diagrams_are_rendered_on_the_server() {
+    let paths = Arc::new(Mutex::new(Vec::new()));
+    let renderer = PlantUmlRenderer::Server(fake_plantuml(paths.clone()).await);
+
+    let image = renderer.render("@startuml\nBob -> Alice : hello\n@enduml", ImageFormat::Svg).await.unwrap();
+    assert!(String::from_utf8(image).unwrap().starts_with("<svg"));
+    assert!(paths.lock().unwrap()[0].starts_with("/plantuml/svg/"));
+
+    let error = renderer.render("@startuml\noops\n@enduml", ImageFormat::Png).await.unwrap_err();
+    assert!(error.to_string().contains("Syntax Error?"));
+    assert!(paths.lock().unwrap()[1].starts_with("/plantuml/png/"));
+}
@@ -0,0 +1,107 @@
+use prism::analyzer::Analyzer;
+use prism::config::{Config, ProjectsConfig};
+use prism::cli::OutputFormat;
+use prism::projects::{discover_projects, find_project_config, format_projects_summary, project_files, Discovery, IgnoreGlobs, ProjectSummary};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn write(repo: &Path, file: &str, content: &str) {
+    let path = repo.join(file);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn monorepo(name: &str) -> PathBuf {
+    let repo = std::env::temp_dir().join(format!("prism_projects_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&repo);
+    write(&repo, "services/billing/requirements/invoices.md", "The invoice page should be fast.");
+    write(&repo, "services/auth/docs/requirements/login.md", "As a user, I want to log in so that I see my data.");
+    write(&repo, "node_modules/widget/requirements/readme.md", "Not a project.");
+    write(&repo, "legacy/.prism.yml", "name: Legacy\ndisabled_rules: [vague-term]\n");
+    write(&repo, "legacy/report.txt", "The report page should be fast and user-friendly.");
+    write(&repo, "legacy/mobile/.prism.yml", "");
+    write(&repo, "legacy/mobile/requirements/offline.md", "The app must work offline.");
+    repo
+}
+
+#[test]
+fn test_projects_are_discovered_by_marker_and_convention() {
+    let repo = monorepo("discover");
+    let projects = discover_projects(&repo, &ProjectsConfig::default().conventions).unwrap();
+
+    let roots: Vec<String> = projects.iter().map(|p| p.root.display().to_string()).collect();
+    assert_eq!(roots, vec!["legacy", "legacy/mobile", "services/auth", "services/billing"]);
+    assert_eq!(projects[0].name, "Legacy");
+    assert_eq!(projects[0].discovered_by, Discovery::Marker);
+    assert_eq!(projects[0].requirement_dirs, vec![PathBuf::from("legacy")]);
+    assert_eq!(projects[1].requirement_dirs, vec![PathBuf::from("legacy/mobile/requirements")]);
+    assert_eq!(projects[2].discovered_by, Discovery::Convention);
+    assert_eq!(projects[2].requirement_dirs, vec![PathBuf::from("services/auth/docs/requirements")]);
+
+    // The nested project's files are its own, not its parent's
+    let files = |i| project_files(&repo, &projects, i, |_| true);
+    assert_eq!(files(0), vec![PathBuf::from("legacy/report.txt")]);
+    assert_eq!(files(1), vec![PathBuf::from("legacy/mobile/requirements/offline.md")]);
+    fs::remove_dir_all(&repo).unwrap();
+}
+
+#[tokio::test]
+async fn test_projects_are_analyzed_with_their_own_settings() {
+    let repo = monorepo("analyze");
+    let projects = discover_projects(&repo, &ProjectsConfig::default().conventions).unwrap();
+    let config = Config::default();
+
+    let mut summaries = Vec::new();
+    for (i, project) in projects.iter().enumerate() {
+        let analyzer = Analyzer::new().unwrap().with_config(project.effective_config(&config));
+        let mut results = Vec::new();
+        for file in project_files(&repo, &projects, i, |_| true) {
+            results.push(analyzer.analyze(&fs::read_to_string(repo.join(file)).unwrap()).await.unwrap());
+        }
+        summaries.push(ProjectSummary::new(project, results.len(), 0, &results));
+    }
+
+    // "fast" is a finding in billing but disabled for Legacy
+    assert!(summaries[3].top_rules.iter().any(|(rule, _)| rule == "vague-term"));
+    assert!(!summaries[0].top_rules.iter().any(|(rule, _)| rule == "vague-term"));
+
+    let report = format_projects_summary(&repo, &summaries);
+    assert!(report.contains("across 4 project(s), 4 file(s)"));
+    assert!(report.contains("| Legacy | `legacy` | 1 |"));
+    assert!(report.contains("| **Combined** |"));
+    fs::remove_dir_all(&repo).unwrap();
+}
+
+#[test]
+fn test_nearest_project_config_is_layered_over_the_global_one() {
+    let repo = std::env::temp_dir().join(format!("prism_project_config_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&repo);
+    write(&repo, ".prism.yml", "provider: ollama\nmodel: llama3\nformat: markdown\n\
+        custom_rules:\n  - id: tbd\n    keywords: [TBD]\n    severity: High\n\
+        ignore: [drafts/, '*.tmp.md']\npresets:\n  ci:\n    generate: [tests]\n");
+    write(&repo, "docs/requirements/login.md", "Login is TBD.");
+
+    let (marker, project) = find_project_config(&repo.join("docs/requirements/login.md")).unwrap().unwrap();
+    assert_eq!(marker, repo.canonicalize().unwrap().join(".prism.yml"));
+
+    let mut config = Config::default();
+    config.llm.provider = "openai".to_string();
+    config.llm.model = "gpt-4".to_string();
+    config.analysis.ignore = vec!["archive/".to_string()];
+    project.apply_to(&mut config);
+    assert_eq!(config.llm.provider, "ollama");
+    assert_eq!(config.llm.model, "llama3");
+    assert_eq!(config.llm.base_url.as_deref(), Some("http://localhost:11434/api/chat"));
+    assert!(matches!(config.output.format, Some(OutputFormat::Markdown)));
+    assert_eq!(config.analysis.custom_rules[0].id, "tbd");
+    assert!(config.presets.contains_key("ci"));
+
+    let ignore = IgnoreGlobs::new(&config.analysis.ignore).unwrap();
+    assert!(ignore.is_ignored(Path::new("archive/old.md")));
+    assert!(ignore.is_ignored(Path::new("drafts/new.md")));
+    assert!(ignore.is_ignored(Path::new("docs/login.tmp.md")));
+    assert!(!ignore.is_ignored(Path::new("docs/requirements/login.md")));
+
+    assert!(find_project_config(&std::env::temp_dir()).unwrap().is_none());
+    fs::remove_dir_all(&repo).unwrap();
+}
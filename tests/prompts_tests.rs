@@ -0,0 +1,37 @@
+use prism::prompts::*;
+
+#[test]
+fn test_built_in_prompts_fill_in_their_variables() {
+    let prompts = PromptTemplates::default();
+    for task in PromptTask::ALL {
+        check_template(task, task.built_in()).unwrap();
+    }
+    let prompt = prompts.render(PromptTask::Ambiguities, &[("text", "The system shall respond {quickly}.")]);
+    assert!(prompt.contains("Requirement text:\nThe system shall respond {quickly}."));
+    assert!(prompt.contains("\"ambiguities\": ["));
+    assert!(!prompt.contains("{text}"));
+}
+
+#[test]
+fn test_template_files_replace_built_in_prompts() {
+    let dir = std::env::temp_dir().join(format!("prism-prompts-{}", std::process::id()));
+    let (global, project) = (dir.join("global"), dir.join("project"));
+    std::fs::create_dir_all(&project).unwrap();
+    assert_eq!(write_built_in_prompts(&global).unwrap().len(), PromptTask::ALL.len());
+    std::fs::write(global.join("ambiguities.txt"), "Acme glossary: member, not customer.\nFind ambiguities in {text} as JSON.").unwrap();
+    std::fs::write(project.join("nfrs.txt"), "NFRs for {text} involving {actors}").unwrap();
+
+    let prompts = PromptTemplates::load(&[global.clone(), project.clone()]).unwrap();
+    assert_eq!(prompts.custom().count(), PromptTask::ALL.len());
+    assert_eq!(
+        prompts.render(PromptTask::Ambiguities, &[("text", "Users log in")]),
+        "Acme glossary: member, not customer.\nFind ambiguities in Users log in as JSON."
+    );
+    assert_eq!(prompts.render(PromptTask::Nfrs, &[("text", "Log in"), ("actors", "[\"user\"]")]), "NFRs for Log in involving [\"user\"]");
+    assert_ne!(prompts.fingerprint(), PromptTemplates::default().fingerprint());
+
+    std::fs::write(project.join("entities.txt"), "Entities of {document}").unwrap();
+    let error = format!("{:#}", PromptTemplates::load(&[global, project]).unwrap_err());
+    assert!(error.contains("entities.txt") && error.contains("{document} is not a variable"), "{}", error);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
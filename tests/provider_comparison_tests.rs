@@ -0,0 +1,61 @@
+use prism::analyzer::{Ambiguity, AmbiguitySeverity, Analyzer, RULE_AI_AMBIGUITY};
+use prism::budget::LlmUsage;
+use prism::provider_comparison::*;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_compare_runs_of_two_providers() {
+    let built_in = Analyzer::new().unwrap().analyze("The customer shall check out fast.").await.unwrap();
+    let mut claude = built_in.clone();
+    claude.ambiguities.push(Ambiguity {
+        text: "check out".to_string(),
+        reason: "Payment methods are not named".to_string(),
+        suggestions: Vec::new(),
+        severity: AmbiguitySeverity::Medium,
+        rule_id: RULE_AI_AMBIGUITY.to_string(),
+        fingerprint: String::new(),
+        location: None,
+    });
+    claude.entities.objects.push("cart".to_string());
+    let usage = LlmUsage { calls: 2, prompt_chars: 4000, response_chars: 800, ..LlmUsage::default() };
+    let run = |provider: &str, model: &str, millis: u64, result| ProviderRun {
+        provider: provider.to_string(),
+        model: model.to_string(),
+        runs: 1,
+        elapsed: Duration::from_millis(millis),
+        usage,
+        result,
+    };
+    let runs = vec![
+        run("openai", "gpt-4o", 1200, Ok(built_in.clone())),
+        run("claude", "claude-3-5-sonnet", 900, Ok(claude)),
+        run("ollama", "llama3.1", 5, Err("Ollama server not available".to_string())),
+    ];
+
+    let comparison = compare_runs(&runs);
+    let [openai, claude, ollama] = &comparison.providers[..] else { panic!("{:?}", comparison.providers) };
+    assert_eq!(claude.findings, openai.findings + 1);
+    assert_eq!((claude.ai_findings, claude.response_ms), (1, 900));
+    assert_eq!(claude.only_findings, ["check out"]);
+    assert_eq!(claude.only_entities, ["object: cart"]);
+    assert!(openai.only_findings.is_empty() && openai.estimated_cost > 0.0);
+    assert!(ollama.error.is_some() && ollama.findings == 0);
+    assert!(comparison.shared_findings.contains(&"fast".to_string()), "{:?}", comparison.shared_findings);
+
+    let markdown = format_provider_comparison_markdown(&comparison);
+    assert!(markdown.contains("| claude | claude-3-5-sonnet | 0.9s | "));
+    assert!(markdown.contains("## 🔎 Only claude (claude-3-5-sonnet)") && markdown.contains("- Object: cart"));
+    assert!(markdown.contains("**ollama (llama3.1)** failed: Ollama server not available"));
+}
+
+#[test]
+fn test_bench_model_targets_and_median_latency() {
+    assert_eq!(parse_model_target("claude"), ("claude".to_string(), None));
+    assert_eq!(parse_model_target("OpenAI:gpt-4o-mini"), ("openai".to_string(), Some("gpt-4o-mini".to_string())));
+    assert_eq!(parse_model_target("ollama:llama3.1:8b"), ("ollama".to_string(), Some("llama3.1:8b".to_string())));
+
+    let ms = Duration::from_millis;
+    assert_eq!(median_duration(vec![ms(900), ms(300), ms(4000)]), ms(900));
+    assert_eq!(median_duration(vec![ms(400), ms(200)]), ms(300));
+    assert_eq!(median_duration(Vec::new()), Duration::ZERO);
+}
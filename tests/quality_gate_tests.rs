@@ -0,0 +1,36 @@
+use prism::analyzer::AmbiguitySeverity;
+use prism::quality_gate::QualityGate;
+use prism::Analyzer;
+
+#[tokio::test]
+async fn test_gate_fails_on_findings_at_or_above_the_severity() {
+    let result = Analyzer::new().unwrap().analyze("The system should be fast.").await.unwrap();
+    let worst = result.ambiguities.iter().map(|a| a.severity).max().unwrap();
+    assert_eq!(worst, AmbiguitySeverity::Medium);
+
+    let gate = QualityGate { fail_on: Some(AmbiguitySeverity::Medium), min_completeness: None };
+    let failing = result.ambiguities.iter().filter(|a| a.severity >= AmbiguitySeverity::Medium).count();
+    assert_eq!(gate.violations(&result), vec![format!("{} finding(s) of severity Medium or above", failing)]);
+
+    let gate = QualityGate { fail_on: Some(AmbiguitySeverity::High), min_completeness: None };
+    assert!(gate.is_enabled());
+    assert!(gate.violations(&result).is_empty());
+    assert!(!QualityGate::default().is_enabled());
+}
+
+#[tokio::test]
+async fn test_gate_fails_below_the_minimum_completeness() {
+    let analyzer = Analyzer::new().unwrap();
+    let text = "As a user, I want to login";
+    let mut result = analyzer.analyze(text).await.unwrap();
+    let gate = QualityGate { fail_on: None, min_completeness: Some(70.0) };
+    assert_eq!(gate.violations(&result), vec!["completeness was not analyzed".to_string()]);
+
+    let completeness = analyzer.analyze_completeness(text, &result.entities).await.unwrap();
+    let score = completeness.completeness_score;
+    result.completeness_analysis = Some(completeness);
+    let below = QualityGate { fail_on: None, min_completeness: Some(score + 1.0) };
+    assert_eq!(below.violations(&result), vec![format!("completeness {:.0}% below the minimum of {:.0}%", score, score + 1.0)]);
+    let at = QualityGate { fail_on: None, min_completeness: Some(score) };
+    assert!(at.violations(&result).is_empty());
+}
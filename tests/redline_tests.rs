@@ -0,0 +1,48 @@
+use chrono::{TimeZone, Utc};
+use prism::redline::{format_redline_document, redline, write_redline_docx, RedlineParagraph, Segment};
+use std::io::{Cursor, Read};
+
+#[test]
+fn test_improvements_are_marked_word_by_word_and_by_paragraph() {
+    let original = "# Login\nThe system should be fast.\nUsers can reset passwords.\n";
+    let improved = "# Login\nThe system shall respond within 2 seconds.\nUsers can reset passwords.\nSessions expire after 30 minutes.\n";
+    let paragraphs = redline(original, improved);
+
+    assert_eq!(paragraphs.len(), 4);
+    assert_eq!(paragraphs[0].segments, [Segment::Same("# Login".to_string())]);
+    assert_eq!(paragraphs[1].segments, [
+        Segment::Same("The system ".to_string()),
+        Segment::Deleted("should be fast.".to_string()),
+        Segment::Inserted("shall respond within 2 seconds.".to_string()),
+    ]);
+    assert!(!paragraphs[2].inserted && !paragraphs[2].deleted);
+    assert!(paragraphs[3].inserted);
+
+    let removed = redline("Keep this.\nDrop this.\n", "Keep this.\n");
+    assert_eq!(removed[1], RedlineParagraph {
+        segments: vec![Segment::Deleted("Drop this.".to_string())],
+        deleted: true,
+        original_line: Some(1),
+        ..RedlineParagraph::default()
+    });
+}
+
+#[test]
+fn test_redline_docx_has_tracked_insertions_and_deletions() {
+    let date = Utc.with_ymd_and_hms(2026, 10, 16, 9, 30, 0).unwrap();
+    let document = format_redline_document(&redline("Pay <soon> & fast.", "Pay within 5 days & fast."), "PRISM (gpt-4)", date);
+    assert!(document.contains("<w:del w:id=\"1\" w:author=\"PRISM (gpt-4)\" w:date=\"2026-10-16T09:30:00Z\"><w:r><w:delText xml:space=\"preserve\">&lt;soon&gt; </w:delText></w:r></w:del>"), "{}", document);
+    assert!(document.contains("<w:ins w:id=\"2\" w:author=\"PRISM (gpt-4)\" w:date=\"2026-10-16T09:30:00Z\"><w:r><w:t xml:space=\"preserve\">within 5 days </w:t></w:r></w:ins>"));
+    assert!(document.contains("<w:t xml:space=\"preserve\">&amp; fast.</w:t>"));
+
+    let mut docx = Cursor::new(Vec::new());
+    write_redline_docx(&mut docx, "Old text.", "New text.", "PRISM", date).unwrap();
+    let mut archive = zip::ZipArchive::new(docx).unwrap();
+    for part in ["[Content_Types].xml", "_rels/.rels", "word/_rels/document.xml.rels", "word/settings.xml"] {
+        assert!(archive.by_name(part).is_ok(), "{}", part);
+    }
+    let mut xml = String::new();
+    archive.by_name("word/document.xml").unwrap().read_to_string(&mut xml).unwrap();
+    assert!(xml.contains("<w:delText xml:space=\"preserve\">Old </w:delText>"));
+    assert!(xml.contains("<w:t xml:space=\"preserve\">New </w:t></w:r></w:ins>"));
+}
@@ -0,0 +1,40 @@
+use prism::analyzer::Analyzer;
+use prism::config::ReleaseGateConfig;
+use prism::release::*;
+
+#[test]
+fn test_find_open_questions() {
+    let text = "As a user, I want to export reports.\n- Export format: TBD\nWhich timezone do reports use?\nOpen question: retention period\nThe report is emailed daily.\n";
+    let questions = find_open_questions(text);
+
+    assert_eq!(questions, vec![
+        "Export format: TBD",
+        "Which timezone do reports use?",
+        "Open question: retention period",
+    ]);
+}
+
+#[tokio::test]
+async fn test_release_check_gates() {
+    let analyzer = Analyzer::new().unwrap();
+    let text = "As a customer, I want to pay by card so that checkout is quick. Acceptance criteria: payment succeeds within 2 seconds. Security: card data is encrypted.";
+    let result = analyzer.analyze(text).await.unwrap();
+    let completeness = analyzer.analyze_completeness(text, &result.entities).await.unwrap();
+
+    let gate = ReleaseGateConfig {
+        min_completeness: 0.0,
+        ..ReleaseGateConfig::default()
+    };
+    let readiness = evaluate_release(&result, &completeness, None, find_open_questions(text), &gate);
+    assert!(readiness.ready);
+    assert_eq!(readiness.gates[1].passed, None);
+
+    let readiness = evaluate_release(&result, &completeness, Some(50.0), vec!["Refund flow: TBD".to_string()], &gate);
+    assert!(!readiness.ready);
+    assert_eq!(readiness.gates[1].passed, Some(false));
+    assert_eq!(readiness.gates[2].passed, Some(false));
+
+    let markdown = format_release_markdown(&readiness);
+    assert!(markdown.contains("NO-GO"));
+    assert!(markdown.contains("- Refund flow: TBD"));
+}
@@ -0,0 +1,36 @@
+use prism::analyzer::{AmbiguitySeverity, Analyzer};
+use prism::report_diff::*;
+
+#[tokio::test]
+async fn test_report_diff_lists_added_and_resolved_findings() {
+    let analyzer = Analyzer::new().unwrap();
+    let old = analyzer.analyze("## Login\nThe user shall log in fast.\nThe admin shall export reports.").await.unwrap();
+    let new = analyzer.analyze(
+        "## Login\nThe user shall log in within 2 seconds.\n## Reports\nThe admin shall export reports.\nThe customer shall view a user-friendly audit log."
+    ).await.unwrap();
+
+    let diff = diff_results(&old, &new);
+    assert!(diff.resolved.iter().any(|f| f.text.eq_ignore_ascii_case("fast")), "{:?}", diff.resolved);
+    assert!(diff.added.iter().any(|f| f.text.eq_ignore_ascii_case("user-friendly")), "{:?}", diff.added);
+    assert!(!diff.is_improvement());
+    assert_eq!(diff.requirements.delta(), 1.0);
+    assert!(diff.actors.added.iter().any(|a| a.eq_ignore_ascii_case("customer")), "{:?}", diff.actors);
+
+    let markdown = format_report_diff_markdown(&diff, "v1.json", "v2.json");
+    assert!(markdown.contains("**Old:** `v1.json`"));
+    assert!(markdown.contains("## 🆕 New Findings") && markdown.contains("user-friendly"));
+}
+
+#[tokio::test]
+async fn test_report_diff_of_a_report_with_itself() {
+    let mut result = Analyzer::new().unwrap().analyze("The system should respond fast.").await.unwrap();
+    let saved: prism::AnalysisResult = serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+    let diff = diff_results(&saved, &result);
+    assert!(diff.added.is_empty() && diff.resolved.is_empty() && diff.is_improvement());
+    assert_eq!(diff.unchanged, result.ambiguities.len());
+
+    result.ambiguities[0].severity = AmbiguitySeverity::Critical;
+    let diff = diff_results(&saved, &result);
+    assert_eq!(diff.severity_changes.len(), 1);
+    assert!(diff.quality.delta() < 0.0);
+}
@@ -0,0 +1,113 @@
+use chrono::{TimeZone, Utc};
+use prism::analyzer::Analyzer;
+use prism::config::{Config, RetryConfig};
+use prism::retry::{is_retryable, parse_retry_after, retry_delay};
+use reqwest::StatusCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+#[test]
+fn test_retry_delay_backs_off_and_honors_retry_after() {
+    let policy = RetryConfig { max_attempts: 5, initial_backoff_ms: 500, max_backoff_ms: 3000 };
+    let now = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+
+    assert_eq!(retry_delay(&policy, 1, None, now), Duration::from_millis(500));
+    assert_eq!(retry_delay(&policy, 3, None, now), Duration::from_millis(2000));
+    assert_eq!(retry_delay(&policy, 4, None, now), Duration::from_millis(3000));
+    assert_eq!(retry_delay(&policy, 1, Some("2"), now), Duration::from_secs(2));
+    assert_eq!(retry_delay(&policy, 1, Some("120"), now), Duration::from_millis(3000));
+    assert_eq!(retry_delay(&policy, 2, Some("soon"), now), Duration::from_millis(1000));
+    assert_eq!(retry_delay(&policy, 1, Some("inf"), now), Duration::from_millis(3000));
+    assert_eq!(retry_delay(&policy, 1, Some("1e20"), now), Duration::from_millis(3000));
+    assert_eq!(retry_delay(&policy, 1, Some("NaN"), now), Duration::from_millis(500));
+
+    assert_eq!(parse_retry_after("Fri, 16 Oct 2026 12:00:07 GMT", now), Some(Duration::from_secs(7)));
+    assert_eq!(parse_retry_after("Fri, 16 Oct 2026 11:59:00 GMT", now), Some(Duration::ZERO));
+
+    assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS));
+    assert!(is_retryable(StatusCode::SERVICE_UNAVAILABLE));
+    assert!(!is_retryable(StatusCode::UNAUTHORIZED));
+}
+
+/// Answers the first `failures` requests with 429 and the rest with a chat completion.
+async fn flaky_server(failures: usize, requests: Arc<AtomicUsize>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0u8; 16384];
+            let _ = socket.read(&mut request).await;
+            let response = if requests.fetch_add(1, Ordering::SeqCst) < failures {
+                "HTTP/1.1 429 Too Many Requests\r\nretry-after: 0\r\ncontent-length: 0\r\nconnection: close\r\n\r\n".to_string()
+            } else {
+                let body = r#"{"choices":[{"message":{"content":"ok"}}]}"#;
+                format!("HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}", body.len(), body)
+            };
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+    format!("http://{}/v1/chat/completions", address)
+}
+
+fn openai_config(base_url: String, max_attempts: u32) -> Config {
+    let mut config = Config::default();
+    config.llm.provider = "openai".to_string();
+    config.llm.api_key = Some("test-key".to_string());
+    config.llm.model = "gpt-4".to_string();
+    config.llm.base_url = Some(base_url);
+    config.llm.retry = RetryConfig { max_attempts, initial_backoff_ms: 10, max_backoff_ms: 50 };
+    config
+}
+
+#[tokio::test]
+async fn test_rate_limited_requests_are_retried() {
+    let requests = Arc::new(AtomicUsize::new(0));
+    let url = flaky_server(2, requests.clone()).await;
+    let analyzer = Analyzer::new().unwrap().with_config(openai_config(url, 3));
+    assert_eq!(analyzer.call_llm("prompt").await.unwrap(), "ok");
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+    let requests = Arc::new(AtomicUsize::new(0));
+    let url = flaky_server(5, requests.clone()).await;
+    let analyzer = Analyzer::new().unwrap().with_config(openai_config(url, 2));
+    assert!(analyzer.call_llm("prompt").await.is_err());
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+}
+
+/// Accepts connections and never answers.
+async fn silent_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let mut sockets = Vec::new();
+        while let Ok((socket, _)) = listener.accept().await {
+            sockets.push(socket);
+        }
+    });
+    format!("http://{}/v1/chat/completions", address)
+}
+
+#[tokio::test]
+async fn test_slow_requests_time_out_and_can_be_cancelled() {
+    let mut config = openai_config(silent_server().await, 3);
+    config.llm.timeout = 1;
+    let analyzer = Analyzer::new().unwrap().with_config(config.clone());
+    let error = analyzer.call_llm("prompt").await.unwrap_err().to_string();
+    assert!(error.contains("OpenAI request timed out after 1s"), "{}", error);
+    assert!(error.contains("prism config --timeout"), "{}", error);
+
+    config.llm.timeout = 60;
+    let (cancel, cancelled) = tokio::sync::watch::channel(false);
+    let analyzer = Analyzer::new().unwrap().with_config(config).with_cancellation(cancelled);
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        cancel.send_replace(true);
+    });
+    let started = std::time::Instant::now();
+    assert!(analyzer.call_llm("prompt").await.unwrap_err().to_string().contains("cancelled"));
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
@@ -0,0 +1,29 @@
+use prism::analyzer::Analyzer;
+use prism::config::{Config, ReviewConfig};
+use prism::reviewers::suggest_reviewers;
+
+#[tokio::test]
+async fn test_reviewers_are_suggested_from_finding_categories() {
+    let text = "The user can login with a password. Search results should be fast.";
+    let analyzer = Analyzer::new().unwrap();
+    let mut result = analyzer.analyze(text).await.unwrap();
+    result.nfr_suggestions = Some(analyzer.generate_nfr_suggestions(text, &result.entities).await.unwrap());
+    result.completeness_analysis = Some(analyzer.analyze_completeness(text, &result.entities).await.unwrap());
+
+    let suggestions = suggest_reviewers(text, &result, &ReviewConfig::default().reviewers);
+    let reviewers: Vec<&str> = suggestions.iter().map(|s| s.reviewer.as_str()).collect();
+    assert_eq!(reviewers, vec!["Security team", "SRE", "QA", "Product owner"]);
+
+    let security = suggestions.iter().find(|s| s.reviewer == "Security team").unwrap();
+    assert_eq!(security.categories, vec!["security"]);
+    assert_eq!(security.categories.len(), security.reasons.len());
+
+    // A project mapping replaces the reviewers of the categories it lists
+    let mut config = Config::default();
+    config.review.reviewers.insert("security".to_string(), vec!["@org/appsec".to_string()]);
+    config.review.reviewers.insert("vague-term".to_string(), vec!["Tech writer".to_string()]);
+    let suggestions = Analyzer::new().unwrap().with_config(config).suggest_reviewers(text, &result);
+    assert!(suggestions.iter().any(|s| s.reviewer == "@org/appsec"));
+    assert!(!suggestions.iter().any(|s| s.reviewer == "Security team"));
+    assert!(suggestions.iter().any(|s| s.reviewer == "Tech writer" && s.categories == ["vague-term"]));
+}
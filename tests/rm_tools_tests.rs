@@ -0,0 +1,63 @@
+use prism::config::{FieldMapping, RmConnection};
+use prism::rm_tools::{html_to_text, next_page, parse_items, NextPage, ResolvedFields, RmClient, RmTool};
+use serde_json::json;
+
+fn connection(yaml: &str) -> RmConnection {
+    serde_yaml::from_str(yaml).unwrap()
+}
+
+#[test]
+fn test_items_parsed_with_tool_defaults_and_overrides() {
+    let polarion = json!({
+        "data": [{ "id": "CHK/WI-7", "attributes": { "title": "Guest checkout",
+            "description": { "type": "text/html", "value": "<p>The cart <b>should</b> load fast.</p><ul><li>Card</li><li>PayPal &amp; more</li></ul>" } } }],
+        "links": { "next": "/polarion/rest/v1/projects/CHK/workitems?page%5Bnumber%5D=2" }
+    });
+    let items = parse_items(&polarion, &RmTool::Polarion.default_fields());
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].id, "CHK/WI-7");
+    assert_eq!(items[0].text, "The cart should load fast.\n- Card\n- PayPal & more");
+    assert_eq!(items[0].file_name("md"), "CHK_WI-7.md");
+    assert!(matches!(next_page(RmTool::Polarion, &polarion), Some(NextPage::Url(url)) if url.ends_with("page%5Bnumber%5D=2")));
+
+    let jama = json!({
+        "meta": { "pageInfo": { "startIndex": 0, "resultCount": 1, "totalResults": 3 } },
+        "data": [{ "id": 1042, "documentKey": "CHK-REQ-12", "fields": { "name": "Receipts", "description": "<p>Email a receipt.</p>", "priority": 3 } }]
+    });
+    let items = parse_items(&jama, &RmTool::Jama.default_fields());
+    assert_eq!((items[0].id.as_str(), items[0].title.as_str()), ("CHK-REQ-12", "Receipts"));
+    assert_eq!(next_page(RmTool::Jama, &jama), Some(NextPage::Offset(1)));
+
+    let mapping = FieldMapping { id: Some("/id".to_string()), ..Default::default() };
+    let items = parse_items(&jama, &ResolvedFields::resolve(RmTool::Jama, &mapping));
+    assert_eq!(items[0].id, "1042");
+    assert_eq!(items[0].analysis_text(), "Receipts\n\nEmail a receipt.");
+
+    let doors = json!({
+        "oslc:responseInfo": { "oslc:nextPage": { "rdf:resource": "https://jazz.example.com/rm/views?page=2" } },
+        "oslc:results": [{ "dcterms:identifier": "4711", "dcterms:title": "Login", "dcterms:description": "Users log in quickly." }]
+    });
+    assert_eq!(parse_items(&doors, &RmTool::DoorsNext.default_fields())[0].title, "Login");
+    assert_eq!(next_page(RmTool::DoorsNext, &doors), Some(NextPage::Url("https://jazz.example.com/rm/views?page=2".to_string())));
+}
+
+#[test]
+fn test_list_url_from_connection() {
+    let polarion = RmClient::new(connection("tool: polarion\nbase_url: https://polarion.example.com/\nquery:\n  query: type:systemRequirement\n"), None);
+    let url = polarion.list_url(Some("CHK")).unwrap();
+    assert!(url.starts_with("https://polarion.example.com/polarion/rest/v1/projects/CHK/workitems?"));
+    assert!(url.contains("query=type%3AsystemRequirement"));
+    assert!(!url.contains("type%3Arequirement"));
+    assert!(polarion.list_url(None).is_err());
+
+    let jama = RmClient::new(connection("tool: jama\nbase_url: https://acme.jamacloud.com\n"), None);
+    assert_eq!(jama.list_url(Some("42")).unwrap(), "https://acme.jamacloud.com/rest/v1/items?maxResults=50&project=42");
+
+    let doors = RmClient::new(connection("tool: doors-next\nbase_url: https://jazz.example.com/rm\n"), None);
+    assert!(doors.list_url(None).unwrap_err().to_string().contains("OSLC query capability"));
+}
+
+#[test]
+fn test_html_to_text() {
+    assert_eq!(html_to_text("<h2>Login</h2><div>Line one<br/>Line&nbsp;two</div>"), "Login\nLine one\nLine two");
+}
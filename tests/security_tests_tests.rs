@@ -0,0 +1,51 @@
+use prism::analyzer::{Analyzer, NfrCategory, NfrPriority, NonFunctionalRequirement};
+use prism::security_tests::{format_security_checklist, generate_security_tests, SecurityTestKind};
+
+const REQUIREMENTS: &str = "# Shop\n\
+    Users must log in with their email and password.\n\
+    Only admins can delete user accounts within 2 seconds.\n\
+    Customers can search products and post comments on a user-friendly page.\n\
+    The catalogue shows the newest products first.\n";
+
+#[tokio::test]
+async fn test_security_tests_for_auth_input_and_nfrs() {
+    let result = Analyzer::new().unwrap().analyze(REQUIREMENTS).await.unwrap();
+    let nfr = NonFunctionalRequirement {
+        category: NfrCategory::Security,
+        requirement: "Card numbers shall be encrypted at rest".to_string(),
+        rationale: String::new(),
+        acceptance_criteria: Vec::new(),
+        priority: NfrPriority::MustHave,
+    };
+    let tests = generate_security_tests(REQUIREMENTS, &result.entities.actions, &[nfr], &result.ambiguities);
+
+    let titles: Vec<&str> = tests.iter().map(|t| t.title.as_str()).collect();
+    assert!(titles.contains(&"Repeated failed sign-ins are throttled"));
+    assert!(titles.contains(&"Users without the required role cannot delete user accounts"));
+    assert!(!titles.iter().any(|t| t.contains("newest")));
+
+    let sql = tests.iter().find(|t| t.title == "SQL injection in the input is treated as data").unwrap();
+    assert_eq!(sql.kind, SecurityTestKind::Injection);
+    assert!(sql.probes.contains(&"' OR '1'='1".to_string()));
+    assert_eq!(sql.sources, vec!["Line 4: Customers can search products and post comments on a user-friendly page."]);
+    assert_eq!(sql.findings, vec!["vague-term: user-friendly"]);
+
+    let exposure = tests.iter().find(|t| t.kind == SecurityTestKind::DataExposure).unwrap();
+    assert_eq!(exposure.sources, vec!["Security NFR: Card numbers shall be encrypted at rest"]);
+    assert_eq!(tests[0].id, "SEC-1");
+    assert_eq!(tests.last().unwrap().id, format!("SEC-{}", tests.len()));
+}
+
+#[test]
+fn test_security_checklist_groups_tests_by_kind() {
+    let tests = generate_security_tests("Only admins can delete user accounts.\nUsers can upload profile pictures.\n", &[], &[], &[]);
+    let checklist = format_security_checklist(&tests, "shop");
+    assert!(checklist.starts_with("# Security Test Checklist: shop\n"));
+    let authorization = checklist.find("## Authorization (A01:2021 Broken Access Control)").unwrap();
+    let injection = checklist.find("## Injection (A03:2021 Injection)").unwrap();
+    assert!(authorization < injection);
+    assert!(checklist.contains("- [ ] **SEC-1** Unauthenticated requests to delete user accounts are refused\n"));
+    assert!(checklist.contains("  - Tools: Burp Repeater: edit the file name and Content-Type of the upload request; ZAP: Path Traversal rule (6)\n"));
+    assert!(checklist.contains("  - From: Line 2: Users can upload profile pictures.\n"));
+    assert!(!checklist.contains("## Authentication"));
+}
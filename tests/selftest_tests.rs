@@ -0,0 +1,33 @@
+use prism::analyzer::Analyzer;
+use prism::selftest::*;
+
+#[tokio::test]
+async fn test_built_in_suite_scored_per_rule() {
+    let cases = parse_suite(BUILT_IN_SUITE).unwrap();
+    assert!(cases.iter().any(|c| c.is_known_bad()) && cases.iter().any(|c| !c.is_known_bad()));
+
+    let analyzer = Analyzer::new().unwrap();
+    let mut outcomes = Vec::new();
+    for case in &cases {
+        outcomes.push(CaseOutcome::new(case, &analyzer.analyze(&case.text).await.unwrap(), false));
+    }
+    let run = SelftestRun::new("built-in", None, outcomes);
+    let vague = run.rules.iter().find(|r| r.rule_id == "vague-term").unwrap();
+    assert!(vague.true_positives > 0);
+    assert_eq!(vague.precision, Some(vague.true_positives as f32 / (vague.true_positives + vague.false_positives) as f32));
+    // The suite has cases the regular expressions are known to miss
+    assert!(run.overall.recall.unwrap() < 1.0);
+    assert!(run.cases.iter().any(|c| c.case_id == "passive-without-modal" && !c.missed().is_empty()));
+
+    let markdown = format_selftest_markdown(&SelftestReport { suite: "built-in".to_string(), known_bad: 1, known_good: 1, runs: vec![run] });
+    assert!(markdown.contains("| `passive-voice` |"));
+    assert!(markdown.contains("- `passive-without-modal`: missed `passive-voice`"));
+}
+
+#[test]
+fn test_suite_needs_unique_case_ids() {
+    assert!(parse_suite("- id: a\n  text: The user shall log in.\n- id: a\n  text: The admin shall log in.\n").is_err());
+    assert!(parse_suite("[]").is_err());
+    let cases = parse_suite("- id: pii\n  text: Store the SSN.\n  expect: [acme-pii]\n").unwrap();
+    assert_eq!(cases[0].expect, ["acme-pii"]);
+}
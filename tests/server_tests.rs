@@ -0,0 +1,94 @@
+use chrono::NaiveDate;
+use prism::api_keys::ApiKeys;
+use prism::config::{ApiKeyConfig, Config};
+use prism::server::{serve, ServerState};
+use serde_json::Value;
+use std::sync::Arc;
+
+fn api_key(name: &str, key: &str, per_minute: Option<u32>, per_day: Option<u32>) -> ApiKeyConfig {
+    ApiKeyConfig { name: name.to_string(), key: key.to_string(), requests_per_minute: per_minute, requests_per_day: per_day }
+}
+
+#[test]
+fn test_api_keys_are_held_to_their_limits() {
+    let keys = ApiKeys::new(&[api_key("payments", "key-1", Some(2), Some(3)), api_key("search", "key-2", None, None)], None).unwrap();
+    assert_eq!(keys.authenticate("key-2").unwrap().name, "search");
+    assert!(keys.authenticate("key-3").is_none());
+
+    let payments = keys.authenticate("key-1").unwrap();
+    let at = |h, m, s| NaiveDate::from_ymd_opt(2024, 5, 6).unwrap().and_hms_opt(h, m, s).unwrap();
+    assert!(keys.acquire(payments, at(10, 0, 0)).is_ok());
+    assert!(keys.acquire(payments, at(10, 0, 20)).is_ok());
+    let exceeded = keys.acquire(payments, at(10, 0, 30)).unwrap_err();
+    assert_eq!((exceeded.limit.as_str(), exceeded.retry_after), ("2 requests per minute", 30));
+
+    assert!(keys.acquire(payments, at(10, 1, 0)).is_ok());
+    let exceeded = keys.acquire(payments, at(12, 0, 0)).unwrap_err();
+    assert_eq!((exceeded.limit.as_str(), exceeded.retry_after), ("3 requests per day", 12 * 3600));
+
+    let next_day = NaiveDate::from_ymd_opt(2024, 5, 7).unwrap().and_hms_opt(8, 0, 0).unwrap();
+    assert!(keys.acquire(payments, next_day).is_ok());
+    let usage = keys.usage(payments);
+    assert_eq!((usage.requests, usage.rejected, usage.requests_today), (4, 2, 1));
+
+    assert!(ApiKeys::new(&[api_key("a", "same", None, None), api_key("b", "same", None, None)], None).is_err());
+}
+
+#[tokio::test]
+async fn test_analyze_requires_an_api_key_when_configured() {
+    let log = std::env::temp_dir().join(format!("prism-usage-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&log);
+    let mut config = Config::default();
+    config.server.api_keys = vec![api_key("payments", "key-1", Some(1), None)];
+    config.server.usage_log = Some(log.clone());
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(serve(listener, Arc::new(ServerState::new(config).unwrap())));
+
+    let client = reqwest::Client::new();
+    let analyze = || client.post(format!("{}/analyze", base)).body("The page should load fast.");
+    assert_eq!(analyze().send().await.unwrap().status(), 401);
+    assert_eq!(analyze().bearer_auth("wrong").send().await.unwrap().status(), 401);
+
+    let response = analyze().bearer_auth("key-1").send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let result: Value = response.json().await.unwrap();
+    assert!(!result["ambiguities"].as_array().unwrap().is_empty());
+
+    let limited = analyze().header("X-API-Key", "key-1").send().await.unwrap();
+    assert_eq!(limited.status(), 429);
+    assert!(limited.headers().contains_key("Retry-After"));
+
+    let usage: Value = client.get(format!("{}/usage", base)).bearer_auth("key-1").send().await.unwrap().json().await.unwrap();
+    assert_eq!(usage["key"], "payments");
+    assert_eq!((usage["usage"]["requests"].as_u64(), usage["usage"]["rejected"].as_u64()), (Some(1), Some(1)));
+
+    let lines: Vec<Value> = std::fs::read_to_string(&log).unwrap().lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(lines.iter().map(|l| l["status"].as_u64().unwrap()).collect::<Vec<_>>(), vec![200, 429]);
+    std::fs::remove_file(&log).unwrap();
+}
+
+#[tokio::test]
+async fn test_oversized_bodies_are_rejected() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(serve(listener, Arc::new(ServerState::new(Config::default()).unwrap())));
+
+    let response = reqwest::Client::new().post(format!("http://{}/analyze", address)).body(vec![b'a'; 5 * 1024 * 1024 + 1]).send().await.unwrap();
+    assert_eq!(response.status(), 413);
+
+    // Without a Content-Length the body is cut off once it passes the limit
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    let mut socket = tokio::net::TcpStream::connect(address).await.unwrap();
+    socket.write_all(b"POST /analyze HTTP/1.1\r\nHost: localhost\r\nTransfer-Encoding: chunked\r\n\r\n").await.unwrap();
+    let chunk = format!("100000\r\n{}\r\n", "a".repeat(0x100000));
+    let mut response = Vec::new();
+    for _ in 0..6 {
+        if socket.write_all(chunk.as_bytes()).await.is_err() {
+            break;
+        }
+    }
+    let _ = socket.read_to_end(&mut response).await;
+    assert!(String::from_utf8_lossy(&response).starts_with("HTTP/1.1 413"), "{}", String::from_utf8_lossy(&response));
+}
@@ -0,0 +1,57 @@
+use prism::config::SigningConfig;
+use prism::signing::*;
+use std::path::Path;
+
+fn args(args: Vec<std::ffi::OsString>) -> Vec<String> {
+    args.into_iter().map(|a| a.to_string_lossy().into_owned()).collect()
+}
+
+#[test]
+fn test_signing_commands_for_minisign_and_sigstore() {
+    assert!(Signer::from_config(&SigningConfig::default()).is_err());
+
+    let minisign = Signer::from_config(&SigningConfig {
+        tool: Some(SigningTool::Minisign),
+        key: Some("keys/prism.key".to_string()),
+        public_key: Some("keys/prism.pub".to_string()),
+        ..SigningConfig::default()
+    }).unwrap();
+    let report = Path::new("out/report.html");
+    let sign = args(minisign.sign_args(report));
+    assert_eq!(&sign[..7], ["-S", "-m", "out/report.html", "-x", "out/report.html.minisig", "-s", "keys/prism.key"]);
+    assert!(sign[8].starts_with("prism ") && sign[8].contains("file:report.html signed:"), "{}", sign[8]);
+    assert_eq!(args(minisign.verify_args(report).unwrap()), ["-V", "-m", "out/report.html", "-x", "out/report.html.minisig", "-p", "keys/prism.pub"]);
+
+    let keyless = SigningConfig { tool: Some(SigningTool::Sigstore), ..SigningConfig::default() };
+    let sigstore = Signer::from_config(&keyless).unwrap();
+    let bundle = Path::new("evidence.zip");
+    assert_eq!(args(sigstore.sign_args(bundle)), ["sign-blob", "--yes", "--bundle", "evidence.zip.sigstore.json", "evidence.zip"]);
+    // Keyless signatures can only be trusted for a known identity
+    assert!(sigstore.verify_args(bundle).is_err());
+    let sigstore = Signer::from_config(&SigningConfig {
+        certificate_identity: Some("release@example.com".to_string()),
+        certificate_oidc_issuer: Some("https://accounts.google.com".to_string()),
+        ..keyless
+    }).unwrap();
+    assert_eq!(
+        args(sigstore.verify_args(bundle).unwrap()),
+        ["verify-blob", "--bundle", "evidence.zip.sigstore.json", "--certificate-identity", "release@example.com", "--certificate-oidc-issuer", "https://accounts.google.com", "evidence.zip"]
+    );
+}
+
+#[tokio::test]
+async fn test_unsigned_file_fails_verification() {
+    let dir = std::env::temp_dir().join(format!("prism-signing-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let report = dir.join("report.md");
+    std::fs::write(&report, "# Report").unwrap();
+    let signer = Signer::from_config(&SigningConfig {
+        tool: Some(SigningTool::Minisign),
+        public_key: Some("prism.pub".to_string()),
+        ..SigningConfig::default()
+    }).unwrap();
+    let error = signer.verify(&report).await.unwrap_err().to_string();
+    assert!(error.contains("report.md.minisig is missing"), "{}", error);
+    assert!(signer.sign(&dir.join("missing.md")).await.is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
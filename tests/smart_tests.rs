@@ -0,0 +1,32 @@
+use prism::smart::{analyze_smart, format_smart_markdown, score_requirement, SmartAxis};
+
+#[test]
+fn test_requirements_are_scored_per_smart_axis() {
+    let smart = score_requirement("REQ-3", 4, "REQ-3: The system shall send a receipt within 5 minutes so that customers can reconcile payments.");
+    assert_eq!(smart.text, "The system shall send a receipt within 5 minutes so that customers can reconcile payments.");
+    assert_eq!(smart.scores.overall(), 100.0);
+    assert!(smart.issues.is_empty());
+    assert!(smart.rewrite.is_none());
+
+    let vague = score_requirement("REQ-1", 2, "The system must be fast.");
+    assert_eq!((vague.scores.specific, vague.scores.measurable, vague.scores.relevant, vague.scores.time_bound), (80, 20, 50, 40));
+    assert!(vague.issues.iter().any(|i| i.axis == SmartAxis::Measurable && i.message == "'fast' can't be tested: give a threshold (within [N] seconds)"));
+    assert_eq!(vague.rewrite.as_deref(), Some("The system must be within [N] seconds so that [benefit]."));
+
+    let absolute = score_requirement("line 5", 5, "It shall always export PDF, CSV etc.");
+    assert_eq!(absolute.scores.achievable, 65);
+    assert_eq!(absolute.rewrite.as_deref(), Some("[The system] shall export PDF, CSV and [the remaining cases] in [99.9]% of cases within [time frame] so that [benefit]."));
+}
+
+#[test]
+fn test_smart_report_lists_rewrites_for_weak_requirements() {
+    let analysis = analyze_smart("# Checkout\n\n- REQ-1: The page must load quickly.\n- REQ-2: The system shall e-mail a receipt within 5 minutes so that customers can reconcile payments.\n");
+    assert_eq!(analysis.requirements.len(), 2);
+    assert_eq!(analysis.requirements[0].line, 3);
+    assert_eq!(analysis.average(SmartAxis::Relevant), 75.0);
+
+    let markdown = format_smart_markdown(&analysis);
+    assert!(markdown.contains("| **REQ-2** The system shall e-mail a receipt within 5 minutes so that customers can reconcile payments. | 100 | 100 | 100 | 100 | 100 | 100 |"));
+    assert!(markdown.contains("**REQ-1** (line 3)\n\n- **Specific:** 'quickly' is vague: say exactly what is meant\n"));
+    assert!(markdown.contains("> The page must load within [N] seconds so that [benefit].\n"));
+}
@@ -0,0 +1,48 @@
+use calamine::{Data, Reader, Xlsx};
+use prism::analyzer::{NfrCategory, NfrPriority, NonFunctionalRequirement};
+use prism::spreadsheet::{finding_rows, format_findings_csv, write_findings_xlsx, FINDING_COLUMNS};
+use prism::{format_report, Analyzer, OutputFormat};
+use std::io::Cursor;
+
+const SPEC: &str = "# Login\nThe system should be fast.\nReports should be generated.\n";
+
+#[tokio::test]
+async fn test_csv_has_a_row_per_finding_and_nfr() {
+    let mut result = Analyzer::new().unwrap().analyze(SPEC).await.unwrap();
+    result.nfr_suggestions = Some(vec![NonFunctionalRequirement {
+        category: NfrCategory::Performance,
+        requirement: "=Pages load within \"2 s\"".to_string(),
+        rationale: "Users leave slow pages".to_string(),
+        acceptance_criteria: vec!["p95 < 2 s".to_string(), "Measured, weekly".to_string()],
+        priority: NfrPriority::MustHave,
+    }]);
+    let csv = format_report(&result, SPEC, &OutputFormat::Csv, Some("login.md")).unwrap();
+    let lines: Vec<&str> = csv.split("\r\n").collect();
+
+    assert_eq!(lines[0], "Type,Severity,Category,Text,Details,Suggestions,File,Line,Column,Fingerprint");
+    assert!(lines[1].starts_with("Ambiguity,Medium,vague-term,fast,Vague or subjective term that lacks specific criteria,Define specific metrics or thresholds; Provide measurable criteria,login.md,2,22,"), "{}", csv);
+    assert!(lines[2].starts_with("Ambiguity,High,passive-voice,should be generated,"), "{}", csv);
+    // Quotes and commas are quoted, a leading = is not read as a formula
+    assert_eq!(lines[3], "NFR,Must Have,Performance,\"'=Pages load within \"\"2 s\"\"\",Users leave slow pages,\"p95 < 2 s; Measured, weekly\",login.md,,,");
+    assert_eq!(lines[4], "");
+    assert_eq!(format_findings_csv(&Analyzer::new().unwrap().analyze("Users log in.").await.unwrap(), None), "Type,Severity,Category,Text,Details,Suggestions,File,Line,Column,Fingerprint\r\n");
+}
+
+#[tokio::test]
+async fn test_findings_workbook_opens_with_one_row_per_finding() {
+    let result = Analyzer::new().unwrap().analyze(SPEC).await.unwrap();
+    let rows = finding_rows(&result, Some("specs/login.md"));
+    let mut workbook = Cursor::new(Vec::new());
+    write_findings_xlsx(&mut workbook, &rows).unwrap();
+
+    let mut workbook: Xlsx<_> = calamine::open_workbook_from_rs(Cursor::new(workbook.into_inner())).unwrap();
+    assert_eq!(workbook.sheet_names(), vec!["Findings".to_string()]);
+    let range = workbook.worksheet_range("Findings").unwrap();
+    assert_eq!(range.height(), 3);
+    let header: Vec<String> = range.rows().next().unwrap().iter().map(|cell| cell.to_string()).collect();
+    assert_eq!(header, FINDING_COLUMNS);
+    assert_eq!(range.get_value((1, 3)), Some(&Data::String("fast".to_string())));
+    assert_eq!(range.get_value((1, 5)), Some(&Data::String("Define specific metrics or thresholds\nProvide measurable criteria".to_string())));
+    assert_eq!(range.get_value((1, 6)), Some(&Data::String("specs/login.md".to_string())));
+    assert_eq!(range.get_value((2, 7)), Some(&Data::Float(3.0)));
+}
@@ -0,0 +1,100 @@
+use prism::analyzer::Analyzer;
+use prism::config::Config;
+use prism::telemetry::{instrument, SpanKind, Tracer};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Answers every request with `body` and keeps the request bodies.
+async fn fake_server(body: &'static str, received: Arc<Mutex<Vec<String>>>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 16384];
+            loop {
+                let n = socket.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                let Some(end) = text.find("\r\n\r\n") else { continue };
+                let length = text.lines()
+                    .find_map(|l| l.to_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap()))
+                    .unwrap_or(0);
+                if n == 0 || request.len() >= end + 4 + length {
+                    received.lock().unwrap().push(text[end + 4..].to_string());
+                    break;
+                }
+            }
+            let response = format!("HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}", body.len(), body);
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+    format!("http://{}", address)
+}
+
+#[tokio::test]
+async fn test_analysis_spans_are_nested_and_exported() {
+    let exports = Arc::new(Mutex::new(Vec::new()));
+    let collector = fake_server("{}", exports.clone()).await;
+    let tracer = Arc::new(Tracer::new(&collector, "prism-ci", BTreeMap::new()));
+    assert_eq!(tracer.endpoint(), format!("{}/v1/traces", collector));
+
+    let llm = fake_server(r#"{"choices":[{"message":{"content":"{\"ambiguities\":[]}"}}]}"#, Arc::new(Mutex::new(Vec::new()))).await;
+    let mut config = Config::default();
+    config.llm.provider = "openai".to_string();
+    config.llm.api_key = Some("test-key".to_string());
+    config.llm.model = "gpt-4o-mini".to_string();
+    config.llm.base_url = Some(format!("{}/v1/chat/completions", llm));
+    let analyzer = Analyzer::new().unwrap().with_config(config).with_tracer(Some(tracer.clone()));
+
+    let root = tracer.start_span("prism analyze", SpanKind::Internal);
+    instrument(Some(&root), analyzer.analyze("The page should load fast.")).await.unwrap();
+    let root_context = root.context();
+    drop(root);
+
+    let spans = tracer.finished_spans();
+    let span = |name: &str| spans.iter().find(|s| s.name == name).unwrap_or_else(|| panic!("no {} span", name));
+    assert!(spans.iter().all(|s| s.context.trace_id == root_context.trace_id));
+    let analyze = span("prism.analyze");
+    assert_eq!(analyze.parent_span_id, Some(root_context.span_id));
+    assert_eq!(span("prism.detector.rules").parent_span_id, Some(analyze.context.span_id));
+    let detector = span("prism.detector.ai_ambiguities");
+    let requests: Vec<_> = spans.iter().filter(|s| s.name == "prism.llm.request").collect();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].parent_span_id, Some(detector.context.span_id));
+    assert_eq!(requests[0].kind, SpanKind::Client);
+
+    assert_eq!(tracer.flush().await.unwrap(), spans.len());
+    assert!(tracer.finished_spans().is_empty());
+    let export: Value = serde_json::from_str(&exports.lock().unwrap()[0]).unwrap();
+    let resource = &export["resourceSpans"][0];
+    assert_eq!(resource["resource"]["attributes"][0]["value"]["stringValue"], "prism-ci");
+    let exported = resource["scopeSpans"][0]["spans"].as_array().unwrap();
+    assert_eq!(exported.len(), spans.len());
+    let request = exported.iter().find(|s| s["name"] == "prism.llm.request").unwrap();
+    assert_eq!(request["traceId"], format!("{:032x}", root_context.trace_id));
+    assert_eq!(request["kind"], 3);
+    assert!(request["attributes"].as_array().unwrap().iter().any(|a| a["key"] == "gen_ai.system" && a["value"]["stringValue"] == "openai"));
+}
+
+#[tokio::test]
+async fn test_spans_are_kept_when_the_collector_is_unreachable() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let unreachable = format!("http://{}/v1/traces", listener.local_addr().unwrap());
+    drop(listener);
+
+    let tracer = Arc::new(Tracer::new(&unreachable, "prism", BTreeMap::new()));
+    let mut span = tracer.start_span("prism.document.extract", SpanKind::Internal);
+    span.set_error("Unsupported file format: odt");
+    drop(span);
+
+    assert!(tracer.flush().await.is_err());
+    let spans = tracer.finished_spans();
+    assert_eq!(spans.len(), 1);
+    assert_eq!(spans[0].parent_span_id, None);
+    assert_eq!(spans[0].error.as_deref(), Some("Unsupported file format: odt"));
+}
@@ -0,0 +1,42 @@
+use prism::analyzer::{Ambiguity, AmbiguitySeverity, Analyzer, SourceLocation};
+use prism::contract_tests::generate_contract_tests;
+use prism::test_coverage::{build_test_coverage, format_test_coverage_markdown};
+
+const REQUIREMENTS: &str = "# Orders\n\
+    As a customer, I want to register an account.\n\
+    The API shall allow clients to create an order.\n\
+    The system should be fast.\n";
+
+fn finding(text: &str, line: usize) -> Ambiguity {
+    Ambiguity {
+        text: text.to_string(),
+        reason: "Vague term".to_string(),
+        suggestions: vec![],
+        severity: AmbiguitySeverity::Medium,
+        rule_id: "vague-term".to_string(),
+        fingerprint: String::new(),
+        location: Some(SourceLocation { file: None, line, column: 1, offset: 0 }),
+    }
+}
+
+#[test]
+fn test_requirements_are_mapped_to_their_derived_tests() {
+    let actions = vec!["want to register".to_string(), "create".to_string()];
+    let analyzer = Analyzer::new().unwrap();
+    let entities = prism::analyzer::ExtractedEntities { actors: vec![], actions: actions.clone(), objects: vec![] };
+    let tests = analyzer.generate_test_cases(&entities);
+    let contracts = generate_contract_tests(REQUIREMENTS);
+    let matrix = build_test_coverage(REQUIREMENTS, &actions, &tests, &contracts, &[finding("fast", 4)]);
+
+    let rows: Vec<(usize, usize)> = matrix.requirements.iter().map(|r| (r.line, r.tests.len())).collect();
+    assert_eq!(rows, vec![(2, 5), (3, 5 + 2), (4, 0)]);
+    assert!(matrix.requirements[1].tests.contains(&"POST /orders: a request to create an order → 201".to_string()));
+    assert_eq!(matrix.requirements[2].findings, vec!["vague-term: fast"]);
+    assert_eq!(matrix.untested().len(), 1);
+    assert!((matrix.coverage_percentage() - 200.0 / 3.0).abs() < 0.01);
+
+    let markdown = format_test_coverage_markdown(&matrix);
+    assert!(markdown.starts_with("**Requirements with derived tests:** 2/3 (67%)\n"));
+    assert!(markdown.contains("| 4 | **The system should be fast** | **⚠️ none** | vague-term: fast |\n"));
+    assert!(markdown.contains("### ⚠️ Requirements Without Tests\n\n- Line 4: The system should be fast (1 finding)\n"));
+}
@@ -0,0 +1,55 @@
+use prism::analyzer::ExtractedEntities;
+use prism::test_data::{format_test_data_fixtures, format_test_data_markdown, suggest_test_data, FieldKind};
+
+const STORIES: &str = "As a customer, I want to register an account with my email and a password between 8 and 64 characters.\n\
+    As a customer, I want to place an order with a quantity of at most 10 items.\n\
+    The user can upload a document up to 5 MB.\n\
+    The user can see the dashboard.\n";
+
+fn entities() -> ExtractedEntities {
+    ExtractedEntities {
+        actors: vec!["customer".to_string(), "user".to_string()],
+        actions: ["want to register", "want to place", "can upload", "can see"].iter().map(|a| a.to_string()).collect(),
+        objects: vec!["account".to_string(), "order".to_string(), "document".to_string()],
+    }
+}
+
+#[test]
+fn test_fields_and_limits_per_action() {
+    let sets = suggest_test_data(STORIES, &entities());
+    let actions: Vec<&str> = sets.iter().map(|s| s.action.as_str()).collect();
+    assert_eq!(actions, vec!["register", "place", "upload"]);
+
+    let register = sets.iter().find(|s| s.action == "register").unwrap();
+    let kinds: Vec<(&str, FieldKind)> = register.fields.iter().map(|f| (f.name.as_str(), f.kind)).collect();
+    assert_eq!(kinds, vec![("email", FieldKind::Email), ("password", FieldKind::Password)]);
+    let password = &register.fields[1];
+    let lengths = |examples: &[prism::test_data::Example]| examples.iter().map(|e| e.value.len()).collect::<Vec<_>>();
+    assert_eq!(lengths(&password.boundary), vec![8, 64]);
+    assert!(lengths(&password.invalid).contains(&7) && lengths(&password.invalid).contains(&65));
+
+    let place = sets.iter().find(|s| s.action == "place").unwrap();
+    assert!(place.fields[0].boundary.iter().any(|e| e.value == "10"));
+    assert!(place.fields[0].invalid.iter().any(|e| e.value == "11"));
+    let upload = sets.iter().find(|s| s.action == "upload").unwrap();
+    assert!(upload.fields[0].boundary.iter().any(|e| e.value == "file of exactly 5 MB"));
+}
+
+#[test]
+fn test_tables_and_fixtures() {
+    let sets = suggest_test_data(STORIES, &entities());
+
+    let markdown = format_test_data_markdown(&sets);
+    assert!(markdown.contains("#### register\n\n| Field | Valid | Boundary | Invalid |\n"));
+    assert!(markdown.contains("| email | `jane.doe@example.com` typical address |"));
+    assert!(markdown.contains("`Aa1!Aa1!… (65 chars)` one character too long (65)"));
+
+    let fixtures = format_test_data_fixtures(&sets);
+    let register = &fixtures["register"];
+    assert_eq!(register["valid"][0]["email"], "jane.doe@example.com");
+    let invalid = register["invalid"].as_array().unwrap();
+    let missing_at = invalid.iter().find(|r| r["email"] == "jane.doe.example.com").unwrap();
+    assert_eq!(missing_at["password"], "Str0ng!Passw0rd");
+    assert_eq!(missing_at["_expect"], "rejected: email missing @");
+    assert!(register["boundary"].as_array().unwrap().iter().all(|r| r.get("_expect").is_none()));
+}
@@ -0,0 +1,30 @@
+use prism::analyzer::Analyzer;
+use prism::cli::OutputFormat;
+use prism::format_report;
+use prism::toc::{add_toc, number_markdown};
+
+#[test]
+fn test_markdown_sections_and_findings_are_numbered_with_contents() {
+    let report = "# Report\n\n## Summary\n\nText\n\n## Findings\n\n### Issue #1: \"fast\"\n\n```\n## not a heading\n```\n\n### Issue #2: [soon]\n";
+    let numbered = number_markdown(report);
+    assert!(numbered.starts_with("# Report\n\n## Contents\n\n- [1. Summary](#section-1)\n- [2. Findings](#section-2)\n  - [2.1 Issue #1: \"fast\"](#section-2-1)\n  - [2.2 Issue #2: \\[soon\\]](#section-2-2)\n\n## <a id=\"section-1\"></a>1. Summary"), "{}", numbered);
+    assert!(numbered.contains("### <a id=\"section-2-2\"></a>2.2 Issue #2: [soon]\n"));
+    assert!(numbered.contains("```\n## not a heading\n```"));
+    assert_eq!(number_markdown("No headings here\n"), "No headings here\n");
+}
+
+#[tokio::test]
+async fn test_html_report_sections_and_finding_rows_are_numbered() {
+    let text = "The system should be fast and the page should load quickly.";
+    let result = Analyzer::new().unwrap().analyze(text).await.unwrap();
+    let html = add_toc(&format_report(&result, text, &OutputFormat::Html, None).unwrap(), &OutputFormat::Html);
+
+    assert!(html.contains("<nav class=\"toc\"><strong>Contents</strong>"));
+    assert!(html.contains("<li><a href=\"#section-1\">⚠️ Findings"), "{}", html);
+    assert!(html.contains("<details open id=\"section-1\">\n<summary>1. ⚠️ Findings"));
+    assert!(html.contains("<table id=\"findings\"><tr><th>#</th>"));
+    assert!(html.contains("<tr id=\"finding-1-1\" data-severity="));
+    assert!(html.find("<nav").unwrap() < html.find("<details").unwrap());
+    // Formats other than markdown and html are left alone
+    assert_eq!(add_toc("{\"a\": 1}", &OutputFormat::Json), "{\"a\": 1}");
+}
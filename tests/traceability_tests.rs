@@ -0,0 +1,49 @@
+use prism::traceability::{format_traceability_markdown, MatchType, TraceabilityAnalyzer};
+
+#[tokio::test]
+async fn test_traceability_matrix_links_ids_and_keywords() {
+    let root = std::env::temp_dir().join("prism_traceability_test");
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::create_dir_all(root.join("tests")).unwrap();
+    std::fs::write(root.join("src/export.rs"), "// REQ-1 CSV export\npub fn export_report_csv() {}\n// REQ-10 is unrelated\n").unwrap();
+    std::fs::write(root.join("src/util.rs"), "pub fn unrelated() {}\n").unwrap();
+    std::fs::write(root.join("tests/export_tests.rs"), "#[test]\nfn test_req_1_writes_header() {}\n").unwrap();
+
+    let analyzer = TraceabilityAnalyzer::new();
+    let requirements = analyzer.extract_requirements(
+        "# Reports\n- REQ-1: The user shall export reports as CSV.\n- The system shall archive invoices after 90 days.\n",
+    );
+    assert_eq!(requirements.len(), 2);
+
+    let matrix = analyzer
+        .analyze_traceability(&requirements, Some(&root.join("src")), Some(&root.join("tests")))
+        .await
+        .unwrap();
+
+    assert_eq!(matrix.coverage_summary.code_files_analyzed, 2);
+    assert_eq!(matrix.coverage_summary.test_files_analyzed, 1);
+    assert_eq!(matrix.coverage_summary.traced_requirements, 1);
+    assert_eq!(matrix.coverage_summary.coverage_percentage, 50.0);
+
+    let export = &matrix.requirements[0];
+    assert_eq!(export.requirement_id, "REQ-1");
+    let lines: Vec<_> = export.code_references.iter().map(|r| r.line_number).collect();
+    assert_eq!(lines, vec![1, 2]);
+    assert!(matches!(export.code_references[0].match_type, MatchType::ExactMatch));
+    assert_eq!(export.test_references[0].test_name, "test_req_1_writes_header");
+    assert!(export.coverage_percentage > 80.0);
+
+    // Requirements without an ID are numbered by position
+    assert_eq!(matrix.requirements[1].requirement_id, "REQ-002");
+    assert_eq!(matrix.requirements[1].coverage_percentage, 0.0);
+
+    assert_eq!(matrix.orphaned_code.len(), 1);
+    assert_eq!(matrix.orphaned_code[0].function_name, "unrelated");
+
+    let markdown = format_traceability_markdown(&matrix);
+    assert!(markdown.contains("| REQ-1 | REQ-1: The user shall export reports as CSV. | 2 | 1 |"));
+    assert!(markdown.contains("## ⚠️ Requirements Without Tests\n\n- **REQ-002**"));
+
+    let _ = std::fs::remove_dir_all(&root);
+}
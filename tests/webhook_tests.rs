@@ -0,0 +1,163 @@
+use prism::config::Config;
+use prism::server::{serve, ServerState};
+use prism::webhook::{hmac_sha256, verify_event, Forge, PushEvent};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn github_push(commit: &str) -> Value {
+    json!({
+        "ref": "refs/heads/main",
+        "after": commit,
+        "repository": { "full_name": "acme/checkout" },
+        "commits": [
+            { "added": ["docs/requirements/login.md", "docs/draft.md"], "modified": ["src/main.rs"], "removed": [] },
+            { "added": [], "modified": ["docs/requirements/cart.md"], "removed": ["docs/draft.md"] }
+        ]
+    })
+}
+
+#[test]
+fn test_event_signatures_are_verified() {
+    // RFC 4231, test case 2
+    assert_eq!(
+        hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+
+    let body = br#"{"after":"abc"}"#;
+    let signature = format!("sha256={}", hex(&hmac_sha256(b"s3cret", body)));
+    assert!(verify_event(Forge::GitHub, "s3cret", body, Some(&signature)));
+    assert!(!verify_event(Forge::GitHub, "other", body, Some(&signature)));
+    assert!(!verify_event(Forge::GitHub, "s3cret", body, None));
+    assert!(verify_event(Forge::GitLab, "s3cret", body, Some("s3cret")));
+    assert!(!verify_event(Forge::GitLab, "s3cret", body, Some("s3cre")));
+}
+
+#[test]
+fn test_push_events_list_changed_requirement_files() {
+    let push = PushEvent::parse(Forge::GitHub, &github_push("9f3c2a1b")).unwrap().unwrap();
+    assert_eq!(push.repository, "acme/checkout");
+    assert_eq!(push.branch, "main");
+    assert_eq!(push.changed_files, vec!["docs/requirements/cart.md", "docs/requirements/login.md", "src/main.rs"]);
+
+    let is_document = |file: &str| file.ends_with(".md");
+    assert_eq!(push.requirement_files(&[], is_document), vec!["docs/requirements/cart.md", "docs/requirements/login.md"]);
+    assert_eq!(push.requirement_files(&["docs/requirements/login.md".to_string()], is_document), vec!["docs/requirements/login.md"]);
+    assert!(push.requirement_files(&["docs/req".to_string()], is_document).is_empty());
+
+    let gitlab = json!({
+        "object_kind": "push", "ref": "refs/heads/develop", "after": "77aa01", "project_id": 15,
+        "project": { "path_with_namespace": "acme/billing", "web_url": "https://gitlab.example.com/acme/billing" },
+        "commits": [{ "added": [], "modified": ["specs/invoice.docx"], "removed": [] }]
+    });
+    let push = PushEvent::parse(Forge::GitLab, &gitlab).unwrap().unwrap();
+    assert_eq!((push.project_id, push.branch.as_str()), (Some(15), "develop"));
+    assert_eq!(push.changed_files, vec!["specs/invoice.docx"]);
+
+    let deleted = json!({ "ref": "refs/heads/old", "after": "0000000000000000000000000000000000000000", "deleted": true });
+    assert_eq!(PushEvent::parse(Forge::GitHub, &deleted).unwrap(), None);
+}
+
+async fn read_request(socket: &mut tokio::net::TcpStream) -> String {
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 4096];
+    loop {
+        let n = socket.read(&mut buffer).await.unwrap();
+        request.extend_from_slice(&buffer[..n]);
+        let text = String::from_utf8_lossy(&request).to_string();
+        if let Some(end) = text.find("\r\n\r\n") {
+            let length = text.lines()
+                .find_map(|l| l.to_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap()))
+                .unwrap_or(0);
+            if request.len() >= end + 4 + length {
+                return text;
+            }
+        }
+        if n == 0 {
+            return text;
+        }
+    }
+}
+
+/// Serves pushed files and records commit comments like the GitHub API.
+async fn fake_github(comments: Arc<Mutex<Vec<String>>>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let request = read_request(&mut socket).await;
+            let (status, body) = if request.starts_with("GET /repos/acme/checkout/contents/docs/requirements/") {
+                ("200 OK", "The login page should load quickly and be user-friendly.".to_string())
+            } else if request.starts_with("POST /repos/acme/checkout/commits/9f3c2a1b/comments") {
+                comments.lock().unwrap().push(request.split("\r\n\r\n").nth(1).unwrap_or_default().to_string());
+                ("201 Created", "{}".to_string())
+            } else {
+                ("404 Not Found", "{}".to_string())
+            };
+            let response = format!("HTTP/1.1 {}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}", status, body.len(), body);
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+    format!("http://{}", address)
+}
+
+#[tokio::test]
+async fn test_github_push_is_analyzed_and_commented() {
+    let comments = Arc::new(Mutex::new(Vec::new()));
+    let mut config = Config::default();
+    config.server.webhook.secret = Some("s3cret".to_string());
+    config.server.webhook.paths = vec!["docs/requirements".to_string()];
+    config.server.webhook.github_token = Some("test-token".to_string());
+    config.server.webhook.github_api_url = fake_github(comments.clone()).await;
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/webhook", listener.local_addr().unwrap());
+    tokio::spawn(serve(listener, Arc::new(ServerState::new(config).unwrap())));
+
+    let client = reqwest::Client::new();
+    let body = github_push("9f3c2a1b").to_string();
+    let signature = format!("sha256={}", hex(&hmac_sha256(b"s3cret", body.as_bytes())));
+    let unsigned = client.post(&url).header("X-GitHub-Event", "push").body(body.clone()).send().await.unwrap();
+    assert_eq!(unsigned.status(), 401);
+
+    let response = client.post(&url)
+        .header("X-GitHub-Event", "push")
+        .header("X-Hub-Signature-256", signature)
+        .body(body)
+        .send().await.unwrap();
+    assert_eq!(response.status(), 202);
+    let accepted: Value = response.json().await.unwrap();
+    assert_eq!(accepted["files"], json!(["docs/requirements/cart.md", "docs/requirements/login.md"]));
+
+    for _ in 0..100 {
+        if !comments.lock().unwrap().is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    let comment: Value = serde_json::from_str(&comments.lock().unwrap()[0]).unwrap();
+    let comment = comment["body"].as_str().unwrap();
+    assert!(comment.contains("Commit `9f3c2a1`: 2 requirement file(s)"));
+    assert!(comment.contains("| `docs/requirements/login.md` |"));
+}
+
+#[tokio::test]
+async fn test_webhook_is_disabled_without_a_secret() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/webhook", listener.local_addr().unwrap());
+    tokio::spawn(serve(listener, Arc::new(ServerState::new(Config::default()).unwrap())));
+
+    let response = reqwest::Client::new().post(&url)
+        .header("X-GitHub-Event", "push")
+        .body(github_push("9f3c2a1b").to_string())
+        .send().await.unwrap();
+    assert_eq!(response.status(), 403);
+}